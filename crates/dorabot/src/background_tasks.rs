@@ -10,6 +10,7 @@ use teloxide::prelude::*;
 use tokio::time::interval;
 
 use crate::core::{alerts, config, metrics, stats_reporter};
+use crate::download::queue::DownloadQueue;
 use crate::storage::SharedStorage;
 use crate::storage::db::DbPool;
 use crate::telegram::Bot;
@@ -21,6 +22,7 @@ const LOCK_SUBSCRIPTION_EXPIRY: i64 = 1103;
 const LOCK_COOKIES_CHECKER: i64 = 1104;
 const LOCK_CONTENT_WATCHER: i64 = 1105;
 const LOCK_DOWNLOADS_CLEANUP: i64 = 1106;
+const LOCK_BACKUPS: i64 = 1107;
 
 /// Default retention period for files in the downloads folder (in days).
 /// Override with the `DOWNLOADS_RETENTION_DAYS` env var. Files older than
@@ -270,6 +272,65 @@ pub async fn spawn_cookies_checker(bot: Bot, shared_storage: Arc<SharedStorage>)
     });
 }
 
+/// Start the scheduled database backup task (every `BACKUP_INTERVAL_HOURS`).
+///
+/// Snapshots SQLite (and Postgres on that backend), uploads to S3 when
+/// configured and applies the GFS retention policy. Admins are notified when a
+/// backup or its upload fails. Disabled with `BACKUP_INTERVAL_HOURS=0`.
+pub async fn spawn_backup_scheduler(bot: Bot, shared_storage: Arc<SharedStorage>) {
+    use crate::storage::backup::{BackupConfig, BackupOutcome, create_backup};
+    use crate::telegram::notifications::notify_admin_text;
+
+    let hours = *config::backup::INTERVAL_HOURS;
+    if hours == 0 {
+        log::info!("Scheduled backups disabled (BACKUP_INTERVAL_HOURS=0)");
+        return;
+    }
+    let backup_config = match BackupConfig::from_config() {
+        Ok(cfg) => cfg.with_shared_storage(&shared_storage),
+        Err(e) => {
+            log::error!("Scheduled backups disabled: invalid backup configuration: {:#}", e);
+            return;
+        }
+    };
+
+    let lock_conn = match shared_storage.as_ref() {
+        SharedStorage::Sqlite { .. } => None,
+        SharedStorage::Postgres { .. } => {
+            match try_acquire_pg_singleton_lock(&shared_storage, LOCK_BACKUPS, "backup scheduler").await {
+                Some(conn) => Some(conn),
+                None => return,
+            }
+        }
+    };
+
+    tokio::spawn(async move {
+        let _lock_conn = lock_conn;
+        let mut interval = interval(Duration::from_secs(hours * 60 * 60));
+        loop {
+            interval.tick().await;
+            match create_backup(&backup_config).await {
+                Ok(outcome) => {
+                    if let BackupOutcome::Created {
+                        upload: Some(Err(_)), ..
+                    } = &outcome
+                    {
+                        notify_admin_text(
+                            &bot,
+                            &format!("⚠️ Scheduled backup upload failed\n\n{}", outcome.summary()),
+                        )
+                        .await;
+                    }
+                }
+                Err(e) => {
+                    log::error!("Scheduled backup failed: {:#}", e);
+                    notify_admin_text(&bot, &format!("❌ Scheduled backup failed: {:#}", e)).await;
+                }
+            }
+        }
+    });
+}
+
 /// Start the downloads folder cleanup task (every 6 hours).
 ///
 /// Deletes files in the configured `DOWNLOAD_FOLDER` whose mtime is older than
@@ -429,7 +490,12 @@ async fn cleanup_downloads_folder(folder: &str, cutoff: std::time::SystemTime) -
     Ok((removed, freed_bytes))
 }
 
-pub async fn spawn_content_watcher(bot: Bot, db_pool: Arc<DbPool>, shared_storage: Arc<SharedStorage>) {
+pub async fn spawn_content_watcher(
+    bot: Bot,
+    db_pool: Arc<DbPool>,
+    shared_storage: Arc<SharedStorage>,
+    download_queue: Arc<DownloadQueue>,
+) {
     use crate::watcher::{WatcherRegistry, scheduler};
 
     let lock_conn = match shared_storage.as_ref() {
@@ -447,6 +513,7 @@ pub async fn spawn_content_watcher(bot: Bot, db_pool: Arc<DbPool>, shared_storag
         Arc::clone(&db_pool),
         Arc::clone(&shared_storage),
         Arc::clone(&watcher_registry),
+        download_queue,
     );
     crate::telegram::subscriptions::start_notification_dispatcher(
         bot,
@@ -567,7 +634,8 @@ pub async fn spawn_stats_reporter(bot: Bot, shared_storage: Arc<SharedStorage>)
 
 /// Start periodic database cleanup (every 6 hours).
 ///
-/// Removes stale data: completed/failed tasks (>7 days), old error logs (>30 days).
+/// Removes stale data: completed/failed tasks (>7 days), old error logs (>30 days),
+/// expired hosted-link files, API request log (>90 days).
 pub async fn spawn_db_cleanup(_db_pool: Arc<DbPool>, shared_storage: Arc<SharedStorage>) {
     let lock_conn = match shared_storage.as_ref() {
         SharedStorage::Sqlite { .. } => None,
@@ -618,6 +686,25 @@ pub async fn spawn_db_cleanup(_db_pool: Arc<DbPool>, shared_storage: Arc<SharedS
                     Err(e) => log::warn!("DB cleanup: url_cache error: {}", e),
                     _ => {}
                 }
+                match crate::core::hosted_links::purge_expired(&shared_storage).await {
+                    Ok(n) if n > 0 => {
+                        total += n;
+                        log::info!("DB cleanup: removed {} expired hosted files", n);
+                    }
+                    Err(e) => log::warn!("DB cleanup: hosted_files error: {}", e),
+                    _ => {}
+                }
+                match shared_storage
+                    .cleanup_old_api_requests(config::api::REQUEST_LOG_RETENTION_DAYS)
+                    .await
+                {
+                    Ok(n) if n > 0 => {
+                        total += n;
+                        log::info!("DB cleanup: removed {} old api_requests entries", n);
+                    }
+                    Err(e) => log::warn!("DB cleanup: api_requests error: {}", e),
+                    _ => {}
+                }
                 if total > 0 {
                     log::info!("DB cleanup: {} rows removed total", total);
                 }
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "doradura")]
@@ -82,6 +82,12 @@ pub enum Commands {
         #[arg(short, long)]
         output: Option<String>,
 
+        /// File name template, `/` makes folders (default: "{artist} - {title}").
+        /// Fields: title, artist, album, uploader, upload_date, platform,
+        /// playlist_index, format, quality; e.g. "{artist}/{album|Singles}/{playlist_index:02} {title}"
+        #[arg(short, long)]
+        template: Option<String>,
+
         /// Show verbose progress
         #[arg(short, long)]
         verbose: bool,
@@ -102,6 +108,49 @@ pub enum Commands {
         #[command(subcommand)]
         command: WebhookCommand,
     },
+
+    /// Create, list and restore database backups
+    Backup {
+        #[command(subcommand)]
+        command: BackupCommand,
+    },
+
+    /// Copy all shared tables between SQLite and Postgres, then verify them
+    MigrateStorage {
+        /// Backend to read from
+        #[arg(long, value_enum)]
+        from: StorageBackend,
+
+        /// Backend to write to
+        #[arg(long, value_enum)]
+        to: StorageBackend,
+
+        /// Rows per batch
+        #[arg(long, default_value_t = 1000)]
+        batch_size: usize,
+
+        /// Only report row counts and column mapping; write nothing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Progress file used to resume an interrupted run
+        #[arg(long, default_value = "migrate-storage.state.json")]
+        state_file: std::path::PathBuf,
+
+        /// Ignore saved progress and start from the first row
+        #[arg(long)]
+        restart: bool,
+
+        /// Only migrate this table (repeatable)
+        #[arg(long = "table")]
+        tables: Vec<String>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StorageBackend {
+    Sqlite,
+    Postgres,
 }
 
 #[derive(Subcommand)]
@@ -122,6 +171,23 @@ pub enum WebhookCommand {
     Info,
 }
 
+#[derive(Subcommand)]
+pub enum BackupCommand {
+    /// Snapshot the databases now (skipped when nothing changed)
+    Create,
+    /// List local backup sets, newest first
+    List,
+    /// Restore a backup set; stop the bot first
+    Restore {
+        /// Set id as shown by `backup list`; fetched from S3 when not local
+        id: String,
+
+        /// age identity file for encrypted backups (default: BACKUP_AGE_IDENTITY)
+        #[arg(long)]
+        identity: Option<std::path::PathBuf>,
+    },
+}
+
 impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
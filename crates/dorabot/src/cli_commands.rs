@@ -5,11 +5,14 @@
 //! - `info` — media info lookup
 //! - `refresh-metadata` — metadata refresh
 //! - `update-ytdlp` — yt-dlp management
+//! - `backup` — database backup create / list / restore
+//! - `migrate-storage` — SQLite ⇄ Postgres data migration
 
 use anyhow::Result;
 use secrecy::ExposeSecret;
 use std::sync::Arc;
 
+use crate::cli::{BackupCommand, StorageBackend};
 use crate::core::config;
 use crate::download::ytdlp;
 use crate::metadata_refresh;
@@ -42,6 +45,133 @@ pub async fn run_ytdlp_update(force: bool, check: bool) -> Result<()> {
     Ok(())
 }
 
+/// Run a backup subcommand
+pub async fn run_backup(command: BackupCommand) -> Result<()> {
+    use crate::storage::backup::{BackupConfig, create_backup, list_backups, restore_backup};
+
+    let mut backup_config = BackupConfig::from_config()?;
+    match command {
+        BackupCommand::Create => {
+            let outcome = create_backup(&backup_config).await?;
+            println!("{}", outcome.summary());
+        }
+        BackupCommand::List => {
+            let backups = list_backups(&backup_config.dir)?;
+            if backups.is_empty() {
+                println!("No backups in {}", backup_config.dir.display());
+            }
+            for manifest in backups {
+                let kinds: Vec<String> = manifest.artifacts.iter().map(|a| a.kind.to_string()).collect();
+                println!(
+                    "{}  schema {:>3}  {:>12} bytes  {}",
+                    manifest.id,
+                    manifest
+                        .schema_version
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    manifest.total_size(),
+                    kinds.join(" + ")
+                );
+            }
+        }
+        BackupCommand::Restore { id, identity } => {
+            if identity.is_some() {
+                backup_config.age_identity = identity;
+            }
+            let manifest = restore_backup(&backup_config, &id).await?;
+            println!(
+                "Restored backup {} (schema {:?}, postgres schema {:?}, taken by v{})",
+                manifest.id, manifest.schema_version, manifest.pg_schema_version, manifest.app_version
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Options for `migrate-storage` besides the direction
+pub struct MigrateStorageArgs {
+    pub batch_size: usize,
+    pub dry_run: bool,
+    pub state_file: std::path::PathBuf,
+    pub restart: bool,
+    pub tables: Vec<String>,
+}
+
+/// Run the SQLite ⇄ Postgres data migration
+pub async fn run_migrate_storage(from: StorageBackend, to: StorageBackend, args: MigrateStorageArgs) -> Result<()> {
+    use crate::storage::shared::connect_postgres;
+    use crate::storage::transfer::{Direction, TransferOptions, transfer};
+
+    let direction = match (from, to) {
+        (StorageBackend::Sqlite, StorageBackend::Postgres) => Direction::SqliteToPostgres,
+        (StorageBackend::Postgres, StorageBackend::Sqlite) => Direction::PostgresToSqlite,
+        _ => return Err(anyhow::anyhow!("--from and --to must be different backends")),
+    };
+    let database_url = config::DATABASE_URL
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("DATABASE_URL must be set for migrate-storage"))?;
+
+    let db_pool =
+        create_pool(&config::DATABASE_PATH).map_err(|e| anyhow::anyhow!("Failed to create database pool: {}", e))?;
+    let pg_pool = connect_postgres(&database_url, 5).await?;
+
+    let options = TransferOptions {
+        direction,
+        batch_size: args.batch_size,
+        dry_run: args.dry_run,
+        state_path: args.state_file,
+        restart: args.restart,
+        tables: args.tables,
+    };
+    let report = transfer(&db_pool, &pg_pool, &options).await?;
+    print!("{}", report);
+
+    if !report.verified() {
+        return Err(anyhow::anyhow!("Verification failed; see the table above"));
+    }
+    Ok(())
+}
+
+/// Where `run_cli_download` saves `url`: the rendered template inside
+/// `output_dir`, with missing folders created and existing files kept.
+async fn cli_output_path(
+    url: &str,
+    template: &crate::download::filename_template::FilenameTemplate,
+    format: &str,
+    quality: &str,
+    output_dir: &str,
+) -> Result<std::path::PathBuf> {
+    use crate::download::filename_template::{TemplateFields, fetch_info_json, unique_path};
+    use crate::download::metadata::get_metadata_from_ytdlp;
+
+    let parsed = url::Url::parse(url)?;
+    let mut fields = TemplateFields::for_url(&parsed);
+    let (title, artist) = get_metadata_from_ytdlp(&parsed, None).await?;
+    fields.title = Some(title).filter(|s| !s.is_empty());
+    fields.artist = Some(artist).filter(|s| !s.is_empty());
+    // Album, upload date etc. are not in the quick metadata.
+    if template.needs_info_json() {
+        fields.merge_info_json(&fetch_info_json(&parsed, None, None).await?);
+    }
+    fields.format = Some(format.to_string());
+    fields.quality = Some(quality.to_string());
+    let rel = template
+        .render(&fields, format)
+        .unwrap_or_else(|| format!("Unknown.{}", format).into());
+    let path = unique_path(std::path::Path::new(output_dir), rel);
+    if let Some(parent) = path.parent() {
+        fs_err::tokio::create_dir_all(parent).await?;
+    }
+    Ok(path)
+}
+
+/// `dir/name.mp3` → `dir/name.%(ext)s`, with `%` in the name escaped for yt-dlp.
+fn ytdlp_output_template(path: &std::path::Path, ext: &str) -> String {
+    let full = path.to_string_lossy();
+    let stem = full.strip_suffix(&format!(".{}", ext)).unwrap_or(&full);
+    format!("{}.%(ext)s", stem.replace('%', "%%"))
+}
+
 /// Run CLI download command
 #[allow(clippy::too_many_arguments)]
 pub async fn run_cli_download(
@@ -50,8 +180,10 @@ pub async fn run_cli_download(
     quality: String,
     bitrate: String,
     output: Option<String>,
+    template: Option<String>,
     verbose: bool,
 ) -> Result<()> {
+    use crate::download::filename_template::FilenameTemplate;
     use crate::download::metadata::{get_proxy_chain, is_proxy_related_error};
     use std::io::{BufRead, BufReader};
     use std::process::{Command, Stdio};
@@ -94,7 +226,24 @@ pub async fn run_cli_download(
         }
     };
 
-    let output_template = format!("{}/%(title)s.%(ext)s", output_dir);
+    let template = match template.as_deref() {
+        Some(t) => FilenameTemplate::parse(t).map_err(|e| anyhow::anyhow!("Invalid --template: {}", e))?,
+        None => FilenameTemplate::default_template(),
+    };
+    let quality_label = if format == "mp3" { &bitrate } else { &quality };
+    let output_template = match cli_output_path(&url, &template, &format, quality_label, &output_dir).await {
+        Ok(path) => {
+            println!("Saving to: {}", path.display());
+            ytdlp_output_template(&path, &format)
+        }
+        Err(e) => {
+            println!(
+                "⚠️ Could not read metadata for the file name ({}), using the video title",
+                e
+            );
+            format!("{}/%(title)s.%(ext)s", output_dir)
+        }
+    };
 
     let proxy_chain = get_proxy_chain();
     let total_proxies = proxy_chain.len();
@@ -10,6 +10,7 @@
 //! - Alert resolution notifications
 //! - Database persistence of alert history
 
+use crate::core::admin_events::{self, AdminEvent};
 use crate::core::{config, metrics};
 use crate::storage::SharedStorage;
 use crate::telegram::Bot;
@@ -359,13 +360,18 @@ impl AlertManager {
             anyhow::bail!("Failed to send alert: {:?}", e);
         }
 
-        // Record alert metric
+        // Record alert metric and notify open admin dashboards
         {
             let severity_str = match alert.severity {
                 Severity::Critical => "critical",
                 Severity::Warning => "warning",
             };
             crate::core::metrics::record_alert(alert.alert_type.as_str(), severity_str);
+            admin_events::publish(AdminEvent::Alert {
+                alert_type: alert.alert_type.as_str().to_string(),
+                severity: severity_str.to_string(),
+                title: alert.title.clone(),
+            });
         }
 
         // Update last alert time
@@ -0,0 +1,158 @@
+//! `/apikey` — personal keys for the public REST API (`/api/v1`).
+//!
+//! - `/apikey` lists active keys and usage.
+//! - `/apikey new [name]` mints a key and shows it once; only its SHA-256 is
+//!   stored.
+//! - `/apikey revoke ID` disables a key immediately.
+//!
+//! Key format and hashing live in `doracore::core::public_api`, storage in
+//! `storage/{db,shared}/public_api.rs`.
+
+use crate::core::config;
+use crate::core::public_api;
+use crate::i18n;
+use crate::storage::SharedStorage;
+use crate::telegram::Bot;
+use teloxide::prelude::*;
+use unic_langid::LanguageIdentifier;
+
+const MAX_NAME_CHARS: usize = 32;
+
+/// `/apikey [new NAME | revoke ID]`
+pub async fn handle_apikey_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_text: &str,
+    shared_storage: &SharedStorage,
+) -> ResponseResult<()> {
+    let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+    if !*config::api::ENABLED {
+        bot.send_message(chat_id, i18n::t(&lang, "api_keys.disabled")).await?;
+        return Ok(());
+    }
+
+    let mut args = message_text.split_whitespace().skip(1);
+    let text = match args.next() {
+        Some("new") => {
+            let name = args.collect::<Vec<_>>().join(" ");
+            create_key(chat_id, &name, shared_storage, &lang).await
+        }
+        Some("revoke") => match args
+            .next()
+            .and_then(|id| id.trim_start_matches('#').parse::<i64>().ok())
+        {
+            Some(key_id) => match shared_storage.revoke_api_key(chat_id.0, key_id).await {
+                Ok(true) => i18n::t_args(&lang, "api_keys.revoked", &doracore::fluent_args!("id" => key_id)),
+                Ok(false) => i18n::t_args(&lang, "api_keys.not_found", &doracore::fluent_args!("id" => key_id)),
+                Err(e) => {
+                    log::error!("Failed to revoke API key {} for {}: {}", key_id, chat_id.0, e);
+                    i18n::t(&lang, "api_keys.failed")
+                }
+            },
+            None => i18n::t(&lang, "api_keys.revoke_usage"),
+        },
+        _ => list_keys(chat_id, shared_storage, &lang).await,
+    };
+    bot.send_message(chat_id, text).await?;
+    Ok(())
+}
+
+async fn create_key(chat_id: ChatId, name: &str, shared_storage: &SharedStorage, lang: &LanguageIdentifier) -> String {
+    let active = match shared_storage.list_api_keys(chat_id.0).await {
+        Ok(keys) => keys.len(),
+        Err(e) => {
+            log::error!("Failed to list API keys for {}: {}", chat_id.0, e);
+            return i18n::t(lang, "api_keys.failed");
+        }
+    };
+    if active >= config::api::MAX_KEYS_PER_USER {
+        return i18n::t_args(
+            lang,
+            "api_keys.limit",
+            &doracore::fluent_args!("max" => config::api::MAX_KEYS_PER_USER as i64),
+        );
+    }
+
+    let name: String = name.trim().chars().take(MAX_NAME_CHARS).collect();
+    let name = if name.is_empty() {
+        format!("key {}", active + 1)
+    } else {
+        name
+    };
+    let key = public_api::generate_key();
+    if let Err(e) = shared_storage
+        .create_api_key(chat_id.0, &name, &key.hash, &key.prefix)
+        .await
+    {
+        log::error!("Failed to create API key for {}: {}", chat_id.0, e);
+        return i18n::t(lang, "api_keys.failed");
+    }
+    log::info!("User {} created API key '{}' ({})", chat_id.0, name, key.prefix);
+
+    let plan = shared_storage
+        .get_user(chat_id.0)
+        .await
+        .ok()
+        .flatten()
+        .map(|u| u.plan)
+        .unwrap_or_default();
+    // The key goes outside the localized text so Fluent's bidi isolation
+    // marks can't end up in what the user copies.
+    format!(
+        "{}\n\n{}",
+        i18n::t_args(
+            lang,
+            "api_keys.created",
+            &doracore::fluent_args!("name" => name, "rpm" => config::api::requests_per_minute(plan) as i64),
+        ),
+        key.raw
+    )
+}
+
+async fn list_keys(chat_id: ChatId, shared_storage: &SharedStorage, lang: &LanguageIdentifier) -> String {
+    let keys = match shared_storage.list_api_keys(chat_id.0).await {
+        Ok(keys) => keys,
+        Err(e) => {
+            log::error!("Failed to list API keys for {}: {}", chat_id.0, e);
+            return i18n::t(lang, "api_keys.failed");
+        }
+    };
+
+    let mut text = i18n::t_args(
+        lang,
+        "api_keys.title",
+        &doracore::fluent_args!(
+            "count" => keys.len() as i64,
+            "max" => config::api::MAX_KEYS_PER_USER as i64
+        ),
+    );
+    text.push_str("\n\n");
+    if keys.is_empty() {
+        text.push_str(&i18n::t(lang, "api_keys.empty"));
+        text.push('\n');
+    }
+    for key in &keys {
+        let last_used = key
+            .last_used_at
+            .clone()
+            .unwrap_or_else(|| i18n::t(lang, "api_keys.never"));
+        text.push_str(&i18n::t_args(
+            lang,
+            "api_keys.item",
+            &doracore::fluent_args!(
+                "id" => key.id,
+                "name" => key.name.clone(),
+                "prefix" => key.key_prefix.clone(),
+                "created" => key.created_at.clone(),
+                "last_used" => last_used
+            ),
+        ));
+        text.push('\n');
+    }
+    text.push('\n');
+    text.push_str(&i18n::t(lang, "api_keys.usage"));
+    if let Some(base_url) = config::share::base_url() {
+        text.push_str(&format!("\n{}/api/v1", base_url.trim_end_matches('/')));
+    }
+    text
+}
@@ -0,0 +1,378 @@
+//! Pay-per-use credits bought with Telegram Stars.
+//!
+//! Free-plan users spend credits on premium actions (high-res video, stories
+//! render, album split) once the daily free allowance is used up; paid plans
+//! are never charged. The debit is written together with the task (see
+//! `QueueTaskInput::credit_charge`) and refunded automatically when the task
+//! fails or is cancelled. Storage lives in `storage/{db,shared}/credits.rs`.
+
+use crate::core::config;
+use crate::core::metrics;
+use crate::i18n;
+use crate::storage::SharedStorage;
+use crate::storage::db::{CreditAction, CreditCharge};
+use crate::telegram::Bot;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, LabeledPrice};
+use unic_langid::LanguageIdentifier;
+use url::Url;
+
+/// How many ledger rows `/credits` shows.
+const HISTORY_LIMIT: i64 = 10;
+
+/// Invoice payload prefix for Star packs: `credits:{credits}:{user_id}`.
+pub const PAYLOAD_PREFIX: &str = "credits:";
+
+/// Which premium action (if any) a queued download is.
+pub fn premium_action(video_quality: Option<&str>, carousel_mask: Option<u32>) -> Option<CreditAction> {
+    if carousel_mask.is_some() {
+        Some(CreditAction::AlbumSplit)
+    } else if config::download::is_highres_quality(video_quality) {
+        Some(CreditAction::Highres)
+    } else {
+        None
+    }
+}
+
+/// Price tag for `action` for this user, or `None` when their plan covers it.
+pub async fn charge_for_user(
+    shared_storage: &SharedStorage,
+    user_id: i64,
+    action: Option<CreditAction>,
+) -> Option<CreditCharge> {
+    let action = action?;
+    let plan = shared_storage
+        .get_user(user_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|user| user.plan)
+        .unwrap_or_default();
+    (!plan.is_paid()).then(|| config::credits::charge_for(action))
+}
+
+fn buy_keyboard(lang: &LanguageIdentifier) -> InlineKeyboardMarkup {
+    let rows = config::credits::PACKS
+        .iter()
+        .map(|&(credits, stars)| {
+            vec![InlineKeyboardButton::callback(
+                i18n::t_args(
+                    lang,
+                    "credits.buy_button",
+                    &doracore::fluent_args!("credits" => credits, "stars" => stars as i64),
+                ),
+                format!("credits:buy:{}", credits),
+            )]
+        })
+        .collect::<Vec<_>>();
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// `/credits` — balance, prices, recent ledger rows and Star-pack buttons.
+pub async fn show_credits(bot: &Bot, chat_id: ChatId, shared_storage: &SharedStorage) -> ResponseResult<()> {
+    let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+    let balance = shared_storage.get_credit_balance(chat_id.0).await.unwrap_or_else(|e| {
+        log::error!("Failed to load credit balance for {}: {}", chat_id.0, e);
+        0
+    });
+    let history = shared_storage
+        .list_credit_transactions(chat_id.0, HISTORY_LIMIT)
+        .await
+        .unwrap_or_default();
+
+    let mut text = format!(
+        "{}\n\n{}\n\n{}",
+        i18n::t(&lang, "credits.title"),
+        i18n::t_args(&lang, "credits.balance", &doracore::fluent_args!("balance" => balance)),
+        i18n::t_args(
+            &lang,
+            "credits.prices",
+            &doracore::fluent_args!(
+                "highres" => *config::credits::HIGHRES_COST,
+                "highres_free" => *config::credits::FREE_HIGHRES_PER_DAY as i64,
+                "stories" => *config::credits::STORIES_COST,
+                "stories_free" => *config::credits::FREE_STORIES_PER_DAY as i64,
+                "album" => *config::credits::ALBUM_SPLIT_COST,
+                "album_free" => *config::credits::FREE_ALBUM_SPLIT_PER_DAY as i64,
+            ),
+        ),
+    );
+
+    text.push_str("\n\n");
+    if history.is_empty() {
+        text.push_str(&i18n::t(&lang, "credits.history_empty"));
+    } else {
+        text.push_str(&i18n::t(&lang, "credits.history_title"));
+        for tx in &history {
+            let label = i18n::t(&lang, &format!("credits.kind_{}", tx.kind));
+            let action = tx
+                .action
+                .as_deref()
+                .map(|a| format!(" · {}", i18n::t(&lang, &format!("credits.action_{}", a))))
+                .unwrap_or_default();
+            text.push_str(&format!(
+                "\n{} {} {:+}{} → {}",
+                tx.created_at.get(..16).unwrap_or(&tx.created_at),
+                label,
+                tx.delta,
+                action,
+                tx.balance_after
+            ));
+        }
+    }
+
+    bot.send_message(chat_id, text)
+        .reply_markup(buy_keyboard(&lang))
+        .await?;
+    Ok(())
+}
+
+/// Send a one-time Stars invoice for the pack with `credits` credits.
+pub async fn send_credits_invoice(
+    bot: &Bot,
+    chat_id: ChatId,
+    shared_storage: &SharedStorage,
+    credits: i64,
+) -> ResponseResult<()> {
+    let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+    let Some(stars) = config::credits::pack_price(credits) else {
+        log::warn!("Unknown credit pack requested: {} by {}", credits, chat_id.0);
+        return Ok(());
+    };
+
+    let args = doracore::fluent_args!("credits" => credits, "stars" => stars as i64);
+    let title = i18n::t_args(&lang, "credits.invoice_title", &args);
+    let payload = format!("{}{}:{}", PAYLOAD_PREFIX, credits, chat_id.0);
+    let invoice_link = bot
+        .create_invoice_link(
+            title.clone(),
+            i18n::t(&lang, "credits.invoice_description"),
+            payload,
+            "XTR".to_string(),
+            vec![LabeledPrice::new(title, stars)],
+        )
+        .await?;
+    metrics::PAYMENT_CHECKOUT_STARTED.with_label_values(&["credits"]).inc();
+
+    let Ok(invoice_url) = Url::parse(&invoice_link) else {
+        log::error!("Invalid credits invoice URL: {}", invoice_link);
+        return Ok(());
+    };
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::url(
+        i18n::t_args(&lang, "credits.pay_button", &args),
+        invoice_url,
+    )]]);
+    bot.send_message(chat_id, i18n::t_args(&lang, "credits.invoice_sent", &args))
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// `credits:show` and `credits:buy:{credits}` callbacks.
+pub async fn handle_credits_callback(
+    bot: &Bot,
+    callback_id: teloxide::types::CallbackQueryId,
+    chat_id: ChatId,
+    data: &str,
+    shared_storage: Arc<SharedStorage>,
+) -> ResponseResult<()> {
+    let _ = bot.answer_callback_query(callback_id).await;
+    if let Some(credits) = data.strip_prefix("credits:buy:")
+        && let Ok(credits) = credits.parse::<i64>()
+    {
+        send_credits_invoice(bot, chat_id, &shared_storage, credits).await?;
+    } else if data == "credits:show" {
+        show_credits(bot, chat_id, &shared_storage).await?;
+    }
+    Ok(())
+}
+
+/// Tell the user a premium action needs more credits than they have.
+pub async fn notify_insufficient(bot: &Bot, chat_id: ChatId, shared_storage: &SharedStorage, cost: i64, balance: i64) {
+    let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+    let text = i18n::t_args(
+        &lang,
+        "credits.insufficient",
+        &doracore::fluent_args!("cost" => cost, "balance" => balance),
+    );
+    let _ = bot.send_message(chat_id, text).reply_markup(buy_keyboard(&lang)).await;
+}
+
+/// Refund whatever `task_id` was charged and tell the user if credits came back.
+/// Safe to call from every failure path — refunds are idempotent.
+pub async fn refund_task(bot: &Bot, shared_storage: &SharedStorage, chat_id: ChatId, task_id: &str, reason: &str) {
+    match shared_storage.refund_task_credits(task_id, reason).await {
+        Ok(Some(credits)) if credits > 0 => {
+            log::info!("Refunded {} credits for task {} ({})", credits, task_id, reason);
+            let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+            let text = i18n::t_args(&lang, "credits.refunded", &doracore::fluent_args!("credits" => credits));
+            let _ = bot.send_message(chat_id, text).await;
+        }
+        Ok(_) => {}
+        Err(e) => log::error!("Failed to refund credits for task {}: {}", task_id, e),
+    }
+}
+
+/// Credit a paid Star pack. Called from `handle_successful_payment` for
+/// `credits:` payloads after the generic logging.
+pub async fn handle_credits_payment(
+    bot: &Bot,
+    msg: &Message,
+    payment: &teloxide::types::SuccessfulPayment,
+    shared_storage: &SharedStorage,
+) -> ResponseResult<()> {
+    let charge_id = &payment.telegram_payment_charge_id.0;
+    // Payload: "credits:{credits}:{user_id}"
+    let mut parts = payment.invoice_payload.split(':').skip(1);
+    let (Some(credits), Some(user_id)) = (
+        parts.next().and_then(|s| s.parse::<i64>().ok()),
+        parts.next().and_then(|s| s.parse::<i64>().ok()),
+    ) else {
+        log::error!("Invalid credits payment payload: {}", payment.invoice_payload);
+        return Ok(());
+    };
+
+    // SEC #12: same payload/sender cross-check as subscriptions.
+    if let Some(from_id) = msg.from.as_ref().map(|u| u.id.0 as i64)
+        && from_id != user_id
+    {
+        log::error!(
+            "❌ Credits payload user_id mismatch: msg.from.id={} payload_user_id={} charge={}",
+            from_id,
+            user_id,
+            charge_id
+        );
+        crate::telegram::notifications::notify_admin_text(
+            bot,
+            &format!(
+                "⚠️ PAYLOAD HIJACK ATTEMPT (credits)\nSender: {}\nPayload user_id: {}\nCharge: {}",
+                from_id, user_id, charge_id
+            ),
+        )
+        .await;
+        return Ok(());
+    }
+
+    // A pack's price may change between invoice and payment; only accept
+    // the price of a pack that is still offered.
+    #[allow(clippy::unnecessary_cast)]
+    let paid = payment.total_amount as u32;
+    if config::credits::pack_price(credits) != Some(paid) {
+        log::error!(
+            "❌ Credits amount mismatch: pack={} paid={} charge={}",
+            credits,
+            paid,
+            charge_id
+        );
+        crate::telegram::notifications::notify_admin_text(
+            bot,
+            &format!(
+                "⚠️ CREDITS AMOUNT MISMATCH\nPack: {} credits\nPaid: {} Stars\nUser: {}\nCharge: {}",
+                credits, paid, user_id, charge_id
+            ),
+        )
+        .await;
+        return Ok(());
+    }
+
+    let lang = i18n::user_lang_from_storage(shared_storage, user_id).await;
+    match shared_storage
+        .add_purchased_credits(user_id, credits, i64::from(paid), charge_id)
+        .await
+    {
+        Ok(Some(balance)) => {
+            metrics::record_payment_success("credits", false);
+            metrics::record_revenue("credits", f64::from(paid));
+            let text = i18n::t_args(
+                &lang,
+                "credits.purchased",
+                &doracore::fluent_args!("credits" => credits, "balance" => balance),
+            );
+            bot.send_message(msg.chat.id, text).await?;
+        }
+        Ok(None) => {
+            log::warn!("⚠️ Duplicate credits charge — already processed. Charge: {}", charge_id);
+        }
+        Err(e) => {
+            log::error!("❌ Failed to record credits purchase: {}", e);
+            metrics::record_payment_failure("credits", "database_error");
+            crate::telegram::notifications::notify_admin_text(
+                bot,
+                &format!(
+                    "PAYMENT FAILURE (credits)\nuser_id: {}\ncredits: {}\ncharge_id: {}\nerror: {}",
+                    user_id, credits, charge_id, e
+                ),
+            )
+            .await;
+            bot.send_message(msg.chat.id, i18n::t(&lang, "credits.purchase_failed"))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Take back credits after a Stars refund of a credit pack.
+pub async fn handle_credits_refund(
+    bot: &Bot,
+    refund: &teloxide::types::RefundedPayment,
+    shared_storage: &SharedStorage,
+) -> ResponseResult<()> {
+    let charge_id = &refund.telegram_payment_charge_id.0;
+    let user_id = refund
+        .invoice_payload
+        .split(':')
+        .nth(2)
+        .and_then(|s| s.parse::<i64>().ok());
+    match shared_storage.revoke_purchased_credits(charge_id).await {
+        Ok(Some(revoked)) => {
+            if let Some(user_id) = user_id {
+                let lang = i18n::user_lang_from_storage(shared_storage, user_id).await;
+                let text = i18n::t_args(&lang, "credits.revoked", &doracore::fluent_args!("credits" => revoked));
+                let _ = bot.send_message(ChatId(user_id), text).await;
+            }
+            crate::telegram::notifications::notify_admin_text(
+                bot,
+                &format!(
+                    "💸 CREDITS REFUND processed\nUser: {:?}\nCharge: {}\nAmount: {} {}\nCredits revoked: {}",
+                    user_id, charge_id, refund.total_amount, refund.currency, revoked
+                ),
+            )
+            .await;
+            metrics::record_payment_failure("credits", "refunded");
+        }
+        Ok(None) => log::warn!("Credits refund for unknown or already revoked charge {}", charge_id),
+        Err(e) => {
+            log::error!("Failed to revoke credits on refund: {}", e);
+            crate::telegram::notifications::notify_admin_text(
+                bot,
+                &format!("⚠️ CREDITS REVOKE FAILED\nCharge: {}\nError: {}", charge_id, e),
+            )
+            .await;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn carousel_selection_is_album_split() {
+        assert_eq!(
+            premium_action(Some("2160p"), Some(0b101)),
+            Some(CreditAction::AlbumSplit)
+        );
+        assert_eq!(premium_action(None, Some(1)), Some(CreditAction::AlbumSplit));
+    }
+
+    #[test]
+    fn highres_only_above_1080p() {
+        assert_eq!(premium_action(Some("2160p"), None), Some(CreditAction::Highres));
+        assert_eq!(premium_action(Some("1440p"), None), Some(CreditAction::Highres));
+        assert_eq!(premium_action(Some("1080p"), None), None);
+        assert_eq!(premium_action(Some("best"), None), None);
+        assert_eq!(premium_action(None, None), None);
+    }
+}
@@ -4,16 +4,20 @@
 //! (alerts, disk with AlertManager, subscriptions, stats, etc.) live here.
 
 // ── Shared modules — provided by doracore ────────────────────────────────────
+pub use doracore::core::admin_events;
+pub use doracore::core::admin_roles;
 pub use doracore::core::categorizer;
 pub use doracore::core::config;
 pub use doracore::core::copyright;
 pub use doracore::core::error;
 pub use doracore::core::error_logger;
+pub use doracore::core::hosted_links;
 pub use doracore::core::logging;
 pub use doracore::core::metrics;
 pub use doracore::core::metrics_server;
 pub use doracore::core::odesli;
 pub use doracore::core::process;
+pub use doracore::core::public_api;
 pub use doracore::core::share;
 pub use doracore::core::types;
 pub use doracore::core::utils;
@@ -24,9 +28,12 @@ pub use doracore::core::disk;
 
 // ── Bot-only modules ──────────────────────────────────────────────────────────
 pub mod alerts;
+pub mod api_keys;
+pub mod credits;
 pub mod export;
 pub mod history;
 pub mod progress_pulse;
+pub mod promo;
 pub mod rate_limiter;
 pub mod retry;
 pub mod stats;
@@ -0,0 +1,543 @@
+//! Promo codes, gift subscriptions and referral rewards.
+//!
+//! - `/redeem CODE` (or `/start promo_CODE`) applies an admin-issued code: a
+//!   plan for N days, a percent discount on the next subscription invoice, or
+//!   credits.
+//! - `/gift` sells a one-time plan as a single-use gift code; the buyer gets a
+//!   `/start gift_CODE` deep link to forward.
+//! - `/invite` shows the user's `/start ref_<id>` link. Both sides get credits
+//!   after the invitee's first successful download.
+//!
+//! Every redemption and referral reward is written to `admin_audit_log`.
+//! Storage lives in `storage/{db,shared}/promo.rs`.
+
+use crate::core::config;
+use crate::core::metrics;
+use crate::i18n;
+use crate::storage::SharedStorage;
+use crate::storage::db::{self, NewPromoCode, PromoCode, PromoKind, RedeemOutcome};
+use crate::telegram::Bot;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, LabeledPrice};
+use url::Url;
+
+/// Invoice payload prefix for gifts: `gift:{plan}:{buyer_id}:{code}`. The gift
+/// code is minted at invoice time so a redelivered payment or a refund maps
+/// back to the same code.
+pub const GIFT_PAYLOAD_PREFIX: &str = "gift:";
+
+/// Stars price after a percent discount, rounded up and never below 1 Star.
+pub fn discounted_price(price: u32, percent: i32) -> u32 {
+    let percent = percent.clamp(0, 99) as u32;
+    (price * (100 - percent)).div_ceil(100).max(1)
+}
+
+/// Stars price of one billing period of `plan`, for the plans that can be sold.
+fn plan_price(plan: &str) -> Option<u32> {
+    match plan {
+        "premium" => Some(*config::subscription::PREMIUM_PRICE_STARS),
+        "vip" => Some(*config::subscription::VIP_PRICE_STARS),
+        _ => None,
+    }
+}
+
+fn plan_label(plan: &str) -> &'static str {
+    if plan == "vip" { "👑 VIP" } else { "⭐ Premium" }
+}
+
+/// Write a promo `admin_audit_log` row. The audit log lives in the SQLite
+/// database the admin dashboard reads, whatever the primary backend is.
+pub fn audit(shared_storage: &SharedStorage, admin_id: i64, action: &str, target_id: &str, details: &str) {
+    let result = db::get_connection(&shared_storage.sqlite_pool())
+        .map_err(anyhow::Error::from)
+        .and_then(|conn| {
+            db::log_admin_audit(&conn, admin_id, action, "promo", target_id, Some(details)).map_err(Into::into)
+        });
+    if let Err(e) = result {
+        log::warn!("Failed to write audit log for {} {}: {}", action, target_id, e);
+    }
+}
+
+async fn bot_link(bot: &Bot, payload: &str) -> Option<String> {
+    let me = bot.get_me().await.ok()?;
+    let username = me.user.username.clone()?;
+    Some(format!("https://t.me/{}?start={}", username, payload))
+}
+
+/// Redeem a user-typed code and apply what it grants.
+pub async fn redeem_code(
+    bot: &Bot,
+    chat_id: ChatId,
+    raw_code: &str,
+    shared_storage: &SharedStorage,
+) -> ResponseResult<()> {
+    let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+    let Some(code) = db::normalize_promo_code(raw_code) else {
+        bot.send_message(chat_id, i18n::t(&lang, "promo.invalid")).await?;
+        return Ok(());
+    };
+
+    // Plan codes would silently replace a running (possibly recurring) paid
+    // plan, so they are only accepted on the free plan.
+    let plan = shared_storage
+        .get_user(chat_id.0)
+        .await
+        .ok()
+        .flatten()
+        .map(|user| user.plan)
+        .unwrap_or_default();
+    if plan.is_paid()
+        && let Ok(Some(promo)) = shared_storage.get_promo_code(&code).await
+        && promo.kind == PromoKind::Plan
+    {
+        bot.send_message(chat_id, i18n::t(&lang, "promo.already_paid")).await?;
+        return Ok(());
+    }
+
+    let promo = match shared_storage.redeem_promo_code(&code, chat_id.0).await {
+        Ok(RedeemOutcome::Redeemed(promo)) => promo,
+        Ok(outcome) => {
+            let key = match outcome {
+                RedeemOutcome::Expired => "promo.expired",
+                RedeemOutcome::Exhausted => "promo.exhausted",
+                RedeemOutcome::AlreadyRedeemed => "promo.already_redeemed",
+                _ => "promo.not_found",
+            };
+            bot.send_message(chat_id, i18n::t(&lang, key)).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            log::error!("Failed to redeem promo code {} for {}: {}", code, chat_id.0, e);
+            bot.send_message(chat_id, i18n::t(&lang, "promo.failed")).await?;
+            return Ok(());
+        }
+    };
+
+    audit(
+        shared_storage,
+        promo.created_by,
+        "promo_redeem",
+        &promo.code,
+        &format!("user={} kind={} source={}", chat_id.0, promo.kind, promo.source),
+    );
+
+    match apply_reward(shared_storage, chat_id.0, &promo).await {
+        Ok(text_key_args) => {
+            let (key, args) = text_key_args;
+            bot.send_message(chat_id, i18n::t_args(&lang, key, &args)).await?;
+        }
+        Err(e) => {
+            log::error!("Failed to apply promo {} for {}: {}", promo.code, chat_id.0, e);
+            crate::telegram::notifications::notify_admin_text(
+                bot,
+                &format!(
+                    "⚠️ PROMO APPLY FAILED\nCode: {}\nUser: {}\nError: {}",
+                    promo.code, chat_id.0, e
+                ),
+            )
+            .await;
+            bot.send_message(chat_id, i18n::t(&lang, "promo.failed")).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Apply a redeemed code. Returns the confirmation message key and args.
+async fn apply_reward(
+    shared_storage: &SharedStorage,
+    user_id: i64,
+    promo: &PromoCode,
+) -> anyhow::Result<(&'static str, i18n::FluentArgs<'static>)> {
+    match promo.kind {
+        PromoKind::Plan => {
+            let plan = promo.plan.clone().unwrap_or_else(|| "premium".to_string());
+            let days = promo.days.unwrap_or(config::promo::GIFT_DAYS);
+            shared_storage
+                .update_user_plan_with_expiry(user_id, &plan, Some(days))
+                .await?;
+            Ok((
+                "promo.plan_activated",
+                doracore::fluent_args!("plan" => plan_label(&plan), "days" => days as i64),
+            ))
+        }
+        PromoKind::Discount => Ok((
+            "promo.discount_saved",
+            doracore::fluent_args!("percent" => promo.percent.unwrap_or(0) as i64),
+        )),
+        PromoKind::Credits => {
+            let credits = promo.credits.unwrap_or(0);
+            let grant_key = format!("promo:{}:{}", promo.code, user_id);
+            let note = format!("promo {}", promo.code);
+            let balance = match shared_storage
+                .grant_credits(user_id, credits, &grant_key, Some(&note))
+                .await?
+            {
+                Some(balance) => balance,
+                None => shared_storage.get_credit_balance(user_id).await?,
+            };
+            Ok((
+                "promo.credits_added",
+                doracore::fluent_args!("credits" => credits, "balance" => balance),
+            ))
+        }
+    }
+}
+
+/// `/redeem CODE`
+pub async fn handle_redeem_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_text: &str,
+    shared_storage: &SharedStorage,
+) -> ResponseResult<()> {
+    match message_text.split_whitespace().nth(1) {
+        Some(code) => redeem_code(bot, chat_id, code, shared_storage).await,
+        None => {
+            let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+            bot.send_message(chat_id, i18n::t(&lang, "promo.usage")).await?;
+            Ok(())
+        }
+    }
+}
+
+/// `/gift` — pick which plan to buy as a gift.
+pub async fn show_gift_menu(bot: &Bot, chat_id: ChatId, shared_storage: &SharedStorage) -> ResponseResult<()> {
+    let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+    let rows = ["premium", "vip"]
+        .iter()
+        .filter_map(|&plan| {
+            let stars = plan_price(plan)?;
+            Some(vec![InlineKeyboardButton::callback(
+                i18n::t_args(
+                    &lang,
+                    "promo.gift_button",
+                    &doracore::fluent_args!("plan" => plan_label(plan), "stars" => stars as i64),
+                ),
+                format!("promo:gift:{}", plan),
+            )])
+        })
+        .collect::<Vec<_>>();
+    bot.send_message(
+        chat_id,
+        i18n::t_args(
+            &lang,
+            "promo.gift_menu",
+            &doracore::fluent_args!("days" => config::promo::GIFT_DAYS as i64),
+        ),
+    )
+    .reply_markup(InlineKeyboardMarkup::new(rows))
+    .await?;
+    Ok(())
+}
+
+/// Send a one-time Stars invoice for a gift of `plan`.
+async fn send_gift_invoice(
+    bot: &Bot,
+    chat_id: ChatId,
+    shared_storage: &SharedStorage,
+    plan: &str,
+) -> ResponseResult<()> {
+    let Some(stars) = plan_price(plan) else {
+        log::warn!("Unknown gift plan requested: {} by {}", plan, chat_id.0);
+        return Ok(());
+    };
+    let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+    let code = format!("GIFT-{}", &uuid::Uuid::new_v4().simple().to_string()[..10]).to_ascii_uppercase();
+    let args = doracore::fluent_args!(
+        "plan" => plan_label(plan),
+        "stars" => stars as i64,
+        "days" => config::promo::GIFT_DAYS as i64,
+    );
+    let title = i18n::t_args(&lang, "promo.gift_invoice_title", &args);
+    let payload = format!("{}{}:{}:{}", GIFT_PAYLOAD_PREFIX, plan, chat_id.0, code);
+    let invoice_link = bot
+        .create_invoice_link(
+            title.clone(),
+            i18n::t_args(&lang, "promo.gift_invoice_description", &args),
+            payload,
+            "XTR".to_string(),
+            vec![LabeledPrice::new(title, stars)],
+        )
+        .await?;
+    metrics::PAYMENT_CHECKOUT_STARTED.with_label_values(&["gift"]).inc();
+
+    let Ok(invoice_url) = Url::parse(&invoice_link) else {
+        log::error!("Invalid gift invoice URL: {}", invoice_link);
+        return Ok(());
+    };
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::url(
+        i18n::t_args(&lang, "promo.gift_pay_button", &args),
+        invoice_url,
+    )]]);
+    bot.send_message(chat_id, i18n::t_args(&lang, "promo.gift_invoice_sent", &args))
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// `promo:gift:{plan}` callbacks.
+pub async fn handle_promo_callback(
+    bot: &Bot,
+    callback_id: teloxide::types::CallbackQueryId,
+    chat_id: ChatId,
+    data: &str,
+    shared_storage: Arc<SharedStorage>,
+) -> ResponseResult<()> {
+    let _ = bot.answer_callback_query(callback_id).await;
+    if let Some(plan) = data.strip_prefix("promo:gift:") {
+        send_gift_invoice(bot, chat_id, &shared_storage, plan).await?;
+    }
+    Ok(())
+}
+
+/// Mint the gift code for a paid gift invoice and hand the buyer the link.
+/// Called from `handle_successful_payment` for `gift:` payloads.
+pub async fn handle_gift_payment(
+    bot: &Bot,
+    msg: &Message,
+    payment: &teloxide::types::SuccessfulPayment,
+    shared_storage: &SharedStorage,
+) -> ResponseResult<()> {
+    let charge_id = &payment.telegram_payment_charge_id.0;
+    // Payload: "gift:{plan}:{buyer_id}:{code}"
+    let mut parts = payment.invoice_payload.split(':').skip(1);
+    let (Some(plan), Some(buyer_id), Some(code)) = (
+        parts.next(),
+        parts.next().and_then(|s| s.parse::<i64>().ok()),
+        parts.next(),
+    ) else {
+        log::error!("Invalid gift payment payload: {}", payment.invoice_payload);
+        return Ok(());
+    };
+
+    // SEC #12: same payload/sender cross-check as subscriptions.
+    if let Some(from_id) = msg.from.as_ref().map(|u| u.id.0 as i64)
+        && from_id != buyer_id
+    {
+        log::error!(
+            "❌ Gift payload user_id mismatch: msg.from.id={} payload_user_id={} charge={}",
+            from_id,
+            buyer_id,
+            charge_id
+        );
+        crate::telegram::notifications::notify_admin_text(
+            bot,
+            &format!(
+                "⚠️ PAYLOAD HIJACK ATTEMPT (gift)\nSender: {}\nPayload user_id: {}\nCharge: {}",
+                from_id, buyer_id, charge_id
+            ),
+        )
+        .await;
+        return Ok(());
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    let paid = payment.total_amount as u32;
+    if plan_price(plan) != Some(paid) {
+        log::error!(
+            "❌ Gift amount mismatch: plan={} paid={} charge={}",
+            plan,
+            paid,
+            charge_id
+        );
+        crate::telegram::notifications::notify_admin_text(
+            bot,
+            &format!(
+                "⚠️ GIFT AMOUNT MISMATCH\nPlan: {}\nPaid: {} Stars\nBuyer: {}\nCharge: {}",
+                plan, paid, buyer_id, charge_id
+            ),
+        )
+        .await;
+        return Ok(());
+    }
+
+    // The UNIQUE charge id makes a redelivered update a no-op.
+    if let Err(e) = shared_storage
+        .save_charge(
+            buyer_id,
+            plan,
+            charge_id,
+            Some(&payment.provider_payment_charge_id),
+            &payment.currency,
+            payment.total_amount as i64,
+            &payment.invoice_payload,
+            false,
+            false,
+            None,
+        )
+        .await
+    {
+        let msg_lower = e.to_string().to_lowercase();
+        if msg_lower.contains("unique") || msg_lower.contains("duplicate") {
+            log::warn!("⚠️ Duplicate gift charge — already processed. Charge: {}", charge_id);
+            return Ok(());
+        }
+        log::error!("❌ Failed to record gift charge: {}", e);
+    }
+
+    let lang = i18n::user_lang_from_storage(shared_storage, buyer_id).await;
+    let new_code = NewPromoCode {
+        code: code.to_string(),
+        kind: PromoKind::Plan,
+        plan: Some(plan.to_string()),
+        days: Some(config::promo::GIFT_DAYS),
+        percent: None,
+        credits: None,
+        max_uses: Some(1),
+        expires_at: None,
+        source: db::PROMO_SOURCE_GIFT,
+        created_by: buyer_id,
+    };
+    if let Err(e) = shared_storage.create_promo_code(&new_code).await {
+        log::error!("❌ Failed to mint gift code {}: {}", code, e);
+        metrics::record_payment_failure("gift", "database_error");
+        crate::telegram::notifications::notify_admin_text(
+            bot,
+            &format!(
+                "PAYMENT FAILURE (gift)\nbuyer: {}\nplan: {}\ncode: {}\ncharge_id: {}\nerror: {}",
+                buyer_id, plan, code, charge_id, e
+            ),
+        )
+        .await;
+        bot.send_message(msg.chat.id, i18n::t(&lang, "promo.gift_failed"))
+            .await?;
+        return Ok(());
+    }
+    metrics::record_payment_success("gift", false);
+    metrics::record_revenue("gift", f64::from(paid));
+
+    let link = bot_link(bot, &format!("gift_{}", code))
+        .await
+        .unwrap_or_else(|| format!("/redeem {}", code));
+    let text = i18n::t_args(
+        &lang,
+        "promo.gift_ready",
+        &doracore::fluent_args!(
+            "plan" => plan_label(plan),
+            "days" => config::promo::GIFT_DAYS as i64,
+            "code" => code,
+            "link" => link,
+        ),
+    );
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
+
+/// Disable the gift code of a refunded gift so it can no longer be redeemed.
+/// A gift that was already redeemed stays with its recipient.
+pub async fn handle_gift_refund(
+    bot: &Bot,
+    refund: &teloxide::types::RefundedPayment,
+    shared_storage: &SharedStorage,
+) -> ResponseResult<()> {
+    let Some(code) = refund.invoice_payload.split(':').nth(3) else {
+        log::error!("Invalid gift refund payload: {}", refund.invoice_payload);
+        return Ok(());
+    };
+    let redeemed = match shared_storage.get_promo_code(code).await {
+        Ok(Some(promo)) => promo.used_count > 0,
+        _ => false,
+    };
+    if let Err(e) = shared_storage.set_promo_code_active(code, false).await {
+        log::error!("Failed to disable refunded gift code {}: {}", code, e);
+    }
+    crate::telegram::notifications::notify_admin_text(
+        bot,
+        &format!(
+            "💸 GIFT REFUND processed\nCode: {}\nCharge: {}\nAmount: {} {}\nAlready redeemed: {}",
+            code, refund.telegram_payment_charge_id.0, refund.total_amount, refund.currency, redeemed
+        ),
+    )
+    .await;
+    metrics::record_payment_failure("gift", "refunded");
+    Ok(())
+}
+
+/// `/start ref_<id>` from a brand-new user: remember the referrer.
+pub async fn record_referral(shared_storage: &SharedStorage, invitee_id: i64, payload: &str) {
+    let Ok(referrer_id) = payload.trim().parse::<i64>() else {
+        return;
+    };
+    let referrer_exists = matches!(shared_storage.get_user(referrer_id).await, Ok(Some(_)));
+    if !referrer_exists {
+        return;
+    }
+    match shared_storage.record_referral(invitee_id, referrer_id).await {
+        Ok(true) => log::info!("Referral recorded: {} invited {}", referrer_id, invitee_id),
+        Ok(false) => {}
+        Err(e) => log::warn!("Failed to record referral {} -> {}: {}", referrer_id, invitee_id, e),
+    }
+}
+
+/// Reward both sides of a referral once the invitee has finished a download.
+/// Cheap no-op for users without a pending referral.
+pub async fn reward_referral(bot: &Bot, shared_storage: &SharedStorage, invitee_id: i64) {
+    let referrer_id = match shared_storage.claim_referral_reward(invitee_id).await {
+        Ok(Some(referrer_id)) => referrer_id,
+        Ok(None) => return,
+        Err(e) => {
+            log::warn!("Failed to claim referral reward for {}: {}", invitee_id, e);
+            return;
+        }
+    };
+    let credits = *config::promo::REFERRAL_REWARD_CREDITS;
+    for (user_id, side) in [(referrer_id, "referrer"), (invitee_id, "invitee")] {
+        let grant_key = format!("referral:{}:{}", invitee_id, side);
+        match shared_storage
+            .grant_credits(user_id, credits, &grant_key, Some("referral"))
+            .await
+        {
+            Ok(_) => {
+                let lang = i18n::user_lang_from_storage(shared_storage, user_id).await;
+                let text = i18n::t_args(
+                    &lang,
+                    "promo.referral_reward",
+                    &doracore::fluent_args!("credits" => credits),
+                );
+                let _ = bot.send_message(ChatId(user_id), text).await;
+            }
+            Err(e) => log::error!("Failed to grant referral credits to {}: {}", user_id, e),
+        }
+    }
+    audit(
+        shared_storage,
+        0,
+        "referral_reward",
+        &invitee_id.to_string(),
+        &format!("referrer={} invitee={} credits={}", referrer_id, invitee_id, credits),
+    );
+}
+
+/// `/invite` — the user's referral link and how many friends joined.
+pub async fn show_invite(bot: &Bot, chat_id: ChatId, shared_storage: &SharedStorage) -> ResponseResult<()> {
+    let lang = i18n::user_lang_from_storage(shared_storage, chat_id.0).await;
+    let (invited, rewarded) = shared_storage.count_referrals(chat_id.0).await.unwrap_or((0, 0));
+    let link = bot_link(bot, &format!("ref_{}", chat_id.0)).await.unwrap_or_default();
+    let text = i18n::t_args(
+        &lang,
+        "promo.invite",
+        &doracore::fluent_args!(
+            "link" => link,
+            "credits" => *config::promo::REFERRAL_REWARD_CREDITS,
+            "invited" => invited,
+            "rewarded" => rewarded,
+        ),
+    );
+    bot.send_message(chat_id, text).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discount_rounds_up_and_keeps_one_star() {
+        assert_eq!(discounted_price(100, 20), 80);
+        assert_eq!(discounted_price(99, 50), 50);
+        assert_eq!(discounted_price(1, 99), 1);
+        assert_eq!(discounted_price(100, 150), 1);
+        assert_eq!(discounted_price(100, 0), 100);
+    }
+}
@@ -441,7 +441,17 @@ pub async fn show_subscription_info(
 ///
 /// Creates a recurring invoice with automatic monthly Star charges.
 /// Telegram will automatically charge the specified amount every 30 days.
-pub async fn create_subscription_invoice(bot: &Bot, chat_id: ChatId, plan: &str) -> ResponseResult<Message> {
+/// A redeemed promo `discount` (`(code, percent)`) only covers the first
+/// period: the invoice is then a one-time payment for 30 days at the
+/// discounted price, without auto-renewal, so Telegram never charges the
+/// discount again. The code goes in the payload so the payment handler can
+/// validate and consume it.
+pub async fn create_subscription_invoice(
+    bot: &Bot,
+    chat_id: ChatId,
+    plan: &str,
+    discount: Option<(&str, i32)>,
+) -> ResponseResult<Message> {
     log::info!(
         "🎯 create_subscription_invoice called for chat_id: {}, plan: {}",
         chat_id.0,
@@ -481,41 +491,64 @@ pub async fn create_subscription_invoice(bot: &Bot, chat_id: ChatId, plan: &str)
         }
     };
 
+    let (description, price_stars) = match discount {
+        Some((code, percent)) => {
+            let discounted = crate::core::promo::discounted_price(price_stars, percent);
+            (
+                format!(
+                    "{}\n\n🎟 Promo code {}: -{}%, {} Stars instead of {} for the first 30 days. \
+                     This payment does not renew; subscribe again at the full price afterwards.",
+                    description, code, percent, discounted, price_stars
+                ),
+                discounted,
+            )
+        }
+        None => (description, price_stars),
+    };
+
     // Create payload to identify the payment
-    let payload = format!("subscription:{}:{}", plan, chat_id.0);
+    let payload = match discount {
+        Some((code, _)) => format!("subscription:{}:{}:{}", plan, chat_id.0, code),
+        None => format!("subscription:{}:{}", plan, chat_id.0),
+    };
     log::info!("📦 Invoice payload: {}", payload);
 
     // Create invoice with subscription support
     use teloxide::types::LabeledPrice;
 
+    let recurring = discount.is_none();
     log::info!(
-        "💰 Creating RECURRING subscription invoice link for {} plan - price: {} Stars every 30 days",
+        "💰 Creating {} subscription invoice link for {} plan - price: {} Stars",
+        if recurring { "RECURRING" } else { "ONE-TIME (promo)" },
         plan,
         price_stars
     );
     log::info!(
-        "📝 Invoice details: title='{}', currency=XTR, price={} Stars, subscription_period=2592000 sec (30 days)",
+        "📝 Invoice details: title='{}', currency=XTR, price={} Stars, recurring={}",
         title,
-        price_stars
+        price_stars,
+        recurring
     );
 
-    // Create invoice link with subscription_period
-    let invoice_link_result = bot
-        .create_invoice_link(
-            title,
-            description.clone(),
-            payload,
-            "XTR".to_string(), // Only XTR (Telegram Stars) for subscriptions
-            vec![LabeledPrice::new(
-                format!(
-                    "{} subscription",
-                    if plan == "premium" { "Premium" } else { "VIP" }
-                ),
-                price_stars, // Price in Stars
-            )],
-        )
-        .subscription_period(Seconds::from_seconds(crate::core::config::subscription::SUBSCRIPTION_PERIOD_SECONDS)) // 30 days in seconds - AUTO-RENEWAL EVERY 30 DAYS
-        .await;
+    let request = bot.create_invoice_link(
+        title,
+        description.clone(),
+        payload,
+        "XTR".to_string(), // Only XTR (Telegram Stars) for subscriptions
+        vec![LabeledPrice::new(
+            format!("{} subscription", if plan == "premium" { "Premium" } else { "VIP" }),
+            price_stars, // Price in Stars
+        )],
+    );
+    // Only full-price invoices renew: a subscription_period would repeat the
+    // discounted price on every renewal.
+    let invoice_link_result = if recurring {
+        request
+            .subscription_period(Seconds::from_seconds(crate::core::config::subscription::SUBSCRIPTION_PERIOD_SECONDS)) // 30 days in seconds - AUTO-RENEWAL EVERY 30 DAYS
+            .await
+    } else {
+        request.await
+    };
 
     match invoice_link_result {
         Ok(invoice_link) => {
@@ -552,7 +585,8 @@ pub async fn create_subscription_invoice(bot: &Bot, chat_id: ChatId, plan: &str)
                 .replace("(", "\\(")
                 .replace(")", "\\)")
                 .replace("+", "\\+")
-                .replace("!", "\\!");
+                .replace("!", "\\!")
+                .replace("_", "\\_");
 
             bot.send_message(
                 chat_id,
@@ -630,6 +664,16 @@ pub async fn handle_refunded_payment(
         refund.invoice_payload
     );
 
+    if refund.invoice_payload.starts_with(crate::core::credits::PAYLOAD_PREFIX) {
+        return crate::core::credits::handle_credits_refund(bot, refund, &shared_storage).await;
+    }
+    if refund
+        .invoice_payload
+        .starts_with(crate::core::promo::GIFT_PAYLOAD_PREFIX)
+    {
+        return crate::core::promo::handle_gift_refund(bot, refund, &shared_storage).await;
+    }
+
     // Look up user by charge_id
     let user_id = match shared_storage.get_user_id_by_charge(charge_id).await {
         Ok(Some(uid)) => uid,
@@ -725,11 +769,34 @@ pub async fn handle_successful_payment(
         );
         log::info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-        // Parse payload: "subscription:premium:12345678"
+        // One-time Star packs: "credits:50:12345678"
+        if payment
+            .invoice_payload
+            .starts_with(crate::core::credits::PAYLOAD_PREFIX)
+        {
+            return crate::core::credits::handle_credits_payment(bot, msg, payment, &shared_storage).await;
+        }
+
+        // Gift subscriptions: "gift:premium:12345678:GIFT-XXXXXXXXXX"
+        if payment
+            .invoice_payload
+            .starts_with(crate::core::promo::GIFT_PAYLOAD_PREFIX)
+        {
+            return crate::core::promo::handle_gift_payment(bot, msg, payment, &shared_storage).await;
+        }
+
+        // Parse payload: "subscription:premium:12345678", with an optional
+        // fourth part naming the discount code applied to the invoice.
         let parts: Vec<&str> = payment.invoice_payload.split(':').collect();
-        if parts.len() == 3 && parts[0] == "subscription" {
+        if (parts.len() == 3 || parts.len() == 4) && parts[0] == "subscription" {
             let plan = parts[1];
             let telegram_id = parts[2].parse::<i64>().unwrap_or(0);
+            // Promo codes only apply to one-time first-period invoices. A
+            // recurring charge carrying a code (links created before promo
+            // invoices stopped renewing) must be at the full price and never
+            // consumes the code again.
+            let promo_renewal = payment.is_recurring && parts.len() == 4;
+            let discount_code = parts.get(3).copied().filter(|_| !promo_renewal);
 
             if telegram_id == 0 {
                 log::error!("Invalid telegram_id in payment payload: {}", payment.invoice_payload);
@@ -770,13 +837,21 @@ pub async fn handle_successful_payment(
                     return Ok(());
                 }
             };
+            let expected_price = match discount_code {
+                Some(code) => match shared_storage.get_promo_code(code).await {
+                    Ok(Some(promo)) => crate::core::promo::discounted_price(expected_price, promo.percent.unwrap_or(0)),
+                    _ => expected_price,
+                },
+                None => expected_price,
+            };
             // Validate payment amount — but skip for recurring payments because
             // Telegram charges the original invoice price, not the current config price.
             // If price was raised after the user subscribed, recurring charges still use
-            // the old amount and that's expected behavior.
+            // the old amount and that's expected behavior. Renewals of promo
+            // invoices are the exception: an old price there is the discount.
             #[allow(clippy::unnecessary_cast)]
             if payment.total_amount as u32 != expected_price {
-                if payment.is_recurring {
+                if payment.is_recurring && !promo_renewal {
                     // Recurring charges use the original invoice price, not current config.
                     log::warn!(
                         "⚠️ Recurring payment at old price: plan={}, current_price={}, paid={}, user={}. Accepting.",
@@ -889,6 +964,12 @@ pub async fn handle_successful_payment(
             }
             log::info!("✅ Payment recorded atomically");
 
+            if let Some(code) = discount_code
+                && let Err(e) = shared_storage.consume_discount(telegram_id, code).await
+            {
+                log::warn!("Failed to consume discount {} for {}: {}", code, telegram_id, e);
+            }
+
             // Track payment success metrics (after successful record)
             metrics::record_payment_success(plan, is_recurring);
             metrics::record_revenue(plan, payment.total_amount as f64);
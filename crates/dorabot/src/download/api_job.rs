@@ -0,0 +1,158 @@
+//! Worker side of public API jobs (`/api/v1/jobs`).
+//!
+//! API jobs are ordinary queue tasks, but nothing is sent to Telegram: the
+//! file is downloaded with the shared pipeline, published as a signed hosted
+//! link and the outcome is recorded on the `api_jobs` row for the API to
+//! report. Failures are recorded there too and refunded, so the queue task
+//! itself always completes and is never retried behind the client's back.
+
+use crate::core::config;
+use crate::core::public_api;
+use crate::download::context::DownloadContext;
+use crate::download::pipeline::{self, PipelineFormat};
+use crate::download::progress::ProgressMessage;
+use crate::download::queue::DownloadFormat;
+use crate::download::source::bot_global;
+use crate::download::ytdlp_errors::sanitize_user_error_message;
+use crate::storage::SharedStorage;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use tokio::time::timeout;
+
+/// Download `ctx.url` for API job `task_id` and publish the result. Returns
+/// whether the job was delivered; a failed job is marked failed on its row and
+/// the credits charged at enqueue are refunded without messaging the chat.
+pub async fn download_for_api(
+    ctx: DownloadContext,
+    task_id: &str,
+    format: &DownloadFormat,
+    video_quality: Option<String>,
+    audio_bitrate: Option<String>,
+) -> ResponseResult<bool> {
+    let Some(shared_storage) = ctx.shared_storage.clone() else {
+        log::error!("API job {} has no shared storage, dropping", task_id);
+        return Ok(false);
+    };
+    match publish(ctx, &shared_storage, task_id, format, video_quality, audio_bitrate).await {
+        Ok(()) => Ok(true),
+        Err(message) => {
+            public_api::fail(&shared_storage, task_id, &message).await;
+            match shared_storage.refund_task_credits(task_id, "failed").await {
+                Ok(Some(credits)) if credits > 0 => {
+                    log::info!("Refunded {} credits for failed API job {}", credits, task_id)
+                }
+                Ok(_) => {}
+                Err(e) => log::error!("Failed to refund credits for API job {}: {}", task_id, e),
+            }
+            Ok(false)
+        }
+    }
+}
+
+/// The job itself; `Err` carries the client-facing failure message.
+async fn publish(
+    ctx: DownloadContext,
+    shared_storage: &Arc<SharedStorage>,
+    task_id: &str,
+    format: &DownloadFormat,
+    video_quality: Option<String>,
+    audio_bitrate: Option<String>,
+) -> Result<(), String> {
+    let download_timeout = config::download::global_timeout_for_quality(video_quality.as_deref());
+    let format = match format {
+        DownloadFormat::Mp3 => PipelineFormat::Audio {
+            bitrate: audio_bitrate,
+            time_range: None,
+        },
+        DownloadFormat::Mp4 => PipelineFormat::Video {
+            quality: video_quality,
+            time_range: None,
+        },
+        DownloadFormat::Srt | DownloadFormat::Txt => return Err("unsupported format".to_string()),
+    };
+
+    let lang = crate::i18n::user_lang_from_storage(shared_storage, ctx.chat_id.0).await;
+    let mut progress_msg = ProgressMessage::new(ctx.chat_id, lang)
+        .silent(true)
+        .task(Some(task_id.to_string()))
+        .api_job(true);
+
+    let phase = timeout(
+        download_timeout,
+        pipeline::download_phase(
+            &ctx.bot,
+            ctx.chat_id,
+            &ctx.url,
+            &format,
+            bot_global(),
+            &mut progress_msg,
+            None,
+            Some(shared_storage),
+        ),
+    )
+    .await;
+    let phase = match phase {
+        Ok(Ok(phase)) => phase,
+        Ok(Err(e)) => {
+            log::warn!("API job {} download failed: {}", task_id, e);
+            return Err(sanitize_user_error_message(&e.into_app_error().to_string()));
+        }
+        Err(_) => {
+            log::warn!("API job {} timed out", task_id);
+            return Err("download timed out".to_string());
+        }
+    };
+
+    let output = phase.output;
+    let link = match public_api::deliver(
+        shared_storage,
+        task_id,
+        ctx.chat_id.0,
+        &output.file_path,
+        &phase.display_title,
+    )
+    .await
+    {
+        Ok(link) => link,
+        Err(e) => {
+            log::warn!("API job {} could not be published: {:#}", task_id, e);
+            pipeline::schedule_cleanup(output.file_path);
+            return Err("could not publish the result".to_string());
+        }
+    };
+    log::info!(
+        "API job {} published as hosted file {} ({} bytes)",
+        task_id,
+        link.id,
+        link.file_size
+    );
+    pipeline::schedule_hosted_cleanup(Arc::clone(shared_storage), &link);
+
+    let (video_quality, audio_bitrate) = match &format {
+        PipelineFormat::Audio { bitrate, .. } => (None, bitrate.as_deref().or(Some("320k"))),
+        PipelineFormat::Video { quality, .. } => (quality.as_deref(), None),
+    };
+    let artist = phase.artist.trim();
+    let canonical_url = doracore::download::url_canonical::canonicalize_url(ctx.url.as_str());
+    if let Err(e) = shared_storage
+        .save_download_history(
+            ctx.chat_id.0,
+            &canonical_url,
+            &phase.title,
+            format.label(),
+            None,
+            (!artist.is_empty()).then_some(artist),
+            Some(link.file_size as i64),
+            output.duration_secs.map(i64::from),
+            video_quality,
+            audio_bitrate,
+            None,
+            None,
+            None,
+        )
+        .await
+    {
+        log::warn!("Failed to save download history for API job {}: {}", task_id, e);
+    }
+    Ok(())
+}
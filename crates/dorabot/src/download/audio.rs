@@ -42,6 +42,7 @@ pub async fn download_and_send_audio(
         alert_manager,
         created_timestamp: _created_timestamp,
         silent,
+        task_id,
     } = ctx;
     log::info!(
         "Starting download_and_send_audio for chat {} with URL: {}",
@@ -93,7 +94,7 @@ pub async fn download_and_send_audio(
         } else {
             crate::i18n::lang_from_code("ru")
         };
-        let mut progress_msg = ProgressMessage::new(chat_id, lang.clone()).silent(silent);
+        let mut progress_msg = ProgressMessage::new(chat_id, lang.clone()).silent(silent).task(task_id);
         if let Some(ref storage) = shared_storage_clone
             && let Ok(style_str) = storage.get_user_progress_bar_style(chat_id.0).await
         {
@@ -128,8 +129,9 @@ pub async fn download_and_send_audio(
                     .ok();
             }
 
-            // Audio-specific: add effects button (skipped in silent mode — no chatter)
-            if !silent {
+            // Audio-specific: add effects button (skipped in silent mode — no chatter,
+            // and for hosted links — there's no audio message to attach it to)
+            if !silent && !pipeline_result.hosted {
                 add_audio_effects_button(
                     &bot_clone,
                     chat_id,
@@ -148,7 +150,7 @@ pub async fn download_and_send_audio(
                 pipeline_result.artist,
                 shared_storage_clone.is_some()
             );
-            if with_lyrics && !silent {
+            if with_lyrics && !silent && !pipeline_result.hosted {
                 let bot_lyr = bot_clone.clone();
                 let title_lyr = pipeline_result.title.clone();
                 let artist_lyr = pipeline_result.artist.clone();
@@ -18,6 +18,8 @@
 //!     message_id: task.message_id,
 //!     alert_manager: alert_manager.clone(),
 //!     created_timestamp: task.created_timestamp,
+//!     silent,
+//!     task_id: Some(task.id.clone()),
 //! };
 //! download_and_send_audio(ctx, audio_bitrate, time_range, with_lyrics).await
 //! ```
@@ -61,4 +63,7 @@ pub struct DownloadContext {
     /// with `disable_notification`, and record the result in `silent_digest`
     /// for a MOTD recap on the user's next interaction.
     pub silent: bool,
+    /// Queue task being processed; tags progress updates for the admin live
+    /// stream. `None` outside the queue processor.
+    pub task_id: Option<String>,
 }
@@ -7,7 +7,9 @@
 pub use doracore::download::audio_effects;
 pub use doracore::download::cookies;
 pub use doracore::download::error;
+pub use doracore::download::feed;
 pub use doracore::download::fetch;
+pub use doracore::download::filename_template;
 pub use doracore::download::playlist;
 pub use doracore::download::proxy;
 pub use doracore::download::ringtone;
@@ -19,6 +21,7 @@ pub use doracore::download::ytdlp_errors;
 pub use doracore::download::builder; // DownloadRequest builder (shared with doracore)
 
 // ── Bot-specific modules ──────────────────────────────────────────────────────
+pub mod api_job; // Public API jobs: download + publish as hosted link
 pub mod audio; // Telegram audio download + send pipeline
 pub mod cancel_registry; // GH #9: per-user cancel flags for active downloads
 pub mod context; // Shared DownloadContext for download entry points
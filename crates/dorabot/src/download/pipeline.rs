@@ -13,6 +13,7 @@ use crate::core::config;
 use crate::core::disk;
 use crate::core::error::AppError;
 use crate::core::error_logger::{self, ErrorType, UserContext};
+use crate::core::hosted_links::HostedLink;
 use crate::core::metrics;
 use crate::core::utils::format_media_caption_rich;
 
@@ -33,14 +34,18 @@ fn caption_platform(url: &str) -> &'static str {
 use crate::download::builder::DownloadConfigBuilder;
 use crate::download::downloader::cleanup_partial_download;
 use crate::download::error::DownloadError;
+use crate::download::feed;
 use crate::download::progress::{DownloadStatus, ProgressMessage};
 use crate::download::send::{
-    send_audio_with_retry, send_error_with_sticker, send_error_with_sticker_and_message, send_video_with_retry,
+    send_audio_with_retry, send_error_with_sticker, send_error_with_sticker_and_message, send_hosted_link,
+    send_video_with_retry,
 };
+use crate::download::source::http::HttpSource;
 use crate::download::source::{DownloadOutput, DownloadSource, MediaMetadata, SourceProgress, SourceRegistry};
 use crate::storage::SharedStorage;
 use crate::storage::db::{self as db, DbPool};
 use crate::telegram::Bot;
+use crate::timestamps::VideoTimestamp;
 use anyhow::Context;
 use doracore::download::ProgressPhase;
 use std::sync::{Arc, LazyLock};
@@ -292,6 +297,17 @@ impl PipelineFormat {
         }
     }
 
+    /// Largest file worth downloading: the upload cap, or the hosted-link
+    /// limit when oversized files can be delivered as a link instead.
+    pub fn download_size_cap(&self) -> u64 {
+        let cap = self.max_file_size();
+        if config::hosted_links::is_available() {
+            cap.max(config::hosted_links::max_bytes())
+        } else {
+            cap
+        }
+    }
+
     /// Returns the time_range regardless of variant.
     pub fn time_range(&self) -> &Option<(String, String)> {
         match self {
@@ -319,6 +335,8 @@ pub struct PipelineResult {
     pub download_path: String,
     /// The download output details
     pub output: DownloadOutput,
+    /// Delivered as a hosted link instead of a Telegram file
+    pub hosted: bool,
 }
 
 /// Result of the download phase (before send).
@@ -385,6 +403,7 @@ fn cached_pipeline_result(
             mime_hint: None,
             additional_files: None,
         },
+        hosted: false,
     }
 }
 
@@ -441,6 +460,21 @@ async fn cached_title_artist(
     }
 }
 
+/// Chapters for a feed episode: inline ones from the feed, otherwise the
+/// Podcasting 2.0 chapters document (best effort).
+async fn feed_episode_chapters(episode: &db::FeedEpisode) -> Vec<VideoTimestamp> {
+    if !episode.chapters.is_empty() {
+        return episode.chapters.clone();
+    }
+    let Some(chapters_url) = episode.chapters_url.as_deref() else {
+        return Vec::new();
+    };
+    feed::fetch_json_chapters(chapters_url).await.unwrap_or_else(|e| {
+        log::warn!("Pipeline: failed to fetch chapters {}: {}", chapters_url, e);
+        Vec::new()
+    })
+}
+
 /// Execute the download phase only: resolve → metadata → pre-checks → download with progress.
 ///
 /// Returns the download result and metadata. The caller handles sending, history,
@@ -461,12 +495,26 @@ pub async fn download_phase(
     let pipeline_start = std::time::Instant::now();
     let file_format_str = format.label().to_string();
 
+    // Podcast enclosures announced by the RSS watcher carry the feed's title,
+    // show name, cover and chapters.
+    let feed_episode = match shared_storage {
+        Some(storage) => storage.get_feed_episode(url.as_str()).await.unwrap_or_else(|e| {
+            log::warn!("Pipeline: feed episode lookup failed: {}", e);
+            None
+        }),
+        None => None,
+    };
+
     // ── Step 1: Resolve source ──
-    let source = registry.resolve(url).ok_or_else(|| {
-        PipelineError::Operational(AppError::Download(DownloadError::Other(
-            "Unsupported URL — no download source found".to_string(),
-        )))
-    })?;
+    // Enclosures are plain files (often without an extension): always HttpSource.
+    let source: Arc<dyn DownloadSource> = match feed_episode {
+        Some(_) => Arc::new(HttpSource::new()),
+        None => registry.resolve(url).ok_or_else(|| {
+            PipelineError::Operational(AppError::Download(DownloadError::Other(
+                "Unsupported URL — no download source found".to_string(),
+            )))
+        })?,
+    };
     log::info!(
         "Pipeline: resolved source '{}' for URL: {}",
         source.name(),
@@ -482,15 +530,27 @@ pub async fn download_phase(
     // percent reporting later in the loop.
     let mut cached_duration_secs: Option<f32> = None;
     let MediaMetadata { title, artist } = {
-        let from_cache = crate::telegram::cache::PREVIEW_CACHE.get(url.as_str()).await.map(|pm| {
-            log::info!("Pipeline: title/artist from preview cache (skipping yt-dlp metadata)");
-            cached_duration_secs = pm.duration.map(|d| d as f32);
+        let from_feed = feed_episode.as_ref().map(|ep| {
+            log::info!("Pipeline: title/artist from feed episode");
+            cached_duration_secs = ep.duration_secs.map(|d| d as f32);
             MediaMetadata {
-                title: pm.title.clone(),
-                artist: pm.artist.clone(),
+                title: ep.title.clone(),
+                artist: ep.podcast.clone(),
             }
         });
 
+        let from_cache = match from_feed {
+            Some(meta) => Some(meta),
+            None => crate::telegram::cache::PREVIEW_CACHE.get(url.as_str()).await.map(|pm| {
+                log::info!("Pipeline: title/artist from preview cache (skipping yt-dlp metadata)");
+                cached_duration_secs = pm.duration.map(|d| d as f32);
+                MediaMetadata {
+                    title: pm.title.clone(),
+                    artist: pm.artist.clone(),
+                }
+            }),
+        };
+
         match from_cache {
             Some(meta) => meta,
             None => match source.get_metadata(url).await {
@@ -511,15 +571,18 @@ pub async fn download_phase(
 
     let display_title = build_display_title(&title, &artist);
     // Pull chapter timestamps from the preview cache populated during the
-    // preview phase (yt-dlp chapters or description fallback). When present,
-    // they render below the title in the send caption — gives the recipient
-    // a quick TOC for long videos like talks or mixes. Cache miss → fall back
-    // to the plain caption.
-    let cached_timestamps = crate::telegram::cache::PREVIEW_CACHE
-        .get(url.as_str())
-        .await
-        .map(|p| p.timestamps)
-        .unwrap_or_default();
+    // preview phase (yt-dlp chapters or description fallback), or from the
+    // feed for podcast episodes. When present, they render below the title in
+    // the send caption — gives the recipient a quick TOC for long videos like
+    // talks or mixes. Cache miss → fall back to the plain caption.
+    let cached_timestamps = match &feed_episode {
+        Some(ep) => feed_episode_chapters(ep).await,
+        None => crate::telegram::cache::PREVIEW_CACHE
+            .get(url.as_str())
+            .await
+            .map(|p| p.timestamps)
+            .unwrap_or_default(),
+    };
     // Rich tech badge: FORMAT · quality · platform (e.g. `MP4 · 1080p · ▶️ YouTube`).
     let badge = {
         let mut parts: Vec<String> = vec![format.label().to_uppercase()];
@@ -632,7 +695,7 @@ pub async fn download_phase(
     }
 
     // File size pre-check (skip when time_range is set — partial downloads are much smaller)
-    let max_size = format.max_file_size();
+    let max_size = format.download_size_cap();
     let has_time_range = format.time_range().is_some();
     if !has_time_range && matches!(format, PipelineFormat::Video { .. }) {
         // Try PREVIEW_CACHE first (avoids a separate yt-dlp call + PO Token generation)
@@ -813,7 +876,7 @@ pub async fn download_phase(
     let mut last_merge_progress = 0u8;
     let mut merge_update_count = 0u32;
 
-    let download_output = loop {
+    let mut download_output = loop {
         tokio::select! {
             Some(sp) = progress_rx.recv() => {
                 // ── Merge-step branch: ffmpeg is muxing the downloaded
@@ -857,6 +920,11 @@ pub async fn download_phase(
                     }
                     continue;
                 }
+                // The highres recode marker carries no progress of its own;
+                // the message stays on the last download percent.
+                if sp.phase == ProgressPhase::Postprocessing {
+                    continue;
+                }
                 let mut safe_progress = sp.percent.clamp(last_progress, 100);
                 if safe_progress == 100 && last_progress < 90 {
                     safe_progress = last_progress;
@@ -925,6 +993,24 @@ pub async fn download_phase(
         }
     };
 
+    if let (Some(ep), PipelineFormat::Audio { .. }) = (&feed_episode, format) {
+        match feed::embed_episode_tags(
+            &download_output.file_path,
+            ep.cover_url.as_deref(),
+            &cached_timestamps,
+            download_output.duration_secs,
+        )
+        .await
+        {
+            Ok(()) => {
+                if let Ok(meta) = fs_err::tokio::metadata(&download_output.file_path).await {
+                    download_output.file_size = meta.len();
+                }
+            }
+            Err(e) => log::warn!("Pipeline: could not embed podcast cover/chapters: {}", e),
+        }
+    }
+
     log::info!(
         "Pipeline: {} downloaded ({:.2} MB)",
         format.label(),
@@ -1121,6 +1207,44 @@ pub async fn execute(
 
     // ── Step 7: Post-validate file size ──
     if download_output.file_size > max_size {
+        // Too big for Telegram — hand out a signed web link if we can
+        if let Some(sent_message) = send_hosted_link(
+            bot,
+            chat_id,
+            shared_storage,
+            &download_output.file_path,
+            &display_title,
+            &progress_msg.lang,
+        )
+        .await
+        {
+            let _ = progress_msg
+                .update(
+                    bot,
+                    DownloadStatus::Success {
+                        title: display_title.as_ref().to_string(),
+                        elapsed_secs: start_time.elapsed().as_secs(),
+                        file_format: Some(file_format_str.clone()),
+                    },
+                )
+                .await;
+            if let Some(msg_id) = message_id {
+                use teloxide::types::MessageId;
+                let reaction = crate::telegram::success_reaction_for_format(Some(&file_format_str));
+                crate::telegram::try_set_reaction(bot, chat_id, MessageId(msg_id), reaction).await;
+            }
+            return Ok(PipelineResult {
+                sent_message,
+                file_size: download_output.file_size,
+                duration: download_output.duration_secs.unwrap_or(0),
+                title,
+                artist,
+                display_title,
+                download_path: download_output.file_path.clone(),
+                output: download_output,
+                hosted: true,
+            });
+        }
         let size_mb = download_output.file_size as f64 / (1024.0 * 1024.0);
         let max_mb = max_size as f64 / (1024.0 * 1024.0);
         log::warn!(
@@ -1452,6 +1576,7 @@ pub async fn execute(
         display_title,
         download_path: download_output.file_path.clone(),
         output: download_output,
+        hosted: false,
     })
 }
 
@@ -1462,10 +1587,34 @@ pub fn schedule_cleanup(download_path: String) {
     schedule_cleanup_with_extras(download_path, Vec::new());
 }
 
+/// Schedule removal of a hosted-link file, then its `hosted_files` row, when
+/// the link expires. `hosted_links::purge_expired` in the periodic DB cleanup
+/// only catches links that were still pending when the bot restarted.
+pub fn schedule_hosted_cleanup(shared_storage: Arc<SharedStorage>, link: &HostedLink) {
+    let delay = (link.expires_at - chrono::Utc::now()).to_std().unwrap_or_default();
+    let cleanup = schedule_cleanup_after(link.file_path.clone(), Vec::new(), delay);
+    let id = link.id.clone();
+    tokio::spawn(async move {
+        let _ = cleanup.await;
+        if let Err(e) = shared_storage.delete_hosted_file(&id).await {
+            log::warn!("Failed to drop expired hosted file {}: {}", id, e);
+        }
+    });
+}
+
 /// Schedule file cleanup for the primary download path plus additional file paths.
 pub fn schedule_cleanup_with_extras(download_path: String, extra_paths: Vec<String>) {
+    schedule_cleanup_after(download_path, extra_paths, config::download::cleanup_delay());
+}
+
+/// Remove `download_path` and `extra_paths` once `delay` has passed.
+fn schedule_cleanup_after(
+    download_path: String,
+    extra_paths: Vec<String>,
+    delay: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        tokio::time::sleep(config::download::cleanup_delay()).await;
+        tokio::time::sleep(delay).await;
         if let Err(e) = fs_err::tokio::remove_file(&download_path).await
             && e.kind() != std::io::ErrorKind::NotFound
         {
@@ -1479,7 +1628,7 @@ pub fn schedule_cleanup_with_extras(download_path: String, extra_paths: Vec<Stri
                 log::warn!("Failed to delete extra file: {}", e);
             }
         }
-    });
+    })
 }
 
 /// Errors that can occur during pipeline execution.
@@ -1,6 +1,7 @@
 // ── Re-export shared progress types from doracore ─────────────────────────────
 pub use doracore::download::progress::{DownloadStatus, ProgressBarStyle, create_progress_bar, source_display_name};
 
+use crate::core::admin_events::{self, AdminEvent};
 use crate::core::extract_retry_after;
 use crate::telegram::{Bot, BotExt};
 use teloxide::prelude::*;
@@ -28,6 +29,12 @@ pub struct ProgressMessage {
     /// Silent mode (V49): when true, `update()` is a no-op and no progress
     /// message is ever created or edited.
     silent: bool,
+    /// Queue task this download belongs to. Updates are published to the
+    /// admin live stream (`core::admin_events`).
+    task_id: Option<String>,
+    /// The task is a public API job: updates are also mirrored to
+    /// `core::public_api` so `/api/v1/jobs/{id}/events` can report progress.
+    api_job: bool,
 }
 
 impl ProgressMessage {
@@ -41,6 +48,8 @@ impl ProgressMessage {
             source_badge: None,
             last_edit_at: None,
             silent: false,
+            task_id: None,
+            api_job: false,
         }
     }
 
@@ -51,6 +60,19 @@ impl ProgressMessage {
         self
     }
 
+    /// Tag updates with the queue task they belong to.
+    pub fn task(mut self, task_id: Option<String>) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    /// Mirror progress to the public API job of the tagged task. Combine with
+    /// `silent(true)` — API jobs have no Telegram message to edit.
+    pub fn api_job(mut self, api_job: bool) -> Self {
+        self.api_job = api_job;
+        self
+    }
+
     /// Whether this handle is in silent mode. Send helpers read this to deliver
     /// the file with `disable_notification` (no ping).
     pub fn is_silent(&self) -> bool {
@@ -70,6 +92,8 @@ impl ProgressMessage {
             source_badge: self.source_badge.clone(),
             last_edit_at: None,
             silent: self.silent,
+            task_id: self.task_id.clone(),
+            api_job: self.api_job,
         }
     }
 
@@ -94,6 +118,14 @@ impl ProgressMessage {
     }
 
     pub async fn update(&mut self, bot: &Bot, status: DownloadStatus) -> ResponseResult<()> {
+        if let Some(task_id) = &self.task_id {
+            if self.api_job {
+                crate::core::public_api::report_progress(task_id, &status);
+            }
+            if let Some(event) = AdminEvent::progress(task_id, &status) {
+                admin_events::publish(event);
+            }
+        }
         // Silent mode: never surface progress to the user.
         if self.silent {
             return Ok(());
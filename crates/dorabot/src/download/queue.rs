@@ -1,5 +1,5 @@
 use crate::core::metrics;
-use crate::storage::db::{DbPool, EnqueueResult, TaskQueueEntry};
+use crate::storage::db::{CreditCharge, DbPool, EnqueueResult, TaskQueueEntry};
 use crate::storage::{QueueTaskInput, SharedStorage};
 
 /// Maximum number of tasks allowed in the queue to prevent unbounded memory growth.
@@ -128,6 +128,20 @@ pub struct DownloadTask {
     /// Whether to fetch and send lyrics highlights alongside the audio.
     #[builder(default = false)]
     pub with_lyrics: bool,
+    /// Credits price for a premium action (high-res, album split). Debited in
+    /// the same transaction that persists the task; `None` = free.
+    pub credit_charge: Option<CreditCharge>,
+}
+
+/// What `DownloadQueue::add_task` did with a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddTaskOutcome {
+    /// The task is queued (in memory or in the DB).
+    Queued,
+    /// Duplicate, queue full or storage error — nothing was queued.
+    Skipped,
+    /// The task's credit charge exceeds the user's balance; nothing was queued.
+    InsufficientCredits { cost: i64, balance: i64 },
 }
 
 /// Thread-safe queue for download tasks.
@@ -216,6 +230,11 @@ impl DownloadQueue {
     /// * `task` - Task to add to the queue
     /// * `db_pool` - Optional database connection pool for persisting the task
     ///
+    /// # Returns
+    ///
+    /// Whether the task was queued; a task whose `credit_charge` the user can't
+    /// afford yields `AddTaskOutcome::InsufficientCredits` and is not queued.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -235,7 +254,7 @@ impl DownloadQueue {
     /// queue.add_task(task, None).await;
     /// # }
     /// ```
-    pub async fn add_task(&self, task: DownloadTask, db_pool: Option<Arc<DbPool>>) {
+    pub async fn add_task(&self, task: DownloadTask, db_pool: Option<Arc<DbPool>>) -> AddTaskOutcome {
         info!("Adding task with priority {:?}: {:?}", task.priority, task);
 
         // Check for duplicates: skip if a task with the same URL, chat_id, and format already exists
@@ -249,7 +268,7 @@ impl DownloadQueue {
                 task.chat_id.0,
                 task.format
             );
-            return;
+            return AddTaskOutcome::Skipped;
         }
 
         let backing_storage = self.backing_storage(db_pool);
@@ -273,7 +292,7 @@ impl DownloadQueue {
         if queue_len >= MAX_QUEUE_SIZE {
             log::warn!("Queue is full ({} tasks), rejecting new task: {}", queue_len, task.url);
             // Don't insert into active_tasks — task is rejected
-            return;
+            return AddTaskOutcome::Skipped;
         }
 
         // Add to the active tasks set (only after confirming queue has space)
@@ -300,6 +319,7 @@ impl DownloadQueue {
                     with_lyrics: task.with_lyrics,
                     priority: priority_value,
                     idempotency_key: &idempotency_key,
+                    credit_charge: task.credit_charge,
                 })
                 .await
             {
@@ -307,19 +327,25 @@ impl DownloadQueue {
                 Ok(EnqueueResult::Duplicate) => {
                     log::info!("Skipping duplicate queued task {}", task.id);
                     active_tasks_remove_after_duplicate(&self.active_tasks, task_key).await;
-                    return;
+                    return AddTaskOutcome::Skipped;
+                }
+                Ok(EnqueueResult::InsufficientCredits { balance }) => {
+                    log::info!("Task {} rejected: insufficient credits ({})", task.id, balance);
+                    active_tasks_remove_after_duplicate(&self.active_tasks, task_key).await;
+                    let cost = task.credit_charge.map_or(0, |charge| charge.cost);
+                    return AddTaskOutcome::InsufficientCredits { cost, balance };
                 }
                 Err(e) => {
                     log::error!("Failed to save task {} to database: {}", task.id, e);
                     active_tasks_remove_after_duplicate(&self.active_tasks, task_key).await;
-                    return;
+                    return AddTaskOutcome::Skipped;
                 }
             }
         }
 
         if backing_storage.is_some() {
             metrics::update_queue_depth_total(queue_len + 1);
-            return;
+            return AddTaskOutcome::Queued;
         }
 
         // Find the insertion position respecting priority order
@@ -340,6 +366,7 @@ impl DownloadQueue {
         metrics::update_queue_depth("medium", medium_count);
         metrics::update_queue_depth("high", high_count);
         metrics::update_queue_depth_total(queue.len());
+        AddTaskOutcome::Queued
     }
 
     /// Pops and returns the first task from the queue (respecting priority).
@@ -629,6 +656,7 @@ impl DownloadQueue {
                 queue_message_id: None,
                 carousel_mask: entry.carousel_mask,
                 with_lyrics: false,
+                credit_charge: None,
             };
 
             // Insert respecting priority order
@@ -729,6 +757,8 @@ impl DownloadQueue {
             queue_message_id: None,
             carousel_mask: entry.carousel_mask,
             with_lyrics: entry.with_lyrics,
+            // Already debited when the task was persisted.
+            credit_charge: None,
         }
     }
 }
@@ -1019,6 +1049,7 @@ mod tests {
             queue_message_id: None,
             carousel_mask: None,
             with_lyrics: false,
+            credit_charge: None,
         };
         let new_task = DownloadTask::builder()
             .url("http://example.com/new".to_string())
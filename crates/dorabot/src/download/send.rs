@@ -8,6 +8,7 @@
 
 use crate::core::config;
 use crate::core::error::AppError;
+use crate::core::hosted_links;
 use crate::core::metrics;
 use crate::core::{BOT_API_RESPONSE_REGEX, BOT_API_START_REGEX, extract_retry_after, is_timeout_or_network_error};
 use crate::download::error::DownloadError;
@@ -16,6 +17,7 @@ use crate::download::progress::{DownloadStatus, ProgressMessage};
 use crate::download::thumbnail::{
     ImageFormat, compress_thumbnail_jpeg, convert_webp_to_jpeg, detect_image_format, generate_thumbnail_from_video,
 };
+use crate::storage::SharedStorage;
 use crate::telegram::Bot;
 use rand::Rng;
 use std::collections::HashMap;
@@ -322,6 +324,69 @@ pub async fn send_error_with_sticker_and_message(bot: &Bot, chat_id: ChatId, cus
     }
 }
 
+/// Publishes a file that is over the upload caps through a signed web link
+/// and sends the link to the chat instead of the file.
+///
+/// Returns `None` (after logging why) when hosting is unavailable — no
+/// `WEB_BASE_URL`, disabled, over `HOSTED_LINK_MAX_MB`, or storage failure —
+/// so the caller can fall back to its usual "file too large" error.
+pub async fn send_hosted_link(
+    bot: &Bot,
+    chat_id: ChatId,
+    shared_storage: Option<&Arc<SharedStorage>>,
+    file_path: &str,
+    display_title: &str,
+    lang: &unic_langid::LanguageIdentifier,
+) -> Option<Message> {
+    let storage = shared_storage?;
+    // Register first, move the file only once the link message is out: if
+    // the send fails, the caller's fallback still finds the file at `file_path`.
+    let link = match hosted_links::prepare(storage, chat_id.0, file_path, display_title).await {
+        Ok(link) => link,
+        Err(e) => {
+            log::warn!("Hosted link not available for {}: {:#}", file_path, e);
+            return None;
+        }
+    };
+
+    let args = doracore::fluent_args!(
+        "title" => display_title.to_string(),
+        "size" => format!("{:.0}", link.file_size as f64 / (1024.0 * 1024.0)),
+        "hours" => *config::hosted_links::TTL_HOURS as i64
+    );
+    // The URL stays outside the Fluent message so no bidi isolation marks
+    // end up glued to it.
+    let text = format!(
+        "{}\n{}",
+        crate::i18n::t_args(lang, "download.hosted_link", &args),
+        link.url
+    );
+    let message = match bot.send_message(chat_id, text).await {
+        Ok(message) => message,
+        Err(e) => {
+            log::warn!("Failed to send hosted link to chat {}: {}", chat_id, e);
+            hosted_links::discard(storage, &link).await;
+            return None;
+        }
+    };
+    if let Err(e) = hosted_links::activate(&link).await {
+        log::warn!("Failed to activate hosted link {}: {:#}", link.id, e);
+        // The link would 404; take it back so the fallback is the only delivery.
+        bot.delete_message(chat_id, message.id).await.ok();
+        hosted_links::discard(storage, &link).await;
+        return None;
+    }
+    crate::download::pipeline::schedule_hosted_cleanup(Arc::clone(storage), &link);
+    log::info!(
+        "Delivered {} to chat {} as hosted link {} ({} bytes)",
+        file_path,
+        chat_id,
+        link.id,
+        link.file_size
+    );
+    Some(message)
+}
+
 /// Generic function to send files with retry logic and animation.
 ///
 /// This function handles the complexity of sending files to Telegram with:
@@ -496,6 +496,12 @@ mod tests {
             quality_preset: None,
             cancel_flag: None,
             experimental_fast_encode: false,
+            limit_rate: None,
+            resume_partial: false,
+            ytdl_bin: None,
+            cookies_file: None,
+            instagram_cookies_file: None,
+            instagram_doc_id: None,
         };
 
         let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
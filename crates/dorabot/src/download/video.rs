@@ -17,7 +17,7 @@ use crate::download::metadata::{
 };
 use crate::download::pipeline::{self, DownloadPhaseResult, PipelineFormat};
 use crate::download::progress::{DownloadStatus, ProgressBarStyle, ProgressMessage};
-use crate::download::send::{send_error_with_sticker, send_video_with_retry};
+use crate::download::send::{send_error_with_sticker, send_hosted_link, send_video_with_retry};
 use crate::download::source::bot_global;
 use crate::storage::SharedStorage;
 use crate::telegram::Bot;
@@ -52,6 +52,7 @@ pub async fn download_and_send_video(
         alert_manager,
         created_timestamp: _created_timestamp,
         silent,
+        task_id,
     } = ctx;
     let bot_clone = bot.clone();
     let shared_storage_clone = shared_storage.clone();
@@ -68,7 +69,7 @@ pub async fn download_and_send_video(
         } else {
             crate::i18n::lang_from_code("ru")
         };
-        let mut progress_msg = ProgressMessage::new(chat_id, lang.clone()).silent(silent);
+        let mut progress_msg = ProgressMessage::new(chat_id, lang.clone()).silent(silent).task(task_id);
         if let Some(ref storage) = shared_storage_clone
             && let Ok(style_str) = storage.get_user_progress_bar_style(chat_id.0).await
         {
@@ -287,7 +288,26 @@ pub async fn download_and_send_video(
                 .unwrap_or(false);
             let target_part_size: u64 = 1900 * 1024 * 1024; // 1.9 GB
 
-            let video_parts = if is_local_bot_api && final_file_size > target_part_size {
+            // Over the Bot API cap without a local server: deliver as a hosted
+            // link if possible, otherwise let the send below report the size error
+            let upload_cap = doracore::core::upload_limits::UploadLimits::from_env()
+                .cap(doracore::core::upload_limits::UploadKind::Video);
+            let hosted = !is_local_bot_api
+                && final_file_size > upload_cap
+                && send_hosted_link(
+                    &bot_clone,
+                    chat_id,
+                    shared_storage_clone.as_ref(),
+                    &actual_file_path,
+                    &display_title,
+                    &progress_msg.lang,
+                )
+                .await
+                .is_some();
+
+            let video_parts = if hosted {
+                Vec::new()
+            } else if is_local_bot_api && final_file_size > target_part_size {
                 log::info!("Video > 1.9GB with Local Bot API — splitting into parts");
                 split_video_into_parts(&actual_file_path, target_part_size).await?
             } else {
@@ -89,8 +89,9 @@ async fn main() -> Result<()> {
             quality,
             bitrate,
             output,
+            template,
             verbose,
-        }) => doradura::cli_commands::run_cli_download(url, format, quality, bitrate, output, verbose).await,
+        }) => doradura::cli_commands::run_cli_download(url, format, quality, bitrate, output, template, verbose).await,
         Some(Commands::Info { url, json }) => doradura::cli_commands::run_cli_info(url, json).await,
         Some(Commands::Webhook { command }) => {
             let bot = doradura::telegram::create_bot()?;
@@ -104,6 +105,25 @@ async fn main() -> Result<()> {
                 WebhookCommand::Info => doradura::webhook::print_webhook_info(&bot).await,
             }
         }
+        Some(Commands::Backup { command }) => doradura::cli_commands::run_backup(command).await,
+        Some(Commands::MigrateStorage {
+            from,
+            to,
+            batch_size,
+            dry_run,
+            state_file,
+            restart,
+            tables,
+        }) => {
+            let options = doradura::cli_commands::MigrateStorageArgs {
+                batch_size,
+                dry_run,
+                state_file,
+                restart,
+                tables,
+            };
+            doradura::cli_commands::run_migrate_storage(from, to, options).await
+        }
         None => {
             log::info!("No command specified, running bot in default mode");
             doradura::startup::run_bot(false).await
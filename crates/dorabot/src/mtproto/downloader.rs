@@ -5,7 +5,9 @@ use super::error::MtProtoError;
 use super::file_id::DecodedFileId;
 use grammers_tl_types as tl;
 use std::path::Path;
+use std::sync::Arc;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::OnceCell;
 
 /// Information about a message (with or without media)
 #[derive(Debug, Clone)]
@@ -41,6 +43,16 @@ pub enum PeerType {
     Channel,
 }
 
+/// A public channel resolved by username
+#[derive(Debug, Clone)]
+pub struct ChannelInfo {
+    pub peer: PeerInfo,
+    /// Access hash required by `channels.*` calls
+    pub access_hash: i64,
+    pub username: String,
+    pub title: String,
+}
+
 /// Chunk size for file downloads (512KB - Telegram limit)
 const CHUNK_SIZE: i64 = 512 * 1024;
 
@@ -72,7 +84,36 @@ pub struct MtProtoDownloader {
     bot_token: Option<String>,
 }
 
+/// Process-wide downloader used by background tasks
+static SHARED_DOWNLOADER: OnceCell<Arc<MtProtoDownloader>> = OnceCell::const_new();
+
 impl MtProtoDownloader {
+    /// Connect using `TELEGRAM_API_ID`, `TELEGRAM_API_HASH`, the bot token
+    /// (`BOT_TOKEN` or `TELOXIDE_TOKEN`) and `MTPROTO_SESSION_PATH`
+    pub async fn from_env() -> Result<Self, MtProtoError> {
+        let api_id: i32 = std::env::var("TELEGRAM_API_ID")
+            .map_err(|_| MtProtoError::NotConfigured("TELEGRAM_API_ID not set".to_string()))?
+            .parse()
+            .map_err(|e| MtProtoError::NotConfigured(format!("Invalid TELEGRAM_API_ID: {}", e)))?;
+        let api_hash = std::env::var("TELEGRAM_API_HASH")
+            .map_err(|_| MtProtoError::NotConfigured("TELEGRAM_API_HASH not set".to_string()))?;
+        let bot_token = std::env::var("BOT_TOKEN")
+            .or_else(|_| std::env::var("TELOXIDE_TOKEN"))
+            .map_err(|_| MtProtoError::NotConfigured("BOT_TOKEN or TELOXIDE_TOKEN not set".to_string()))?;
+        let session_path = std::env::var("MTPROTO_SESSION_PATH").unwrap_or_else(|_| "mtproto_session.bin".to_string());
+
+        let client = MtProtoClient::new_bot(api_id, &api_hash, &bot_token, Path::new(&session_path)).await?;
+        Ok(Self::with_bot_token(client, bot_token))
+    }
+
+    /// Shared downloader, connected from the environment on first use
+    pub async fn shared() -> Result<Arc<Self>, MtProtoError> {
+        SHARED_DOWNLOADER
+            .get_or_try_init(|| async { Self::from_env().await.map(Arc::new) })
+            .await
+            .cloned()
+    }
+
     /// Create a new downloader with the given client
     pub fn new(client: MtProtoClient) -> Self {
         Self {
@@ -584,6 +625,71 @@ impl MtProtoDownloader {
         self.parse_messages(message_list)
     }
 
+    /// Resolve a public broadcast channel by username (`contacts.resolveUsername`)
+    pub async fn resolve_channel(&self, username: &str) -> Result<ChannelInfo, MtProtoError> {
+        let resolved = self
+            .client
+            .inner()
+            .invoke(&tl::functions::contacts::ResolveUsername {
+                username: username.to_string(),
+            })
+            .await
+            .map_err(MtProtoError::Invocation)?;
+        let tl::enums::contacts::ResolvedPeer::Peer(resolved) = resolved;
+
+        resolved
+            .chats
+            .into_iter()
+            .find_map(|chat| match chat {
+                tl::enums::Chat::Channel(channel) if channel.broadcast => Some(ChannelInfo {
+                    peer: PeerInfo {
+                        peer_type: PeerType::Channel,
+                        id: channel.id,
+                    },
+                    access_hash: channel.access_hash?,
+                    username: channel.username.unwrap_or_else(|| username.to_string()),
+                    title: channel.title,
+                }),
+                _ => None,
+            })
+            .ok_or_else(|| MtProtoError::ChannelNotFound(username.to_string()))
+    }
+
+    /// Get channel posts by ID (`channels.getMessages`, allowed for bots on
+    /// public channels). IDs that don't exist (yet) or were deleted are skipped.
+    pub async fn get_channel_messages(
+        &self,
+        channel: &ChannelInfo,
+        message_ids: &[i32],
+    ) -> Result<Vec<MessageInfo>, MtProtoError> {
+        let input_messages: Vec<_> = message_ids
+            .iter()
+            .map(|&id| tl::enums::InputMessage::Id(tl::types::InputMessageId { id }))
+            .collect();
+
+        let messages = self
+            .client
+            .inner()
+            .invoke(&tl::functions::channels::GetMessages {
+                channel: tl::enums::InputChannel::Channel(tl::types::InputChannel {
+                    channel_id: channel.peer.id,
+                    access_hash: channel.access_hash,
+                }),
+                id: input_messages,
+            })
+            .await
+            .map_err(MtProtoError::Invocation)?;
+
+        let message_list = match messages {
+            tl::enums::messages::Messages::Messages(m) => m.messages,
+            tl::enums::messages::Messages::Slice(m) => m.messages,
+            tl::enums::messages::Messages::ChannelMessages(m) => m.messages,
+            tl::enums::messages::Messages::NotModified(_) => vec![],
+        };
+
+        self.parse_messages(message_list)
+    }
+
     /// Parse message list into MessageInfo structs
     fn parse_messages(&self, message_list: Vec<tl::enums::Message>) -> Result<Vec<MessageInfo>, MtProtoError> {
         let mut result = Vec::new();
@@ -56,6 +56,14 @@ pub enum MtProtoError {
     /// DC migration required but failed
     #[error("DC migration failed: {0}")]
     DcMigration(String),
+
+    /// API credentials missing from the environment
+    #[error("MTProto not configured: {0}")]
+    NotConfigured(String),
+
+    /// Username doesn't resolve to a public channel
+    #[error("Channel not found: {0}")]
+    ChannelNotFound(String),
 }
 
 impl From<MtProtoError> for crate::core::error::AppError {
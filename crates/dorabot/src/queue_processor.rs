@@ -10,7 +10,8 @@ use tokio::time::interval;
 use tracing::Instrument;
 
 use crate::core::retry::Retryable;
-use crate::core::{alerts, config, metrics, rate_limiter, subscription};
+use crate::core::{alerts, config, credits, metrics, promo, rate_limiter, subscription};
+use crate::download::api_job::download_for_api;
 use crate::download::context::DownloadContext;
 use crate::download::queue::{self as queue};
 use crate::download::ytdlp_errors::sanitize_user_error_message;
@@ -212,6 +213,7 @@ async fn process_single_task(
             {
                 log::error!("Failed to mark task {} as failed in DB: {}", task.id, db_err);
             }
+            credits::refund_task(&bot, &shared_storage, task.chat_id, &task.id, "failed").await;
             queue_for_cleanup
                 .remove_active_task(&task.url, task.chat_id, task.format.as_str())
                 .await;
@@ -295,6 +297,7 @@ async fn process_single_task(
                 None,
             )
             .await;
+            credits::refund_task(&bot, &shared_storage, task.chat_id, &task.id, "invalid URL").await;
             queue_for_cleanup
                 .remove_active_task(&task.url, task.chat_id, task.format.as_str())
                 .await;
@@ -357,6 +360,7 @@ async fn process_single_task(
                     config::admin::MAX_TASK_RETRIES,
                 )
                 .await;
+            credits::refund_task(&bot, &shared_storage, task.chat_id, &task.id, "daily limit").await;
             queue_for_cleanup
                 .remove_active_task(&task.url, task.chat_id, task.format.as_str())
                 .await;
@@ -430,8 +434,21 @@ async fn process_single_task(
         alert_manager: alert_manager.clone(),
         created_timestamp,
         silent,
+        task_id: Some(task_id.clone()),
     };
+    // Jobs submitted through /api/v1 are published as hosted links instead
+    // of being sent to the chat.
+    let is_api_job = shared_storage.is_api_job(&task_id).await.unwrap_or_else(|e| {
+        log::warn!("Failed to check whether task {} is an API job: {}", task_id, e);
+        false
+    });
+    // A failed API job still completes its task (it is refunded and recorded
+    // on the job row) but must not count towards a referral reward.
+    let mut delivered = true;
     let result = match task_format {
+        _ if is_api_job => download_for_api(ctx, &task_id, &task_format, video_quality, audio_bitrate)
+            .await
+            .map(|ok| delivered = ok),
         queue::DownloadFormat::Mp4 => download_and_send_video(ctx, video_quality, time_range.clone()).await,
         queue::DownloadFormat::Srt | queue::DownloadFormat::Txt => {
             download_and_send_subtitles(ctx, task_format_str.clone()).await
@@ -448,6 +465,9 @@ async fn process_single_task(
                 log::warn!("Failed to mark task {} as completed: {}", task_id, e);
             }
             log::info!("Task {} completed successfully", task_id);
+            if delivered {
+                promo::reward_referral(&bot, &shared_storage, task_chat_id.0).await;
+            }
         }
         Err(e) => {
             let admin_error_msg = format!("{:?}", e);
@@ -472,6 +492,8 @@ async fn process_single_task(
             {
                 Ok(retry_scheduled) => {
                     if !retry_scheduled {
+                        // Retries keep the debit; only a final failure gives the credits back.
+                        credits::refund_task(&bot, &shared_storage, task_chat_id, &task_id, "failed").await;
                         // Silent mode (V49): record the failure for the MOTD recap
                         // so a quiet download that failed isn't lost.
                         if silent {
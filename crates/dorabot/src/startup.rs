@@ -92,8 +92,37 @@ pub async fn run_bot(use_webhook: bool) -> Result<()> {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create shared storage backend ({:?}): {}", driver, e))?;
 
+    // Refuse to run against a schema the embedded migrations don't describe.
+    // A failing check (e.g. no CREATE SCHEMA privilege) only blocks startup in strict mode.
+    let drifts = match shared_storage.check_schema().await {
+        Ok(drifts) => drifts,
+        Err(e) if *config::SCHEMA_DRIFT_STRICT => {
+            return Err(anyhow::anyhow!("Schema drift check failed: {:#}", e));
+        }
+        Err(e) => {
+            log::warn!(
+                "Schema drift check failed, starting anyway (SCHEMA_DRIFT_STRICT=false): {:#}",
+                e
+            );
+            Vec::new()
+        }
+    };
+    for drift in &drifts {
+        if drift.is_blocking() {
+            log::error!("{}", drift);
+        } else {
+            log::warn!("{}", drift);
+        }
+    }
+    if drifts.iter().any(|d| d.is_blocking()) && *config::SCHEMA_DRIFT_STRICT {
+        return Err(anyhow::anyhow!(
+            "Database schema drift detected; fix the schema or set SCHEMA_DRIFT_STRICT=false to start anyway"
+        ));
+    }
+
     // Initialize core services
     crate::core::error_logger::init_error_logger(Arc::clone(&shared_storage));
+    crate::core::admin_roles::init(Arc::clone(&shared_storage)).await;
     crate::download::audio_effects::start_cleanup_task(Arc::clone(&shared_storage));
 
     let rate_limiter =
@@ -156,9 +185,16 @@ pub async fn run_bot(use_webhook: bool) -> Result<()> {
 
     background_tasks::spawn_subscription_expiry_checker(Arc::clone(&shared_storage)).await;
     background_tasks::spawn_cookies_checker(bot.clone(), Arc::clone(&shared_storage)).await;
-    background_tasks::spawn_content_watcher(bot.clone(), Arc::clone(&db_pool), Arc::clone(&shared_storage)).await;
+    background_tasks::spawn_content_watcher(
+        bot.clone(),
+        Arc::clone(&db_pool),
+        Arc::clone(&shared_storage),
+        Arc::clone(&download_queue),
+    )
+    .await;
     background_tasks::spawn_db_cleanup(Arc::clone(&db_pool), Arc::clone(&shared_storage)).await;
     background_tasks::spawn_downloads_cleanup(Arc::clone(&shared_storage)).await;
+    background_tasks::spawn_backup_scheduler(bot.clone(), Arc::clone(&shared_storage)).await;
 
     // Kill any orphan yt-dlp/ffmpeg processes left by a previous bot
     // generation (v0.49.2). Container restarts leave child processes
@@ -1,4 +1,4 @@
-use super::is_admin;
+use super::{Permission, can};
 use crate::storage::SharedStorage;
 use crate::storage::db::DbPool;
 use crate::telegram::Bot;
@@ -18,7 +18,7 @@ pub async fn handle_send_command(
     db_pool: Arc<DbPool>,
     shared_storage: Arc<SharedStorage>,
 ) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::Broadcast, "/send") {
         bot.send_message(chat_id, "Access denied.").await?;
         return Ok(());
     }
@@ -83,7 +83,7 @@ pub async fn handle_broadcast_command(
     db_pool: Arc<DbPool>,
     shared_storage: Arc<SharedStorage>,
 ) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::Broadcast, "/broadcast") {
         bot.send_message(chat_id, "Access denied.").await?;
         return Ok(());
     }
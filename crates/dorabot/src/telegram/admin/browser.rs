@@ -1,4 +1,4 @@
-use super::{escape_markdown, is_admin};
+use super::{Permission, can, escape_markdown};
 use crate::telegram::Bot;
 use crate::telegram::BotExt;
 use anyhow::Result;
@@ -39,7 +39,7 @@ pub(super) async fn cookie_manager_request(method: &str, path: &str) -> anyhow::
 ///
 /// Starts a visual login session via noVNC so the admin can log in to YouTube.
 pub async fn handle_browser_login_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageCookies, "/browser_login") {
         bot.send_message(chat_id, "❌ Only admins can use this command.")
             .await?;
         return Ok(());
@@ -105,7 +105,7 @@ pub async fn handle_browser_login_command(bot: &Bot, chat_id: ChatId, user_id: i
 ///
 /// Shows the current cookie manager status.
 pub async fn handle_browser_status_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageCookies, "/browser_status") {
         bot.send_message(chat_id, "❌ Only admins can use this command.")
             .await?;
         return Ok(());
@@ -449,10 +449,16 @@ pub async fn handle_browser_callback(
 }
 
 /// Shows proxy statistics and health status
-pub async fn handle_proxy_stats_command(bot: &Bot, chat_id: ChatId, _user_id: i64) -> Result<()> {
+pub async fn handle_proxy_stats_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
     use crate::core::config;
     use crate::download::proxy::ProxyListManager;
 
+    if !can(user_id, Permission::ManageCookies, "/proxy_stats") {
+        bot.send_message(chat_id, "❌ Only admins can use this command.")
+            .await?;
+        return Ok(());
+    }
+
     if config::proxy::WARP_PROXY.is_none() && config::proxy::PROXY_FILE.is_none() {
         bot.send_md(
             chat_id,
@@ -517,10 +523,16 @@ pub async fn handle_proxy_stats_command(bot: &Bot, chat_id: ChatId, _user_id: i6
 }
 
 /// Resets proxy health statistics
-pub async fn handle_proxy_reset_command(bot: &Bot, chat_id: ChatId, _user_id: i64) -> Result<()> {
+pub async fn handle_proxy_reset_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
     use crate::core::config;
     use crate::download::proxy::ProxyListManager;
 
+    if !can(user_id, Permission::ManageCookies, "/proxy_reset") {
+        bot.send_message(chat_id, "❌ Only admins can use this command.")
+            .await?;
+        return Ok(());
+    }
+
     if config::proxy::WARP_PROXY.is_none() && config::proxy::PROXY_FILE.is_none() {
         bot.send_md(chat_id, "❌ *No proxies configured*").await?;
         return Ok(());
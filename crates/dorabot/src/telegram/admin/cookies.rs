@@ -1,5 +1,5 @@
 use super::{
-    browser::cookie_manager_request, download_helpers::download_file_from_telegram, escape_markdown, is_admin,
+    Permission, browser::cookie_manager_request, can, download_helpers::download_file_from_telegram, escape_markdown,
 };
 use crate::download::cookies;
 use crate::download::ytdlp;
@@ -23,7 +23,7 @@ static LAST_COOKIE_NOTIFICATION: LazyLock<Mutex<Option<Instant>>> = LazyLock::ne
 ///
 /// Shows detailed diagnostic information about the current cookies file
 pub async fn handle_diagnose_cookies_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageCookies, "/diagnose_cookies") {
         bot.send_message(chat_id, "❌ This command is for administrators only.")
             .await?;
         return Ok(());
@@ -75,7 +75,7 @@ pub async fn handle_update_cookies_command(
     );
 
     // Check admin permissions
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageCookies, "/update_cookies") {
         log::warn!("❌ Non-admin user {} attempted to use /update_cookies", user_id);
         bot.send_message(chat_id, "❌ This command is only available to administrators.")
             .await?;
@@ -122,7 +122,7 @@ pub async fn handle_update_ytdlp_command(bot: &Bot, chat_id: ChatId, user_id: i6
         chat_id
     );
 
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageCookies, "/update_ytdlp") {
         log::warn!("❌ Non-admin user {} attempted to use /update_ytdlp", user_id);
         bot.send_message(chat_id, "❌ This command is only available to administrators.")
             .await?;
@@ -495,7 +495,7 @@ pub async fn handle_update_ig_cookies_command(
         chat_id
     );
 
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageCookies, "/update_ig_cookies") {
         log::warn!("❌ Non-admin user {} attempted to use /update_ig_cookies", user_id);
         bot.send_message(chat_id, "❌ This command is only available to administrators.")
             .await?;
@@ -419,30 +419,14 @@ pub async fn download_file_with_fallback(
 
 /// Downloads a file via MTProto using message_id to get fresh file_reference
 async fn download_via_mtproto(message_id: i32, destination_path: Option<PathBuf>) -> Result<PathBuf> {
-    use crate::mtproto::{MtProtoClient, MtProtoDownloader};
-
-    // Load MTProto credentials from environment
-    let api_id: i32 = std::env::var("TELEGRAM_API_ID")
-        .map_err(|_| anyhow::anyhow!("TELEGRAM_API_ID not set"))?
-        .parse()
-        .map_err(|e| anyhow::anyhow!("Invalid TELEGRAM_API_ID: {}", e))?;
-
-    let api_hash = std::env::var("TELEGRAM_API_HASH").map_err(|_| anyhow::anyhow!("TELEGRAM_API_HASH not set"))?;
-
-    let bot_token = std::env::var("BOT_TOKEN")
-        .or_else(|_| std::env::var("TELOXIDE_TOKEN"))
-        .map_err(|_| anyhow::anyhow!("BOT_TOKEN or TELOXIDE_TOKEN not set"))?;
-
-    let session_path = std::env::var("MTPROTO_SESSION_PATH").unwrap_or_else(|_| "mtproto_session.bin".to_string());
+    use crate::mtproto::MtProtoDownloader;
 
     log::info!("🔌 Initializing MTProto client for fallback download...");
 
-    let client = MtProtoClient::new_bot(api_id, &api_hash, &bot_token, std::path::Path::new(&session_path))
+    let downloader = MtProtoDownloader::from_env()
         .await
         .map_err(|e| anyhow::anyhow!("Failed to initialize MTProto client: {}", e))?;
 
-    let downloader = MtProtoDownloader::with_bot_token(client, bot_token);
-
     // Get message with fresh media info
     log::info!("📨 Fetching message {} for fresh file_reference...", message_id);
     let messages = downloader
@@ -2,17 +2,22 @@
 //!
 //! This module contains all admin-related commands and utilities:
 //! - User management (/users, /setplan, /admin)
+//! - Promo codes (/promo)
 //! - System diagnostics (/version, /botapi_speed, /transactions, /backup)
 //! - Cookie management (/update_cookies, /diagnose_cookies)
 //! - Browser automation (/browser_login, /browser_status)
 //! - Proxy management (/proxy_stats, /proxy_reset)
 //! - Broadcast (/send, /broadcast)
 //! - File download helpers
+//!
+//! Each command checks a permission with [`can`]; which roles hold which
+//! permission is defined in `core::admin_roles`.
 
 pub mod broadcast;
 pub mod browser;
 pub mod cookies;
 pub mod download_helpers;
+pub mod promo;
 pub mod system;
 pub mod users;
 
@@ -24,10 +29,13 @@ pub use browser::{
 };
 pub use cookies::*;
 pub use download_helpers::{download_file_from_telegram, download_file_with_fallback};
+pub use promo::*;
 pub use system::*;
 pub use users::*;
 
-use crate::core::config::admin::{ADMIN_IDS, ADMIN_USER_ID};
+use crate::core::admin_roles;
+
+pub use crate::core::admin_roles::Permission;
 
 // Re-export escape_markdown for backward compatibility (other modules import from here)
 pub use crate::core::escape_markdown;
@@ -35,15 +43,17 @@ pub use crate::core::escape_markdown;
 /// Maximum message length for Telegram (with margin) - for backward compatibility
 pub const MAX_MESSAGE_LENGTH: usize = crate::core::TELEGRAM_MESSAGE_LIMIT;
 
-/// Check if user is admin
+/// Check if user is admin: an owner from `ADMIN_IDS` / `ADMIN_USER_ID` or
+/// staff with a role granted on the dashboard.
 pub fn is_admin(user_id: i64) -> bool {
-    if !ADMIN_IDS.is_empty() {
-        return ADMIN_IDS.contains(&user_id);
-    }
-    if *ADMIN_USER_ID != 0 {
-        return *ADMIN_USER_ID == user_id;
-    }
-    false
+    admin_roles::role_of(user_id).is_some()
+}
+
+/// Whether `user_id` may run the admin `command`, which needs `permission`
+/// (see the matrix in `core::admin_roles`). Denials of staff are written to
+/// the admin audit log.
+pub fn can(user_id: i64, permission: Permission, command: &str) -> bool {
+    admin_roles::check(user_id, permission, command)
 }
 
 /// Truncate message for Telegram - for backward compatibility with original behavior
@@ -89,13 +99,14 @@ mod tests {
 
     #[test]
     fn test_is_admin() {
-        if !super::ADMIN_IDS.is_empty() {
-            let admin_id = super::ADMIN_IDS[0];
-            let non_admin_id = super::ADMIN_IDS.iter().max().copied().unwrap_or(0) + 1;
+        use crate::core::config::admin::{ADMIN_IDS, ADMIN_USER_ID};
+        if !ADMIN_IDS.is_empty() {
+            let admin_id = ADMIN_IDS[0];
+            let non_admin_id = ADMIN_IDS.iter().max().copied().unwrap_or(0) + 1;
             assert!(super::is_admin(admin_id));
             assert!(!super::is_admin(non_admin_id));
-        } else if *super::ADMIN_USER_ID != 0 {
-            let admin_id = *super::ADMIN_USER_ID;
+        } else if *ADMIN_USER_ID != 0 {
+            let admin_id = *ADMIN_USER_ID;
             assert!(super::is_admin(admin_id));
             assert!(!super::is_admin(admin_id + 1));
         } else {
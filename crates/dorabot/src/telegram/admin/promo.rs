@@ -0,0 +1,242 @@
+use super::{Permission, can};
+use crate::core::promo;
+use crate::storage::SharedStorage;
+use crate::storage::db::{self, NewPromoCode, PromoKind};
+use crate::telegram::Bot;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+const PROMO_USAGE: &str = "Usage:\n\
+    /promo list\n\
+    /promo add <CODE> plan <premium|vip> <days> [max_uses] [valid_days]\n\
+    /promo add <CODE> discount <percent 1-99> [max_uses] [valid_days]\n\
+    /promo add <CODE> credits <amount> [max_uses] [valid_days]\n\
+    /promo off <CODE>\n\
+    /promo on <CODE>";
+
+/// Parse the arguments after `/promo add`. `valid_days` is turned into an
+/// absolute UTC expiry relative to `now`.
+fn parse_promo_add(args: &[&str], admin_id: i64, now: DateTime<Utc>) -> Result<NewPromoCode, String> {
+    let code = args
+        .first()
+        .and_then(|raw| db::normalize_promo_code(raw))
+        .ok_or("Code must be 3-32 characters of A-Z, 0-9, '-' or '_'")?;
+    let kind: PromoKind = args
+        .get(1)
+        .and_then(|k| k.parse().ok())
+        .ok_or("Kind must be plan, discount or credits")?;
+
+    let number = |idx: usize, what: &str| -> Result<Option<i64>, String> {
+        match args.get(idx) {
+            None => Ok(None),
+            Some(raw) => match raw.parse::<i64>() {
+                Ok(n) if n > 0 => Ok(Some(n)),
+                _ => Err(format!("{} must be a positive integer", what)),
+            },
+        }
+    };
+
+    let mut new = NewPromoCode {
+        code,
+        kind,
+        plan: None,
+        days: None,
+        percent: None,
+        credits: None,
+        max_uses: None,
+        expires_at: None,
+        source: db::PROMO_SOURCE_ADMIN,
+        created_by: admin_id,
+    };
+    let rest = match kind {
+        PromoKind::Plan => {
+            let plan = args.get(2).copied().unwrap_or_default();
+            if !["premium", "vip"].contains(&plan) {
+                return Err("Plan must be premium or vip".to_string());
+            }
+            new.plan = Some(plan.to_string());
+            new.days = Some(number(3, "days")?.ok_or("days is required")? as i32);
+            4
+        }
+        PromoKind::Discount => {
+            let percent = number(2, "percent")?.ok_or("percent is required")?;
+            if percent > 99 {
+                return Err("percent must be between 1 and 99".to_string());
+            }
+            new.percent = Some(percent as i32);
+            3
+        }
+        PromoKind::Credits => {
+            new.credits = Some(number(2, "amount")?.ok_or("amount is required")?);
+            3
+        }
+    };
+    new.max_uses = number(rest, "max_uses")?.map(|n| n as i32);
+    new.expires_at = number(rest + 1, "valid_days")?.map(|days| {
+        (now + chrono::Duration::days(days))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    });
+    Ok(new)
+}
+
+fn describe(code: &db::PromoCode) -> String {
+    let reward = match code.kind {
+        PromoKind::Plan => format!(
+            "{} for {} days",
+            code.plan.as_deref().unwrap_or("premium"),
+            code.days.unwrap_or_default()
+        ),
+        PromoKind::Discount => format!("-{}%", code.percent.unwrap_or_default()),
+        PromoKind::Credits => format!("{} credits", code.credits.unwrap_or_default()),
+    };
+    let uses = match code.max_uses {
+        Some(max) => format!("{}/{}", code.used_count, max),
+        None => format!("{}/∞", code.used_count),
+    };
+    format!(
+        "{} {} — {} — uses {}{}{}",
+        if code.is_active { "🟢" } else { "⚪" },
+        code.code,
+        reward,
+        uses,
+        code.expires_at
+            .as_deref()
+            .map(|e| format!(" — until {}", e))
+            .unwrap_or_default(),
+        if code.source == db::PROMO_SOURCE_GIFT {
+            format!(" — gift from {}", code.created_by)
+        } else {
+            String::new()
+        }
+    )
+}
+
+/// Handle /promo command - create, list and toggle promo codes
+pub async fn handle_promo_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    message_text: &str,
+    shared_storage: Arc<SharedStorage>,
+) -> Result<()> {
+    if !can(user_id, Permission::ManagePlans, "/promo") {
+        bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
+            .await?;
+        return Ok(());
+    }
+
+    let parts: Vec<&str> = message_text.split_whitespace().collect();
+    match parts.get(1).copied() {
+        Some("list") => {
+            let codes = shared_storage.list_promo_codes(30).await?;
+            let text = if codes.is_empty() {
+                "No promo codes yet.".to_string()
+            } else {
+                let lines = codes.iter().map(describe).collect::<Vec<_>>().join("\n");
+                format!("🎟 Promo codes (latest {}):\n\n{}", codes.len(), lines)
+            };
+            bot.send_message(chat_id, text).await?;
+        }
+        Some("add") => match parse_promo_add(&parts[2..], user_id, Utc::now()) {
+            Ok(new) => {
+                if shared_storage.create_promo_code(&new).await? {
+                    promo::audit(
+                        &shared_storage,
+                        user_id,
+                        "promo_create",
+                        &new.code,
+                        &format!(
+                            "kind={} plan={:?} days={:?} percent={:?} credits={:?} max_uses={:?} expires_at={:?}",
+                            new.kind, new.plan, new.days, new.percent, new.credits, new.max_uses, new.expires_at
+                        ),
+                    );
+                    bot.send_message(chat_id, format!("✅ Promo code {} created.", new.code))
+                        .await?;
+                } else {
+                    bot.send_message(chat_id, format!("❌ Promo code {} already exists.", new.code))
+                        .await?;
+                }
+            }
+            Err(e) => {
+                bot.send_message(chat_id, format!("❌ {}\n\n{}", e, PROMO_USAGE))
+                    .await?;
+            }
+        },
+        Some(action @ ("off" | "on")) => {
+            let Some(code) = parts.get(2).and_then(|raw| db::normalize_promo_code(raw)) else {
+                bot.send_message(chat_id, PROMO_USAGE).await?;
+                return Ok(());
+            };
+            let active = action == "on";
+            if shared_storage.set_promo_code_active(&code, active).await? {
+                let audit_action = if active { "promo_enable" } else { "promo_disable" };
+                promo::audit(&shared_storage, user_id, audit_action, &code, "");
+                bot.send_message(
+                    chat_id,
+                    format!(
+                        "✅ Promo code {} {}.",
+                        code,
+                        if active { "enabled" } else { "disabled" }
+                    ),
+                )
+                .await?;
+            } else {
+                bot.send_message(chat_id, format!("❌ Promo code {} not found.", code))
+                    .await?;
+            }
+        }
+        _ => {
+            bot.send_message(chat_id, PROMO_USAGE).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn parses_plan_code_with_limits() {
+        let new = parse_promo_add(&["summer", "plan", "vip", "14", "100", "7"], 1, now()).unwrap();
+        assert_eq!(new.code, "SUMMER");
+        assert_eq!(new.kind, PromoKind::Plan);
+        assert_eq!(new.plan.as_deref(), Some("vip"));
+        assert_eq!(new.days, Some(14));
+        assert_eq!(new.max_uses, Some(100));
+        assert_eq!(new.expires_at.as_deref(), Some("2026-01-08 00:00:00"));
+        assert_eq!(new.source, db::PROMO_SOURCE_ADMIN);
+    }
+
+    #[test]
+    fn parses_discount_and_credits() {
+        let new = parse_promo_add(&["HALF", "discount", "50"], 1, now()).unwrap();
+        assert_eq!(new.percent, Some(50));
+        assert_eq!(new.max_uses, None);
+        assert_eq!(new.expires_at, None);
+
+        let new = parse_promo_add(&["BONUS", "credits", "20", "5"], 1, now()).unwrap();
+        assert_eq!(new.credits, Some(20));
+        assert_eq!(new.max_uses, Some(5));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(parse_promo_add(&[], 1, now()).is_err());
+        assert!(parse_promo_add(&["X", "credits", "5"], 1, now()).is_err());
+        assert!(parse_promo_add(&["CODE", "plan", "free", "30"], 1, now()).is_err());
+        assert!(parse_promo_add(&["CODE", "plan", "premium"], 1, now()).is_err());
+        assert!(parse_promo_add(&["CODE", "discount", "100"], 1, now()).is_err());
+        assert!(parse_promo_add(&["CODE", "credits", "-3"], 1, now()).is_err());
+        assert!(parse_promo_add(&["CODE", "bogus", "1"], 1, now()).is_err());
+    }
+}
@@ -1,8 +1,9 @@
-use super::{escape_markdown, indent_lines, is_admin, truncate_message};
+use super::{Permission, can, escape_markdown, indent_lines, truncate_message};
 use crate::core::config;
 use crate::core::{BOT_API_RESPONSE_REGEX, BOT_API_START_SIMPLE_REGEX};
 use crate::downsub::DownsubGateway;
-use crate::storage::backup::{create_backup, list_backups};
+use crate::storage::SharedStorage;
+use crate::storage::backup::{BackupConfig, create_backup, list_backups};
 use crate::telegram::Bot;
 use crate::telegram::BotExt;
 use anyhow::Result;
@@ -144,7 +145,7 @@ pub async fn handle_version_command(bot: &Bot, chat_id: ChatId, user_id: i64) ->
         chat_id
     );
 
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageSystem, "/version") {
         log::warn!("❌ Non-admin user {} attempted to use /version", user_id);
         bot.send_message(chat_id, "❌ This command is only available to administrators.")
             .await?;
@@ -340,7 +341,7 @@ struct BotApiUploadPending {
 
 /// Handle /botapi_speed command - show upload speed stats from local Bot API logs (admin only)
 pub async fn handle_botapi_speed_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageSystem, "/botapi_speed") {
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
@@ -595,7 +596,7 @@ pub(super) fn format_transaction_partner_for_log(partner: &TransactionPartner) -
 
 /// Handle /transactions command - list recent Telegram Stars transactions (admin only)
 pub async fn handle_transactions_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ViewRevenue, "/transactions") {
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
@@ -668,28 +669,38 @@ pub async fn handle_transactions_command(bot: &Bot, chat_id: ChatId, user_id: i6
 }
 
 /// Handle /backup command - create database backup
-pub async fn handle_backup_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
-    if !is_admin(user_id) {
+pub async fn handle_backup_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    shared_storage: &SharedStorage,
+) -> Result<()> {
+    if !can(user_id, Permission::Backups, "/backup") {
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
     }
 
-    match create_backup(&config::DATABASE_PATH) {
-        Ok(backup_path) => {
-            let backups = list_backups().unwrap_or_default();
+    let backup_config = match BackupConfig::from_config() {
+        Ok(cfg) => cfg.with_shared_storage(shared_storage),
+        Err(e) => {
+            bot.send_message(chat_id, format!("❌ Invalid backup configuration: {:#}", e))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    match create_backup(&backup_config).await {
+        Ok(outcome) => {
+            let backups = list_backups(&backup_config.dir).unwrap_or_default();
             bot.send_message(
                 chat_id,
-                format!(
-                    "✅ Backup created successfully!\n\n📁 Path: {}\n📊 Total backups: {}",
-                    backup_path.display(),
-                    backups.len()
-                ),
+                format!("✅ {}\n\n📊 Total backups: {}", outcome.summary(), backups.len()),
             )
             .await?;
         }
         Err(e) => {
-            bot.send_message(chat_id, format!("❌ Error creating backup: {}", e))
+            bot.send_message(chat_id, format!("❌ Error creating backup: {:#}", e))
                 .await?;
         }
     }
@@ -707,7 +718,7 @@ pub async fn handle_backup_command(bot: &Bot, chat_id: ChatId, user_id: i64) ->
 /// uploads sent as `sendVideo`, which would defeat the point of staging
 /// pre-encoded test files. Documents pass through untouched.
 pub async fn handle_test_circle_save_command(bot: &Bot, msg: &Message, user_id: i64) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageSystem, "/test_circle_save") {
         bot.send_message(msg.chat.id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
@@ -807,7 +818,7 @@ pub async fn handle_test_circle_save_command(bot: &Bot, msg: &Message, user_id:
 /// describing the encode preset, file size, and bitrate so the admin can
 /// match render quality back to encoder choices when comparing.
 pub async fn handle_test_circle_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageSystem, "/test_circle") {
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
@@ -895,7 +906,7 @@ pub async fn handle_test_circle_command(bot: &Bot, chat_id: ChatId, user_id: i64
 /// loop, but on-demand and reporting *every* failure mode (including timeouts)
 /// straight back to the admin chat.
 pub async fn handle_update_health_check_command(bot: &Bot, chat_id: ChatId, user_id: i64) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageSystem, "/update_health_check") {
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
@@ -987,7 +998,7 @@ pub async fn handle_downsub_health_command(
     user_id: i64,
     downsub_gateway: Arc<DownsubGateway>,
 ) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageSystem, "/downsub_health") {
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
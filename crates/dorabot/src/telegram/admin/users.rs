@@ -1,4 +1,6 @@
-use super::{MAX_MESSAGE_LENGTH, download_helpers::download_file_from_telegram, escape_markdown, is_admin};
+use super::{
+    MAX_MESSAGE_LENGTH, Permission, can, download_helpers::download_file_from_telegram, escape_markdown, is_admin,
+};
 use crate::core::types::Plan;
 use crate::storage::SharedStorage;
 use crate::storage::db::DbPool;
@@ -19,7 +21,7 @@ pub async fn handle_users_command(
 ) -> Result<()> {
     log::debug!("Users command: username={:?}, is_admin={}", username, is_admin(user_id));
 
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ViewUsers, "/users") {
         log::warn!("User {:?} tried to access /users command without permission", username);
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
@@ -152,7 +154,7 @@ pub async fn handle_setplan_command(
     message_text: &str,
     shared_storage: Arc<SharedStorage>,
 ) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManagePlans, "/setplan") {
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
@@ -272,7 +274,7 @@ pub async fn handle_admin_command(
     user_id: i64,
     shared_storage: Arc<SharedStorage>,
 ) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ViewUsers, "/admin") {
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
@@ -291,7 +293,7 @@ pub async fn handle_charges_command(
     shared_storage: Arc<SharedStorage>,
     args: &str,
 ) -> Result<()> {
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ViewRevenue, "/charges") {
         bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
             .await?;
         return Ok(());
@@ -422,7 +424,7 @@ pub async fn handle_download_tg_command(
     message_text: &str,
 ) -> Result<()> {
     // Check admin permissions
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ManageSystem, "/download_tg") {
         bot.send_message(chat_id, "❌ This command is only available to administrators.")
             .await?;
         return Ok(());
@@ -520,7 +522,7 @@ pub async fn handle_sent_files_command(
     message_text: &str,
 ) -> Result<()> {
     // Check admin permissions
-    if !is_admin(user_id) {
+    if !can(user_id, Permission::ViewUsers, "/sent_files") {
         bot.send_message(chat_id, "❌ This command is only available to administrators.")
             .await?;
         return Ok(());
@@ -31,7 +31,7 @@ pub async fn handle_analytics_command(
 
     // Check if user is admin
     let user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok()).unwrap_or(0);
-    if !admin::is_admin(user_id) {
+    if !admin::can(user_id, admin::Permission::ViewDashboard, "/analytics") {
         bot.send_message(chat_id, "❌ This command is available to administrators only.")
             .await?;
         return Ok(());
@@ -74,7 +74,7 @@ pub async fn handle_health_command(
 
     // Check if user is admin
     let user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok()).unwrap_or(0);
-    if !admin::is_admin(user_id) {
+    if !admin::can(user_id, admin::Permission::ViewDashboard, "/health") {
         bot.send_message(chat_id, "❌ This command is available to administrators only.")
             .await?;
         return Ok(());
@@ -107,7 +107,7 @@ pub async fn handle_metrics_command(
 
     // Check if user is admin
     let user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok()).unwrap_or(0);
-    if !admin::is_admin(user_id) {
+    if !admin::can(user_id, admin::Permission::ViewDashboard, "/metrics") {
         bot.send_message(chat_id, "❌ This command is available to administrators only.")
             .await?;
         return Ok(());
@@ -146,7 +146,7 @@ pub async fn handle_revenue_command(
 
     // Check if user is admin
     let user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok()).unwrap_or(0);
-    if !admin::is_admin(user_id) {
+    if !admin::can(user_id, admin::Permission::ViewRevenue, "/revenue") {
         bot.send_message(chat_id, "❌ This command is available to administrators only.")
             .await?;
         return Ok(());
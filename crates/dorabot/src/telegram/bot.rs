@@ -43,12 +43,24 @@ pub enum Command {
     Export,
     #[command(description = "subscription and plan information")]
     Plan,
+    #[command(description = "credits balance, history and Star packs")]
+    Credits,
+    #[command(description = "redeem a promo or gift code")]
+    Redeem,
+    #[command(description = "gift a subscription to a friend")]
+    Gift,
+    #[command(description = "invite friends and earn credits")]
+    Invite,
+    #[command(description = "manage REST API keys")]
+    Apikey,
     #[command(description = "create a DB backup (admins only)")]
     Backup,
     #[command(description = "list all users (admin only)")]
     Users,
     #[command(description = "change user plan (admin only)")]
     Setplan,
+    #[command(description = "manage promo codes (admin only)")]
+    Promo,
     #[command(description = "view Stars transactions (admin only)")]
     Transactions,
     #[command(description = "user management panel (admin only)")]
@@ -108,11 +120,15 @@ const BOT_COMMAND_DEFINITIONS: &[(&str, &str)] = &[
     ("explore", "bot_commands.explore"),
     ("downloads", "bot_commands.downloads"),
     ("plan", "bot_commands.plan"),
+    ("credits", "bot_commands.credits"),
+    ("redeem", "bot_commands.redeem"),
+    ("gift", "bot_commands.gift"),
+    ("invite", "bot_commands.invite"),
     ("subscriptions", "bot_commands.subscriptions"),
     ("player", "bot_commands.player"),
     ("playlists", "bot_commands.playlists"),
     // Hidden from menu but still work: /info, /downsub, /uploads, /cuts,
-    // /history, /stats, /export, /playlist_integrations, /backup, admin commands
+    // /history, /stats, /export, /playlist_integrations, /apikey, /backup, admin commands
 ];
 
 fn build_bot_commands(lang: &LanguageIdentifier) -> Vec<BotCommand> {
@@ -223,7 +223,7 @@ async fn try_intercept_admin_search(
     if text.trim().starts_with('/') {
         return Ok(false);
     }
-    if !crate::telegram::admin::is_admin(msg.chat.id.0) {
+    if !crate::core::admin_roles::allows(msg.chat.id.0, crate::core::admin_roles::Permission::ViewUsers) {
         return Ok(false);
     }
     if !crate::telegram::menu::admin_users::is_admin_searching(shared_storage, msg.chat.id.0).await {
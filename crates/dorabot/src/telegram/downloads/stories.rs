@@ -27,9 +27,11 @@ use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 use tokio::time::timeout;
 
+use crate::core::credits;
 use crate::core::escape_markdown;
 use crate::i18n;
 use crate::storage::SharedStorage;
+use crate::storage::db::{CreditAction, DebitOutcome};
 use crate::telegram::Bot;
 use crate::telegram::BotExt;
 
@@ -515,6 +517,25 @@ async fn start_render(ctx: &CallbackCtx, download_id: i64, settings: StorySettin
         return Ok(());
     };
 
+    // Free plans pay for the render with credits once the daily allowance is
+    // used up. The render isn't a queue task, so it gets its own job id.
+    let job_id = format!("stories:{}", uuid::Uuid::new_v4());
+    if let Some(charge) =
+        credits::charge_for_user(&ctx.shared_storage, ctx.chat_id.0, Some(CreditAction::Stories)).await
+    {
+        match ctx.shared_storage.debit_credits(ctx.chat_id.0, &job_id, &charge).await {
+            Ok(DebitOutcome::Insufficient { balance }) => {
+                credits::notify_insufficient(&ctx.bot, ctx.chat_id, &ctx.shared_storage, charge.cost, balance).await;
+                return Ok(());
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::error!("stories: credit debit failed for {}: {}", ctx.chat_id.0, e);
+                return Ok(());
+            }
+        }
+    }
+
     // Replace the config card with progress feedback.
     ctx.bot.try_delete(ctx.chat_id, ctx.message_id).await;
 
@@ -523,8 +544,25 @@ async fn start_render(ctx: &CallbackCtx, download_id: i64, settings: StorySettin
     let chat_id = ctx.chat_id;
     let title = download.title.clone();
     tokio::spawn(async move {
-        if let Err(e) = run_stories(bot, shared_storage, chat_id, download_id, file_id, title, settings).await {
-            log::error!("stories: processing failed for download {}: {}", download_id, e);
+        let delivered = match run_stories(
+            bot.clone(),
+            Arc::clone(&shared_storage),
+            chat_id,
+            download_id,
+            file_id,
+            title,
+            settings,
+        )
+        .await
+        {
+            Ok(delivered) => delivered,
+            Err(e) => {
+                log::error!("stories: processing failed for download {}: {}", download_id, e);
+                false
+            }
+        };
+        if !delivered {
+            credits::refund_task(&bot, &shared_storage, chat_id, &job_id, "failed").await;
         }
     });
 
@@ -534,6 +572,9 @@ async fn start_render(ctx: &CallbackCtx, download_id: i64, settings: StorySettin
 /// Download the source MP4, render it to vertical 9:16 with the chosen reframe
 /// mode + quality, split into [`StorySettings::seg_secs`] segments and send each
 /// as a portrait video.
+///
+/// Returns `false` when nothing reached the user, so the caller can refund the
+/// credits debited for the render.
 #[allow(clippy::too_many_arguments)]
 async fn run_stories(
     bot: Bot,
@@ -543,7 +584,7 @@ async fn run_stories(
     file_id: String,
     title: String,
     settings: StorySettings,
-) -> ResponseResult<()> {
+) -> ResponseResult<bool> {
     let lang = i18n::user_lang_from_storage(&shared_storage, chat_id.0).await;
     let status = bot.send_message(chat_id, i18n::t(&lang, "stories-preparing")).await?;
 
@@ -580,7 +621,7 @@ async fn run_stories(
         bot.send_message(chat_id, i18n::t(&lang, "stories-download-failed"))
             .await
             .ok();
-        return Ok(());
+        return Ok(false);
     }
 
     // Cap overly long sources so the encode stays bounded.
@@ -616,7 +657,7 @@ async fn run_stories(
             Err(_) => {
                 bot.delete_message(chat_id, status.id).await.ok();
                 bot.send_message(chat_id, i18n::t(&lang, "stories-timeout")).await.ok();
-                return Ok(());
+                return Ok(false);
             }
         }
     };
@@ -627,7 +668,7 @@ async fn run_stories(
         bot.send_message(chat_id, i18n::t(&lang, "stories-cut-failed"))
             .await
             .ok();
-        return Ok(());
+        return Ok(false);
     }
 
     // ── Collect produced segments ──
@@ -650,7 +691,7 @@ async fn run_stories(
         bot.send_message(chat_id, i18n::t(&lang, "stories-no-segments"))
             .await
             .ok();
-        return Ok(());
+        return Ok(false);
     }
 
     let total = segments.len();
@@ -717,7 +758,7 @@ async fn run_stories(
 
     // `guard` drops here, removing the temp directory and all segments.
     drop(guard);
-    Ok(())
+    Ok(sent > 0)
 }
 
 /// Build the ffmpeg command: reframe the clip into the 9:16 frame (blurred fill
@@ -75,6 +75,15 @@ pub(super) async fn handle_start_command(bot: &Bot, msg: &Message, deps: &Handle
         .map(|user| user.is_some())
         .unwrap_or(false);
 
+    // Referral deep link: /start ref_{referrer_id}. Only brand-new users can
+    // be referred; the rest of the new-user flow runs as usual.
+    if !user_exists
+        && let Some(text) = msg.text()
+        && let Some(referrer) = text.strip_prefix("/start ref_")
+    {
+        crate::core::promo::record_referral(&deps.shared_storage, msg.chat.id.0, referrer).await;
+    }
+
     if user_exists {
         // Existing user - show enhanced main menu
         let _ = show_enhanced_main_menu(bot, msg.chat.id, deps.db_pool.clone(), deps.shared_storage.clone()).await;
@@ -158,6 +167,23 @@ pub(super) async fn handle_start_command(bot: &Bot, msg: &Message, deps: &Handle
         }
     }
 
+    // Gift / promo deep links: /start gift_{code}, /start promo_{code}.
+    // Redeemed after the welcome flow so the recipient has a user row.
+    if let Some(text) = msg.text()
+        && let Some(code) = text
+            .strip_prefix("/start gift_")
+            .or_else(|| text.strip_prefix("/start promo_"))
+    {
+        let has_user = matches!(deps.shared_storage.get_user(msg.chat.id.0).await, Ok(Some(_)));
+        if !has_user {
+            let username = msg.from.as_ref().and_then(|u| u.username.clone());
+            if let Err(e) = deps.shared_storage.create_user(msg.chat.id.0, username).await {
+                log::warn!("Failed to create user before redeeming deep-link code: {}", e);
+            }
+        }
+        let _ = crate::core::promo::redeem_code(bot, msg.chat.id, code, &deps.shared_storage).await;
+    }
+
     Ok(())
 }
 
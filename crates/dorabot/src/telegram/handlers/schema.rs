@@ -295,10 +295,8 @@ fn richtest_handler(_deps: HandlerDeps) -> UpdateHandler<HandlerError> {
         .endpoint(move |bot: Bot, msg: Message| async move {
             let user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok()).unwrap_or(0);
 
-            // Admin gate: only the configured admin may probe.
-            if user_id != *crate::core::config::admin::ADMIN_USER_ID
-                && !crate::core::config::admin::ADMIN_IDS.contains(&user_id)
-            {
+            // Admin gate: only staff with `manage_system` may probe.
+            if !crate::telegram::admin::can(user_id, crate::telegram::admin::Permission::ManageSystem, "/richtest") {
                 let _ = bot.send_message(msg.chat.id, "❌ admin only").await;
                 return Ok(());
             }
@@ -466,10 +464,10 @@ fn command_handler(deps: HandlerDeps) -> UpdateHandler<HandlerError> {
     use crate::telegram::{
         handle_admin_command, handle_analytics_command, handle_backup_command, handle_botapi_speed_command,
         handle_charges_command, handle_download_tg_command, handle_downsub_command, handle_downsub_health_command,
-        handle_health_command, handle_info_command, handle_metrics_command, handle_revenue_command,
-        handle_sent_files_command, handle_setplan_command, handle_test_circle_command, handle_test_circle_save_command,
-        handle_transactions_command, handle_update_health_check_command, handle_users_command, handle_version_command,
-        show_main_menu,
+        handle_health_command, handle_info_command, handle_metrics_command, handle_promo_command,
+        handle_revenue_command, handle_sent_files_command, handle_setplan_command, handle_test_circle_command,
+        handle_test_circle_save_command, handle_transactions_command, handle_update_health_check_command,
+        handle_users_command, handle_version_command, show_main_menu,
     };
 
     Update::filter_message()
@@ -574,7 +572,7 @@ fn command_handler(deps: HandlerDeps) -> UpdateHandler<HandlerError> {
                             }
                             Command::Backup => {
                                 let user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok()).unwrap_or(0);
-                                let _ = handle_backup_command(&bot, msg.chat.id, user_id).await;
+                                let _ = handle_backup_command(&bot, msg.chat.id, user_id, &deps.shared_storage).await;
                             }
                             Command::Plan => {
                                 let _ = show_subscription_info(
@@ -585,6 +583,37 @@ fn command_handler(deps: HandlerDeps) -> UpdateHandler<HandlerError> {
                                 )
                                 .await;
                             }
+                            Command::Credits => {
+                                let _ =
+                                    crate::core::credits::show_credits(&bot, msg.chat.id, &deps.shared_storage).await;
+                            }
+                            Command::Redeem => {
+                                let message_text = msg.text().unwrap_or("");
+                                let _ = crate::core::promo::handle_redeem_command(
+                                    &bot,
+                                    msg.chat.id,
+                                    message_text,
+                                    &deps.shared_storage,
+                                )
+                                .await;
+                            }
+                            Command::Gift => {
+                                let _ =
+                                    crate::core::promo::show_gift_menu(&bot, msg.chat.id, &deps.shared_storage).await;
+                            }
+                            Command::Invite => {
+                                let _ = crate::core::promo::show_invite(&bot, msg.chat.id, &deps.shared_storage).await;
+                            }
+                            Command::Apikey => {
+                                let message_text = msg.text().unwrap_or("");
+                                let _ = crate::core::api_keys::handle_apikey_command(
+                                    &bot,
+                                    msg.chat.id,
+                                    message_text,
+                                    &deps.shared_storage,
+                                )
+                                .await;
+                            }
                             Command::Users => {
                                 let username = msg.from.as_ref().and_then(|u| u.username.as_deref());
                                 let user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok()).unwrap_or(0);
@@ -610,6 +639,18 @@ fn command_handler(deps: HandlerDeps) -> UpdateHandler<HandlerError> {
                                 )
                                 .await;
                             }
+                            Command::Promo => {
+                                let user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok()).unwrap_or(0);
+                                let message_text = msg.text().unwrap_or("");
+                                let _ = handle_promo_command(
+                                    &bot,
+                                    msg.chat.id,
+                                    user_id,
+                                    message_text,
+                                    deps.shared_storage.clone(),
+                                )
+                                .await;
+                            }
                             Command::Transactions => {
                                 let user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok()).unwrap_or(0);
                                 let _ = handle_transactions_command(&bot, msg.chat.id, user_id).await;
@@ -726,9 +767,10 @@ fn command_handler(deps: HandlerDeps) -> UpdateHandler<HandlerError> {
                                 let _ = handle_version_command(&bot, msg.chat.id, user_id).await;
                             }
                             Command::Subscriptions => {
-                                crate::telegram::subscriptions::handle_subscriptions_command(
+                                crate::telegram::subscriptions::handle_subscriptions_text(
                                     &bot,
                                     msg.chat.id,
+                                    msg.text().unwrap_or(""),
                                     &deps.db_pool,
                                     &deps.shared_storage,
                                 )
@@ -907,7 +949,10 @@ fn pre_checkout_handler(deps: HandlerDeps) -> UpdateHandler<HandlerError> {
             log::info!("Received pre_checkout_query: id={}, payload={}", query_id, payload);
 
             // Validate the payload
-            if payload.starts_with("subscription:") {
+            if payload.starts_with("subscription:")
+                || payload.starts_with(crate::core::credits::PAYLOAD_PREFIX)
+                || payload.starts_with(crate::core::promo::GIFT_PAYLOAD_PREFIX)
+            {
                 // Approve the payment
                 match bot.answer_pre_checkout_query(query_id.clone(), true).await {
                     Ok(_) => {
@@ -37,6 +37,17 @@ fn preserves_search_mode(action: Option<&str>) -> bool {
     matches!(action, Some("s"))
 }
 
+/// Permission an `au:` callback needs: plan changes are owner-only, block
+/// and settings edits need `manage_users`, browsing needs `view_users`.
+pub(crate) fn callback_permission(data: &str) -> admin::Permission {
+    let action = data.strip_prefix("au:").and_then(|rest| rest.split(':').next());
+    match action {
+        Some("sp" | "se") => admin::Permission::ManagePlans,
+        Some("b" | "cb" | "st" | "cs") => admin::Permission::ManageUsers,
+        _ => admin::Permission::ViewUsers,
+    }
+}
+
 // --- Filter ---
 
 #[derive(Clone, Copy, PartialEq, Default)]
@@ -650,7 +661,8 @@ pub async fn handle_callback(
 
 #[cfg(test)]
 mod tests {
-    use super::preserves_search_mode;
+    use super::{callback_permission, preserves_search_mode};
+    use crate::telegram::admin::Permission;
 
     #[test]
     fn search_mode_is_preserved_only_for_search_prompt_action() {
@@ -659,4 +671,14 @@ mod tests {
         assert!(!preserves_search_mode(Some("u")));
         assert!(!preserves_search_mode(None));
     }
+
+    #[test]
+    fn callback_permission_follows_the_action() {
+        assert_eq!(callback_permission("au:l:0:a"), Permission::ViewUsers);
+        assert_eq!(callback_permission("au:u:42"), Permission::ViewUsers);
+        assert_eq!(callback_permission("au:sp:42:premium"), Permission::ManagePlans);
+        assert_eq!(callback_permission("au:se:42:premium:30"), Permission::ManagePlans);
+        assert_eq!(callback_permission("au:cb:42"), Permission::ManageUsers);
+        assert_eq!(callback_permission("au:cs:42:quality:720"), Permission::ManageUsers);
+    }
 }
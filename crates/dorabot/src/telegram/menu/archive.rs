@@ -1,8 +1,8 @@
-use crate::core::validation::sanitize_filename;
-use anyhow::Context;
+use crate::download::filename_template::{ArchiveNames, TemplateFields, archive_template};
 use crate::storage::db::{self, DbPool, DownloadHistoryEntry};
 use crate::telegram::admin::download_file_from_telegram;
 use crate::telegram::{Bot, BotExt};
+use anyhow::Context;
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -405,7 +405,7 @@ async fn handle_build(
     // Download files from Telegram
     let mut downloaded_files: Vec<(String, PathBuf)> = Vec::new();
     let mut skipped = 0usize;
-    let mut name_counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    let mut archive_names = ArchiveNames::default();
 
     for (i, item) in items.iter().enumerate() {
         let file_id = match &item.file_id {
@@ -416,31 +416,12 @@ async fn handle_build(
             }
         };
 
-        // Build filename
-        let base_name = if let Some(ref author) = item.author {
-            if author.is_empty() {
-                item.title.clone()
-            } else {
-                format!("{} - {}", author, item.title)
-            }
-        } else {
-            item.title.clone()
-        };
-
-        let sanitized = sanitize_filename(&base_name);
-        let ext = &item.format;
-
-        // Deduplicate names
-        let key = format!("{}.{}", sanitized, ext);
-        let count = name_counts.entry(key).or_insert(0);
-        *count += 1;
-        let filename = if *count > 1 {
-            format!("{} ({}).{}", sanitized, count, ext)
-        } else {
-            format!("{}.{}", sanitized, ext)
-        };
-
-        let dest_path = temp_dir.join(&filename);
+        // Entry name from the archive template; files land flat in the temp dir
+        let rel = archive_template()
+            .render(&TemplateFields::from(item), &item.format)
+            .unwrap_or_else(|| PathBuf::from(format!("download-{}.{}", item.id, item.format)));
+        let filename = archive_names.claim(&rel);
+        let dest_path = temp_dir.join(format!("{}.{}", i, item.format));
 
         match download_file_from_telegram(bot, file_id, Some(dest_path.clone())).await {
             Ok(_) => {
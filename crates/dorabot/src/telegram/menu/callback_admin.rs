@@ -25,9 +25,11 @@ pub async fn handle_admin_callback(
     if data.starts_with("analytics:") {
         let _ = bot.answer_callback_query(callback_id.clone()).await;
 
-        let is_admin = i64::try_from(from.id.0).ok().map(admin::is_admin).unwrap_or(false);
+        let allowed = i64::try_from(from.id.0)
+            .ok()
+            .is_some_and(|id| admin::can(id, admin::Permission::ViewDashboard, data));
 
-        if !is_admin {
+        if !allowed {
             bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
                 .await?;
             return Ok(true);
@@ -71,9 +73,11 @@ pub async fn handle_admin_callback(
     if data.starts_with("metrics:") {
         let _ = bot.answer_callback_query(callback_id.clone()).await;
 
-        let is_admin = i64::try_from(from.id.0).ok().map(admin::is_admin).unwrap_or(false);
+        let allowed = i64::try_from(from.id.0)
+            .ok()
+            .is_some_and(|id| admin::can(id, admin::Permission::ViewDashboard, data));
 
-        if !is_admin {
+        if !allowed {
             bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
                 .await?;
             return Ok(true);
@@ -96,8 +100,10 @@ pub async fn handle_admin_callback(
 
     if data.starts_with("au:") {
         let _ = bot.answer_callback_query(callback_id.clone()).await;
-        let is_admin = i64::try_from(from.id.0).ok().map(admin::is_admin).unwrap_or(false);
-        if !is_admin {
+        let allowed = i64::try_from(from.id.0)
+            .ok()
+            .is_some_and(|id| admin::can(id, admin_users::callback_permission(data), data));
+        if !allowed {
             bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
                 .await?;
             return Ok(true);
@@ -111,9 +117,11 @@ pub async fn handle_admin_callback(
     if data.starts_with("admin:") {
         let _ = bot.answer_callback_query(callback_id.clone()).await;
 
-        let is_admin = i64::try_from(from.id.0).ok().map(admin::is_admin).unwrap_or(false);
+        let allowed = i64::try_from(from.id.0)
+            .ok()
+            .is_some_and(|id| admin::can(id, admin::Permission::ManageCookies, data));
 
-        if !is_admin {
+        if !allowed {
             bot.send_message(chat_id, "❌ You don't have permission to execute this command.")
                 .await?;
             return Ok(true);
@@ -15,7 +15,7 @@ use teloxide::prelude::*;
 use url::Url;
 
 use crate::core::rate_limiter::RateLimiter;
-use crate::download::queue::{DownloadFormat, DownloadQueue, DownloadTask};
+use crate::download::queue::{AddTaskOutcome, DownloadFormat, DownloadQueue, DownloadTask};
 use crate::storage::SharedStorage;
 use crate::storage::cache;
 use crate::storage::db::DbPool;
@@ -138,7 +138,19 @@ pub async fn handle_download_callback(
                             .priority(priority)
                             .build();
                         task_mp4.time_range = time_range.clone();
-                        download_queue.add_task(task_mp4, Some(Arc::clone(&db_pool))).await;
+                        task_mp4.credit_charge = crate::core::credits::charge_for_user(
+                            &shared_storage,
+                            chat_id.0,
+                            crate::core::credits::premium_action(task_mp4.video_quality.as_deref(), None),
+                        )
+                        .await;
+                        if let AddTaskOutcome::InsufficientCredits { cost, balance } =
+                            download_queue.add_task(task_mp4, Some(Arc::clone(&db_pool))).await
+                        {
+                            crate::core::credits::notify_insufficient(bot, chat_id, &shared_storage, cost, balance)
+                                .await;
+                            return Ok(());
+                        }
 
                         let audio_bitrate = Some(
                             shared_storage
@@ -216,7 +228,19 @@ pub async fn handle_download_callback(
                         task.time_range = time_range.clone();
                         task.carousel_mask = carousel_mask;
                         task.with_lyrics = with_lyrics;
-                        download_queue.add_task(task, Some(Arc::clone(&db_pool))).await;
+                        task.credit_charge = crate::core::credits::charge_for_user(
+                            &shared_storage,
+                            chat_id.0,
+                            crate::core::credits::premium_action(task.video_quality.as_deref(), carousel_mask),
+                        )
+                        .await;
+                        if let AddTaskOutcome::InsufficientCredits { cost, balance } =
+                            download_queue.add_task(task, Some(Arc::clone(&db_pool))).await
+                        {
+                            crate::core::credits::notify_insufficient(bot, chat_id, &shared_storage, cost, balance)
+                                .await;
+                            return Ok(());
+                        }
 
                         if !silent
                             && let Some(msg_id) = send_queue_position_message(
@@ -120,6 +120,10 @@ pub(crate) enum CallbackKind {
     Long,
     #[strum(serialize = "exp")]
     Explore,
+    #[strum(serialize = "credits")]
+    Credits,
+    #[strum(serialize = "promo")]
+    Promo,
 }
 
 impl CallbackKind {
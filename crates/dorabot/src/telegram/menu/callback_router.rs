@@ -200,6 +200,7 @@ pub async fn handle_menu_callback(
                             crate::telegram::subscriptions::show_subscribe_confirm(
                                 &bot,
                                 chat_id,
+                                "instagram",
                                 username,
                                 &db_pool,
                                 &shared_storage,
@@ -566,6 +567,32 @@ pub async fn handle_menu_callback(
                     .await?;
                 }
 
+                CallbackKind::Credits => {
+                    try_forward!(
+                        "Credits",
+                        crate::core::credits::handle_credits_callback(
+                            &bot,
+                            callback_id.clone(),
+                            chat_id,
+                            &data,
+                            Arc::clone(&shared_storage),
+                        )
+                    );
+                }
+
+                CallbackKind::Promo => {
+                    try_forward!(
+                        "Promo",
+                        crate::core::promo::handle_promo_callback(
+                            &bot,
+                            callback_id.clone(),
+                            chat_id,
+                            &data,
+                            Arc::clone(&shared_storage),
+                        )
+                    );
+                }
+
                 CallbackKind::DlCancel => {
                     let signalled = crate::download::cancel_registry::cancel(chat_id.0);
                     let answer_text = if signalled {
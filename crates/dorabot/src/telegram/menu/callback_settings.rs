@@ -79,7 +79,7 @@ pub async fn handle_settings_callback(
     }
 
     if let Some(plan) = data.strip_prefix("subscribe:") {
-        handle_settings_subscribe(bot, callback_id, chat_id, data, plan).await?;
+        handle_settings_subscribe(bot, callback_id, chat_id, data, plan, &shared_storage).await?;
         return Ok(true);
     }
 
@@ -429,6 +429,7 @@ async fn handle_settings_subscribe(
     chat_id: ChatId,
     data: &str,
     plan: &str,
+    shared_storage: &SharedStorage,
 ) -> ResponseResult<()> {
     log::info!("🔔 Subscribe callback received: data={}, chat_id={}", data, chat_id.0);
     bot.answer_callback_query(callback_id.clone()).await?;
@@ -436,7 +437,15 @@ async fn handle_settings_subscribe(
     match plan {
         "premium" | "vip" => {
             log::info!("✅ Valid plan '{}', creating invoice for chat_id={}", plan, chat_id.0);
-            match create_subscription_invoice(bot, chat_id, plan).await {
+            let discount = shared_storage
+                .get_pending_discount(chat_id.0)
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to load pending discount for {}: {}", chat_id.0, e);
+                    None
+                });
+            let discount = discount.as_ref().map(|(code, percent)| (code.as_str(), *percent));
+            match create_subscription_invoice(bot, chat_id, plan, discount).await {
                 Ok(msg) => {
                     log::info!(
                         "✅ Invoice created successfully for user {} plan {}. Message ID: {}",
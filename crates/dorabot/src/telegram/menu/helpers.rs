@@ -1,5 +1,5 @@
 use crate::core::rate_limiter::RateLimiter;
-use crate::download::queue::{DownloadFormat, DownloadQueue, DownloadTask};
+use crate::download::queue::{AddTaskOutcome, DownloadFormat, DownloadQueue, DownloadTask};
 use crate::i18n;
 use crate::storage::SharedStorage;
 use crate::storage::cache;
@@ -173,7 +173,18 @@ pub(crate) async fn enqueue_download_tasks(
             .priority(crate::download::queue::TaskPriority::from_plan(plan))
             .build();
         task_mp4.time_range = time_range.clone();
-        download_queue.add_task(task_mp4, Some(Arc::clone(&db_pool))).await;
+        task_mp4.credit_charge = crate::core::credits::charge_for_user(
+            &shared_storage,
+            chat_id.0,
+            crate::core::credits::premium_action(task_mp4.video_quality.as_deref(), None),
+        )
+        .await;
+        if let AddTaskOutcome::InsufficientCredits { cost, balance } =
+            download_queue.add_task(task_mp4, Some(Arc::clone(&db_pool))).await
+        {
+            crate::core::credits::notify_insufficient(bot, chat_id, &shared_storage, cost, balance).await;
+            return;
+        }
 
         let audio_bitrate = Some(
             shared_storage
@@ -232,7 +243,18 @@ pub(crate) async fn enqueue_download_tasks(
             .priority(crate::download::queue::TaskPriority::from_plan(plan))
             .build();
         task.time_range = time_range.clone();
-        download_queue.add_task(task, Some(Arc::clone(&db_pool))).await;
+        task.credit_charge = crate::core::credits::charge_for_user(
+            &shared_storage,
+            chat_id.0,
+            crate::core::credits::premium_action(task.video_quality.as_deref(), None),
+        )
+        .await;
+        if let AddTaskOutcome::InsufficientCredits { cost, balance } =
+            download_queue.add_task(task, Some(Arc::clone(&db_pool))).await
+        {
+            crate::core::credits::notify_insufficient(bot, chat_id, &shared_storage, cost, balance).await;
+            return;
+        }
     }
 
     // Send queue position notification and store message ID for later deletion
@@ -43,19 +43,11 @@ fn truncate_str(s: &str, max_chars: usize) -> &str {
 // ── Plan limits ───────────────────────────────────────────────────────────
 
 fn max_playlists(plan: Plan) -> i64 {
-    match plan {
-        Plan::Free => 3,
-        Plan::Premium => 10,
-        Plan::Vip => 100,
-    }
+    plan.max_playlists()
 }
 
 pub fn max_tracks_per_playlist(plan: Plan) -> i64 {
-    match plan {
-        Plan::Free => 50,
-        Plan::Premium => 200,
-        Plan::Vip => 1000,
-    }
+    plan.max_playlist_tracks()
 }
 
 // ── Name input session ────────────────────────────────────────────────────
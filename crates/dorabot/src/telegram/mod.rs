@@ -82,7 +82,7 @@ pub use admin::{
     handle_backup_command, handle_botapi_speed_command, handle_broadcast_command, handle_browser_callback,
     handle_browser_login_command, handle_browser_status_command, handle_charges_command,
     handle_check_ytdlp_version_callback, handle_cookies_file_upload, handle_download_tg_command,
-    handle_downsub_health_command, handle_ig_cookies_file_upload, handle_proxy_reset_command,
+    handle_downsub_health_command, handle_ig_cookies_file_upload, handle_promo_command, handle_proxy_reset_command,
     handle_proxy_stats_command, handle_send_command, handle_sent_files_command, handle_setplan_command,
     handle_test_circle_command, handle_test_circle_save_command, handle_transactions_command,
     handle_update_cookies_command, handle_update_health_check_command, handle_update_ig_cookies_command,
@@ -1,4 +1,4 @@
-use crate::core::config::admin::{ADMIN_IDS, ADMIN_USER_ID};
+use crate::core::config;
 use crate::storage::db::{self as db, DbPool};
 use crate::telegram::Bot;
 use std::sync::Arc;
@@ -43,13 +43,7 @@ async fn send_plain_text_chunks(bot: &Bot, chat_id: ChatId, text: &str) {
 }
 
 fn admin_chat_ids() -> Vec<ChatId> {
-    if !ADMIN_IDS.is_empty() {
-        return ADMIN_IDS.iter().copied().map(ChatId).collect();
-    }
-    if *ADMIN_USER_ID != 0 {
-        return vec![ChatId(*ADMIN_USER_ID)];
-    }
-    Vec::new()
+    config::admin::OWNER_IDS.iter().copied().map(ChatId).collect()
 }
 
 /// Sends a plain-text message to the configured admins (uses `ADMIN_IDS` or `ADMIN_USER_ID`).
@@ -4,12 +4,19 @@
 //
 use crate::core::config;
 use crate::download::source::instagram::InstagramSource;
+use crate::mtproto::MtProtoDownloader;
 use crate::storage::SharedStorage;
 use crate::storage::db::DbPool;
 use crate::telegram::cb;
 use crate::telegram::{Bot, BotExt};
 use crate::watcher::WatcherRegistry;
-use crate::watcher::traits::WatchNotification;
+use crate::watcher::auto_download::{AUTO_FORMATS, DAILY_CAP_STEPS, quality_steps};
+use crate::watcher::filter::{apply_filter_args, describe_filters};
+use crate::watcher::instagram::MASK_STORIES;
+use crate::watcher::rss::{feed_episode, normalize_feed_url};
+use crate::watcher::telegram_channel::parse_channel_username;
+use crate::watcher::traits::{ContentWatcher, WatchNotification};
+use crate::watcher::youtube::YoutubeSource;
 use futures_util::StreamExt as _;
 use sqlx::{Postgres, pool::PoolConnection};
 use std::path::PathBuf;
@@ -17,9 +24,11 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use teloxide::prelude::*;
 use teloxide::types::{
-    CallbackQueryId, ChatId, InlineKeyboardMarkup, InputFile, InputMedia, InputMediaPhoto, InputMediaVideo, MessageId,
+    CallbackQueryId, ChatId, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, InputMedia, InputMediaPhoto,
+    InputMediaVideo, MessageId, Recipient,
 };
 use tokio::sync::mpsc;
+use url::Url;
 
 /// Max subscriptions by plan.
 fn max_subscriptions_for_plan(plan: &str) -> u32 {
@@ -30,6 +39,92 @@ fn max_subscriptions_for_plan(plan: &str) -> u32 {
     }
 }
 
+fn source_emoji(source_type: &str) -> &'static str {
+    match source_type {
+        "instagram" => "📸",
+        "youtube" => "▶️",
+        "rss" => "🎙",
+        "telegram_channel" => "📣",
+        _ => "🔗",
+    }
+}
+
+/// Content types offered for a source. Instagram stories need cookies.
+fn available_content_types<'a>(watcher: &'a dyn ContentWatcher, source_id: &str) -> Vec<(u32, &'a str)> {
+    let mut types = watcher.content_types_for(source_id);
+    if watcher.source_type() == "instagram" && crate::download::cookies::load_instagram_cookie_header().is_none() {
+        types.retain(|(bit, _)| *bit != MASK_STORIES);
+    }
+    types
+}
+
+/// Labels of the content types enabled in `mask`, e.g. "Posts + Stories".
+fn mask_labels(watcher: Option<&dyn ContentWatcher>, source_id: &str, mask: u32) -> String {
+    let Some(watcher) = watcher else {
+        return String::new();
+    };
+    watcher
+        .content_types_for(source_id)
+        .into_iter()
+        .filter(|(bit, _)| mask & bit != 0)
+        .map(|(_, label)| label)
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn toggle_label(label: &str, enabled: bool) -> String {
+    format!("{}{}", if enabled { "✅ " } else { "☐ " }, label)
+}
+
+/// Longest source ID sent verbatim in `cw:` callback data (Telegram caps it at 64 bytes).
+const MAX_CALLBACK_SOURCE_ID_LEN: usize = 40;
+
+/// Source IDs that can't travel in callback data (feed URLs: long and full of
+/// `:`) are swapped for a URL-cache id, `~<id>`.
+async fn callback_source_ref(db_pool: &DbPool, shared_storage: &SharedStorage, source_id: &str) -> String {
+    if source_id.len() <= MAX_CALLBACK_SOURCE_ID_LEN && !source_id.contains(':') && !source_id.starts_with('~') {
+        return source_id.to_string();
+    }
+    let url_id = crate::storage::cache::store_url(db_pool, Some(shared_storage), source_id).await;
+    format!("~{}", url_id)
+}
+
+/// Inverse of [`callback_source_ref`]. `None` when the cached ID has expired.
+async fn resolve_source_ref(db_pool: &DbPool, shared_storage: &SharedStorage, source_ref: &str) -> Option<String> {
+    match source_ref.strip_prefix('~') {
+        Some(url_id) => crate::storage::cache::get_url(db_pool, Some(shared_storage), url_id).await,
+        None => Some(source_ref.to_string()),
+    }
+}
+
+/// Content-type toggles plus Confirm/Cancel for a subscription not yet created.
+/// `source_ref` is the callback-safe form of `source_id`.
+fn pending_keyboard(
+    watcher: &dyn ContentWatcher,
+    source_id: &str,
+    source_ref: &str,
+    mask: u32,
+) -> InlineKeyboardMarkup {
+    let source_type = watcher.source_type();
+    let toggle_row: Vec<InlineKeyboardButton> = available_content_types(watcher, source_id)
+        .into_iter()
+        .map(|(bit, label)| {
+            cb(
+                toggle_label(label, mask & bit != 0),
+                format!("cw:ptog:{}:{}:{}:{}", source_type, source_ref, bit, mask),
+            )
+        })
+        .collect();
+
+    InlineKeyboardMarkup::new(vec![
+        toggle_row,
+        vec![
+            cb("✔ Confirm", format!("cw:ok:{}:{}:{}", source_type, source_ref, mask)),
+            cb("✖ Cancel", "cw:cancel".to_string()),
+        ],
+    ])
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum SubscriptionCallback<'a> {
     ConfirmSubscribe {
@@ -39,7 +134,8 @@ enum SubscriptionCallback<'a> {
     },
     Cancel,
     TogglePending {
-        username: &'a str,
+        source_type: &'a str,
+        source_id: &'a str,
         bit: u32,
         current_mask: u32,
     },
@@ -53,6 +149,18 @@ enum SubscriptionCallback<'a> {
         sub_id: i64,
         bit: u32,
     },
+    AutoToggle {
+        sub_id: i64,
+    },
+    AutoFormat {
+        sub_id: i64,
+    },
+    AutoQuality {
+        sub_id: i64,
+    },
+    AutoCap {
+        sub_id: i64,
+    },
     List,
 }
 
@@ -69,11 +177,21 @@ fn parse_subscription_callback(data: &str) -> Option<SubscriptionCallback<'_>> {
             mask: parts.next().and_then(|mask| mask.parse().ok()).unwrap_or(3),
         }),
         "cancel" => Some(SubscriptionCallback::Cancel),
-        "ptog" => Some(SubscriptionCallback::TogglePending {
-            username: parts.next()?,
-            bit: parts.next().and_then(|bit| bit.parse().ok()).unwrap_or(0),
-            current_mask: parts.next().and_then(|mask| mask.parse().ok()).unwrap_or(3),
-        }),
+        "ptog" => {
+            let rest: Vec<&str> = parts.collect();
+            // Buttons sent before YouTube support omit the source type: cw:ptog:<username>:<bit>:<mask>
+            let (source_type, rest) = if rest.len() >= 4 {
+                (rest[0], &rest[1..])
+            } else {
+                ("instagram", &rest[..])
+            };
+            Some(SubscriptionCallback::TogglePending {
+                source_type,
+                source_id: *rest.first()?,
+                bit: rest.get(1).and_then(|bit| bit.parse().ok()).unwrap_or(0),
+                current_mask: rest.get(2).and_then(|mask| mask.parse().ok()).unwrap_or(3),
+            })
+        }
         "manage" => Some(SubscriptionCallback::Manage {
             sub_id: parts.next()?.parse().ok()?,
         }),
@@ -84,6 +202,18 @@ fn parse_subscription_callback(data: &str) -> Option<SubscriptionCallback<'_>> {
             sub_id: parts.next()?.parse().ok()?,
             bit: parts.next()?.parse().ok()?,
         }),
+        "auto" => Some(SubscriptionCallback::AutoToggle {
+            sub_id: parts.next()?.parse().ok()?,
+        }),
+        "afmt" => Some(SubscriptionCallback::AutoFormat {
+            sub_id: parts.next()?.parse().ok()?,
+        }),
+        "aq" => Some(SubscriptionCallback::AutoQuality {
+            sub_id: parts.next()?.parse().ok()?,
+        }),
+        "cap" => Some(SubscriptionCallback::AutoCap {
+            sub_id: parts.next()?.parse().ok()?,
+        }),
         "list" => Some(SubscriptionCallback::List),
         _ => None,
     }
@@ -91,6 +221,77 @@ fn parse_subscription_callback(data: &str) -> Option<SubscriptionCallback<'_>> {
 
 // ─── /subscriptions command ───
 
+/// Map a link (or bare YouTube `@handle`) to a subscribable `(source_type, source_id)`.
+/// Any other http(s) link is treated as an RSS/Atom feed.
+fn parse_subscription_target(input: &str) -> Option<(&'static str, String)> {
+    if let Some(source) = YoutubeSource::parse(input) {
+        return Some(("youtube", source.source_id()));
+    }
+    let url = Url::parse(input).ok()?;
+    if let Some(username) = InstagramSource::extract_profile_username(&url) {
+        return Some(("instagram", username));
+    }
+    if let Some(username) = parse_channel_username(input) {
+        return Some(("telegram_channel", username));
+    }
+    // Video, post and invite links on these sites are not feeds
+    let is_instagram = url
+        .host_str()
+        .is_some_and(|host| host == "instagram.com" || host.ends_with(".instagram.com"));
+    let is_telegram = url
+        .host_str()
+        .is_some_and(|host| matches!(host, "t.me" | "www.t.me" | "telegram.me" | "www.telegram.me"));
+    if is_instagram || is_telegram || crate::core::share::is_youtube_url(input) {
+        return None;
+    }
+    normalize_feed_url(input).map(|feed_url| ("rss", feed_url))
+}
+
+/// Handle `/subscriptions [link]`: with a channel, playlist, profile or feed link
+/// start the subscribe flow, otherwise list the user's subscriptions.
+pub async fn handle_subscriptions_text(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_text: &str,
+    db_pool: &Arc<DbPool>,
+    shared_storage: &Arc<SharedStorage>,
+) {
+    let args: Vec<&str> = message_text.split_whitespace().skip(1).collect();
+    let Some(&arg) = args.first() else {
+        handle_subscriptions_command(bot, chat_id, db_pool, shared_storage).await;
+        return;
+    };
+    if arg.eq_ignore_ascii_case("filter") {
+        handle_filter_command(bot, chat_id, &args[1..], shared_storage).await;
+        return;
+    }
+
+    match parse_subscription_target(arg) {
+        Some((source_type, source_id)) => {
+            let registry = WatcherRegistry::default_registry();
+            show_subscribe_confirm(
+                bot,
+                chat_id,
+                source_type,
+                &source_id,
+                db_pool,
+                shared_storage,
+                &registry,
+            )
+            .await;
+        }
+        None => {
+            let _ = bot
+                .send_message(
+                    chat_id,
+                    "Can't subscribe to that link. Send a YouTube channel or playlist, \
+                     an Instagram profile, a public Telegram channel, or a podcast/RSS feed.",
+                )
+                .await;
+        }
+    }
+}
+
 /// Handle the /subscriptions command: show list of user's subscriptions.
 pub async fn handle_subscriptions_command(
     bot: &Bot,
@@ -120,7 +321,8 @@ pub async fn handle_subscriptions_command(
     if subs.is_empty() {
         let text = format!(
             "🔔 You have no active subscriptions.\n\n\
-             Send an Instagram profile link to subscribe to updates.\n\
+             Send /subscriptions with a YouTube channel or playlist link \
+             (or open an Instagram profile) to subscribe to updates.\n\
              Limit: 0/{} subscriptions",
             max_subs
         );
@@ -130,25 +332,12 @@ pub async fn handle_subscriptions_command(
 
     let mut text = format!("🔔 Your Subscriptions ({}/{})\n", subs.len(), max_subs);
 
-    let mut buttons: Vec<Vec<teloxide::types::InlineKeyboardButton>> = Vec::new();
+    let registry = WatcherRegistry::default_registry();
+    let mut buttons: Vec<Vec<InlineKeyboardButton>> = Vec::new();
 
     for (i, sub) in subs.iter().enumerate() {
-        let source_emoji = match sub.source_type.as_str() {
-            "instagram" => "📸",
-            _ => "🔗",
-        };
-
-        let types: Vec<&str> = {
-            let mut t = Vec::new();
-            if sub.watch_mask & 1 != 0 {
-                t.push("Posts");
-            }
-            if sub.watch_mask & 2 != 0 {
-                t.push("Stories");
-            }
-            t
-        };
-        let types_str = types.join(" + ");
+        let source_emoji = source_emoji(&sub.source_type);
+        let types_str = mask_labels(registry.get(&sub.source_type), &sub.source_id, sub.watch_mask);
 
         let last_check = sub.last_checked_at.as_deref().unwrap_or("never");
 
@@ -176,26 +365,28 @@ pub async fn handle_subscriptions_command(
     let _ = bot.send_message(chat_id, text).reply_markup(keyboard).await;
 }
 
-// ─── Subscribe flow (triggered from ig:sub:<username>) ───
+// ─── Subscribe flow (triggered from ig:sub:<username> or /subscriptions <link>) ───
 
 /// Show subscribe confirmation dialog.
 pub async fn show_subscribe_confirm(
     bot: &Bot,
     chat_id: ChatId,
-    username: &str,
+    source_type: &str,
+    source_id: &str,
     db_pool: &Arc<DbPool>,
     shared_storage: &Arc<SharedStorage>,
     registry: &WatcherRegistry,
 ) {
-    let watcher = match registry.get("instagram") {
+    let watcher = match registry.get(source_type) {
         Some(w) => w,
         None => {
-            let _ = bot.send_message(chat_id, "Instagram watcher not available").await;
+            let _ = bot
+                .send_message(chat_id, format!("{} watcher not available", source_type))
+                .await;
             return;
         }
     };
 
-    let _ = db_pool;
     let plan = shared_storage
         .get_user(chat_id.0)
         .await
@@ -220,12 +411,15 @@ pub async fn show_subscribe_confirm(
 
     // Check if already subscribed
     if let Ok(Some(existing)) = shared_storage
-        .has_content_subscription(chat_id.0, "instagram", username)
+        .has_content_subscription(chat_id.0, source_type, source_id)
         .await
         && existing.is_active
     {
         let _ = bot
-            .send_message(chat_id, format!("You're already subscribed to @{}!", username))
+            .send_message(
+                chat_id,
+                format!("You're already subscribed to {}!", existing.display_name),
+            )
             .await;
         return;
     }
@@ -244,8 +438,8 @@ pub async fn show_subscribe_confirm(
         return;
     }
 
-    // Resolve the profile to validate it exists
-    let (display_name, _meta) = match watcher.resolve_source(username).await {
+    // Resolve the source to validate it exists
+    let (display_name, _meta) = match watcher.resolve_source(source_id).await {
         Ok(r) => r,
         Err(e) => {
             let _ = bot.send_message(chat_id, format!("Cannot subscribe: {}", e)).await;
@@ -253,37 +447,26 @@ pub async fn show_subscribe_confirm(
         }
     };
 
-    let default_mask = watcher.default_watch_mask();
-    let text = format!("Subscribe to {} updates?", display_name);
+    let text = format!("{} Subscribe to {} updates?", source_emoji(source_type), display_name);
 
-    let has_cookies = crate::download::cookies::load_instagram_cookie_header().is_some();
-    let effective_mask = if has_cookies { default_mask } else { 1 }; // Posts only without cookies
-
-    let mut toggle_row = vec![cb(
-        format!("{}Posts", if effective_mask & 1 != 0 { "✅ " } else { "☐ " }),
-        format!("cw:ptog:{}:1:{}", username, effective_mask),
-    )];
-
-    if has_cookies {
-        toggle_row.push(cb(
-            format!("{}Stories", if effective_mask & 2 != 0 { "✅ " } else { "☐ " }),
-            format!("cw:ptog:{}:2:{}", username, effective_mask),
-        ));
-    }
-
-    let keyboard = InlineKeyboardMarkup::new(vec![
-        toggle_row,
-        vec![
-            cb("✔ Confirm", format!("cw:ok:instagram:{}:{}", username, effective_mask)),
-            cb("✖ Cancel", "cw:cancel".to_string()),
-        ],
-    ]);
+    // Default mask limited to what this source offers (e.g. Posts only without cookies)
+    let available = available_content_types(watcher, source_id)
+        .iter()
+        .fold(0u32, |mask, (bit, _)| mask | bit);
+    let effective_mask = match watcher.default_watch_mask() & available {
+        0 => available,
+        mask => mask,
+    };
 
+    let source_ref = callback_source_ref(db_pool, shared_storage, source_id).await;
+    let keyboard = pending_keyboard(watcher, source_id, &source_ref, effective_mask);
     let _ = bot.send_message(chat_id, text).reply_markup(keyboard).await;
 }
 
 // ─── Callback handler (cw: prefix) ───
 
+const EXPIRED_BUTTON: &str = "This button has expired. Send /subscriptions <link> again.";
+
 /// Handle all `cw:` callbacks.
 pub async fn handle_subscription_callback(
     bot: &Bot,
@@ -304,15 +487,19 @@ pub async fn handle_subscription_callback(
     match callback {
         SubscriptionCallback::ConfirmSubscribe {
             source_type,
-            source_id,
+            source_id: source_ref,
             mask,
         } => {
+            let Some(source_id) = resolve_source_ref(&db_pool, &shared_storage, source_ref).await else {
+                let _ = bot.edit_message_text(chat_id, message_id, EXPIRED_BUTTON).await;
+                return;
+            };
             handle_confirm_subscribe(
                 bot,
                 chat_id,
                 message_id,
                 source_type,
-                source_id,
+                &source_id,
                 mask,
                 &db_pool,
                 &shared_storage,
@@ -324,23 +511,64 @@ pub async fn handle_subscription_callback(
             bot.try_delete(chat_id, message_id).await;
         }
         SubscriptionCallback::TogglePending {
-            username,
+            source_type,
+            source_id: source_ref,
             bit,
             current_mask,
         } => {
+            let Some(source_id) = resolve_source_ref(&db_pool, &shared_storage, source_ref).await else {
+                let _ = bot.edit_message_text(chat_id, message_id, EXPIRED_BUTTON).await;
+                return;
+            };
             let new_mask = current_mask ^ bit;
             // Don't allow mask=0
             let new_mask = if new_mask == 0 { bit } else { new_mask };
-            update_toggle_keyboard(bot, chat_id, message_id, username, new_mask).await;
+            update_toggle_keyboard(
+                bot,
+                chat_id,
+                message_id,
+                source_type,
+                &source_id,
+                source_ref,
+                new_mask,
+                registry,
+            )
+            .await;
         }
         SubscriptionCallback::Manage { sub_id } => {
-            show_manage_subscription(bot, chat_id, message_id, sub_id, &db_pool, &shared_storage).await;
+            show_manage_subscription(bot, chat_id, message_id, sub_id, &db_pool, &shared_storage, registry).await;
         }
         SubscriptionCallback::Unsub { sub_id } => {
             handle_unsubscribe(bot, chat_id, message_id, sub_id, &db_pool, &shared_storage).await;
         }
         SubscriptionCallback::ToggleExisting { sub_id, bit } => {
-            handle_toggle_content_type(bot, chat_id, message_id, sub_id, bit, &db_pool, &shared_storage).await;
+            handle_toggle_content_type(
+                bot,
+                chat_id,
+                message_id,
+                sub_id,
+                bit,
+                &db_pool,
+                &shared_storage,
+                registry,
+            )
+            .await;
+        }
+        SubscriptionCallback::AutoToggle { sub_id }
+        | SubscriptionCallback::AutoFormat { sub_id }
+        | SubscriptionCallback::AutoQuality { sub_id }
+        | SubscriptionCallback::AutoCap { sub_id } => {
+            handle_auto_download_setting(
+                bot,
+                chat_id,
+                message_id,
+                sub_id,
+                callback,
+                &db_pool,
+                &shared_storage,
+                registry,
+            )
+            .await;
         }
         SubscriptionCallback::List => {
             bot.try_delete(chat_id, message_id).await;
@@ -394,21 +622,10 @@ async fn handle_confirm_subscribe(
         .await
     {
         Ok(_id) => {
-            let types: Vec<&str> = {
-                let mut t = Vec::new();
-                if mask & 1 != 0 {
-                    t.push("Posts");
-                }
-                if mask & 2 != 0 {
-                    t.push("Stories");
-                }
-                t
-            };
-
             let text = format!(
                 "🔔 Subscribed to {} ({})\n\nYou'll be notified when new content appears.",
                 display_name,
-                types.join(" + "),
+                mask_labels(Some(watcher), source_id, mask),
             );
 
             let keyboard = InlineKeyboardMarkup::new(vec![vec![cb("📋 My Subscriptions", "cw:list".to_string())]]);
@@ -434,32 +651,23 @@ async fn handle_confirm_subscribe(
     }
 }
 
-async fn update_toggle_keyboard(bot: &Bot, chat_id: ChatId, message_id: MessageId, username: &str, new_mask: u32) {
-    let has_cookies = crate::download::cookies::load_instagram_cookie_header().is_some();
-
-    let mut toggle_row = vec![cb(
-        format!("{}Posts", if new_mask & 1 != 0 { "✅ " } else { "☐ " }),
-        format!("cw:ptog:{}:1:{}", username, new_mask),
-    )];
-
-    if has_cookies {
-        toggle_row.push(cb(
-            format!("{}Stories", if new_mask & 2 != 0 { "✅ " } else { "☐ " }),
-            format!("cw:ptog:{}:2:{}", username, new_mask),
-        ));
-    }
-
-    let keyboard = InlineKeyboardMarkup::new(vec![
-        toggle_row,
-        vec![
-            cb("✔ Confirm", format!("cw:ok:instagram:{}:{}", username, new_mask)),
-            cb("✖ Cancel", "cw:cancel".to_string()),
-        ],
-    ]);
+async fn update_toggle_keyboard(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    source_type: &str,
+    source_id: &str,
+    source_ref: &str,
+    new_mask: u32,
+    registry: &WatcherRegistry,
+) {
+    let Some(watcher) = registry.get(source_type) else {
+        return;
+    };
 
     let _ = bot
         .edit_message_reply_markup(chat_id, message_id)
-        .reply_markup(keyboard)
+        .reply_markup(pending_keyboard(watcher, source_id, source_ref, new_mask))
         .await;
 }
 
@@ -470,6 +678,7 @@ async fn show_manage_subscription(
     sub_id: i64,
     db_pool: &Arc<DbPool>,
     shared_storage: &Arc<SharedStorage>,
+    registry: &WatcherRegistry,
 ) {
     let _ = db_pool;
     let sub = match shared_storage.get_content_subscription(sub_id).await {
@@ -492,9 +701,25 @@ async fn show_manage_subscription(
         return;
     }
 
-    let source_emoji = match sub.source_type.as_str() {
-        "instagram" => "📸",
-        _ => "🔗",
+    let source_emoji = source_emoji(&sub.source_type);
+    let watcher = registry.get(&sub.source_type);
+
+    let auto_line = if sub.auto_download {
+        let mut line = format!(
+            "On — {}, {}, up to {}/day",
+            sub.auto_format.to_uppercase(),
+            sub.auto_quality.as_deref().unwrap_or("default quality"),
+            sub.daily_cap,
+        );
+        if sub.filters.auto_mask != 0 {
+            line.push_str(&format!(
+                " ({} only)",
+                mask_labels(watcher, &sub.source_id, sub.filters.auto_mask)
+            ));
+        }
+        line
+    } else {
+        "Off".to_string()
     };
 
     let text = format!(
@@ -502,31 +727,56 @@ async fn show_manage_subscription(
          Source: {}\n\
          Status: {}\n\
          Last check: {}\n\
-         Errors: {}",
+         Errors: {}\n\
+         Auto-download: {}\n\
+         Filters: {}\n\n\
+         Set filters: /subscriptions filter {} include=word,word exclude=word min=5 max=90 types=all\n\
+         (durations in minutes or HH:MM:SS, an empty value clears, \"reset\" clears all)",
         source_emoji,
         sub.display_name,
         sub.source_type,
         if sub.is_active { "Active" } else { "Inactive" },
         sub.last_checked_at.as_deref().unwrap_or("never"),
         sub.consecutive_errors,
+        auto_line,
+        describe_filters(&sub.filters),
+        sub.id,
     );
 
-    let keyboard = InlineKeyboardMarkup::new(vec![
-        vec![
+    let toggle_row: Vec<InlineKeyboardButton> = watcher
+        .map(|w| w.content_types_for(&sub.source_id))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(bit, label)| {
             cb(
-                format!("{}Posts", if sub.watch_mask & 1 != 0 { "✅ " } else { "☐ " }),
-                format!("cw:tog:{}:1", sub_id),
-            ),
+                toggle_label(label, sub.watch_mask & bit != 0),
+                format!("cw:tog:{}:{}", sub_id, bit),
+            )
+        })
+        .collect();
+
+    let mut rows = vec![
+        toggle_row,
+        vec![cb(
+            toggle_label("⬇️ Auto-download", sub.auto_download),
+            format!("cw:auto:{}", sub_id),
+        )],
+    ];
+    if sub.auto_download {
+        rows.push(vec![
+            cb(sub.auto_format.to_uppercase(), format!("cw:afmt:{}", sub_id)),
             cb(
-                format!("{}Stories", if sub.watch_mask & 2 != 0 { "✅ " } else { "☐ " }),
-                format!("cw:tog:{}:2", sub_id),
+                sub.auto_quality.as_deref().unwrap_or("Default quality"),
+                format!("cw:aq:{}", sub_id),
             ),
-        ],
-        vec![
-            cb("🔕 Unsubscribe", format!("cw:unsub:{}", sub_id)),
-            cb("🔙 Back", "cw:list".to_string()),
-        ],
+            cb(format!("{}/day", sub.daily_cap), format!("cw:cap:{}", sub_id)),
+        ]);
+    }
+    rows.push(vec![
+        cb("🔕 Unsubscribe", format!("cw:unsub:{}", sub_id)),
+        cb("🔙 Back", "cw:list".to_string()),
     ]);
+    let keyboard = InlineKeyboardMarkup::new(rows);
 
     let _ = bot
         .edit_message_text(chat_id, message_id, text)
@@ -584,6 +834,7 @@ async fn handle_toggle_content_type(
     bit: u32,
     db_pool: &Arc<DbPool>,
     shared_storage: &Arc<SharedStorage>,
+    registry: &WatcherRegistry,
 ) {
     let _ = db_pool;
     let sub = match shared_storage.get_content_subscription(sub_id).await {
@@ -611,7 +862,106 @@ async fn handle_toggle_content_type(
     }
 
     // Refresh the manage view
-    show_manage_subscription(bot, chat_id, message_id, sub_id, db_pool, shared_storage).await;
+    show_manage_subscription(bot, chat_id, message_id, sub_id, db_pool, shared_storage, registry).await;
+}
+
+/// The step after `current` in `steps`, wrapping around.
+fn next_step<T: PartialEq + Copy>(steps: &[T], current: T) -> T {
+    let next = steps.iter().position(|step| *step == current).map_or(0, |i| i + 1);
+    steps[next % steps.len()]
+}
+
+/// Toggle auto-download or cycle its format, quality or daily cap.
+async fn handle_auto_download_setting(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    sub_id: i64,
+    callback: SubscriptionCallback<'_>,
+    db_pool: &Arc<DbPool>,
+    shared_storage: &Arc<SharedStorage>,
+    registry: &WatcherRegistry,
+) {
+    let sub = match shared_storage.get_content_subscription(sub_id).await {
+        Ok(Some(s)) => s,
+        _ => return,
+    };
+
+    if sub.user_id != chat_id.0 {
+        log::warn!(
+            "User {} attempted to access subscription {} owned by {}",
+            chat_id.0,
+            sub_id,
+            sub.user_id
+        );
+        return;
+    }
+
+    let mut enabled = sub.auto_download;
+    let mut format = sub.auto_format.as_str();
+    let mut quality = sub.auto_quality.as_deref();
+    let mut daily_cap = sub.daily_cap;
+    match callback {
+        SubscriptionCallback::AutoToggle { .. } => enabled = !enabled,
+        SubscriptionCallback::AutoFormat { .. } => {
+            format = next_step(AUTO_FORMATS, format);
+            quality = None;
+        }
+        SubscriptionCallback::AutoQuality { .. } => quality = next_step(quality_steps(format), quality),
+        SubscriptionCallback::AutoCap { .. } => daily_cap = next_step(DAILY_CAP_STEPS, daily_cap),
+        _ => return,
+    }
+
+    if let Err(e) = shared_storage
+        .update_content_auto_download(sub_id, enabled, format, quality, daily_cap)
+        .await
+    {
+        log::error!("Failed to update auto-download settings: {}", e);
+        return;
+    }
+
+    show_manage_subscription(bot, chat_id, message_id, sub_id, db_pool, shared_storage, registry).await;
+}
+
+/// Handle `/subscriptions filter <id> key=value…`.
+async fn handle_filter_command(bot: &Bot, chat_id: ChatId, args: &[&str], shared_storage: &SharedStorage) {
+    let sub = match args.first().and_then(|id| id.parse::<i64>().ok()) {
+        Some(sub_id) => shared_storage.get_content_subscription(sub_id).await.ok().flatten(),
+        None => None,
+    };
+    let Some(sub) = sub.filter(|sub| sub.user_id == chat_id.0 && sub.is_active) else {
+        let _ = bot
+            .send_message(
+                chat_id,
+                "Usage: /subscriptions filter <id> include=word,word exclude=word min=5 max=90 types=all\n\
+                 The id is shown in the subscription's Manage view.",
+            )
+            .await;
+        return;
+    };
+
+    let registry = WatcherRegistry::default_registry();
+    let content_types = registry
+        .get(&sub.source_type)
+        .map(|w| w.content_types_for(&sub.source_id))
+        .unwrap_or_default();
+    let filters = match apply_filter_args(&sub.filters, &args[1..], &content_types) {
+        Ok(filters) => filters,
+        Err(e) => {
+            let _ = bot.send_message(chat_id, format!("❌ {}", e)).await;
+            return;
+        }
+    };
+
+    let text = match shared_storage.update_content_filters(sub.id, &filters).await {
+        Ok(()) => format!("✅ Filters for {}: {}", sub.display_name, describe_filters(&filters)),
+        Err(e) => {
+            log::error!("Failed to update subscription filters: {}", e);
+            "Failed to save filters".to_string()
+        }
+    };
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![cb("⚙️ Manage", format!("cw:manage:{}", sub.id))]]);
+    let _ = bot.send_message(chat_id, text).reply_markup(keyboard).await;
 }
 
 // ─── Notification dispatcher ───
@@ -710,7 +1060,7 @@ async fn send_story_notification(
         format!("cw:unsub:{}", notification.subscription_id),
     )]]);
 
-    let caption = format!("📱 {}", notification.update.description);
+    let caption = format!("📱 {}{}", notification.update.description, auto_note(notification));
 
     // Download all media in parallel
     let futures: Vec<_> = media
@@ -820,8 +1170,10 @@ async fn send_post_notification(
     )]]);
 
     let caption = format!(
-        "📸 New post by {}\n{}",
-        notification.display_name, notification.update.url
+        "📸 New post by {}\n{}{}",
+        notification.display_name,
+        notification.update.url,
+        auto_note(notification)
     );
 
     // Download media files in parallel
@@ -909,6 +1261,213 @@ async fn send_post_notification(
     Ok(())
 }
 
+/// Send a YouTube notification with one-tap MP3/MP4 buttons. The buttons use
+/// the regular `dl:` callbacks, so a tap goes through the normal download queue.
+async fn send_youtube_notification(
+    bot: &Bot,
+    db_pool: &DbPool,
+    shared_storage: &SharedStorage,
+    chat_id: ChatId,
+    notification: &WatchNotification,
+) -> Result<(), teloxide::RequestError> {
+    let update = &notification.update;
+    let url_id = crate::storage::cache::store_url(db_pool, Some(shared_storage), &update.url).await;
+
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![
+            cb("🎵 MP3", format!("dl:mp3:{}", url_id)),
+            cb("🎬 MP4", format!("dl:mp4:{}", url_id)),
+        ],
+        vec![cb(
+            "🔕 Unsubscribe",
+            format!("cw:unsub:{}", notification.subscription_id),
+        )],
+    ]);
+
+    let heading = match update.content_type.as_str() {
+        "short" => "New short",
+        "live" => "🔴 Livestream",
+        "playlist" => "Added to playlist",
+        _ => "New video",
+    };
+    let text = format!(
+        "▶️ {} — {}\n{}\n{}{}",
+        heading,
+        notification.display_name,
+        update.description,
+        update.url,
+        auto_note(notification)
+    );
+
+    bot.send_message(chat_id, text).reply_markup(keyboard).await.map(|_| ())
+}
+
+/// Send a new-episode notification for a feed enclosure. The episode metadata
+/// is saved first so the download keeps its title, show, cover and chapters;
+/// the buttons are the regular `dl:` callbacks.
+async fn send_rss_notification(
+    bot: &Bot,
+    db_pool: &DbPool,
+    shared_storage: &SharedStorage,
+    chat_id: ChatId,
+    notification: &WatchNotification,
+) -> Result<(), teloxide::RequestError> {
+    let update = &notification.update;
+    let (Some(feed), Some(episode)) = (&update.feed, feed_episode(update)) else {
+        return send_text_notification(bot, chat_id, notification).await;
+    };
+
+    if let Err(e) = shared_storage.upsert_feed_episode(&episode).await {
+        log::warn!("Failed to save feed episode {}: {}", update.url, e);
+    }
+
+    let url_id = crate::storage::cache::store_url(db_pool, Some(shared_storage), &update.url).await;
+    let mut download_row = vec![cb("🎵 MP3", format!("dl:mp3:{}", url_id))];
+    if feed.is_video {
+        download_row.push(cb("🎬 MP4", format!("dl:mp4:{}", url_id)));
+    }
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        download_row,
+        vec![cb(
+            "🔕 Unsubscribe",
+            format!("cw:unsub:{}", notification.subscription_id),
+        )],
+    ]);
+
+    let heading = if feed.is_video { "New video" } else { "New episode" };
+    let text = format!(
+        "🎙 {} — {}\n{}{}",
+        heading,
+        notification.display_name,
+        update.description,
+        auto_note(notification)
+    );
+
+    bot.send_message(chat_id, text).reply_markup(keyboard).await.map(|_| ())
+}
+
+/// Deliver a new channel post: forward it when the bot can see the channel,
+/// otherwise re-send the file fetched through MTProto. Falls back to a text
+/// notification with the post link.
+async fn send_channel_notification(
+    bot: &Bot,
+    chat_id: ChatId,
+    notification: &WatchNotification,
+) -> Result<(), teloxide::RequestError> {
+    let Some(post) = &notification.update.channel_post else {
+        return send_text_notification(bot, chat_id, notification).await;
+    };
+
+    let from = Recipient::ChannelUsername(format!("@{}", post.username));
+    match bot.forward_message(chat_id, from, MessageId(post.message_id)).await {
+        Ok(_) => {
+            let text = format!("📣 {}{}", notification.display_name, auto_note(notification));
+            let keyboard = InlineKeyboardMarkup::new(vec![vec![cb(
+                "🔕 Unsubscribe",
+                format!("cw:unsub:{}", notification.subscription_id),
+            )]]);
+            return bot.send_message(chat_id, text).reply_markup(keyboard).await.map(|_| ());
+        }
+        Err(e) => log::debug!("Forward of {} failed, re-sending: {}", notification.update.url, e),
+    }
+
+    match resend_channel_post(bot, chat_id, notification).await {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            log::warn!("Re-send of {} failed: {}", notification.update.url, e);
+            send_text_notification(bot, chat_id, notification).await
+        }
+    }
+}
+
+/// Download a channel post's media through MTProto and upload it to `chat_id`.
+async fn resend_channel_post(bot: &Bot, chat_id: ChatId, notification: &WatchNotification) -> anyhow::Result<()> {
+    use doracore::core::upload_limits::{UploadKind, UploadLimits};
+
+    let post = notification
+        .update
+        .channel_post
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("not a channel post"))?;
+    let kind = if post.is_video {
+        UploadKind::Video
+    } else {
+        UploadKind::Audio
+    };
+    let max_size = UploadLimits::from_env().cap(kind);
+    if u64::try_from(post.size_bytes).unwrap_or(u64::MAX) > max_size {
+        anyhow::bail!("{} bytes exceeds the upload limit", post.size_bytes);
+    }
+
+    let downloader = MtProtoDownloader::shared().await?;
+    let channel = crate::mtproto::ChannelInfo {
+        peer: crate::mtproto::PeerInfo {
+            peer_type: crate::mtproto::PeerType::Channel,
+            id: post.channel_id,
+        },
+        access_hash: post.access_hash,
+        username: post.username.clone(),
+        title: notification.display_name.clone(),
+    };
+    let media = downloader
+        .get_channel_messages(&channel, &[post.message_id])
+        .await?
+        .into_iter()
+        .find_map(|message| message.media)
+        .ok_or_else(|| anyhow::anyhow!("post has no media"))?;
+
+    let ext = if post.is_video { "mp4" } else { "mp3" };
+    let temp_path = std::env::temp_dir().join(format!("dora_sub_{}_{}.{}", std::process::id(), next_temp_id(), ext));
+    let file = match downloader.download_media(&media, &temp_path).await {
+        Ok(_) => {
+            let file = InputFile::file(&temp_path);
+            match &media.filename {
+                Some(name) => file.file_name(name.clone()),
+                None => file,
+            }
+        }
+        Err(e) => {
+            fs_err::tokio::remove_file(&temp_path).await.ok();
+            return Err(e.into());
+        }
+    };
+
+    let caption = format!(
+        "📣 {}\n{}\n{}{}",
+        notification.display_name,
+        notification.update.description,
+        notification.update.url,
+        auto_note(notification)
+    );
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![cb(
+        "🔕 Unsubscribe",
+        format!("cw:unsub:{}", notification.subscription_id),
+    )]]);
+    let result = if post.is_video {
+        bot.send_video(chat_id, file)
+            .caption(caption)
+            .reply_markup(keyboard)
+            .await
+            .map(|_| ())
+    } else {
+        bot.send_audio(chat_id, file)
+            .caption(caption)
+            .reply_markup(keyboard)
+            .await
+            .map(|_| ())
+    };
+    fs_err::tokio::remove_file(&temp_path).await.ok();
+    result.map_err(Into::into)
+}
+
+/// Trailing line telling the user the item is already being downloaded.
+fn auto_note(notification: &WatchNotification) -> String {
+    notification
+        .auto_queued
+        .map(|format| format!("\n⬇️ Auto-downloading as {}", format.to_uppercase()))
+        .unwrap_or_default()
+}
+
 /// Fallback: send a plain text notification (original behavior).
 async fn send_text_notification(
     bot: &Bot,
@@ -920,7 +1479,7 @@ async fn send_text_notification(
         format!("cw:unsub:{}", notification.subscription_id),
     )]]);
 
-    let text = match notification.update.content_type.as_str() {
+    let mut text = match notification.update.content_type.as_str() {
         "post" => format!(
             "📸 New post by {}\n{}",
             notification.display_name, notification.update.url
@@ -928,6 +1487,7 @@ async fn send_text_notification(
         "story" => format!("📱 {}", notification.update.description),
         _ => notification.update.description.clone(),
     };
+    text.push_str(&auto_note(notification));
 
     bot.send_message(chat_id, &text)
         .reply_markup(keyboard)
@@ -953,6 +1513,15 @@ pub fn start_notification_dispatcher(
             let chat_id = ChatId(notification.user_id);
 
             let result = match notification.update.content_type.as_str() {
+                _ if notification.source_type == "youtube" => {
+                    send_youtube_notification(&bot, &db_pool, &shared_storage, chat_id, &notification).await
+                }
+                _ if notification.source_type == "rss" => {
+                    send_rss_notification(&bot, &db_pool, &shared_storage, chat_id, &notification).await
+                }
+                _ if notification.source_type == "telegram_channel" => {
+                    send_channel_notification(&bot, chat_id, &notification).await
+                }
                 "story" => send_story_notification(&bot, &http_client, chat_id, &notification).await,
                 "post" => send_post_notification(&bot, &http_client, &ig_source, chat_id, &notification).await,
                 _ => send_text_notification(&bot, chat_id, &notification).await,
@@ -993,7 +1562,7 @@ fn handle_send_error(
 
 #[cfg(test)]
 mod tests {
-    use super::{SubscriptionCallback, parse_subscription_callback};
+    use super::{SubscriptionCallback, next_step, parse_subscription_callback, parse_subscription_target};
 
     #[test]
     fn parses_confirm_subscribe_callback() {
@@ -1012,13 +1581,95 @@ mod tests {
         assert_eq!(
             parse_subscription_callback("cw:ptog:cristiano:not-a-bit:not-a-mask"),
             Some(SubscriptionCallback::TogglePending {
-                username: "cristiano",
+                source_type: "instagram",
+                source_id: "cristiano",
                 bit: 0,
                 current_mask: 3,
             })
         );
     }
 
+    #[test]
+    fn parses_pending_toggle_with_source_type() {
+        assert_eq!(
+            parse_subscription_callback("cw:ptog:youtube:list=PLabc:8:9"),
+            Some(SubscriptionCallback::TogglePending {
+                source_type: "youtube",
+                source_id: "list=PLabc",
+                bit: 8,
+                current_mask: 9,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_auto_download_callbacks() {
+        assert_eq!(
+            parse_subscription_callback("cw:auto:12"),
+            Some(SubscriptionCallback::AutoToggle { sub_id: 12 })
+        );
+        assert_eq!(
+            parse_subscription_callback("cw:afmt:12"),
+            Some(SubscriptionCallback::AutoFormat { sub_id: 12 })
+        );
+        assert_eq!(
+            parse_subscription_callback("cw:aq:12"),
+            Some(SubscriptionCallback::AutoQuality { sub_id: 12 })
+        );
+        assert_eq!(
+            parse_subscription_callback("cw:cap:12"),
+            Some(SubscriptionCallback::AutoCap { sub_id: 12 })
+        );
+        assert_eq!(parse_subscription_callback("cw:cap:x"), None);
+    }
+
+    #[test]
+    fn next_step_wraps_and_recovers_unknown_values() {
+        assert_eq!(next_step(&[1, 3, 5], 3), 5);
+        assert_eq!(next_step(&[1, 3, 5], 5), 1);
+        assert_eq!(next_step(&[1, 3, 5], 7), 1);
+    }
+
+    #[test]
+    fn subscription_targets_from_links() {
+        assert_eq!(
+            parse_subscription_target("https://www.youtube.com/@mkbhd"),
+            Some(("youtube", "@mkbhd".to_string()))
+        );
+        assert_eq!(
+            parse_subscription_target("https://youtube.com/playlist?list=PLabc"),
+            Some(("youtube", "list=PLabc".to_string()))
+        );
+        assert_eq!(
+            parse_subscription_target("https://www.instagram.com/cristiano/"),
+            Some(("instagram", "cristiano".to_string()))
+        );
+        assert_eq!(parse_subscription_target("https://youtu.be/dQw4w9WgXcQ"), None);
+        assert_eq!(
+            parse_subscription_target("https://feeds.example.com/show.xml#latest"),
+            Some(("rss", "https://feeds.example.com/show.xml".to_string()))
+        );
+        assert_eq!(parse_subscription_target("https://www.instagram.com/p/ABC123/"), None);
+        assert_eq!(
+            parse_subscription_target("https://t.me/DoraSound/125"),
+            Some(("telegram_channel", "dorasound".to_string()))
+        );
+        assert_eq!(parse_subscription_target("https://t.me/joinchat/AAAAAE"), None);
+    }
+
+    #[test]
+    fn parses_pending_toggle_with_cached_source_ref() {
+        assert_eq!(
+            parse_subscription_callback("cw:ptog:rss:~0123456789ab:2:3"),
+            Some(SubscriptionCallback::TogglePending {
+                source_type: "rss",
+                source_id: "~0123456789ab",
+                bit: 2,
+                current_mask: 3,
+            })
+        );
+    }
+
     #[test]
     fn rejects_callbacks_with_invalid_numeric_ids() {
         assert_eq!(parse_subscription_callback("cw:manage:not-a-number"), None);
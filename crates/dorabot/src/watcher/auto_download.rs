@@ -0,0 +1,203 @@
+//! Auto-download for content subscriptions: new items that pass a
+//! subscription's filters are queued as regular low-priority download tasks.
+//!
+//! Whether delivery is quiet is decided by the worker from the user's silent
+//! downloads setting, which also records the result in the silent digest.
+
+use crate::core::credits;
+use crate::download::queue::{AddTaskOutcome, DownloadFormat, DownloadQueue, DownloadTask, TaskPriority};
+use crate::storage::SharedStorage;
+use crate::storage::db::DbPool;
+use crate::storage::shared::ContentSubscriptionRecord;
+use crate::watcher::rss::feed_episode;
+use crate::watcher::traits::WatchUpdate;
+use std::sync::Arc;
+use teloxide::types::ChatId;
+
+/// Formats offered for auto-download.
+pub const AUTO_FORMATS: &[&str] = &["mp3", "mp4"];
+
+/// Daily cap choices cycled in the manage view.
+pub const DAILY_CAP_STEPS: &[u32] = &[1, 3, 5, 10, 20];
+
+/// Quality choices per format cycled in the manage view; `None` = user default.
+pub fn quality_steps(format: &str) -> &'static [Option<&'static str>] {
+    match format {
+        "mp4" => &[None, Some("480p"), Some("720p"), Some("1080p")],
+        _ => &[None, Some("128k"), Some("192k"), Some("320k")],
+    }
+}
+
+/// Audio-only enclosures can't be fetched as video.
+fn effective_format(sub: &ContentSubscriptionRecord, update: &WatchUpdate) -> &'static str {
+    let audio_only = update.feed.as_ref().is_some_and(|feed| !feed.is_video);
+    if sub.auto_format == "mp4" && !audio_only {
+        "mp4"
+    } else {
+        "mp3"
+    }
+}
+
+/// Queue `update` for `sub` if today's cap allows. Returns the format it was
+/// queued in; `None` when the cap is reached or the queue refused the task
+/// (duplicate, full, not enough credits), so the caller falls back to a
+/// plain notification. A refused task gives its cap slot back.
+pub async fn enqueue(
+    shared_storage: &Arc<SharedStorage>,
+    db_pool: &Arc<DbPool>,
+    download_queue: &DownloadQueue,
+    sub: &ContentSubscriptionRecord,
+    update: &WatchUpdate,
+) -> Option<&'static str> {
+    match shared_storage.try_reserve_content_auto_download(sub.id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            log::debug!("Auto-download cap reached for subscription {}", sub.id);
+            return None;
+        }
+        Err(e) => {
+            log::warn!("Auto-download reservation failed for subscription {}: {}", sub.id, e);
+            return None;
+        }
+    }
+
+    if let Some(episode) = feed_episode(update)
+        && let Err(e) = shared_storage.upsert_feed_episode(&episode).await
+    {
+        log::warn!("Failed to save feed episode {}: {}", update.url, e);
+    }
+
+    let format = effective_format(sub, update);
+    // A quality picked for the other format (video → audio fallback) doesn't apply
+    let quality = sub.auto_quality.clone().filter(|_| format == sub.auto_format);
+    let is_video = format == "mp4";
+    let (video_quality, audio_bitrate) = if is_video {
+        let quality = match quality {
+            Some(quality) => quality,
+            None => shared_storage
+                .get_user_video_quality(sub.user_id)
+                .await
+                .unwrap_or_else(|_| "best".to_string()),
+        };
+        (Some(quality), None)
+    } else {
+        let bitrate = match quality {
+            Some(bitrate) => bitrate,
+            None => shared_storage
+                .get_user_audio_bitrate(sub.user_id)
+                .await
+                .unwrap_or_else(|_| "320k".to_string()),
+        };
+        (None, Some(bitrate))
+    };
+
+    let mut task = DownloadTask::builder()
+        .url(update.url.clone())
+        .chat_id(ChatId(sub.user_id))
+        .is_video(is_video)
+        .format(if is_video {
+            DownloadFormat::Mp4
+        } else {
+            DownloadFormat::Mp3
+        })
+        .maybe_video_quality(video_quality)
+        .maybe_audio_bitrate(audio_bitrate)
+        .priority(TaskPriority::Low)
+        .build();
+    task.credit_charge = credits::charge_for_user(
+        shared_storage,
+        sub.user_id,
+        credits::premium_action(task.video_quality.as_deref(), None),
+    )
+    .await;
+
+    match download_queue.add_task(task, Some(Arc::clone(db_pool))).await {
+        AddTaskOutcome::Queued => {
+            log::info!(
+                "Auto-queued {} from subscription {} for user {}",
+                update.url,
+                sub.id,
+                sub.user_id
+            );
+            Some(format)
+        }
+        outcome => {
+            log::info!(
+                "Auto-download of {} for subscription {} not queued: {:?}",
+                update.url,
+                sub.id,
+                outcome
+            );
+            if let Err(e) = shared_storage.release_content_auto_download(sub.id).await {
+                log::warn!(
+                    "Failed to release auto-download slot for subscription {}: {}",
+                    sub.id,
+                    e
+                );
+            }
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::shared::ContentFilters;
+    use crate::watcher::traits::FeedMedia;
+
+    fn sub(auto_format: &str) -> ContentSubscriptionRecord {
+        ContentSubscriptionRecord {
+            id: 1,
+            user_id: 7,
+            source_type: "rss".to_string(),
+            source_id: "https://example.com/feed.xml".to_string(),
+            display_name: "Show".to_string(),
+            watch_mask: 3,
+            last_seen_state: None,
+            source_meta: None,
+            is_active: true,
+            last_checked_at: None,
+            last_error: None,
+            consecutive_errors: 0,
+            created_at: String::new(),
+            updated_at: String::new(),
+            auto_download: true,
+            auto_format: auto_format.to_string(),
+            auto_quality: None,
+            filters: ContentFilters::default(),
+            daily_cap: 5,
+        }
+    }
+
+    fn update(is_video: Option<bool>) -> WatchUpdate {
+        WatchUpdate {
+            content_type: "episode".to_string(),
+            url: "https://cdn.example.com/ep1.mp3".to_string(),
+            description: "Episode 1".to_string(),
+            title: Some("Episode 1".to_string()),
+            duration_secs: None,
+            shortcode: None,
+            media: vec![],
+            feed: is_video.map(|is_video| FeedMedia {
+                title: "Episode 1".to_string(),
+                podcast: "Show".to_string(),
+                feed_url: "https://example.com/feed.xml".to_string(),
+                cover_url: None,
+                duration_secs: None,
+                chapters: vec![],
+                chapters_url: None,
+                is_video,
+            }),
+            channel_post: None,
+        }
+    }
+
+    #[test]
+    fn audio_enclosures_fall_back_to_mp3() {
+        assert_eq!(effective_format(&sub("mp4"), &update(Some(false))), "mp3");
+        assert_eq!(effective_format(&sub("mp4"), &update(Some(true))), "mp4");
+        assert_eq!(effective_format(&sub("mp4"), &update(None)), "mp4");
+        assert_eq!(effective_format(&sub("mp3"), &update(Some(true))), "mp3");
+    }
+}
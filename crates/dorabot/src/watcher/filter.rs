@@ -0,0 +1,195 @@
+//! Per-subscription item filters (title keywords, duration bounds) and the
+//! auto-download content-type selection, stored as V54
+//! `content_subscriptions.filters` JSON.
+
+use crate::storage::shared::{ContentFilters, ContentSubscriptionRecord};
+use crate::watcher::traits::WatchUpdate;
+
+/// Whether `update` passes the subscription's keyword and duration filters.
+/// Items with an unknown duration pass the duration bounds.
+pub fn matches_filters(filters: &ContentFilters, update: &WatchUpdate) -> bool {
+    let title = update.title.as_deref().unwrap_or(&update.description).to_lowercase();
+    let contains = |keyword: &String| title.contains(&keyword.to_lowercase());
+
+    if !filters.include.is_empty() && !filters.include.iter().any(contains) {
+        return false;
+    }
+    if filters.exclude.iter().any(contains) {
+        return false;
+    }
+    match update.duration_secs {
+        Some(secs) => {
+            filters.min_duration_secs.is_none_or(|min| secs >= min)
+                && filters.max_duration_secs.is_none_or(|max| secs <= max)
+        }
+        None => true,
+    }
+}
+
+/// Whether an item of content-type `bit` should be queued for `sub`.
+pub fn wants_auto_download(sub: &ContentSubscriptionRecord, bit: u32) -> bool {
+    sub.auto_download && (sub.filters.auto_mask == 0 || bit == 0 || sub.filters.auto_mask & bit != 0)
+}
+
+/// Apply `/subscriptions filter` arguments (`include=a,b exclude=c min=5
+/// max=1:30:00 types=videos`, or `reset`) on top of `current`. Durations are
+/// minutes or `MM:SS` / `HH:MM:SS`; an empty value clears the field.
+/// `content_types` are the source's `(bit, label)` pairs for `types=`.
+pub fn apply_filter_args(
+    current: &ContentFilters,
+    args: &[&str],
+    content_types: &[(u32, &str)],
+) -> Result<ContentFilters, String> {
+    let mut filters = current.clone();
+    for arg in args {
+        if arg.eq_ignore_ascii_case("reset") {
+            filters = ContentFilters::default();
+            continue;
+        }
+        let (key, value) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("Expected key=value, got \"{}\"", arg))?;
+        match key.to_ascii_lowercase().as_str() {
+            "include" => filters.include = parse_keywords(value),
+            "exclude" => filters.exclude = parse_keywords(value),
+            "min" => filters.min_duration_secs = parse_duration(value)?,
+            "max" => filters.max_duration_secs = parse_duration(value)?,
+            "types" => filters.auto_mask = parse_types(value, content_types)?,
+            _ => return Err(format!("Unknown filter \"{}\"", key)),
+        }
+    }
+    if let (Some(min), Some(max)) = (filters.min_duration_secs, filters.max_duration_secs)
+        && min > max
+    {
+        return Err("min is longer than max".to_string());
+    }
+    Ok(filters)
+}
+
+fn parse_keywords(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|keyword| keyword.trim().to_string())
+        .filter(|keyword| !keyword.is_empty())
+        .collect()
+}
+
+fn parse_duration(value: &str) -> Result<Option<u64>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let secs = match value.parse::<u64>() {
+        Ok(minutes) => Some(minutes * 60),
+        Err(_) => crate::timestamps::parse_timestamp_to_secs(value).and_then(|secs| u64::try_from(secs).ok()),
+    };
+    secs.map(Some)
+        .ok_or_else(|| format!("Bad duration \"{}\" (minutes or HH:MM:SS)", value))
+}
+
+fn parse_types(value: &str, content_types: &[(u32, &str)]) -> Result<u32, String> {
+    if value.is_empty() || value.eq_ignore_ascii_case("all") {
+        return Ok(0);
+    }
+    value.split(',').try_fold(0, |mask, name| {
+        let name = name.trim();
+        content_types
+            .iter()
+            .find(|(_, label)| label.eq_ignore_ascii_case(name))
+            .map(|(bit, _)| mask | bit)
+            .ok_or_else(|| format!("Unknown content type \"{}\"", name))
+    })
+}
+
+/// One-line summary for the manage view, e.g. `+live −trailer · ≥ 5:00`.
+pub fn describe_filters(filters: &ContentFilters) -> String {
+    let mut parts = Vec::new();
+    if !filters.include.is_empty() {
+        parts.push(format!("+{}", filters.include.join(" +")));
+    }
+    if !filters.exclude.is_empty() {
+        parts.push(format!("−{}", filters.exclude.join(" −")));
+    }
+    let clock = crate::download::playlist::format_duration;
+    match (filters.min_duration_secs, filters.max_duration_secs) {
+        (Some(min), Some(max)) => parts.push(format!("{}–{}", clock(min), clock(max))),
+        (Some(min), None) => parts.push(format!("≥ {}", clock(min))),
+        (None, Some(max)) => parts.push(format!("≤ {}", clock(max))),
+        (None, None) => {}
+    }
+    if parts.is_empty() {
+        "none".to_string()
+    } else {
+        parts.join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(title: &str, duration_secs: Option<u64>) -> WatchUpdate {
+        WatchUpdate {
+            content_type: "video".to_string(),
+            url: "https://www.youtube.com/watch?v=abc".to_string(),
+            description: title.to_string(),
+            title: Some(title.to_string()),
+            duration_secs,
+            shortcode: None,
+            media: vec![],
+            feed: None,
+            channel_post: None,
+        }
+    }
+
+    #[test]
+    fn keyword_filters_are_case_insensitive() {
+        let filters = ContentFilters {
+            include: vec!["Live".to_string(), "session".to_string()],
+            exclude: vec!["teaser".to_string()],
+            ..Default::default()
+        };
+        assert!(matches_filters(&filters, &update("LIVE at Wembley", None)));
+        assert!(matches_filters(&filters, &update("Studio Session #4", None)));
+        assert!(!matches_filters(&filters, &update("Live teaser", None)));
+        assert!(!matches_filters(&filters, &update("New single", None)));
+    }
+
+    #[test]
+    fn duration_bounds_skip_unknown_durations() {
+        let filters = ContentFilters {
+            min_duration_secs: Some(120),
+            max_duration_secs: Some(3600),
+            ..Default::default()
+        };
+        assert!(!matches_filters(&filters, &update("short", Some(59))));
+        assert!(matches_filters(&filters, &update("episode", Some(1800))));
+        assert!(!matches_filters(&filters, &update("stream", Some(4 * 3600))));
+        assert!(matches_filters(&filters, &update("unknown", None)));
+    }
+
+    #[test]
+    fn filter_args_update_and_clear_fields() {
+        let types = [(1, "Videos"), (2, "Shorts")];
+        let filters = apply_filter_args(
+            &ContentFilters::default(),
+            &["include=live,acoustic", "min=5", "max=1:30:00", "types=videos"],
+            &types,
+        )
+        .unwrap();
+        assert_eq!(filters.include, vec!["live", "acoustic"]);
+        assert_eq!(filters.min_duration_secs, Some(300));
+        assert_eq!(filters.max_duration_secs, Some(5400));
+        assert_eq!(filters.auto_mask, 1);
+
+        let cleared = apply_filter_args(&filters, &["include=", "max=", "types=all"], &types).unwrap();
+        assert!(cleared.include.is_empty());
+        assert_eq!(cleared.max_duration_secs, None);
+        assert_eq!(cleared.min_duration_secs, Some(300));
+        assert_eq!(cleared.auto_mask, 0);
+
+        assert!(apply_filter_args(&filters, &["reset"], &types).unwrap().is_empty());
+        assert!(apply_filter_args(&filters, &["min=soon"], &types).is_err());
+        assert!(apply_filter_args(&filters, &["types=stories"], &types).is_err());
+        assert!(apply_filter_args(&ContentFilters::default(), &["min=10", "max=5"], &types).is_err());
+    }
+}
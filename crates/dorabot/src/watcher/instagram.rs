@@ -47,6 +47,14 @@ impl ContentWatcher for InstagramWatcher {
         MASK_POSTS | MASK_STORIES
     }
 
+    fn content_type_mask(&self, content_type: &str) -> u32 {
+        match content_type {
+            "post" => MASK_POSTS,
+            "story" => MASK_STORIES,
+            _ => 0,
+        }
+    }
+
     async fn check(
         &self,
         source_id: &str,
@@ -94,8 +102,12 @@ impl ContentWatcher for InstagramWatcher {
                                         if post.is_video { "reel" } else { "post" },
                                         source_id
                                     ),
+                                    title: None,
+                                    duration_secs: None,
                                     shortcode: Some(post.shortcode.clone()),
                                     media: vec![],
+                                    feed: None,
+                                    channel_post: None,
                                 });
                             }
                         }
@@ -131,6 +143,8 @@ impl ContentWatcher for InstagramWatcher {
                                         new_count,
                                         if new_count == 1 { "story" } else { "stories" }
                                     ),
+                                    title: None,
+                                    duration_secs: None,
                                     shortcode: None,
                                     media: new_stories
                                         .iter()
@@ -140,6 +154,8 @@ impl ContentWatcher for InstagramWatcher {
                                             duration_secs: s.duration_secs,
                                         })
                                         .collect(),
+                                    feed: None,
+                                    channel_post: None,
                                 });
                             }
                         }
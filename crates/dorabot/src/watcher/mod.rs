@@ -1,16 +1,22 @@
-//! Content watcher system for monitoring sources (Instagram, YouTube, etc.)
-//! for new posts, stories, and other content.
+//! Content watcher system for monitoring sources (Instagram, YouTube, RSS,
+//! Telegram channels) for new posts, stories, and other content.
 //!
 //! Architecture: The watcher module is independent from teloxide. It emits
 //! `WatchNotification` structs through a `tokio::mpsc` channel. The Telegram
-//! layer (`telegram/subscriptions.rs`) receives and formats them.
+//! layer (`telegram/subscriptions.rs`) receives and formats them. Auto-download
+//! subscriptions are handed to the download queue (`auto_download`).
 
+pub mod auto_download;
 pub mod db;
+pub mod filter;
 pub mod instagram;
+pub mod rss;
 pub mod scheduler;
+pub mod telegram_channel;
 pub mod traits;
+pub mod youtube;
 
-pub use traits::{CheckResult, ContentWatcher, WatchNotification, WatchUpdate};
+pub use traits::{CheckResult, ContentWatcher, FeedMedia, WatchNotification, WatchUpdate};
 
 use std::collections::HashMap;
 
@@ -46,6 +52,9 @@ impl WatcherRegistry {
     pub fn default_registry() -> Self {
         let mut registry = Self::new();
         registry.register(Box::new(instagram::InstagramWatcher::new()));
+        registry.register(Box::new(youtube::YoutubeWatcher::new()));
+        registry.register(Box::new(rss::RssWatcher::new()));
+        registry.register(Box::new(telegram_channel::TelegramChannelWatcher::new()));
         registry
     }
 }
@@ -92,10 +101,36 @@ mod tests {
     #[test]
     fn registry_get_unknown_returns_none() {
         let r = WatcherRegistry::default_registry();
-        assert!(r.get("youtube").is_none());
+        assert!(r.get("yt").is_none());
         assert!(r.get("tiktok").is_none());
     }
 
+    #[test]
+    fn registry_get_youtube_returns_some() {
+        let r = WatcherRegistry::default_registry();
+        let w = r.get("youtube").expect("youtube watcher must exist");
+        assert_eq!(w.source_type(), "youtube");
+        assert!(r.source_types().contains(&"youtube"));
+    }
+
+    #[test]
+    fn registry_get_rss_returns_some() {
+        let r = WatcherRegistry::default_registry();
+        let w = r.get("rss").expect("rss watcher must exist");
+        assert_eq!(w.source_type(), "rss");
+        assert_eq!(w.default_watch_mask(), rss::MASK_EPISODES | rss::MASK_VIDEOS);
+    }
+
+    #[test]
+    fn registry_get_telegram_channel_returns_some() {
+        let r = WatcherRegistry::default_registry();
+        let w = r.get("telegram_channel").expect("telegram_channel watcher must exist");
+        assert_eq!(w.source_type(), "telegram_channel");
+        assert_eq!(w.content_type_mask("audio"), telegram_channel::MASK_AUDIO);
+        assert_eq!(w.content_type_mask("video"), telegram_channel::MASK_VIDEO);
+        assert_eq!(w.requests_per_check(3), 20);
+    }
+
     // ── Watcher metadata ─────────────────────────────────────────────────────
 
     #[test]
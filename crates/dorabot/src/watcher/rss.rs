@@ -0,0 +1,282 @@
+//! RSS / Atom content watcher — follows podcast and media feeds and reports
+//! new audio episodes and video enclosures.
+//!
+//! The source ID is the feed URL. Polls are conditional (ETag /
+//! Last-Modified), so an unchanged feed costs a 304. Items are deduplicated by
+//! guid (Atom id, enclosure URL or link when the feed has no guids).
+
+use crate::download::feed::{self, EnclosureKind, FeedFetch, FeedItem, Validators};
+use crate::download::playlist;
+use crate::storage::db::FeedEpisode;
+use crate::watcher::traits::{CheckResult, ContentWatcher, FeedMedia, WatchUpdate};
+use crate::watcher::youtube::diff_seen;
+use async_trait::async_trait;
+use serde_json::{Value as JsonValue, json};
+use url::Url;
+
+/// Bitmask constants for feed content types.
+pub const MASK_EPISODES: u32 = 1;
+pub const MASK_VIDEOS: u32 = 2;
+
+/// Max updates emitted per check (a feed re-publishing its archive must not flood chats).
+const MAX_UPDATES: usize = 5;
+const MAX_FEED_URL_LEN: usize = 2048;
+
+/// Canonical source ID for a feed link: an http(s) URL without fragment.
+pub fn normalize_feed_url(input: &str) -> Option<String> {
+    let mut url = Url::parse(input.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+    url.set_fragment(None);
+    let url = url.to_string();
+    (url.len() <= MAX_FEED_URL_LEN).then_some(url)
+}
+
+fn content_type_of(kind: &EnclosureKind) -> &'static str {
+    match kind {
+        EnclosureKind::Audio => "episode",
+        EnclosureKind::Video => "video",
+    }
+}
+
+fn describe(item: &FeedItem) -> String {
+    match item.duration_secs {
+        Some(secs) if secs > 0 => format!("{} ({})", item.title, playlist::format_duration(u64::from(secs))),
+        _ => item.title.clone(),
+    }
+}
+
+fn validators_from(state: Option<&JsonValue>) -> Validators {
+    state
+        .and_then(|s| serde_json::from_value(s.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Episode metadata to persist for a feed update, keyed by its enclosure URL,
+/// so a later download keeps the title, show, cover and chapters.
+pub fn feed_episode(update: &WatchUpdate) -> Option<FeedEpisode> {
+    let feed = update.feed.as_ref()?;
+    Some(FeedEpisode {
+        enclosure_url: update.url.clone(),
+        feed_url: feed.feed_url.clone(),
+        title: feed.title.clone(),
+        podcast: feed.podcast.clone(),
+        cover_url: feed.cover_url.clone(),
+        duration_secs: feed.duration_secs.map(i64::from),
+        chapters: feed.chapters.clone(),
+        chapters_url: feed.chapters_url.clone(),
+    })
+}
+
+pub struct RssWatcher;
+
+impl RssWatcher {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RssWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ContentWatcher for RssWatcher {
+    fn source_type(&self) -> &str {
+        "rss"
+    }
+
+    fn display_name(&self) -> &str {
+        "RSS / Podcast"
+    }
+
+    fn content_types(&self) -> Vec<(u32, &str)> {
+        vec![(MASK_EPISODES, "Episodes"), (MASK_VIDEOS, "Videos")]
+    }
+
+    fn content_type_mask(&self, content_type: &str) -> u32 {
+        match content_type {
+            "episode" => MASK_EPISODES,
+            "video" => MASK_VIDEOS,
+            _ => 0,
+        }
+    }
+
+    async fn check(
+        &self,
+        source_id: &str,
+        watch_mask: u32,
+        last_state: Option<&JsonValue>,
+        _source_meta: Option<&JsonValue>,
+    ) -> anyhow::Result<CheckResult> {
+        let prev_seen: Option<Vec<String>> = last_state
+            .and_then(|s| s.get("seen"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+        // Without a seen list the full body is needed to seed it
+        let validators = match prev_seen {
+            Some(_) => validators_from(last_state),
+            None => Validators::default(),
+        };
+
+        let (body, validators) = match feed::fetch_feed(source_id, &validators).await? {
+            FeedFetch::NotModified => {
+                return Ok(CheckResult {
+                    updates: Vec::new(),
+                    new_state: last_state.cloned().unwrap_or_else(|| json!({})),
+                    new_meta: None,
+                });
+            }
+            FeedFetch::Modified { body, validators } => (body, validators),
+        };
+        let parsed = feed::parse_feed(&body)?;
+
+        let ids: Vec<String> = parsed.items.iter().map(|item| item.guid.clone()).collect();
+        let (new_indices, mut merged) = diff_seen(prev_seen.as_deref(), &ids, usize::MAX, true);
+        let wanted: Vec<usize> = new_indices
+            .into_iter()
+            .filter(|&i| {
+                let kind = parsed.items[i].enclosure.as_ref().and_then(|e| e.kind());
+                kind.is_some_and(|kind| watch_mask & self.content_type_mask(content_type_of(&kind)) != 0)
+            })
+            .collect();
+        let (emitted, held_back) = take_oldest(&wanted, &ids, &mut merged);
+
+        let updates = emitted
+            .into_iter()
+            .filter_map(|i| {
+                let item = &parsed.items[i];
+                let enclosure = item.enclosure.as_ref()?;
+                let kind = enclosure.kind()?;
+                Some(WatchUpdate {
+                    content_type: content_type_of(&kind).to_string(),
+                    url: enclosure.url.clone(),
+                    description: describe(item),
+                    title: Some(item.title.clone()),
+                    duration_secs: item.duration_secs.map(u64::from),
+                    shortcode: None,
+                    media: vec![],
+                    feed: Some(FeedMedia {
+                        title: item.title.clone(),
+                        podcast: parsed.title.clone(),
+                        feed_url: source_id.to_string(),
+                        cover_url: item.image_url.clone().or_else(|| parsed.image_url.clone()),
+                        duration_secs: item.duration_secs,
+                        chapters: item.chapters.clone(),
+                        chapters_url: item.chapters_url.clone(),
+                        is_video: kind == EnclosureKind::Video,
+                    }),
+                    channel_post: None,
+                })
+            })
+            .collect();
+
+        let mut new_state = json!({ "seen": merged });
+        // Held-back items are only in this body; without validators the next
+        // check gets a full response instead of a 304.
+        if !held_back {
+            if let Some(etag) = validators.etag {
+                new_state["etag"] = json!(etag);
+            }
+            if let Some(last_modified) = validators.last_modified {
+                new_state["last_modified"] = json!(last_modified);
+            }
+        }
+
+        Ok(CheckResult {
+            updates,
+            new_state,
+            new_meta: None,
+        })
+    }
+
+    async fn resolve_source(&self, source_id: &str) -> anyhow::Result<(String, Option<JsonValue>)> {
+        let url = normalize_feed_url(source_id).ok_or_else(|| anyhow::anyhow!("Not a feed URL: {}", source_id))?;
+        let body = match feed::fetch_feed(&url, &Validators::default()).await? {
+            FeedFetch::Modified { body, .. } => body,
+            FeedFetch::NotModified => anyhow::bail!("Feed {} answered 304 without validators", url),
+        };
+        let parsed = feed::parse_feed(&body)?;
+        if !parsed.items.iter().any(|item| item.enclosure.is_some()) && !parsed.items.is_empty() {
+            anyhow::bail!("This feed has no audio or video episodes");
+        }
+
+        let display_name = match parsed.title.as_str() {
+            "" => Url::parse(&url)?.host_str().unwrap_or("feed").to_string(),
+            title => title.to_string(),
+        };
+        let meta = json!({ "url": url, "cover": parsed.image_url });
+        Ok((display_name, Some(meta)))
+    }
+
+    fn requests_per_check(&self, _watch_mask: u32) -> u32 {
+        // One conditional GET covers every content type
+        1
+    }
+}
+
+/// Pick at most [`MAX_UPDATES`] of the `wanted` new items (indices into a
+/// newest-first feed) to emit now, oldest first so chat order is
+/// chronological. The rest are dropped from `seen` so the next check reports
+/// them. Returns the emitted indices and whether anything was held back.
+fn take_oldest(wanted: &[usize], ids: &[String], seen: &mut Vec<String>) -> (Vec<usize>, bool) {
+    let (held_back, emitted) = wanted.split_at(wanted.len().saturating_sub(MAX_UPDATES));
+    seen.retain(|guid| !held_back.iter().any(|&i| ids[i] == *guid));
+    (emitted.iter().rev().copied().collect(), !held_back.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_feed_urls() {
+        assert_eq!(
+            normalize_feed_url(" https://example.com/feed.xml#top ").as_deref(),
+            Some("https://example.com/feed.xml")
+        );
+        assert_eq!(
+            normalize_feed_url("http://example.com/rss?format=podcast").as_deref(),
+            Some("http://example.com/rss?format=podcast")
+        );
+        assert_eq!(normalize_feed_url("ftp://example.com/feed.xml"), None);
+        assert_eq!(normalize_feed_url("example.com/feed.xml"), None);
+    }
+
+    #[test]
+    fn content_type_masks() {
+        let w = RssWatcher::new();
+        assert_eq!(w.content_type_mask("episode"), MASK_EPISODES);
+        assert_eq!(w.content_type_mask("video"), MASK_VIDEOS);
+        assert_eq!(w.content_type_mask("post"), 0);
+        assert_eq!(w.requests_per_check(MASK_EPISODES | MASK_VIDEOS), 1);
+    }
+
+    #[test]
+    fn backlog_past_the_limit_stays_unseen() {
+        let ids: Vec<String> = (0..8).rev().map(|i| format!("ep{}", i)).collect();
+        let mut seen = ids.clone();
+        // ep7..ep0, newest first; everything new
+        let (emitted, held_back) = take_oldest(&(0..8).collect::<Vec<_>>(), &ids, &mut seen);
+        assert_eq!(emitted, vec![7, 6, 5, 4, 3]);
+        assert!(held_back);
+        assert_eq!(seen, ["ep4", "ep3", "ep2", "ep1", "ep0"]);
+
+        let mut seen = ids.clone();
+        let (emitted, held_back) = take_oldest(&[0, 1], &ids, &mut seen);
+        assert_eq!(emitted, vec![1, 0]);
+        assert!(!held_back);
+        assert_eq!(seen.len(), 8);
+    }
+
+    #[test]
+    fn validators_come_from_state() {
+        let state = json!({ "seen": ["a"], "etag": "\"v1\"", "last_modified": "Tue, 02 Jan 2024 10:00:00 GMT" });
+        let v = validators_from(Some(&state));
+        assert_eq!(v.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(v.last_modified.as_deref(), Some("Tue, 02 Jan 2024 10:00:00 GMT"));
+        assert_eq!(validators_from(None), Validators::default());
+    }
+}
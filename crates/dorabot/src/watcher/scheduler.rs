@@ -2,12 +2,16 @@
 //!
 //! Runs as a `tokio::spawn`ed task, emitting `WatchNotification`s through an mpsc channel.
 //! The Telegram layer receives these and sends formatted messages to users.
+//! Items matching an auto-download subscription are also handed to the
+//! download queue; with silent downloads on, the notification is skipped and
+//! the silent digest reports the result instead.
 
 use crate::core::config;
+use crate::download::queue::DownloadQueue;
 use crate::storage::SharedStorage;
 use crate::storage::db::DbPool;
-use crate::watcher::WatcherRegistry;
 use crate::watcher::traits::WatchNotification;
+use crate::watcher::{WatcherRegistry, auto_download, filter};
 use anyhow::Context;
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -21,6 +25,7 @@ pub fn start_scheduler(
     db_pool: Arc<DbPool>,
     shared_storage: Arc<SharedStorage>,
     registry: Arc<WatcherRegistry>,
+    download_queue: Arc<DownloadQueue>,
 ) -> mpsc::Receiver<WatchNotification> {
     let (tx, rx) = mpsc::channel(1000); // bounded to 1000 pending notifications
 
@@ -37,7 +42,7 @@ pub fn start_scheduler(
         loop {
             ticker.tick().await;
 
-            if let Err(e) = run_check_cycle(&db_pool, &shared_storage, &registry, &tx).await {
+            if let Err(e) = run_check_cycle(&db_pool, &shared_storage, &registry, &download_queue, &tx).await {
                 log::error!("Watcher check cycle failed: {}", e);
             }
         }
@@ -61,9 +66,9 @@ async fn run_check_cycle(
     db_pool: &Arc<DbPool>,
     shared_storage: &Arc<SharedStorage>,
     registry: &WatcherRegistry,
+    download_queue: &DownloadQueue,
     tx: &mpsc::Sender<WatchNotification>,
 ) -> anyhow::Result<()> {
-    let _ = db_pool;
     let groups = shared_storage
         .get_active_content_source_groups()
         .await
@@ -113,14 +118,26 @@ async fn run_check_cycle(
                 // no notification was actually delivered.
                 let mut all_sent = true;
                 for update in &result.updates {
+                    // Only notify subscribers that watch this content type
+                    let bit = watcher.content_type_mask(&update.content_type);
                     for sub in &group.subscriptions {
-                        // Only notify if the subscriber watches this content type
-                        let bit = match update.content_type.as_str() {
-                            "post" => 1,
-                            "story" => 2,
-                            _ => 0,
-                        };
-                        if bit == 0 || sub.watch_mask & bit != 0 {
+                        if (bit == 0 || sub.watch_mask & bit != 0) && filter::matches_filters(&sub.filters, update) {
+                            // Channel posts are delivered as the file itself
+                            let auto_queued = if update.channel_post.is_none() && filter::wants_auto_download(sub, bit)
+                            {
+                                auto_download::enqueue(shared_storage, db_pool, download_queue, sub, update).await
+                            } else {
+                                None
+                            };
+                            // Silent users get the digest recap instead of a message
+                            if auto_queued.is_some()
+                                && shared_storage
+                                    .get_user_silent_downloads(sub.user_id)
+                                    .await
+                                    .unwrap_or(false)
+                            {
+                                continue;
+                            }
                             let notification = WatchNotification {
                                 user_id: sub.user_id,
                                 source_type: group.source_type.clone(),
@@ -128,6 +145,7 @@ async fn run_check_cycle(
                                 display_name: sub.display_name.clone(),
                                 subscription_id: sub.id,
                                 update: update.clone(),
+                                auto_queued,
                             };
                             if let Err(e) = tx.try_send(notification) {
                                 use tokio::sync::mpsc::error::TrySendError;
@@ -0,0 +1,538 @@
+//! Public Telegram channel watcher — follows music channels through the
+//! MTProto client and reports new audio and video posts.
+//!
+//! The source ID is the channel username (lowercase, without `@`). Bots can't
+//! read channel history, but may fetch posts by ID, so the watcher keeps the
+//! last seen message ID and asks for the next window of IDs on every check.
+//! While that window is empty it also probes further ahead: a post found past
+//! a run of deleted IDs proves the run can never fill, so later checks skip it.
+
+use crate::mtproto::{ChannelInfo, MessageInfo, MtProtoDownloader, PeerInfo, PeerType};
+use crate::watcher::traits::{ChannelPost, CheckResult, ContentWatcher, WatchUpdate};
+use async_trait::async_trait;
+use serde_json::{Value as JsonValue, json};
+use std::future::Future;
+use url::Url;
+
+/// Bitmask constants for channel content types.
+pub const MASK_AUDIO: u32 = 1;
+pub const MASK_VIDEO: u32 = 2;
+
+/// Message IDs fetched per request (`channels.getMessages` limit).
+const ID_WINDOW: i32 = 100;
+/// Max bisection steps when seeding the last message ID: enough to narrow
+/// the gap between two probes (at most 2^24 IDs) down to one window.
+const MAX_SEED_STEPS: usize = 18;
+/// Requests a first check makes: the probe, the bisection, the final window.
+const SEED_REQUESTS: u32 = MAX_SEED_STEPS as u32 + 2;
+/// Max updates emitted per check.
+const MAX_UPDATES: usize = 10;
+/// Max empty windows skipped per check on the way to a known later post.
+const MAX_GAP_WINDOWS: usize = 5;
+/// Quiet checks probe the `n`-th window after the next one, `n` cycling
+/// through `1..=MAX_PROBE`, so gaps up to that many windows are found.
+const MAX_PROBE: u32 = 10;
+
+/// Path segments on t.me that aren't channel usernames.
+const RESERVED_PATHS: &[&str] = &[
+    "joinchat",
+    "addstickers",
+    "addemoji",
+    "addlist",
+    "share",
+    "proxy",
+    "socks",
+    "iv",
+    "c",
+];
+
+/// Extract a channel username from `https://t.me/<name>`, `t.me/s/<name>`
+/// or a post link `https://t.me/<name>/<id>`.
+pub fn parse_channel_username(input: &str) -> Option<String> {
+    let input = input.trim();
+    let url = if input.contains("://") {
+        Url::parse(input).ok()?
+    } else {
+        Url::parse(&format!("https://{}", input)).ok()?
+    };
+    let host = url.host_str()?.trim_start_matches("www.");
+    if !matches!(host, "t.me" | "telegram.me") {
+        return None;
+    }
+
+    let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+    let mut name = segments.next()?;
+    if name == "s" {
+        name = segments.next()?;
+    }
+    let name = name.to_ascii_lowercase();
+    let valid = (5..=32).contains(&name.len())
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !RESERVED_PATHS.contains(&name.as_str());
+    valid.then_some(name)
+}
+
+/// Content type of a post's media: `"audio"` or `"video"`; `None` for
+/// photos, files and text posts.
+fn content_type_of(message: &MessageInfo) -> Option<&'static str> {
+    let mime = message.media.as_ref()?.mime_type.as_deref()?;
+    if mime.starts_with("audio/") {
+        Some("audio")
+    } else if mime.starts_with("video/") {
+        Some("video")
+    } else {
+        None
+    }
+}
+
+/// Post title: first caption line, else the file name.
+fn post_title(message: &MessageInfo) -> String {
+    let caption = message.text.lines().map(str::trim).find(|line| !line.is_empty());
+    match (caption, message.media.as_ref().and_then(|m| m.filename.as_deref())) {
+        (Some(line), _) => line.chars().take(200).collect(),
+        (None, Some(filename)) => filename.to_string(),
+        (None, None) => format!("Post #{}", message.id),
+    }
+}
+
+/// The next window of message IDs after `last_id`.
+fn next_window(last_id: i32) -> Vec<i32> {
+    (last_id + 1..=last_id.saturating_add(ID_WINDOW)).collect()
+}
+
+/// Last ID before the window a quiet check probes, `probe` windows past the
+/// one following `cursor`.
+fn probe_start(cursor: i32, probe: u32) -> i32 {
+    cursor.saturating_add(ID_WINDOW.saturating_mul(probe as i32))
+}
+
+/// Probe IDs used to find the newest post of a channel without history access.
+fn seed_probe_ids() -> Vec<i32> {
+    (0..=24).map(|exp| 1 << exp).collect()
+}
+
+/// IDs asked for in one bisection step over `(lo, hi)`: half a window from
+/// the midpoint, then IDs spread across the rest of the upper half, so a run
+/// of deleted posts at the midpoint doesn't end the search too low.
+fn bisect_ids(lo: i32, hi: i32) -> Vec<i32> {
+    let mid = lo + (hi - lo) / 2;
+    let dense_end = mid.saturating_add(ID_WINDOW / 2).min(hi);
+    let mut ids: Vec<i32> = (mid..dense_end).collect();
+    let slots = ID_WINDOW as usize - ids.len();
+    let rest = hi - dense_end;
+    if rest > 0 && slots > 0 {
+        let step = (rest as usize / slots).max(1);
+        ids.extend((dense_end..hi).step_by(step).take(slots));
+    }
+    ids
+}
+
+/// Newest post ID, found without history access: probe powers of two, then
+/// bisect between the highest existing probe and the next power of two and
+/// finish with one full window. `fetch` is `channels.getMessages`; this makes
+/// at most [`SEED_REQUESTS`] calls.
+async fn seed_last_id<F, Fut>(mut fetch: F) -> anyhow::Result<i32>
+where
+    F: FnMut(Vec<i32>) -> Fut,
+    Fut: Future<Output = anyhow::Result<Vec<MessageInfo>>>,
+{
+    let mut lo = newest_id(&fetch(seed_probe_ids()).await?).unwrap_or(0);
+    let mut hi = if lo > 0 { lo.saturating_mul(2) } else { 1 };
+    for _ in 0..MAX_SEED_STEPS {
+        if hi - lo <= ID_WINDOW {
+            break;
+        }
+        match newest_id(&fetch(bisect_ids(lo, hi)).await?) {
+            Some(id) => lo = id,
+            None => hi = lo + (hi - lo) / 2,
+        }
+    }
+    Ok(newest_id(&fetch(next_window(lo)).await?).map_or(lo, |id| id.max(lo)))
+}
+
+fn channel_from_meta(source_id: &str, meta: Option<&JsonValue>) -> Option<ChannelInfo> {
+    let meta = meta?;
+    Some(ChannelInfo {
+        peer: PeerInfo {
+            peer_type: PeerType::Channel,
+            id: meta.get("channel_id")?.as_i64()?,
+        },
+        access_hash: meta.get("access_hash")?.as_i64()?,
+        username: source_id.to_string(),
+        title: meta
+            .get("title")
+            .and_then(|t| t.as_str())
+            .unwrap_or(source_id)
+            .to_string(),
+    })
+}
+
+fn channel_meta(channel: &ChannelInfo) -> JsonValue {
+    json!({
+        "channel_id": channel.peer.id,
+        "access_hash": channel.access_hash,
+        "title": channel.title,
+    })
+}
+
+/// Updates for new media posts matching `watch_mask`, oldest first, and the
+/// message ID the next check continues after: the last emitted post when
+/// some were held back, else the newest message of the window.
+fn updates_from_messages(
+    channel: &ChannelInfo,
+    messages: &[MessageInfo],
+    watch_mask: u32,
+) -> (Vec<WatchUpdate>, Option<i32>) {
+    let mut posts: Vec<(&MessageInfo, &'static str)> = messages
+        .iter()
+        .filter_map(|message| Some((message, content_type_of(message)?)))
+        .filter(|(_, content_type)| watch_mask & mask_of(content_type) != 0)
+        .collect();
+    posts.sort_by_key(|(message, _)| message.id);
+    let resume_after = if posts.len() > MAX_UPDATES {
+        posts.truncate(MAX_UPDATES);
+        posts.last().map(|(message, _)| message.id)
+    } else {
+        newest_id(messages)
+    };
+
+    let updates = posts
+        .into_iter()
+        .map(|(message, content_type)| {
+            let media = message.media.as_ref();
+            let title = post_title(message);
+            WatchUpdate {
+                content_type: content_type.to_string(),
+                url: format!("https://t.me/{}/{}", channel.username, message.id),
+                description: title.clone(),
+                title: Some(title),
+                duration_secs: media.and_then(|m| m.duration).and_then(|d| u64::try_from(d).ok()),
+                shortcode: None,
+                media: vec![],
+                feed: None,
+                channel_post: Some(ChannelPost {
+                    channel_id: channel.peer.id,
+                    access_hash: channel.access_hash,
+                    username: channel.username.clone(),
+                    message_id: message.id,
+                    is_video: content_type == "video",
+                    size_bytes: media.map(|m| m.size).unwrap_or(0),
+                }),
+            }
+        })
+        .collect();
+    (updates, resume_after)
+}
+
+fn mask_of(content_type: &str) -> u32 {
+    match content_type {
+        "audio" => MASK_AUDIO,
+        "video" => MASK_VIDEO,
+        _ => 0,
+    }
+}
+
+fn newest_id(messages: &[MessageInfo]) -> Option<i32> {
+    messages.iter().map(|message| message.id).max()
+}
+
+pub struct TelegramChannelWatcher;
+
+impl TelegramChannelWatcher {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TelegramChannelWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ContentWatcher for TelegramChannelWatcher {
+    fn source_type(&self) -> &str {
+        "telegram_channel"
+    }
+
+    fn display_name(&self) -> &str {
+        "Telegram channel"
+    }
+
+    fn content_types(&self) -> Vec<(u32, &str)> {
+        vec![(MASK_AUDIO, "Audio"), (MASK_VIDEO, "Video")]
+    }
+
+    fn content_type_mask(&self, content_type: &str) -> u32 {
+        mask_of(content_type)
+    }
+
+    async fn check(
+        &self,
+        source_id: &str,
+        watch_mask: u32,
+        last_state: Option<&JsonValue>,
+        source_meta: Option<&JsonValue>,
+    ) -> anyhow::Result<CheckResult> {
+        let downloader = MtProtoDownloader::shared().await?;
+        let (channel, new_meta) = match channel_from_meta(source_id, source_meta) {
+            Some(channel) => (channel, None),
+            None => {
+                let channel = downloader.resolve_channel(source_id).await?;
+                let meta = channel_meta(&channel);
+                (channel, Some(meta))
+            }
+        };
+
+        let last_id = last_state
+            .and_then(|s| s.get("last_id"))
+            .and_then(|v| v.as_i64())
+            .and_then(|id| i32::try_from(id).ok());
+        let Some(last_id) = last_id else {
+            // First check: remember where the channel is, emit nothing
+            let (downloader, channel) = (&downloader, &channel);
+            let last_id =
+                seed_last_id(|ids| async move { downloader.get_channel_messages(channel, &ids).await }).await?;
+            return Ok(CheckResult {
+                updates: Vec::new(),
+                new_state: json!({ "last_id": last_id }),
+                new_meta,
+            });
+        };
+
+        let mut beyond = last_state
+            .and_then(|s| s.get("beyond"))
+            .and_then(|v| v.as_i64())
+            .and_then(|id| i32::try_from(id).ok());
+        let probe = last_state
+            .and_then(|s| s.get("probe"))
+            .and_then(|v| v.as_u64())
+            .and_then(|n| u32::try_from(n).ok())
+            .unwrap_or(1)
+            .clamp(1, MAX_PROBE);
+
+        let mut cursor = last_id;
+        for _ in 0..MAX_GAP_WINDOWS {
+            let messages = downloader.get_channel_messages(&channel, &next_window(cursor)).await?;
+            if !messages.is_empty() {
+                let (updates, resume_after) = updates_from_messages(&channel, &messages, watch_mask);
+                let last_id = resume_after.map_or(cursor, |id| id.max(cursor));
+                return Ok(CheckResult {
+                    updates,
+                    new_state: json!({ "last_id": last_id }),
+                    new_meta,
+                });
+            }
+            // An empty window below a known post stays empty for good
+            match beyond {
+                Some(id) if id > cursor.saturating_add(ID_WINDOW) => cursor = cursor.saturating_add(ID_WINDOW),
+                _ => {
+                    beyond = None;
+                    break;
+                }
+            }
+        }
+        if beyond.is_some() {
+            return Ok(CheckResult {
+                updates: Vec::new(),
+                new_state: json!({ "last_id": cursor, "beyond": beyond }),
+                new_meta,
+            });
+        }
+
+        // Quiet channel, or a run of deleted IDs: look further ahead
+        let ahead = downloader
+            .get_channel_messages(&channel, &next_window(probe_start(cursor, probe)))
+            .await?;
+        let new_state = match ahead.iter().map(|message| message.id).min() {
+            Some(id) => json!({ "last_id": cursor, "beyond": id }),
+            None => json!({ "last_id": cursor, "probe": probe % MAX_PROBE + 1 }),
+        };
+        Ok(CheckResult {
+            updates: Vec::new(),
+            new_state,
+            new_meta,
+        })
+    }
+
+    async fn resolve_source(&self, source_id: &str) -> anyhow::Result<(String, Option<JsonValue>)> {
+        let username = parse_channel_username(source_id)
+            .or_else(|| parse_channel_username(&format!("https://t.me/{}", source_id.trim_start_matches('@'))))
+            .ok_or_else(|| anyhow::anyhow!("Not a channel username: {}", source_id))?;
+        let downloader = MtProtoDownloader::shared().await?;
+        let channel = downloader.resolve_channel(&username).await?;
+        Ok((channel.title.clone(), Some(channel_meta(&channel))))
+    }
+
+    fn requests_per_check(&self, _watch_mask: u32) -> u32 {
+        // One getMessages call covers every content type. A check walks up
+        // to MAX_GAP_WINDOWS windows plus one probe ahead; the first check
+        // seeds the last ID instead, which costs more.
+        SEED_REQUESTS.max(MAX_GAP_WINDOWS as u32 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtproto::{MediaInfo, MediaType};
+
+    fn channel() -> ChannelInfo {
+        ChannelInfo {
+            peer: PeerInfo {
+                peer_type: PeerType::Channel,
+                id: 1001,
+            },
+            access_hash: 42,
+            username: "dorasound".to_string(),
+            title: "Dora Sound".to_string(),
+        }
+    }
+
+    fn message(id: i32, text: &str, mime: Option<&str>) -> MessageInfo {
+        MessageInfo {
+            id,
+            date: 0,
+            text: text.to_string(),
+            from_id: None,
+            peer_id: PeerInfo {
+                peer_type: PeerType::Channel,
+                id: 1001,
+            },
+            media: mime.map(|mime| MediaInfo {
+                message_id: id,
+                date: 0,
+                media_type: MediaType::Document,
+                id: 7,
+                access_hash: 8,
+                file_reference: vec![],
+                dc_id: 2,
+                size: 5_000_000,
+                filename: Some("track.mp3".to_string()),
+                mime_type: Some(mime.to_string()),
+                duration: Some(215),
+            }),
+            out: false,
+        }
+    }
+
+    #[test]
+    fn parses_channel_links() {
+        assert_eq!(
+            parse_channel_username("https://t.me/DoraSound").as_deref(),
+            Some("dorasound")
+        );
+        assert_eq!(parse_channel_username("t.me/s/dorasound").as_deref(), Some("dorasound"));
+        assert_eq!(
+            parse_channel_username("https://telegram.me/dorasound/1234").as_deref(),
+            Some("dorasound")
+        );
+        assert_eq!(parse_channel_username("https://t.me/joinchat/AAAAAE"), None);
+        assert_eq!(parse_channel_username("https://t.me/+AbCdEf"), None);
+        assert_eq!(parse_channel_username("https://t.me/abc"), None);
+        assert_eq!(parse_channel_username("https://example.com/dorasound"), None);
+    }
+
+    #[test]
+    fn media_posts_become_updates_oldest_first() {
+        let messages = vec![
+            message(12, "Live clip\nrecorded yesterday", Some("video/mp4")),
+            message(11, "", Some("audio/mpeg")),
+            message(13, "Cover art", Some("image/jpeg")),
+            message(14, "Just text", None),
+        ];
+        let (updates, resume_after) = updates_from_messages(&channel(), &messages, MASK_AUDIO | MASK_VIDEO);
+        assert_eq!(updates.len(), 2);
+        assert_eq!(resume_after, Some(14));
+        assert_eq!(updates[0].content_type, "audio");
+        assert_eq!(updates[0].description, "track.mp3");
+        assert_eq!(updates[0].url, "https://t.me/dorasound/11");
+        assert_eq!(updates[0].duration_secs, Some(215));
+        assert_eq!(updates[1].title.as_deref(), Some("Live clip"));
+        assert!(updates[1].channel_post.as_ref().is_some_and(|post| post.is_video));
+
+        let (audio_only, _) = updates_from_messages(&channel(), &messages, MASK_AUDIO);
+        assert_eq!(audio_only.len(), 1);
+        assert_eq!(newest_id(&messages), Some(14));
+    }
+
+    #[test]
+    fn held_back_posts_are_picked_up_next_check() {
+        let mut messages: Vec<MessageInfo> = (101..=115).map(|id| message(id, "", Some("audio/mpeg"))).collect();
+        messages.push(message(120, "Just text", None));
+        let (updates, resume_after) = updates_from_messages(&channel(), &messages, MASK_AUDIO);
+        assert_eq!(updates.len(), MAX_UPDATES);
+        assert_eq!(updates.last().unwrap().url, "https://t.me/dorasound/110");
+        assert_eq!(resume_after, Some(110));
+
+        // A text post past the media doesn't hold the cursor back
+        let (updates, resume_after) = updates_from_messages(&channel(), &messages[10..], MASK_AUDIO);
+        assert_eq!(updates.len(), 5);
+        assert_eq!(resume_after, Some(120));
+    }
+
+    #[test]
+    fn channel_meta_round_trips() {
+        let meta = channel_meta(&channel());
+        let restored = channel_from_meta("dorasound", Some(&meta)).unwrap();
+        assert_eq!(restored.peer.id, 1001);
+        assert_eq!(restored.access_hash, 42);
+        assert_eq!(restored.title, "Dora Sound");
+        assert!(channel_from_meta("dorasound", None).is_none());
+    }
+
+    #[test]
+    fn id_windows() {
+        assert_eq!(next_window(0).first(), Some(&1));
+        assert_eq!(next_window(250).len(), ID_WINDOW as usize);
+        assert_eq!(next_window(250).last(), Some(&350));
+        assert_eq!(seed_probe_ids().last(), Some(&(1 << 24)));
+        assert_eq!(next_window(probe_start(250, 1)).first(), Some(&351));
+        assert_eq!(probe_start(250, MAX_PROBE), 250 + ID_WINDOW * 10);
+        let ids = bisect_ids(8192, 16384);
+        assert_eq!(ids.len(), ID_WINDOW as usize);
+        assert_eq!(ids.first(), Some(&12288));
+        assert!(ids.iter().all(|id| (12288..16384).contains(id)));
+    }
+
+    /// Seed against a fake `getMessages` that knows which IDs exist.
+    async fn seed_with(exists: impl Fn(i32) -> bool) -> (i32, u32) {
+        let mut calls = 0;
+        let last_id = seed_last_id(|ids: Vec<i32>| {
+            calls += 1;
+            let found: Vec<MessageInfo> = ids
+                .into_iter()
+                .filter(|id| exists(*id))
+                .map(|id| message(id, "", None))
+                .collect();
+            std::future::ready(Ok(found))
+        })
+        .await
+        .unwrap();
+        (last_id, calls)
+    }
+
+    #[tokio::test]
+    async fn seeding_finds_the_newest_post() {
+        // Past the old linear walk's reach (8192 + 2000)
+        let (last_id, calls) = seed_with(|id| (1..=15_000).contains(&id)).await;
+        assert_eq!(last_id, 15_000);
+        assert!(calls <= SEED_REQUESTS);
+
+        // Runs of deleted posts don't stop the search
+        let (last_id, _) = seed_with(|id| {
+            (1..=15_000).contains(&id) && !(11_000..=11_400).contains(&id) && !(14_000..14_150).contains(&id)
+        })
+        .await;
+        assert_eq!(last_id, 15_000);
+
+        for newest in [37, 100, 129, 4097, (1 << 25) - 1] {
+            let (last_id, calls) = seed_with(|id| (1..=newest).contains(&id)).await;
+            assert_eq!(last_id, newest);
+            assert!(calls <= SEED_REQUESTS);
+        }
+        assert_eq!(seed_with(|_| false).await.0, 0);
+    }
+}
@@ -4,6 +4,7 @@
 //! checks for new content. The watcher module has zero teloxide dependency —
 //! notifications are emitted as plain structs through an mpsc channel.
 
+use crate::timestamps::VideoTimestamp;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +16,34 @@ pub struct MediaAttachment {
     pub duration_secs: Option<f64>,
 }
 
+/// Episode metadata carried by feed enclosures (podcasts), so the download
+/// keeps the episode title, show name, cover and chapters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedMedia {
+    pub title: String,
+    /// Show (feed) name.
+    pub podcast: String,
+    pub feed_url: String,
+    pub cover_url: Option<String>,
+    pub duration_secs: Option<u32>,
+    pub chapters: Vec<VideoTimestamp>,
+    /// Podcasting 2.0 chapters document, fetched at download time.
+    pub chapters_url: Option<String>,
+    pub is_video: bool,
+}
+
+/// A post in a public Telegram channel, with what the dispatcher needs to
+/// forward it or re-send its media via MTProto.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelPost {
+    pub channel_id: i64,
+    pub access_hash: i64,
+    pub username: String,
+    pub message_id: i32,
+    pub is_video: bool,
+    pub size_bytes: i64,
+}
+
 /// A single new content item detected by a watcher.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WatchUpdate {
@@ -24,10 +53,18 @@ pub struct WatchUpdate {
     pub url: String,
     /// Short human-readable description
     pub description: String,
+    /// Item title, when the source has one (matched by subscription keyword filters).
+    pub title: Option<String>,
+    /// Item duration, when known (matched by subscription duration filters).
+    pub duration_secs: Option<u64>,
     /// Post shortcode for deferred media resolution (posts only).
     pub shortcode: Option<String>,
     /// Pre-resolved media attachments (stories have CDN URLs immediately).
     pub media: Vec<MediaAttachment>,
+    /// Podcast metadata for feed enclosures (RSS only).
+    pub feed: Option<FeedMedia>,
+    /// Source post for Telegram channel media.
+    pub channel_post: Option<ChannelPost>,
 }
 
 /// Result of a check operation.
@@ -50,6 +87,8 @@ pub struct WatchNotification {
     pub display_name: String,
     pub subscription_id: i64,
     pub update: WatchUpdate,
+    /// Format (`"mp3"` / `"mp4"`) the item was auto-queued in, if any.
+    pub auto_queued: Option<&'static str>,
 }
 
 /// Trait implemented by each content source (Instagram, YouTube, etc.).
@@ -69,11 +108,26 @@ pub trait ContentWatcher: Send + Sync {
     /// Example: `[(1, "Posts"), (2, "Stories")]`
     fn content_types(&self) -> Vec<(u32, &str)>;
 
+    /// Content types that apply to one particular source. Sources of the
+    /// same type may support different subsets (e.g. a YouTube playlist has
+    /// no Shorts tab).
+    fn content_types_for(&self, source_id: &str) -> Vec<(u32, &str)> {
+        let _ = source_id;
+        self.content_types()
+    }
+
     /// Default watch mask for new subscriptions.
     fn default_watch_mask(&self) -> u32 {
         3
     }
 
+    /// Bitmask for a `WatchUpdate::content_type`, used to fan updates out
+    /// only to subscribers watching that type. `0` notifies everyone.
+    fn content_type_mask(&self, content_type: &str) -> u32 {
+        let _ = content_type;
+        0
+    }
+
     /// Check for new content since `last_state`.
     ///
     /// When `last_state` is `None` (first check), populate state but
@@ -0,0 +1,543 @@
+//! YouTube content watcher — monitors channels (videos, shorts, livestreams)
+//! and playlists (new additions).
+//!
+//! Listings come from `yt-dlp --flat-playlist` via `download::playlist`, so no
+//! API key is needed. State is the set of video IDs already seen per content
+//! type; anything not in the set on the next check is new.
+
+use crate::download::playlist::{self, PlaylistEntry};
+use crate::watcher::traits::{CheckResult, ContentWatcher, WatchUpdate};
+use async_trait::async_trait;
+use serde_json::{Value as JsonValue, json};
+use std::collections::{BTreeMap, HashSet};
+use url::Url;
+
+/// Bitmask constants for YouTube content types.
+pub const MASK_VIDEOS: u32 = 1;
+pub const MASK_SHORTS: u32 = 2;
+pub const MASK_LIVE: u32 = 4;
+pub const MASK_PLAYLIST: u32 = 8;
+
+/// Newest entries listed per channel tab on each check.
+const TAB_SCAN_LIMIT: usize = 15;
+/// Entries listed from the top of a playlist, for lists that prepend.
+const PLAYLIST_HEAD_SCAN: usize = 15;
+/// Entries listed from the end of a playlist, where most lists append.
+const PLAYLIST_TAIL_SCAN: usize = 500;
+/// Seen IDs kept per content type; larger than a full playlist scan so every
+/// listed entry stays seen.
+const SEEN_CAP: usize = 600;
+/// Max updates emitted per content type per check.
+const MAX_UPDATES_PER_TYPE: usize = 5;
+/// `cw:ptog:youtube:<source_id>:<bit>:<mask>` must fit Telegram's 64-byte callback limit.
+const MAX_SOURCE_ID_LEN: usize = 40;
+
+/// A subscribable YouTube source. Its canonical `source_id` is compact enough
+/// to travel inside callback data: `@handle`, `UC…`, `c/name`, `user/name`
+/// or `list=PL…`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YoutubeSource {
+    Handle(String),
+    Channel(String),
+    Custom(String),
+    User(String),
+    Playlist(String),
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+impl YoutubeSource {
+    /// Parse a channel/playlist URL, a bare `@handle`, or a canonical source ID.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let source = if let Ok(url) = Url::parse(input) {
+            Self::from_url(&url)?
+        } else if let Some(handle) = input.strip_prefix('@') {
+            Self::Handle(handle.to_string())
+        } else if let Some(list) = input.strip_prefix("list=") {
+            Self::Playlist(list.to_string())
+        } else if let Some(name) = input.strip_prefix("c/") {
+            Self::Custom(name.to_string())
+        } else if let Some(name) = input.strip_prefix("user/") {
+            Self::User(name.to_string())
+        } else if input.starts_with("UC") && input.len() == 24 {
+            Self::Channel(input.to_string())
+        } else {
+            return None;
+        };
+
+        let name = match &source {
+            Self::Handle(n) | Self::Channel(n) | Self::Custom(n) | Self::User(n) | Self::Playlist(n) => n,
+        };
+        (is_valid_name(name) && source.source_id().len() <= MAX_SOURCE_ID_LEN).then_some(source)
+    }
+
+    fn from_url(url: &Url) -> Option<Self> {
+        let host = url.host_str()?.to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        if !matches!(host, "youtube.com" | "m.youtube.com" | "music.youtube.com") {
+            return None;
+        }
+
+        if let Some((_, list)) = url.query_pairs().find(|(k, _)| k == "list") {
+            // RD… lists are auto-generated mixes, different for every viewer.
+            if list.starts_with("RD") {
+                return None;
+            }
+            return Some(Self::Playlist(list.into_owned()));
+        }
+
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        match segments.as_slice() {
+            [first, ..] if first.starts_with('@') => Some(Self::Handle(first[1..].to_string())),
+            ["channel", id, ..] => Some(Self::Channel(id.to_string())),
+            ["c", name, ..] => Some(Self::Custom(name.to_string())),
+            ["user", name, ..] => Some(Self::User(name.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Canonical ID stored in `content_subscriptions.source_id`.
+    pub fn source_id(&self) -> String {
+        match self {
+            Self::Handle(h) => format!("@{}", h),
+            Self::Channel(id) => id.clone(),
+            Self::Custom(name) => format!("c/{}", name),
+            Self::User(name) => format!("user/{}", name),
+            Self::Playlist(id) => format!("list={}", id),
+        }
+    }
+
+    pub fn is_playlist(&self) -> bool {
+        matches!(self, Self::Playlist(_))
+    }
+
+    pub fn url(&self) -> String {
+        match self {
+            Self::Handle(h) => format!("https://www.youtube.com/@{}", h),
+            Self::Channel(id) => format!("https://www.youtube.com/channel/{}", id),
+            Self::Custom(name) => format!("https://www.youtube.com/c/{}", name),
+            Self::User(name) => format!("https://www.youtube.com/user/{}", name),
+            Self::Playlist(id) => format!("https://www.youtube.com/playlist?list={}", id),
+        }
+    }
+
+    /// Listings to poll for `watch_mask`: `(content_type, url, playlist items)`.
+    fn feeds(&self, watch_mask: u32) -> Vec<(&'static str, String, String)> {
+        if self.is_playlist() {
+            if watch_mask & MASK_PLAYLIST == 0 {
+                return Vec::new();
+            }
+            let items = format!("1:{},-{}:", PLAYLIST_HEAD_SCAN, PLAYLIST_TAIL_SCAN);
+            return vec![("playlist", self.url(), items)];
+        }
+        [
+            (MASK_VIDEOS, "video", "videos"),
+            (MASK_SHORTS, "short", "shorts"),
+            (MASK_LIVE, "live", "streams"),
+        ]
+        .into_iter()
+        .filter(|(bit, _, _)| watch_mask & bit != 0)
+        .map(|(_, content_type, tab)| {
+            (
+                content_type,
+                format!("{}/{}", self.url(), tab),
+                format!("1:{}", TAB_SCAN_LIMIT),
+            )
+        })
+        .collect()
+    }
+}
+
+/// Compare a fresh listing with the IDs seen so far. Returns the indices of
+/// at most `max_new` new entries (in listing order) and the updated seen
+/// list, most recent listing first, capped at [`SEEN_CAP`]. The oldest new
+/// entries win — the end of a `newest_first` listing, the start otherwise —
+/// and new entries past `max_new` stay unseen so the next check reports
+/// them. With no previous list (first check or a newly enabled type)
+/// nothing counts as new.
+pub(crate) fn diff_seen(
+    prev: Option<&[String]>,
+    current: &[String],
+    max_new: usize,
+    newest_first: bool,
+) -> (Vec<usize>, Vec<String>) {
+    let Some(prev) = prev else {
+        return (Vec::new(), current.iter().take(SEEN_CAP).cloned().collect());
+    };
+    let seen: HashSet<&str> = prev.iter().map(String::as_str).collect();
+    let mut new_indices: Vec<usize> = current
+        .iter()
+        .enumerate()
+        .filter(|(_, id)| !seen.contains(id.as_str()))
+        .map(|(i, _)| i)
+        .collect();
+    let held_back: HashSet<usize> = if newest_first {
+        let keep_from = new_indices.len().saturating_sub(max_new);
+        new_indices.drain(..keep_from).collect()
+    } else {
+        new_indices
+            .split_off(max_new.min(new_indices.len()))
+            .into_iter()
+            .collect()
+    };
+
+    let listed: HashSet<&str> = current.iter().map(String::as_str).collect();
+    let merged = current
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !held_back.contains(i))
+        .map(|(_, id)| id)
+        .chain(prev.iter().filter(|id| !listed.contains(id.as_str())))
+        .take(SEEN_CAP)
+        .cloned()
+        .collect();
+    (new_indices, merged)
+}
+
+fn entry_id(entry: &PlaylistEntry) -> String {
+    entry.id.clone().unwrap_or_else(|| entry.url.clone())
+}
+
+fn describe(entry: &PlaylistEntry) -> String {
+    match entry.duration {
+        Some(secs) if secs > 0 => format!("{} ({})", entry.title, playlist::format_duration(secs)),
+        _ => entry.title.clone(),
+    }
+}
+
+pub struct YoutubeWatcher;
+
+impl YoutubeWatcher {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for YoutubeWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ContentWatcher for YoutubeWatcher {
+    fn source_type(&self) -> &str {
+        "youtube"
+    }
+
+    fn display_name(&self) -> &str {
+        "YouTube"
+    }
+
+    fn content_types(&self) -> Vec<(u32, &str)> {
+        vec![
+            (MASK_VIDEOS, "Videos"),
+            (MASK_SHORTS, "Shorts"),
+            (MASK_LIVE, "Livestreams"),
+            (MASK_PLAYLIST, "Playlist additions"),
+        ]
+    }
+
+    fn content_types_for(&self, source_id: &str) -> Vec<(u32, &str)> {
+        let is_playlist = YoutubeSource::parse(source_id).is_some_and(|s| s.is_playlist());
+        self.content_types()
+            .into_iter()
+            .filter(|(bit, _)| (*bit == MASK_PLAYLIST) == is_playlist)
+            .collect()
+    }
+
+    fn default_watch_mask(&self) -> u32 {
+        MASK_VIDEOS | MASK_PLAYLIST
+    }
+
+    fn content_type_mask(&self, content_type: &str) -> u32 {
+        match content_type {
+            "video" => MASK_VIDEOS,
+            "short" => MASK_SHORTS,
+            "live" => MASK_LIVE,
+            "playlist" => MASK_PLAYLIST,
+            _ => 0,
+        }
+    }
+
+    async fn check(
+        &self,
+        source_id: &str,
+        watch_mask: u32,
+        last_state: Option<&JsonValue>,
+        _source_meta: Option<&JsonValue>,
+    ) -> anyhow::Result<CheckResult> {
+        let source =
+            YoutubeSource::parse(source_id).ok_or_else(|| anyhow::anyhow!("Invalid YouTube source: {}", source_id))?;
+
+        let mut seen: BTreeMap<String, Vec<String>> = last_state
+            .and_then(|s| s.get("seen"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        let feeds = source.feeds(watch_mask);
+        let mut updates = Vec::new();
+        let mut listed = 0;
+        let mut last_error = None;
+
+        for (content_type, url, items) in &feeds {
+            let listing = match Url::parse(url) {
+                Ok(u) => playlist::extract_entries(&u, items).await,
+                Err(e) => Err(e.into()),
+            };
+            let info = match listing {
+                Ok(info) => info,
+                Err(e) => {
+                    // One missing tab (e.g. no shorts) must not block the others
+                    log::warn!("YoutubeWatcher: failed to list {}: {}", url, e);
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            listed += 1;
+
+            let ids: Vec<String> = info.entries.iter().map(entry_id).collect();
+            let (new_indices, merged) = diff_seen(
+                seen.get(*content_type).map(Vec::as_slice),
+                &ids,
+                MAX_UPDATES_PER_TYPE,
+                !source.is_playlist(),
+            );
+
+            // Channel tabs list newest first; emit oldest first so chat order is chronological.
+            let mut fresh: Vec<&PlaylistEntry> = new_indices.iter().map(|&i| &info.entries[i]).collect();
+            if !source.is_playlist() {
+                fresh.reverse();
+            }
+            updates.extend(fresh.into_iter().map(|entry| WatchUpdate {
+                content_type: content_type.to_string(),
+                url: entry.url.clone(),
+                description: describe(entry),
+                title: Some(entry.title.clone()),
+                duration_secs: entry.duration,
+                shortcode: None,
+                media: vec![],
+                feed: None,
+                channel_post: None,
+            }));
+
+            seen.insert(content_type.to_string(), merged);
+        }
+
+        if listed == 0
+            && let Some(e) = last_error
+        {
+            anyhow::bail!("YouTube check failed: {}", e);
+        }
+
+        Ok(CheckResult {
+            updates,
+            new_state: json!({ "seen": seen }),
+            new_meta: None,
+        })
+    }
+
+    async fn resolve_source(&self, source_id: &str) -> anyhow::Result<(String, Option<JsonValue>)> {
+        let source = YoutubeSource::parse(source_id)
+            .ok_or_else(|| anyhow::anyhow!("Not a YouTube channel or playlist: {}", source_id))?;
+
+        let base = Url::parse(&source.url())?;
+        let listing = if source.is_playlist() {
+            playlist::extract_recent_entries(&base, 1).await
+        } else {
+            // Shorts-only channels have no videos tab; fall back to the channel page
+            let videos = Url::parse(&format!("{}/videos", source.url()))?;
+            match playlist::extract_recent_entries(&videos, 1).await {
+                Ok(info) => Ok(info),
+                Err(_) => playlist::extract_recent_entries(&base, 1).await,
+            }
+        };
+        let info = listing.map_err(|e| anyhow::anyhow!("Failed to resolve YouTube source {}: {}", source_id, e))?;
+
+        let display_name = if source.is_playlist() {
+            match info.uploader {
+                Some(uploader) => format!("{} ({})", info.title, uploader),
+                None => info.title,
+            }
+        } else {
+            info.uploader
+                .unwrap_or_else(|| info.title.strip_suffix(" - Videos").unwrap_or(&info.title).to_string())
+        };
+
+        let meta = json!({
+            "kind": if source.is_playlist() { "playlist" } else { "channel" },
+            "url": source.url(),
+        });
+
+        Ok((display_name, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_channel_urls_and_handles() {
+        assert_eq!(
+            YoutubeSource::parse("https://www.youtube.com/@LinusTechTips/videos"),
+            Some(YoutubeSource::Handle("LinusTechTips".into()))
+        );
+        assert_eq!(
+            YoutubeSource::parse("@mkbhd"),
+            Some(YoutubeSource::Handle("mkbhd".into()))
+        );
+        assert_eq!(
+            YoutubeSource::parse("https://m.youtube.com/channel/UCBJycsmduvYEL83R_U4JriQ"),
+            Some(YoutubeSource::Channel("UCBJycsmduvYEL83R_U4JriQ".into()))
+        );
+        assert_eq!(
+            YoutubeSource::parse("https://youtube.com/c/Vsauce"),
+            Some(YoutubeSource::Custom("Vsauce".into()))
+        );
+        assert_eq!(
+            YoutubeSource::parse("https://www.youtube.com/user/PewDiePie"),
+            Some(YoutubeSource::User("PewDiePie".into()))
+        );
+    }
+
+    #[test]
+    fn parses_playlist_urls() {
+        let source = YoutubeSource::parse("https://www.youtube.com/playlist?list=PLabc-123_x").unwrap();
+        assert_eq!(source, YoutubeSource::Playlist("PLabc-123_x".into()));
+        assert!(source.is_playlist());
+        assert_eq!(
+            YoutubeSource::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLxyz"),
+            Some(YoutubeSource::Playlist("PLxyz".into()))
+        );
+        // Personal mixes are not subscribable
+        assert_eq!(
+            YoutubeSource::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=RDdQw4w9WgXcQ"),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_non_sources() {
+        assert_eq!(
+            YoutubeSource::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            None
+        );
+        assert_eq!(YoutubeSource::parse("https://youtu.be/dQw4w9WgXcQ"), None);
+        assert_eq!(YoutubeSource::parse("https://instagram.com/@someone"), None);
+        assert_eq!(YoutubeSource::parse("@bad:name"), None);
+        assert_eq!(YoutubeSource::parse("cristiano"), None);
+    }
+
+    #[test]
+    fn source_id_round_trips() {
+        for input in [
+            "@mkbhd",
+            "UCBJycsmduvYEL83R_U4JriQ",
+            "c/Vsauce",
+            "user/PewDiePie",
+            "list=PLabc",
+        ] {
+            let source = YoutubeSource::parse(input).unwrap();
+            assert_eq!(source.source_id(), input);
+            assert_eq!(YoutubeSource::parse(&source.url()), Some(source));
+        }
+    }
+
+    #[test]
+    fn source_id_fits_callback_data() {
+        let long = format!("@{}", "a".repeat(60));
+        assert_eq!(YoutubeSource::parse(&long), None);
+        let id = YoutubeSource::parse("list=PL01234567890123456789012345678901")
+            .unwrap()
+            .source_id();
+        assert!(format!("cw:ptog:youtube:{}:4:15", id).len() <= 64);
+    }
+
+    #[test]
+    fn feeds_follow_mask_and_source_kind() {
+        let channel = YoutubeSource::Handle("x".into());
+        let feeds = channel.feeds(MASK_VIDEOS | MASK_LIVE | MASK_PLAYLIST);
+        let urls: Vec<&str> = feeds.iter().map(|(_, url, _)| url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://www.youtube.com/@x/videos",
+                "https://www.youtube.com/@x/streams"
+            ]
+        );
+
+        assert_eq!(feeds[0].2, "1:15");
+
+        let list = YoutubeSource::Playlist("PLx".into());
+        assert!(list.feeds(MASK_VIDEOS).is_empty());
+        let feeds = list.feeds(MASK_PLAYLIST);
+        assert_eq!(feeds[0].0, "playlist");
+        // Both ends: appended videos land at the tail of long playlists
+        assert_eq!(feeds[0].2, "1:15,-500:");
+        assert!(SEEN_CAP >= PLAYLIST_HEAD_SCAN + PLAYLIST_TAIL_SCAN);
+    }
+
+    #[test]
+    fn content_types_depend_on_source() {
+        let w = YoutubeWatcher::new();
+        let channel: Vec<u32> = w.content_types_for("@x").iter().map(|(m, _)| *m).collect();
+        assert_eq!(channel, vec![MASK_VIDEOS, MASK_SHORTS, MASK_LIVE]);
+        let list: Vec<u32> = w.content_types_for("list=PLx").iter().map(|(m, _)| *m).collect();
+        assert_eq!(list, vec![MASK_PLAYLIST]);
+        assert_eq!(w.content_type_mask("short"), MASK_SHORTS);
+        assert_eq!(w.content_type_mask("post"), 0);
+    }
+
+    #[test]
+    fn first_listing_only_seeds_state() {
+        let (new, merged) = diff_seen(None, &ids(&["c", "b", "a"]), 5, true);
+        assert!(new.is_empty());
+        assert_eq!(merged, ids(&["c", "b", "a"]));
+    }
+
+    #[test]
+    fn detects_new_ids_and_keeps_old_ones() {
+        let prev = ids(&["b", "a"]);
+        let (new, merged) = diff_seen(Some(&prev), &ids(&["d", "c", "b"]), 5, true);
+        assert_eq!(new, vec![0, 1]);
+        // "a" scrolled off the listing but stays seen, so it can't re-trigger
+        assert_eq!(merged, ids(&["d", "c", "b", "a"]));
+    }
+
+    #[test]
+    fn seen_list_is_capped() {
+        let prev: Vec<String> = (0..SEEN_CAP).map(|i| format!("old{}", i)).collect();
+        let (new, merged) = diff_seen(Some(&prev), &ids(&["fresh"]), 5, true);
+        assert_eq!(new, vec![0]);
+        assert_eq!(merged.len(), SEEN_CAP);
+        assert_eq!(merged[0], "fresh");
+    }
+
+    #[test]
+    fn new_ids_past_the_limit_stay_unseen() {
+        // Newest first: the two oldest new entries go out, "e" and "d" wait.
+        let prev = ids(&["a"]);
+        let (new, merged) = diff_seen(Some(&prev), &ids(&["e", "d", "c", "b", "a"]), 2, true);
+        assert_eq!(new, vec![2, 3]);
+        assert_eq!(merged, ids(&["c", "b", "a"]));
+        let (new, _) = diff_seen(Some(&merged), &ids(&["e", "d", "c", "b", "a"]), 2, true);
+        assert_eq!(new, vec![0, 1]);
+
+        // Appending playlist: the earliest additions go out first.
+        let (new, merged) = diff_seen(Some(&prev), &ids(&["a", "b", "c", "d"]), 2, false);
+        assert_eq!(new, vec![1, 2]);
+        assert_eq!(merged, ids(&["a", "b", "c"]));
+    }
+}
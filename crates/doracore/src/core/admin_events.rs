@@ -0,0 +1,178 @@
+//! In-process event bus behind the admin dashboard's live stream
+//! (`GET /admin/api/events`).
+//!
+//! Producers — task queue transitions in `SharedStorage`, download progress
+//! updates, the error logger and the alert manager — call [`publish`]; every
+//! open dashboard holds a [`subscribe`] receiver. The bus is a fixed-size
+//! broadcast channel, so a slow dashboard misses events (and is told to
+//! refetch) rather than growing memory. Events cover this process only; the
+//! dashboard keeps its slow polling for anything else.
+
+use std::sync::LazyLock;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::download::progress::DownloadStatus;
+
+/// Events buffered for subscribers that fall behind.
+const CAPACITY: usize = 256;
+/// Error messages are cut to this many characters on the stream.
+const MAX_MESSAGE_CHARS: usize = 300;
+
+/// Something the dashboard should show without waiting for the next poll.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AdminEvent {
+    /// A task entered `status` (`pending`, `leased`, `processing`,
+    /// `uploading`, `completed`, `dead_letter`, `cancelled`).
+    Queue {
+        task_id: String,
+        user_id: Option<i64>,
+        status: &'static str,
+    },
+    /// Download progress of a running task.
+    Progress {
+        task_id: String,
+        /// `starting` / `downloading` / `merging` / `uploading`.
+        phase: &'static str,
+        percent: Option<u8>,
+        speed_mbs: Option<f64>,
+        eta_seconds: Option<u64>,
+    },
+    /// A new `error_log` entry. Not named `error` on the wire: that name is
+    /// taken by `EventSource`'s own connection-error event.
+    #[serde(rename = "error_log")]
+    Error {
+        user_id: Option<i64>,
+        error_type: String,
+        message: String,
+    },
+    /// An alert was sent to the admin chat.
+    Alert {
+        alert_type: String,
+        severity: String,
+        title: String,
+    },
+}
+
+impl AdminEvent {
+    /// SSE event name; matches the serialized `type`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AdminEvent::Queue { .. } => "queue",
+            AdminEvent::Progress { .. } => "progress",
+            AdminEvent::Error { .. } => "error_log",
+            AdminEvent::Alert { .. } => "alert",
+        }
+    }
+
+    pub fn queue(task_id: &str, user_id: Option<i64>, status: &'static str) -> Self {
+        AdminEvent::Queue {
+            task_id: task_id.to_string(),
+            user_id,
+            status,
+        }
+    }
+
+    pub fn error(user_id: Option<i64>, error_type: &str, message: &str) -> Self {
+        AdminEvent::Error {
+            user_id,
+            error_type: error_type.to_string(),
+            message: message.chars().take(MAX_MESSAGE_CHARS).collect(),
+        }
+    }
+
+    /// Progress event for a status update of `task_id`; `None` for terminal
+    /// statuses, which arrive as queue events instead.
+    pub fn progress(task_id: &str, status: &DownloadStatus) -> Option<Self> {
+        let (phase, percent, speed_mbs, eta_seconds) = match status {
+            DownloadStatus::Starting { .. } => ("starting", None, None, None),
+            DownloadStatus::Downloading {
+                progress,
+                speed_mbs,
+                eta_seconds,
+                ..
+            } => ("downloading", Some(*progress), *speed_mbs, *eta_seconds),
+            DownloadStatus::Merging { progress, .. } => ("merging", Some(*progress), None, None),
+            DownloadStatus::Uploading {
+                progress,
+                speed_mbs,
+                eta_seconds,
+                ..
+            } => ("uploading", *progress, *speed_mbs, *eta_seconds),
+            DownloadStatus::Success { .. } | DownloadStatus::Completed { .. } | DownloadStatus::Error { .. } => {
+                return None;
+            }
+        };
+        Some(AdminEvent::Progress {
+            task_id: task_id.to_string(),
+            phase,
+            percent,
+            speed_mbs,
+            eta_seconds,
+        })
+    }
+}
+
+static BUS: LazyLock<broadcast::Sender<AdminEvent>> = LazyLock::new(|| broadcast::channel(CAPACITY).0);
+
+/// Push an event to every open dashboard. Free when nobody is watching.
+pub fn publish(event: AdminEvent) {
+    if BUS.receiver_count() > 0 {
+        let _ = BUS.send(event);
+    }
+}
+
+/// Receiver for a new dashboard connection.
+pub fn subscribe() -> broadcast::Receiver<AdminEvent> {
+    BUS.subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn subscribers_receive_published_events() {
+        publish(AdminEvent::queue("unseen", None, "pending"));
+        let mut rx = subscribe();
+        publish(AdminEvent::queue("t1", Some(42), "processing"));
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            AdminEvent::queue("t1", Some(42), "processing")
+        );
+    }
+
+    #[test]
+    fn events_serialize_with_their_name_as_type() {
+        let event = AdminEvent::error(Some(1), "timeout", &"x".repeat(1000));
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], event.name());
+        assert_eq!(json["message"].as_str().unwrap().len(), MAX_MESSAGE_CHARS);
+    }
+
+    #[test]
+    fn progress_skips_terminal_statuses() {
+        let merging = DownloadStatus::Merging {
+            title: "t".into(),
+            progress: 70,
+            file_format: None,
+            update_count: 1,
+            artist: None,
+        };
+        assert!(matches!(
+            AdminEvent::progress("t1", &merging),
+            Some(AdminEvent::Progress {
+                phase: "merging",
+                percent: Some(70),
+                ..
+            })
+        ));
+        let done = DownloadStatus::Completed {
+            title: "t".into(),
+            file_format: None,
+        };
+        assert_eq!(AdminEvent::progress("t1", &done), None);
+    }
+}
@@ -0,0 +1,383 @@
+//! Admin roles and the permission matrix shared by the web dashboard and the
+//! Telegram admin commands.
+//!
+//! - Users in `ADMIN_IDS` (or `ADMIN_USER_ID` when it is unset) are always
+//!   [`AdminRole::Owner`].
+//! - Other staff get a role from the dashboard (V58 `admin_roles`, in shared
+//!   storage so every instance sees the same grants). Lookups read an
+//!   in-process cache that [`init`] refreshes every [`ROLE_REFRESH`]; changes
+//!   made through [`grant`] / [`revoke`] update it right away, so a revoke on
+//!   another instance takes effect here within one refresh.
+//! - Roles are ordered: each one has the permissions of the roles below it.
+//!   [`Permission::min_role`] is the matrix; [`route_permission`] maps admin
+//!   web routes onto it, Telegram commands name their permission at the call
+//!   site.
+//! - [`check`] records denials of staff members in `admin_audit_log`
+//!   (`permission_denied`).
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, OnceLock, RwLock};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::core::config;
+use crate::storage::SharedStorage;
+use crate::storage::db::{self, AdminRoleGrant};
+
+/// How often the role cache is reloaded from shared storage.
+pub const ROLE_REFRESH: Duration = Duration::from_secs(10);
+
+/// Staff role, lowest to highest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminRole {
+    /// Read-only dashboard: queue, errors, health, alerts, feedback.
+    Viewer,
+    /// Looks users up and handles their problems; no payments or cookies.
+    Support,
+    /// Runs the service: queue, errors, cookies, proxies, diagnostics.
+    Operator,
+    /// Everything, including plans, revenue, broadcasts and roles.
+    Owner,
+}
+
+impl AdminRole {
+    pub const ALL: [AdminRole; 4] = [
+        AdminRole::Viewer,
+        AdminRole::Support,
+        AdminRole::Operator,
+        AdminRole::Owner,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdminRole::Viewer => "viewer",
+            AdminRole::Support => "support",
+            AdminRole::Operator => "operator",
+            AdminRole::Owner => "owner",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|role| role.as_str() == s.trim())
+    }
+
+    pub fn allows(self, permission: Permission) -> bool {
+        self >= permission.min_role()
+    }
+
+    /// Every permission this role has.
+    pub fn permissions(self) -> Vec<Permission> {
+        Permission::ALL.into_iter().filter(|p| self.allows(*p)).collect()
+    }
+}
+
+/// Something an admin can do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Overview, queue, errors, health, alerts, feedback, analytics.
+    ViewDashboard,
+    /// User list, user details and download history.
+    ViewUsers,
+    /// Block users, change their settings, reply to feedback and errors.
+    ManageUsers,
+    /// Retry and cancel tasks, resolve errors, acknowledge alerts.
+    ManageQueue,
+    /// Cookies, browser login, proxies and yt-dlp updates.
+    ManageCookies,
+    /// Diagnostics such as /version, /botapi_speed and health checks.
+    ManageSystem,
+    /// Admin audit log.
+    ViewAudit,
+    /// Revenue, charges and transactions.
+    ViewRevenue,
+    /// Change plans and manage promo codes.
+    ManagePlans,
+    /// Messages to all users.
+    Broadcast,
+    /// Database backups.
+    Backups,
+    /// Grant and revoke admin roles.
+    ManageRoles,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 12] = [
+        Permission::ViewDashboard,
+        Permission::ViewUsers,
+        Permission::ManageUsers,
+        Permission::ManageQueue,
+        Permission::ManageCookies,
+        Permission::ManageSystem,
+        Permission::ViewAudit,
+        Permission::ViewRevenue,
+        Permission::ManagePlans,
+        Permission::Broadcast,
+        Permission::Backups,
+        Permission::ManageRoles,
+    ];
+
+    /// The permission matrix: lowest role that has this permission.
+    pub fn min_role(self) -> AdminRole {
+        match self {
+            Permission::ViewDashboard => AdminRole::Viewer,
+            Permission::ViewUsers | Permission::ManageUsers => AdminRole::Support,
+            Permission::ManageQueue | Permission::ManageCookies | Permission::ManageSystem | Permission::ViewAudit => {
+                AdminRole::Operator
+            }
+            Permission::ViewRevenue
+            | Permission::ManagePlans
+            | Permission::Broadcast
+            | Permission::Backups
+            | Permission::ManageRoles => AdminRole::Owner,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Permission::ViewDashboard => "view_dashboard",
+            Permission::ViewUsers => "view_users",
+            Permission::ManageUsers => "manage_users",
+            Permission::ManageQueue => "manage_queue",
+            Permission::ManageCookies => "manage_cookies",
+            Permission::ManageSystem => "manage_system",
+            Permission::ViewAudit => "view_audit",
+            Permission::ViewRevenue => "view_revenue",
+            Permission::ManagePlans => "manage_plans",
+            Permission::Broadcast => "broadcast",
+            Permission::Backups => "backups",
+            Permission::ManageRoles => "manage_roles",
+        }
+    }
+}
+
+/// Permission needed for an admin web route, by method and the path as
+/// registered with axum. Unknown routes need [`Permission::ManageRoles`], so
+/// a new endpoint is owner-only until it is added here.
+pub fn route_permission(method: &str, path: &str) -> Permission {
+    use Permission::*;
+    let is_get = method == "GET";
+    match path.strip_prefix("/admin/api").unwrap_or(path) {
+        "/admin" if is_get => ViewDashboard,
+        "/users" | "/downloads" | "/users/{id}/details" if is_get => ViewUsers,
+        "/users/{id}/plan" => ManagePlans,
+        "/users/{id}/block" | "/users/{id}/settings" => ManageUsers,
+        "/queue" | "/errors" | "/feedback" | "/alerts" | "/health" | "/analytics" | "/subscriptions" | "/counts"
+        | "/events"
+            if is_get =>
+        {
+            ViewDashboard
+        }
+        "/queue/{id}/retry" | "/queue/{id}/cancel" | "/queue/bulk-cancel" => ManageQueue,
+        "/errors/{id}/resolve" | "/errors/{id}/retry" | "/errors/bulk-resolve" => ManageQueue,
+        "/alerts/{id}/acknowledge" => ManageQueue,
+        "/errors/{id}/notify" | "/feedback/{id}/status" | "/subscriptions/{id}/toggle" => ManageUsers,
+        "/broadcast" => Broadcast,
+        "/revenue" if is_get => ViewRevenue,
+        "/audit" if is_get => ViewAudit,
+        "/roles" | "/roles/{id}/revoke" | "/sessions" | "/sessions/{id}/revoke" => ManageRoles,
+        _ => ManageRoles,
+    }
+}
+
+static STORAGE: OnceLock<Arc<SharedStorage>> = OnceLock::new();
+static GRANTS: LazyLock<RwLock<HashMap<i64, AdminRole>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Owners configured through `ADMIN_IDS` (or `ADMIN_USER_ID` when it is
+/// unset), see [`config::admin::OWNER_IDS`].
+pub fn is_configured_owner(user_id: i64) -> bool {
+    user_id != 0 && config::admin::OWNER_IDS.contains(&user_id)
+}
+
+/// Role of `user_id`, or `None` for non-staff.
+pub fn role_of(user_id: i64) -> Option<AdminRole> {
+    if is_configured_owner(user_id) {
+        return Some(AdminRole::Owner);
+    }
+    GRANTS.read().ok()?.get(&user_id).copied()
+}
+
+/// Whether `user_id` may do `permission`, without recording anything.
+pub fn allows(user_id: i64, permission: Permission) -> bool {
+    role_of(user_id).is_some_and(|role| role.allows(permission))
+}
+
+/// Whether `user_id` may do `permission`. Denials of staff members are
+/// written to `admin_audit_log` with `action` (a route or command) as the
+/// details; non-staff are just refused.
+pub fn check(user_id: i64, permission: Permission, action: &str) -> bool {
+    let Some(role) = role_of(user_id) else {
+        return false;
+    };
+    if role.allows(permission) {
+        return true;
+    }
+    log::warn!(
+        "Admin {} ({}) denied {} for {}",
+        user_id,
+        role.as_str(),
+        permission.as_str(),
+        action
+    );
+    if let Some(storage) = STORAGE.get() {
+        let pool = storage.sqlite_pool();
+        let result = db::get_connection(&pool).map_err(anyhow::Error::from).and_then(|conn| {
+            db::log_admin_audit(
+                &conn,
+                user_id,
+                "permission_denied",
+                "permission",
+                permission.as_str(),
+                Some(&format!("{} ({})", action, role.as_str())),
+            )
+            .map_err(Into::into)
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to record permission denial for {}: {}", user_id, e);
+        }
+    }
+    false
+}
+
+/// Load granted roles from shared storage and keep reloading them every
+/// [`ROLE_REFRESH`]. Call once at startup, inside the runtime. On Postgres,
+/// roles still in this instance's SQLite file are moved over first.
+pub async fn init(shared_storage: Arc<SharedStorage>) {
+    match shared_storage.import_sqlite_admin_roles().await {
+        Ok(0) => {}
+        Ok(n) => log::info!("Moved {} admin role(s) from SQLite to Postgres", n),
+        Err(e) => log::warn!("Could not move admin roles to Postgres: {}", e),
+    }
+    if let Err(e) = reload(&shared_storage).await {
+        log::error!("Failed to load admin roles: {:#}", e);
+    }
+    if STORAGE.set(Arc::clone(&shared_storage)).is_err() {
+        return;
+    }
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(ROLE_REFRESH).await;
+            if let Err(e) = reload(&shared_storage).await {
+                log::warn!("Failed to refresh admin roles: {:#}", e);
+            }
+        }
+    });
+}
+
+async fn reload(shared_storage: &SharedStorage) -> Result<()> {
+    let grants = shared_storage.list_admin_roles().await?;
+    if let Ok(mut cache) = GRANTS.write() {
+        *cache = parse_grants(grants);
+    }
+    Ok(())
+}
+
+fn parse_grants(grants: Vec<AdminRoleGrant>) -> HashMap<i64, AdminRole> {
+    grants
+        .into_iter()
+        .filter_map(|grant| match AdminRole::parse(&grant.role) {
+            Some(role) => Some((grant.user_id, role)),
+            None => {
+                log::warn!("Ignoring unknown admin role '{}' for {}", grant.role, grant.user_id);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Grant `role` to `user_id` and update the cache.
+pub async fn grant(shared_storage: &SharedStorage, user_id: i64, role: AdminRole, granted_by: i64) -> Result<()> {
+    shared_storage
+        .set_admin_role(user_id, role.as_str(), granted_by)
+        .await?;
+    if let Ok(mut cache) = GRANTS.write() {
+        cache.insert(user_id, role);
+    }
+    Ok(())
+}
+
+/// Revoke the role of `user_id` and update the cache. Returns whether there
+/// was one.
+pub async fn revoke(shared_storage: &SharedStorage, user_id: i64) -> Result<bool> {
+    let removed = shared_storage.remove_admin_role(user_id).await?;
+    if let Ok(mut cache) = GRANTS.write() {
+        cache.remove(&user_id);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn support_can_look_up_users_but_not_payments_or_cookies() {
+        let support = AdminRole::Support;
+        assert!(support.allows(Permission::ViewDashboard));
+        assert!(support.allows(Permission::ViewUsers));
+        assert!(support.allows(Permission::ManageUsers));
+        assert!(!support.allows(Permission::ManagePlans));
+        assert!(!support.allows(Permission::ViewRevenue));
+        assert!(!support.allows(Permission::ManageCookies));
+        assert_eq!(AdminRole::Owner.permissions().len(), Permission::ALL.len());
+        assert_eq!(AdminRole::Viewer.permissions(), vec![Permission::ViewDashboard]);
+    }
+
+    #[test]
+    fn roles_round_trip_through_their_names() {
+        for role in AdminRole::ALL {
+            assert_eq!(AdminRole::parse(role.as_str()), Some(role));
+        }
+        assert_eq!(AdminRole::parse("admin"), None);
+    }
+
+    #[test]
+    fn routes_map_onto_the_matrix() {
+        assert_eq!(route_permission("GET", "/admin"), Permission::ViewDashboard);
+        assert_eq!(route_permission("GET", "/admin/api/users"), Permission::ViewUsers);
+        assert_eq!(
+            route_permission("POST", "/admin/api/users/{id}/plan"),
+            Permission::ManagePlans
+        );
+        assert_eq!(
+            route_permission("POST", "/admin/api/queue/{id}/retry"),
+            Permission::ManageQueue
+        );
+        assert_eq!(route_permission("GET", "/admin/api/revenue"), Permission::ViewRevenue);
+        assert_eq!(route_permission("POST", "/admin/api/revenue"), Permission::ManageRoles);
+        assert_eq!(route_permission("GET", "/admin/api/sessions"), Permission::ManageRoles);
+        assert_eq!(
+            route_permission("GET", "/admin/api/something-new"),
+            Permission::ManageRoles
+        );
+    }
+
+    #[tokio::test]
+    async fn grants_update_the_cache() {
+        // Removed with the directory when the test ends.
+        let dir = tempfile::TempDir::new().unwrap();
+        let pool = db::create_pool(dir.path().join("admin_roles.db").to_string_lossy().as_ref()).unwrap();
+        let storage = SharedStorage::Sqlite {
+            db_pool: Arc::new(pool),
+        };
+        let user_id = -4242;
+
+        grant(&storage, user_id, AdminRole::Support, 1).await.unwrap();
+        assert_eq!(role_of(user_id), Some(AdminRole::Support));
+        assert!(allows(user_id, Permission::ViewUsers));
+        assert!(!allows(user_id, Permission::Broadcast));
+
+        // A revoke made elsewhere shows up on the next reload
+        storage.remove_admin_role(user_id).await.unwrap();
+        assert_eq!(role_of(user_id), Some(AdminRole::Support));
+        reload(&storage).await.unwrap();
+        assert_eq!(role_of(user_id), None);
+
+        grant(&storage, user_id, AdminRole::Viewer, 1).await.unwrap();
+        assert!(revoke(&storage, user_id).await.unwrap());
+        assert_eq!(role_of(user_id), None);
+    }
+}
@@ -75,6 +75,16 @@ pub static DOWNLOAD_FOLDER: LazyLock<String> = LazyLock::new(|| {
     })
 });
 
+/// File name template for entries of history archives (ZIP downloads)
+/// Read from ARCHIVE_NAME_TEMPLATE environment variable
+/// Default: {artist} - {title}; use e.g. {artist}/{album|Singles}/{title} for folders
+pub static ARCHIVE_NAME_TEMPLATE: LazyLock<String> = LazyLock::new(|| {
+    env::var("ARCHIVE_NAME_TEMPLATE")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| crate::download::filename_template::DEFAULT_TEMPLATE.to_string())
+});
+
 /// Temporary files directory for processing (clips, cuts, exports, etc.)
 /// Read from TEMP_FILES_DIR environment variable
 /// Defaults to /tmp on production, supports tilde (~) expansion
@@ -97,6 +107,17 @@ pub static DATABASE_DRIVER: LazyLock<DatabaseDriver> = LazyLock::new(DatabaseDri
 /// Read from DATABASE_URL environment variable
 pub static DATABASE_URL: LazyLock<Option<String>> = LazyLock::new(|| env::var("DATABASE_URL").ok());
 
+/// Refuse to start when the live database schema drifts from the embedded
+/// migrations; `false` only logs the drift
+/// Read from SCHEMA_DRIFT_STRICT environment variable
+/// Default: true
+pub static SCHEMA_DRIFT_STRICT: LazyLock<bool> = LazyLock::new(|| {
+    env::var("SCHEMA_DRIFT_STRICT")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(true)
+});
+
 /// Redis connection string for distributed cooldowns and cache coordination
 /// Read from REDIS_URL environment variable
 pub static REDIS_URL: LazyLock<Option<String>> = LazyLock::new(|| env::var("REDIS_URL").ok());
@@ -458,6 +479,18 @@ pub mod admin {
             .unwrap_or(0)
     });
 
+    /// Configured owners: `ADMIN_IDS` if set, otherwise `ADMIN_USER_ID`.
+    /// Admin checks, notifications and the dashboard all resolve owners here.
+    pub static OWNER_IDS: LazyLock<Vec<i64>> = LazyLock::new(|| {
+        if !ADMIN_IDS.is_empty() {
+            ADMIN_IDS.clone()
+        } else if *ADMIN_USER_ID != 0 {
+            vec![*ADMIN_USER_ID]
+        } else {
+            Vec::new()
+        }
+    });
+
     /// Maximum retry attempts for failed tasks before giving up
     pub const MAX_TASK_RETRIES: i32 = 5;
 }
@@ -491,6 +524,187 @@ pub mod subscription {
     pub const SUBSCRIPTION_PERIOD_SECONDS: u32 = 2592000; // 30 days
 }
 
+/// Pay-per-use credits (bought in Star packs, spent on premium actions)
+pub mod credits {
+    use std::env;
+    use std::sync::LazyLock;
+
+    use crate::storage::db::{CreditAction, CreditCharge};
+
+    fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+        env::var(key).ok().and_then(|s| s.parse().ok()).unwrap_or(default)
+    }
+
+    /// Credit packs offered for Stars, as `(credits, stars)` pairs
+    /// Read from CREDIT_PACKS environment variable (`credits:stars,...`)
+    /// Default: 10:25,50:100,150:250
+    pub static PACKS: LazyLock<Vec<(i64, u32)>> = LazyLock::new(|| {
+        let raw = env::var("CREDIT_PACKS").unwrap_or_else(|_| "10:25,50:100,150:250".to_string());
+        raw.split(',')
+            .filter_map(|pack| {
+                let (credits, stars) = pack.trim().split_once(':')?;
+                Some((credits.parse().ok()?, stars.parse().ok()?))
+            })
+            .filter(|&(credits, stars)| credits > 0 && stars > 0)
+            .collect()
+    });
+
+    /// Stars price of the pack with exactly `credits` credits, if offered
+    pub fn pack_price(credits: i64) -> Option<u32> {
+        PACKS.iter().find(|&&(c, _)| c == credits).map(|&(_, stars)| stars)
+    }
+
+    /// Credits per high-res (1440p+) video download beyond the free allowance
+    /// Read from CREDITS_HIGHRES_COST environment variable
+    /// Default: 2
+    pub static HIGHRES_COST: LazyLock<i64> = LazyLock::new(|| env_or("CREDITS_HIGHRES_COST", 2));
+
+    /// Free high-res downloads per user per UTC day
+    /// Read from CREDITS_FREE_HIGHRES_PER_DAY environment variable
+    /// Default: 1
+    pub static FREE_HIGHRES_PER_DAY: LazyLock<u32> = LazyLock::new(|| env_or("CREDITS_FREE_HIGHRES_PER_DAY", 1));
+
+    /// Credits per stories render beyond the free allowance
+    /// Read from CREDITS_STORIES_COST environment variable
+    /// Default: 3
+    pub static STORIES_COST: LazyLock<i64> = LazyLock::new(|| env_or("CREDITS_STORIES_COST", 3));
+
+    /// Free stories renders per user per UTC day
+    /// Read from CREDITS_FREE_STORIES_PER_DAY environment variable
+    /// Default: 1
+    pub static FREE_STORIES_PER_DAY: LazyLock<u32> = LazyLock::new(|| env_or("CREDITS_FREE_STORIES_PER_DAY", 1));
+
+    /// Credits per album split (selected carousel items) beyond the free allowance
+    /// Read from CREDITS_ALBUM_SPLIT_COST environment variable
+    /// Default: 1
+    pub static ALBUM_SPLIT_COST: LazyLock<i64> = LazyLock::new(|| env_or("CREDITS_ALBUM_SPLIT_COST", 1));
+
+    /// Free album splits per user per UTC day
+    /// Read from CREDITS_FREE_ALBUM_SPLIT_PER_DAY environment variable
+    /// Default: 3
+    pub static FREE_ALBUM_SPLIT_PER_DAY: LazyLock<u32> =
+        LazyLock::new(|| env_or("CREDITS_FREE_ALBUM_SPLIT_PER_DAY", 3));
+
+    /// Configured price tag for a premium action
+    pub fn charge_for(action: CreditAction) -> CreditCharge {
+        let (cost, free_per_day) = match action {
+            CreditAction::Highres => (*HIGHRES_COST, *FREE_HIGHRES_PER_DAY),
+            CreditAction::Stories => (*STORIES_COST, *FREE_STORIES_PER_DAY),
+            CreditAction::AlbumSplit => (*ALBUM_SPLIT_COST, *FREE_ALBUM_SPLIT_PER_DAY),
+        };
+        CreditCharge {
+            action,
+            cost,
+            free_per_day,
+        }
+    }
+}
+
+/// Promo codes, gift subscriptions and referral rewards
+pub mod promo {
+    use std::env;
+    use std::sync::LazyLock;
+
+    /// Credits granted to both the referrer and the invitee after the
+    /// invitee's first successful download
+    /// Read from REFERRAL_REWARD_CREDITS environment variable
+    /// Default: 5
+    pub static REFERRAL_REWARD_CREDITS: LazyLock<i64> = LazyLock::new(|| {
+        env::var("REFERRAL_REWARD_CREDITS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(5)
+    });
+
+    /// Days of plan a gift subscription grants (one billing period)
+    pub const GIFT_DAYS: i32 = (super::subscription::SUBSCRIPTION_PERIOD_SECONDS / 86_400) as i32;
+}
+
+/// Database backup configuration
+pub mod backup {
+    use std::env;
+    use std::path::PathBuf;
+    use std::sync::LazyLock;
+
+    use secrecy::SecretString;
+
+    fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+        env::var(key).ok().and_then(|s| s.parse().ok()).unwrap_or(default)
+    }
+
+    fn env_opt(key: &str) -> Option<String> {
+        env::var(key).ok().filter(|s| !s.trim().is_empty())
+    }
+
+    /// Directory holding local backup sets
+    /// Read from BACKUP_DIR environment variable
+    /// Default: `backups/` next to DATABASE_PATH
+    pub static DIR: LazyLock<PathBuf> = LazyLock::new(|| match env_opt("BACKUP_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => std::path::Path::new(super::DATABASE_PATH.as_str())
+            .parent()
+            .map(|p| p.join("backups"))
+            .unwrap_or_else(|| PathBuf::from("backups")),
+    });
+
+    /// Hours between scheduled backups (0 disables the scheduler)
+    /// Read from BACKUP_INTERVAL_HOURS environment variable
+    /// Default: 24
+    pub static INTERVAL_HOURS: LazyLock<u64> = LazyLock::new(|| env_or("BACKUP_INTERVAL_HOURS", 24));
+
+    /// Daily backups kept by the GFS retention policy
+    /// Read from BACKUP_KEEP_DAILY environment variable
+    /// Default: 7
+    pub static KEEP_DAILY: LazyLock<usize> = LazyLock::new(|| env_or("BACKUP_KEEP_DAILY", 7));
+
+    /// Weekly backups kept by the GFS retention policy
+    /// Read from BACKUP_KEEP_WEEKLY environment variable
+    /// Default: 4
+    pub static KEEP_WEEKLY: LazyLock<usize> = LazyLock::new(|| env_or("BACKUP_KEEP_WEEKLY", 4));
+
+    /// Monthly backups kept by the GFS retention policy
+    /// Read from BACKUP_KEEP_MONTHLY environment variable
+    /// Default: 6
+    pub static KEEP_MONTHLY: LazyLock<usize> = LazyLock::new(|| env_or("BACKUP_KEEP_MONTHLY", 6));
+
+    /// `age` recipient (public key) used to encrypt backup artifacts
+    /// Read from BACKUP_AGE_RECIPIENT environment variable
+    /// Default: none (artifacts are only compressed)
+    pub static AGE_RECIPIENT: LazyLock<Option<String>> = LazyLock::new(|| env_opt("BACKUP_AGE_RECIPIENT"));
+
+    /// Path to the `age` identity file used to decrypt on restore
+    /// Read from BACKUP_AGE_IDENTITY environment variable
+    pub static AGE_IDENTITY: LazyLock<Option<PathBuf>> =
+        LazyLock::new(|| env_opt("BACKUP_AGE_IDENTITY").map(PathBuf::from));
+
+    /// S3-compatible endpoint for off-site copies, e.g. `https://s3.eu-central-1.amazonaws.com`
+    /// or `http://localhost:9000` for MinIO. Uploads are disabled unless this,
+    /// the bucket and both keys are set.
+    /// Read from BACKUP_S3_ENDPOINT environment variable
+    pub static S3_ENDPOINT: LazyLock<Option<String>> = LazyLock::new(|| env_opt("BACKUP_S3_ENDPOINT"));
+
+    /// Read from BACKUP_S3_BUCKET environment variable
+    pub static S3_BUCKET: LazyLock<Option<String>> = LazyLock::new(|| env_opt("BACKUP_S3_BUCKET"));
+
+    /// Read from BACKUP_S3_REGION environment variable
+    /// Default: us-east-1
+    pub static S3_REGION: LazyLock<String> =
+        LazyLock::new(|| env_opt("BACKUP_S3_REGION").unwrap_or_else(|| "us-east-1".to_string()));
+
+    /// Key prefix for backup sets inside the bucket
+    /// Read from BACKUP_S3_PREFIX environment variable
+    /// Default: doradura/
+    pub static S3_PREFIX: LazyLock<String> =
+        LazyLock::new(|| env_opt("BACKUP_S3_PREFIX").unwrap_or_else(|| "doradura/".to_string()));
+
+    /// Read from BACKUP_S3_ACCESS_KEY environment variable
+    pub static S3_ACCESS_KEY: LazyLock<Option<String>> = LazyLock::new(|| env_opt("BACKUP_S3_ACCESS_KEY"));
+
+    /// Read from BACKUP_S3_SECRET_KEY environment variable
+    pub static S3_SECRET_KEY: LazyLock<Option<SecretString>> =
+        LazyLock::new(|| env_opt("BACKUP_S3_SECRET_KEY").map(SecretString::from));
+}
+
 /// Metrics and monitoring configuration
 pub mod metrics {
     use std::env;
@@ -802,6 +1016,118 @@ pub mod share {
     }
 }
 
+/// Signed web links for files over the Telegram upload caps
+pub mod hosted_links {
+    use std::env;
+    use std::sync::LazyLock;
+
+    /// Publish oversized files as web links instead of failing the download.
+    /// Read from HOSTED_LINKS_ENABLED environment variable.
+    /// Default: true (still requires WEB_BASE_URL)
+    pub static ENABLED: LazyLock<bool> = LazyLock::new(|| {
+        env::var("HOSTED_LINKS_ENABLED")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(true)
+    });
+
+    /// How long a link stays valid, in hours.
+    /// Read from HOSTED_LINK_TTL_HOURS environment variable.
+    /// Default: 24
+    pub static TTL_HOURS: LazyLock<u64> = LazyLock::new(|| {
+        env::var("HOSTED_LINK_TTL_HOURS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|h| *h > 0)
+            .unwrap_or(24)
+    });
+
+    /// Largest file we are willing to host, in MB.
+    /// Read from HOSTED_LINK_MAX_MB environment variable.
+    /// Default: 4096 (4 GB)
+    pub static MAX_MB: LazyLock<u64> = LazyLock::new(|| {
+        env::var("HOSTED_LINK_MAX_MB")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(4096)
+    });
+
+    /// HMAC key for link signatures.
+    /// Read from HOSTED_LINK_SECRET environment variable. When unset, a key is
+    /// derived from BOT_TOKEN so links survive restarts and work on every
+    /// instance without extra configuration.
+    pub static SECRET: LazyLock<Option<String>> =
+        LazyLock::new(|| env::var("HOSTED_LINK_SECRET").ok().filter(|s| !s.trim().is_empty()));
+
+    /// True when links can actually be served (enabled and a public base URL).
+    pub fn is_available() -> bool {
+        *ENABLED && super::share::base_url().is_some()
+    }
+
+    pub fn ttl() -> std::time::Duration {
+        std::time::Duration::from_secs(*TTL_HOURS * 3600)
+    }
+
+    pub fn max_bytes() -> u64 {
+        *MAX_MB * 1024 * 1024
+    }
+}
+
+/// Public REST API (/api/v1) configuration
+pub mod api {
+    use std::env;
+    use std::sync::LazyLock;
+
+    use crate::core::types::Plan;
+
+    fn rpm_from_env(var: &str, default: u32) -> u32 {
+        env::var(var)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(default)
+    }
+
+    /// Serve /api/v1 at all.
+    /// Read from API_ENABLED environment variable.
+    /// Default: true (keys still have to be created with /apikey)
+    pub static ENABLED: LazyLock<bool> = LazyLock::new(|| {
+        env::var("API_ENABLED")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(true)
+    });
+
+    /// Requests per minute per key on the free plan.
+    /// Read from API_RPM_FREE environment variable.
+    /// Default: 30
+    pub static RPM_FREE: LazyLock<u32> = LazyLock::new(|| rpm_from_env("API_RPM_FREE", 30));
+
+    /// Requests per minute per key on Premium.
+    /// Read from API_RPM_PREMIUM environment variable.
+    /// Default: 120
+    pub static RPM_PREMIUM: LazyLock<u32> = LazyLock::new(|| rpm_from_env("API_RPM_PREMIUM", 120));
+
+    /// Requests per minute per key on VIP.
+    /// Read from API_RPM_VIP environment variable.
+    /// Default: 600
+    pub static RPM_VIP: LazyLock<u32> = LazyLock::new(|| rpm_from_env("API_RPM_VIP", 600));
+
+    /// Active keys a user may hold at once.
+    pub const MAX_KEYS_PER_USER: usize = 5;
+
+    /// How long the request log is kept, in days.
+    pub const REQUEST_LOG_RETENTION_DAYS: i64 = 90;
+
+    pub fn requests_per_minute(plan: Plan) -> u32 {
+        match plan {
+            Plan::Free => *RPM_FREE,
+            Plan::Premium => *RPM_PREMIUM,
+            Plan::Vip => *RPM_VIP,
+        }
+    }
+}
+
 /// Content watcher / subscription monitoring configuration
 pub mod watcher {
     use std::env;
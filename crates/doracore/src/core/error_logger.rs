@@ -3,6 +3,7 @@
 //! Provides centralized error logging with user context.
 //! Errors are stored in the database for monitoring and reporting.
 
+use crate::core::admin_events::{self, AdminEvent};
 use crate::storage::SharedStorage;
 use std::sync::Arc;
 
@@ -89,6 +90,8 @@ impl ErrorLogger {
         url: Option<&str>,
         context: Option<&str>,
     ) {
+        admin_events::publish(AdminEvent::error(user.user_id, error_type.as_str(), error_message));
+
         let shared_storage = Arc::clone(&self.shared_storage);
         let username = user.username.clone();
         let error_type_str = error_type.as_str().to_string();
@@ -0,0 +1,274 @@
+//! Signed, expiring web links for files too large for the messenger.
+//!
+//! When a finished download exceeds the platform's upload caps, the file is
+//! moved into `DOWNLOAD_FOLDER/hosted/`, registered in `hosted_files`, and
+//! served by the web server at `/d/{id}?exp=..&sig=..`. The signature is an
+//! HMAC-SHA256 over `id:exp`, so links can't be extended or forged. The
+//! caller schedules file and row removal at `expires_at`; [`purge_expired`]
+//! catches links whose scheduled cleanup was lost to a restart.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use hmac::{Hmac, Mac};
+use secrecy::ExposeSecret;
+use sha2::{Digest, Sha256};
+
+use crate::core::config;
+use crate::core::validation::sanitize_filename;
+use crate::storage::SharedStorage;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A published file, ready to hand to the user.
+#[derive(Debug, Clone)]
+pub struct HostedLink {
+    pub id: String,
+    /// Absolute, signed URL.
+    pub url: String,
+    /// Where the file lives once published, under [`hosted_dir`].
+    pub file_path: String,
+    /// Where the file was before publishing; it stays there until [`activate`].
+    pub source_path: String,
+    pub file_size: u64,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+fn signing_key() -> Vec<u8> {
+    match config::hosted_links::SECRET.as_deref() {
+        Some(secret) => secret.as_bytes().to_vec(),
+        None => {
+            let mut hasher = Sha256::new();
+            hasher.update(b"hosted-links:");
+            hasher.update(config::BOT_TOKEN.expose_secret().as_bytes());
+            hasher.finalize().to_vec()
+        }
+    }
+}
+
+fn mac_for(key: &[u8], id: &str, expires_at: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take key of any size");
+    mac.update(format!("{}:{}", id, expires_at).as_bytes());
+    mac
+}
+
+fn sign_with(key: &[u8], id: &str, expires_at: i64) -> String {
+    hex::encode(mac_for(key, id, expires_at).finalize().into_bytes())
+}
+
+fn verify_with(key: &[u8], id: &str, expires_at: i64, signature: &str, now: i64) -> bool {
+    if expires_at <= now {
+        return false;
+    }
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    mac_for(key, id, expires_at).verify_slice(&signature).is_ok()
+}
+
+/// Signature for the `sig` query parameter of a link.
+pub fn sign(id: &str, expires_at: i64) -> String {
+    sign_with(&signing_key(), id, expires_at)
+}
+
+/// Check a link's signature and that it hasn't expired yet.
+pub fn verify(id: &str, expires_at: i64, signature: &str) -> bool {
+    verify_with(
+        &signing_key(),
+        id,
+        expires_at,
+        signature,
+        chrono::Utc::now().timestamp(),
+    )
+}
+
+/// Absolute, signed `/d/{id}` URL valid until `expires_at`, or `None` without
+/// a public base URL.
+pub fn link_url(id: &str, expires_at: i64) -> Option<String> {
+    let base_url = config::share::base_url()?;
+    Some(format!(
+        "{}/d/{}?exp={}&sig={}",
+        base_url.trim_end_matches('/'),
+        id,
+        expires_at,
+        sign(id, expires_at)
+    ))
+}
+
+/// Where hosted files live. A subdirectory, so the downloads-folder sweep
+/// (files only, one level deep) leaves them alone until their link expires.
+pub fn hosted_dir() -> PathBuf {
+    PathBuf::from(shellexpand::tilde(&*config::DOWNLOAD_FOLDER).into_owned()).join("hosted")
+}
+
+/// MIME type announced for a hosted file, by extension.
+pub fn content_type_for(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("mp3") => "audio/mpeg",
+        Some("m4a") => "audio/mp4",
+        Some("flac") => "audio/flac",
+        Some("wav") => "audio/wav",
+        Some("ogg" | "opus") => "audio/ogg",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("mkv") => "video/x-matroska",
+        Some("zip") => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Move into `dest`, copying when the hosted dir is on another filesystem.
+async fn move_file(src: &Path, dest: &Path) -> Result<()> {
+    if fs_err::tokio::rename(src, dest).await.is_ok() {
+        return Ok(());
+    }
+    fs_err::tokio::copy(src, dest).await?;
+    let _ = fs_err::tokio::remove_file(src).await;
+    Ok(())
+}
+
+/// Publish `file_path` for `user_id` and return its signed link.
+///
+/// The file is moved out of the regular download cleanup's reach, so the
+/// caller's `schedule_cleanup` on the original path becomes a no-op; the
+/// caller schedules cleanup of `HostedLink::file_path` at `expires_at`.
+pub async fn publish(
+    shared_storage: &Arc<SharedStorage>,
+    user_id: i64,
+    file_path: &str,
+    display_name: &str,
+) -> Result<HostedLink> {
+    let link = prepare(shared_storage, user_id, file_path, display_name).await?;
+    if let Err(e) = activate(&link).await {
+        discard(shared_storage, &link).await;
+        return Err(e);
+    }
+    Ok(link)
+}
+
+/// Register `file_path` for `user_id` and sign its link, but leave the file
+/// where it is until [`activate`]. For callers that must deliver the link
+/// first and still need the original file if that fails; [`discard`] drops
+/// a link that never went out.
+pub async fn prepare(
+    shared_storage: &Arc<SharedStorage>,
+    user_id: i64,
+    file_path: &str,
+    display_name: &str,
+) -> Result<HostedLink> {
+    if !config::hosted_links::is_available() {
+        bail!("hosted links are disabled or WEB_BASE_URL is not set");
+    }
+    let src = Path::new(file_path);
+    let file_size = fs_err::tokio::metadata(src).await?.len();
+    if file_size > config::hosted_links::max_bytes() {
+        bail!(
+            "file is {} MB, hosting limit is {} MB",
+            file_size / (1024 * 1024),
+            *config::hosted_links::MAX_MB
+        );
+    }
+
+    let ext = src.extension().and_then(|e| e.to_str()).unwrap_or("bin");
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let dir = hosted_dir();
+    fs_err::tokio::create_dir_all(&dir).await?;
+    let dest = dir.join(format!("{}.{}", id, ext));
+
+    let name = sanitize_filename(display_name.trim());
+    let file_name = if name.is_empty() {
+        format!("download.{}", ext)
+    } else {
+        format!("{}.{}", name, ext)
+    };
+    let ttl = chrono::Duration::from_std(config::hosted_links::ttl()).unwrap_or_else(|_| chrono::Duration::hours(24));
+    let expires_at = chrono::Utc::now() + ttl;
+
+    let dest_str = dest.to_string_lossy();
+    shared_storage
+        .create_hosted_file(
+            &id,
+            user_id,
+            &dest_str,
+            &file_name,
+            content_type_for(&dest),
+            file_size as i64,
+            expires_at.timestamp(),
+        )
+        .await?;
+
+    let url = link_url(&id, expires_at.timestamp()).unwrap_or_default();
+    Ok(HostedLink {
+        id,
+        url,
+        file_path: dest_str.into_owned(),
+        source_path: file_path.to_string(),
+        file_size,
+        expires_at,
+    })
+}
+
+/// Move a [`prepare`]d link's file into [`hosted_dir`] so the link serves it.
+pub async fn activate(link: &HostedLink) -> Result<()> {
+    let dest = Path::new(&link.file_path);
+    if let Err(e) = move_file(Path::new(&link.source_path), dest).await {
+        let _ = fs_err::tokio::remove_file(dest).await;
+        return Err(e).with_context(|| format!("moving {} into {}", link.source_path, hosted_dir().display()));
+    }
+    Ok(())
+}
+
+/// Drop the row of a link that was never [`activate`]d. The file stays at
+/// `HostedLink::source_path`.
+pub async fn discard(shared_storage: &SharedStorage, link: &HostedLink) {
+    if let Err(e) = shared_storage.delete_hosted_file(&link.id).await {
+        log::warn!("Failed to drop unused hosted file {}: {}", link.id, e);
+    }
+}
+
+/// Delete expired hosted files (rows and bytes). Returns how many went.
+///
+/// A safety net for links whose scheduled cleanup didn't run because the
+/// process restarted before `expires_at`.
+pub async fn purge_expired(shared_storage: &SharedStorage) -> Result<usize> {
+    let paths = shared_storage.take_expired_hosted_files().await?;
+    for path in &paths {
+        if let Err(e) = fs_err::tokio::remove_file(path).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            log::warn!("Failed to delete hosted file: {}", e);
+        }
+    }
+    Ok(paths.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_round_trip() {
+        let key = b"test-key";
+        let sig = sign_with(key, "abc", 2_000);
+        assert!(verify_with(key, "abc", 2_000, &sig, 1_000));
+        // Expired, tampered expiry, other id, other key, garbage
+        assert!(!verify_with(key, "abc", 2_000, &sig, 2_000));
+        assert!(!verify_with(key, "abc", 3_000, &sig, 1_000));
+        assert!(!verify_with(key, "abd", 2_000, &sig, 1_000));
+        assert!(!verify_with(b"other", "abc", 2_000, &sig, 1_000));
+        assert!(!verify_with(key, "abc", 2_000, "not-hex", 1_000));
+    }
+
+    #[test]
+    fn content_types_by_extension() {
+        assert_eq!(content_type_for(Path::new("/x/a.MP3")), "audio/mpeg");
+        assert_eq!(content_type_for(Path::new("/x/a.mp4")), "video/mp4");
+        assert_eq!(content_type_for(Path::new("/x/a")), "application/octet-stream");
+    }
+}
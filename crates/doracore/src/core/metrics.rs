@@ -407,6 +407,15 @@ metric!(
         labels = ["message_type"]
 );
 
+metric!(
+    /// Public REST API calls
+    /// Labels: endpoint (route template), status (HTTP status code)
+    pub API_REQUESTS_TOTAL: CounterVec =
+        "doradura_api_requests_total",
+        "Total number of public API requests",
+        labels = ["endpoint", "status"]
+);
+
 metric!(
     /// Total registered users
     pub TOTAL_USERS: Gauge =
@@ -720,6 +729,7 @@ pub fn init_metrics() {
     let _ = &*MESSAGE_TYPES_TOTAL;
     let _ = &*TOTAL_USERS;
     let _ = &*USERS_BY_PLAN;
+    let _ = &*API_REQUESTS_TOTAL;
 
     // Initialize format request counters
     FORMAT_REQUESTS_TOTAL.with_label_values(&["mp3", "free"]);
@@ -852,6 +862,13 @@ pub fn record_command(command: &str) {
     COMMAND_USAGE_TOTAL.with_label_values(&[command]).inc();
 }
 
+/// Helper function to record a public API call
+pub fn record_api_request(endpoint: &str, status: u16) {
+    API_REQUESTS_TOTAL
+        .with_label_values(&[endpoint, &status.to_string()])
+        .inc();
+}
+
 /// Helper function to record format request
 pub fn record_format_request(format: &str, plan: &str) {
     FORMAT_REQUESTS_TOTAL.with_label_values(&[format, plan]).inc();
@@ -1,5 +1,7 @@
 //! Core utilities, configuration, and common functionality
 
+pub mod admin_events;
+pub mod admin_roles;
 pub mod categorizer;
 pub mod config;
 pub mod copyright;
@@ -7,12 +9,14 @@ pub mod country;
 pub mod disk;
 pub mod error;
 pub mod error_logger;
+pub mod hosted_links;
 pub mod llm;
 pub mod logging;
 pub mod metrics;
 pub mod metrics_server;
 pub mod odesli;
 pub mod process;
+pub mod public_api;
 pub mod share;
 pub mod types;
 pub mod upload_limits;
@@ -0,0 +1,166 @@
+//! Glue between the public REST API (`core::web::api`) and the download worker.
+//!
+//! - API keys: `dora_` + 64 hex chars, generated here and stored hashed.
+//! - Jobs are ordinary `task_queue` rows plus an `api_jobs` row. The worker
+//!   downloads them like any other task but, instead of uploading to Telegram,
+//!   publishes the file through [`hosted_links`](crate::core::hosted_links)
+//!   and records the result with [`deliver`].
+//! - Live progress is kept in-process so the SSE stream can show percentages
+//!   when the job runs on this instance; the task_queue status covers the rest.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+
+use crate::core::hosted_links::{self, HostedLink};
+use crate::download::progress::DownloadStatus;
+use crate::storage::SharedStorage;
+
+/// Prefix of every raw API key.
+pub const KEY_PREFIX: &str = "dora_";
+/// Characters of the raw key kept in `api_keys.key_prefix`.
+const SHOWN_PREFIX_LEN: usize = 12;
+
+/// A freshly minted key. `raw` is shown to the user once and never stored.
+pub struct NewApiKey {
+    pub raw: String,
+    pub prefix: String,
+    pub hash: Vec<u8>,
+}
+
+pub fn generate_key() -> NewApiKey {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let raw = format!("{}{}", KEY_PREFIX, hex::encode(bytes));
+    NewApiKey {
+        prefix: raw[..SHOWN_PREFIX_LEN].to_string(),
+        hash: hash_key(&raw),
+        raw,
+    }
+}
+
+/// SHA-256 of a raw key, as stored in `api_keys.key_hash`.
+pub fn hash_key(raw: &str) -> Vec<u8> {
+    Sha256::digest(raw.trim().as_bytes()).to_vec()
+}
+
+/// Where a running job is, as reported to SSE subscribers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobProgress {
+    pub stage: &'static str,
+    pub percent: Option<u8>,
+}
+
+static PROGRESS: LazyLock<Mutex<HashMap<String, JobProgress>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn progress_for(status: &DownloadStatus) -> Option<JobProgress> {
+    let (stage, percent) = match status {
+        DownloadStatus::Starting { .. } => ("starting", None),
+        DownloadStatus::Downloading { progress, .. } => ("downloading", Some(*progress)),
+        DownloadStatus::Merging { progress, .. } => ("merging", Some(*progress)),
+        DownloadStatus::Uploading { progress, .. } => ("publishing", *progress),
+        // Terminal states are reported from the api_jobs row
+        DownloadStatus::Success { .. } | DownloadStatus::Completed { .. } | DownloadStatus::Error { .. } => {
+            return None;
+        }
+    };
+    Some(JobProgress { stage, percent })
+}
+
+/// Record a progress update for an API job running on this instance.
+pub fn report_progress(task_id: &str, status: &DownloadStatus) {
+    if let Some(progress) = progress_for(status)
+        && let Ok(mut map) = PROGRESS.lock()
+    {
+        map.insert(task_id.to_string(), progress);
+    }
+}
+
+/// Latest in-process progress of a job, if it runs here.
+pub fn progress(task_id: &str) -> Option<JobProgress> {
+    PROGRESS.lock().ok()?.get(task_id).copied()
+}
+
+fn clear_progress(task_id: &str) {
+    if let Ok(mut map) = PROGRESS.lock() {
+        map.remove(task_id);
+    }
+}
+
+/// Publish a finished API job's file and record the link on the job.
+pub async fn deliver(
+    shared_storage: &Arc<SharedStorage>,
+    task_id: &str,
+    user_id: i64,
+    file_path: &str,
+    display_name: &str,
+) -> Result<HostedLink> {
+    let result = async {
+        let link = hosted_links::publish(shared_storage, user_id, file_path, display_name).await?;
+        shared_storage
+            .set_api_job_result(task_id, &link.id, link.expires_at.timestamp())
+            .await?;
+        Ok(link)
+    }
+    .await;
+    clear_progress(task_id);
+    result
+}
+
+/// Record that an API job failed; the message is shown to the API client.
+pub async fn fail(shared_storage: &SharedStorage, task_id: &str, error: &str) {
+    clear_progress(task_id);
+    if let Err(e) = shared_storage.set_api_job_error(task_id, error).await {
+        log::warn!("Failed to record error for API job {}: {}", task_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_prefixed_and_hashed() {
+        let key = generate_key();
+        assert!(key.raw.starts_with(KEY_PREFIX));
+        assert_eq!(key.raw.len(), KEY_PREFIX.len() + 64);
+        assert!(key.raw.starts_with(&key.prefix));
+        assert_eq!(key.hash, hash_key(&key.raw));
+        assert_eq!(hash_key(&format!(" {}\n", key.raw)), key.hash);
+        assert_ne!(generate_key().raw, key.raw);
+    }
+
+    #[test]
+    fn progress_tracks_running_stages_only() {
+        report_progress(
+            "job-1",
+            &DownloadStatus::Merging {
+                title: "t".into(),
+                progress: 40,
+                file_format: None,
+                update_count: 0,
+                artist: None,
+            },
+        );
+        assert_eq!(
+            progress("job-1"),
+            Some(JobProgress {
+                stage: "merging",
+                percent: Some(40)
+            })
+        );
+        report_progress(
+            "job-1",
+            &DownloadStatus::Completed {
+                title: "t".into(),
+                file_format: None,
+            },
+        );
+        assert_eq!(progress("job-1").map(|p| p.stage), Some("merging"));
+        clear_progress("job-1");
+        assert_eq!(progress("job-1"), None);
+    }
+}
@@ -55,6 +55,24 @@ impl Plan {
             Plan::Vip => "VIP",
         }
     }
+
+    /// How many playlists a user on this plan may own.
+    pub fn max_playlists(&self) -> i64 {
+        match self {
+            Plan::Free => 3,
+            Plan::Premium => 10,
+            Plan::Vip => 100,
+        }
+    }
+
+    /// How many tracks fit in one playlist on this plan.
+    pub fn max_playlist_tracks(&self) -> i64 {
+        match self {
+            Plan::Free => 50,
+            Plan::Premium => 200,
+            Plan::Vip => 1000,
+        }
+    }
 }
 
 // rusqlite FromSql: read plan from DB text column via strum's FromStr
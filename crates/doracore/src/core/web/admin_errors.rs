@@ -237,6 +237,7 @@ pub(super) async fn admin_api_error_retry(
         with_lyrics: false, // admin-retry path doesn't preserve user's lyrics toggle (rare, fine)
         priority: 10,       // higher than default so admin retries jump the queue
         idempotency_key: &idempotency_key,
+        credit_charge: None, // admin retries never cost the user credits
     };
 
     if let Err(e) = state.shared_storage.save_task_to_queue(input).await {
@@ -0,0 +1,91 @@
+//! Live admin dashboard stream: `GET /admin/api/events`.
+//!
+//! Forwards [`admin_events`](crate::core::admin_events) to the dashboard as
+//! server-sent events. Each stream holds one broadcast receiver, the number
+//! of open streams is capped, and a stream ends after [`STREAM_MAX`] so the
+//! browser reconnects and the admin session is re-checked.
+
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use axum::response::{
+    IntoResponse, Response,
+    sse::{Event, KeepAlive, Sse},
+};
+use futures_util::stream::{self, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+
+use crate::core::admin_events;
+
+use super::auth::RequireAdmin;
+
+/// Concurrent live streams across all admins.
+const MAX_STREAMS: usize = 16;
+/// Lifetime of one stream before the browser has to reconnect.
+const STREAM_MAX: Duration = Duration::from_secs(30 * 60);
+
+static OPEN_STREAMS: AtomicUsize = AtomicUsize::new(0);
+
+/// One of the [`MAX_STREAMS`] slots; released when the stream is dropped.
+struct StreamSlot;
+
+impl StreamSlot {
+    fn acquire() -> Option<Self> {
+        OPEN_STREAMS
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |open| {
+                (open < MAX_STREAMS).then_some(open + 1)
+            })
+            .ok()
+            .map(|_| StreamSlot)
+    }
+}
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        OPEN_STREAMS.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// GET /admin/api/events — queue changes, task progress, new errors and
+/// alerts as they happen. Sends `ready` on connect and `lagged` when this
+/// client fell behind and should refetch.
+pub(super) async fn admin_api_events(_admin: RequireAdmin) -> Response {
+    let Some(slot) = StreamSlot::acquire() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Too many live streams").into_response();
+    };
+    let rx = admin_events::subscribe();
+    let deadline = Instant::now() + STREAM_MAX;
+
+    let ready = stream::once(async { Ok::<_, Infallible>(Event::default().event("ready").data("{}")) });
+    let events = stream::unfold((rx, slot), move |(mut rx, slot)| async move {
+        let event = match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Ok(event)) => Event::default()
+                .event(event.name())
+                .json_data(&event)
+                .unwrap_or_default(),
+            Ok(Err(RecvError::Lagged(missed))) => Event::default().event("lagged").data(missed.to_string()),
+            Ok(Err(RecvError::Closed)) | Err(_) => return None,
+        };
+        Some((Ok(event), (rx, slot)))
+    });
+    Sse::new(ready.chain(events))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_slots_are_bounded_and_released() {
+        let slots: Vec<_> = (0..MAX_STREAMS).map_while(|_| StreamSlot::acquire()).collect();
+        assert_eq!(slots.len(), MAX_STREAMS);
+        assert!(StreamSlot::acquire().is_none());
+        drop(slots);
+        assert!(StreamSlot::acquire().is_some());
+    }
+}
@@ -587,6 +587,8 @@ pub(super) async fn admin_api_revenue(
     let plan_filter = q.plan.unwrap_or_default();
     let offset = ((page - 1) * REVENUE_PER_PAGE) as i64;
     let db = state.shared_storage.sqlite_pool();
+    // Stars credits (V51): pack sales vs. what was actually spent.
+    let credits = state.shared_storage.get_credit_stats().await.unwrap_or_default();
 
     let result = tokio::task::spawn_blocking(move || -> Result<serde_json::Value, rusqlite::Error> {
         let conn = get_connection(&db).map_err(|_| rusqlite::Error::InvalidQuery)?;
@@ -668,6 +670,16 @@ pub(super) async fn admin_api_revenue(
                 "recurring_count": recurring_count,
             },
             "revenue_per_day": revenue_per_day,
+            "credits": {
+                "purchases": credits.purchases,
+                "stars_total": credits.stars_total,
+                "credits_sold": credits.credits_sold,
+                "credits_burned": credits.credits_burned,
+                "credits_refunded": credits.credits_refunded,
+                "credits_revoked": credits.credits_revoked,
+                "outstanding": credits.outstanding,
+                "burn_by_action": credits.burn_by_action,
+            },
             "charges": {
                 "items": charges,
                 "total": total,
@@ -4,6 +4,7 @@ use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json, Response};
 
+use crate::core::admin_events::{self, AdminEvent};
 use crate::storage::get_connection;
 
 use super::auth::{RequireAdmin, RequireAdminPost};
@@ -150,6 +151,7 @@ pub(super) async fn admin_api_queue_retry(
         Ok(Ok(0)) => (StatusCode::NOT_FOUND, "Task not found or not retryable").into_response(),
         Ok(Ok(_)) => {
             log::info!("Admin {} retried task {}", admin_id, tid2);
+            admin_events::publish(AdminEvent::queue(&tid2, None, "pending"));
             Json(OkResponse::ok()).into_response()
         }
         _ => (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response(),
@@ -182,6 +184,8 @@ pub(super) async fn admin_api_queue_cancel(
         Ok(Ok(0)) => (StatusCode::NOT_FOUND, "Task not found or not cancellable").into_response(),
         Ok(Ok(_)) => {
             log::info!("Admin {} cancelled task {}", admin_id, task_id);
+            refund_cancelled(&state, &task_id).await;
+            admin_events::publish(AdminEvent::queue(&task_id, None, "cancelled"));
             Json(OkResponse::ok()).into_response()
         }
         _ => (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response(),
@@ -200,8 +204,13 @@ pub(super) async fn admin_api_queue_bulk_cancel(
         let conn = get_connection(&db).map_err(|_| rusqlite::Error::InvalidQuery)?;
         let valid = ["pending", "leased"];
         if !valid.contains(&status_filter.as_str()) {
-            return Ok(0);
+            return Ok((0, Vec::new()));
         }
+        let ids: Vec<String> = conn
+            .prepare("SELECT id FROM task_queue WHERE status = ?1")?
+            .query_map(rusqlite::params![&status_filter], |r| r.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
         let n = conn.execute(
             "UPDATE task_queue SET status = 'dead_letter', error_message = 'Bulk cancelled by admin' \
              WHERE status = ?1",
@@ -215,15 +224,31 @@ pub(super) async fn admin_api_queue_bulk_cancel(
             &status_filter,
             Some(&format!("count={}", n)),
         );
-        Ok::<_, rusqlite::Error>(n)
+        Ok::<_, rusqlite::Error>((n, ids))
     })
     .await;
 
     match result {
-        Ok(Ok(n)) => {
+        Ok(Ok((n, ids))) => {
             log::info!("Admin {} bulk-cancelled {} tasks", admin_id, n);
+            for id in &ids {
+                refund_cancelled(&state, id).await;
+                admin_events::publish(AdminEvent::queue(id, None, "cancelled"));
+            }
             Json(BulkCountOk::new("cancelled", n as i64)).into_response()
         }
         _ => (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response(),
     }
 }
+
+/// Return the credits charged for a task the admin cancelled. Goes through the
+/// shared ledger so the refund lands in Postgres when that backend is active.
+async fn refund_cancelled(state: &WebState, task_id: &str) {
+    if let Err(e) = state
+        .shared_storage
+        .refund_task_credits(task_id, "cancelled by admin")
+        .await
+    {
+        log::warn!("Failed to refund credits for cancelled task {}: {}", task_id, e);
+    }
+}
@@ -0,0 +1,194 @@
+//! Staff management: who has which admin role, and who is logged in.
+//!
+//! The matrix itself is fixed in `core::admin_roles`; these handlers only
+//! grant and revoke roles for users outside `ADMIN_IDS`, and list or end
+//! admin dashboard sessions.
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+
+use crate::core::admin_roles::{self, AdminRole};
+use crate::core::config;
+use crate::storage::get_connection;
+
+use super::auth::{RequireAdmin, RequireAdminPost, extract_admin_cookie, hash_session_token};
+use super::helpers::log_audit;
+use super::types::*;
+
+/// GET /admin/api/roles — granted roles, configured owners and the matrix.
+pub(super) async fn admin_api_roles(_admin: RequireAdmin, State(state): State<WebState>) -> Response {
+    let grants = match state.shared_storage.list_admin_roles().await {
+        Ok(grants) => grants,
+        Err(e) => {
+            log::error!("Failed to list admin roles: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response();
+        }
+    };
+
+    let mut result = Vec::with_capacity(grants.len());
+    for grant in grants {
+        let username = state
+            .shared_storage
+            .get_user(grant.user_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|u| u.username)
+            .unwrap_or_default();
+        result.push(ApiRoleGrant {
+            user_id: grant.user_id,
+            username,
+            role: grant.role,
+            granted_by: grant.granted_by,
+            granted_at: grant.granted_at,
+        });
+    }
+
+    let owners = config::admin::OWNER_IDS.clone();
+    let roles = AdminRole::ALL
+        .into_iter()
+        .map(|role| ApiRoleDef {
+            role,
+            permissions: role.permissions(),
+        })
+        .collect();
+    Json(ApiRoles {
+        owners,
+        grants: result,
+        roles,
+    })
+    .into_response()
+}
+
+/// POST /admin/api/roles — grant a role (replaces the previous one).
+pub(super) async fn admin_api_role_grant(
+    RequireAdminPost(admin_id): RequireAdminPost,
+    State(state): State<WebState>,
+    Json(body): Json<RoleGrantReq>,
+) -> Response {
+    let Some(role) = AdminRole::parse(&body.role) else {
+        return (StatusCode::BAD_REQUEST, "Unknown role").into_response();
+    };
+    if body.user_id <= 0 {
+        return (StatusCode::BAD_REQUEST, "Invalid user id").into_response();
+    }
+    if admin_roles::is_configured_owner(body.user_id) {
+        return (StatusCode::BAD_REQUEST, "User is an owner via ADMIN_IDS").into_response();
+    }
+
+    let user_id = body.user_id;
+    if let Err(e) = admin_roles::grant(&state.shared_storage, user_id, role, admin_id).await {
+        log::error!("Failed to grant admin role: {:#}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response();
+    }
+    audit(&state, admin_id, "set_role", user_id, Some(role.as_str())).await;
+    log::info!("Admin {} granted role {} to {}", admin_id, role.as_str(), user_id);
+    Json(OkResponse::ok()).into_response()
+}
+
+/// POST /admin/api/roles/:id/revoke — revoke a role and end that user's
+/// admin sessions.
+pub(super) async fn admin_api_role_revoke(
+    RequireAdminPost(admin_id): RequireAdminPost,
+    State(state): State<WebState>,
+    Path(user_id): Path<i64>,
+) -> Response {
+    match admin_roles::revoke(&state.shared_storage, user_id).await {
+        Ok(false) => (StatusCode::NOT_FOUND, "No role granted").into_response(),
+        Ok(true) => {
+            audit(&state, admin_id, "revoke_role", user_id, None).await;
+            if let Err(e) = state.shared_storage.delete_admin_sessions_for(user_id).await {
+                log::error!("Failed to end sessions of {}: {}", user_id, e);
+            }
+            log::info!("Admin {} revoked the role of {}", admin_id, user_id);
+            Json(OkResponse::ok()).into_response()
+        }
+        Err(e) => {
+            log::error!("Failed to revoke admin role: {:#}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response()
+        }
+    }
+}
+
+/// Record a role change on `user_id` in this instance's audit log.
+async fn audit(state: &WebState, admin_id: i64, action: &'static str, user_id: i64, details: Option<&'static str>) {
+    let db = state.shared_storage.sqlite_pool();
+    let _ = tokio::task::spawn_blocking(move || {
+        if let Ok(conn) = get_connection(&db) {
+            log_audit(&conn, admin_id, action, "user", &user_id.to_string(), details);
+        }
+    })
+    .await;
+}
+
+/// GET /admin/api/sessions — live admin dashboard sessions across instances.
+pub(super) async fn admin_api_sessions(
+    _admin: RequireAdmin,
+    State(state): State<WebState>,
+    header_map: HeaderMap,
+) -> Response {
+    let sessions = match state.shared_storage.list_admin_sessions().await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to list admin sessions: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response();
+        }
+    };
+    let current = extract_admin_cookie(&header_map).map(|t| hash_session_token(&t));
+
+    let mut result = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        let username = state
+            .shared_storage
+            .get_user(session.admin_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|u| u.username)
+            .unwrap_or_default();
+        result.push(ApiAdminSession {
+            id: hex::encode(&session.token_hash),
+            current: current.as_deref() == Some(session.token_hash.as_slice()),
+            admin_id: session.admin_id,
+            username,
+            created_at: session.created_at,
+            last_seen: session.last_seen,
+            expires_at: session.expires_at,
+            user_agent: session.user_agent,
+            ip: session.ip,
+        });
+    }
+    Json(result).into_response()
+}
+
+/// POST /admin/api/sessions/:id/revoke — force-logout one session.
+pub(super) async fn admin_api_session_revoke(
+    RequireAdminPost(admin_id): RequireAdminPost,
+    State(state): State<WebState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Ok(token_hash) = hex::decode(&id) else {
+        return (StatusCode::BAD_REQUEST, "Invalid session id").into_response();
+    };
+
+    match state.shared_storage.delete_admin_session(&token_hash).await {
+        Ok(false) => (StatusCode::NOT_FOUND, "Session not found").into_response(),
+        Ok(true) => {
+            let db = state.shared_storage.sqlite_pool();
+            let short_id: String = id.chars().take(12).collect();
+            let _ = tokio::task::spawn_blocking(move || {
+                if let Ok(conn) = get_connection(&db) {
+                    log_audit(&conn, admin_id, "revoke_session", "session", &short_id, None);
+                }
+            })
+            .await;
+            log::info!("Admin {} ended an admin session", admin_id);
+            Json(OkResponse::ok()).into_response()
+        }
+        Err(e) => {
+            log::error!("Failed to revoke admin session: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response()
+        }
+    }
+}
@@ -0,0 +1,509 @@
+//! Authenticated public REST API under `/api/v1` for programmatic downloads.
+//!
+//! Callers authenticate with a personal key from the bot's `/apikey` command,
+//! sent as `Authorization: Bearer dora_…` (or `X-API-Key`). Each key is rate
+//! limited per minute according to its owner's plan, and every authenticated
+//! call lands in `api_requests` plus the `doradura_api_requests_total` metric.
+//!
+//! Jobs go through the regular task queue; the worker publishes the result as
+//! a signed hosted link instead of sending it to Telegram (see
+//! `crate::core::public_api`).
+
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+
+use axum::{
+    Extension, Json,
+    extract::{MatchedPath, Path, Query, Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures_util::stream::{self, Stream};
+
+use crate::core::config;
+use crate::core::hosted_links;
+use crate::core::metrics;
+use crate::core::public_api::{self, JobProgress};
+use crate::core::types::Plan;
+use crate::storage::db::{ApiJob, CreditAction, EnqueueResult};
+use crate::storage::shared::QueueTaskInput;
+
+use super::auth::check_rate_limit;
+use super::portal_api::history_page;
+use super::types::{
+    API_RATE_LIMIT, API_WINDOW_SECS, ApiCaller, ApiJobReq, ApiJobStatus, ErrorResponse, PortalHistoryQuery, WebState,
+};
+
+const MAX_URL_LEN: usize = 2048;
+const VIDEO_QUALITIES: &[&str] = &["best", "4320p", "2160p", "1440p", "1080p", "720p", "480p", "360p"];
+const AUDIO_BITRATES: &[&str] = &["128k", "192k", "256k", "320k"];
+/// How often the SSE stream re-checks a job.
+const EVENT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// SSE streams are closed after this long even if the job is still running;
+/// clients reconnect or fall back to polling.
+const EVENT_STREAM_MAX: Duration = Duration::from_secs(3600);
+
+fn api_error(status: StatusCode, error: &'static str) -> Response {
+    (status, Json(ErrorResponse { error })).into_response()
+}
+
+fn db_error(e: anyhow::Error) -> Response {
+    log::error!("API storage error: {:#}", e);
+    api_error(StatusCode::INTERNAL_SERVER_ERROR, "internal error")
+}
+
+/// Raw key from `Authorization: Bearer …` or `X-API-Key`.
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    bearer
+        .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|key| key.starts_with(public_api::KEY_PREFIX))
+}
+
+/// Route layer for `/api/v1/*`: resolves the key, applies the plan's rate
+/// limit and records the call.
+pub(super) async fn api_key_guard(State(state): State<WebState>, mut req: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let endpoint = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let method = req.method().to_string();
+
+    let key = match presented_key(req.headers()) {
+        Some(raw) => {
+            state
+                .shared_storage
+                .get_api_key_by_hash(&public_api::hash_key(raw))
+                .await
+        }
+        None => Ok(None),
+    };
+    let key = match key {
+        Ok(Some(key)) => key,
+        Ok(None) => {
+            metrics::record_api_request(&endpoint, StatusCode::UNAUTHORIZED.as_u16());
+            return api_error(StatusCode::UNAUTHORIZED, "missing or invalid API key");
+        }
+        Err(e) => return db_error(e),
+    };
+
+    let user = state.shared_storage.get_user(key.user_id).await.ok().flatten();
+    let response = if user.as_ref().is_some_and(|u| u.is_blocked) {
+        api_error(StatusCode::FORBIDDEN, "account blocked")
+    } else {
+        let plan = user.map(|u| u.plan).unwrap_or_default();
+        let allowed = check_rate_limit(
+            &API_RATE_LIMIT,
+            &key.id.to_string(),
+            config::api::requests_per_minute(plan),
+            API_WINDOW_SECS,
+        )
+        .await;
+        if allowed {
+            req.extensions_mut().insert(ApiCaller {
+                key_id: key.id,
+                user_id: key.user_id,
+                plan,
+            });
+            next.run(req).await
+        } else {
+            let mut resp = api_error(StatusCode::TOO_MANY_REQUESTS, "rate limit exceeded");
+            resp.headers_mut()
+                .insert(header::RETRY_AFTER, API_WINDOW_SECS.to_string().parse().unwrap());
+            resp
+        }
+    };
+
+    let status = response.status().as_u16();
+    metrics::record_api_request(&endpoint, status);
+    if let Err(e) = state
+        .shared_storage
+        .record_api_request(
+            key.id,
+            key.user_id,
+            &method,
+            &endpoint,
+            status,
+            started.elapsed().as_millis() as i64,
+        )
+        .await
+    {
+        log::warn!("Failed to record API request for key {}: {}", key.id, e);
+    }
+    response
+}
+
+/// Public view of a job: stored result first, then the live queue state.
+fn job_status(job: ApiJob, live: Option<JobProgress>, now: i64) -> ApiJobStatus {
+    let mut status = ApiJobStatus {
+        id: job.task_id,
+        status: "queued",
+        url: job.url,
+        format: job.format,
+        created_at: job.created_at,
+        stage: None,
+        progress: None,
+        error: None,
+        result_url: None,
+        expires_at: None,
+    };
+
+    if let Some(error) = job.error {
+        status.status = "failed";
+        status.error = Some(error);
+    } else if let (Some(file_id), Some(expires_at)) = (job.hosted_file_id, job.result_expires_at) {
+        status.expires_at = Some(expires_at);
+        if expires_at > now {
+            status.status = "completed";
+            status.result_url = hosted_links::link_url(&file_id, expires_at);
+        } else {
+            status.status = "expired";
+        }
+    } else {
+        match job.task_status.as_deref() {
+            Some("pending" | "leased") => {}
+            Some("processing" | "uploading") => {
+                status.status = "processing";
+                status.stage = live.map(|p| p.stage);
+                status.progress = live.and_then(|p| p.percent);
+            }
+            Some("dead_letter") => {
+                status.status = "failed";
+                status.error = Some(job.task_error.unwrap_or_else(|| "download failed".to_string()));
+            }
+            Some(_) => {
+                status.status = "failed";
+                status.error = Some("finished without a result".to_string());
+            }
+            // Cancelled, or cleaned up before anything was recorded
+            None => status.status = "expired",
+        }
+    }
+    status
+}
+
+fn is_terminal(status: &ApiJobStatus) -> bool {
+    matches!(status.status, "completed" | "failed" | "expired")
+}
+
+async fn load_status(state: &WebState, user_id: i64, task_id: &str) -> Result<Option<ApiJobStatus>, anyhow::Error> {
+    let job = state.shared_storage.get_api_job(user_id, task_id).await?;
+    Ok(job.map(|job| job_status(job, public_api::progress(task_id), chrono::Utc::now().timestamp())))
+}
+
+fn task_priority(plan: Plan) -> i32 {
+    match plan {
+        Plan::Vip => 2,
+        Plan::Premium => 1,
+        Plan::Free => 0,
+    }
+}
+
+/// POST /api/v1/jobs — enqueue a download of `url` as `mp3` or `mp4`.
+pub(super) async fn api_create_job(
+    Extension(caller): Extension<ApiCaller>,
+    State(state): State<WebState>,
+    Json(body): Json<ApiJobReq>,
+) -> Response {
+    let url = body.url.trim();
+    let valid_url = url.len() <= MAX_URL_LEN
+        && url::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some());
+    if !valid_url {
+        return api_error(StatusCode::BAD_REQUEST, "url must be an http(s) URL");
+    }
+    let format = body.format.trim().to_ascii_lowercase();
+    let is_video = match format.as_str() {
+        "mp4" => true,
+        "mp3" => false,
+        _ => return api_error(StatusCode::BAD_REQUEST, "format must be mp3 or mp4"),
+    };
+    if body.quality.as_deref().is_some_and(|q| !VIDEO_QUALITIES.contains(&q)) {
+        return api_error(StatusCode::BAD_REQUEST, "unsupported quality");
+    }
+    if body.bitrate.as_deref().is_some_and(|b| !AUDIO_BITRATES.contains(&b)) {
+        return api_error(StatusCode::BAD_REQUEST, "unsupported bitrate");
+    }
+
+    let user = match state.shared_storage.get_user(caller.user_id).await {
+        Ok(user) => user,
+        Err(e) => return db_error(e),
+    };
+    let video_quality = body
+        .quality
+        .or_else(|| user.as_ref().map(|u| u.video_quality.clone()))
+        .unwrap_or_else(|| "best".to_string());
+    let audio_bitrate = body
+        .bitrate
+        .or_else(|| user.as_ref().map(|u| u.audio_bitrate.clone()))
+        .unwrap_or_else(|| "320k".to_string());
+    let credit_charge =
+        (is_video && !caller.plan.is_paid() && config::download::is_highres_quality(Some(&video_quality)))
+            .then(|| config::credits::charge_for(CreditAction::Highres));
+
+    // The api_jobs row goes in first so the worker never sees the task
+    // without knowing it belongs to the API.
+    let task_id = uuid::Uuid::new_v4().to_string();
+    if let Err(e) = state
+        .shared_storage
+        .create_api_job(&task_id, caller.key_id, caller.user_id, url, &format)
+        .await
+    {
+        return db_error(e);
+    }
+
+    let idempotency_key = format!("api_{}_{}", caller.key_id, task_id);
+    let input = QueueTaskInput {
+        task_id: &task_id,
+        user_id: caller.user_id,
+        url,
+        message_id: None,
+        format: &format,
+        is_video,
+        video_quality: is_video.then_some(video_quality.as_str()),
+        audio_bitrate: (!is_video).then_some(audio_bitrate.as_str()),
+        time_range_start: None,
+        time_range_end: None,
+        carousel_mask: None,
+        with_lyrics: false,
+        priority: task_priority(caller.plan),
+        idempotency_key: &idempotency_key,
+        credit_charge,
+    };
+
+    let rejected = match state.shared_storage.save_task_to_queue(input).await {
+        Ok(EnqueueResult::Enqueued | EnqueueResult::Duplicate) => None,
+        Ok(EnqueueResult::InsufficientCredits { .. }) => Some((
+            api_error(StatusCode::PAYMENT_REQUIRED, "not enough credits for this quality"),
+            "not enough credits",
+        )),
+        Err(e) => {
+            log::error!("API enqueue failed for user {}: {}", caller.user_id, e);
+            Some((
+                api_error(StatusCode::INTERNAL_SERVER_ERROR, "enqueue failed"),
+                "enqueue failed",
+            ))
+        }
+    };
+    if let Some((response, reason)) = rejected {
+        public_api::fail(&state.shared_storage, &task_id, reason).await;
+        return response;
+    }
+
+    match load_status(&state, caller.user_id, &task_id).await {
+        Ok(Some(status)) => (
+            StatusCode::ACCEPTED,
+            [(header::LOCATION, format!("/api/v1/jobs/{}", task_id))],
+            Json(status),
+        )
+            .into_response(),
+        Ok(None) => api_error(StatusCode::NOT_FOUND, "job not found"),
+        Err(e) => db_error(e),
+    }
+}
+
+/// GET /api/v1/jobs/{id} — current status of one of the caller's jobs.
+pub(super) async fn api_get_job(
+    Extension(caller): Extension<ApiCaller>,
+    State(state): State<WebState>,
+    Path(task_id): Path<String>,
+) -> Response {
+    match load_status(&state, caller.user_id, &task_id).await {
+        Ok(Some(status)) => Json(status).into_response(),
+        Ok(None) => api_error(StatusCode::NOT_FOUND, "job not found"),
+        Err(e) => db_error(e),
+    }
+}
+
+/// GET /api/v1/jobs/{id}/events — server-sent `status` events until the job
+/// reaches a terminal state.
+pub(super) async fn api_job_events(
+    Extension(caller): Extension<ApiCaller>,
+    State(state): State<WebState>,
+    Path(task_id): Path<String>,
+) -> Response {
+    match load_status(&state, caller.user_id, &task_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return api_error(StatusCode::NOT_FOUND, "job not found"),
+        Err(e) => return db_error(e),
+    }
+
+    let started = Instant::now();
+    let events = stream::unfold(
+        (state, caller.user_id, task_id, None::<ApiJobStatus>, false),
+        move |(state, user_id, task_id, last, done)| async move {
+            if done {
+                return None;
+            }
+            loop {
+                if last.is_some() {
+                    tokio::time::sleep(EVENT_POLL_INTERVAL).await;
+                }
+                if started.elapsed() > EVENT_STREAM_MAX {
+                    return None;
+                }
+                let status = match load_status(&state, user_id, &task_id).await {
+                    Ok(Some(status)) => status,
+                    Ok(None) => return None,
+                    Err(e) => {
+                        log::warn!("API event stream for {} failed: {}", task_id, e);
+                        return None;
+                    }
+                };
+                if last.as_ref() == Some(&status) {
+                    continue;
+                }
+                let event: Result<Event, Infallible> =
+                    Ok(Event::default().event("status").json_data(&status).unwrap_or_default());
+                let done = is_terminal(&status);
+                return Some((event, (state, user_id, task_id, Some(status), done)));
+            }
+        },
+    );
+    sse(events)
+}
+
+fn sse(events: impl Stream<Item = Result<Event, Infallible>> + Send + 'static) -> Response {
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// GET /api/v1/jobs/{id}/result — redirect to the signed download link.
+pub(super) async fn api_job_result(
+    Extension(caller): Extension<ApiCaller>,
+    State(state): State<WebState>,
+    Path(task_id): Path<String>,
+) -> Response {
+    let status = match load_status(&state, caller.user_id, &task_id).await {
+        Ok(Some(status)) => status,
+        Ok(None) => return api_error(StatusCode::NOT_FOUND, "job not found"),
+        Err(e) => return db_error(e),
+    };
+    match (status.status, status.result_url) {
+        ("completed", Some(url)) => (StatusCode::FOUND, [(header::LOCATION, url)]).into_response(),
+        ("expired", _) => api_error(StatusCode::GONE, "result expired"),
+        ("failed", _) => api_error(StatusCode::CONFLICT, "job failed"),
+        _ => api_error(StatusCode::CONFLICT, "result not ready"),
+    }
+}
+
+/// GET /api/v1/history — the caller's download history, newest first.
+/// Takes the same filters as the portal (`page`, `search`, `format`,
+/// `category`, `period`).
+pub(super) async fn api_history(
+    Extension(caller): Extension<ApiCaller>,
+    State(state): State<WebState>,
+    Query(q): Query<PortalHistoryQuery>,
+) -> Response {
+    history_page(&state, caller.user_id, &q).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job() -> ApiJob {
+        ApiJob {
+            task_id: "t1".into(),
+            key_id: 1,
+            user_id: 42,
+            url: "https://example.com/v".into(),
+            format: "mp4".into(),
+            hosted_file_id: None,
+            result_expires_at: None,
+            error: None,
+            created_at: "2026-01-01 00:00:00".into(),
+            task_status: Some("pending".into()),
+            task_error: None,
+        }
+    }
+
+    #[test]
+    fn key_is_read_from_either_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(presented_key(&headers), None);
+        headers.insert("x-api-key", "dora_abc".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("dora_abc"));
+        headers.insert(header::AUTHORIZATION, "Bearer dora_def".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("dora_def"));
+        headers.insert(header::AUTHORIZATION, "Bearer something-else".parse().unwrap());
+        headers.remove("x-api-key");
+        assert_eq!(presented_key(&headers), None);
+    }
+
+    #[test]
+    fn status_follows_queue_until_a_result_is_recorded() {
+        assert_eq!(job_status(job(), None, 0).status, "queued");
+
+        let live = JobProgress {
+            stage: "downloading",
+            percent: Some(30),
+        };
+        let running = job_status(
+            ApiJob {
+                task_status: Some("processing".into()),
+                ..job()
+            },
+            Some(live),
+            0,
+        );
+        assert_eq!(
+            (running.status, running.stage, running.progress),
+            ("processing", Some("downloading"), Some(30))
+        );
+
+        let dead = job_status(
+            ApiJob {
+                task_status: Some("dead_letter".into()),
+                task_error: Some("boom".into()),
+                ..job()
+            },
+            None,
+            0,
+        );
+        assert_eq!((dead.status, dead.error.as_deref()), ("failed", Some("boom")));
+
+        let gone = job_status(
+            ApiJob {
+                task_status: None,
+                ..job()
+            },
+            None,
+            0,
+        );
+        assert_eq!(gone.status, "expired");
+        assert!(is_terminal(&gone));
+    }
+
+    #[test]
+    fn recorded_outcome_wins_over_queue_state() {
+        let failed = job_status(
+            ApiJob {
+                error: Some("unsupported site".into()),
+                task_status: Some("completed".into()),
+                ..job()
+            },
+            None,
+            0,
+        );
+        assert_eq!(failed.status, "failed");
+
+        let done = ApiJob {
+            hosted_file_id: Some("abc".into()),
+            result_expires_at: Some(1_000),
+            task_status: Some("completed".into()),
+            ..job()
+        };
+        assert_eq!(job_status(done.clone(), None, 999).status, "completed");
+        let expired = job_status(done, None, 1_000);
+        assert_eq!((expired.status, expired.result_url), ("expired", None));
+    }
+}
@@ -1,11 +1,10 @@
 //! Authentication and authorization for the admin panel.
 
 use std::collections::BTreeMap;
-use std::sync::LazyLock;
 
 use axum::{
     body::Body,
-    extract::{FromRequestParts, Query, State},
+    extract::{FromRequestParts, MatchedPath, Query, State},
     http::{HeaderMap, StatusCode, header, request::Parts},
     response::{Html, IntoResponse, Response},
 };
@@ -13,48 +12,29 @@ use hmac::{Hmac, Mac};
 use secrecy::ExposeSecret;
 use sha2::{Digest, Sha256};
 
+use crate::core::admin_roles;
 use crate::core::config;
 use crate::core::copyright::get_bot_username;
+use crate::storage::db::AdminSession;
 
 use super::helpers::constant_time_eq;
-use super::types::{AUTH_MAX_ATTEMPTS, AUTH_RATE_LIMIT, AUTH_WINDOW_SECS, TelegramAuth, WebState};
+use super::types::{
+    AUTH_MAX_ATTEMPTS, AUTH_RATE_LIMIT, AUTH_WINDOW_SECS, PORTAL_AUTH_RATE_LIMIT, TelegramAuth, WebState,
+};
 
 // --- CSRF ---
+//
+// Each admin session carries its own random CSRF token (stored next to the
+// session, see `SharedStorage::create_admin_session`). The dashboard page
+// embeds it and echoes it back in `x-csrf-token`, so any instance can check
+// it without a process-local secret.
 
-static CSRF_SECRET: LazyLock<String> = LazyLock::new(|| {
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
-    let bytes: [u8; 32] = rng.r#gen();
-    hex::encode(bytes)
-});
-
-pub(super) fn generate_csrf_token(session_token: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(format!("csrf:{}:{}", session_token, &*CSRF_SECRET));
-    hex::encode(hasher.finalize())
-}
-
-pub(super) fn verify_csrf(header_map: &HeaderMap, _bot_token: &str) -> bool {
+fn csrf_matches(header_map: &HeaderMap, session: &AdminSession) -> bool {
     let csrf_header = header_map
         .get("x-csrf-token")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
-    if csrf_header.is_empty() {
-        return false;
-    }
-    // Regenerate expected token from admin cookie
-    let cookie_str = header_map
-        .get(header::COOKIE)
-        .and_then(|c| c.to_str().ok())
-        .unwrap_or("");
-    if let Some(token_val) = cookie_str
-        .split(';')
-        .find_map(|cookie| cookie.trim().strip_prefix("admin_token="))
-    {
-        constant_time_eq(&generate_csrf_token(token_val), csrf_header)
-    } else {
-        false
-    }
+    !csrf_header.is_empty() && constant_time_eq(&session.csrf_token, csrf_header)
 }
 
 // --- Rate limiting helpers ---
@@ -121,6 +101,59 @@ pub(super) async fn check_rate_limit(
     true
 }
 
+/// Count an admin Telegram Login attempt from `ip`. Counters live in `SharedStorage`, so the limit holds across
+/// instances; if the store is unavailable the in-process limiter is used.
+pub(super) async fn login_allowed(state: &WebState, ip: &str) -> bool {
+    let bucket = format!("login:{}", ip);
+    match state
+        .shared_storage
+        .hit_rate_limit(&bucket, AUTH_WINDOW_SECS as i64)
+        .await
+    {
+        Ok(hits) => hits <= AUTH_MAX_ATTEMPTS,
+        Err(e) => {
+            log::warn!("Shared login rate limit unavailable, using local limiter: {}", e);
+            check_rate_limit(&AUTH_RATE_LIMIT, ip, AUTH_MAX_ATTEMPTS, AUTH_WINDOW_SECS).await
+        }
+    }
+}
+
+/// Whether `ip` may try another portal login. Unlike the admin login, only
+/// failed verifications count (see `record_portal_login_failure`), so regular
+/// users signing in never use up the limit, and the portal has its own
+/// bucket so it cannot lock admins out of /admin/auth.
+pub(super) async fn portal_login_allowed(state: &WebState, ip: &str) -> bool {
+    let bucket = format!("portal_login:{}", ip);
+    match state
+        .shared_storage
+        .rate_limit_hits(&bucket, AUTH_WINDOW_SECS as i64)
+        .await
+    {
+        Ok(hits) => hits < AUTH_MAX_ATTEMPTS,
+        Err(e) => {
+            log::warn!("Shared portal login rate limit unavailable, using local limiter: {}", e);
+            let rates = PORTAL_AUTH_RATE_LIMIT.read().await;
+            !matches!(
+                rates.get(ip),
+                Some((count, since)) if since.elapsed().as_secs() < AUTH_WINDOW_SECS && *count >= AUTH_MAX_ATTEMPTS
+            )
+        }
+    }
+}
+
+/// Count a failed portal login from `ip` against its `portal_login:` bucket.
+pub(super) async fn record_portal_login_failure(state: &WebState, ip: &str) {
+    let bucket = format!("portal_login:{}", ip);
+    if let Err(e) = state
+        .shared_storage
+        .hit_rate_limit(&bucket, AUTH_WINDOW_SECS as i64)
+        .await
+    {
+        log::warn!("Shared portal login rate limit unavailable, using local limiter: {}", e);
+        check_rate_limit(&PORTAL_AUTH_RATE_LIMIT, ip, AUTH_MAX_ATTEMPTS, AUTH_WINDOW_SECS).await;
+    }
+}
+
 // --- Telegram hash verification ---
 
 /// Verify Telegram auth hash.
@@ -159,6 +192,15 @@ pub(super) fn verify_telegram_hash(auth: &TelegramAuth, bot_token: &str) -> bool
     constant_time_eq(&hex::encode(result), &auth.hash)
 }
 
+/// Telegram Login data is only accepted within 5 minutes of `auth_date`.
+pub(super) fn auth_date_is_fresh(auth_date: i64) -> bool {
+    let now_unix = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    now_unix - auth_date <= 300
+}
+
 // --- Session tokens (DB-backed) ---
 //
 // The previous implementation generated a deterministic sha256(user_id:bot_token)
@@ -168,10 +210,14 @@ pub(super) fn verify_telegram_hash(auth: &TelegramAuth, bot_token: &str) -> bool
 //   * not revocable on logout (Set-Cookie Max-Age=0 only hints the browser).
 // This meant BOT_TOKEN leak = permanent global admin access with no recourse.
 //
-// The new design stores only SHA-256(raw_token) in a DB table with an
-// `expires_at` timestamp. verify_admin looks up the hash; logout deletes
+// The new design stores only SHA-256(raw_token) with an `expires_at`
+// timestamp in `admin_sessions`, through `SharedStorage` so every instance
+// sees the same sessions. verify_admin looks up the hash; logout deletes
 // the row. The raw token is the cookie value; it is never persisted.
 
+/// Admin sessions last 24 hours.
+const SESSION_TTL_HOURS: i64 = 24;
+
 /// Generate a cryptographically random 32-byte session token (hex-encoded, 64 chars).
 pub(super) fn new_session_token() -> String {
     use rand::RngCore;
@@ -181,68 +227,14 @@ pub(super) fn new_session_token() -> String {
 }
 
 /// SHA-256 the raw token for DB storage. We never store the raw token.
-fn hash_session_token(token: &str) -> Vec<u8> {
+pub(super) fn hash_session_token(token: &str) -> Vec<u8> {
     let mut hasher = Sha256::new();
     hasher.update(token.as_bytes());
     hasher.finalize().to_vec()
 }
 
-/// Create and persist a new admin session. Returns the raw token to set as cookie.
-pub(super) fn create_admin_session(
-    conn: &rusqlite::Connection,
-    admin_id: i64,
-    user_agent: Option<&str>,
-    ip: Option<&str>,
-) -> Result<String, rusqlite::Error> {
-    let token = new_session_token();
-    let hash = hash_session_token(&token);
-    conn.execute(
-        "INSERT INTO admin_sessions (token_hash, admin_id, expires_at, user_agent, ip) \
-         VALUES (?1, ?2, datetime('now', '+24 hours'), ?3, ?4)",
-        rusqlite::params![hash, admin_id, user_agent, ip],
-    )?;
-    Ok(token)
-}
-
-/// Look up an admin session by raw token. Returns `admin_id` if valid + not expired.
-pub(super) fn lookup_admin_session(conn: &rusqlite::Connection, raw_token: &str) -> Option<i64> {
-    let hash = hash_session_token(raw_token);
-    let result = conn
-        .query_row(
-            "SELECT admin_id FROM admin_sessions \
-             WHERE token_hash = ?1 AND expires_at > datetime('now')",
-            rusqlite::params![hash],
-            |row| row.get::<_, i64>(0),
-        )
-        .ok();
-
-    // Update last_seen on successful lookup (best-effort; ignore errors).
-    if result.is_some() {
-        let _ = conn.execute(
-            "UPDATE admin_sessions SET last_seen = datetime('now') WHERE token_hash = ?1",
-            rusqlite::params![hash_session_token(raw_token)],
-        );
-    }
-    result
-}
-
-/// Revoke a specific admin session (logout).
-pub(super) fn revoke_admin_session(conn: &rusqlite::Connection, raw_token: &str) -> Result<(), rusqlite::Error> {
-    let hash = hash_session_token(raw_token);
-    conn.execute(
-        "DELETE FROM admin_sessions WHERE token_hash = ?1",
-        rusqlite::params![hash],
-    )?;
-    Ok(())
-}
-
-/// Best-effort periodic cleanup of expired sessions (called from verify_admin).
-fn cleanup_expired_sessions(conn: &rusqlite::Connection) {
-    let _ = conn.execute("DELETE FROM admin_sessions WHERE expires_at <= datetime('now')", []);
-}
-
 /// Extract the `admin_token` cookie value from a header map.
-fn extract_admin_cookie(header_map: &HeaderMap) -> Option<String> {
+pub(super) fn extract_admin_cookie(header_map: &HeaderMap) -> Option<String> {
     let cookie_str = header_map.get(header::COOKIE).and_then(|c| c.to_str().ok())?;
     cookie_str
         .split(';')
@@ -253,35 +245,35 @@ fn extract_admin_cookie(header_map: &HeaderMap) -> Option<String> {
 
 /// Verify admin cookie + CSRF token for POST requests.
 #[allow(clippy::result_large_err)]
-pub(super) fn verify_admin_post(header_map: &HeaderMap, state: &WebState) -> Result<i64, Response> {
-    let admin_id = verify_admin(header_map, state)?;
-    if !verify_csrf(header_map, "") {
+pub(super) async fn verify_admin_post(header_map: &HeaderMap, state: &WebState) -> Result<AdminSession, Response> {
+    let session = verify_admin(header_map, state).await?;
+    if !csrf_matches(header_map, &session) {
         return Err((StatusCode::FORBIDDEN, "Invalid CSRF token").into_response());
     }
-    Ok(admin_id)
+    Ok(session)
 }
 
-/// Verify admin cookie against the DB session store. Returns admin_id on success.
+/// Verify admin cookie against the shared session store.
 #[allow(clippy::result_large_err)]
-pub(super) fn verify_admin(header_map: &HeaderMap, state: &WebState) -> Result<i64, Response> {
+pub(super) async fn verify_admin(header_map: &HeaderMap, state: &WebState) -> Result<AdminSession, Response> {
     let raw_token = extract_admin_cookie(header_map)
         .ok_or_else(|| (StatusCode::UNAUTHORIZED, "Not authenticated").into_response())?;
 
-    let pool = state.shared_storage.sqlite_pool();
-    let conn = crate::storage::get_connection(&pool)
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response())?;
-
-    if let Some(admin_id) = lookup_admin_session(&conn, &raw_token) {
-        // Defense in depth: verify admin_id is still in the allowlist
-        // (in case they were removed from ADMIN_IDS after issuance).
-        if config::admin::ADMIN_IDS.contains(&admin_id) || *config::admin::ADMIN_USER_ID == admin_id {
-            // Periodically clean up expired rows (every verification is fine —
-            // cheap query with an index; happens ~once per admin request).
-            cleanup_expired_sessions(&conn);
-            return Ok(admin_id);
-        }
+    let session = state
+        .shared_storage
+        .get_admin_session(&hash_session_token(&raw_token))
+        .await
+        .map_err(|e| {
+            log::error!("Admin session lookup failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response()
+        })?;
+
+    match session {
+        // Defense in depth: verify admin_id still has a role (in case they
+        // were removed from ADMIN_IDS or had their role revoked after issuance).
+        Some(session) if admin_roles::role_of(session.admin_id).is_some() => Ok(session),
+        _ => Err((StatusCode::UNAUTHORIZED, "Not authenticated").into_response()),
     }
-    Err((StatusCode::UNAUTHORIZED, "Not authenticated").into_response())
 }
 
 // --- Admin auth extractors ---
@@ -293,9 +285,11 @@ pub(super) fn verify_admin(header_map: &HeaderMap, state: &WebState) -> Result<i
 // compiler refusing to build the route.
 
 /// GET-style admin auth: verifies the `admin_token` cookie against the session
-/// store and checks the user is still in the admin allowlist. Returns the
+/// store and checks the user's role allows the matched route. Returns the
 /// resolved admin user id via `.0` — not every handler needs the id, so the
-/// field is intentionally allowed to be unread.
+/// field is intentionally allowed to be unread. The resolved [`AdminSession`]
+/// is left in the request extensions for handlers that need its CSRF token
+/// (`Extension<AdminSession>` after this extractor).
 #[allow(dead_code)]
 pub struct RequireAdmin(pub i64);
 
@@ -303,7 +297,11 @@ impl FromRequestParts<WebState> for RequireAdmin {
     type Rejection = Response;
 
     async fn from_request_parts(parts: &mut Parts, state: &WebState) -> Result<Self, Self::Rejection> {
-        verify_admin(&parts.headers, state).map(RequireAdmin)
+        let session = verify_admin(&parts.headers, state).await?;
+        authorize_route(parts, session.admin_id)?;
+        let admin_id = session.admin_id;
+        parts.extensions.insert(session);
+        Ok(RequireAdmin(admin_id))
     }
 }
 
@@ -315,24 +313,54 @@ impl FromRequestParts<WebState> for RequireAdminPost {
     type Rejection = Response;
 
     async fn from_request_parts(parts: &mut Parts, state: &WebState) -> Result<Self, Self::Rejection> {
-        verify_admin_post(&parts.headers, state).map(RequireAdminPost)
+        let session = verify_admin_post(&parts.headers, state).await?;
+        authorize_route(parts, session.admin_id)?;
+        let admin_id = session.admin_id;
+        parts.extensions.insert(session);
+        Ok(RequireAdminPost(admin_id))
+    }
+}
+
+/// Check the admin's role against the permission the matched route needs
+/// (`admin_roles::route_permission`). Denials are audited.
+#[allow(clippy::result_large_err)]
+fn authorize_route(parts: &Parts, admin_id: i64) -> Result<(), Response> {
+    let path = parts
+        .extensions
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str)
+        .unwrap_or_else(|| parts.uri.path());
+    let method = parts.method.as_str();
+    let permission = admin_roles::route_permission(method, path);
+    if admin_roles::check(admin_id, permission, &format!("{} {}", method, path)) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            format!("Your role does not allow this ({})", permission.as_str()),
+        )
+            .into_response())
     }
 }
 
 // --- Auth route handlers ---
 
-/// GET /admin/login -- Login page with Telegram Widget.
-pub(super) async fn admin_login_handler(State(_state): State<WebState>) -> Response {
-    let bot_username = match get_bot_username() {
-        Some(u) => u.to_string(),
+/// Bot username for the Telegram Login Widget.
+pub(super) fn widget_bot_username() -> Option<String> {
+    match get_bot_username() {
+        Some(u) => Some(u.to_string()),
         None => {
             // Fallback to ADMIN_USERNAME env var if bot hasn't started yet
             let fallback = config::admin::ADMIN_USERNAME.clone();
-            if fallback.is_empty() {
-                return (StatusCode::INTERNAL_SERVER_ERROR, "Bot username not available yet").into_response();
-            }
-            fallback
+            (!fallback.is_empty()).then_some(fallback)
         }
+    }
+}
+
+/// GET /admin/login -- Login page with Telegram Widget.
+pub(super) async fn admin_login_handler(State(_state): State<WebState>) -> Response {
+    let Some(bot_username) = widget_bot_username() else {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Bot username not available yet").into_response();
     };
 
     // HTML template lives in a sibling `.html` file — easier to edit, gets
@@ -351,7 +379,7 @@ pub(super) async fn admin_auth_handler(
 ) -> Response {
     // 0. Rate-limit by IP
     let ip = extract_ip(&header_map);
-    if !check_rate_limit(&AUTH_RATE_LIMIT, &ip, AUTH_MAX_ATTEMPTS, AUTH_WINDOW_SECS).await {
+    if !login_allowed(&state, &ip).await {
         return (
             StatusCode::TOO_MANY_REQUESTS,
             "Too many login attempts. Try again later.",
@@ -365,41 +393,46 @@ pub(super) async fn admin_auth_handler(
     }
 
     // 2. Reject stale auth data (must be within 5 minutes)
-    let now_unix = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0);
-    if now_unix - auth.auth_date > 300 {
+    if !auth_date_is_fresh(auth.auth_date) {
         return (StatusCode::UNAUTHORIZED, "Auth data expired. Please log in again.").into_response();
     }
 
-    // 3. Check if user is admin
-    let is_admin = config::admin::ADMIN_IDS.contains(&auth.id) || *config::admin::ADMIN_USER_ID == auth.id;
-    if !is_admin {
+    // 3. Check if user has an admin role
+    if admin_roles::role_of(auth.id).is_none() {
         return (StatusCode::FORBIDDEN, "Not an admin").into_response();
     }
 
     // 4. Create a random session token, persist its hash in the DB, return raw to client.
+    let raw_token = new_session_token();
+    let csrf_token = new_session_token();
     let user_agent = header_map.get(header::USER_AGENT).and_then(|v| v.to_str().ok());
-    let ip_for_session = if ip != "unknown" { Some(ip.as_str()) } else { None };
-
-    let pool = state.shared_storage.sqlite_pool();
-    let conn = match crate::storage::get_connection(&pool) {
-        Ok(c) => c,
-        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response(),
-    };
-
-    let raw_token = match create_admin_session(&conn, auth.id, user_agent, ip_for_session) {
-        Ok(t) => t,
-        Err(e) => {
-            log::error!("Failed to create admin session: {}", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "Session store error").into_response();
-        }
-    };
+    let ip_for_session = (ip != "unknown").then_some(ip.as_str());
+    if let Err(e) = state
+        .shared_storage
+        .create_admin_session(
+            &hash_session_token(&raw_token),
+            auth.id,
+            &csrf_token,
+            SESSION_TTL_HOURS,
+            user_agent,
+            ip_for_session,
+        )
+        .await
+    {
+        log::error!("Failed to create admin session: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Session store error").into_response();
+    }
+    // Opportunistic cleanup; logins are rare enough to carry it
+    let _ = state.shared_storage.purge_expired_admin_sessions().await;
+    let _ = state
+        .shared_storage
+        .purge_rate_limits(AUTH_WINDOW_SECS as i64 * 2)
+        .await;
 
     let cookie = format!(
-        "admin_token={}; Path=/admin; HttpOnly; Secure; SameSite=Lax; Max-Age=86400",
-        raw_token
+        "admin_token={}; Path=/admin; HttpOnly; Secure; SameSite=Lax; Max-Age={}",
+        raw_token,
+        SESSION_TTL_HOURS * 3600
     );
 
     Response::builder()
@@ -415,10 +448,10 @@ pub(super) async fn admin_logout_handler(State(state): State<WebState>, header_m
     // Server-side revocation: delete the session row so the cookie stops working
     // even if the browser keeps it.
     if let Some(raw_token) = extract_admin_cookie(&header_map) {
-        let pool = state.shared_storage.sqlite_pool();
-        if let Ok(conn) = crate::storage::get_connection(&pool) {
-            let _ = revoke_admin_session(&conn, &raw_token);
-        }
+        let _ = state
+            .shared_storage
+            .delete_admin_session(&hash_session_token(&raw_token))
+            .await;
     }
 
     let cookie = "admin_token=; Path=/admin; HttpOnly; Secure; SameSite=Lax; Max-Age=0";
@@ -438,43 +471,42 @@ mod tests {
     use super::*;
     use axum::http::HeaderValue;
 
+    fn session(csrf_token: &str) -> AdminSession {
+        AdminSession {
+            admin_id: 1,
+            csrf_token: csrf_token.to_string(),
+        }
+    }
+
     #[test]
-    fn verify_csrf_accepts_matching_admin_cookie() {
-        let session_token = "session-123";
-        let csrf = generate_csrf_token(session_token);
+    fn csrf_matches_session_token() {
         let mut headers = HeaderMap::new();
-        headers.insert(
-            "x-csrf-token",
-            HeaderValue::from_str(&csrf).expect("csrf header must be valid"),
-        );
-        headers.insert(
-            header::COOKIE,
-            HeaderValue::from_static("foo=bar; admin_token=session-123; theme=dark"),
-        );
+        headers.insert("x-csrf-token", HeaderValue::from_static("csrf-123"));
+        assert!(csrf_matches(&headers, &session("csrf-123")));
+        assert!(!csrf_matches(&headers, &session("csrf-456")));
+    }
 
-        assert!(verify_csrf(&headers, "unused"));
+    #[test]
+    fn csrf_rejects_missing_header() {
+        assert!(!csrf_matches(&HeaderMap::new(), &session("csrf-123")));
+
+        let mut empty = HeaderMap::new();
+        empty.insert("x-csrf-token", HeaderValue::from_static(""));
+        assert!(!csrf_matches(&empty, &session("")));
     }
 
     #[test]
-    fn verify_csrf_rejects_missing_or_malformed_admin_cookie() {
-        let csrf = generate_csrf_token("session-123");
+    fn extract_admin_cookie_ignores_similar_names() {
         let mut headers = HeaderMap::new();
-        headers.insert(
-            "x-csrf-token",
-            HeaderValue::from_str(&csrf).expect("csrf header must be valid"),
-        );
         headers.insert(
             header::COOKIE,
-            HeaderValue::from_static("foo=bar; admin_tokenx=session-123"),
+            HeaderValue::from_static("foo=bar; admin_tokenx=nope; admin_token=session-123"),
         );
-        assert!(!verify_csrf(&headers, "unused"));
+        assert_eq!(extract_admin_cookie(&headers).as_deref(), Some("session-123"));
 
-        let mut missing_cookie_headers = HeaderMap::new();
-        missing_cookie_headers.insert(
-            "x-csrf-token",
-            HeaderValue::from_str(&csrf).expect("csrf header must be valid"),
-        );
-        assert!(!verify_csrf(&missing_cookie_headers, "unused"));
+        let mut missing = HeaderMap::new();
+        missing.insert(header::COOKIE, HeaderValue::from_static("admin_tokenx=nope"));
+        assert_eq!(extract_admin_cookie(&missing), None);
     }
 
     #[test]
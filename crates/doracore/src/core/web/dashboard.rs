@@ -2,16 +2,17 @@
 
 use std::sync::Arc;
 
+use axum::Extension;
 use axum::extract::State;
 use axum::http::StatusCode;
-use axum::http::header;
 use axum::response::{Html, IntoResponse, Response};
 use indoc::formatdoc;
 
-use crate::storage::db::DbPool;
+use crate::core::admin_roles::{self, AdminRole};
+use crate::storage::db::{AdminSession, DbPool};
 use crate::storage::get_connection;
 
-use super::auth::{RequireAdmin, generate_csrf_token};
+use super::auth::RequireAdmin;
 use super::helpers::{fmt_num, html_escape};
 use super::types::WebState;
 
@@ -21,13 +22,12 @@ use super::types::WebState;
 
 /// GET /admin — Admin Dashboard.
 ///
-/// `RequireAdmin` enforces the auth check at the extractor layer. `header_map`
-/// is still needed afterwards to extract the admin cookie for CSRF token
-/// generation.
+/// `RequireAdmin` enforces the auth check at the extractor layer and leaves
+/// the resolved session behind, whose CSRF token the page embeds.
 pub(super) async fn admin_dashboard_handler(
-    _admin: RequireAdmin,
+    RequireAdmin(admin_id): RequireAdmin,
+    Extension(session): Extension<AdminSession>,
     State(state): State<WebState>,
-    header_map: header::HeaderMap,
 ) -> Response {
     // Fetch stats (sync SQLite — offload to blocking thread pool)
     let db = state.shared_storage.sqlite_pool();
@@ -36,19 +36,9 @@ pub(super) async fn admin_dashboard_handler(
         Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response(),
     };
 
-    // Generate CSRF token from admin cookie
-    let admin_token = header_map
-        .get(header::COOKIE)
-        .and_then(|c| c.to_str().ok())
-        .unwrap_or("")
-        .split(';')
-        .find(|s| s.trim().starts_with("admin_token="))
-        .and_then(|t| t.trim().strip_prefix("admin_token="))
-        .unwrap_or("");
-    let csrf_token = generate_csrf_token(admin_token);
-
     // Render Dashboard
-    let html = render_admin_dashboard(&stats, &csrf_token);
+    let role = admin_roles::role_of(admin_id).unwrap_or(AdminRole::Viewer);
+    let html = render_admin_dashboard(&stats, &session.csrf_token, role);
     Html(html).into_response()
 }
 
@@ -175,7 +165,7 @@ fn fetch_admin_stats(db: &Arc<DbPool>) -> AdminStats {
 // Renderer
 // ---------------------------------------------------------------------------
 
-fn render_admin_dashboard(stats: &AdminStats, csrf_token: &str) -> String {
+fn render_admin_dashboard(stats: &AdminStats, csrf_token: &str, role: AdminRole) -> String {
     // --- Overview cards ---
     let cards_html = formatdoc! {r#"
         <div class="stat-card">
@@ -300,5 +290,12 @@ fn render_admin_dashboard(stats: &AdminStats, csrf_token: &str) -> String {
         errors_today = fmt_num(stats.errors_today),
         err_color = if stats.errors_today > 0 { "#ef4444" } else { "inherit" },
         csrf_token = csrf_token,
+        admin_role = role.as_str(),
+        admin_permissions = role
+            .permissions()
+            .iter()
+            .map(|p| p.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
     )
 }
@@ -0,0 +1,186 @@
+//! GET /d/{id} — downloads behind signed hosted links.
+//!
+//! See `crate::core::hosted_links` for how links are minted. Supports single
+//! `Range: bytes=` requests so browsers and download managers can resume;
+//! only requests starting at byte 0 bump the download counter.
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+use crate::core::hosted_links;
+
+use super::types::{HostedLinkQuery, WebState};
+
+/// Parse a `Range` header against a file of `size` bytes.
+///
+/// `Ok(None)` means "send the whole file" — no header, a malformed one, or a
+/// multi-range request (which we're allowed to ignore). `Err(())` means the
+/// range can't be satisfied (416).
+fn parse_range(value: Option<&str>, size: u64) -> Result<Option<(u64, u64)>, ()> {
+    let Some(spec) = value.and_then(|v| v.trim().strip_prefix("bytes=")) else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        // Suffix range: the last N bytes
+        let Ok(suffix) = end.parse::<u64>() else {
+            return Ok(None);
+        };
+        if suffix == 0 || size == 0 {
+            return Err(());
+        }
+        (size.saturating_sub(suffix), size - 1)
+    } else {
+        let Ok(start) = start.parse::<u64>() else {
+            return Ok(None);
+        };
+        let end = if end.is_empty() {
+            size.saturating_sub(1)
+        } else {
+            match end.parse::<u64>() {
+                Ok(end) if end >= start => end.min(size.saturating_sub(1)),
+                _ => return Ok(None),
+            }
+        };
+        if start >= size {
+            return Err(());
+        }
+        (start, end)
+    };
+    Ok(Some(range))
+}
+
+/// `attachment` disposition with an ASCII fallback plus the UTF-8 name.
+fn content_disposition(file_name: &str) -> String {
+    let ascii: String = file_name
+        .chars()
+        .map(|c| if c.is_ascii_graphic() || c == ' ' { c } else { '_' })
+        .filter(|c| *c != '"' && *c != '\\')
+        .collect();
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        ascii,
+        urlencoding::encode(file_name)
+    )
+}
+
+/// GET /d/{id}?exp=..&sig=..
+pub(super) async fn hosted_download_handler(
+    State(state): State<WebState>,
+    Path(id): Path<String>,
+    Query(q): Query<HostedLinkQuery>,
+    header_map: HeaderMap,
+) -> Response {
+    if !hosted_links::verify(&id, q.exp, &q.sig) {
+        return (StatusCode::FORBIDDEN, "This link is invalid or has expired").into_response();
+    }
+    let hosted = match state.shared_storage.get_hosted_file(&id).await {
+        Ok(Some(hosted)) => hosted,
+        Ok(None) => return (StatusCode::GONE, "This file is no longer available").into_response(),
+        Err(e) => {
+            log::error!("Hosted file lookup failed for {}: {}", id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response();
+        }
+    };
+    let mut file = match tokio::fs::File::open(&hosted.file_path).await {
+        Ok(file) => file,
+        Err(e) => {
+            log::warn!("Hosted file {} missing on disk: {}", id, e);
+            return (StatusCode::GONE, "This file is no longer available").into_response();
+        }
+    };
+    let size = match file.metadata().await {
+        Ok(meta) => meta.len(),
+        Err(_) => return (StatusCode::GONE, "This file is no longer available").into_response(),
+    };
+
+    let range_header = header_map.get(header::RANGE).and_then(|v| v.to_str().ok());
+    let range = match parse_range(range_header, size) {
+        Ok(range) => range,
+        Err(()) => {
+            return Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", size))
+                .body(Body::empty())
+                .unwrap();
+        }
+    };
+
+    if range.is_none_or(|(start, _)| start == 0)
+        && let Err(e) = state.shared_storage.record_hosted_download(&id).await
+    {
+        log::warn!("Failed to count hosted download {}: {}", id, e);
+    }
+
+    let (status, start, len) = match range {
+        Some((start, end)) => (StatusCode::PARTIAL_CONTENT, start, end - start + 1),
+        None => (StatusCode::OK, 0, size),
+    };
+    if start > 0
+        && let Err(e) = file.seek(std::io::SeekFrom::Start(start)).await
+    {
+        log::warn!("Seek failed on hosted file {}: {}", id, e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Read error").into_response();
+    }
+
+    let mut builder = Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, hosted.content_type.as_str())
+        .header(header::CONTENT_LENGTH, len)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CONTENT_DISPOSITION, content_disposition(&hosted.file_name))
+        .header(header::CACHE_CONTROL, "private, no-store");
+    if let Some((start, end)) = range {
+        builder = builder.header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, size));
+    }
+    builder
+        .body(Body::from_stream(ReaderStream::new(file.take(len))))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_are_parsed_and_clamped() {
+        assert_eq!(parse_range(None, 100), Ok(None));
+        assert_eq!(parse_range(Some("bytes=0-9"), 100), Ok(Some((0, 9))));
+        assert_eq!(parse_range(Some("bytes=50-"), 100), Ok(Some((50, 99))));
+        assert_eq!(parse_range(Some("bytes=90-200"), 100), Ok(Some((90, 99))));
+        assert_eq!(parse_range(Some("bytes=-10"), 100), Ok(Some((90, 99))));
+        assert_eq!(parse_range(Some("bytes=-500"), 100), Ok(Some((0, 99))));
+    }
+
+    #[test]
+    fn odd_ranges_fall_back_or_fail() {
+        // Ignored: whole file
+        assert_eq!(parse_range(Some("bytes=0-1,5-6"), 100), Ok(None));
+        assert_eq!(parse_range(Some("items=0-1"), 100), Ok(None));
+        assert_eq!(parse_range(Some("bytes=9-1"), 100), Ok(None));
+        assert_eq!(parse_range(Some("bytes=x-"), 100), Ok(None));
+        // Unsatisfiable
+        assert_eq!(parse_range(Some("bytes=100-"), 100), Err(()));
+        assert_eq!(parse_range(Some("bytes=-0"), 100), Err(()));
+    }
+
+    #[test]
+    fn disposition_keeps_unicode_name_encoded() {
+        let value = content_disposition("Дора \"live\".mp3");
+        assert!(value.starts_with("attachment; filename=\"____ live.mp3\";"));
+        assert!(value.ends_with("filename*=UTF-8''%D0%94%D0%BE%D1%80%D0%B0%20%22live%22.mp3"));
+    }
+}
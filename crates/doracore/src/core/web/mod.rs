@@ -1,6 +1,9 @@
-//! Public-facing web server for share pages and admin dashboard.
+//! Public-facing web server for share pages, the user portal and admin dashboard.
 //!
-//! Serves beautiful ambilight share pages with streaming links at /s/{id}.
+//! Serves beautiful ambilight share pages with streaming links at /s/{id}
+//! and signed downloads for files over the upload caps at /d/{id}.
+//! Regular users manage their history, playlists and settings at /me, and
+//! tooling submits downloads through the key-authenticated REST API at /api/v1.
 //! Runs on WEB_PORT (default 3000) alongside the internal metrics server.
 
 use std::sync::Arc;
@@ -21,12 +24,18 @@ use crate::core::types::PlanChangeNotifier;
 use crate::storage::SharedStorage;
 
 mod admin_errors;
+mod admin_live;
 mod admin_misc;
 mod admin_queue;
+mod admin_staff;
 mod admin_users;
+mod api;
 mod auth;
 mod dashboard;
 mod helpers;
+mod hosted;
+mod portal;
+mod portal_api;
 mod public;
 mod types;
 
@@ -116,18 +125,72 @@ pub async fn start_web_server(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     let bot_token = config::BOT_TOKEN.clone();
+
+    // Admin sessions used to live in each instance's SQLite file
+    match shared_storage.import_sqlite_admin_sessions().await {
+        Ok(0) => {}
+        Ok(n) => log::info!("Moved {} admin session(s) from SQLite to Postgres", n),
+        Err(e) => log::warn!("Could not move admin sessions to Postgres: {}", e),
+    }
+
     let state = WebState {
         shared_storage,
         bot_token,
         plan_notifier,
     };
 
+    // Public REST API; every route goes through the key guard.
+    let api_routes = if *config::api::ENABLED {
+        Router::new()
+            .route("/api/v1/jobs", post(api::api_create_job))
+            .route("/api/v1/jobs/{id}", get(api::api_get_job))
+            .route("/api/v1/jobs/{id}/events", get(api::api_job_events))
+            .route("/api/v1/jobs/{id}/result", get(api::api_job_result))
+            .route("/api/v1/history", get(api::api_history))
+            .route_layer(middleware::from_fn_with_state(state.clone(), api::api_key_guard))
+    } else {
+        Router::new()
+    };
+
     let app = Router::new()
         .route("/s/{id}", get(public::share_page_handler))
         .route("/api/s/{id}", get(public::share_api_handler))
         .route("/api/timeline", get(public::timeline_api_handler))
         .route("/health", get(public::health_handler))
         .route("/privacy", get(public::privacy_handler))
+        .route("/d/{id}", get(hosted::hosted_download_handler))
+        // User portal
+        .route("/me", get(portal::portal_handler))
+        .route("/me/login", get(portal::portal_login_handler))
+        .route("/me/auth", get(portal::portal_auth_handler))
+        .route("/me/logout", get(portal::portal_logout_handler))
+        .route("/me/api/history", get(portal_api::portal_api_history))
+        .route("/me/api/history/{id}/category", post(portal_api::portal_api_history_category))
+        .route("/me/api/history/{id}/delete", post(portal_api::portal_api_history_delete))
+        .route("/me/api/history/{id}/resend", post(portal_api::portal_api_history_resend))
+        .route(
+            "/me/api/categories",
+            get(portal_api::portal_api_categories).post(portal_api::portal_api_category_create),
+        )
+        .route(
+            "/me/api/playlists",
+            get(portal_api::portal_api_playlists).post(portal_api::portal_api_playlist_create),
+        )
+        .route("/me/api/playlists/{id}/rename", post(portal_api::portal_api_playlist_rename))
+        .route("/me/api/playlists/{id}/delete", post(portal_api::portal_api_playlist_delete))
+        .route(
+            "/me/api/playlists/{id}/items",
+            get(portal_api::portal_api_playlist_items).post(portal_api::portal_api_playlist_add),
+        )
+        .route(
+            "/me/api/playlists/{id}/items/{item_id}/remove",
+            post(portal_api::portal_api_playlist_remove),
+        )
+        .route(
+            "/me/api/settings",
+            get(portal_api::portal_api_settings).post(portal_api::portal_api_settings_update),
+        )
+        .route("/me/api/archive", post(portal_api::portal_api_archive))
         // Admin routes
         .route("/admin", get(dashboard::admin_dashboard_handler))
         .route("/admin/login", get(auth::admin_login_handler))
@@ -167,9 +230,20 @@ pub async fn start_web_server(
         // Content subscriptions
         .route("/admin/api/subscriptions", get(admin_misc::admin_api_subscriptions))
         .route("/admin/api/subscriptions/{id}/toggle", post(admin_misc::admin_api_sub_toggle))
+        // Staff roles
+        .route(
+            "/admin/api/roles",
+            get(admin_staff::admin_api_roles).post(admin_staff::admin_api_role_grant),
+        )
+        .route("/admin/api/roles/{id}/revoke", post(admin_staff::admin_api_role_revoke))
+        .route("/admin/api/sessions", get(admin_staff::admin_api_sessions))
+        .route("/admin/api/sessions/{id}/revoke", post(admin_staff::admin_api_session_revoke))
         // Lightweight polling for tab badges
         .route("/admin/api/counts", get(admin_misc::admin_api_counts))
+        // Live push of queue, progress, error and alert events
+        .route("/admin/api/events", get(admin_live::admin_api_events))
         .route("/metrics", get(public::metrics_handler))
+        .merge(api_routes)
         .with_state(state)
         .layer(DefaultBodyLimit::max(1024 * 1024)) // 1 MB
         // IP allowlist MUST run before security_headers so that a denied
@@ -181,7 +255,13 @@ pub async fn start_web_server(
     log::info!("  /s/:id      - Share page (HTML)");
     log::info!("  /api/s/:id  - Share page (JSON)");
     log::info!("  /privacy    - Privacy Policy");
+    log::info!("  /d/:id      - Hosted download links (signed)");
+    log::info!("  /me         - User portal (Telegram Login)");
+    if *config::api::ENABLED {
+        log::info!("  /api/v1     - Public REST API (API key)");
+    }
     log::info!("  /admin      - Admin Dashboard");
+    log::info!("  /admin/api/events - Admin live stream (SSE)");
     log::info!("  /health     - Health check");
     log::info!("  /metrics    - Prometheus metrics (Bearer auth)");
 
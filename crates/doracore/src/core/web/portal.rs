@@ -0,0 +1,272 @@
+//! Self-service user portal at `/me`: Telegram Login for regular users and
+//! the portal page itself. The JSON API lives in `portal_api.rs`.
+//!
+//! Sessions are stored hashed in `portal_sessions` through `SharedStorage`, so
+//! they work on both backends. Each session carries its own random CSRF token
+//! which the page echoes back in `x-csrf-token` on every POST.
+
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, Query, State},
+    http::{HeaderMap, StatusCode, header, request::Parts},
+    response::{Html, IntoResponse, Response},
+};
+use secrecy::ExposeSecret;
+
+use crate::storage::db::PortalSession;
+
+use super::auth::{
+    auth_date_is_fresh, extract_ip, hash_session_token, new_session_token, portal_login_allowed,
+    record_portal_login_failure, verify_telegram_hash, widget_bot_username,
+};
+use super::helpers::{constant_time_eq, html_escape};
+use super::types::{TelegramAuth, WebState};
+
+const PORTAL_COOKIE: &str = "me_token";
+/// Portal sessions last a week; admin sessions stay at 24 hours.
+const SESSION_TTL_HOURS: i64 = 24 * 7;
+
+/// Extract the `me_token` cookie value from a header map.
+fn extract_portal_cookie(header_map: &HeaderMap) -> Option<String> {
+    let cookie_str = header_map.get(header::COOKIE).and_then(|c| c.to_str().ok())?;
+    cookie_str.split(';').find_map(|s| {
+        s.trim()
+            .strip_prefix(PORTAL_COOKIE)
+            .and_then(|rest| rest.strip_prefix('='))
+            .map(|v| v.to_string())
+    })
+}
+
+/// Resolve the session behind the request's portal cookie.
+async fn load_session(header_map: &HeaderMap, state: &WebState) -> Option<PortalSession> {
+    let raw_token = extract_portal_cookie(header_map)?;
+    match state
+        .shared_storage
+        .get_portal_session(&hash_session_token(&raw_token))
+        .await
+    {
+        Ok(session) => session,
+        Err(e) => {
+            log::error!("Portal session lookup failed: {}", e);
+            None
+        }
+    }
+}
+
+// --- Portal auth extractors ---
+
+/// GET-style portal auth: a valid `me_token` session. Yields the user ID.
+pub struct RequireUser(pub i64);
+
+impl FromRequestParts<WebState> for RequireUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &WebState) -> Result<Self, Self::Rejection> {
+        load_session(&parts.headers, state)
+            .await
+            .map(|session| RequireUser(session.user_id))
+            .ok_or_else(|| (StatusCode::UNAUTHORIZED, "Not authenticated").into_response())
+    }
+}
+
+/// POST-style portal auth: everything `RequireUser` does, plus the
+/// `x-csrf-token` header must match the session's CSRF token.
+pub struct RequireUserPost(pub i64);
+
+impl FromRequestParts<WebState> for RequireUserPost {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &WebState) -> Result<Self, Self::Rejection> {
+        let session = load_session(&parts.headers, state)
+            .await
+            .ok_or_else(|| (StatusCode::UNAUTHORIZED, "Not authenticated").into_response())?;
+        if !csrf_matches(&parts.headers, &session) {
+            return Err((StatusCode::FORBIDDEN, "Invalid CSRF token").into_response());
+        }
+        Ok(RequireUserPost(session.user_id))
+    }
+}
+
+fn csrf_matches(header_map: &HeaderMap, session: &PortalSession) -> bool {
+    let csrf_header = header_map
+        .get("x-csrf-token")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    !csrf_header.is_empty() && constant_time_eq(&session.csrf_token, csrf_header)
+}
+
+fn redirect(location: &str) -> Response {
+    Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header(header::LOCATION, location)
+        .body(Body::empty())
+        .unwrap()
+}
+
+// --- Route handlers ---
+
+/// GET /me -- The portal page, or a redirect to the login page.
+pub(super) async fn portal_handler(State(state): State<WebState>, header_map: HeaderMap) -> Response {
+    let Some(session) = load_session(&header_map, &state).await else {
+        return redirect("/me/login");
+    };
+
+    let user = state.shared_storage.get_user(session.user_id).await.ok().flatten();
+    let name = user
+        .as_ref()
+        .and_then(|u| u.username.clone())
+        .map(|u| format!("@{}", u))
+        .unwrap_or_else(|| session.user_id.to_string());
+    let plan = user.map(|u| u.plan.display_name()).unwrap_or("Free");
+
+    const PORTAL_HTML: &str = include_str!("html/portal.html");
+    let html = PORTAL_HTML
+        .replace("{CSRF_TOKEN}", &session.csrf_token)
+        .replace("{USER_NAME}", &html_escape(&name))
+        .replace("{PLAN}", plan);
+    Html(html).into_response()
+}
+
+/// GET /me/login -- Login page with the Telegram Widget.
+pub(super) async fn portal_login_handler() -> Response {
+    let Some(bot_username) = widget_bot_username() else {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Bot username not available yet").into_response();
+    };
+    const LOGIN_HTML: &str = include_str!("html/portal_login.html");
+    Html(LOGIN_HTML.replace("{BOT_USERNAME}", &bot_username)).into_response()
+}
+
+/// GET /me/auth -- Telegram authentication callback for regular users.
+pub(super) async fn portal_auth_handler(
+    State(state): State<WebState>,
+    header_map: HeaderMap,
+    Query(auth): Query<TelegramAuth>,
+) -> Response {
+    let ip = extract_ip(&header_map);
+    if !portal_login_allowed(&state, &ip).await {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many login attempts. Try again later.",
+        )
+            .into_response();
+    }
+
+    if !verify_telegram_hash(&auth, state.bot_token.expose_secret()) {
+        record_portal_login_failure(&state, &ip).await;
+        return (StatusCode::UNAUTHORIZED, "Invalid hash").into_response();
+    }
+    if !auth_date_is_fresh(auth.auth_date) {
+        record_portal_login_failure(&state, &ip).await;
+        return (StatusCode::UNAUTHORIZED, "Auth data expired. Please log in again.").into_response();
+    }
+
+    // Only people who already use the bot have anything to manage here
+    match state.shared_storage.get_user(auth.id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::FORBIDDEN,
+                "Start the bot in Telegram first, then log in again.",
+            )
+                .into_response();
+        }
+        Err(e) => {
+            log::error!("Portal login user lookup failed: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response();
+        }
+    }
+    if state.shared_storage.is_user_blocked(auth.id).await.unwrap_or(false) {
+        return (StatusCode::FORBIDDEN, "Account blocked").into_response();
+    }
+
+    let raw_token = new_session_token();
+    let csrf_token = new_session_token();
+    let user_agent = header_map.get(header::USER_AGENT).and_then(|v| v.to_str().ok());
+    let ip_for_session = (ip != "unknown").then_some(ip.as_str());
+    if let Err(e) = state
+        .shared_storage
+        .create_portal_session(
+            &hash_session_token(&raw_token),
+            auth.id,
+            &csrf_token,
+            SESSION_TTL_HOURS,
+            user_agent,
+            ip_for_session,
+        )
+        .await
+    {
+        log::error!("Failed to create portal session: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Session store error").into_response();
+    }
+    // Opportunistic cleanup; logins are rare enough to carry it
+    let _ = state.shared_storage.purge_expired_portal_sessions().await;
+
+    let cookie = format!(
+        "{}={}; Path=/me; HttpOnly; Secure; SameSite=Lax; Max-Age={}",
+        PORTAL_COOKIE,
+        raw_token,
+        SESSION_TTL_HOURS * 3600
+    );
+    let mut response = redirect("/me");
+    response
+        .headers_mut()
+        .insert(header::SET_COOKIE, cookie.parse().unwrap());
+    response
+}
+
+/// GET /me/logout -- Revoke the session server-side and clear the cookie.
+pub(super) async fn portal_logout_handler(State(state): State<WebState>, header_map: HeaderMap) -> Response {
+    if let Some(raw_token) = extract_portal_cookie(&header_map) {
+        let _ = state
+            .shared_storage
+            .delete_portal_session(&hash_session_token(&raw_token))
+            .await;
+    }
+
+    let mut response = redirect("/me/login");
+    response.headers_mut().insert(
+        header::SET_COOKIE,
+        format!(
+            "{}=; Path=/me; HttpOnly; Secure; SameSite=Lax; Max-Age=0",
+            PORTAL_COOKIE
+        )
+        .parse()
+        .unwrap(),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn portal_cookie_is_not_confused_with_admin_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("admin_token=abc; me_tokenx=evil; me_token=def"),
+        );
+        assert_eq!(extract_portal_cookie(&headers).as_deref(), Some("def"));
+
+        headers.insert(header::COOKIE, HeaderValue::from_static("admin_token=abc"));
+        assert_eq!(extract_portal_cookie(&headers), None);
+    }
+
+    #[test]
+    fn csrf_must_match_the_session_token() {
+        let session = PortalSession {
+            user_id: 1,
+            csrf_token: "token-1".to_string(),
+        };
+        let mut headers = HeaderMap::new();
+        assert!(!csrf_matches(&headers, &session));
+
+        headers.insert("x-csrf-token", HeaderValue::from_static("token-2"));
+        assert!(!csrf_matches(&headers, &session));
+
+        headers.insert("x-csrf-token", HeaderValue::from_static("token-1"));
+        assert!(csrf_matches(&headers, &session));
+    }
+}
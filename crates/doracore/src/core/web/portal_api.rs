@@ -0,0 +1,739 @@
+//! JSON API behind the `/me` user portal: history, categories, playlists,
+//! settings, re-sending files to the user's chat and zip archives.
+//!
+//! Every handler is scoped to the session's user; rows owned by someone else
+//! answer 404 exactly like missing ones.
+
+use std::path::PathBuf;
+
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use secrecy::ExposeSecret;
+use serde_json::json;
+
+use crate::core::config;
+use crate::core::types::Plan;
+use crate::download::filename_template::{ArchiveNames, TemplateFields, archive_template};
+use crate::storage::db::{DownloadHistoryEntry, Playlist};
+use crate::storage::shared::period_cutoff;
+
+use super::auth::check_rate_limit;
+use super::portal::{RequireUser, RequireUserPost};
+use super::types::{
+    CreatedOk, OkResponse, PORTAL_ACTION_RATE_LIMIT, PORTAL_ACTION_WINDOW_SECS, PORTAL_ACTIONS_PER_MIN,
+    PaginatedResponse, PortalAddItemReq, PortalArchiveReq, PortalCategoryReq, PortalDownload, PortalHistoryQuery,
+    PortalNameReq, PortalPlaylist, PortalPlaylistItem, PortalPlaylists, PortalSettings, SettingsUpdatedOk, WebState,
+};
+
+const HISTORY_PER_PAGE: u32 = 50;
+const MAX_ARCHIVE_FILES: usize = 50;
+const MAX_ARCHIVE_BYTES: u64 = 200 * 1024 * 1024;
+const MAX_NAME_LEN: usize = 64;
+
+const DOWNLOAD_FORMATS: &[&str] = &["mp3", "mp4", "srt", "txt"];
+const VIDEO_QUALITIES: &[&str] = &["best", "1080p", "720p", "480p", "360p"];
+const AUDIO_BITRATES: &[&str] = &["128k", "192k", "256k", "320k"];
+
+fn db_error(e: anyhow::Error) -> Response {
+    log::error!("Portal storage error: {:#}", e);
+    (StatusCode::INTERNAL_SERVER_ERROR, "DB error").into_response()
+}
+
+fn not_found() -> Response {
+    (StatusCode::NOT_FOUND, "Not found").into_response()
+}
+
+/// Trimmed, length-checked playlist/category name.
+fn clean_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    (!name.is_empty() && name.chars().count() <= MAX_NAME_LEN).then(|| name.to_string())
+}
+
+async fn user_plan(state: &WebState, user_id: i64) -> Plan {
+    match state.shared_storage.get_user(user_id).await {
+        Ok(Some(user)) => user.plan,
+        _ => Plan::Free,
+    }
+}
+
+/// The playlist if it exists and belongs to `user_id`.
+async fn owned_playlist(state: &WebState, user_id: i64, playlist_id: i64) -> Result<Playlist, Response> {
+    match state.shared_storage.get_playlist(playlist_id).await {
+        Ok(Some(playlist)) if playlist.user_id == user_id => Ok(playlist),
+        Ok(_) => Err(not_found()),
+        Err(e) => Err(db_error(e)),
+    }
+}
+
+async fn owned_entry(state: &WebState, user_id: i64, entry_id: i64) -> Result<DownloadHistoryEntry, Response> {
+    match state.shared_storage.get_download_history_entry(user_id, entry_id).await {
+        Ok(Some(entry)) => Ok(entry),
+        Ok(None) => Err(not_found()),
+        Err(e) => Err(db_error(e)),
+    }
+}
+
+async fn action_allowed(user_id: i64) -> Result<(), Response> {
+    if check_rate_limit(
+        &PORTAL_ACTION_RATE_LIMIT,
+        &user_id.to_string(),
+        PORTAL_ACTIONS_PER_MIN,
+        PORTAL_ACTION_WINDOW_SECS,
+    )
+    .await
+    {
+        Ok(())
+    } else {
+        Err((StatusCode::TOO_MANY_REQUESTS, "Slow down a little").into_response())
+    }
+}
+
+// --- History ---
+
+/// GET /me/api/history — the user's downloads, newest first.
+pub(super) async fn portal_api_history(
+    RequireUser(user_id): RequireUser,
+    State(state): State<WebState>,
+    Query(q): Query<PortalHistoryQuery>,
+) -> Response {
+    history_page(&state, user_id, &q).await
+}
+
+/// One page of `user_id`'s history. Shared with `GET /api/v1/history`.
+pub(super) async fn history_page(state: &WebState, user_id: i64, q: &PortalHistoryQuery) -> Response {
+    let format = q.format.as_deref().filter(|f| matches!(*f, "mp3" | "mp4"));
+    let search = q.search.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let category = q.category.as_deref().filter(|c| !c.is_empty());
+    let entries = match state
+        .shared_storage
+        .get_download_history_filtered(user_id, format, search, category, period_cutoff(q.period.as_deref()))
+        .await
+    {
+        Ok(entries) => entries,
+        Err(e) => return db_error(e),
+    };
+
+    let page = q.page.unwrap_or(1).max(1);
+    let total = entries.len() as i64;
+    let total_pages = total.div_ceil(HISTORY_PER_PAGE as i64) as u32;
+    let items = entries
+        .into_iter()
+        .skip(((page - 1) * HISTORY_PER_PAGE) as usize)
+        .take(HISTORY_PER_PAGE as usize)
+        .map(|e| PortalDownload {
+            id: e.id,
+            title: e.title,
+            author: e.author.unwrap_or_default(),
+            format: e.format,
+            file_size: e.file_size,
+            duration: e.duration,
+            downloaded_at: e.downloaded_at,
+            url: e.url,
+            category: e.category,
+        })
+        .collect();
+
+    Json(PaginatedResponse {
+        items,
+        total,
+        page,
+        per_page: HISTORY_PER_PAGE,
+        total_pages,
+    })
+    .into_response()
+}
+
+/// POST /me/api/history/{id}/category — set or clear an entry's category.
+pub(super) async fn portal_api_history_category(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Path(entry_id): Path<i64>,
+    Json(body): Json<PortalCategoryReq>,
+) -> Response {
+    if let Err(resp) = owned_entry(&state, user_id, entry_id).await {
+        return resp;
+    }
+    let category = match body.category.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        None => None,
+        Some(name) => {
+            let Some(name) = clean_name(name) else {
+                return (StatusCode::BAD_REQUEST, "Invalid category name").into_response();
+            };
+            if let Err(e) = state.shared_storage.create_user_category(user_id, &name).await {
+                return db_error(e);
+            }
+            Some(name)
+        }
+    };
+    match state
+        .shared_storage
+        .set_download_category(user_id, entry_id, category.as_deref())
+        .await
+    {
+        Ok(()) => Json(OkResponse::ok()).into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// POST /me/api/history/{id}/delete — remove an entry from the history.
+pub(super) async fn portal_api_history_delete(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Path(entry_id): Path<i64>,
+) -> Response {
+    match state
+        .shared_storage
+        .delete_download_history_entry(user_id, entry_id)
+        .await
+    {
+        Ok(true) => Json(OkResponse::ok()).into_response(),
+        Ok(false) => not_found(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// POST /me/api/history/{id}/resend — send the cached file to the user's chat.
+pub(super) async fn portal_api_history_resend(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Path(entry_id): Path<i64>,
+) -> Response {
+    if let Err(resp) = action_allowed(user_id).await {
+        return resp;
+    }
+    let entry = match owned_entry(&state, user_id, entry_id).await {
+        Ok(entry) => entry,
+        Err(resp) => return resp,
+    };
+    let Some(file_id) = entry.file_id.as_deref() else {
+        return (StatusCode::CONFLICT, "This download has no stored file").into_response();
+    };
+
+    let (method, field) = match entry.format.as_str() {
+        "mp3" => ("sendAudio", "audio"),
+        "mp4" => ("sendVideo", "video"),
+        _ => ("sendDocument", "document"),
+    };
+    let mut payload = json!({
+        "chat_id": user_id,
+        "caption": entry.title,
+    });
+    payload[field] = json!(file_id);
+    match bot_api_call(state.bot_token.expose_secret(), method, &payload).await {
+        Ok(_) => Json(OkResponse::ok()).into_response(),
+        Err(e) => {
+            log::warn!("Portal resend of {} for {} failed: {}", entry_id, user_id, e);
+            (StatusCode::BAD_GATEWAY, "Telegram refused the file").into_response()
+        }
+    }
+}
+
+// --- Categories ---
+
+/// GET /me/api/categories
+pub(super) async fn portal_api_categories(
+    RequireUser(user_id): RequireUser,
+    State(state): State<WebState>,
+) -> Response {
+    match state.shared_storage.get_user_categories(user_id).await {
+        Ok(categories) => Json(categories).into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// POST /me/api/categories — create an (empty) category.
+pub(super) async fn portal_api_category_create(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Json(body): Json<PortalNameReq>,
+) -> Response {
+    let Some(name) = clean_name(&body.name) else {
+        return (StatusCode::BAD_REQUEST, "Invalid category name").into_response();
+    };
+    match state.shared_storage.create_user_category(user_id, &name).await {
+        Ok(()) => Json(OkResponse::ok()).into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+// --- Playlists ---
+
+/// GET /me/api/playlists
+pub(super) async fn portal_api_playlists(RequireUser(user_id): RequireUser, State(state): State<WebState>) -> Response {
+    let playlists = match state.shared_storage.get_user_playlists(user_id).await {
+        Ok(playlists) => playlists,
+        Err(e) => return db_error(e),
+    };
+    let mut items = Vec::with_capacity(playlists.len());
+    for playlist in playlists {
+        let item_count = state
+            .shared_storage
+            .count_playlist_items(playlist.id)
+            .await
+            .unwrap_or(0);
+        items.push(PortalPlaylist {
+            id: playlist.id,
+            name: playlist.name,
+            description: playlist.description,
+            item_count,
+            updated_at: playlist.updated_at,
+        });
+    }
+    let max = user_plan(&state, user_id).await.max_playlists();
+    Json(PortalPlaylists { items, max }).into_response()
+}
+
+/// POST /me/api/playlists — create a playlist within the plan's limit.
+pub(super) async fn portal_api_playlist_create(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Json(body): Json<PortalNameReq>,
+) -> Response {
+    let Some(name) = clean_name(&body.name) else {
+        return (StatusCode::BAD_REQUEST, "Invalid playlist name").into_response();
+    };
+    let count = state.shared_storage.count_user_playlists(user_id).await.unwrap_or(0);
+    if count >= user_plan(&state, user_id).await.max_playlists() {
+        return (StatusCode::FORBIDDEN, "Playlist limit reached for your plan").into_response();
+    }
+    let description = body.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+    match state.shared_storage.create_playlist(user_id, &name, description).await {
+        Ok(id) => Json(CreatedOk::new(id)).into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// POST /me/api/playlists/{id}/rename
+pub(super) async fn portal_api_playlist_rename(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Path(playlist_id): Path<i64>,
+    Json(body): Json<PortalNameReq>,
+) -> Response {
+    let Some(name) = clean_name(&body.name) else {
+        return (StatusCode::BAD_REQUEST, "Invalid playlist name").into_response();
+    };
+    if let Err(resp) = owned_playlist(&state, user_id, playlist_id).await {
+        return resp;
+    }
+    match state.shared_storage.rename_playlist(playlist_id, user_id, &name).await {
+        Ok(()) => Json(OkResponse::ok()).into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// POST /me/api/playlists/{id}/delete
+pub(super) async fn portal_api_playlist_delete(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Path(playlist_id): Path<i64>,
+) -> Response {
+    if let Err(resp) = owned_playlist(&state, user_id, playlist_id).await {
+        return resp;
+    }
+    match state.shared_storage.delete_playlist(playlist_id).await {
+        Ok(()) => Json(OkResponse::ok()).into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// GET /me/api/playlists/{id}/items
+pub(super) async fn portal_api_playlist_items(
+    RequireUser(user_id): RequireUser,
+    State(state): State<WebState>,
+    Path(playlist_id): Path<i64>,
+) -> Response {
+    if let Err(resp) = owned_playlist(&state, user_id, playlist_id).await {
+        return resp;
+    }
+    match state.shared_storage.get_playlist_items(playlist_id).await {
+        Ok(items) => Json(
+            items
+                .into_iter()
+                .map(|item| PortalPlaylistItem {
+                    id: item.id,
+                    position: item.position,
+                    title: item.title,
+                    artist: item.artist,
+                    url: item.url,
+                    duration_secs: item.duration_secs,
+                    download_id: item.download_history_id,
+                })
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// Playlist `source` label for a history URL — same mapping as the bot's
+/// "add from history" button.
+fn playlist_source(url: &str) -> &'static str {
+    if url.contains("spotify.com") {
+        "spotify"
+    } else if url.contains("soundcloud.com") {
+        "soundcloud"
+    } else {
+        "youtube"
+    }
+}
+
+/// POST /me/api/playlists/{id}/items — append a history entry.
+pub(super) async fn portal_api_playlist_add(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Path(playlist_id): Path<i64>,
+    Json(body): Json<PortalAddItemReq>,
+) -> Response {
+    if let Err(resp) = owned_playlist(&state, user_id, playlist_id).await {
+        return resp;
+    }
+    let entry = match owned_entry(&state, user_id, body.download_id).await {
+        Ok(entry) => entry,
+        Err(resp) => return resp,
+    };
+    let count = state
+        .shared_storage
+        .count_playlist_items(playlist_id)
+        .await
+        .unwrap_or(0);
+    if count >= user_plan(&state, user_id).await.max_playlist_tracks() {
+        return (StatusCode::FORBIDDEN, "Playlist is full for your plan").into_response();
+    }
+    match state
+        .shared_storage
+        .add_playlist_item(
+            playlist_id,
+            &entry.title,
+            entry.author.as_deref(),
+            &entry.url,
+            entry.duration.map(|d| d as i32),
+            entry.file_id.as_deref(),
+            playlist_source(&entry.url),
+        )
+        .await
+    {
+        Ok(id) => Json(CreatedOk::new(id)).into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// POST /me/api/playlists/{id}/items/{item_id}/remove
+pub(super) async fn portal_api_playlist_remove(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Path((playlist_id, item_id)): Path<(i64, i64)>,
+) -> Response {
+    if let Err(resp) = owned_playlist(&state, user_id, playlist_id).await {
+        return resp;
+    }
+    let items = match state.shared_storage.get_playlist_items(playlist_id).await {
+        Ok(items) => items,
+        Err(e) => return db_error(e),
+    };
+    if !items.iter().any(|item| item.id == item_id) {
+        return not_found();
+    }
+    match state.shared_storage.remove_playlist_item(item_id).await {
+        Ok(()) => Json(OkResponse::ok()).into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+// --- Settings ---
+
+/// GET /me/api/settings
+pub(super) async fn portal_api_settings(RequireUser(user_id): RequireUser, State(state): State<WebState>) -> Response {
+    let user = match state.shared_storage.get_user(user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return not_found(),
+        Err(e) => return db_error(e),
+    };
+    let silent = state
+        .shared_storage
+        .get_user_silent_downloads(user_id)
+        .await
+        .unwrap_or(false);
+    Json(PortalSettings {
+        download_format: Some(user.download_format),
+        video_quality: Some(user.video_quality),
+        audio_bitrate: Some(user.audio_bitrate),
+        language: Some(user.language),
+        send_as_document: Some(user.send_as_document != 0),
+        send_audio_as_document: Some(user.send_audio_as_document != 0),
+        silent_downloads: Some(silent),
+    })
+    .into_response()
+}
+
+/// POST /me/api/settings — update any subset of the settings.
+pub(super) async fn portal_api_settings_update(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Json(body): Json<PortalSettings>,
+) -> Response {
+    let storage = &state.shared_storage;
+    let mut updated = Vec::new();
+
+    let result: anyhow::Result<()> = async {
+        if let Some(format) = body.download_format.as_deref().filter(|v| DOWNLOAD_FORMATS.contains(v)) {
+            storage.set_user_download_format(user_id, format).await?;
+            updated.push("download_format");
+        }
+        if let Some(quality) = body.video_quality.as_deref().filter(|v| VIDEO_QUALITIES.contains(v)) {
+            storage.set_user_video_quality(user_id, quality).await?;
+            updated.push("video_quality");
+        }
+        if let Some(bitrate) = body.audio_bitrate.as_deref().filter(|v| AUDIO_BITRATES.contains(v)) {
+            storage.set_user_audio_bitrate(user_id, bitrate).await?;
+            updated.push("audio_bitrate");
+        }
+        if let Some(lang) = body.language.as_deref().and_then(crate::i18n::is_language_supported) {
+            storage.set_user_language(user_id, lang).await?;
+            updated.push("language");
+        }
+        if let Some(enabled) = body.send_as_document {
+            storage.set_user_send_as_document(user_id, i32::from(enabled)).await?;
+            updated.push("send_as_document");
+        }
+        if let Some(enabled) = body.send_audio_as_document {
+            storage
+                .set_user_send_audio_as_document(user_id, i32::from(enabled))
+                .await?;
+            updated.push("send_audio_as_document");
+        }
+        if let Some(enabled) = body.silent_downloads {
+            storage.set_user_silent_downloads(user_id, enabled).await?;
+            updated.push("silent_downloads");
+        }
+        Ok(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => Json(SettingsUpdatedOk::new(updated)).into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+// --- Archives ---
+
+/// Bot API base URL — the local server when configured, else the cloud API.
+fn bot_api_base() -> String {
+    config::bot_api::get_url()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| "https://api.telegram.org".to_string())
+}
+
+/// Minimal Bot API call — doracore can't depend on teloxide, so this goes
+/// through reqwest like the admin notify path.
+async fn bot_api_call(bot_token: &str, method: &str, payload: &serde_json::Value) -> anyhow::Result<serde_json::Value> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+    let resp: serde_json::Value = client
+        .post(format!("{}/bot{}/{}", bot_api_base(), bot_token, method))
+        .json(payload)
+        .send()
+        .await?
+        .json()
+        .await?;
+    if resp["ok"].as_bool() != Some(true) {
+        anyhow::bail!(
+            "{} failed: {}",
+            method,
+            resp["description"].as_str().unwrap_or("unknown error")
+        );
+    }
+    Ok(resp["result"].clone())
+}
+
+/// Fetch a stored file's bytes via `getFile`. A local Bot API server answers
+/// with an absolute path on its own disk instead of a download path.
+async fn fetch_telegram_file(bot_token: &str, file_id: &str) -> anyhow::Result<Vec<u8>> {
+    let file = bot_api_call(bot_token, "getFile", &json!({ "file_id": file_id })).await?;
+    let file_path = file["file_path"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("getFile returned no file_path"))?;
+    if file_path.starts_with('/') {
+        return Ok(fs_err::tokio::read(file_path).await?);
+    }
+    let bytes = reqwest::get(format!("{}/file/bot{}/{}", bot_api_base(), bot_token, file_path))
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    Ok(bytes.to_vec())
+}
+
+/// `Artist - Title.ext`, made unique within the archive.
+fn archive_file_name(entry: &DownloadHistoryEntry, names: &mut ArchiveNames) -> String {
+    let rel = archive_template()
+        .render(&TemplateFields::from(entry), &entry.format)
+        .unwrap_or_else(|| PathBuf::from(format!("download-{}.{}", entry.id, entry.format)));
+    names.claim(&rel)
+}
+
+fn build_zip(files: Vec<(String, Vec<u8>)>) -> zip::result::ZipResult<Vec<u8>> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    // Media is already compressed; storing keeps the build cheap
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (name, data) in files {
+        zip.start_file(name, options)?;
+        zip.write_all(&data)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// POST /me/api/archive — zip the selected downloads. Files Telegram won't
+/// hand out (too large for the cloud API, expired) are skipped and counted
+/// in `X-Archive-Skipped`.
+pub(super) async fn portal_api_archive(
+    RequireUserPost(user_id): RequireUserPost,
+    State(state): State<WebState>,
+    Json(body): Json<PortalArchiveReq>,
+) -> Response {
+    if body.ids.is_empty() || body.ids.len() > MAX_ARCHIVE_FILES {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("Select between 1 and {} files", MAX_ARCHIVE_FILES),
+        )
+            .into_response();
+    }
+    if let Err(resp) = action_allowed(user_id).await {
+        return resp;
+    }
+
+    let mut entries = Vec::with_capacity(body.ids.len());
+    for id in &body.ids {
+        match owned_entry(&state, user_id, *id).await {
+            Ok(entry) if entry.file_id.is_some() => entries.push(entry),
+            Ok(_) | Err(_) => {}
+        }
+    }
+    let declared: u64 = entries
+        .iter()
+        .filter_map(|e| e.file_size)
+        .map(|s| s.max(0) as u64)
+        .sum();
+    if declared > MAX_ARCHIVE_BYTES {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Selection is larger than {} MB", MAX_ARCHIVE_BYTES / (1024 * 1024)),
+        )
+            .into_response();
+    }
+
+    let bot_token = state.bot_token.expose_secret();
+    let mut used_names = ArchiveNames::default();
+    let mut files = Vec::with_capacity(entries.len());
+    let mut total: u64 = 0;
+    let mut skipped = body.ids.len() - entries.len();
+    for entry in &entries {
+        let Some(file_id) = entry.file_id.as_deref() else {
+            continue;
+        };
+        match fetch_telegram_file(bot_token, file_id).await {
+            Ok(data) if total + data.len() as u64 <= MAX_ARCHIVE_BYTES => {
+                total += data.len() as u64;
+                files.push((archive_file_name(entry, &mut used_names), data));
+            }
+            Ok(_) => skipped += 1,
+            Err(e) => {
+                log::warn!("Portal archive: skipping {} for {}: {}", entry.id, user_id, e);
+                skipped += 1;
+            }
+        }
+    }
+    if files.is_empty() {
+        return (StatusCode::BAD_GATEWAY, "None of the selected files could be fetched").into_response();
+    }
+
+    let zip = match tokio::task::spawn_blocking(move || build_zip(files)).await {
+        Ok(Ok(zip)) => zip,
+        Ok(Err(e)) => {
+            log::error!("Portal archive build failed: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Archive error").into_response();
+        }
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Archive error").into_response(),
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/zip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"doradura-archive.zip\"",
+        )
+        .header("X-Archive-Skipped", skipped.to_string())
+        .body(Body::from(zip))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i64, author: Option<&str>, title: &str) -> DownloadHistoryEntry {
+        DownloadHistoryEntry {
+            id,
+            url: "https://youtu.be/x".to_string(),
+            title: title.to_string(),
+            format: "mp3".to_string(),
+            downloaded_at: String::new(),
+            file_id: Some("file".to_string()),
+            author: author.map(str::to_string),
+            file_size: None,
+            duration: None,
+            video_quality: None,
+            audio_bitrate: None,
+            bot_api_url: None,
+            bot_api_is_local: None,
+            source_id: None,
+            part_index: None,
+            category: None,
+            speed: None,
+        }
+    }
+
+    #[test]
+    fn archive_names_are_sanitized_and_unique() {
+        let mut used = ArchiveNames::default();
+        assert_eq!(
+            archive_file_name(&entry(1, Some("Дора"), "Втюрилась"), &mut used),
+            "Дора - Втюрилась.mp3"
+        );
+        assert_eq!(
+            archive_file_name(&entry(2, Some("Дора"), "Втюрилась"), &mut used),
+            "Дора - Втюрилась (2).mp3"
+        );
+        assert_eq!(archive_file_name(&entry(3, None, "a/b: c?"), &mut used), "ab c.mp3");
+        assert_eq!(archive_file_name(&entry(4, None, "???"), &mut used), "download-4.mp3");
+    }
+
+    #[test]
+    fn names_are_trimmed_and_bounded() {
+        assert_eq!(clean_name("  Road trip ").as_deref(), Some("Road trip"));
+        assert_eq!(clean_name("   "), None);
+        assert_eq!(clean_name(&"x".repeat(MAX_NAME_LEN + 1)), None);
+    }
+
+    #[test]
+    fn zip_contains_every_file() {
+        let zip = build_zip(vec![
+            ("a.mp3".to_string(), vec![1, 2, 3]),
+            ("b.mp4".to_string(), vec![4]),
+        ])
+        .unwrap();
+        let archive = zip::ZipArchive::new(std::io::Cursor::new(zip)).unwrap();
+        assert_eq!(archive.len(), 2);
+    }
+}
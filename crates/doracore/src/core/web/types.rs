@@ -7,21 +7,40 @@ use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
 use tokio::sync::RwLock;
 
+use crate::core::admin_roles::{AdminRole, Permission};
 use crate::core::types::PlanChangeNotifier;
 use crate::storage::SharedStorage;
 
 // --- Rate limiters ---
 
+/// Login attempts, keyed by IP. Only a fallback: login counters normally live
+/// in `SharedStorage` (see `auth::login_allowed`).
 pub(super) static AUTH_RATE_LIMIT: LazyLock<RwLock<HashMap<String, (u32, std::time::Instant)>>> =
     LazyLock::new(|| RwLock::new(HashMap::new()));
 
+/// Failed portal logins, keyed by IP. Fallback for `auth::portal_login_allowed`,
+/// like `AUTH_RATE_LIMIT`.
+pub(super) static PORTAL_AUTH_RATE_LIMIT: LazyLock<RwLock<HashMap<String, (u32, std::time::Instant)>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
 pub(super) static SHARE_RATE_LIMIT: LazyLock<RwLock<HashMap<String, (u32, std::time::Instant)>>> =
     LazyLock::new(|| RwLock::new(HashMap::new()));
 
+/// Re-sends and archive builds from the user portal, keyed by user ID.
+pub(super) static PORTAL_ACTION_RATE_LIMIT: LazyLock<RwLock<HashMap<String, (u32, std::time::Instant)>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Public API calls, keyed by API key ID. The limit depends on the owner's plan.
+pub(super) static API_RATE_LIMIT: LazyLock<RwLock<HashMap<String, (u32, std::time::Instant)>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
 pub(super) const AUTH_MAX_ATTEMPTS: u32 = 10;
 pub(super) const AUTH_WINDOW_SECS: u64 = 300;
 pub(super) const SHARE_MAX_PER_MIN: u32 = 60;
 pub(super) const SHARE_WINDOW_SECS: u64 = 60;
+pub(super) const PORTAL_ACTIONS_PER_MIN: u32 = 10;
+pub(super) const PORTAL_ACTION_WINDOW_SECS: u64 = 60;
+pub(super) const API_WINDOW_SECS: u64 = 60;
 
 // --- Page size constants ---
 // Each handler sub-module defines its own local constant for the page size it uses.
@@ -325,8 +344,196 @@ pub(super) struct NotifyUserReq {
     pub mark_resolved: bool,
 }
 
+// --- Admin roles API types ---
+
+#[derive(Deserialize)]
+pub(super) struct RoleGrantReq {
+    pub user_id: i64,
+    pub role: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct ApiRoleGrant {
+    pub user_id: i64,
+    pub username: String,
+    pub role: String,
+    pub granted_by: i64,
+    pub granted_at: String,
+}
+
+/// A role and everything it may do, for the matrix shown on the dashboard.
+#[derive(Serialize)]
+pub(super) struct ApiRoleDef {
+    pub role: AdminRole,
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ApiRoles {
+    /// Owners from `config::admin::OWNER_IDS`; not editable here.
+    pub owners: Vec<i64>,
+    pub grants: Vec<ApiRoleGrant>,
+    pub roles: Vec<ApiRoleDef>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ApiAdminSession {
+    /// Hex of the session's token hash; never the cookie token itself.
+    pub id: String,
+    /// The session making this request.
+    pub current: bool,
+    pub admin_id: i64,
+    pub username: String,
+    pub created_at: String,
+    pub last_seen: String,
+    pub expires_at: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
 // AdminStats is defined locally in dashboard.rs where it is used.
 
+// --- User portal (/me) types ---
+
+#[derive(Deserialize)]
+pub(super) struct PortalHistoryQuery {
+    pub page: Option<u32>,
+    pub search: Option<String>,
+    /// `mp3` / `mp4`; anything else means all.
+    pub format: Option<String>,
+    pub category: Option<String>,
+    /// `d` / `w` / `m`; anything else means all time.
+    pub period: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct PortalDownload {
+    pub id: i64,
+    pub title: String,
+    pub author: String,
+    pub format: String,
+    pub file_size: Option<i64>,
+    pub duration: Option<i64>,
+    pub downloaded_at: String,
+    pub url: String,
+    pub category: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct PortalCategoryReq {
+    /// `None` or empty clears the category.
+    pub category: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct PortalNameReq {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct PortalPlaylist {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub item_count: i64,
+    pub updated_at: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct PortalPlaylists {
+    pub items: Vec<PortalPlaylist>,
+    pub max: i64,
+}
+
+#[derive(Serialize)]
+pub(super) struct PortalPlaylistItem {
+    pub id: i64,
+    pub position: i32,
+    pub title: String,
+    pub artist: Option<String>,
+    pub url: String,
+    pub duration_secs: Option<i32>,
+    pub download_id: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct PortalAddItemReq {
+    pub download_id: i64,
+}
+
+/// Editable settings. Every field is optional on update; unknown values are
+/// ignored rather than rejected, like the admin settings endpoint.
+#[derive(Serialize, Deserialize, Default)]
+pub(super) struct PortalSettings {
+    pub download_format: Option<String>,
+    pub video_quality: Option<String>,
+    pub audio_bitrate: Option<String>,
+    pub language: Option<String>,
+    pub send_as_document: Option<bool>,
+    pub send_audio_as_document: Option<bool>,
+    pub silent_downloads: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct PortalArchiveReq {
+    pub ids: Vec<i64>,
+}
+
+// --- Hosted links ---
+
+/// Query of a signed `/d/{id}` link: unix expiry and its HMAC.
+#[derive(Deserialize)]
+pub(super) struct HostedLinkQuery {
+    pub exp: i64,
+    pub sig: String,
+}
+
+// --- Public API (/api/v1) ---
+
+/// The key owner, resolved by `api::api_key_guard` and stored in the request
+/// extensions for handlers.
+#[derive(Clone, Copy)]
+pub(super) struct ApiCaller {
+    pub key_id: i64,
+    pub user_id: i64,
+    pub plan: crate::core::types::Plan,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ApiJobReq {
+    pub url: String,
+    /// `mp3` or `mp4`.
+    pub format: String,
+    /// Video quality for `mp4`; defaults to the user's setting.
+    pub quality: Option<String>,
+    /// Audio bitrate for `mp3`; defaults to the user's setting.
+    pub bitrate: Option<String>,
+}
+
+/// A job as reported by `GET /api/v1/jobs/{id}` and its SSE stream.
+#[derive(Serialize, Clone, PartialEq)]
+pub(super) struct ApiJobStatus {
+    pub id: String,
+    /// `queued` / `processing` / `completed` / `failed` / `expired`.
+    pub status: &'static str,
+    pub url: String,
+    pub format: String,
+    pub created_at: String,
+    /// Current step while processing (`downloading`, `merging`, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Signed download link once completed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
 // ============================================================================
 // Typed mutation-response envelopes
 //
@@ -508,6 +715,19 @@ impl BroadcastStartOk {
     }
 }
 
+/// Create response carrying the new row ID.
+#[derive(Serialize)]
+pub(super) struct CreatedOk {
+    pub ok: bool,
+    pub id: i64,
+}
+
+impl CreatedOk {
+    pub fn new(id: i64) -> Self {
+        Self { ok: true, id }
+    }
+}
+
 /// Subscription toggle response.
 #[derive(Serialize)]
 pub(super) struct ToggleOk {
@@ -35,6 +35,12 @@ pub struct DownloadConfigBuilder {
     quality_preset: Option<VideoQualityPreset>,
     cancel_flag: Option<Arc<AtomicBool>>,
     experimental_fast_encode: bool,
+    limit_rate: Option<String>,
+    resume_partial: bool,
+    ytdl_bin: Option<String>,
+    cookies_file: Option<String>,
+    instagram_cookies_file: Option<String>,
+    instagram_doc_id: Option<String>,
 }
 
 impl DownloadConfigBuilder {
@@ -53,6 +59,12 @@ impl DownloadConfigBuilder {
             quality_preset: None,
             cancel_flag: None,
             experimental_fast_encode: false,
+            limit_rate: None,
+            resume_partial: false,
+            ytdl_bin: None,
+            cookies_file: None,
+            instagram_cookies_file: None,
+            instagram_doc_id: None,
         }
     }
 
@@ -129,6 +141,43 @@ impl DownloadConfigBuilder {
         self
     }
 
+    /// Cap the download's bandwidth (yt-dlp `--limit-rate` syntax, e.g. "2M").
+    pub fn limit_rate(mut self, rate: &str) -> Self {
+        self.limit_rate = Some(rate.to_string());
+        self
+    }
+
+    /// Continue a partial download already at the output path instead of
+    /// starting over.
+    pub fn resume_partial(mut self, enabled: bool) -> Self {
+        self.resume_partial = enabled;
+        self
+    }
+
+    /// Run this download with `bin` instead of `YTDL_BIN`.
+    pub fn ytdl_bin(mut self, bin: &str) -> Self {
+        self.ytdl_bin = Some(bin.to_string());
+        self
+    }
+
+    /// Hand yt-dlp this cookies file instead of `YTDL_COOKIES_FILE`.
+    pub fn cookies_file(mut self, path: &str) -> Self {
+        self.cookies_file = Some(path.to_string());
+        self
+    }
+
+    /// Use this cookies file for Instagram instead of `INSTAGRAM_COOKIES_FILE`.
+    pub fn instagram_cookies_file(mut self, path: &str) -> Self {
+        self.instagram_cookies_file = Some(path.to_string());
+        self
+    }
+
+    /// Query Instagram's GraphQL API with this `doc_id` instead of `INSTAGRAM_DOC_ID`.
+    pub fn instagram_doc_id(mut self, doc_id: &str) -> Self {
+        self.instagram_doc_id = Some(doc_id.to_string());
+        self
+    }
+
     /// Build the `DownloadRequest`, generating the output path from title and artist.
     ///
     /// Adds a timestamp to the filename to prevent race conditions with concurrent downloads.
@@ -152,6 +201,12 @@ impl DownloadConfigBuilder {
             quality_preset: self.quality_preset,
             cancel_flag: self.cancel_flag,
             experimental_fast_encode: self.experimental_fast_encode,
+            limit_rate: self.limit_rate,
+            resume_partial: self.resume_partial,
+            ytdl_bin: self.ytdl_bin,
+            cookies_file: self.cookies_file,
+            instagram_cookies_file: self.instagram_cookies_file,
+            instagram_doc_id: self.instagram_doc_id,
         }
     }
 
@@ -0,0 +1,685 @@
+//! RSS 2.0 / Atom feed parsing for podcast and media feeds.
+//!
+//! Understands the iTunes podcast extensions (`itunes:image`,
+//! `itunes:duration`, `itunes:author`), Podlove Simple Chapters
+//! (`psc:chapters`) and Podcasting 2.0 JSON chapters (`podcast:chapters`).
+//! Feeds are fetched through the SSRF-guarded client of `source::http` with
+//! ETag / Last-Modified validators so unchanged feeds cost a 304.
+
+use crate::core::process::{FFMPEG_TIMEOUT, run_with_timeout};
+use crate::download::source::http::guarded_get;
+use crate::timestamps::{TimestampSource, VideoTimestamp};
+use anyhow::{Context, Result, bail};
+use futures_util::StreamExt;
+use reqwest::header::{ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use roxmltree::{Document, Node, ParsingOptions};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command;
+use url::Url;
+
+const ATOM_NS: &str = "http://www.w3.org/2005/Atom";
+const ITUNES_NS: &str = "http://www.itunes.com/dtds/podcast-1.0.dtd";
+const PSC_NS: &str = "http://podlove.org/simple-chapters";
+const PODCAST_NS: &str = "https://podcastindex.org/namespace/1.0";
+const MEDIA_NS: &str = "http://search.yahoo.com/mrss/";
+/// RSS 1.0 (RDF) puts its plain elements in a namespace; RSS 2.0 does not.
+const RSS1_NS: &str = "http://purl.org/rss/1.0/";
+
+/// Feeds larger than this are rejected (big podcast archives run a few MB).
+const MAX_FEED_BYTES: usize = 16 * 1024 * 1024;
+/// Chapter documents and cover images are small.
+const MAX_ASSET_BYTES: usize = 5 * 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "m4a", "aac", "ogg", "oga", "opus", "wav", "flac"];
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "mov", "webm", "mkv"];
+
+/// A parsed feed.
+#[derive(Debug, Clone, Default)]
+pub struct Feed {
+    pub title: String,
+    pub author: Option<String>,
+    /// Podcast cover (`itunes:image`, `<image><url>` or Atom `logo`/`icon`).
+    pub image_url: Option<String>,
+    /// Items in document order (usually newest first).
+    pub items: Vec<FeedItem>,
+}
+
+/// One feed item (RSS `<item>` / Atom `<entry>`).
+#[derive(Debug, Clone, Default)]
+pub struct FeedItem {
+    /// Stable dedupe key: guid, Atom id, enclosure URL, link or title — first present.
+    pub guid: String,
+    pub title: String,
+    pub link: Option<String>,
+    pub published: Option<String>,
+    pub enclosure: Option<Enclosure>,
+    pub duration_secs: Option<u32>,
+    /// Episode artwork; falls back to the feed cover at the call site.
+    pub image_url: Option<String>,
+    /// Inline Podlove chapters.
+    pub chapters: Vec<VideoTimestamp>,
+    /// Podcasting 2.0 JSON chapters document, fetched on demand.
+    pub chapters_url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnclosureKind {
+    Audio,
+    Video,
+}
+
+/// A media attachment of a feed item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enclosure {
+    pub url: String,
+    pub mime_type: Option<String>,
+    pub length: Option<u64>,
+}
+
+impl Enclosure {
+    /// Audio or video, from the MIME type or else the URL extension.
+    /// `None` for anything else (PDFs, images, …).
+    pub fn kind(&self) -> Option<EnclosureKind> {
+        if let Some(mime) = self.mime_type.as_deref().map(str::to_ascii_lowercase) {
+            if mime.starts_with("audio/") {
+                return Some(EnclosureKind::Audio);
+            }
+            if mime.starts_with("video/") {
+                return Some(EnclosureKind::Video);
+            }
+        }
+        let path = Url::parse(&self.url).ok()?.path().to_ascii_lowercase();
+        let ext = path.rsplit_once('.')?.1;
+        if AUDIO_EXTENSIONS.contains(&ext) {
+            Some(EnclosureKind::Audio)
+        } else if VIDEO_EXTENSIONS.contains(&ext) {
+            Some(EnclosureKind::Video)
+        } else {
+            None
+        }
+    }
+}
+
+/// HTTP cache validators remembered between polls.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+/// Outcome of a conditional feed fetch.
+#[derive(Debug)]
+pub enum FeedFetch {
+    NotModified,
+    Modified { body: String, validators: Validators },
+}
+
+/// `ns == None` matches plain RSS elements (no namespace or RSS 1.0).
+fn is_named(node: &Node, ns: Option<&str>, name: &str) -> bool {
+    let tag = node.tag_name();
+    node.is_element()
+        && tag.name() == name
+        && match ns {
+            Some(ns) => tag.namespace() == Some(ns),
+            None => matches!(tag.namespace(), None | Some(RSS1_NS)),
+        }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, ns: Option<&str>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| is_named(n, ns, name))
+}
+
+/// Text content including CDATA sections, trimmed; `None` when empty.
+fn text_of(node: Node) -> Option<String> {
+    let text: String = node
+        .descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn child_text(node: Node, ns: Option<&str>, name: &str) -> Option<String> {
+    child(node, ns, name).and_then(text_of)
+}
+
+fn attr(node: Node, name: &str) -> Option<String> {
+    node.attribute(name)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Parse `HH:MM:SS(.mmm)`, `MM:SS` or plain seconds.
+pub fn parse_clock(value: &str) -> Option<f64> {
+    let mut total = 0.0;
+    let parts: Vec<&str> = value.trim().split(':').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    for part in parts {
+        let n: f64 = part.trim().parse().ok()?;
+        if !n.is_finite() || n < 0.0 {
+            return None;
+        }
+        total = total * 60.0 + n;
+    }
+    Some(total)
+}
+
+fn chapter(start: f64, title: Option<String>) -> VideoTimestamp {
+    VideoTimestamp {
+        source: TimestampSource::Chapter,
+        time_seconds: start as i64,
+        end_seconds: None,
+        label: title,
+    }
+}
+
+/// Sort chapters and fill each `end_seconds` from the next start.
+fn finish_chapters(mut chapters: Vec<VideoTimestamp>) -> Vec<VideoTimestamp> {
+    chapters.sort_by_key(|c| c.time_seconds);
+    chapters.dedup_by_key(|c| c.time_seconds);
+    let starts: Vec<i64> = chapters.iter().skip(1).map(|c| c.time_seconds).collect();
+    for (c, next) in chapters.iter_mut().zip(starts) {
+        c.end_seconds = Some(next);
+    }
+    chapters
+}
+
+fn psc_chapters(item: Node) -> Vec<VideoTimestamp> {
+    let Some(list) = child(item, Some(PSC_NS), "chapters") else {
+        return Vec::new();
+    };
+    let chapters = list
+        .children()
+        .filter(|n| is_named(n, Some(PSC_NS), "chapter"))
+        .filter_map(|n| {
+            let start = parse_clock(n.attribute("start")?)?;
+            Some(chapter(start, attr(n, "title")))
+        })
+        .collect();
+    finish_chapters(chapters)
+}
+
+/// Parse a Podcasting 2.0 JSON chapters document. Chapters marked
+/// `"toc": false` are skipped.
+pub fn parse_json_chapters(json: &str) -> Result<Vec<VideoTimestamp>> {
+    #[derive(Deserialize)]
+    struct Doc {
+        #[serde(default)]
+        chapters: Vec<Entry>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Entry {
+        start_time: f64,
+        title: Option<String>,
+        toc: Option<bool>,
+    }
+
+    let doc: Doc = serde_json::from_str(json).context("invalid chapters JSON")?;
+    let chapters = doc
+        .chapters
+        .into_iter()
+        .filter(|e| e.toc != Some(false) && e.start_time.is_finite() && e.start_time >= 0.0)
+        .map(|e| chapter(e.start_time, e.title.filter(|t| !t.trim().is_empty())))
+        .collect();
+    Ok(finish_chapters(chapters))
+}
+
+fn rss_item(item: Node) -> FeedItem {
+    let enclosure = child(item, None, "enclosure")
+        .or_else(|| child(item, Some(MEDIA_NS), "content"))
+        .and_then(|n| {
+            Some(Enclosure {
+                url: attr(n, "url")?,
+                mime_type: attr(n, "type"),
+                length: n
+                    .attribute("length")
+                    .or(n.attribute("fileSize"))
+                    .and_then(|l| l.trim().parse().ok()),
+            })
+        });
+
+    FeedItem {
+        guid: String::new(),
+        title: child_text(item, None, "title")
+            .or_else(|| child_text(item, Some(ITUNES_NS), "title"))
+            .unwrap_or_default(),
+        link: child_text(item, None, "link"),
+        published: child_text(item, None, "pubDate"),
+        duration_secs: child_text(item, Some(ITUNES_NS), "duration")
+            .and_then(|d| parse_clock(&d))
+            .map(|d| d as u32),
+        image_url: child(item, Some(ITUNES_NS), "image").and_then(|n| attr(n, "href")),
+        chapters: psc_chapters(item),
+        chapters_url: child(item, Some(PODCAST_NS), "chapters").and_then(|n| attr(n, "url")),
+        enclosure,
+    }
+    .with_guid(child_text(item, None, "guid"))
+}
+
+fn atom_entry(entry: Node) -> FeedItem {
+    let links: Vec<Node> = entry
+        .children()
+        .filter(|n| is_named(n, Some(ATOM_NS), "link"))
+        .collect();
+    let link = |rel: &str| {
+        links
+            .iter()
+            .copied()
+            .find(|n| n.attribute("rel").unwrap_or("alternate") == rel)
+    };
+
+    let enclosure = link("enclosure").and_then(|n| {
+        Some(Enclosure {
+            url: attr(n, "href")?,
+            mime_type: attr(n, "type"),
+            length: n.attribute("length").and_then(|l| l.trim().parse().ok()),
+        })
+    });
+
+    FeedItem {
+        guid: String::new(),
+        title: child_text(entry, Some(ATOM_NS), "title").unwrap_or_default(),
+        link: link("alternate").and_then(|n| attr(n, "href")),
+        published: child_text(entry, Some(ATOM_NS), "published")
+            .or_else(|| child_text(entry, Some(ATOM_NS), "updated")),
+        duration_secs: child_text(entry, Some(ITUNES_NS), "duration")
+            .and_then(|d| parse_clock(&d))
+            .map(|d| d as u32),
+        image_url: child(entry, Some(ITUNES_NS), "image").and_then(|n| attr(n, "href")),
+        chapters: psc_chapters(entry),
+        chapters_url: child(entry, Some(PODCAST_NS), "chapters").and_then(|n| attr(n, "url")),
+        enclosure,
+    }
+    .with_guid(child_text(entry, Some(ATOM_NS), "id"))
+}
+
+impl FeedItem {
+    fn with_guid(mut self, guid: Option<String>) -> Self {
+        self.guid = guid
+            .or_else(|| self.enclosure.as_ref().map(|e| e.url.clone()))
+            .or_else(|| self.link.clone())
+            .unwrap_or_else(|| self.title.clone());
+        self
+    }
+}
+
+/// Parse an RSS 2.0 (or RSS 1.0/RDF) or Atom document.
+pub fn parse_feed(xml: &str) -> Result<Feed> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let doc = Document::parse_with_options(xml, options).context("feed is not valid XML")?;
+    let root = doc.root_element();
+
+    let feed = match root.tag_name().name() {
+        "rss" | "RDF" => {
+            let channel = root
+                .children()
+                .find(|n| is_named(n, None, "channel"))
+                .context("RSS feed has no <channel>")?;
+            // RSS 2.0 nests items in <channel>; RSS 1.0 puts them next to it
+            let item_parent = if root.tag_name().name() == "rss" { channel } else { root };
+            Feed {
+                title: child_text(channel, None, "title").unwrap_or_default(),
+                author: child_text(channel, Some(ITUNES_NS), "author"),
+                image_url: child(channel, Some(ITUNES_NS), "image")
+                    .and_then(|n| attr(n, "href"))
+                    .or_else(|| child(channel, None, "image").and_then(|n| child_text(n, None, "url"))),
+                items: item_parent
+                    .children()
+                    .filter(|n| is_named(n, None, "item"))
+                    .map(rss_item)
+                    .collect(),
+            }
+        }
+        "feed" if root.tag_name().namespace() == Some(ATOM_NS) => Feed {
+            title: child_text(root, Some(ATOM_NS), "title").unwrap_or_default(),
+            author: child(root, Some(ATOM_NS), "author").and_then(|a| child_text(a, Some(ATOM_NS), "name")),
+            image_url: child(root, Some(ITUNES_NS), "image")
+                .and_then(|n| attr(n, "href"))
+                .or_else(|| child_text(root, Some(ATOM_NS), "logo"))
+                .or_else(|| child_text(root, Some(ATOM_NS), "icon")),
+            items: root
+                .children()
+                .filter(|n| is_named(n, Some(ATOM_NS), "entry"))
+                .map(atom_entry)
+                .collect(),
+        },
+        other => bail!("not an RSS or Atom feed (root element <{}>)", other),
+    };
+    Ok(feed)
+}
+
+/// Read a response body, refusing anything larger than `limit`.
+async fn read_limited(response: reqwest::Response, limit: usize) -> Result<Vec<u8>> {
+    if response.content_length().is_some_and(|len| len as usize > limit) {
+        bail!("response larger than {} bytes", limit);
+    }
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        body.extend_from_slice(&chunk.context("failed to read response body")?);
+        if body.len() > limit {
+            bail!("response larger than {} bytes", limit);
+        }
+    }
+    Ok(body)
+}
+
+/// SSRF-guarded GET with a size cap. `None` means 304 Not Modified.
+async fn get(url: &str, headers: HeaderMap, limit: usize) -> Result<Option<(HeaderMap, Vec<u8>)>> {
+    let url = Url::parse(url).with_context(|| format!("invalid URL {}", url))?;
+    let response = tokio::time::timeout(FETCH_TIMEOUT, guarded_get(&url, headers))
+        .await
+        .context("request timed out")??;
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        bail!("HTTP {} for {}", response.status(), url);
+    }
+    let headers = response.headers().clone();
+    let body = tokio::time::timeout(FETCH_TIMEOUT, read_limited(response, limit))
+        .await
+        .context("download timed out")??;
+    Ok(Some((headers, body)))
+}
+
+/// Fetch a feed, sending `If-None-Match` / `If-Modified-Since` from the
+/// previous poll. Returns [`FeedFetch::NotModified`] on 304.
+pub async fn fetch_feed(url: &str, validators: &Validators) -> Result<FeedFetch> {
+    let mut headers = HeaderMap::new();
+    if let Some(v) = validators.etag.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+        headers.insert(IF_NONE_MATCH, v);
+    }
+    if let Some(v) = validators
+        .last_modified
+        .as_deref()
+        .and_then(|v| HeaderValue::from_str(v).ok())
+    {
+        headers.insert(IF_MODIFIED_SINCE, v);
+    }
+
+    let Some((headers, body)) = get(url, headers, MAX_FEED_BYTES).await? else {
+        return Ok(FeedFetch::NotModified);
+    };
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    Ok(FeedFetch::Modified {
+        body: String::from_utf8_lossy(&body).into_owned(),
+        validators: Validators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        },
+    })
+}
+
+/// Fetch and parse a Podcasting 2.0 JSON chapters document.
+pub async fn fetch_json_chapters(url: &str) -> Result<Vec<VideoTimestamp>> {
+    let (_, body) = get(url, HeaderMap::new(), MAX_ASSET_BYTES)
+        .await?
+        .context("unexpected 304 for chapters")?;
+    parse_json_chapters(&String::from_utf8_lossy(&body))
+}
+
+/// Write `chapters` as an FFMETADATA document (millisecond timebase).
+fn ffmetadata(chapters: &[VideoTimestamp], duration_secs: Option<u32>) -> String {
+    let escape = |s: &str| {
+        s.chars()
+            .flat_map(|c| match c {
+                '=' | ';' | '#' | '\\' | '\n' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect::<String>()
+    };
+    let mut out = String::from(";FFMETADATA1\n");
+    for (i, c) in chapters.iter().enumerate() {
+        let end = c
+            .end_seconds
+            .or(duration_secs.map(i64::from))
+            .filter(|end| *end > c.time_seconds)
+            .unwrap_or(c.time_seconds + 1);
+        out.push_str(&format!(
+            "[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            c.time_seconds * 1000,
+            end * 1000,
+            escape(c.label.as_deref().unwrap_or(&format!("Chapter {}", i + 1)))
+        ));
+    }
+    out
+}
+
+/// Embed podcast chapters and cover art into a downloaded audio file in place
+/// (stream copy, no re-encode). `cover_url` is fetched through the SSRF guard.
+/// Best effort: the original file is left untouched on any failure.
+pub async fn embed_episode_tags(
+    path: &str,
+    cover_url: Option<&str>,
+    chapters: &[VideoTimestamp],
+    duration_secs: Option<u32>,
+) -> Result<()> {
+    if cover_url.is_none() && chapters.is_empty() {
+        return Ok(());
+    }
+
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("mp3")
+        .to_ascii_lowercase();
+    let stem = format!("{}.tags", path);
+    let meta_path = format!("{}.ffmeta", stem);
+    let cover_path = format!("{}.cover", stem);
+    let out_path = format!("{}.{}", stem, ext);
+
+    let cover = match cover_url {
+        Some(url) => match get(url, HeaderMap::new(), MAX_ASSET_BYTES).await {
+            Ok(Some((_, bytes))) if !bytes.is_empty() => {
+                fs_err::tokio::write(&cover_path, bytes).await?;
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                log::warn!("embed_episode_tags: cover fetch failed for {}: {}", url, e);
+                false
+            }
+        },
+        None => false,
+    };
+    fs_err::tokio::write(&meta_path, ffmetadata(chapters, duration_secs)).await?;
+
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(["-y", "-i", path, "-i", &meta_path]);
+    if cover {
+        cmd.args(["-i", &cover_path]);
+    }
+    cmd.args(["-map", "0:a", "-map_metadata", "0", "-map_chapters", "1", "-c", "copy"]);
+    if cover {
+        cmd.args(["-map", "2:v", "-disposition:v:0", "attached_pic"]);
+    }
+    if ext == "mp3" {
+        cmd.args(["-id3v2_version", "3"]);
+    }
+    cmd.arg(&out_path);
+
+    let result = run_with_timeout(&mut cmd, FFMPEG_TIMEOUT).await;
+    let _ = fs_err::tokio::remove_file(&meta_path).await;
+    let _ = fs_err::tokio::remove_file(&cover_path).await;
+
+    match result {
+        Ok(output) if output.status.success() => {
+            fs_err::tokio::rename(&out_path, path).await?;
+            Ok(())
+        }
+        Ok(output) => {
+            let _ = fs_err::tokio::remove_file(&out_path).await;
+            bail!(
+                "ffmpeg failed to tag {}: {}",
+                path,
+                String::from_utf8_lossy(&output.stderr).lines().last().unwrap_or("")
+            )
+        }
+        Err(e) => {
+            let _ = fs_err::tokio::remove_file(&out_path).await;
+            Err(e.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PODCAST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"
+     xmlns:psc="http://podlove.org/simple-chapters"
+     xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Dora Talks</title>
+    <itunes:author>Dora</itunes:author>
+    <itunes:image href="https://example.com/cover.jpg"/>
+    <item>
+      <title><![CDATA[Episode 2: Ropes & Knots]]></title>
+      <guid isPermaLink="false">ep-2</guid>
+      <pubDate>Tue, 02 Jan 2024 10:00:00 GMT</pubDate>
+      <enclosure url="https://cdn.example.com/ep2.mp3" type="audio/mpeg" length="1234"/>
+      <itunes:duration>1:02:03</itunes:duration>
+      <itunes:image href="https://example.com/ep2.jpg"/>
+      <psc:chapters version="1.2">
+        <psc:chapter start="00:05:00.500" title="Knots"/>
+        <psc:chapter start="0" title="Intro"/>
+      </psc:chapters>
+      <podcast:chapters url="https://example.com/ep2.json" type="application/json+chapters"/>
+    </item>
+    <item>
+      <title>Trailer video</title>
+      <enclosure url="https://cdn.example.com/trailer" type="video/mp4"/>
+    </item>
+    <item>
+      <title>Show notes only</title>
+      <link>https://example.com/notes</link>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn parses_rss_podcast() {
+        let feed = parse_feed(PODCAST).unwrap();
+        assert_eq!(feed.title, "Dora Talks");
+        assert_eq!(feed.author.as_deref(), Some("Dora"));
+        assert_eq!(feed.image_url.as_deref(), Some("https://example.com/cover.jpg"));
+        assert_eq!(feed.items.len(), 3);
+
+        let ep = &feed.items[0];
+        assert_eq!(ep.guid, "ep-2");
+        assert_eq!(ep.title, "Episode 2: Ropes & Knots");
+        assert_eq!(ep.duration_secs, Some(3723));
+        assert_eq!(ep.image_url.as_deref(), Some("https://example.com/ep2.jpg"));
+        assert_eq!(ep.chapters_url.as_deref(), Some("https://example.com/ep2.json"));
+        let enclosure = ep.enclosure.as_ref().unwrap();
+        assert_eq!(enclosure.length, Some(1234));
+        assert_eq!(enclosure.kind(), Some(EnclosureKind::Audio));
+
+        // Chapters are sorted and chained
+        let starts: Vec<i64> = ep.chapters.iter().map(|c| c.time_seconds).collect();
+        assert_eq!(starts, vec![0, 300]);
+        assert_eq!(ep.chapters[0].end_seconds, Some(300));
+        assert_eq!(ep.chapters[1].label.as_deref(), Some("Knots"));
+    }
+
+    #[test]
+    fn guid_falls_back_to_enclosure_then_link() {
+        let feed = parse_feed(PODCAST).unwrap();
+        assert_eq!(feed.items[1].guid, "https://cdn.example.com/trailer");
+        assert_eq!(
+            feed.items[1].enclosure.as_ref().unwrap().kind(),
+            Some(EnclosureKind::Video)
+        );
+        assert_eq!(feed.items[2].guid, "https://example.com/notes");
+        assert!(feed.items[2].enclosure.is_none());
+    }
+
+    #[test]
+    fn parses_atom_with_enclosure() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Vlog</title>
+  <logo>https://example.com/logo.png</logo>
+  <author><name>Stan</name></author>
+  <entry>
+    <id>tag:example.com,2024:1</id>
+    <title type="html">First</title>
+    <updated>2024-01-01T00:00:00Z</updated>
+    <link href="https://example.com/1"/>
+    <link rel="enclosure" href="https://example.com/1.webm" type="video/webm" length="99"/>
+  </entry>
+</feed>"#;
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.title, "Vlog");
+        assert_eq!(feed.author.as_deref(), Some("Stan"));
+        assert_eq!(feed.image_url.as_deref(), Some("https://example.com/logo.png"));
+        let entry = &feed.items[0];
+        assert_eq!(entry.guid, "tag:example.com,2024:1");
+        assert_eq!(entry.link.as_deref(), Some("https://example.com/1"));
+        assert_eq!(entry.published.as_deref(), Some("2024-01-01T00:00:00Z"));
+        assert_eq!(entry.enclosure.as_ref().unwrap().kind(), Some(EnclosureKind::Video));
+    }
+
+    #[test]
+    fn rejects_non_feeds() {
+        assert!(parse_feed("<html><body/></html>").is_err());
+        assert!(parse_feed("not xml").is_err());
+    }
+
+    #[test]
+    fn enclosure_kind_from_extension() {
+        let e = |url: &str, mime: Option<&str>| Enclosure {
+            url: url.to_string(),
+            mime_type: mime.map(str::to_string),
+            length: None,
+        };
+        assert_eq!(e("https://x.com/a.m4a?x=1", None).kind(), Some(EnclosureKind::Audio));
+        assert_eq!(e("https://x.com/a.MP4", None).kind(), Some(EnclosureKind::Video));
+        assert_eq!(e("https://x.com/a.pdf", Some("application/pdf")).kind(), None);
+    }
+
+    #[test]
+    fn parses_clock_values() {
+        assert_eq!(parse_clock("3723"), Some(3723.0));
+        assert_eq!(parse_clock("62:03"), Some(3723.0));
+        assert_eq!(parse_clock("01:02:03.5"), Some(3723.5));
+        assert_eq!(parse_clock("1:2:3:4"), None);
+        assert_eq!(parse_clock("abc"), None);
+    }
+
+    #[test]
+    fn parses_json_chapters_skipping_hidden() {
+        let json = r#"{"version":"1.2.0","chapters":[
+            {"startTime":0,"title":"Intro"},
+            {"startTime":95.5,"title":"Ad","toc":false},
+            {"startTime":120,"title":"Main"}]}"#;
+        let chapters = parse_json_chapters(json).unwrap();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].end_seconds, Some(120));
+        assert_eq!(chapters[1].label.as_deref(), Some("Main"));
+        assert_eq!(chapters[1].source, TimestampSource::Chapter);
+    }
+
+    #[test]
+    fn ffmetadata_escapes_and_closes_last_chapter() {
+        let chapters = finish_chapters(vec![chapter(0.0, Some("A=B".into())), chapter(60.0, None)]);
+        let meta = ffmetadata(&chapters, Some(90));
+        assert!(meta.starts_with(";FFMETADATA1\n"));
+        assert!(meta.contains("START=0\nEND=60000\ntitle=A\\=B\n"));
+        assert!(meta.contains("START=60000\nEND=90000\ntitle=Chapter 2\n"));
+    }
+}
@@ -0,0 +1,599 @@
+//! Output filename templates.
+//!
+//! A template is plain text with `{field}` placeholders; `/` starts a
+//! subfolder, so `{artist}/{album}/{playlist_index:02} {title}` files tracks
+//! into an Artist/Album library. Placeholders take an optional zero-pad
+//! width (`{playlist_index:03}`, index only) and a fallback for when the
+//! value is unknown (`{album|Singles}`).
+//!
+//! Field values are run through [`sanitize_filename`] before they are
+//! substituted, so a `/` in a title never creates a folder; every rendered
+//! path segment is sanitized again and trimmed so it stays a plain relative
+//! path (no `..`, no absolute paths, no empty folders).
+
+use crate::core::config;
+use crate::core::process::run_with_timeout;
+use crate::core::validation::sanitize_filename;
+use crate::download::metadata::{add_cookies_file_args_with_proxy, get_proxy_chain};
+use crate::storage::db::DownloadHistoryEntry;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::sync::LazyLock;
+use tokio::process::Command;
+use url::Url;
+
+/// What every frontend used before templates existed.
+pub const DEFAULT_TEMPLATE: &str = "{artist} - {title}";
+
+/// Artist/Album/Track folder hierarchy.
+pub const LIBRARY_TEMPLATE: &str = "{artist|Unknown Artist}/{album|Singles}/{playlist_index:02} {title}";
+
+/// Longest path segment in bytes; most filesystems stop at 255 and the
+/// extension and collision suffix need room.
+const MAX_SEGMENT_BYTES: usize = 200;
+
+/// A placeholder name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Artist,
+    Album,
+    Uploader,
+    UploadDate,
+    Platform,
+    PlaylistIndex,
+    Format,
+    Quality,
+}
+
+impl Field {
+    pub const ALL: [Field; 9] = [
+        Field::Title,
+        Field::Artist,
+        Field::Album,
+        Field::Uploader,
+        Field::UploadDate,
+        Field::Platform,
+        Field::PlaylistIndex,
+        Field::Format,
+        Field::Quality,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Title => "title",
+            Field::Artist => "artist",
+            Field::Album => "album",
+            Field::Uploader => "uploader",
+            Field::UploadDate => "upload_date",
+            Field::Platform => "platform",
+            Field::PlaylistIndex => "playlist_index",
+            Field::Format => "format",
+            Field::Quality => "quality",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.name() == name)
+    }
+}
+
+/// Values a template is rendered from. Unknown values stay `None` and render
+/// as the placeholder's fallback (or nothing).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateFields {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub uploader: Option<String>,
+    /// `YYYY-MM-DD`
+    pub upload_date: Option<String>,
+    /// Lowercase site name: `youtube`, `soundcloud`, ...
+    pub platform: Option<String>,
+    /// 1-based position in the playlist the item was queued from.
+    pub playlist_index: Option<usize>,
+    /// Container: `mp3`, `mp4`, ...
+    pub format: Option<String>,
+    /// `1080p`, `320k`, ...
+    pub quality: Option<String>,
+}
+
+impl TemplateFields {
+    /// Fields for `url`: platform from the host, playlist index from an
+    /// `index=` query parameter. Everything else is filled in by the caller.
+    pub fn for_url(url: &Url) -> Self {
+        Self {
+            platform: platform_from_url(url),
+            playlist_index: url
+                .query_pairs()
+                .find(|(k, _)| k == "index")
+                .and_then(|(_, v)| v.parse().ok()),
+            ..Default::default()
+        }
+    }
+
+    /// Fill unset fields from a yt-dlp info JSON (`--dump-single-json`).
+    pub fn merge_info_json(&mut self, info: &serde_json::Value) {
+        let text = |key: &str| {
+            info.get(key)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let uploader = text("uploader").or_else(|| text("channel"));
+
+        self.title = self.title.take().or_else(|| text("track")).or_else(|| text("title"));
+        self.artist = self
+            .artist
+            .take()
+            .or_else(|| text("artist"))
+            .or_else(|| text("creator"))
+            .or_else(|| uploader.clone());
+        self.album = self.album.take().or_else(|| text("album"));
+        self.uploader = self.uploader.take().or(uploader);
+        self.upload_date = self
+            .upload_date
+            .take()
+            .or_else(|| text("upload_date").and_then(|d| iso_date(&d)));
+        if self.platform.is_none() {
+            self.platform = text("webpage_url")
+                .and_then(|u| Url::parse(&u).ok())
+                .and_then(|u| platform_from_url(&u))
+                .or_else(|| text("extractor_key").map(|k| k.to_lowercase()));
+        }
+        self.playlist_index = self
+            .playlist_index
+            .or_else(|| info.get("playlist_index").and_then(|v| v.as_u64()).map(|i| i as usize));
+    }
+}
+
+impl From<&DownloadHistoryEntry> for TemplateFields {
+    fn from(entry: &DownloadHistoryEntry) -> Self {
+        let mut fields = Url::parse(&entry.url).map(|u| Self::for_url(&u)).unwrap_or_default();
+        fields.title = Some(entry.title.clone());
+        fields.artist = entry.author.clone();
+        fields.format = Some(entry.format.clone());
+        fields.quality = entry.video_quality.clone().or_else(|| entry.audio_bitrate.clone());
+        fields
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Field {
+        field: Field,
+        width: usize,
+        fallback: Option<String>,
+    },
+}
+
+/// A parsed filename template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilenameTemplate {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl FilenameTemplate {
+    /// Parse `template`. Errors are short, user-facing sentences.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let source = template.trim();
+        if source.is_empty() {
+            return Err("Template is empty".to_string());
+        }
+
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = source;
+        while let Some(open) = rest.find(['{', '}']) {
+            if rest[open..].starts_with('}') {
+                return Err("Unmatched '}'".to_string());
+            }
+            literal.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let close = after.find('}').ok_or("Unclosed '{'")?;
+            let placeholder = &after[..close];
+            if placeholder.contains('{') {
+                return Err("Unclosed '{'".to_string());
+            }
+            if !literal.is_empty() {
+                parts.push(Part::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(parse_placeholder(placeholder)?);
+            rest = &after[close + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        if !parts.iter().any(|p| matches!(p, Part::Field { .. })) {
+            return Err("Template needs at least one {field}".to_string());
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            parts,
+        })
+    }
+
+    /// The default `{artist} - {title}` template.
+    pub fn default_template() -> Self {
+        Self::parse(DEFAULT_TEMPLATE).expect("default template parses")
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether rendering reads `field`.
+    pub fn uses(&self, field: Field) -> bool {
+        self.parts
+            .iter()
+            .any(|p| matches!(p, Part::Field { field: f, .. } if *f == field))
+    }
+
+    /// Whether rendering needs more than title, artist, platform, format and
+    /// quality — i.e. whether callers should fetch the full info JSON.
+    pub fn needs_info_json(&self) -> bool {
+        [Field::Album, Field::Uploader, Field::UploadDate, Field::PlaylistIndex]
+            .into_iter()
+            .any(|f| self.uses(f))
+    }
+
+    /// Relative path `folders/name.ext`, or `None` when the file name itself
+    /// comes out empty (every field it uses is unknown).
+    pub fn render(&self, fields: &TemplateFields, ext: &str) -> Option<PathBuf> {
+        let mut rendered = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => rendered.push_str(text),
+                Part::Field { field, width, fallback } => {
+                    let value = field_value(fields, *field, *width)
+                        .map(|v| sanitize_filename(&v))
+                        .filter(|v| !v.trim().is_empty())
+                        .or_else(|| fallback.clone());
+                    rendered.push_str(value.as_deref().unwrap_or_default());
+                }
+            }
+        }
+
+        let mut segments: Vec<&str> = rendered.split('/').collect();
+        let stem = clean_segment(segments.pop().unwrap_or_default());
+        if stem.is_empty() {
+            return None;
+        }
+        let mut path: PathBuf = segments
+            .into_iter()
+            .map(clean_segment)
+            .filter(|s| !s.is_empty())
+            .collect();
+        path.push(format!("{}.{}", stem, ext));
+        Some(path)
+    }
+}
+
+impl Default for FilenameTemplate {
+    fn default() -> Self {
+        Self::default_template()
+    }
+}
+
+impl fmt::Display for FilenameTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<Part, String> {
+    let (spec, fallback) = match placeholder.split_once('|') {
+        Some((spec, fallback)) => (spec, Some(fallback.to_string())),
+        None => (placeholder, None),
+    };
+    let (name, width) = match spec.split_once(':') {
+        Some((name, width)) => (name.trim(), Some(width.trim())),
+        None => (spec.trim(), None),
+    };
+    let field = Field::from_name(name).ok_or_else(|| {
+        let known: Vec<&str> = Field::ALL.iter().map(|f| f.name()).collect();
+        format!("Unknown field {{{}}} — use one of: {}", name, known.join(", "))
+    })?;
+    let width = match width {
+        None => 0,
+        Some(_) if field != Field::PlaylistIndex => {
+            return Err(format!("Only {{playlist_index}} takes a width, not {{{}}}", name));
+        }
+        Some(w) => w
+            .parse::<usize>()
+            .ok()
+            .filter(|w| *w <= 6)
+            .ok_or_else(|| format!("Bad width '{}' in {{{}}}", w, name))?,
+    };
+    Ok(Part::Field { field, width, fallback })
+}
+
+fn field_value(fields: &TemplateFields, field: Field, width: usize) -> Option<String> {
+    match field {
+        Field::Title => fields.title.clone(),
+        Field::Artist => fields.artist.clone(),
+        Field::Album => fields.album.clone(),
+        Field::Uploader => fields.uploader.clone(),
+        Field::UploadDate => fields.upload_date.clone(),
+        Field::Platform => fields.platform.clone(),
+        Field::PlaylistIndex => fields.playlist_index.map(|i| format!("{:0w$}", i, w = width)),
+        Field::Format => fields.format.clone(),
+        Field::Quality => fields.quality.clone(),
+    }
+}
+
+/// One path segment: filesystem-safe, no leading dots (hidden files, `..`),
+/// no dangling separators left by empty fields, bounded length.
+fn clean_segment(segment: &str) -> String {
+    let cleaned = sanitize_filename(segment);
+    let trimmed = cleaned
+        .trim_matches(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .trim_start_matches('.')
+        .trim_end_matches(|c: char| c.is_whitespace() || c == '.');
+    let mut end = trimmed.len().min(MAX_SEGMENT_BYTES);
+    while !trimmed.is_char_boundary(end) {
+        end -= 1;
+    }
+    trimmed[..end].trim_end().to_string()
+}
+
+/// `20240131` → `2024-01-31`.
+fn iso_date(yyyymmdd: &str) -> Option<String> {
+    let d = yyyymmdd.trim();
+    (d.len() == 8 && d.bytes().all(|b| b.is_ascii_digit())).then(|| format!("{}-{}-{}", &d[..4], &d[4..6], &d[6..]))
+}
+
+/// `https://music.youtube.com/...` → `youtube`, `https://youtu.be/...` → `youtube`.
+fn platform_from_url(url: &Url) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    if host == "youtu.be" {
+        return Some("youtube".to_string());
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let name = match labels.len() {
+        0 | 1 => host.as_str(),
+        n => labels[n - 2],
+    };
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// yt-dlp's info JSON for a single item, for [`TemplateFields::merge_info_json`].
+///
+/// `ytdl_bin` / `cookies_file` override `YTDL_BIN` / `YTDL_COOKIES_FILE`,
+/// the same as on a `DownloadRequest`.
+pub async fn fetch_info_json(
+    url: &Url,
+    ytdl_bin: Option<&str>,
+    cookies_file: Option<&str>,
+) -> anyhow::Result<serde_json::Value> {
+    let ytdl_bin = ytdl_bin.unwrap_or(&config::YTDL_BIN);
+    let mut args: Vec<&str> = vec![
+        "--dump-single-json",
+        "--no-playlist",
+        "--skip-download",
+        "--socket-timeout",
+        "30",
+    ];
+    let proxy = get_proxy_chain().into_iter().flatten().next();
+    add_cookies_file_args_with_proxy(&mut args, proxy.as_ref(), None, cookies_file);
+    args.push(url.as_str());
+
+    let mut cmd = Command::new(ytdl_bin);
+    cmd.args(&args).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let output = run_with_timeout(&mut cmd, config::download::ytdlp_timeout())
+        .await
+        .with_context(|| "Failed to run yt-dlp")?;
+    if !output.status.success() {
+        anyhow::bail!("yt-dlp failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    serde_json::from_slice(&output.stdout).with_context(|| "Failed to parse yt-dlp info JSON")
+}
+
+/// `dir/rel`, or `dir/.../stem_2.ext`, `stem_3.ext`, ... when a file with
+/// that name is already there, so repeated downloads never overwrite.
+pub fn unique_path(dir: &Path, rel: impl AsRef<Path>) -> PathBuf {
+    let candidate = dir.join(rel);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let parent = candidate.parent().unwrap_or(dir).to_path_buf();
+    let Some(stem) = candidate.file_stem().and_then(|s| s.to_str()) else {
+        return candidate;
+    };
+    let ext = candidate.extension().and_then(|e| e.to_str());
+    (2..)
+        .map(|n| match ext {
+            Some(ext) => parent.join(format!("{}_{}.{}", stem, n, ext)),
+            None => parent.join(format!("{}_{}", stem, n)),
+        })
+        .find(|p| !p.exists())
+        .unwrap_or(candidate)
+}
+
+/// Hands out archive entry names, turning the second `a.mp3` into
+/// `a (2).mp3`. Names use `/` between folders, as ZIP expects.
+#[derive(Debug, Default)]
+pub struct ArchiveNames {
+    used: HashMap<String, usize>,
+}
+
+impl ArchiveNames {
+    pub fn claim(&mut self, rel: &Path) -> String {
+        let name = rel
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => s.to_str(),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        let count = self.used.entry(name.clone()).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            return name;
+        }
+        match name.rsplit_once('.') {
+            Some((stem, ext)) if !stem.ends_with('/') && !stem.is_empty() => format!("{} ({}).{}", stem, count, ext),
+            _ => format!("{} ({})", name, count),
+        }
+    }
+}
+
+/// Template for archive entry names (`ARCHIVE_NAME_TEMPLATE`); an invalid
+/// value is logged once and the default is used instead.
+pub fn archive_template() -> &'static FilenameTemplate {
+    static TEMPLATE: LazyLock<FilenameTemplate> =
+        LazyLock::new(|| match FilenameTemplate::parse(&config::ARCHIVE_NAME_TEMPLATE) {
+            Ok(t) => t,
+            Err(e) => {
+                log::warn!("ARCHIVE_NAME_TEMPLATE ignored: {}", e);
+                FilenameTemplate::default_template()
+            }
+        });
+    &TEMPLATE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> TemplateFields {
+        TemplateFields {
+            title: Some("Втюрилась".to_string()),
+            artist: Some("Дора".to_string()),
+            album: Some("Младшая сестра".to_string()),
+            playlist_index: Some(3),
+            format: Some("mp3".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_template_matches_legacy_names() {
+        let t = FilenameTemplate::default_template();
+        assert_eq!(
+            t.render(&fields(), "mp3").unwrap(),
+            PathBuf::from("Дора - Втюрилась.mp3")
+        );
+
+        let no_artist = TemplateFields {
+            artist: None,
+            ..fields()
+        };
+        assert_eq!(t.render(&no_artist, "mp3").unwrap(), PathBuf::from("Втюрилась.mp3"));
+        assert_eq!(t.render(&TemplateFields::default(), "mp3"), None);
+    }
+
+    #[test]
+    fn library_template_builds_folders() {
+        let t = FilenameTemplate::parse(LIBRARY_TEMPLATE).unwrap();
+        assert_eq!(
+            t.render(&fields(), "mp3").unwrap(),
+            PathBuf::from("Дора/Младшая сестра/03 Втюрилась.mp3")
+        );
+
+        let single = TemplateFields {
+            album: None,
+            playlist_index: None,
+            ..fields()
+        };
+        assert_eq!(
+            t.render(&single, "mp3").unwrap(),
+            PathBuf::from("Дора/Singles/Втюрилась.mp3")
+        );
+        assert!(t.needs_info_json());
+        assert!(!FilenameTemplate::default_template().needs_info_json());
+    }
+
+    #[test]
+    fn values_cannot_escape_or_add_folders() {
+        let t = FilenameTemplate::parse("{artist}/{title}").unwrap();
+        let nasty = TemplateFields {
+            title: Some("a/b: c?".to_string()),
+            artist: Some("../..".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(t.render(&nasty, "mp3").unwrap(), PathBuf::from("ab c.mp3"));
+
+        let long = TemplateFields {
+            title: Some("я".repeat(300)),
+            ..Default::default()
+        };
+        let name = t.render(&long, "mp3").unwrap();
+        assert!(name.to_str().unwrap().len() <= MAX_SEGMENT_BYTES + 4);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(FilenameTemplate::parse("").is_err());
+        assert!(FilenameTemplate::parse("no fields").is_err());
+        assert!(FilenameTemplate::parse("{titel}").unwrap_err().contains("titel"));
+        assert!(FilenameTemplate::parse("{title").is_err());
+        assert!(FilenameTemplate::parse("title}").is_err());
+        assert!(FilenameTemplate::parse("{title:02}").is_err());
+        assert!(FilenameTemplate::parse("{playlist_index:x}").is_err());
+        assert!(FilenameTemplate::parse("{upload_date} {title|Untitled}").is_ok());
+    }
+
+    #[test]
+    fn info_json_fills_missing_fields() {
+        let info = serde_json::json!({
+            "title": "Song (Official Video)",
+            "uploader": "Dora Official",
+            "upload_date": "20240131",
+            "webpage_url": "https://www.youtube.com/watch?v=x",
+            "playlist_index": 7,
+        });
+        let mut f = TemplateFields {
+            title: Some("Song".to_string()),
+            ..Default::default()
+        };
+        f.merge_info_json(&info);
+        assert_eq!(f.title.as_deref(), Some("Song"));
+        assert_eq!(f.artist.as_deref(), Some("Dora Official"));
+        assert_eq!(f.upload_date.as_deref(), Some("2024-01-31"));
+        assert_eq!(f.platform.as_deref(), Some("youtube"));
+        assert_eq!(f.playlist_index, Some(7));
+
+        let url = Url::parse("https://youtu.be/x?list=PL1&index=4").unwrap();
+        let f = TemplateFields::for_url(&url);
+        assert_eq!(f.platform.as_deref(), Some("youtube"));
+        assert_eq!(f.playlist_index, Some(4));
+    }
+
+    #[test]
+    fn unique_path_avoids_existing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(unique_path(dir.path(), "song.mp3"), dir.path().join("song.mp3"));
+
+        fs_err::write(dir.path().join("song.mp3"), "a").unwrap();
+        assert_eq!(unique_path(dir.path(), "song.mp3"), dir.path().join("song_2.mp3"));
+
+        fs_err::write(dir.path().join("song_2.mp3"), "b").unwrap();
+        assert_eq!(unique_path(dir.path(), "song.mp3"), dir.path().join("song_3.mp3"));
+
+        fs_err::create_dir_all(dir.path().join("Dora/Singles")).unwrap();
+        fs_err::write(dir.path().join("Dora/Singles/song.mp3"), "c").unwrap();
+        assert_eq!(
+            unique_path(dir.path(), "Dora/Singles/song.mp3"),
+            dir.path().join("Dora/Singles/song_2.mp3")
+        );
+    }
+
+    #[test]
+    fn archive_names_are_unique() {
+        let mut names = ArchiveNames::default();
+        assert_eq!(names.claim(Path::new("Dora/a.mp3")), "Dora/a.mp3");
+        assert_eq!(names.claim(Path::new("Dora/a.mp3")), "Dora/a (2).mp3");
+        assert_eq!(names.claim(Path::new("a.mp3")), "a.mp3");
+    }
+}
@@ -263,6 +263,17 @@ pub fn add_cookies_args_with_proxy<'a>(
     args: &mut Vec<&'a str>,
     proxy: Option<&ProxyConfig>,
     cached_pot_arg: Option<&'a str>,
+) {
+    add_cookies_file_args_with_proxy(args, proxy, cached_pot_arg, None);
+}
+
+/// Like [`add_cookies_args_with_proxy`], but `cookies_file` (when set) is
+/// passed to yt-dlp instead of `YTDL_COOKIES_FILE` / `YTDL_COOKIES_BROWSER`.
+pub fn add_cookies_file_args_with_proxy<'a>(
+    args: &mut Vec<&'a str>,
+    proxy: Option<&ProxyConfig>,
+    cached_pot_arg: Option<&'a str>,
+    cookies_file: Option<&'a str>,
 ) {
     if let Some(proxy_config) = proxy {
         log::info!("Using proxy [{}]: {}", proxy_config.name, proxy_config.masked_url());
@@ -298,6 +309,14 @@ pub fn add_cookies_args_with_proxy<'a>(
         }
     }
 
+    // Per-request cookies file (dora passes its settings this way)
+    if let Some(path) = cookies_file {
+        args.push("--cookies");
+        args.push(path);
+        log::debug!("Using request cookies path: {}", path);
+        return;
+    }
+
     // Priority 1: Cookies file (use cached path — no allocation)
     if let Some(cached_path) = get_cached_cookies_path() {
         args.push("--cookies");
@@ -408,6 +427,16 @@ pub fn add_no_cookies_args(args: &mut Vec<&str>, proxy: Option<&ProxyConfig>) {
 ///
 /// Returns `true` if Instagram cookies were added, `false` if none are available.
 pub fn add_instagram_cookies_args_with_proxy(args: &mut Vec<&str>, proxy: Option<&ProxyConfig>) -> bool {
+    add_instagram_cookies_file_args_with_proxy(args, proxy, None)
+}
+
+/// Like [`add_instagram_cookies_args_with_proxy`], but `cookies_file` (when
+/// set) is used instead of `INSTAGRAM_COOKIES_FILE`.
+pub fn add_instagram_cookies_file_args_with_proxy<'a>(
+    args: &mut Vec<&'a str>,
+    proxy: Option<&ProxyConfig>,
+    cookies_file: Option<&'a str>,
+) -> bool {
     if let Some(proxy_config) = proxy {
         log::info!(
             "[IG_COOKIES] Using proxy [{}]: {}",
@@ -422,6 +451,13 @@ pub fn add_instagram_cookies_args_with_proxy(args: &mut Vec<&str>, proxy: Option
         }
     }
 
+    if let Some(path) = cookies_file {
+        args.push("--cookies");
+        args.push(path);
+        log::info!("[IG_COOKIES] Using request Instagram cookies: {}", path);
+        return true;
+    }
+
     if let Some(cached_path) = get_cached_instagram_cookies_path() {
         args.push("--cookies");
         args.push(cached_path);
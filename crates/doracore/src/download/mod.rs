@@ -6,7 +6,9 @@ pub mod cookies;
 pub mod downloader;
 pub mod error;
 pub mod fast_metadata;
+pub mod feed;
 pub mod fetch;
+pub mod filename_template;
 pub mod metadata;
 pub mod playlist;
 pub mod progress;
@@ -7,6 +7,7 @@ use crate::core::process::run_with_timeout;
 use crate::download::metadata::add_cookies_args;
 use anyhow::Context;
 use serde::Deserialize;
+use std::collections::HashSet;
 use std::process::Stdio;
 use tokio::process::Command;
 use url::Url;
@@ -34,6 +35,8 @@ pub struct PlaylistInfo {
 pub struct PlaylistEntry {
     /// Video URL
     pub url: String,
+    /// Platform video ID, when yt-dlp reports one
+    pub id: Option<String>,
     /// Video title
     pub title: String,
     /// Video duration in seconds
@@ -175,13 +178,17 @@ pub async fn extract_playlist(url: &Url) -> anyhow::Result<PlaylistInfo> {
                     break;
                 }
 
-                let video_url = entry
-                    .url
-                    .or_else(|| entry.id.map(|id| format!("https://www.youtube.com/watch?v={}", id)));
+                let video_url = entry.url.or_else(|| {
+                    entry
+                        .id
+                        .as_ref()
+                        .map(|id| format!("https://www.youtube.com/watch?v={}", id))
+                });
 
                 if let Some(video_url) = video_url {
                     entries.push(PlaylistEntry {
                         url: video_url,
+                        id: entry.id,
                         title: entry.title.unwrap_or_else(|| format!("Video {}", pos + 1)),
                         duration: entry.duration.map(|d| d as u64),
                         position: pos + 1,
@@ -198,13 +205,17 @@ pub async fn extract_playlist(url: &Url) -> anyhow::Result<PlaylistInfo> {
                 continue;
             }
 
-            let video_url = entry
-                .url
-                .or_else(|| entry.id.map(|id| format!("https://www.youtube.com/watch?v={}", id)));
+            let video_url = entry.url.or_else(|| {
+                entry
+                    .id
+                    .as_ref()
+                    .map(|id| format!("https://www.youtube.com/watch?v={}", id))
+            });
 
             if let Some(video_url) = video_url {
                 entries.push(PlaylistEntry {
                     url: video_url,
+                    id: entry.id,
                     title: entry.title.unwrap_or_else(|| format!("Video {}", entries.len() + 1)),
                     duration: entry.duration.map(|d| d as u64),
                     position: entries.len() + 1,
@@ -229,6 +240,93 @@ pub async fn extract_playlist(url: &Url) -> anyhow::Result<PlaylistInfo> {
     })
 }
 
+/// Lists the first `limit` entries of a channel tab or playlist, newest first
+/// for channel tabs and in playlist order otherwise.
+///
+/// Unlike [`extract_playlist`], an empty listing is not an error (a channel
+/// may simply have no shorts yet), and the title/uploader come from the
+/// container itself via `--dump-single-json`.
+pub async fn extract_recent_entries(url: &Url, limit: usize) -> anyhow::Result<PlaylistInfo> {
+    let playlist = list_entries(url, &format!("1:{}", limit.max(1))).await?;
+    Ok(playlist_info_from_json(playlist, limit))
+}
+
+/// Lists the entries selected by a yt-dlp `--playlist-items` spec, e.g.
+/// `1:15,-500:` for both ends of a long playlist. Entries picked by
+/// overlapping ranges are listed once, in spec order.
+pub async fn extract_entries(url: &Url, items: &str) -> anyhow::Result<PlaylistInfo> {
+    let playlist = list_entries(url, items).await?;
+    Ok(playlist_info_from_json(playlist, usize::MAX))
+}
+
+async fn list_entries(url: &Url, items: &str) -> anyhow::Result<YtdlpPlaylistJson> {
+    let ytdl_bin = &*config::YTDL_BIN;
+
+    let mut args: Vec<&str> = vec![
+        "--flat-playlist",
+        "--playlist-items",
+        items,
+        "--dump-single-json",
+        "-i",
+        "--socket-timeout",
+        "30",
+    ];
+
+    add_cookies_args(&mut args);
+
+    args.push(url.as_str());
+
+    log::debug!("Listing entries {} of: {}", items, url);
+
+    let mut cmd = Command::new(ytdl_bin);
+    cmd.args(&args).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let output = run_with_timeout(&mut cmd, config::download::ytdlp_timeout())
+        .await
+        .with_context(|| "Failed to run yt-dlp")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("yt-dlp failed: {}", stderr);
+    }
+
+    serde_json::from_slice(&output.stdout).with_context(|| "Failed to parse yt-dlp playlist JSON")
+}
+
+fn playlist_info_from_json(playlist: YtdlpPlaylistJson, limit: usize) -> PlaylistInfo {
+    let mut listed = HashSet::new();
+    let entries: Vec<PlaylistEntry> = playlist
+        .entries
+        .into_iter()
+        .filter_map(|entry| {
+            let url = entry.url.or_else(|| {
+                entry
+                    .id
+                    .as_ref()
+                    .map(|id| format!("https://www.youtube.com/watch?v={}", id))
+            })?;
+            Some((url, entry.id, entry.title, entry.duration))
+        })
+        .filter(|(url, ..)| listed.insert(url.clone()))
+        .take(limit)
+        .enumerate()
+        .map(|(pos, (url, id, title, duration))| PlaylistEntry {
+            url,
+            id,
+            title: title.unwrap_or_else(|| format!("Video {}", pos + 1)),
+            duration: duration.map(|d| d as u64),
+            position: pos + 1,
+        })
+        .collect();
+
+    PlaylistInfo {
+        title: playlist.title.unwrap_or_else(|| "Playlist".to_string()),
+        uploader: playlist.uploader,
+        entry_count: entries.len(),
+        entries,
+        truncated: false,
+    }
+}
+
 /// Extracts the latest (most recent) track URL and title from a channel/artist page.
 ///
 /// Uses `yt-dlp --flat-playlist --playlist-items 1` to get just the first item
@@ -457,4 +555,40 @@ mod tests {
         assert_eq!(format_duration(3661), "1:01:01");
         assert_eq!(format_duration(30), "0:30");
     }
+
+    #[test]
+    fn test_playlist_info_from_json_keeps_ids_and_limit() {
+        let json = r#"{"title": "Chan - Videos", "uploader": "Chan", "entries": [
+            {"id": "aaa", "url": "https://www.youtube.com/watch?v=aaa", "title": "First", "duration": 61.0},
+            {"id": "bbb", "title": null},
+            {"title": "no url or id"},
+            {"id": "ccc", "url": "https://www.youtube.com/shorts/ccc"}
+        ]}"#;
+        let playlist: YtdlpPlaylistJson = serde_json::from_str(json).unwrap();
+        let info = playlist_info_from_json(playlist, 2);
+        assert_eq!(info.uploader.as_deref(), Some("Chan"));
+        assert_eq!(info.entries.len(), 2);
+        assert_eq!(info.entries[0].id.as_deref(), Some("aaa"));
+        assert_eq!(info.entries[0].duration, Some(61));
+        assert_eq!(info.entries[1].url, "https://www.youtube.com/watch?v=bbb");
+        assert_eq!(info.entries[1].title, "Video 2");
+    }
+
+    #[test]
+    fn test_playlist_info_from_json_drops_overlapping_entries() {
+        let json = r#"{"title": "List", "entries": [{"id": "aaa"}, {"id": "bbb"}, {"id": "aaa"}]}"#;
+        let playlist: YtdlpPlaylistJson = serde_json::from_str(json).unwrap();
+        let ids: Vec<_> = playlist_info_from_json(playlist, usize::MAX)
+            .entries
+            .into_iter()
+            .filter_map(|e| e.id)
+            .collect();
+        assert_eq!(ids, ["aaa", "bbb"]);
+    }
+
+    #[test]
+    fn test_playlist_info_from_json_empty_is_ok() {
+        let playlist: YtdlpPlaylistJson = serde_json::from_str(r#"{"title": "Chan - Shorts"}"#).unwrap();
+        assert!(playlist_info_from_json(playlist, 15).entries.is_empty());
+    }
 }
@@ -182,6 +182,58 @@ fn build_pinned_client(check: &SsrfCheck) -> Result<Client, AppError> {
 /// SSRF re-validation so redirects cannot escape into the private network.
 const MAX_REDIRECT_HOPS: u8 = 5;
 
+/// SSRF-guarded GET: every hop (the first request and each redirect) is
+/// resolved, checked against private ranges and sent through a client pinned to
+/// the validated IPs. Cross-host redirects rebuild the pinned client. `headers`
+/// are sent on every hop.
+///
+/// Shared by direct downloads and feed fetching (`download::feed`).
+pub(crate) async fn guarded_get(url: &Url, headers: reqwest::header::HeaderMap) -> Result<reqwest::Response, AppError> {
+    let check = check_ssrf(url).await?;
+    let mut client = build_pinned_client(&check)?;
+    let mut host = check.host;
+    let mut current = url.clone();
+
+    for _ in 0..MAX_REDIRECT_HOPS {
+        let resp = client
+            .get(current.as_str())
+            .headers(headers.clone())
+            .send()
+            .await
+            .map_err(|e| AppError::Download(DownloadError::Other(format!("HTTP request failed: {}", e))))?;
+
+        if !resp.status().is_redirection() {
+            return Ok(resp);
+        }
+
+        let loc = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .ok_or_else(|| AppError::Download(DownloadError::Other("redirect missing Location".into())))?
+            .to_str()
+            .map_err(|_| AppError::Download(DownloadError::Other("invalid Location header".into())))?
+            .to_string();
+        drop(resp);
+
+        let next = current
+            .join(&loc)
+            .map_err(|e| AppError::Download(DownloadError::Other(format!("invalid redirect URL: {}", e))))?;
+
+        // Re-validate EVERY redirect hop BEFORE connecting.
+        let next_check = check_ssrf(&next).await?;
+        if next_check.host != host {
+            log::info!("cross-host redirect: {} -> {}", host, next_check.host);
+            client = build_pinned_client(&next_check)?;
+            host = next_check.host;
+        }
+        current = next;
+    }
+
+    Err(AppError::Download(DownloadError::Other(
+        "too many redirects".to_string(),
+    )))
+}
+
 /// Download source for direct HTTP file downloads.
 ///
 /// Each request builds its own pinned `reqwest::Client` via `build_pinned_client`
@@ -337,74 +389,21 @@ impl DownloadSource for HttpSource {
     ) -> Result<DownloadOutput, AppError> {
         log::info!("📥 HTTP direct download: {}", request.url);
 
-        // SSRF guard — resolve and validate, pin reqwest to the validated IPs so
-        // a second DNS lookup cannot return a different (private) address.
-        let ssrf_check = check_ssrf(&request.url).await?;
-        let pinned_client = build_pinned_client(&ssrf_check)?;
-
         // Check if we can resume (file already partially downloaded)
         let existing_size = fs_err::tokio::metadata(&request.output_path)
             .await
             .map(|m| m.len())
             .unwrap_or(0);
 
-        // Manual redirect loop: each hop gets SSRF re-validated before the connection.
-        // Cross-host redirects rebuild a new pinned client with fresh DNS validation.
-        let mut current = request.url.clone();
-        let mut current_client = pinned_client;
-        let mut current_host = ssrf_check.host.clone();
-        let response: reqwest::Response;
-
-        let mut hops: u8 = 0;
-        loop {
-            if hops >= MAX_REDIRECT_HOPS {
-                return Err(AppError::Download(DownloadError::Other(
-                    "too many redirects".to_string(),
-                )));
-            }
-            hops += 1;
-
-            let mut req = current_client.get(current.as_str());
-            if existing_size > 0 && hops == 1 {
-                log::info!("Resuming download from byte {}: {}", existing_size, request.output_path);
-                req = req.header("Range", format!("bytes={}-", existing_size));
-            }
-
-            let resp = req
-                .send()
-                .await
-                .map_err(|e| AppError::Download(DownloadError::Other(format!("HTTP request failed: {}", e))))?;
-
-            if resp.status().is_redirection() {
-                let loc = resp
-                    .headers()
-                    .get(reqwest::header::LOCATION)
-                    .ok_or_else(|| AppError::Download(DownloadError::Other("redirect missing Location".into())))?
-                    .to_str()
-                    .map_err(|_| AppError::Download(DownloadError::Other("invalid Location header".into())))?
-                    .to_string();
-
-                drop(resp);
-                let next = current
-                    .join(&loc)
-                    .map_err(|e| AppError::Download(DownloadError::Other(format!("invalid redirect URL: {}", e))))?;
-
-                // Re-validate EVERY redirect hop BEFORE connecting.
-                let next_check = check_ssrf(&next).await?;
-
-                // Cross-host redirect → rebuild pinned client for the new host.
-                if next_check.host != current_host {
-                    log::info!("cross-host redirect: {} -> {}", current_host, next_check.host);
-                    current_client = build_pinned_client(&next_check)?;
-                    current_host = next_check.host.clone();
-                }
-                current = next;
-                continue;
+        let mut headers = reqwest::header::HeaderMap::new();
+        if existing_size > 0 {
+            log::info!("Resuming download from byte {}: {}", existing_size, request.output_path);
+            if let Ok(range) = reqwest::header::HeaderValue::from_str(&format!("bytes={}-", existing_size)) {
+                headers.insert(reqwest::header::RANGE, range);
             }
-
-            response = resp;
-            break;
         }
+        // SSRF guard: every hop is validated and pinned to the checked IPs
+        let response = guarded_get(&request.url, headers).await?;
 
         if !response.status().is_success() && response.status().as_u16() != 206 {
             return Err(AppError::Download(DownloadError::Other(format!(
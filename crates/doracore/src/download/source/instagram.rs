@@ -333,12 +333,17 @@ impl InstagramSource {
     ///
     /// Uses curl to bypass TLS fingerprinting that blocks reqwest on datacenter IPs.
     pub async fn fetch_graphql_media(&self, shortcode: &str) -> Result<GraphQLMedia, AppError> {
+        self.fetch_graphql_media_with_doc_id(shortcode, &config::INSTAGRAM_DOC_ID)
+            .await
+    }
+
+    /// [`Self::fetch_graphql_media`] with an explicit GraphQL `doc_id`.
+    async fn fetch_graphql_media_with_doc_id(&self, shortcode: &str, doc_id: &str) -> Result<GraphQLMedia, AppError> {
         if !RATE_LIMITER.acquire() {
             log::warn!("InstagramSource: rate limited, falling back to yt-dlp");
             return Err(ig_err("Rate limited"));
         }
 
-        let doc_id = config::INSTAGRAM_DOC_ID.as_str();
         let variables = format!(r#"{{"shortcode":"{}"}}"#, shortcode);
         let body = format!(
             "doc_id={}&variables={}&lsd={}",
@@ -1227,7 +1232,8 @@ impl DownloadSource for InstagramSource {
         log::info!("InstagramSource: downloading shortcode={}", shortcode);
 
         // Try GraphQL first
-        let graphql_result = self.fetch_graphql_media(&shortcode).await;
+        let doc_id = request.instagram_doc_id.as_deref().unwrap_or(&config::INSTAGRAM_DOC_ID);
+        let graphql_result = self.fetch_graphql_media_with_doc_id(&shortcode, doc_id).await;
 
         match graphql_result {
             Ok(media) => {
@@ -161,6 +161,23 @@ pub struct DownloadRequest {
     /// wall-clock on 4K VP9 input (4:03 → 2:18 for a 30s clip on the
     /// shared Railway host) at the cost of ~1 VMAF.
     pub experimental_fast_encode: bool,
+    /// Bandwidth cap passed to yt-dlp as `--limit-rate` (e.g. "2M", "512K").
+    /// `None` means no cap (the bot always downloads at full throughput).
+    pub limit_rate: Option<String>,
+    /// Continue an interrupted download at `output_path` instead of starting
+    /// over: yt-dlp `--continue` on its `.part` file. `HttpSource` always
+    /// resumes via Range when the file exists.
+    pub resume_partial: bool,
+    /// yt-dlp binary for this download. `None` falls back to `YTDL_BIN`.
+    pub ytdl_bin: Option<String>,
+    /// Netscape cookies file for yt-dlp. `None` falls back to
+    /// `YTDL_COOKIES_FILE` / `YTDL_COOKIES_BROWSER`.
+    pub cookies_file: Option<String>,
+    /// Cookies file for Instagram's yt-dlp tier. `None` falls back to
+    /// `INSTAGRAM_COOKIES_FILE`.
+    pub instagram_cookies_file: Option<String>,
+    /// Instagram GraphQL `doc_id`. `None` falls back to `INSTAGRAM_DOC_ID`.
+    pub instagram_doc_id: Option<String>,
 }
 
 /// An additional media file from a multi-item post (e.g., Instagram carousel).
@@ -417,6 +434,12 @@ mod tests {
             quality_preset: None,
             cancel_flag: None,
             experimental_fast_encode: false,
+            limit_rate: None,
+            resume_partial: false,
+            ytdl_bin: None,
+            cookies_file: None,
+            instagram_cookies_file: None,
+            instagram_doc_id: None,
         };
 
         let (tx, mut rx) = mpsc::unbounded_channel();
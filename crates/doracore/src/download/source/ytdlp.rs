@@ -14,12 +14,14 @@ use crate::download::cookies::report_and_wait_for_refresh;
 use crate::download::downloader::{cleanup_partial_download, parse_merge_progress, parse_progress};
 use crate::download::error::DownloadError;
 use crate::download::metadata::{
-    add_cookies_args_with_proxy, add_instagram_cookies_args_with_proxy, add_no_cookies_args, build_highres_format,
-    build_telegram_safe_format, default_pot_token, default_youtube_extractor_args, find_actual_downloaded_file,
-    get_estimated_filesize, get_metadata_from_ytdlp, get_proxy_chain, is_proxy_related_error, probe_duration_seconds,
-    probe_video_codec,
+    add_cookies_file_args_with_proxy, add_instagram_cookies_file_args_with_proxy, add_no_cookies_args,
+    build_highres_format, build_telegram_safe_format, default_pot_token, default_youtube_extractor_args,
+    find_actual_downloaded_file, get_estimated_filesize, get_metadata_from_ytdlp, get_proxy_chain,
+    is_proxy_related_error, probe_duration_seconds, probe_video_codec,
+};
+use crate::download::source::{
+    DownloadOutput, DownloadRequest, DownloadSource, ProgressPhase, SourceProgress, VideoQualityPreset,
 };
-use crate::download::source::{DownloadOutput, DownloadRequest, DownloadSource, SourceProgress, VideoQualityPreset};
 use crate::download::ytdlp_errors::{YtDlpErrorType, analyze_ytdlp_error, get_error_message};
 use async_trait::async_trait;
 use std::collections::VecDeque;
@@ -323,12 +325,16 @@ impl YtDlpSource {
         request: &DownloadRequest,
         progress_tx: mpsc::UnboundedSender<SourceProgress>,
     ) -> Result<DownloadOutput, AppError> {
-        let ytdl_bin = config::YTDL_BIN.clone();
+        let ytdl_bin = request.ytdl_bin.clone().unwrap_or_else(|| config::YTDL_BIN.clone());
         let url_str = request.url.to_string();
         let download_path = request.output_path.clone();
         let bitrate_str = request.audio_bitrate.clone().unwrap_or_else(|| "320k".to_string());
         let time_range = request.time_range.clone();
         let cancel_flag = request.cancel_flag.clone();
+        let limit_rate = request.limit_rate.clone();
+        let resume_partial = request.resume_partial;
+        let cookies_file = request.cookies_file.clone();
+        let instagram_cookies_file = request.instagram_cookies_file.clone();
 
         // Experimental features graduated to main workflow
         if request.concurrent_fragments > 1 {
@@ -339,6 +345,7 @@ impl YtDlpSource {
         }
         let cf_str = concurrent_fragments_str(request.concurrent_fragments);
         let is_youtube = crate::core::share::is_youtube_url(request.url.as_str());
+        let is_instagram = is_instagram_url(request.url.as_str());
 
         let subprocess_timeout = config::download::ytdlp_download_timeout_for_quality(None);
         let handle = tokio::task::spawn_blocking(move || {
@@ -351,10 +358,15 @@ impl YtDlpSource {
                 &progress_tx,
                 "audio",
                 subprocess_timeout,
-                move |args, proxy_option| {
+                move |args, proxy_option, transfer| {
                     push_audio_format_args(args, true);
                     if is_youtube {
-                        add_cookies_args_with_proxy(args, proxy_option, default_pot_token());
+                        add_cookies_file_args_with_proxy(
+                            args,
+                            proxy_option,
+                            default_pot_token(),
+                            transfer.cookies_file,
+                        );
                         args.push("--extractor-args");
                         args.push(default_youtube_extractor_args());
                     } else {
@@ -365,28 +377,30 @@ impl YtDlpSource {
                     push_js_runtimes_tail(args, cf_str);
                     args.push("--postprocessor-args");
                 },
-                {
-                    let url_for_tier2 = url_str.clone();
-                    move |args: &mut Vec<&str>, proxy_option: Option<&crate::download::metadata::ProxyConfig>| {
-                        // Tier 2 (cookies): audio-specific args
-                        push_audio_format_args(args, true);
-                        if is_instagram_url(&url_for_tier2) {
-                            add_instagram_cookies_args_with_proxy(args, proxy_option);
-                        } else {
-                            add_cookies_args_with_proxy(args, proxy_option, default_pot_token());
-                            args.push("--extractor-args");
-                            args.push(default_youtube_extractor_args());
-                        }
-                        push_js_runtimes_tail(args, cf_str);
-                        args.push("--postprocessor-args");
+                move |args, proxy_option, transfer| {
+                    // Tier 2 (cookies): audio-specific args
+                    push_audio_format_args(args, true);
+                    if is_instagram {
+                        add_instagram_cookies_file_args_with_proxy(args, proxy_option, transfer.instagram_cookies_file);
+                    } else {
+                        add_cookies_file_args_with_proxy(
+                            args,
+                            proxy_option,
+                            default_pot_token(),
+                            transfer.cookies_file,
+                        );
+                        args.push("--extractor-args");
+                        args.push(default_youtube_extractor_args());
                     }
+                    push_js_runtimes_tail(args, cf_str);
+                    args.push("--postprocessor-args");
                 },
-                move |args, proxy_option| {
+                move |args, proxy_option, transfer| {
                     // Tier 3 (fixup never): audio-specific args
                     args.push("--fixup");
                     args.push("never");
                     push_audio_format_args(args, false);
-                    add_cookies_args_with_proxy(args, proxy_option, default_pot_token());
+                    add_cookies_file_args_with_proxy(args, proxy_option, default_pot_token(), transfer.cookies_file);
                     args.push("--extractor-args");
                     args.push(default_youtube_extractor_args());
                     push_js_runtimes_tail(args, cf_str);
@@ -394,6 +408,12 @@ impl YtDlpSource {
                 &postprocessor_args,
                 time_range.as_ref(),
                 cancel_flag,
+                TransferArgs {
+                    limit_rate: limit_rate.as_deref(),
+                    resume_partial,
+                    cookies_file: cookies_file.as_deref(),
+                    instagram_cookies_file: instagram_cookies_file.as_deref(),
+                },
             )
         });
 
@@ -421,11 +441,15 @@ impl YtDlpSource {
         request: &DownloadRequest,
         progress_tx: mpsc::UnboundedSender<SourceProgress>,
     ) -> Result<DownloadOutput, AppError> {
-        let ytdl_bin = config::YTDL_BIN.clone();
+        let ytdl_bin = request.ytdl_bin.clone().unwrap_or_else(|| config::YTDL_BIN.clone());
         let url_str = request.url.to_string();
         let download_path = request.output_path.clone();
         let time_range = request.time_range.clone();
         let cancel_flag = request.cancel_flag.clone();
+        let limit_rate = request.limit_rate.clone();
+        let resume_partial = request.resume_partial;
+        let cookies_file = request.cookies_file.clone();
+        let instagram_cookies_file = request.instagram_cookies_file.clone();
 
         // Experimental features graduated to main workflow
         if request.concurrent_fragments > 1 {
@@ -436,6 +460,7 @@ impl YtDlpSource {
         }
         let cf_str = concurrent_fragments_str(request.concurrent_fragments);
         let is_youtube = crate::core::share::is_youtube_url(request.url.as_str());
+        let is_instagram = is_instagram_url(request.url.as_str());
 
         // High-resolution (1440p/2160p/4320p) requires AV1/VP9 codecs — YouTube
         // has no H.264 above 1080p. To get a Telegram-inline-playable mp4 we:
@@ -505,6 +530,7 @@ impl YtDlpSource {
 
         let subprocess_timeout =
             config::download::ytdlp_download_timeout_for(request.video_quality.as_deref(), Some(preset));
+        let postprocess_tx = progress_tx.clone();
         let handle = tokio::task::spawn_blocking(move || {
             download_with_fallback_chain(
                 &ytdl_bin,
@@ -513,7 +539,7 @@ impl YtDlpSource {
                 &progress_tx,
                 "video",
                 subprocess_timeout,
-                move |args, proxy_option| {
+                move |args, proxy_option, transfer| {
                     push_video_format_args(args, true, container);
                     // Phase 2 (v0.49.0): skip yt-dlp's --recode-video.
                     // We probe vcodec post-download and dispatch ourselves —
@@ -521,7 +547,12 @@ impl YtDlpSource {
                     // AV1 still recodes via libx264 (the only codec Telegram
                     // doesn't play inline). Saves 5-10 min per non-AV1 video.
                     if is_youtube {
-                        add_cookies_args_with_proxy(args, proxy_option, default_pot_token());
+                        add_cookies_file_args_with_proxy(
+                            args,
+                            proxy_option,
+                            default_pot_token(),
+                            transfer.cookies_file,
+                        );
                         args.push("--extractor-args");
                         args.push(default_youtube_extractor_args());
                     } else {
@@ -531,31 +562,33 @@ impl YtDlpSource {
                     }
                     push_js_runtimes_tail(args, cf_str);
                 },
-                {
-                    let url_for_tier2 = url_str.clone();
-                    move |args: &mut Vec<&str>, proxy_option: Option<&crate::download::metadata::ProxyConfig>| {
-                        // Tier 2 (cookies + PO token).
-                        push_video_format_args(args, true, container);
-                        // Phase 2: see Tier 1 comment — yt-dlp produces mkv,
-                        // we transmux-or-recode after download.
-                        if is_instagram_url(&url_for_tier2) {
-                            add_instagram_cookies_args_with_proxy(args, proxy_option);
-                        } else {
-                            add_cookies_args_with_proxy(args, proxy_option, default_pot_token());
-                            args.push("--extractor-args");
-                            args.push(default_youtube_extractor_args());
-                        }
-                        push_js_runtimes_tail(args, cf_str);
+                move |args, proxy_option, transfer| {
+                    // Tier 2 (cookies + PO token).
+                    push_video_format_args(args, true, container);
+                    // Phase 2: see Tier 1 comment — yt-dlp produces mkv,
+                    // we transmux-or-recode after download.
+                    if is_instagram {
+                        add_instagram_cookies_file_args_with_proxy(args, proxy_option, transfer.instagram_cookies_file);
+                    } else {
+                        add_cookies_file_args_with_proxy(
+                            args,
+                            proxy_option,
+                            default_pot_token(),
+                            transfer.cookies_file,
+                        );
+                        args.push("--extractor-args");
+                        args.push(default_youtube_extractor_args());
                     }
+                    push_js_runtimes_tail(args, cf_str);
                 },
-                move |args, proxy_option| {
+                move |args, proxy_option, transfer| {
                     // Tier 3 (fixup never): same client logic as tier 2.
                     args.push("--fixup");
                     args.push("never");
                     push_video_format_args(args, false, container);
                     // Phase 2: see Tier 1 comment — yt-dlp produces mkv,
                     // we transmux-or-recode after download.
-                    add_cookies_args_with_proxy(args, proxy_option, default_pot_token());
+                    add_cookies_file_args_with_proxy(args, proxy_option, default_pot_token(), transfer.cookies_file);
                     args.push("--extractor-args");
                     args.push(default_youtube_extractor_args());
                     push_js_runtimes_tail(args, cf_str);
@@ -563,6 +596,12 @@ impl YtDlpSource {
                 &format_arg,
                 time_range.as_ref(),
                 cancel_flag,
+                TransferArgs {
+                    limit_rate: limit_rate.as_deref(),
+                    resume_partial,
+                    cookies_file: cookies_file.as_deref(),
+                    instagram_cookies_file: instagram_cookies_file.as_deref(),
+                },
             )
         });
 
@@ -588,6 +627,11 @@ impl YtDlpSource {
                 mkv_actual,
                 mp4_target,
             );
+            let _ = postprocess_tx.send(SourceProgress {
+                percent: 100,
+                phase: ProgressPhase::Postprocessing,
+                ..Default::default()
+            });
             transmux_or_recode_to_mp4(
                 &mkv_actual,
                 &mp4_target,
@@ -719,13 +763,15 @@ fn try_tier1<F>(
     tier1_args_fn: &F,
     subprocess_timeout: Duration,
     cancel_flag: Option<&std::sync::Arc<std::sync::atomic::AtomicBool>>,
+    transfer: TransferArgs<'_>,
 ) -> Result<(), (YtDlpErrorType, String)>
 where
-    F: Fn(&mut Vec<&str>, Option<&crate::download::metadata::ProxyConfig>),
+    F: for<'b> Fn(&mut Vec<&'b str>, Option<&crate::download::metadata::ProxyConfig>, TransferArgs<'b>),
 {
     // Experimental features graduated to main workflow
     let mut args: Vec<&str> = build_common_args(download_path);
-    tier1_args_fn(&mut args, proxy_option);
+    push_transfer_args(&mut args, transfer);
+    tier1_args_fn(&mut args, proxy_option, transfer);
 
     if media_type == "audio" {
         args.push(extra_arg);
@@ -780,9 +826,10 @@ fn try_tier2<F>(
     runtime_handle: &tokio::runtime::Handle,
     subprocess_timeout: Duration,
     cancel_flag: Option<&std::sync::Arc<std::sync::atomic::AtomicBool>>,
+    transfer: TransferArgs<'_>,
 ) -> Tier2Outcome
 where
-    F: Fn(&mut Vec<&str>, Option<&crate::download::metadata::ProxyConfig>),
+    F: for<'b> Fn(&mut Vec<&'b str>, Option<&crate::download::metadata::ProxyConfig>, TransferArgs<'b>),
 {
     // Experimental features graduated to main workflow
     crate::download::cookies::log_cookie_file_diagnostics(&format!("{}_TIER2_BEFORE", media_type.to_uppercase()));
@@ -791,7 +838,8 @@ where
     cleanup_partial_download(download_path);
 
     let mut cookies_args: Vec<&str> = build_common_args_minimal(download_path);
-    tier2_args_fn(&mut cookies_args, proxy_option);
+    push_transfer_args(&mut cookies_args, transfer);
+    tier2_args_fn(&mut cookies_args, proxy_option, transfer);
     if media_type == "audio" {
         cookies_args.push(extra_arg);
     } else if let Some(pos) = cookies_args.iter().position(|a| *a == "--format") {
@@ -866,9 +914,10 @@ fn try_tier3<F>(
     tier3_args_fn: &F,
     subprocess_timeout: Duration,
     cancel_flag: Option<&std::sync::Arc<std::sync::atomic::AtomicBool>>,
+    transfer: TransferArgs<'_>,
 ) -> bool
 where
-    F: Fn(&mut Vec<&str>, Option<&crate::download::metadata::ProxyConfig>),
+    F: for<'b> Fn(&mut Vec<&'b str>, Option<&crate::download::metadata::ProxyConfig>, TransferArgs<'b>),
 {
     // Experimental features graduated to main workflow
     log::warn!("🔧 Postprocessing error, retrying with --fixup never...");
@@ -877,7 +926,8 @@ where
     cleanup_partial_download(download_path);
 
     let mut fixup_args: Vec<&str> = build_common_args_minimal(download_path);
-    tier3_args_fn(&mut fixup_args, proxy_option);
+    push_transfer_args(&mut fixup_args, transfer);
+    tier3_args_fn(&mut fixup_args, proxy_option, transfer);
     if media_type == "video"
         && let Some(pos) = fixup_args.iter().position(|a| *a == "--format")
     {
@@ -926,11 +976,12 @@ fn download_with_fallback_chain<F1, F2, F3>(
     extra_arg: &str,
     time_range: Option<&(String, String)>,
     cancel_flag: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
+    transfer: TransferArgs<'_>,
 ) -> Result<Option<u32>, AppError>
 where
-    F1: Fn(&mut Vec<&str>, Option<&crate::download::metadata::ProxyConfig>),
-    F2: Fn(&mut Vec<&str>, Option<&crate::download::metadata::ProxyConfig>),
-    F3: Fn(&mut Vec<&str>, Option<&crate::download::metadata::ProxyConfig>),
+    F1: for<'b> Fn(&mut Vec<&'b str>, Option<&crate::download::metadata::ProxyConfig>, TransferArgs<'b>),
+    F2: for<'b> Fn(&mut Vec<&'b str>, Option<&crate::download::metadata::ProxyConfig>, TransferArgs<'b>),
+    F3: for<'b> Fn(&mut Vec<&'b str>, Option<&crate::download::metadata::ProxyConfig>, TransferArgs<'b>),
 {
     // Experimental features graduated to main workflow
     let runtime_handle = tokio::runtime::Handle::current();
@@ -984,6 +1035,7 @@ where
             &tier1_args_fn,
             subprocess_timeout,
             cancel_flag.as_ref(),
+            transfer,
         );
         log::info!("⏱️ [TIER1] done in {:.1}s", tier1_start.elapsed().as_secs_f64());
 
@@ -1084,6 +1136,7 @@ where
                         &runtime_handle,
                         subprocess_timeout,
                         cancel_flag.as_ref(),
+                        transfer,
                     );
                     log::info!("⏱️ [TIER2] done in {:.1}s", tier2_start.elapsed().as_secs_f64());
                     match tier2_result {
@@ -1119,6 +1172,7 @@ where
                         &tier3_args_fn,
                         subprocess_timeout,
                         cancel_flag.as_ref(),
+                        transfer,
                     );
                     crate::core::metrics::record_tier_attempt("tier3_fixup_never", tier3_ok);
                     if tier3_ok {
@@ -1202,6 +1256,32 @@ fn build_common_args_minimal(download_path: &str) -> Vec<&str> {
     ]
 }
 
+/// Per-request transfer flags applied on every tier: an opt-in bandwidth cap
+/// and `.part` resume. The bot uses neither; dora sets both. The cookies
+/// files are not pushed here — the tier closures add them where they add
+/// cookies at all.
+#[derive(Debug, Clone, Copy, Default)]
+struct TransferArgs<'a> {
+    limit_rate: Option<&'a str>,
+    resume_partial: bool,
+    cookies_file: Option<&'a str>,
+    instagram_cookies_file: Option<&'a str>,
+}
+
+fn push_transfer_args<'a>(args: &mut Vec<&'a str>, transfer: TransferArgs<'a>) {
+    if let Some(rate) = transfer.limit_rate {
+        args.push("--limit-rate");
+        args.push(rate);
+    }
+    // `--force-overwrites` implies `--no-continue`; swap it so yt-dlp picks
+    // up the `.part` file left by an interrupted run.
+    if transfer.resume_partial
+        && let Some(flag) = args.iter_mut().find(|a| **a == "--force-overwrites")
+    {
+        *flag = "--continue";
+    }
+}
+
 /// Build common yt-dlp arguments shared by Tier 1 (full set with rate limiting).
 /// Starts from `build_common_args_minimal` and appends the retry/throttle flags
 /// so the two functions cannot drift out of sync on the shared prefix.
@@ -1289,6 +1369,27 @@ mod common_args_tests {
         assert_eq!(args[1], "/custom/path.mp4");
     }
 
+    #[test]
+    fn transfer_args_add_rate_and_swap_overwrite_for_continue() {
+        use super::{TransferArgs, push_transfer_args};
+
+        let mut args = build_common_args_minimal("/tmp/t.mp3");
+        push_transfer_args(&mut args, TransferArgs::default());
+        assert_eq!(args.as_slice(), EXPECTED_MINIMAL);
+
+        push_transfer_args(
+            &mut args,
+            TransferArgs {
+                limit_rate: Some("512K"),
+                resume_partial: true,
+                ..TransferArgs::default()
+            },
+        );
+        assert!(!args.contains(&"--force-overwrites"));
+        assert_eq!(args[3], "--continue");
+        assert_eq!(&args[args.len() - 2..], &["--limit-rate", "512K"]);
+    }
+
     // ==== Byte-identical tests for the Tier 1/2/3 helper functions ====
 
     use super::{push_audio_format_args, push_js_runtimes_tail, push_video_format_args};
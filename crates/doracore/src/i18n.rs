@@ -64,7 +64,7 @@ pub fn user_lang_from_pool(db_pool: &Arc<db::DbPool>, telegram_id: i64) -> Langu
 }
 
 /// Resolves the language for a user via the backend-aware shared storage client.
-pub async fn user_lang_from_storage(storage: &Arc<SharedStorage>, telegram_id: i64) -> LanguageIdentifier {
+pub async fn user_lang_from_storage(storage: &SharedStorage, telegram_id: i64) -> LanguageIdentifier {
     match storage.get_user_language(telegram_id).await {
         Ok(lang_code) => lang_from_code(&lang_code),
         Err(_) => DEFAULT_LANG.clone(),
@@ -8,6 +8,7 @@
 
 pub mod highlights;
 pub mod providers;
+pub mod synced;
 pub mod title_parser;
 
 use lazy_regex::{Lazy, Regex, lazy_regex};
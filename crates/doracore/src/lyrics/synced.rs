@@ -0,0 +1,156 @@
+//! Time-synced lyrics (LRC) from LRCLIB.
+//!
+//! LRCLIB returns a `syncedLyrics` field in LRC format alongside the plain
+//! text for many tracks. [`parse_lrc`] turns it into sorted [`LrcLine`]s and
+//! [`line_at`] finds the line to highlight for a playback position.
+
+use std::time::Duration;
+
+use lazy_regex::{Lazy, Regex, lazy_regex};
+
+use super::build_http_client;
+
+/// `[mm:ss]`, `[mm:ss.xx]` or `[mm:ss.xxx]` timestamp tag.
+static TIMESTAMP_RE: Lazy<Regex> = lazy_regex!(r"\[(\d{1,3}):(\d{1,2})(?:[.:](\d{1,3}))?\]");
+
+/// One lyric line and the moment it starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LrcLine {
+    pub time: Duration,
+    pub text: String,
+}
+
+/// Synced lyrics for one track, as matched on LRCLIB.
+#[derive(Debug, Clone)]
+pub struct SyncedLyrics {
+    pub artist: String,
+    pub title: String,
+    pub album: Option<String>,
+    /// Timed lines, sorted by start time. Empty when LRCLIB only had plain text.
+    pub lines: Vec<LrcLine>,
+    pub plain: Option<String>,
+}
+
+/// Parse LRC text into lines sorted by start time.
+///
+/// A line may carry several timestamps (`[00:12.00][01:40.50]Chorus`) and is
+/// then repeated at each. Metadata tags (`[ar:…]`, `[offset:…]`) and lines
+/// without a timestamp are skipped.
+pub fn parse_lrc(text: &str) -> Vec<LrcLine> {
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let mut rest = raw.trim();
+        let mut times = Vec::new();
+        while let Some(caps) = TIMESTAMP_RE.captures(rest) {
+            let Some(m) = caps.get(0) else { break };
+            if m.start() != 0 {
+                break;
+            }
+            let minutes: u64 = caps[1].parse().unwrap_or(0);
+            let seconds: u64 = caps[2].parse().unwrap_or(0);
+            let millis = caps.get(3).map_or(0, |f| {
+                let digits = f.as_str();
+                let value: u64 = digits.parse().unwrap_or(0);
+                match digits.len() {
+                    1 => value * 100,
+                    2 => value * 10,
+                    _ => value,
+                }
+            });
+            times.push(Duration::from_millis((minutes * 60 + seconds) * 1000 + millis));
+            rest = rest[m.end()..].trim_start();
+        }
+        let text = rest.trim().to_string();
+        lines.extend(times.into_iter().map(|time| LrcLine {
+            time,
+            text: text.clone(),
+        }));
+    }
+    lines.sort_by_key(|l| l.time);
+    lines
+}
+
+/// Index of the line being sung at `pos`, or `None` before the first one.
+pub fn line_at(lines: &[LrcLine], pos: Duration) -> Option<usize> {
+    lines.partition_point(|l| l.time <= pos).checked_sub(1)
+}
+
+/// Look up synced lyrics on LRCLIB. Prefers the first result that has
+/// `syncedLyrics`; falls back to the first result's plain text with no
+/// timed lines. `None` if nothing matched or the request failed.
+pub async fn fetch_synced_lyrics(artist: &str, title: &str) -> Option<SyncedLyrics> {
+    let client = build_http_client()?;
+    let url = if artist.is_empty() {
+        format!("https://lrclib.net/api/search?q={}", urlencoding::encode(title))
+    } else {
+        format!(
+            "https://lrclib.net/api/search?artist_name={}&track_name={}",
+            urlencoding::encode(artist),
+            urlencoding::encode(title),
+        )
+    };
+
+    let resp: serde_json::Value = client.get(&url).send().await.ok()?.json().await.ok()?;
+    let arr = resp.as_array()?;
+    let best = arr
+        .iter()
+        .find(|r| r["syncedLyrics"].as_str().is_some_and(|s| !s.trim().is_empty()))
+        .or_else(|| arr.first())?;
+
+    let lines = best["syncedLyrics"].as_str().map(parse_lrc).unwrap_or_default();
+    let plain = best["plainLyrics"]
+        .as_str()
+        .filter(|s| !s.trim().is_empty())
+        .map(String::from);
+    if lines.is_empty() && plain.is_none() {
+        return None;
+    }
+
+    Some(SyncedLyrics {
+        artist: best["artistName"].as_str().unwrap_or(artist).to_string(),
+        title: best["trackName"].as_str().unwrap_or(title).to_string(),
+        album: best["albumName"].as_str().map(String::from),
+        lines,
+        plain,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_sorts_timestamps() {
+        let lrc = "[ar:Someone]\n[00:12.50]First\n[00:05]Intro\n[01:02.345]Late\nno timestamp";
+        let lines = parse_lrc(lrc);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].time, Duration::from_secs(5));
+        assert_eq!(lines[0].text, "Intro");
+        assert_eq!(lines[1].time, Duration::from_millis(12_500));
+        assert_eq!(lines[2].time, Duration::from_millis(62_345));
+    }
+
+    #[test]
+    fn repeats_lines_with_multiple_timestamps() {
+        let lines = parse_lrc("[00:10.00][00:40.00]Chorus\n[00:20.00]Verse");
+        let texts: Vec<_> = lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, ["Chorus", "Verse", "Chorus"]);
+    }
+
+    #[test]
+    fn keeps_blank_instrumental_lines() {
+        let lines = parse_lrc("[00:01.00]Hello\n[00:03.00]");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].text, "");
+    }
+
+    #[test]
+    fn line_at_tracks_position() {
+        let lines = parse_lrc("[00:05.00]a\n[00:10.00]b\n[00:15.00]c");
+        assert_eq!(line_at(&lines, Duration::from_secs(1)), None);
+        assert_eq!(line_at(&lines, Duration::from_secs(5)), Some(0));
+        assert_eq!(line_at(&lines, Duration::from_millis(14_999)), Some(1));
+        assert_eq!(line_at(&lines, Duration::from_secs(300)), Some(2));
+        assert_eq!(line_at(&[], Duration::from_secs(3)), None);
+    }
+}
@@ -2,7 +2,7 @@
 //! a given platform by consulting these — instead of hard-coding Telegram
 //! assumptions (5-button rows, edit-in-place, HTML) everywhere.
 
-use super::types::TextStyle;
+use super::types::{Delivery, MediaKind, TextStyle};
 
 /// What a platform's messaging surface can do. Drives UI degradation
 /// (inline keyboard → reply buttons → list → numbered text).
@@ -92,6 +92,23 @@ impl Capabilities {
         let native_cap = self.max_buttons_total.max(self.list_menu_max);
         button_count > native_cap
     }
+
+    /// How a file of `size` bytes should be delivered: natively, as a
+    /// document, or — above every cap — as a hosted link.
+    pub fn delivery_for(&self, kind: MediaKind, size: u64) -> Delivery {
+        let native_cap = match kind {
+            MediaKind::Audio => self.max_audio_bytes,
+            MediaKind::Video | MediaKind::VideoNote | MediaKind::Animation => self.max_video_bytes,
+            MediaKind::Photo | MediaKind::Document => self.max_document_bytes,
+        };
+        if size <= native_cap {
+            Delivery::Native
+        } else if size <= self.max_document_bytes {
+            Delivery::Document
+        } else {
+            Delivery::HostedLink
+        }
+    }
 }
 
 #[cfg(test)]
@@ -115,4 +132,16 @@ mod tests {
     fn imessage_always_text_menu() {
         assert!(Capabilities::IMESSAGE.needs_text_menu(1));
     }
+
+    #[test]
+    fn oversized_media_degrades_to_document_then_hosted_link() {
+        let wa = Capabilities::WHATSAPP;
+        assert_eq!(wa.delivery_for(MediaKind::Audio, 10_000_000), Delivery::Native);
+        assert_eq!(wa.delivery_for(MediaKind::Audio, 50_000_000), Delivery::Document);
+        assert_eq!(wa.delivery_for(MediaKind::Video, 500_000_000), Delivery::HostedLink);
+        assert_eq!(
+            Capabilities::IMESSAGE.delivery_for(MediaKind::Video, 150_000_000),
+            Delivery::HostedLink
+        );
+    }
 }
@@ -14,8 +14,8 @@ pub mod types;
 
 pub use capabilities::Capabilities;
 pub use types::{
-    Button, ChatRef, InboundEvent, InboundMessage, Keyboard, MediaKind, MediaSource, MessageHandle, OutboundMessage,
-    Platform, TextStyle, UserRef,
+    Button, ChatRef, Delivery, InboundEvent, InboundMessage, Keyboard, MediaKind, MediaSource, MessageHandle,
+    OutboundMessage, Platform, TextStyle, UserRef,
 };
 
 use async_trait::async_trait;
@@ -73,6 +73,18 @@ pub enum MediaSource {
     CachedRef(String),
 }
 
+/// How a finished file reaches the user, given the platform's size caps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Delivery {
+    /// Send with the native primitive for its kind (audio, video, …).
+    Native,
+    /// Too big for the native primitive but fits as a document.
+    Document,
+    /// Too big for the platform altogether: publish it through
+    /// `core::hosted_links` and send the signed URL.
+    HostedLink,
+}
+
 /// How the text body should be interpreted by the adapter.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TextStyle {
@@ -0,0 +1,142 @@
+//! Compression, optional `age` encryption and checksums for backup artifacts.
+//!
+//! Encryption shells out to the `age` CLI (as downloads shell out to yt-dlp
+//! and ffmpeg) so key handling stays in a tool operators already trust.
+
+use anyhow::{Context, Result, bail};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use fs_err as fs;
+use sha2::{Digest, Sha256};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::core::process::run_with_timeout_raw;
+
+/// Upper bound for a single `age` invocation.
+const AGE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// gzip `src` into `dst`.
+pub fn gzip_file(src: &Path, dst: &Path) -> Result<()> {
+    let mut reader = BufReader::new(fs::File::open(src)?);
+    let mut encoder = GzEncoder::new(BufWriter::new(fs::File::create(dst)?), Compression::default());
+    io::copy(&mut reader, &mut encoder).with_context(|| format!("compress {}", src.display()))?;
+    encoder.finish()?.flush()?;
+    Ok(())
+}
+
+/// Decompress gzip `src` into `dst`.
+pub fn gunzip_file(src: &Path, dst: &Path) -> Result<()> {
+    let mut decoder = GzDecoder::new(BufReader::new(fs::File::open(src)?));
+    let mut writer = BufWriter::new(fs::File::create(dst)?);
+    io::copy(&mut decoder, &mut writer).with_context(|| format!("decompress {}", src.display()))?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Hex SHA-256 of a file's contents.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+async fn run_age(args: &[&std::ffi::OsStr], what: &str) -> Result<()> {
+    let mut cmd = Command::new("age");
+    cmd.args(args);
+    let output = match run_with_timeout_raw(&mut cmd, AGE_TIMEOUT).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(e).with_context(|| format!("run age to {}", what)),
+        Err(_) => bail!("age timed out while trying to {}", what),
+    };
+    if !output.status.success() {
+        bail!(
+            "age failed to {}: {}",
+            what,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Encrypt `src` to the `age` public key `recipient`.
+pub async fn age_encrypt(src: &Path, dst: &Path, recipient: &str) -> Result<()> {
+    run_age(
+        &[
+            "-r".as_ref(),
+            recipient.as_ref(),
+            "-o".as_ref(),
+            dst.as_os_str(),
+            src.as_os_str(),
+        ],
+        "encrypt",
+    )
+    .await
+}
+
+/// Decrypt `src` with the `age` identity file at `identity`.
+pub async fn age_decrypt(src: &Path, dst: &Path, identity: &Path) -> Result<()> {
+    run_age(
+        &[
+            "-d".as_ref(),
+            "-i".as_ref(),
+            identity.as_os_str(),
+            "-o".as_ref(),
+            dst.as_os_str(),
+            src.as_os_str(),
+        ],
+        "decrypt",
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn gzip_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let raw = dir.path().join("raw");
+        let gz = dir.path().join("raw.gz");
+        let back = dir.path().join("back");
+        let content = b"CREATE TABLE users (id INTEGER);\n".repeat(1000);
+        fs::write(&raw, &content).unwrap();
+
+        gzip_file(&raw, &gz).unwrap();
+        assert!(fs::metadata(&gz).unwrap().len() < content.len() as u64);
+        gunzip_file(&gz, &back).unwrap();
+        assert_eq!(fs::read(&back).unwrap(), content);
+    }
+
+    #[test]
+    fn sha256_matches_known_digest() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("abc");
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn gunzip_rejects_garbage() {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("bad.gz");
+        fs::write(&src, b"not gzip").unwrap();
+        assert!(gunzip_file(&src, &dir.path().join("out")).is_err());
+    }
+}
@@ -0,0 +1,743 @@
+//! Database backups.
+//!
+//! A backup is a *set* directory `<BACKUP_DIR>/<YYYYMMDD_HHMMSS_mmm>/` with one
+//! artifact per database and a `manifest.json`:
+//! - `sqlite.db.gz` — `VACUUM INTO` snapshot of `DATABASE_PATH` (always; on the
+//!   Postgres backend SQLite still holds instance-local state such as the
+//!   admin audit log);
+//! - `postgres.sql.gz` — `pg_dump` of `DATABASE_URL` on the Postgres backend.
+//!
+//! Artifacts get an extra `.age` suffix when `BACKUP_AGE_RECIPIENT` is set.
+//! Backups are incremental at set granularity: when no database changed since
+//! the newest set, nothing is written. Sets are pruned with a GFS policy
+//! locally and, when `BACKUP_S3_*` is configured, mirrored to and pruned in an
+//! S3-compatible bucket. Restores verify checksums and refuse sets taken with
+//! a newer SQLite or Postgres schema than this binary's migrations.
+
+mod archive;
+mod retention;
+mod s3;
+mod snapshot;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::config::{self, DatabaseDriver};
+use crate::storage::SharedStorage;
+use crate::storage::migrations;
+use crate::storage::shared::latest_pg_schema_version;
+
+pub use retention::RetentionPolicy;
+pub use s3::S3Target;
+
+/// Manifest file name inside each set
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Set id format; ids sort chronologically
+const SET_ID_FORMAT: &str = "%Y%m%d_%H%M%S_%3f";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ArtifactKind {
+    Sqlite,
+    Postgres,
+}
+
+impl ArtifactKind {
+    fn raw_name(self) -> &'static str {
+        match self {
+            Self::Sqlite => "sqlite.db",
+            Self::Postgres => "postgres.sql",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub kind: ArtifactKind,
+    /// File name inside the set directory
+    pub file: String,
+    /// SHA-256 of the stored (compressed, maybe encrypted) file
+    pub sha256: String,
+    /// SHA-256 of the raw snapshot, used to skip unchanged backups
+    pub content_sha256: String,
+    pub size: u64,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub app_version: String,
+    /// Highest SQLite migration applied when the backup was taken
+    pub schema_version: Option<i32>,
+    /// Highest `pg_schema_history` version in the Postgres dump; `None` when
+    /// the set has no dump (or predates this field)
+    #[serde(default)]
+    pub pg_schema_version: Option<i32>,
+    pub artifacts: Vec<Artifact>,
+}
+
+impl Manifest {
+    pub fn total_size(&self) -> u64 {
+        self.artifacts.iter().map(|a| a.size).sum()
+    }
+}
+
+/// Where backups come from and go to.
+pub struct BackupConfig {
+    pub dir: PathBuf,
+    pub sqlite_path: PathBuf,
+    /// Set on the Postgres backend
+    pub postgres_url: Option<String>,
+    /// Shared-storage pool for reading `pg_schema_history`; without it a
+    /// backup opens its own connection to `postgres_url`
+    pub pg_pool: Option<sqlx::PgPool>,
+    pub age_recipient: Option<String>,
+    pub age_identity: Option<PathBuf>,
+    pub retention: RetentionPolicy,
+    pub remote: Option<S3Target>,
+}
+
+impl BackupConfig {
+    pub fn from_config() -> Result<Self> {
+        let postgres_url = match *config::DATABASE_DRIVER {
+            DatabaseDriver::Postgres => config::DATABASE_URL.clone(),
+            DatabaseDriver::Sqlite => None,
+        };
+        Ok(Self {
+            dir: config::backup::DIR.clone(),
+            sqlite_path: PathBuf::from(config::DATABASE_PATH.as_str()),
+            postgres_url,
+            pg_pool: None,
+            age_recipient: config::backup::AGE_RECIPIENT.clone(),
+            age_identity: config::backup::AGE_IDENTITY.clone(),
+            retention: RetentionPolicy::from_config(),
+            remote: S3Target::from_config()?,
+        })
+    }
+
+    /// Reuse `storage`'s Postgres pool instead of connecting per backup.
+    pub fn with_shared_storage(mut self, storage: &SharedStorage) -> Self {
+        if let SharedStorage::Postgres { pg_pool, .. } = storage {
+            self.pg_pool = Some(pg_pool.clone());
+        }
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum BackupOutcome {
+    Created {
+        manifest: Manifest,
+        path: PathBuf,
+        /// `Some` when an S3 target is configured: `Ok` once every file is uploaded
+        upload: Option<std::result::Result<(), String>>,
+        /// Sets removed by the retention policy
+        pruned: usize,
+    },
+    /// Nothing changed since `latest`; no new set was written
+    Unchanged { latest: Manifest },
+}
+
+impl BackupOutcome {
+    /// One-paragraph human summary for admin notifications and the CLI.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Created {
+                manifest,
+                path,
+                upload,
+                pruned,
+            } => {
+                let kinds: Vec<String> = manifest.artifacts.iter().map(|a| a.kind.to_string()).collect();
+                let upload = match upload {
+                    None => "not configured".to_string(),
+                    Some(Ok(())) => "ok".to_string(),
+                    Some(Err(e)) => format!("FAILED: {}", e),
+                };
+                format!(
+                    "Backup {} created ({}, {} bytes)\nPath: {}\nUpload: {}\nPruned: {}",
+                    manifest.id,
+                    kinds.join(" + "),
+                    manifest.total_size(),
+                    path.display(),
+                    upload,
+                    pruned
+                )
+            }
+            Self::Unchanged { latest } => format!("No changes since backup {}; nothing written", latest.id),
+        }
+    }
+}
+
+enum Staged {
+    Changed(Manifest),
+    Unchanged(Manifest),
+}
+
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(f).await.context("backup task panicked")?
+}
+
+/// Snapshot every database into a new set, upload it and apply retention.
+pub async fn create_backup(cfg: &BackupConfig) -> Result<BackupOutcome> {
+    fs::create_dir_all(&cfg.dir)?;
+    let created_at = Utc::now();
+    let id = created_at.format(SET_ID_FORMAT).to_string();
+    let staging = cfg.dir.join(format!(".{}.partial", id));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    let staged = stage_set(cfg, &staging, &id, created_at).await;
+    let manifest = match staged {
+        Ok(Staged::Changed(manifest)) => manifest,
+        Ok(Staged::Unchanged(latest)) => {
+            fs::remove_dir_all(&staging)?;
+            log::info!("Backup skipped: nothing changed since {}", latest.id);
+            return Ok(BackupOutcome::Unchanged { latest });
+        }
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    let path = cfg.dir.join(&id);
+    fs::rename(&staging, &path)?;
+    log::info!(
+        "Created backup {} ({} artifact(s), {} bytes)",
+        path.display(),
+        manifest.artifacts.len(),
+        manifest.total_size()
+    );
+
+    let upload = match &cfg.remote {
+        Some(remote) => Some(upload_set(remote, &path, &manifest).await.map_err(|e| {
+            log::error!("Failed to upload backup {} to {}: {:#}", id, remote.describe(), e);
+            format!("{:#}", e)
+        })),
+        None => None,
+    };
+    let pruned = prune_backups(cfg).await?;
+
+    Ok(BackupOutcome::Created {
+        manifest,
+        path,
+        upload,
+        pruned,
+    })
+}
+
+async fn stage_set(cfg: &BackupConfig, staging: &Path, id: &str, created_at: DateTime<Utc>) -> Result<Staged> {
+    let mut raw: Vec<(ArtifactKind, PathBuf)> = Vec::new();
+
+    let sqlite_raw = staging.join(ArtifactKind::Sqlite.raw_name());
+    {
+        let src = cfg.sqlite_path.clone();
+        let dst = sqlite_raw.clone();
+        blocking(move || snapshot::sqlite_snapshot(&src, &dst)).await?;
+    }
+    raw.push((ArtifactKind::Sqlite, sqlite_raw.clone()));
+
+    let mut pg_schema_version = None;
+    if let Some(url) = cfg.postgres_url.as_deref() {
+        let dst = staging.join(ArtifactKind::Postgres.raw_name());
+        let pool = match &cfg.pg_pool {
+            Some(pool) => pool.clone(),
+            None => snapshot::pg_connect(url).await?,
+        };
+        pg_schema_version = snapshot::pg_schema_version(&pool).await?;
+        snapshot::pg_dump(url, &dst).await?;
+        raw.push((ArtifactKind::Postgres, dst));
+    }
+
+    let schema_version = blocking(move || snapshot::sqlite_schema_version(&sqlite_raw)).await?;
+    let mut hashes = Vec::with_capacity(raw.len());
+    for (kind, path) in &raw {
+        let path = path.clone();
+        hashes.push((*kind, blocking(move || archive::sha256_file(&path)).await?));
+    }
+
+    if let Some(latest) = list_backups(&cfg.dir)?.into_iter().next()
+        && is_unchanged(&latest, &hashes)
+    {
+        return Ok(Staged::Unchanged(latest));
+    }
+
+    let mut artifacts = Vec::with_capacity(raw.len());
+    for ((kind, raw_path), (_, content_sha256)) in raw.into_iter().zip(hashes) {
+        let gz = raw_path.with_file_name(format!("{}.gz", kind.raw_name()));
+        {
+            let (src, dst) = (raw_path.clone(), gz.clone());
+            blocking(move || archive::gzip_file(&src, &dst)).await?;
+        }
+        fs::remove_file(&raw_path)?;
+
+        let stored = match cfg.age_recipient.as_deref() {
+            Some(recipient) => {
+                let encrypted = gz.with_file_name(format!("{}.gz.age", kind.raw_name()));
+                archive::age_encrypt(&gz, &encrypted, recipient).await?;
+                fs::remove_file(&gz)?;
+                encrypted
+            }
+            None => gz,
+        };
+        let sha256 = {
+            let path = stored.clone();
+            blocking(move || archive::sha256_file(&path)).await?
+        };
+        artifacts.push(Artifact {
+            kind,
+            file: stored
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string(),
+            sha256,
+            content_sha256,
+            size: fs::metadata(&stored)?.len(),
+            encrypted: cfg.age_recipient.is_some(),
+        });
+    }
+
+    let manifest = Manifest {
+        id: id.to_string(),
+        created_at,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version,
+        pg_schema_version,
+        artifacts,
+    };
+    fs::write(staging.join(MANIFEST_FILE), serde_json::to_vec_pretty(&manifest)?)?;
+    Ok(Staged::Changed(manifest))
+}
+
+fn is_unchanged(latest: &Manifest, hashes: &[(ArtifactKind, String)]) -> bool {
+    latest.artifacts.len() == hashes.len()
+        && hashes.iter().all(|(kind, hash)| {
+            latest
+                .artifacts
+                .iter()
+                .any(|a| a.kind == *kind && a.content_sha256 == *hash)
+        })
+}
+
+fn read_manifest(set_dir: &Path) -> Result<Manifest> {
+    let path = set_dir.join(MANIFEST_FILE);
+    let bytes = fs::read(&path)?;
+    serde_json::from_slice(&bytes).with_context(|| format!("parse {}", path.display()))
+}
+
+/// Complete local sets, newest first. In-progress (`.partial`) directories
+/// and pre-manifest legacy files are ignored.
+pub fn list_backups(dir: &Path) -> Result<Vec<Manifest>> {
+    let mut manifests = Vec::new();
+    if !dir.is_dir() {
+        return Ok(manifests);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_none_or(|n| n.starts_with('.'));
+        if hidden || !path.join(MANIFEST_FILE).is_file() {
+            continue;
+        }
+        match read_manifest(&path) {
+            Ok(manifest) => manifests.push(manifest),
+            Err(e) => log::warn!("Skipping unreadable backup {}: {:#}", path.display(), e),
+        }
+    }
+    manifests.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+    Ok(manifests)
+}
+
+async fn upload_set(remote: &S3Target, set_dir: &Path, manifest: &Manifest) -> Result<()> {
+    for artifact in &manifest.artifacts {
+        let key = remote.key(&format!("{}/{}", manifest.id, artifact.file));
+        remote.put_file(&key, &set_dir.join(&artifact.file)).await?;
+    }
+    // Manifest last: a listed manifest means the whole set is there.
+    let key = remote.key(&format!("{}/{}", manifest.id, MANIFEST_FILE));
+    remote.put_file(&key, &set_dir.join(MANIFEST_FILE)).await?;
+    log::info!("Uploaded backup {} to {}", manifest.id, remote.describe());
+    Ok(())
+}
+
+async fn download_set(remote: &S3Target, id: &str, set_dir: &Path) -> Result<()> {
+    let keys = remote.list(&remote.key(&format!("{}/", id))).await?;
+    if !keys.iter().any(|k| k.ends_with(MANIFEST_FILE)) {
+        bail!("Backup {} not found locally or in {}", id, remote.describe());
+    }
+    fs::create_dir_all(set_dir)?;
+    for key in keys {
+        let Some(name) = key.rsplit('/').next() else { continue };
+        remote.get_file(&key, &set_dir.join(name)).await?;
+    }
+    Ok(())
+}
+
+fn set_timestamp(id: &str) -> Option<DateTime<Utc>> {
+    let seconds = id.get(..15)?;
+    NaiveDateTime::parse_from_str(seconds, "%Y%m%d_%H%M%S")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// Apply the retention policy locally and in the S3 target. Returns the
+/// number of local sets removed.
+pub async fn prune_backups(cfg: &BackupConfig) -> Result<usize> {
+    let local = list_backups(&cfg.dir)?;
+    let keep = cfg
+        .retention
+        .retained(&local.iter().map(|m| m.created_at).collect::<Vec<_>>());
+    let mut removed = 0;
+    for (i, manifest) in local.iter().enumerate() {
+        if keep.contains(&i) {
+            continue;
+        }
+        match fs::remove_dir_all(cfg.dir.join(&manifest.id)) {
+            Ok(()) => {
+                removed += 1;
+                log::info!("Pruned backup {}", manifest.id);
+            }
+            Err(e) => log::warn!("Failed to prune backup {}: {}", manifest.id, e),
+        }
+    }
+
+    if let Some(remote) = &cfg.remote
+        && let Err(e) = prune_remote(remote, cfg.retention).await
+    {
+        log::warn!("Failed to prune backups in {}: {:#}", remote.describe(), e);
+    }
+    Ok(removed)
+}
+
+async fn prune_remote(remote: &S3Target, retention: RetentionPolicy) -> Result<()> {
+    let prefix = remote.key("");
+    let mut sets: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for key in remote.list(&prefix).await? {
+        if let Some((id, _)) = key[prefix.len()..].split_once('/') {
+            sets.entry(id.to_string()).or_default().push(key.clone());
+        }
+    }
+    // Only complete sets take part; a set without a manifest may still be uploading.
+    let complete: Vec<(String, DateTime<Utc>)> = sets
+        .iter()
+        .filter(|(_, keys)| keys.iter().any(|k| k.ends_with(MANIFEST_FILE)))
+        .filter_map(|(id, _)| Some((id.clone(), set_timestamp(id)?)))
+        .collect();
+    let keep = retention.retained(&complete.iter().map(|(_, t)| *t).collect::<Vec<_>>());
+    for (i, (id, _)) in complete.iter().enumerate() {
+        if keep.contains(&i) {
+            continue;
+        }
+        let mut keys = sets.remove(id).unwrap_or_default();
+        // Manifest first so a half-deleted set no longer counts as complete.
+        keys.sort_by_key(|k| !k.ends_with(MANIFEST_FILE));
+        for key in keys {
+            remote.delete(&key).await?;
+        }
+        log::info!("Pruned backup {} from {}", id, remote.describe());
+    }
+    Ok(())
+}
+
+/// Refuse backups taken with migrations this binary does not know, checking
+/// the SQLite version against `latest_sqlite` and the Postgres one against
+/// `latest_pg`.
+fn check_schema_version(manifest: &Manifest, latest_sqlite: i32, latest_pg: i32) -> Result<()> {
+    let checks = [
+        ("SQLite", manifest.schema_version, latest_sqlite),
+        ("Postgres", manifest.pg_schema_version, latest_pg),
+    ];
+    for (backend, version, latest_known) in checks {
+        if let Some(version) = version
+            && version > latest_known
+        {
+            bail!(
+                "Backup {} has {} schema version {} but this build only knows migrations up to {}; upgrade before restoring",
+                manifest.id,
+                backend,
+                version,
+                latest_known
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Restore set `id` (downloading it from S3 when it is not local). Every
+/// artifact is verified and decoded before anything is overwritten. The bot
+/// must be stopped: the SQLite file is replaced in place.
+pub async fn restore_backup(cfg: &BackupConfig, id: &str) -> Result<Manifest> {
+    let set_dir = cfg.dir.join(id);
+    if !set_dir.join(MANIFEST_FILE).is_file() {
+        match &cfg.remote {
+            Some(remote) => download_set(remote, id, &set_dir).await?,
+            None => bail!("Backup {} does not exist in {}", id, cfg.dir.display()),
+        }
+    }
+    let manifest = read_manifest(&set_dir)?;
+    check_schema_version(
+        &manifest,
+        migrations::latest_schema_version(),
+        latest_pg_schema_version(),
+    )?;
+
+    let work = cfg.dir.join(format!(".{}.restore", id));
+    if work.exists() {
+        fs::remove_dir_all(&work)?;
+    }
+    fs::create_dir_all(&work)?;
+    let result = restore_from(cfg, &manifest, &set_dir, &work).await;
+    let _ = fs::remove_dir_all(&work);
+    result?;
+    log::info!("Restored backup {}", manifest.id);
+    Ok(manifest)
+}
+
+async fn restore_from(cfg: &BackupConfig, manifest: &Manifest, set_dir: &Path, work: &Path) -> Result<()> {
+    let mut decoded = Vec::with_capacity(manifest.artifacts.len());
+    for artifact in &manifest.artifacts {
+        let stored = set_dir.join(&artifact.file);
+        let actual = {
+            let path = stored.clone();
+            blocking(move || archive::sha256_file(&path)).await?
+        };
+        if actual != artifact.sha256 {
+            bail!("Checksum mismatch for {} in backup {}", artifact.file, manifest.id);
+        }
+
+        let gz = if artifact.encrypted {
+            let identity = cfg
+                .age_identity
+                .as_deref()
+                .context("Backup is encrypted; set BACKUP_AGE_IDENTITY to the age identity file")?;
+            let out = work.join(format!("{}.gz", artifact.kind.raw_name()));
+            archive::age_decrypt(&stored, &out, identity).await?;
+            out
+        } else {
+            stored
+        };
+        let raw = work.join(artifact.kind.raw_name());
+        {
+            let (src, dst) = (gz.clone(), raw.clone());
+            blocking(move || archive::gunzip_file(&src, &dst)).await?;
+        }
+
+        match artifact.kind {
+            ArtifactKind::Sqlite => {
+                let path = raw.clone();
+                let version = blocking(move || {
+                    snapshot::sqlite_integrity_check(&path)?;
+                    snapshot::sqlite_schema_version(&path)
+                })
+                .await?;
+                if version != manifest.schema_version {
+                    bail!(
+                        "Backup {} manifest says schema {:?} but the SQLite snapshot is at {:?}",
+                        manifest.id,
+                        manifest.schema_version,
+                        version
+                    );
+                }
+            }
+            ArtifactKind::Postgres => {
+                if cfg.postgres_url.is_none() {
+                    bail!(
+                        "Backup {} contains a Postgres dump but DATABASE_URL is not configured",
+                        manifest.id
+                    );
+                }
+            }
+        }
+        decoded.push((artifact.kind, raw));
+    }
+
+    for (kind, raw) in decoded {
+        match kind {
+            ArtifactKind::Sqlite => {
+                let (src, dst) = (raw, cfg.sqlite_path.clone());
+                blocking(move || replace_sqlite(&src, &dst)).await?;
+            }
+            ArtifactKind::Postgres => {
+                let url = cfg.postgres_url.as_deref().unwrap_or_default();
+                snapshot::pg_restore(url, &raw).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Swap the SQLite file for `src` and drop the stale WAL/SHM sidecars.
+fn replace_sqlite(src: &Path, db_path: &Path) -> Result<()> {
+    let sidecar = |suffix: &str| PathBuf::from(format!("{}{}", db_path.display(), suffix));
+    let tmp = sidecar(".restoring");
+    fs::copy(src, &tmp)?;
+    fs::rename(&tmp, db_path)?;
+    for suffix in ["-wal", "-shm"] {
+        let path = sidecar(suffix);
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use tempfile::TempDir;
+
+    fn test_config(dir: &TempDir) -> BackupConfig {
+        BackupConfig {
+            dir: dir.path().join("backups"),
+            sqlite_path: dir.path().join("database.sqlite"),
+            postgres_url: None,
+            pg_pool: None,
+            age_recipient: None,
+            age_identity: None,
+            retention: RetentionPolicy {
+                daily: 7,
+                weekly: 4,
+                monthly: 6,
+            },
+            remote: None,
+        }
+    }
+
+    fn seed_db(path: &Path) {
+        let conn = Connection::open(path).unwrap();
+        conn.pragma_update(None, "journal_mode", "WAL").unwrap();
+        conn.execute_batch(
+            "CREATE TABLE refinery_schema_history (version INTEGER PRIMARY KEY, name TEXT);
+             INSERT INTO refinery_schema_history VALUES (1, 'init');
+             CREATE TABLE users (id INTEGER PRIMARY KEY);
+             INSERT INTO users (id) VALUES (1);",
+        )
+        .unwrap();
+    }
+
+    fn user_count(path: &Path) -> i64 {
+        Connection::open(path)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM users", [], |r| r.get(0))
+            .unwrap()
+    }
+
+    fn manifest(version: Option<i32>, pg_version: Option<i32>) -> Manifest {
+        Manifest {
+            id: "20260101_000000_000".to_string(),
+            created_at: Utc::now(),
+            app_version: "test".to_string(),
+            schema_version: version,
+            pg_schema_version: pg_version,
+            artifacts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_schema_version_check() {
+        assert!(check_schema_version(&manifest(Some(40), None), 52, 52).is_ok());
+        assert!(check_schema_version(&manifest(Some(52), Some(52)), 52, 52).is_ok());
+        assert!(check_schema_version(&manifest(None, None), 52, 52).is_ok());
+        let err = check_schema_version(&manifest(Some(53), None), 52, 52).unwrap_err();
+        assert!(err.to_string().contains("upgrade before restoring"));
+        let err = check_schema_version(&manifest(Some(52), Some(54)), 52, 53).unwrap_err();
+        assert!(err.to_string().contains("Postgres schema version 54"));
+    }
+
+    #[test]
+    fn test_manifest_without_pg_version_parses() {
+        let json = r#"{"id":"20260101_000000_000","created_at":"2026-01-01T00:00:00Z",
+            "app_version":"1.0","schema_version":52,"artifacts":[]}"#;
+        let manifest: Manifest = serde_json::from_str(json).unwrap();
+        assert_eq!(manifest.pg_schema_version, None);
+    }
+
+    #[test]
+    fn test_set_timestamp() {
+        let ts = set_timestamp("20260315_041500_123").unwrap();
+        assert_eq!(ts.format("%Y-%m-%d %H:%M:%S").to_string(), "2026-03-15 04:15:00");
+        assert!(set_timestamp("latest").is_none());
+    }
+
+    #[test]
+    fn test_list_backups_missing_dir() {
+        assert!(list_backups(Path::new("/nonexistent/dir")).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_backup_skip_and_restore_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let cfg = test_config(&dir);
+        seed_db(&cfg.sqlite_path);
+
+        let first = match create_backup(&cfg).await.unwrap() {
+            BackupOutcome::Created { manifest, upload, .. } => {
+                assert!(upload.is_none());
+                manifest
+            }
+            other => panic!("expected a new backup, got {:?}", other),
+        };
+        assert_eq!(first.schema_version, Some(1));
+        assert_eq!(first.artifacts.len(), 1);
+        assert_eq!(first.artifacts[0].file, "sqlite.db.gz");
+
+        // Nothing changed → no new set.
+        assert!(matches!(
+            create_backup(&cfg).await.unwrap(),
+            BackupOutcome::Unchanged { ref latest } if latest.id == first.id
+        ));
+
+        Connection::open(&cfg.sqlite_path)
+            .unwrap()
+            .execute("INSERT INTO users (id) VALUES (2)", [])
+            .unwrap();
+        assert!(matches!(
+            create_backup(&cfg).await.unwrap(),
+            BackupOutcome::Created { .. }
+        ));
+        assert_eq!(list_backups(&cfg.dir).unwrap().len(), 2);
+        assert_eq!(user_count(&cfg.sqlite_path), 2);
+
+        restore_backup(&cfg, &first.id).await.unwrap();
+        assert_eq!(user_count(&cfg.sqlite_path), 1);
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_tampered_artifact() {
+        let dir = TempDir::new().unwrap();
+        let cfg = test_config(&dir);
+        seed_db(&cfg.sqlite_path);
+        let BackupOutcome::Created { manifest, path, .. } = create_backup(&cfg).await.unwrap() else {
+            panic!("expected a new backup");
+        };
+        fs::write(path.join(&manifest.artifacts[0].file), b"tampered").unwrap();
+
+        let err = restore_backup(&cfg, &manifest.id).await.unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert_eq!(user_count(&cfg.sqlite_path), 1);
+    }
+
+    #[tokio::test]
+    async fn test_restore_backup_nonexistent() {
+        let dir = TempDir::new().unwrap();
+        let err = restore_backup(&test_config(&dir), "20200101_000000_000")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+    }
+}
@@ -0,0 +1,146 @@
+//! Grandfather-father-son retention for backup sets.
+
+use chrono::{DateTime, Datelike, Utc};
+use std::collections::BTreeSet;
+
+use crate::core::config;
+
+/// Keep the newest backup of each of the last `daily` days, `weekly` ISO
+/// weeks and `monthly` calendar months. The newest backup overall is always
+/// kept, even with an all-zero policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub daily: usize,
+    pub weekly: usize,
+    pub monthly: usize,
+}
+
+impl RetentionPolicy {
+    pub fn from_config() -> Self {
+        Self {
+            daily: *config::backup::KEEP_DAILY,
+            weekly: *config::backup::KEEP_WEEKLY,
+            monthly: *config::backup::KEEP_MONTHLY,
+        }
+    }
+
+    /// Indices into `timestamps` that the policy keeps.
+    pub fn retained(&self, timestamps: &[DateTime<Utc>]) -> BTreeSet<usize> {
+        let mut newest_first: Vec<usize> = (0..timestamps.len()).collect();
+        newest_first.sort_by_key(|&i| std::cmp::Reverse(timestamps[i]));
+
+        let mut keep = BTreeSet::new();
+        if let Some(&newest) = newest_first.first() {
+            keep.insert(newest);
+        }
+        keep_per_bucket(&newest_first, timestamps, self.daily, &mut keep, |t| {
+            (t.year(), t.ordinal())
+        });
+        keep_per_bucket(&newest_first, timestamps, self.weekly, &mut keep, |t| {
+            let week = t.iso_week();
+            (week.year(), week.week())
+        });
+        keep_per_bucket(&newest_first, timestamps, self.monthly, &mut keep, |t| {
+            (t.year(), t.month())
+        });
+        keep
+    }
+}
+
+/// Walk newest → oldest and keep the first backup seen in each of the first
+/// `limit` distinct buckets.
+fn keep_per_bucket<K: PartialEq>(
+    newest_first: &[usize],
+    timestamps: &[DateTime<Utc>],
+    limit: usize,
+    keep: &mut BTreeSet<usize>,
+    bucket: impl Fn(&DateTime<Utc>) -> K,
+) {
+    let mut last: Option<K> = None;
+    let mut buckets = 0;
+    for &i in newest_first {
+        if buckets >= limit {
+            break;
+        }
+        let key = bucket(&timestamps[i]);
+        if last.as_ref() != Some(&key) {
+            keep.insert(i);
+            buckets += 1;
+            last = Some(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn daily_backups(days: i64) -> Vec<DateTime<Utc>> {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 3, 0, 0).unwrap();
+        (0..days).map(|d| start + Duration::days(d)).collect()
+    }
+
+    #[test]
+    fn empty_input_keeps_nothing() {
+        let policy = RetentionPolicy {
+            daily: 7,
+            weekly: 4,
+            monthly: 6,
+        };
+        assert!(policy.retained(&[]).is_empty());
+    }
+
+    #[test]
+    fn zero_policy_still_keeps_newest() {
+        let policy = RetentionPolicy {
+            daily: 0,
+            weekly: 0,
+            monthly: 0,
+        };
+        let ts = daily_backups(10);
+        assert_eq!(policy.retained(&ts), BTreeSet::from([9]));
+    }
+
+    #[test]
+    fn gfs_keeps_days_weeks_and_months() {
+        let policy = RetentionPolicy {
+            daily: 7,
+            weekly: 4,
+            monthly: 3,
+        };
+        // 2026-01-01 .. 2026-04-30, one backup per day.
+        let ts = daily_backups(120);
+        let keep = policy.retained(&ts);
+
+        // Last seven days.
+        for i in 113..120 {
+            assert!(keep.contains(&i), "day {} should be kept", i);
+        }
+        // Newest backup of April, March and February.
+        let newest_of = |month: u32| (0..ts.len()).rev().find(|&i| ts[i].month() == month).unwrap();
+        assert!(keep.contains(&newest_of(3)));
+        assert!(keep.contains(&newest_of(2)));
+        assert!(!keep.contains(&newest_of(1)));
+        // Days + at most 3 extra weeks + at most 2 extra months.
+        assert!(keep.len() <= 7 + 3 + 2, "kept {:?}", keep);
+    }
+
+    #[test]
+    fn several_backups_per_day_keep_only_the_newest() {
+        let policy = RetentionPolicy {
+            daily: 2,
+            weekly: 0,
+            monthly: 0,
+        };
+        let day = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap();
+        let ts = vec![
+            day + Duration::hours(1),
+            day + Duration::hours(5),
+            day + Duration::hours(23),
+            day - Duration::hours(2),
+            day - Duration::hours(20),
+        ];
+        assert_eq!(policy.retained(&ts), BTreeSet::from([2, 3]));
+    }
+}
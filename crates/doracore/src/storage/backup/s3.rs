@@ -0,0 +1,411 @@
+//! Minimal S3-compatible client for off-site backup copies.
+//!
+//! Speaks path-style requests signed with AWS Signature V4, which AWS S3,
+//! MinIO, Cloudflare R2 and Backblaze B2 all accept. Only the four calls the
+//! backup job needs are implemented: put, get, delete and list.
+
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::Method;
+use secrecy::{ExposeSecret, SecretString};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+use url::Url;
+
+use crate::core::config;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// SigV4 payload hash for bodies streamed without hashing them first.
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Request body for [`S3Target::send`].
+enum Payload {
+    Empty,
+    /// Streamed from disk with its length, so archives never sit in memory
+    File(tokio::fs::File, u64),
+}
+
+pub struct S3Target {
+    endpoint: Url,
+    bucket: String,
+    region: String,
+    prefix: String,
+    access_key: String,
+    secret_key: SecretString,
+    client: reqwest::Client,
+}
+
+impl S3Target {
+    /// Target for the bucket root; see [`Self::with_prefix`].
+    pub fn new(endpoint: &str, bucket: &str, region: &str, access_key: &str, secret_key: SecretString) -> Result<Self> {
+        let endpoint = Url::parse(endpoint).with_context(|| format!("invalid S3 endpoint {}", endpoint))?;
+        if endpoint.host_str().is_none() {
+            bail!("S3 endpoint {} has no host", endpoint);
+        }
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("build S3 HTTP client")?;
+        Ok(Self {
+            endpoint,
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            prefix: String::new(),
+            access_key: access_key.to_string(),
+            secret_key,
+            client,
+        })
+    }
+
+    /// Keep every object under `prefix` (e.g. `doradura/`).
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    /// Target from `BACKUP_S3_*`, or `None` when uploads are not configured.
+    pub fn from_config() -> Result<Option<Self>> {
+        let (Some(endpoint), Some(bucket), Some(access_key), Some(secret_key)) = (
+            config::backup::S3_ENDPOINT.as_deref(),
+            config::backup::S3_BUCKET.as_deref(),
+            config::backup::S3_ACCESS_KEY.as_deref(),
+            config::backup::S3_SECRET_KEY.clone(),
+        ) else {
+            return Ok(None);
+        };
+        Self::new(endpoint, bucket, &config::backup::S3_REGION, access_key, secret_key)
+            .map(|target| Some(target.with_prefix(&config::backup::S3_PREFIX)))
+    }
+
+    /// Full object key for `name` under the configured prefix.
+    pub fn key(&self, name: &str) -> String {
+        format!("{}{}", self.prefix, name)
+    }
+
+    /// `bucket/prefix` for log messages.
+    pub fn describe(&self) -> String {
+        format!("s3://{}/{}", self.bucket, self.prefix)
+    }
+
+    /// Upload `path` as a stream. The body is signed as `UNSIGNED-PAYLOAD`;
+    /// the manifest's checksums cover its integrity.
+    pub async fn put_file(&self, key: &str, path: &Path) -> Result<()> {
+        let file = tokio::fs::File::open(path)
+            .await
+            .with_context(|| format!("open {}", path.display()))?;
+        let len = file.metadata().await?.len();
+        self.send(Method::PUT, key, &[], Payload::File(file, len)).await?;
+        Ok(())
+    }
+
+    pub async fn get_file(&self, key: &str, dst: &Path) -> Result<()> {
+        let mut response = self.send(Method::GET, key, &[], Payload::Empty).await?;
+        let mut file = tokio::fs::File::create(dst)
+            .await
+            .with_context(|| format!("create {}", dst.display()))?;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .with_context(|| format!("read s3 object {}", key))?
+        {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        self.send(Method::DELETE, key, &[], Payload::Empty).await?;
+        Ok(())
+    }
+
+    /// All object keys starting with `prefix` (ListObjectsV2, paginated).
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix)];
+            if let Some(token) = token.as_deref() {
+                query.push(("continuation-token", token));
+            }
+            let body = self.send(Method::GET, "", &query, Payload::Empty).await?.text().await?;
+            keys.extend(xml_values(&body, "Key"));
+            token = xml_values(&body, "NextContinuationToken").into_iter().next();
+            if token.is_none() || !body.contains("<IsTruncated>true</IsTruncated>") {
+                return Ok(keys);
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        payload: Payload,
+    ) -> Result<reqwest::Response> {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", self.endpoint.host_str().unwrap_or_default(), port),
+            None => self.endpoint.host_str().unwrap_or_default().to_string(),
+        };
+        let base = self.endpoint.path().trim_end_matches('/');
+        let uri = if key.is_empty() {
+            format!("{}/{}", base, uri_encode(&self.bucket, false))
+        } else {
+            format!("{}/{}/{}", base, uri_encode(&self.bucket, false), uri_encode(key, true))
+        };
+        let query = canonical_query(query);
+        let (payload_hash, body, content_length) = match payload {
+            Payload::Empty => (hex::encode(Sha256::digest(b"")), reqwest::Body::from(Vec::new()), None),
+            Payload::File(file, len) => (
+                UNSIGNED_PAYLOAD.to_string(),
+                reqwest::Body::wrap_stream(ReaderStream::new(file)),
+                Some(len),
+            ),
+        };
+
+        let headers = [
+            ("host", host.as_str()),
+            ("x-amz-content-sha256", payload_hash.as_str()),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+        let request = canonical_request(method.as_str(), &uri, &query, &headers, &payload_hash);
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let signing = signing_key(self.secret_key.expose_secret(), &date, &self.region, "s3");
+        let signature = sign(&signing, &amz_date, &scope, &request);
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key,
+            scope,
+            signed_headers(&headers),
+            signature
+        );
+
+        let mut url = format!("{}://{}{}", self.endpoint.scheme(), host, uri);
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query);
+        }
+        let mut request = self
+            .client
+            .request(method.clone(), &url)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("x-amz-date", &amz_date)
+            .header("authorization", authorization);
+        // S3 rejects chunked uploads; a streamed body needs an explicit length.
+        if let Some(len) = content_length {
+            request = request.header(reqwest::header::CONTENT_LENGTH, len);
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("S3 {} {}", method, uri))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            bail!(
+                "S3 {} {} failed: {} {}",
+                method,
+                uri,
+                status,
+                text.chars().take(300).collect::<String>()
+            );
+        }
+        Ok(response)
+    }
+}
+
+/// RFC 3986 encoding as SigV4 expects; `/` is kept in object keys.
+fn uri_encode(value: &str, keep_slash: bool) -> String {
+    if keep_slash {
+        value.split('/').map(urlencoding::encode).collect::<Vec<_>>().join("/")
+    } else {
+        urlencoding::encode(value).into_owned()
+    }
+}
+
+fn canonical_query(query: &[(&str, &str)]) -> String {
+    let mut pairs: Vec<(String, String)> = query
+        .iter()
+        .map(|(k, v)| (uri_encode(k, false), uri_encode(v, false)))
+        .collect();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// `headers` must be lowercase and sorted by name.
+fn signed_headers(headers: &[(&str, &str)]) -> String {
+    headers.iter().map(|(k, _)| *k).collect::<Vec<_>>().join(";")
+}
+
+fn canonical_request(method: &str, uri: &str, query: &str, headers: &[(&str, &str)], payload_hash: &str) -> String {
+    let canonical_headers: String = headers.iter().map(|(k, v)| format!("{}:{}\n", k, v.trim())).collect();
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method,
+        uri,
+        query,
+        canonical_headers,
+        signed_headers(headers),
+        payload_hash
+    )
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    #[allow(clippy::expect_used)]
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac(format!("AWS4{}", secret).as_bytes(), date);
+    let k_region = hmac(&k_date, region);
+    let k_service = hmac(&k_region, service);
+    hmac(&k_service, "aws4_request")
+}
+
+fn sign(signing_key: &[u8], amz_date: &str, scope: &str, canonical_request: &str) -> String {
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    hex::encode(hmac(signing_key, &string_to_sign))
+}
+
+/// Text of every `<tag>…</tag>` element, XML entities decoded.
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else { break };
+        values.push(
+            rest[..end]
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&"),
+        );
+        rest = &rest[end + close.len()..];
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs_err as fs;
+
+    const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn signing_key_matches_aws_example() {
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn get_object_signature_matches_aws_example() {
+        let headers = [
+            ("host", "examplebucket.s3.amazonaws.com"),
+            ("range", "bytes=0-9"),
+            ("x-amz-content-sha256", EMPTY_SHA256),
+            ("x-amz-date", "20130524T000000Z"),
+        ];
+        let request = canonical_request("GET", "/test.txt", "", &headers, EMPTY_SHA256);
+        assert_eq!(
+            hex::encode(Sha256::digest(request.as_bytes())),
+            "7344ae5b7ee6c3e7e6b0fe0640412a37625d1fbfff95c48bbb2dc43964946972"
+        );
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY",
+            "20130524",
+            "us-east-1",
+            "s3",
+        );
+        let signature = sign(&key, "20130524T000000Z", "20130524/us-east-1/s3/aws4_request", &request);
+        assert_eq!(
+            signature,
+            "f0e8bdb87c964420e857bd35b5d6ed310bd44f0170aba48dd91039c6036bdb41"
+        );
+    }
+
+    #[test]
+    fn query_is_sorted_and_encoded() {
+        assert_eq!(
+            canonical_query(&[("prefix", "doradura/2026 01"), ("list-type", "2")]),
+            "list-type=2&prefix=doradura%2F2026%2001"
+        );
+        assert_eq!(uri_encode("doradura/a b.gz", true), "doradura/a%20b.gz");
+    }
+
+    #[test]
+    fn parses_list_response() {
+        let xml = "<ListBucketResult><IsTruncated>true</IsTruncated>\
+                   <Contents><Key>doradura/a&amp;b</Key></Contents>\
+                   <Contents><Key>doradura/c</Key></Contents>\
+                   <NextContinuationToken>tok</NextContinuationToken></ListBucketResult>";
+        assert_eq!(xml_values(xml, "Key"), vec!["doradura/a&b", "doradura/c"]);
+        assert_eq!(xml_values(xml, "NextContinuationToken"), vec!["tok"]);
+        assert!(xml_values(xml, "Missing").is_empty());
+    }
+
+    /// Round trip against a real bucket, e.g. a local MinIO:
+    /// `docker run -p 9000:9000 minio/minio server /data`, create the bucket,
+    /// then run with `BACKUP_TEST_S3_{ENDPOINT,BUCKET,ACCESS_KEY,SECRET_KEY}`.
+    #[tokio::test]
+    #[ignore = "requires an S3-compatible endpoint (e.g. local MinIO)"]
+    async fn minio_roundtrip() {
+        let env = |k: &str| std::env::var(k).unwrap();
+        let target = S3Target::new(
+            &env("BACKUP_TEST_S3_ENDPOINT"),
+            &env("BACKUP_TEST_S3_BUCKET"),
+            "us-east-1",
+            &env("BACKUP_TEST_S3_ACCESS_KEY"),
+            SecretString::from(env("BACKUP_TEST_S3_SECRET_KEY")),
+        )
+        .unwrap()
+        .with_prefix("doradura-test/");
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("object");
+        fs::write(&src, b"backup bytes").unwrap();
+
+        let key = target.key("roundtrip/object.gz");
+        target.put_file(&key, &src).await.unwrap();
+        assert!(target.list(&target.key("roundtrip/")).await.unwrap().contains(&key));
+        let dst = dir.path().join("downloaded");
+        target.get_file(&key, &dst).await.unwrap();
+        assert_eq!(fs::read(&dst).unwrap(), b"backup bytes");
+        target.delete(&key).await.unwrap();
+        assert!(!target.list(&target.key("roundtrip/")).await.unwrap().contains(&key));
+    }
+}
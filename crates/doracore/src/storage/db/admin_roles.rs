@@ -0,0 +1,84 @@
+//! SQLite operations on the V58 `admin_roles` table, used through
+//! `SharedStorage` on the SQLite backend. Roles are stored as their
+//! `core::admin_roles::AdminRole` names; the permission matrix is in code.
+
+use anyhow::Result;
+
+use super::DbConnection;
+
+/// A role granted from the dashboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminRoleGrant {
+    pub user_id: i64,
+    pub role: String,
+    pub granted_by: i64,
+    pub granted_at: String,
+}
+
+/// All granted roles, oldest first.
+pub fn list_admin_roles(conn: &DbConnection) -> Result<Vec<AdminRoleGrant>> {
+    let mut stmt =
+        conn.prepare("SELECT user_id, role, granted_by, granted_at FROM admin_roles ORDER BY granted_at, user_id")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(AdminRoleGrant {
+                user_id: row.get(0)?,
+                role: row.get(1)?,
+                granted_by: row.get(2)?,
+                granted_at: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+/// Grant `role` to `user_id`, replacing any previous role.
+pub fn set_admin_role(conn: &DbConnection, user_id: i64, role: &str, granted_by: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO admin_roles (user_id, role, granted_by) VALUES (?1, ?2, ?3) \
+         ON CONFLICT(user_id) DO UPDATE SET role = excluded.role, granted_by = excluded.granted_by, \
+         granted_at = CURRENT_TIMESTAMP",
+        rusqlite::params![user_id, role, granted_by],
+    )?;
+    Ok(())
+}
+
+/// Revoke the role of `user_id`. Returns whether there was one.
+pub fn remove_admin_role(conn: &DbConnection, user_id: i64) -> Result<bool> {
+    Ok(conn.execute("DELETE FROM admin_roles WHERE user_id = ?1", rusqlite::params![user_id])? > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{create_pool, get_connection};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn setup_pool() -> crate::storage::db::DbPool {
+        let counter = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir().join(format!("admin_roles_test_{}_{}.db", std::process::id(), counter));
+        let _ = fs_err::remove_file(&path);
+        create_pool(path.to_string_lossy().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn grant_replace_and_revoke() {
+        let pool = setup_pool();
+        let conn = get_connection(&pool).unwrap();
+        set_admin_role(&conn, 10, "support", 1).unwrap();
+        set_admin_role(&conn, 10, "operator", 2).unwrap();
+
+        let roles = list_admin_roles(&conn).unwrap();
+        assert_eq!(roles.len(), 1);
+        assert_eq!(
+            (roles[0].user_id, roles[0].role.as_str(), roles[0].granted_by),
+            (10, "operator", 2)
+        );
+
+        assert!(remove_admin_role(&conn, 10).unwrap());
+        assert!(!remove_admin_role(&conn, 10).unwrap());
+        assert!(list_admin_roles(&conn).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+//! SQLite operations on the V43 `admin_sessions` table and the V59
+//! `web_rate_limits` counters.
+//!
+//! See migrations/V59__admin_auth_state.sql for column commentary. The shared
+//! `SharedStorage` wrapper lives at `storage/shared/admin_sessions.rs` and
+//! dispatches to either this module or the Postgres branch.
+
+use anyhow::Result;
+use rusqlite::OptionalExtension;
+
+use super::DbConnection;
+
+/// A live admin dashboard session, resolved from the cookie's token hash.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminSession {
+    pub admin_id: i64,
+    /// Per-session value the dashboard sends back in `x-csrf-token`.
+    pub csrf_token: String,
+}
+
+/// An unexpired admin session as listed on the dashboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminSessionInfo {
+    pub token_hash: Vec<u8>,
+    pub admin_id: i64,
+    pub created_at: String,
+    pub last_seen: String,
+    pub expires_at: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+/// Persist a new admin session valid for `ttl_hours`.
+pub fn create_admin_session(
+    conn: &DbConnection,
+    token_hash: &[u8],
+    admin_id: i64,
+    csrf_token: &str,
+    ttl_hours: i64,
+    user_agent: Option<&str>,
+    ip: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO admin_sessions (token_hash, admin_id, csrf_token, expires_at, user_agent, ip)
+         VALUES (?1, ?2, ?3, datetime('now', '+' || ?4 || ' hours'), ?5, ?6)",
+        rusqlite::params![token_hash, admin_id, csrf_token, ttl_hours, user_agent, ip],
+    )?;
+    Ok(())
+}
+
+/// Look up an unexpired session and bump its `last_seen`.
+pub fn get_admin_session(conn: &DbConnection, token_hash: &[u8]) -> Result<Option<AdminSession>> {
+    let session = conn
+        .query_row(
+            "UPDATE admin_sessions SET last_seen = datetime('now')
+             WHERE token_hash = ?1 AND expires_at > datetime('now') AND csrf_token IS NOT NULL
+             RETURNING admin_id, csrf_token",
+            rusqlite::params![token_hash],
+            |row| {
+                Ok(AdminSession {
+                    admin_id: row.get(0)?,
+                    csrf_token: row.get(1)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(session)
+}
+
+/// All unexpired sessions, most recently active first.
+pub fn list_admin_sessions(conn: &DbConnection) -> Result<Vec<AdminSessionInfo>> {
+    let mut stmt = conn.prepare(
+        "SELECT token_hash, admin_id, created_at, last_seen, expires_at, user_agent, ip
+         FROM admin_sessions WHERE expires_at > datetime('now')
+         ORDER BY last_seen DESC",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(AdminSessionInfo {
+                token_hash: row.get(0)?,
+                admin_id: row.get(1)?,
+                created_at: row.get(2)?,
+                last_seen: row.get(3)?,
+                expires_at: row.get(4)?,
+                user_agent: row.get(5)?,
+                ip: row.get(6)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+/// Revoke one session (logout / force-logout). Returns whether it existed.
+pub fn delete_admin_session(conn: &DbConnection, token_hash: &[u8]) -> Result<bool> {
+    Ok(conn.execute(
+        "DELETE FROM admin_sessions WHERE token_hash = ?1",
+        rusqlite::params![token_hash],
+    )? > 0)
+}
+
+/// Revoke every session of `admin_id`. Returns the number removed.
+pub fn delete_admin_sessions_for(conn: &DbConnection, admin_id: i64) -> Result<usize> {
+    Ok(conn.execute(
+        "DELETE FROM admin_sessions WHERE admin_id = ?1",
+        rusqlite::params![admin_id],
+    )?)
+}
+
+/// Drop expired sessions. Returns the number of rows removed.
+pub fn purge_expired_admin_sessions(conn: &DbConnection) -> Result<usize> {
+    Ok(conn.execute("DELETE FROM admin_sessions WHERE expires_at <= datetime('now')", [])?)
+}
+
+/// Count a hit against `bucket` in a fixed window of `window_secs` starting
+/// at its first hit. Returns the hits so far in the current window,
+/// including this one.
+pub fn hit_rate_limit(conn: &DbConnection, bucket: &str, window_secs: i64, now: i64) -> Result<u32> {
+    let hits: i64 = conn.query_row(
+        "INSERT INTO web_rate_limits (bucket, hits, window_start) VALUES (?1, 1, ?2)
+         ON CONFLICT(bucket) DO UPDATE SET
+             hits = CASE WHEN window_start <= ?2 - ?3 THEN 1 ELSE hits + 1 END,
+             window_start = CASE WHEN window_start <= ?2 - ?3 THEN ?2 ELSE window_start END
+         RETURNING hits",
+        rusqlite::params![bucket, now, window_secs],
+        |row| row.get(0),
+    )?;
+    Ok(u32::try_from(hits).unwrap_or(u32::MAX))
+}
+
+/// Hits recorded against `bucket` in its current window, without counting a
+/// new one. A window that has run out reads as zero.
+pub fn rate_limit_hits(conn: &DbConnection, bucket: &str, window_secs: i64, now: i64) -> Result<u32> {
+    let hits: Option<i64> = conn
+        .query_row(
+            "SELECT hits FROM web_rate_limits WHERE bucket = ?1 AND window_start > ?2 - ?3",
+            rusqlite::params![bucket, now, window_secs],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(hits.map_or(0, |h| u32::try_from(h).unwrap_or(u32::MAX)))
+}
+
+/// Drop rate-limit buckets whose window started before `cutoff` (unix secs).
+pub fn purge_rate_limits(conn: &DbConnection, cutoff: i64) -> Result<usize> {
+    Ok(conn.execute(
+        "DELETE FROM web_rate_limits WHERE window_start < ?1",
+        rusqlite::params![cutoff],
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{create_pool, get_connection};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn setup_pool() -> crate::storage::db::DbPool {
+        let counter = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir().join(format!("admin_sessions_test_{}_{}.db", std::process::id(), counter));
+        let _ = fs_err::remove_file(&path);
+        create_pool(path.to_string_lossy().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn session_lifecycle() {
+        let pool = setup_pool();
+        let conn = get_connection(&pool).unwrap();
+        create_admin_session(&conn, b"live", 7, "csrf-1", 24, Some("test-agent"), None).unwrap();
+        create_admin_session(&conn, b"other", 7, "csrf-2", 24, None, Some("10.0.0.1")).unwrap();
+        create_admin_session(&conn, b"stale", 8, "csrf-3", -1, None, None).unwrap();
+
+        assert_eq!(
+            get_admin_session(&conn, b"live").unwrap(),
+            Some(AdminSession {
+                admin_id: 7,
+                csrf_token: "csrf-1".to_string(),
+            })
+        );
+        assert_eq!(get_admin_session(&conn, b"stale").unwrap(), None);
+        assert_eq!(list_admin_sessions(&conn).unwrap().len(), 2);
+
+        assert_eq!(purge_expired_admin_sessions(&conn).unwrap(), 1);
+        assert!(delete_admin_session(&conn, b"live").unwrap());
+        assert!(!delete_admin_session(&conn, b"live").unwrap());
+        assert_eq!(delete_admin_sessions_for(&conn, 7).unwrap(), 1);
+        assert!(list_admin_sessions(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rate_limit_window_resets() {
+        let pool = setup_pool();
+        let conn = get_connection(&pool).unwrap();
+        assert_eq!(hit_rate_limit(&conn, "login:1.2.3.4", 300, 1_000).unwrap(), 1);
+        assert_eq!(hit_rate_limit(&conn, "login:1.2.3.4", 300, 1_100).unwrap(), 2);
+        assert_eq!(hit_rate_limit(&conn, "login:5.6.7.8", 300, 1_100).unwrap(), 1);
+        // Window is anchored at the first hit, not the latest one
+        assert_eq!(hit_rate_limit(&conn, "login:1.2.3.4", 300, 1_300).unwrap(), 1);
+
+        // Peeking does not count a hit and ignores expired windows
+        assert_eq!(rate_limit_hits(&conn, "login:5.6.7.8", 300, 1_150).unwrap(), 1);
+        assert_eq!(rate_limit_hits(&conn, "login:5.6.7.8", 300, 1_150).unwrap(), 1);
+        assert_eq!(rate_limit_hits(&conn, "login:5.6.7.8", 300, 1_400).unwrap(), 0);
+        assert_eq!(rate_limit_hits(&conn, "portal_login:1.2.3.4", 300, 1_300).unwrap(), 0);
+
+        assert_eq!(purge_rate_limits(&conn, 1_200).unwrap(), 1);
+    }
+}
@@ -0,0 +1,577 @@
+//! SQLite operations on the V51 `credit_balances` / `credit_ledger` tables.
+//!
+//! Pay-per-use credits bought with Telegram Stars. Every balance change is a
+//! ledger row written in the same transaction as the balance update; refunds
+//! and purchases are idempotent through the partial unique indexes on
+//! `(task_id, kind)` and `(telegram_charge_id, kind)`. The shared wrapper lives
+//! at `storage/shared/credits.rs`.
+
+use anyhow::Result;
+use rusqlite::{Connection, OptionalExtension};
+
+use super::DbConnection;
+
+/// Premium action that costs credits once the daily free allowance is used up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::AsRefStr, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum CreditAction {
+    /// 1440p / 2160p / 4320p video download.
+    Highres,
+    /// Stories (vertical 9:16 segments) render.
+    Stories,
+    /// Downloading a hand-picked subset of a multi-item post (carousel/album).
+    AlbumSplit,
+}
+
+impl CreditAction {
+    /// Alias for `Into::<&'static str>::into` — matches the other stored enums.
+    pub fn as_str(&self) -> &'static str {
+        self.into()
+    }
+}
+
+/// Price tag attached to a task at enqueue time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreditCharge {
+    pub action: CreditAction,
+    /// Credits debited when the free allowance is exhausted.
+    pub cost: i64,
+    /// Uses of `action` per UTC day that don't cost anything.
+    pub free_per_day: u32,
+}
+
+/// What [`apply_credit_debit`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebitOutcome {
+    /// Covered by the daily free allowance (zero-delta `allowance` row).
+    Allowance,
+    /// Credits were debited; `balance_after` is the remaining balance.
+    Charged { balance_after: i64 },
+    /// Not enough credits — nothing was written.
+    Insufficient { balance: i64 },
+}
+
+/// One ledger row as shown in the user's history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreditTransaction {
+    pub id: i64,
+    pub delta: i64,
+    pub balance_after: i64,
+    pub kind: String,
+    pub action: Option<String>,
+    pub task_id: Option<String>,
+    pub stars_amount: Option<i64>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+/// Aggregate credit sales and burn for the admin revenue report.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CreditStats {
+    /// Number of Star-pack purchases.
+    pub purchases: i64,
+    /// Stars received for credit packs.
+    pub stars_total: i64,
+    /// Credits added by purchases.
+    pub credits_sold: i64,
+    /// Credits debited by premium actions, net of refunds.
+    pub credits_burned: i64,
+    /// Credits returned for failed or cancelled tasks.
+    pub credits_refunded: i64,
+    /// Credits taken back after a Stars refund.
+    pub credits_revoked: i64,
+    /// Sum of all current balances (unspent liability).
+    pub outstanding: i64,
+    /// Net burn per action, e.g. `("highres", 120)`.
+    pub burn_by_action: Vec<(String, i64)>,
+}
+
+/// Current credit balance (0 for users that never bought credits).
+pub fn get_credit_balance(conn: &Connection, user_id: i64) -> Result<i64> {
+    let balance = conn
+        .query_row(
+            "SELECT balance FROM credit_balances WHERE user_id = ?1",
+            rusqlite::params![user_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    Ok(balance.unwrap_or(0))
+}
+
+/// Add `delta` to the balance (creating the row) and return the new balance.
+/// Caller must hold a transaction.
+fn bump_balance(conn: &Connection, user_id: i64, delta: i64) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO credit_balances (user_id, balance, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(user_id) DO UPDATE SET
+            balance = balance + excluded.balance,
+            updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![user_id, delta],
+    )?;
+    get_credit_balance(conn, user_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_ledger_row(
+    conn: &Connection,
+    user_id: i64,
+    delta: i64,
+    balance_after: i64,
+    kind: &str,
+    action: Option<&str>,
+    task_id: Option<&str>,
+    telegram_charge_id: Option<&str>,
+    stars_amount: Option<i64>,
+    note: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO credit_ledger
+            (user_id, delta, balance_after, kind, action, task_id, telegram_charge_id, stars_amount, note)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            user_id,
+            delta,
+            balance_after,
+            kind,
+            action,
+            task_id,
+            telegram_charge_id,
+            stars_amount,
+            note
+        ],
+    )?;
+    Ok(())
+}
+
+/// Run `f` inside `BEGIN IMMEDIATE`, committing on `Ok` and rolling back on `Err`.
+fn immediate_tx<T>(conn: &Connection, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    conn.execute_batch("BEGIN IMMEDIATE")?;
+    let result = f();
+    if result.is_ok() {
+        conn.execute_batch("COMMIT")?;
+    } else {
+        let _ = conn.execute_batch("ROLLBACK");
+    }
+    result
+}
+
+/// Debit `charge` for `task_id`: a zero-delta `allowance` row while today's
+/// free uses last, otherwise a `debit` row if the balance covers the cost.
+///
+/// Does NOT open a transaction — the caller wraps it together with the task
+/// insert so the debit and the task land (or roll back) as one unit. Allowance
+/// rows whose task was refunded don't count against the day.
+pub fn apply_credit_debit(
+    conn: &Connection,
+    user_id: i64,
+    task_id: &str,
+    charge: &CreditCharge,
+) -> Result<DebitOutcome> {
+    let action = charge.action.as_str();
+    if charge.free_per_day > 0 {
+        let used_today: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM credit_ledger l
+             WHERE l.user_id = ?1 AND l.kind = 'allowance' AND l.action = ?2
+               AND date(l.created_at) = date('now')
+               AND NOT EXISTS (
+                   SELECT 1 FROM credit_ledger r WHERE r.task_id = l.task_id AND r.kind = 'refund'
+               )",
+            rusqlite::params![user_id, action],
+            |r| r.get(0),
+        )?;
+        if used_today < i64::from(charge.free_per_day) {
+            let balance = get_credit_balance(conn, user_id)?;
+            insert_ledger_row(
+                conn,
+                user_id,
+                0,
+                balance,
+                "allowance",
+                Some(action),
+                Some(task_id),
+                None,
+                None,
+                None,
+            )?;
+            return Ok(DebitOutcome::Allowance);
+        }
+    }
+
+    let balance = get_credit_balance(conn, user_id)?;
+    if balance < charge.cost {
+        return Ok(DebitOutcome::Insufficient { balance });
+    }
+    let balance_after = bump_balance(conn, user_id, -charge.cost)?;
+    insert_ledger_row(
+        conn,
+        user_id,
+        -charge.cost,
+        balance_after,
+        "debit",
+        Some(action),
+        Some(task_id),
+        None,
+        None,
+        None,
+    )?;
+    Ok(DebitOutcome::Charged { balance_after })
+}
+
+/// Standalone debit for work that isn't a `task_queue` row (stories render).
+/// `Insufficient` leaves the ledger untouched.
+pub fn debit_credits(conn: &DbConnection, user_id: i64, task_id: &str, charge: &CreditCharge) -> Result<DebitOutcome> {
+    immediate_tx(conn, || apply_credit_debit(conn, user_id, task_id, charge))
+}
+
+/// Return the credits debited for `task_id`. Idempotent: returns `None` when
+/// the task was never charged or already refunded, `Some(credits)` otherwise
+/// (0 for an allowance use, which is given back to the day's quota).
+pub fn refund_task_credits(conn: &DbConnection, task_id: &str, reason: &str) -> Result<Option<i64>> {
+    immediate_tx(conn, || {
+        let charged: Option<(i64, i64, Option<String>)> = conn
+            .query_row(
+                "SELECT user_id, delta, action FROM credit_ledger
+                 WHERE task_id = ?1 AND kind IN ('debit', 'allowance')",
+                rusqlite::params![task_id],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .optional()?;
+        let Some((user_id, delta, action)) = charged else {
+            return Ok(None);
+        };
+        let already: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM credit_ledger WHERE task_id = ?1 AND kind = 'refund')",
+            rusqlite::params![task_id],
+            |r| r.get(0),
+        )?;
+        if already {
+            return Ok(None);
+        }
+        let amount = -delta;
+        let balance_after = if amount > 0 {
+            bump_balance(conn, user_id, amount)?
+        } else {
+            get_credit_balance(conn, user_id)?
+        };
+        insert_ledger_row(
+            conn,
+            user_id,
+            amount,
+            balance_after,
+            "refund",
+            action.as_deref(),
+            Some(task_id),
+            None,
+            None,
+            Some(reason),
+        )?;
+        Ok(Some(amount))
+    })
+}
+
+/// Credit a Star-pack purchase. Returns the new balance, or `None` if this
+/// `telegram_charge_id` was already credited (Telegram re-delivered the update).
+pub fn add_purchased_credits(
+    conn: &DbConnection,
+    user_id: i64,
+    credits: i64,
+    stars_amount: i64,
+    telegram_charge_id: &str,
+) -> Result<Option<i64>> {
+    immediate_tx(conn, || {
+        let already: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM credit_ledger WHERE telegram_charge_id = ?1 AND kind = 'purchase')",
+            rusqlite::params![telegram_charge_id],
+            |r| r.get(0),
+        )?;
+        if already {
+            return Ok(None);
+        }
+        let balance_after = bump_balance(conn, user_id, credits)?;
+        insert_ledger_row(
+            conn,
+            user_id,
+            credits,
+            balance_after,
+            "purchase",
+            None,
+            None,
+            Some(telegram_charge_id),
+            Some(stars_amount),
+            None,
+        )?;
+        Ok(Some(balance_after))
+    })
+}
+
+/// Give free credits (promo code, referral reward). `grant_key` is stored as
+/// the row's `task_id`, so the same grant is applied at most once; returns the
+/// new balance, or `None` if `grant_key` was already granted.
+pub fn grant_credits(
+    conn: &DbConnection,
+    user_id: i64,
+    credits: i64,
+    grant_key: &str,
+    note: Option<&str>,
+) -> Result<Option<i64>> {
+    immediate_tx(conn, || {
+        let already: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM credit_ledger WHERE task_id = ?1 AND kind = 'grant')",
+            rusqlite::params![grant_key],
+            |r| r.get(0),
+        )?;
+        if already {
+            return Ok(None);
+        }
+        let balance_after = bump_balance(conn, user_id, credits)?;
+        insert_ledger_row(
+            conn,
+            user_id,
+            credits,
+            balance_after,
+            "grant",
+            None,
+            Some(grant_key),
+            None,
+            None,
+            note,
+        )?;
+        Ok(Some(balance_after))
+    })
+}
+
+/// Take back the credits of a refunded Star-pack purchase, never below zero
+/// (credits already spent stay spent). Returns the credits revoked, or `None`
+/// if the charge isn't a credit purchase or was already revoked.
+pub fn revoke_purchased_credits(conn: &DbConnection, telegram_charge_id: &str) -> Result<Option<i64>> {
+    immediate_tx(conn, || {
+        let purchase: Option<(i64, i64)> = conn
+            .query_row(
+                "SELECT user_id, delta FROM credit_ledger WHERE telegram_charge_id = ?1 AND kind = 'purchase'",
+                rusqlite::params![telegram_charge_id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()?;
+        let Some((user_id, credits)) = purchase else {
+            return Ok(None);
+        };
+        let already: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM credit_ledger WHERE telegram_charge_id = ?1 AND kind = 'revoke')",
+            rusqlite::params![telegram_charge_id],
+            |r| r.get(0),
+        )?;
+        if already {
+            return Ok(None);
+        }
+        let revoked = credits.min(get_credit_balance(conn, user_id)?).max(0);
+        let balance_after = bump_balance(conn, user_id, -revoked)?;
+        insert_ledger_row(
+            conn,
+            user_id,
+            -revoked,
+            balance_after,
+            "revoke",
+            None,
+            None,
+            Some(telegram_charge_id),
+            None,
+            None,
+        )?;
+        Ok(Some(revoked))
+    })
+}
+
+/// Most recent ledger rows for a user, newest first.
+pub fn list_credit_transactions(conn: &DbConnection, user_id: i64, limit: i64) -> Result<Vec<CreditTransaction>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, delta, balance_after, kind, action, task_id, stars_amount, note, created_at
+         FROM credit_ledger WHERE user_id = ?1
+         ORDER BY id DESC LIMIT ?2",
+    )?;
+    let rows = stmt
+        .query_map(rusqlite::params![user_id, limit], |r| {
+            Ok(CreditTransaction {
+                id: r.get(0)?,
+                delta: r.get(1)?,
+                balance_after: r.get(2)?,
+                kind: r.get(3)?,
+                action: r.get(4)?,
+                task_id: r.get(5)?,
+                stars_amount: r.get(6)?,
+                note: r.get(7)?,
+                created_at: r.get(8)?,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Aggregate credit sales and burn across all users.
+pub fn get_credit_stats(conn: &Connection) -> Result<CreditStats> {
+    let mut stats = conn.query_row(
+        "SELECT
+            COALESCE(SUM(CASE WHEN kind = 'purchase' THEN 1 ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN kind = 'purchase' THEN stars_amount ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN kind = 'purchase' THEN delta ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN kind = 'debit' THEN -delta ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN kind = 'refund' THEN delta ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN kind = 'revoke' THEN -delta ELSE 0 END), 0)
+         FROM credit_ledger",
+        [],
+        |r| {
+            let debited: i64 = r.get(3)?;
+            let refunded: i64 = r.get(4)?;
+            Ok(CreditStats {
+                purchases: r.get(0)?,
+                stars_total: r.get(1)?,
+                credits_sold: r.get(2)?,
+                credits_burned: debited - refunded,
+                credits_refunded: refunded,
+                credits_revoked: r.get(5)?,
+                ..CreditStats::default()
+            })
+        },
+    )?;
+    stats.outstanding = conn.query_row("SELECT COALESCE(SUM(balance), 0) FROM credit_balances", [], |r| {
+        r.get(0)
+    })?;
+    let mut stmt = conn.prepare(
+        "SELECT action, COALESCE(SUM(-delta), 0) FROM credit_ledger
+         WHERE kind IN ('debit', 'refund') AND action IS NOT NULL
+         GROUP BY action ORDER BY 2 DESC",
+    )?;
+    stats.burn_by_action = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{create_pool, get_connection};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static C: AtomicU64 = AtomicU64::new(0);
+
+    fn pool() -> crate::storage::db::DbPool {
+        let n = C.fetch_add(1, Ordering::SeqCst);
+        let p = std::env::temp_dir().join(format!("credits_{}_{}.db", std::process::id(), n));
+        let _ = fs_err::remove_file(&p);
+        create_pool(p.to_string_lossy().as_ref()).unwrap()
+    }
+
+    const HIGHRES: CreditCharge = CreditCharge {
+        action: CreditAction::Highres,
+        cost: 5,
+        free_per_day: 1,
+    };
+
+    #[test]
+    fn grant_is_idempotent_per_key() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        assert_eq!(
+            grant_credits(&conn, 7, 5, "referral:9:referrer", None).unwrap(),
+            Some(5)
+        );
+        assert_eq!(grant_credits(&conn, 7, 5, "referral:9:referrer", None).unwrap(), None);
+        assert_eq!(
+            grant_credits(&conn, 7, 3, "promo:FREE3:7", Some("promo FREE3")).unwrap(),
+            Some(8)
+        );
+    }
+
+    #[test]
+    fn purchase_is_idempotent_per_charge() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        assert_eq!(get_credit_balance(&conn, 7).unwrap(), 0);
+        assert_eq!(add_purchased_credits(&conn, 7, 10, 50, "ch_1").unwrap(), Some(10));
+        assert_eq!(add_purchased_credits(&conn, 7, 10, 50, "ch_1").unwrap(), None);
+        assert_eq!(get_credit_balance(&conn, 7).unwrap(), 10);
+    }
+
+    #[test]
+    fn allowance_then_debit_then_insufficient() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        add_purchased_credits(&conn, 7, 6, 30, "ch_1").unwrap();
+
+        assert_eq!(
+            debit_credits(&conn, 7, "t1", &HIGHRES).unwrap(),
+            DebitOutcome::Allowance
+        );
+        assert_eq!(
+            debit_credits(&conn, 7, "t2", &HIGHRES).unwrap(),
+            DebitOutcome::Charged { balance_after: 1 }
+        );
+        assert_eq!(
+            debit_credits(&conn, 7, "t3", &HIGHRES).unwrap(),
+            DebitOutcome::Insufficient { balance: 1 }
+        );
+        // Insufficient writes nothing.
+        assert_eq!(list_credit_transactions(&conn, 7, 10).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn refund_restores_balance_once() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        add_purchased_credits(&conn, 7, 10, 50, "ch_1").unwrap();
+        let paid = CreditCharge {
+            free_per_day: 0,
+            ..HIGHRES
+        };
+        debit_credits(&conn, 7, "t1", &paid).unwrap();
+        assert_eq!(get_credit_balance(&conn, 7).unwrap(), 5);
+
+        assert_eq!(refund_task_credits(&conn, "t1", "failed").unwrap(), Some(5));
+        assert_eq!(refund_task_credits(&conn, "t1", "failed").unwrap(), None);
+        assert_eq!(refund_task_credits(&conn, "unknown", "failed").unwrap(), None);
+        assert_eq!(get_credit_balance(&conn, 7).unwrap(), 10);
+    }
+
+    #[test]
+    fn refunded_allowance_frees_the_daily_slot() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        assert_eq!(
+            debit_credits(&conn, 7, "t1", &HIGHRES).unwrap(),
+            DebitOutcome::Allowance
+        );
+        assert_eq!(refund_task_credits(&conn, "t1", "cancelled").unwrap(), Some(0));
+        assert_eq!(
+            debit_credits(&conn, 7, "t2", &HIGHRES).unwrap(),
+            DebitOutcome::Allowance
+        );
+    }
+
+    #[test]
+    fn revoke_never_goes_negative_and_stats_add_up() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        add_purchased_credits(&conn, 7, 10, 50, "ch_1").unwrap();
+        let paid = CreditCharge {
+            free_per_day: 0,
+            ..HIGHRES
+        };
+        debit_credits(&conn, 7, "t1", &paid).unwrap();
+        debit_credits(&conn, 7, "t2", &paid).unwrap();
+        refund_task_credits(&conn, "t2", "failed").unwrap();
+
+        assert_eq!(revoke_purchased_credits(&conn, "ch_1").unwrap(), Some(5));
+        assert_eq!(revoke_purchased_credits(&conn, "ch_1").unwrap(), None);
+        assert_eq!(get_credit_balance(&conn, 7).unwrap(), 0);
+
+        let stats = get_credit_stats(&conn).unwrap();
+        assert_eq!(stats.purchases, 1);
+        assert_eq!(stats.stars_total, 50);
+        assert_eq!(stats.credits_sold, 10);
+        assert_eq!(stats.credits_burned, 5);
+        assert_eq!(stats.credits_refunded, 5);
+        assert_eq!(stats.credits_revoked, 5);
+        assert_eq!(stats.outstanding, 0);
+        assert_eq!(stats.burn_by_action, vec![("highres".to_string(), 5)]);
+    }
+}
@@ -0,0 +1,148 @@
+//! SQLite operations on the V53 `feed_episodes` table.
+//!
+//! The `rss` content watcher stores one [`FeedEpisode`] per notified enclosure
+//! so the download pipeline can restore podcast metadata (title, show, cover,
+//! chapters) for the enclosure URL. The shared wrapper lives at
+//! `storage/shared/feed_episodes.rs`.
+
+use anyhow::Result;
+use rusqlite::OptionalExtension;
+
+use super::DbConnection;
+use crate::timestamps::VideoTimestamp;
+
+/// Podcast metadata for one enclosure URL.
+#[derive(Debug, Clone, Default)]
+pub struct FeedEpisode {
+    pub enclosure_url: String,
+    pub feed_url: String,
+    pub title: String,
+    /// Show (feed) name, used as the artist.
+    pub podcast: String,
+    pub cover_url: Option<String>,
+    pub duration_secs: Option<i64>,
+    /// Inline chapters from the feed item.
+    pub chapters: Vec<VideoTimestamp>,
+    /// Podcasting 2.0 chapters document, fetched at download time.
+    pub chapters_url: Option<String>,
+}
+
+impl FeedEpisode {
+    pub(crate) fn chapters_json(&self) -> Option<String> {
+        if self.chapters.is_empty() {
+            return None;
+        }
+        serde_json::to_string(&self.chapters).ok()
+    }
+
+    pub(crate) fn parse_chapters(json: Option<String>) -> Vec<VideoTimestamp> {
+        json.and_then(|j| serde_json::from_str(&j).ok()).unwrap_or_default()
+    }
+}
+
+/// Fetch the episode stored for an enclosure URL, if any.
+pub fn get_feed_episode(conn: &DbConnection, enclosure_url: &str) -> Result<Option<FeedEpisode>> {
+    let row = conn
+        .query_row(
+            "SELECT enclosure_url, feed_url, title, podcast, cover_url, duration_secs, chapters_json, chapters_url
+             FROM feed_episodes WHERE enclosure_url = ?1",
+            rusqlite::params![enclosure_url],
+            |r| {
+                Ok(FeedEpisode {
+                    enclosure_url: r.get(0)?,
+                    feed_url: r.get(1)?,
+                    title: r.get(2)?,
+                    podcast: r.get(3)?,
+                    cover_url: r.get(4)?,
+                    duration_secs: r.get(5)?,
+                    chapters: FeedEpisode::parse_chapters(r.get(6)?),
+                    chapters_url: r.get(7)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(row)
+}
+
+/// Insert or refresh an episode (feeds may edit titles or chapters later).
+pub fn upsert_feed_episode(conn: &DbConnection, episode: &FeedEpisode) -> Result<()> {
+    conn.execute(
+        "INSERT INTO feed_episodes
+            (enclosure_url, feed_url, title, podcast, cover_url, duration_secs, chapters_json, chapters_url)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(enclosure_url) DO UPDATE SET
+            feed_url = excluded.feed_url,
+            title = excluded.title,
+            podcast = excluded.podcast,
+            cover_url = excluded.cover_url,
+            duration_secs = excluded.duration_secs,
+            chapters_json = excluded.chapters_json,
+            chapters_url = excluded.chapters_url",
+        rusqlite::params![
+            episode.enclosure_url,
+            episode.feed_url,
+            episode.title,
+            episode.podcast,
+            episode.cover_url,
+            episode.duration_secs,
+            episode.chapters_json(),
+            episode.chapters_url
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{create_pool, get_connection};
+    use crate::timestamps::TimestampSource;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static C: AtomicU64 = AtomicU64::new(0);
+
+    fn pool() -> crate::storage::db::DbPool {
+        let n = C.fetch_add(1, Ordering::SeqCst);
+        let p = std::env::temp_dir().join(format!("feed_ep_{}_{}.db", std::process::id(), n));
+        let _ = fs_err::remove_file(&p);
+        create_pool(p.to_string_lossy().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn upsert_then_get_round_trips_chapters() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        let url = "https://cdn.example.com/ep1.mp3";
+        assert!(get_feed_episode(&conn, url).unwrap().is_none());
+
+        let mut episode = FeedEpisode {
+            enclosure_url: url.to_string(),
+            feed_url: "https://example.com/feed.xml".to_string(),
+            title: "Episode 1".to_string(),
+            podcast: "Dora Talks".to_string(),
+            cover_url: Some("https://example.com/cover.jpg".to_string()),
+            duration_secs: Some(3600),
+            chapters: vec![VideoTimestamp {
+                source: TimestampSource::Chapter,
+                time_seconds: 0,
+                end_seconds: Some(60),
+                label: Some("Intro".to_string()),
+            }],
+            chapters_url: None,
+        };
+        upsert_feed_episode(&conn, &episode).unwrap();
+        let got = get_feed_episode(&conn, url).unwrap().unwrap();
+        assert_eq!(got.podcast, "Dora Talks");
+        assert_eq!(got.duration_secs, Some(3600));
+        assert_eq!(got.chapters.len(), 1);
+        assert_eq!(got.chapters[0].label.as_deref(), Some("Intro"));
+
+        // Feed edits replace the stored row
+        episode.title = "Episode 1 (remastered)".to_string();
+        episode.chapters.clear();
+        upsert_feed_episode(&conn, &episode).unwrap();
+        let got = get_feed_episode(&conn, url).unwrap().unwrap();
+        assert_eq!(got.title, "Episode 1 (remastered)");
+        assert!(got.chapters.is_empty());
+    }
+}
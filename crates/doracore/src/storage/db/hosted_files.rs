@@ -0,0 +1,141 @@
+//! SQLite operations on the V56 `hosted_files` table.
+//!
+//! See migrations/V56__hosted_files.sql for column commentary. The shared
+//! `SharedStorage` wrapper lives at `storage/shared/hosted_files.rs` and
+//! dispatches to either this module or the Postgres branch.
+
+use anyhow::Result;
+use rusqlite::OptionalExtension;
+
+use super::DbConnection;
+
+/// A file published through a signed `/d/{id}` link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostedFile {
+    pub id: String,
+    pub user_id: i64,
+    pub file_path: String,
+    /// Name offered to the browser in `Content-Disposition`.
+    pub file_name: String,
+    pub content_type: String,
+    pub file_size: i64,
+    pub download_count: i64,
+}
+
+/// Register a hosted file that stays downloadable until `expires_at_unix`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_hosted_file(
+    conn: &DbConnection,
+    id: &str,
+    user_id: i64,
+    file_path: &str,
+    file_name: &str,
+    content_type: &str,
+    file_size: i64,
+    expires_at_unix: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO hosted_files (id, user_id, file_path, file_name, content_type, file_size, expires_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, datetime(?7, 'unixepoch'))",
+        rusqlite::params![
+            id,
+            user_id,
+            file_path,
+            file_name,
+            content_type,
+            file_size,
+            expires_at_unix
+        ],
+    )?;
+    Ok(())
+}
+
+/// Look up an unexpired hosted file.
+pub fn get_hosted_file(conn: &DbConnection, id: &str) -> Result<Option<HostedFile>> {
+    let file = conn
+        .query_row(
+            "SELECT id, user_id, file_path, file_name, content_type, file_size, download_count
+             FROM hosted_files WHERE id = ?1 AND expires_at > datetime('now')",
+            rusqlite::params![id],
+            |row| {
+                Ok(HostedFile {
+                    id: row.get(0)?,
+                    user_id: row.get(1)?,
+                    file_path: row.get(2)?,
+                    file_name: row.get(3)?,
+                    content_type: row.get(4)?,
+                    file_size: row.get(5)?,
+                    download_count: row.get(6)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(file)
+}
+
+/// Count one download of a hosted file.
+pub fn record_hosted_download(conn: &DbConnection, id: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE hosted_files SET download_count = download_count + 1, last_download_at = datetime('now')
+         WHERE id = ?1",
+        rusqlite::params![id],
+    )?;
+    Ok(())
+}
+
+/// Drop one hosted file's row once its file has been removed.
+pub fn delete_hosted_file(conn: &DbConnection, id: &str) -> Result<()> {
+    conn.execute("DELETE FROM hosted_files WHERE id = ?1", rusqlite::params![id])?;
+    Ok(())
+}
+
+/// Delete expired rows and return their file paths so the caller can remove
+/// the files themselves.
+pub fn take_expired_hosted_files(conn: &DbConnection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("DELETE FROM hosted_files WHERE expires_at <= datetime('now') RETURNING file_path")?;
+    let paths = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{create_pool, get_connection};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn setup_pool() -> crate::storage::db::DbPool {
+        let counter = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir().join(format!("hosted_files_test_{}_{}.db", std::process::id(), counter));
+        let _ = fs_err::remove_file(&path);
+        create_pool(path.to_string_lossy().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn hosted_file_lifecycle() {
+        let pool = setup_pool();
+        let conn = get_connection(&pool).unwrap();
+        let now = chrono::Utc::now().timestamp();
+        create_hosted_file(&conn, "live", 7, "/tmp/a.mp4", "a.mp4", "video/mp4", 1024, now + 3600).unwrap();
+        create_hosted_file(&conn, "stale", 7, "/tmp/b.mp3", "b.mp3", "audio/mpeg", 512, now - 60).unwrap();
+
+        assert_eq!(get_hosted_file(&conn, "stale").unwrap(), None);
+        record_hosted_download(&conn, "live").unwrap();
+        record_hosted_download(&conn, "live").unwrap();
+        let live = get_hosted_file(&conn, "live").unwrap().unwrap();
+        assert_eq!(live.download_count, 2);
+        assert_eq!(live.file_name, "a.mp4");
+
+        assert_eq!(
+            take_expired_hosted_files(&conn).unwrap(),
+            vec!["/tmp/b.mp3".to_string()]
+        );
+        assert!(take_expired_hosted_files(&conn).unwrap().is_empty());
+        assert!(get_hosted_file(&conn, "live").unwrap().is_some());
+        delete_hosted_file(&conn, "live").unwrap();
+        assert_eq!(get_hosted_file(&conn, "live").unwrap(), None);
+    }
+}
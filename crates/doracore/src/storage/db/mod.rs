@@ -1,13 +1,21 @@
 //! Database access layer -- re-exports from sub-modules.
 
+mod admin_roles;
+mod admin_sessions;
 mod categories;
+mod credits;
 mod cuts;
 mod download_history;
 mod errors;
+mod feed_episodes;
+mod hosted_files;
 mod lyrics_overrides;
 mod playlists;
 mod pool;
 mod popular_files;
+mod portal_sessions;
+mod promo;
+mod public_api;
 mod sessions;
 mod silent_digest;
 mod subscriptions;
@@ -15,14 +23,22 @@ mod synced_playlists;
 mod task_queue;
 mod users;
 mod vault;
+pub use admin_roles::*;
+pub use admin_sessions::*;
 pub use categories::*;
+pub use credits::*;
 pub use cuts::*;
 pub use download_history::*;
 pub use errors::*;
+pub use feed_episodes::*;
+pub use hosted_files::*;
 pub use lyrics_overrides::*;
 pub use playlists::*;
 pub use pool::*;
 pub use popular_files::*;
+pub use portal_sessions::*;
+pub use promo::*;
+pub use public_api::*;
 pub use sessions::*;
 pub use silent_digest::*;
 pub use subscriptions::*;
@@ -71,6 +87,27 @@ pub fn set_bot_asset(conn: &DbConnection, key: &str, file_id: &str) -> Result<()
     Ok(())
 }
 
+// ==================== Admin Audit Log ====================
+
+/// Append a row to the V40 `admin_audit_log` (shown on the admin dashboard).
+/// `admin_id` is the acting admin, or the issuer for user-triggered events
+/// such as promo redemptions.
+pub fn log_admin_audit(
+    conn: &rusqlite::Connection,
+    admin_id: i64,
+    action: &str,
+    target_type: &str,
+    target_id: &str,
+    details: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO admin_audit_log (admin_id, action, target_type, target_id, details) \
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![admin_id, action, target_type, target_id, details],
+    )?;
+    Ok(())
+}
+
 // ==================== Video Timestamps ====================
 
 use crate::timestamps::{TimestampSource, VideoTimestamp};
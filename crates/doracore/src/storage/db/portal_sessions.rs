@@ -0,0 +1,107 @@
+//! SQLite operations on the V55 `portal_sessions` table.
+//!
+//! See migrations/V55__portal_sessions.sql for column commentary. The shared
+//! `SharedStorage` wrapper lives at `storage/shared/portal_sessions.rs` and
+//! dispatches to either this module or the Postgres branch.
+
+use anyhow::Result;
+use rusqlite::OptionalExtension;
+
+use super::DbConnection;
+
+/// A live user portal session, resolved from the cookie's token hash.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortalSession {
+    pub user_id: i64,
+    /// Per-session value the portal page sends back in `x-csrf-token`.
+    pub csrf_token: String,
+}
+
+/// Persist a new portal session valid for `ttl_hours`.
+pub fn create_portal_session(
+    conn: &DbConnection,
+    token_hash: &[u8],
+    user_id: i64,
+    csrf_token: &str,
+    ttl_hours: i64,
+    user_agent: Option<&str>,
+    ip: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO portal_sessions (token_hash, user_id, csrf_token, expires_at, user_agent, ip)
+         VALUES (?1, ?2, ?3, datetime('now', '+' || ?4 || ' hours'), ?5, ?6)",
+        rusqlite::params![token_hash, user_id, csrf_token, ttl_hours, user_agent, ip],
+    )?;
+    Ok(())
+}
+
+/// Look up an unexpired session and bump its `last_seen`.
+pub fn get_portal_session(conn: &DbConnection, token_hash: &[u8]) -> Result<Option<PortalSession>> {
+    let session = conn
+        .query_row(
+            "UPDATE portal_sessions SET last_seen = datetime('now')
+             WHERE token_hash = ?1 AND expires_at > datetime('now')
+             RETURNING user_id, csrf_token",
+            rusqlite::params![token_hash],
+            |row| {
+                Ok(PortalSession {
+                    user_id: row.get(0)?,
+                    csrf_token: row.get(1)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(session)
+}
+
+/// Revoke a session (logout).
+pub fn delete_portal_session(conn: &DbConnection, token_hash: &[u8]) -> Result<()> {
+    conn.execute(
+        "DELETE FROM portal_sessions WHERE token_hash = ?1",
+        rusqlite::params![token_hash],
+    )?;
+    Ok(())
+}
+
+/// Drop expired sessions. Returns the number of rows removed.
+pub fn purge_expired_portal_sessions(conn: &DbConnection) -> Result<usize> {
+    Ok(conn.execute("DELETE FROM portal_sessions WHERE expires_at <= datetime('now')", [])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{create_pool, get_connection};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn setup_pool() -> crate::storage::db::DbPool {
+        let counter = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir().join(format!("portal_sessions_test_{}_{}.db", std::process::id(), counter));
+        let _ = fs_err::remove_file(&path);
+        create_pool(path.to_string_lossy().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn session_lifecycle() {
+        let pool = setup_pool();
+        let conn = get_connection(&pool).unwrap();
+        create_portal_session(&conn, b"live", 7, "csrf-1", 24, Some("test-agent"), None).unwrap();
+        create_portal_session(&conn, b"stale", 7, "csrf-2", -1, None, None).unwrap();
+
+        assert_eq!(
+            get_portal_session(&conn, b"live").unwrap(),
+            Some(PortalSession {
+                user_id: 7,
+                csrf_token: "csrf-1".to_string(),
+            })
+        );
+        assert_eq!(get_portal_session(&conn, b"stale").unwrap(), None);
+        assert_eq!(get_portal_session(&conn, b"missing").unwrap(), None);
+
+        assert_eq!(purge_expired_portal_sessions(&conn).unwrap(), 1);
+        delete_portal_session(&conn, b"live").unwrap();
+        assert_eq!(get_portal_session(&conn, b"live").unwrap(), None);
+    }
+}
@@ -0,0 +1,378 @@
+//! SQLite operations on the V52 `promo_codes` / `promo_redemptions` /
+//! `referrals` tables.
+//!
+//! Redemption bookkeeping only — applying the reward (plan, discount, credits)
+//! is the caller's job. The shared wrapper lives at `storage/shared/promo.rs`.
+
+use anyhow::Result;
+use rusqlite::{Connection, OptionalExtension};
+
+use super::DbConnection;
+
+/// What a promo code grants.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString, strum::AsRefStr, strum::IntoStaticStr,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum PromoKind {
+    /// A paid plan for `days`.
+    Plan,
+    /// Percent off the next subscription invoice.
+    Discount,
+    /// Free credits.
+    Credits,
+}
+
+impl PromoKind {
+    /// Alias for `Into::<&'static str>::into` — matches the other stored enums.
+    pub fn as_str(&self) -> &'static str {
+        self.into()
+    }
+}
+
+/// Where a code came from: issued by an admin or minted for a gift purchase.
+pub const PROMO_SOURCE_ADMIN: &str = "admin";
+pub const PROMO_SOURCE_GIFT: &str = "gift";
+
+/// A row of `promo_codes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromoCode {
+    pub code: String,
+    pub kind: PromoKind,
+    pub plan: Option<String>,
+    pub days: Option<i32>,
+    pub percent: Option<i32>,
+    pub credits: Option<i64>,
+    /// `None` = unlimited.
+    pub max_uses: Option<i32>,
+    pub used_count: i32,
+    /// `YYYY-MM-DD HH:MM:SS` UTC, `None` = never expires.
+    pub expires_at: Option<String>,
+    pub source: String,
+    /// Issuing admin, or the buyer for gift codes.
+    pub created_by: i64,
+    pub is_active: bool,
+    pub created_at: String,
+}
+
+/// Input for [`create_promo_code`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewPromoCode {
+    pub code: String,
+    pub kind: PromoKind,
+    pub plan: Option<String>,
+    pub days: Option<i32>,
+    pub percent: Option<i32>,
+    pub credits: Option<i64>,
+    pub max_uses: Option<i32>,
+    pub expires_at: Option<String>,
+    pub source: &'static str,
+    pub created_by: i64,
+}
+
+/// Result of [`redeem_promo_code`]. Only `Redeemed` writes anything.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedeemOutcome {
+    Redeemed(PromoCode),
+    /// Unknown or disabled code.
+    NotFound,
+    Expired,
+    /// `max_uses` reached.
+    Exhausted,
+    AlreadyRedeemed,
+}
+
+/// Uppercase a user-typed code and check it is 3–32 chars of `[A-Z0-9_-]`.
+pub fn normalize_promo_code(raw: &str) -> Option<String> {
+    let code = raw.trim().to_ascii_uppercase();
+    let valid =
+        (3..=32).contains(&code.len()) && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    valid.then_some(code)
+}
+
+const PROMO_COLUMNS: &str = "code, kind, plan, days, percent, credits, max_uses, used_count, expires_at, source, \
+                             created_by, is_active, created_at";
+
+fn map_promo_code(r: &rusqlite::Row<'_>) -> rusqlite::Result<PromoCode> {
+    let kind: String = r.get(1)?;
+    Ok(PromoCode {
+        code: r.get(0)?,
+        kind: kind.parse().unwrap_or(PromoKind::Credits),
+        plan: r.get(2)?,
+        days: r.get(3)?,
+        percent: r.get(4)?,
+        credits: r.get(5)?,
+        max_uses: r.get(6)?,
+        used_count: r.get(7)?,
+        expires_at: r.get(8)?,
+        source: r.get(9)?,
+        created_by: r.get(10)?,
+        is_active: r.get::<_, i32>(11)? != 0,
+        created_at: r.get(12)?,
+    })
+}
+
+/// Insert a new code. Returns `false` if the code already exists.
+pub fn create_promo_code(conn: &Connection, new: &NewPromoCode) -> Result<bool> {
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO promo_codes
+            (code, kind, plan, days, percent, credits, max_uses, expires_at, source, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        rusqlite::params![
+            new.code,
+            new.kind.as_str(),
+            new.plan,
+            new.days,
+            new.percent,
+            new.credits,
+            new.max_uses,
+            new.expires_at,
+            new.source,
+            new.created_by
+        ],
+    )?;
+    Ok(inserted > 0)
+}
+
+pub fn get_promo_code(conn: &Connection, code: &str) -> Result<Option<PromoCode>> {
+    let row = conn
+        .query_row(
+            &format!("SELECT {PROMO_COLUMNS} FROM promo_codes WHERE code = ?1"),
+            rusqlite::params![code],
+            map_promo_code,
+        )
+        .optional()?;
+    Ok(row)
+}
+
+/// Most recently created codes first.
+pub fn list_promo_codes(conn: &Connection, limit: i64) -> Result<Vec<PromoCode>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {PROMO_COLUMNS} FROM promo_codes ORDER BY created_at DESC, code LIMIT ?1"
+    ))?;
+    let rows = stmt
+        .query_map(rusqlite::params![limit], map_promo_code)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Enable or disable a code. Returns `false` if it doesn't exist.
+pub fn set_promo_code_active(conn: &Connection, code: &str, active: bool) -> Result<bool> {
+    let n = conn.execute(
+        "UPDATE promo_codes SET is_active = ?2 WHERE code = ?1",
+        rusqlite::params![code, i32::from(active)],
+    )?;
+    Ok(n > 0)
+}
+
+/// Record that `user_id` redeemed `code`, enforcing expiry, `max_uses` and one
+/// redemption per user in a single `BEGIN IMMEDIATE` transaction.
+pub fn redeem_promo_code(conn: &DbConnection, code: &str, user_id: i64) -> Result<RedeemOutcome> {
+    conn.execute_batch("BEGIN IMMEDIATE")?;
+    let result = (|| -> Result<RedeemOutcome> {
+        let row = conn
+            .query_row(
+                &format!(
+                    "SELECT {PROMO_COLUMNS},
+                            expires_at IS NOT NULL AND expires_at <= datetime('now')
+                     FROM promo_codes WHERE code = ?1 AND is_active = 1"
+                ),
+                rusqlite::params![code],
+                |r| Ok((map_promo_code(r)?, r.get::<_, bool>(13)?)),
+            )
+            .optional()?;
+        let Some((promo, expired)) = row else {
+            return Ok(RedeemOutcome::NotFound);
+        };
+        if expired {
+            return Ok(RedeemOutcome::Expired);
+        }
+        if promo.max_uses.is_some_and(|max| promo.used_count >= max) {
+            return Ok(RedeemOutcome::Exhausted);
+        }
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO promo_redemptions (code, user_id) VALUES (?1, ?2)",
+            rusqlite::params![code, user_id],
+        )?;
+        if inserted == 0 {
+            return Ok(RedeemOutcome::AlreadyRedeemed);
+        }
+        conn.execute(
+            "UPDATE promo_codes SET used_count = used_count + 1 WHERE code = ?1",
+            rusqlite::params![code],
+        )?;
+        Ok(RedeemOutcome::Redeemed(PromoCode {
+            used_count: promo.used_count + 1,
+            ..promo
+        }))
+    })();
+    if matches!(result, Ok(RedeemOutcome::Redeemed(_))) {
+        conn.execute_batch("COMMIT")?;
+    } else {
+        let _ = conn.execute_batch("ROLLBACK");
+    }
+    result
+}
+
+/// Best unconsumed discount the user has redeemed: `(code, percent)`.
+pub fn get_pending_discount(conn: &Connection, user_id: i64) -> Result<Option<(String, i32)>> {
+    let row = conn
+        .query_row(
+            "SELECT p.code, p.percent FROM promo_redemptions r
+             JOIN promo_codes p ON p.code = r.code
+             WHERE r.user_id = ?1 AND r.consumed_at IS NULL AND p.kind = 'discount' AND p.percent IS NOT NULL
+             ORDER BY p.percent DESC LIMIT 1",
+            rusqlite::params![user_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()?;
+    Ok(row)
+}
+
+/// Mark a discount redemption as used by a paid invoice. No-op if already consumed.
+pub fn consume_discount(conn: &Connection, user_id: i64, code: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE promo_redemptions SET consumed_at = CURRENT_TIMESTAMP
+         WHERE user_id = ?1 AND code = ?2 AND consumed_at IS NULL",
+        rusqlite::params![user_id, code],
+    )?;
+    Ok(())
+}
+
+/// Remember who invited `invitee_id`. First referrer wins; self-referrals are
+/// ignored. Returns `true` when a new row was written.
+pub fn record_referral(conn: &Connection, invitee_id: i64, referrer_id: i64) -> Result<bool> {
+    if invitee_id == referrer_id {
+        return Ok(false);
+    }
+    let n = conn.execute(
+        "INSERT OR IGNORE INTO referrals (invitee_id, referrer_id) VALUES (?1, ?2)",
+        rusqlite::params![invitee_id, referrer_id],
+    )?;
+    Ok(n > 0)
+}
+
+/// Flip the invitee's referral to rewarded. Returns the referrer exactly once
+/// — on the first call after the referral was recorded — and `None` after.
+pub fn claim_referral_reward(conn: &Connection, invitee_id: i64) -> Result<Option<i64>> {
+    let referrer = conn
+        .query_row(
+            "UPDATE referrals SET rewarded_at = CURRENT_TIMESTAMP
+             WHERE invitee_id = ?1 AND rewarded_at IS NULL
+             RETURNING referrer_id",
+            rusqlite::params![invitee_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    Ok(referrer)
+}
+
+/// `(invited, rewarded)` counts for a referrer.
+pub fn count_referrals(conn: &Connection, referrer_id: i64) -> Result<(i64, i64)> {
+    let counts = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(CASE WHEN rewarded_at IS NOT NULL THEN 1 ELSE 0 END), 0)
+         FROM referrals WHERE referrer_id = ?1",
+        rusqlite::params![referrer_id],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )?;
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{create_pool, get_connection};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static C: AtomicU64 = AtomicU64::new(0);
+
+    fn pool() -> crate::storage::db::DbPool {
+        let n = C.fetch_add(1, Ordering::SeqCst);
+        let p = std::env::temp_dir().join(format!("promo_{}_{}.db", std::process::id(), n));
+        let _ = fs_err::remove_file(&p);
+        create_pool(p.to_string_lossy().as_ref()).unwrap()
+    }
+
+    fn credits_code(code: &str, max_uses: Option<i32>, expires_at: Option<&str>) -> NewPromoCode {
+        NewPromoCode {
+            code: code.to_string(),
+            kind: PromoKind::Credits,
+            plan: None,
+            days: None,
+            percent: None,
+            credits: Some(10),
+            max_uses,
+            expires_at: expires_at.map(str::to_string),
+            source: PROMO_SOURCE_ADMIN,
+            created_by: 1,
+        }
+    }
+
+    #[test]
+    fn normalize_rejects_junk() {
+        assert_eq!(normalize_promo_code(" spring-24 ").as_deref(), Some("SPRING-24"));
+        assert_eq!(normalize_promo_code("ab"), None);
+        assert_eq!(normalize_promo_code("no spaces"), None);
+    }
+
+    #[test]
+    fn redeem_enforces_limits() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        assert!(create_promo_code(&conn, &credits_code("ONCE", Some(1), None)).unwrap());
+        assert!(!create_promo_code(&conn, &credits_code("ONCE", Some(1), None)).unwrap());
+
+        assert!(matches!(
+            redeem_promo_code(&conn, "ONCE", 10).unwrap(),
+            RedeemOutcome::Redeemed(p) if p.used_count == 1
+        ));
+        assert_eq!(redeem_promo_code(&conn, "ONCE", 10).unwrap(), RedeemOutcome::Exhausted);
+        assert_eq!(redeem_promo_code(&conn, "NOPE", 10).unwrap(), RedeemOutcome::NotFound);
+
+        create_promo_code(&conn, &credits_code("MANY", None, None)).unwrap();
+        assert!(matches!(
+            redeem_promo_code(&conn, "MANY", 10).unwrap(),
+            RedeemOutcome::Redeemed(_)
+        ));
+        assert_eq!(
+            redeem_promo_code(&conn, "MANY", 10).unwrap(),
+            RedeemOutcome::AlreadyRedeemed
+        );
+
+        create_promo_code(&conn, &credits_code("OLD", None, Some("2000-01-01 00:00:00"))).unwrap();
+        assert_eq!(redeem_promo_code(&conn, "OLD", 10).unwrap(), RedeemOutcome::Expired);
+
+        set_promo_code_active(&conn, "MANY", false).unwrap();
+        assert_eq!(redeem_promo_code(&conn, "MANY", 11).unwrap(), RedeemOutcome::NotFound);
+    }
+
+    #[test]
+    fn discount_is_pending_until_consumed() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        let mut code = credits_code("HALF", None, None);
+        code.kind = PromoKind::Discount;
+        code.credits = None;
+        code.percent = Some(50);
+        create_promo_code(&conn, &code).unwrap();
+        redeem_promo_code(&conn, "HALF", 5).unwrap();
+
+        assert_eq!(get_pending_discount(&conn, 5).unwrap(), Some(("HALF".to_string(), 50)));
+        consume_discount(&conn, 5, "HALF").unwrap();
+        assert_eq!(get_pending_discount(&conn, 5).unwrap(), None);
+    }
+
+    #[test]
+    fn referral_reward_claimed_once() {
+        let pool = pool();
+        let conn = get_connection(&pool).unwrap();
+        assert!(!record_referral(&conn, 3, 3).unwrap());
+        assert!(record_referral(&conn, 3, 1).unwrap());
+        assert!(!record_referral(&conn, 3, 2).unwrap());
+
+        assert_eq!(claim_referral_reward(&conn, 3).unwrap(), Some(1));
+        assert_eq!(claim_referral_reward(&conn, 3).unwrap(), None);
+        assert_eq!(claim_referral_reward(&conn, 4).unwrap(), None);
+        assert_eq!(count_referrals(&conn, 1).unwrap(), (1, 1));
+    }
+}
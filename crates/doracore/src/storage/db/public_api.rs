@@ -0,0 +1,284 @@
+//! SQLite operations on the V57 public API tables: `api_keys`, `api_jobs`
+//! and `api_requests`.
+//!
+//! See migrations/V57__public_api.sql for column commentary. The shared
+//! `SharedStorage` wrapper lives at `storage/shared/public_api.rs` and
+//! dispatches to either this module or the Postgres branch.
+
+use anyhow::Result;
+use rusqlite::OptionalExtension;
+
+use super::DbConnection;
+
+/// An active (not revoked) API key. The key itself is never stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKey {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    /// First characters of the raw key, for listings.
+    pub key_prefix: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+/// A download submitted through the API, joined with its task_queue row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiJob {
+    pub task_id: String,
+    pub key_id: i64,
+    pub user_id: i64,
+    pub url: String,
+    pub format: String,
+    /// `hosted_files.id` of the published result.
+    pub hosted_file_id: Option<String>,
+    /// Unix time the result link stops working.
+    pub result_expires_at: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: String,
+    /// `task_queue.status`; `None` once the task row has been cleaned up.
+    pub task_status: Option<String>,
+    pub task_error: Option<String>,
+}
+
+const API_KEY_COLUMNS: &str = "id, user_id, name, key_prefix, created_at, last_used_at";
+
+fn api_key_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ApiKey> {
+    Ok(ApiKey {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        name: row.get(2)?,
+        key_prefix: row.get(3)?,
+        created_at: row.get(4)?,
+        last_used_at: row.get(5)?,
+    })
+}
+
+/// Store a new key for `user_id`. Returns its id.
+pub fn create_api_key(conn: &DbConnection, user_id: i64, name: &str, key_hash: &[u8], key_prefix: &str) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO api_keys (user_id, name, key_hash, key_prefix) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![user_id, name, key_hash, key_prefix],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// The user's active keys, oldest first.
+pub fn list_api_keys(conn: &DbConnection, user_id: i64) -> Result<Vec<ApiKey>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM api_keys WHERE user_id = ?1 AND revoked_at IS NULL ORDER BY id",
+        API_KEY_COLUMNS
+    ))?;
+    let keys = stmt
+        .query_map(rusqlite::params![user_id], api_key_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(keys)
+}
+
+/// Revoke one of the user's keys. Returns false if it wasn't theirs or was
+/// already revoked.
+pub fn revoke_api_key(conn: &DbConnection, user_id: i64, key_id: i64) -> Result<bool> {
+    let changed = conn.execute(
+        "UPDATE api_keys SET revoked_at = datetime('now')
+         WHERE id = ?1 AND user_id = ?2 AND revoked_at IS NULL",
+        rusqlite::params![key_id, user_id],
+    )?;
+    Ok(changed > 0)
+}
+
+/// Resolve an active key by the SHA-256 of the raw key.
+pub fn get_api_key_by_hash(conn: &DbConnection, key_hash: &[u8]) -> Result<Option<ApiKey>> {
+    let key = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM api_keys WHERE key_hash = ?1 AND revoked_at IS NULL",
+                API_KEY_COLUMNS
+            ),
+            rusqlite::params![key_hash],
+            api_key_from_row,
+        )
+        .optional()?;
+    Ok(key)
+}
+
+/// Log one API call and bump the key's `last_used_at`.
+#[allow(clippy::too_many_arguments)]
+pub fn record_api_request(
+    conn: &DbConnection,
+    key_id: i64,
+    user_id: i64,
+    method: &str,
+    endpoint: &str,
+    status: u16,
+    duration_ms: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO api_requests (key_id, user_id, method, endpoint, status, duration_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![key_id, user_id, method, endpoint, status, duration_ms],
+    )?;
+    conn.execute(
+        "UPDATE api_keys SET last_used_at = datetime('now') WHERE id = ?1",
+        rusqlite::params![key_id],
+    )?;
+    Ok(())
+}
+
+/// Drop request log rows older than `days`. Returns the number removed.
+pub fn cleanup_old_api_requests(conn: &DbConnection, days: i64) -> Result<usize> {
+    Ok(conn.execute(
+        "DELETE FROM api_requests WHERE created_at < datetime('now', '-' || ?1 || ' days')",
+        rusqlite::params![days],
+    )?)
+}
+
+/// Link a freshly enqueued task to the key that submitted it.
+#[allow(clippy::too_many_arguments)]
+pub fn create_api_job(
+    conn: &DbConnection,
+    task_id: &str,
+    key_id: i64,
+    user_id: i64,
+    url: &str,
+    format: &str,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO api_jobs (task_id, key_id, user_id, url, format) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![task_id, key_id, user_id, url, format],
+    )?;
+    Ok(())
+}
+
+/// One of the user's API jobs, with the live task_queue status.
+pub fn get_api_job(conn: &DbConnection, user_id: i64, task_id: &str) -> Result<Option<ApiJob>> {
+    let job = conn
+        .query_row(
+            "SELECT j.task_id, j.key_id, j.user_id, j.url, j.format, j.hosted_file_id, j.result_expires_at,
+                    j.error, j.created_at, t.status, t.error_message
+             FROM api_jobs j LEFT JOIN task_queue t ON t.id = j.task_id
+             WHERE j.task_id = ?1 AND j.user_id = ?2",
+            rusqlite::params![task_id, user_id],
+            |row| {
+                Ok(ApiJob {
+                    task_id: row.get(0)?,
+                    key_id: row.get(1)?,
+                    user_id: row.get(2)?,
+                    url: row.get(3)?,
+                    format: row.get(4)?,
+                    hosted_file_id: row.get(5)?,
+                    result_expires_at: row.get(6)?,
+                    error: row.get(7)?,
+                    created_at: row.get(8)?,
+                    task_status: row.get(9)?,
+                    task_error: row.get(10)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(job)
+}
+
+/// Whether `task_id` was submitted through the API. Used by the worker to
+/// pick hosted-link delivery.
+pub fn is_api_job(conn: &DbConnection, task_id: &str) -> Result<bool> {
+    let found = conn
+        .query_row(
+            "SELECT 1 FROM api_jobs WHERE task_id = ?1",
+            rusqlite::params![task_id],
+            |_| Ok(()),
+        )
+        .optional()?;
+    Ok(found.is_some())
+}
+
+/// Record the published result of a job.
+pub fn set_api_job_result(conn: &DbConnection, task_id: &str, hosted_file_id: &str, expires_at: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE api_jobs SET hosted_file_id = ?2, result_expires_at = ?3, error = NULL, finished_at = datetime('now')
+         WHERE task_id = ?1",
+        rusqlite::params![task_id, hosted_file_id, expires_at],
+    )?;
+    Ok(())
+}
+
+/// Record why a job failed.
+pub fn set_api_job_error(conn: &DbConnection, task_id: &str, error: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE api_jobs SET error = ?2, finished_at = datetime('now') WHERE task_id = ?1",
+        rusqlite::params![task_id, error],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{create_pool, get_connection};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn setup_pool() -> crate::storage::db::DbPool {
+        let counter = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir().join(format!("public_api_test_{}_{}.db", std::process::id(), counter));
+        let _ = fs_err::remove_file(&path);
+        create_pool(path.to_string_lossy().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn keys_resolve_until_revoked() {
+        let pool = setup_pool();
+        let conn = get_connection(&pool).unwrap();
+        let id = create_api_key(&conn, 42, "ci", b"hash-1", "dora_abc").unwrap();
+        create_api_key(&conn, 42, "laptop", b"hash-2", "dora_def").unwrap();
+
+        let key = get_api_key_by_hash(&conn, b"hash-1").unwrap().unwrap();
+        assert_eq!((key.id, key.user_id, key.name.as_str()), (id, 42, "ci"));
+        assert_eq!(list_api_keys(&conn, 42).unwrap().len(), 2);
+
+        // Someone else's key can't be revoked
+        assert!(!revoke_api_key(&conn, 7, id).unwrap());
+        assert!(revoke_api_key(&conn, 42, id).unwrap());
+        assert!(!revoke_api_key(&conn, 42, id).unwrap());
+        assert_eq!(get_api_key_by_hash(&conn, b"hash-1").unwrap(), None);
+        assert_eq!(list_api_keys(&conn, 42).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn requests_touch_last_used() {
+        let pool = setup_pool();
+        let conn = get_connection(&pool).unwrap();
+        let id = create_api_key(&conn, 42, "ci", b"hash", "dora_abc").unwrap();
+        assert_eq!(list_api_keys(&conn, 42).unwrap()[0].last_used_at, None);
+
+        record_api_request(&conn, id, 42, "GET", "/api/v1/history", 200, 12).unwrap();
+        assert!(list_api_keys(&conn, 42).unwrap()[0].last_used_at.is_some());
+        assert_eq!(cleanup_old_api_requests(&conn, 30).unwrap(), 0);
+    }
+
+    #[test]
+    fn jobs_are_scoped_to_their_owner() {
+        let pool = setup_pool();
+        let conn = get_connection(&pool).unwrap();
+        create_api_job(&conn, "task-1", 1, 42, "https://example.com/v", "mp4").unwrap();
+
+        assert!(is_api_job(&conn, "task-1").unwrap());
+        assert!(!is_api_job(&conn, "task-2").unwrap());
+        assert_eq!(get_api_job(&conn, 7, "task-1").unwrap(), None);
+
+        let job = get_api_job(&conn, 42, "task-1").unwrap().unwrap();
+        assert_eq!(job.task_status, None);
+        assert_eq!(job.hosted_file_id, None);
+
+        set_api_job_result(&conn, "task-1", "abc", 2_000_000_000).unwrap();
+        let job = get_api_job(&conn, 42, "task-1").unwrap().unwrap();
+        assert_eq!(job.hosted_file_id.as_deref(), Some("abc"));
+        assert_eq!(job.result_expires_at, Some(2_000_000_000));
+
+        set_api_job_error(&conn, "task-1", "boom").unwrap();
+        assert_eq!(
+            get_api_job(&conn, 42, "task-1").unwrap().unwrap().error.as_deref(),
+            Some("boom")
+        );
+    }
+}
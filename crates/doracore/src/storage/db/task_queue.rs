@@ -43,6 +43,10 @@ pub struct TaskQueueEntry {
 pub enum EnqueueResult {
     Enqueued,
     Duplicate,
+    /// The task's credit charge exceeded the user's balance; nothing was written.
+    InsufficientCredits {
+        balance: i64,
+    },
 }
 
 fn map_task_queue_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<TaskQueueEntry> {
@@ -238,6 +238,228 @@ fn ensure_tables(conn: &Connection) {
             updated_at   TEXT NOT NULL
         )",
     );
+
+    // V51: credits ledger — Star-pack balances plus the append-only ledger of
+    // purchases, debits and refunds. Mirrored in migrations/V51__credits_ledger.sql.
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS credit_balances (
+            user_id    INTEGER PRIMARY KEY,
+            balance    INTEGER NOT NULL DEFAULT 0,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+    );
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS credit_ledger (
+            id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id            INTEGER NOT NULL,
+            delta              INTEGER NOT NULL,
+            balance_after      INTEGER NOT NULL,
+            kind               TEXT    NOT NULL,
+            action             TEXT,
+            task_id            TEXT,
+            telegram_charge_id TEXT,
+            stars_amount       INTEGER,
+            note               TEXT,
+            created_at         TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+    );
+    let _ = conn
+        .execute_batch("CREATE INDEX IF NOT EXISTS idx_credit_ledger_user ON credit_ledger(user_id, created_at DESC)");
+    let _ = conn.execute_batch(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_ledger_task_kind
+            ON credit_ledger(task_id, kind) WHERE task_id IS NOT NULL",
+    );
+    let _ = conn.execute_batch(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_ledger_charge_kind
+            ON credit_ledger(telegram_charge_id, kind) WHERE telegram_charge_id IS NOT NULL",
+    );
+
+    // V52: promo codes, gift subscriptions and referrals.
+    // Mirrored in migrations/V52__promo_codes.sql.
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS promo_codes (
+            code       TEXT PRIMARY KEY,
+            kind       TEXT    NOT NULL,
+            plan       TEXT,
+            days       INTEGER,
+            percent    INTEGER,
+            credits    INTEGER,
+            max_uses   INTEGER,
+            used_count INTEGER NOT NULL DEFAULT 0,
+            expires_at TIMESTAMP,
+            source     TEXT    NOT NULL DEFAULT 'admin',
+            created_by INTEGER NOT NULL,
+            is_active  INTEGER NOT NULL DEFAULT 1,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+    );
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS promo_redemptions (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            code        TEXT    NOT NULL,
+            user_id     INTEGER NOT NULL,
+            consumed_at TIMESTAMP,
+            redeemed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(code, user_id)
+        )",
+    );
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_promo_redemptions_user ON promo_redemptions(user_id)");
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS referrals (
+            invitee_id  INTEGER PRIMARY KEY,
+            referrer_id INTEGER NOT NULL,
+            created_at  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            rewarded_at TIMESTAMP
+        )",
+    );
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_referrals_referrer ON referrals(referrer_id)");
+
+    // V53: feed_episodes — podcast/RSS episode metadata keyed by enclosure URL.
+    // Mirrored in migrations/V53__feed_episodes.sql.
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS feed_episodes (
+            enclosure_url TEXT PRIMARY KEY,
+            feed_url      TEXT NOT NULL,
+            title         TEXT NOT NULL,
+            podcast       TEXT NOT NULL,
+            cover_url     TEXT,
+            duration_secs INTEGER,
+            chapters_json TEXT,
+            chapters_url  TEXT,
+            created_at    TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+    );
+
+    // V54: content subscription auto-download + filters.
+    // Mirrored in migrations/V54__content_auto_download.sql.
+    let content_sub_alters = [
+        "ALTER TABLE content_subscriptions ADD COLUMN auto_download INTEGER NOT NULL DEFAULT 0",
+        "ALTER TABLE content_subscriptions ADD COLUMN auto_format TEXT NOT NULL DEFAULT 'mp3'",
+        "ALTER TABLE content_subscriptions ADD COLUMN auto_quality TEXT DEFAULT NULL",
+        "ALTER TABLE content_subscriptions ADD COLUMN filters TEXT DEFAULT NULL",
+        "ALTER TABLE content_subscriptions ADD COLUMN daily_cap INTEGER NOT NULL DEFAULT 5",
+        "ALTER TABLE content_subscriptions ADD COLUMN auto_day TEXT DEFAULT NULL",
+        "ALTER TABLE content_subscriptions ADD COLUMN auto_count INTEGER NOT NULL DEFAULT 0",
+    ];
+    for sql in &content_sub_alters {
+        let _ = conn.execute_batch(sql); // ignore "duplicate column" errors
+    }
+
+    // V55: portal_sessions — user portal (/me) logins.
+    // Mirrored in migrations/V55__portal_sessions.sql.
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS portal_sessions (
+            token_hash BLOB PRIMARY KEY,
+            user_id    INTEGER NOT NULL,
+            csrf_token TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            expires_at TEXT NOT NULL,
+            last_seen  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            user_agent TEXT,
+            ip         TEXT
+        )",
+    );
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_portal_sessions_user ON portal_sessions(user_id)");
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_portal_sessions_expires ON portal_sessions(expires_at)");
+
+    // V56: hosted_files — signed web links for files over the upload caps.
+    // Mirrored in migrations/V56__hosted_files.sql.
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS hosted_files (
+            id               TEXT PRIMARY KEY,
+            user_id          INTEGER NOT NULL,
+            file_path        TEXT NOT NULL,
+            file_name        TEXT NOT NULL,
+            content_type     TEXT NOT NULL,
+            file_size        INTEGER NOT NULL,
+            download_count   INTEGER NOT NULL DEFAULT 0,
+            created_at       TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            expires_at       TEXT NOT NULL,
+            last_download_at TEXT
+        )",
+    );
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_hosted_files_user ON hosted_files(user_id)");
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_hosted_files_expires ON hosted_files(expires_at)");
+
+    // V57: public REST API — keys, jobs and the request log.
+    // Mirrored in migrations/V57__public_api.sql.
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS api_keys (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id      INTEGER NOT NULL,
+            name         TEXT NOT NULL,
+            key_hash     BLOB NOT NULL UNIQUE,
+            key_prefix   TEXT NOT NULL,
+            created_at   TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            last_used_at TEXT,
+            revoked_at   TEXT
+        )",
+    );
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_api_keys_user ON api_keys(user_id)");
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS api_jobs (
+            task_id           TEXT PRIMARY KEY,
+            key_id            INTEGER NOT NULL,
+            user_id           INTEGER NOT NULL,
+            url               TEXT NOT NULL,
+            format            TEXT NOT NULL,
+            hosted_file_id    TEXT,
+            result_expires_at INTEGER,
+            error             TEXT,
+            created_at        TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            finished_at       TEXT
+        )",
+    );
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_api_jobs_user ON api_jobs(user_id, created_at)");
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS api_requests (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            key_id      INTEGER NOT NULL,
+            user_id     INTEGER NOT NULL,
+            method      TEXT NOT NULL,
+            endpoint    TEXT NOT NULL,
+            status      INTEGER NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            created_at  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+    );
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_api_requests_created ON api_requests(created_at)");
+    let _ = conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_api_requests_key ON api_requests(key_id, created_at)");
+
+    // V58: admin roles for staff outside ADMIN_IDS.
+    // Mirrored in migrations/V58__admin_roles.sql.
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS admin_roles (
+            user_id    INTEGER PRIMARY KEY,
+            role       TEXT NOT NULL,
+            granted_by INTEGER NOT NULL,
+            granted_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+    );
+
+    // V59: per-session CSRF tokens and shared login rate limits.
+    // Mirrored in migrations/V59__admin_auth_state.sql.
+    let _ = conn.execute_batch("ALTER TABLE admin_sessions ADD COLUMN csrf_token TEXT"); // ignore "duplicate column"
+    let _ = conn
+        .execute_batch("UPDATE admin_sessions SET csrf_token = lower(hex(randomblob(32))) WHERE csrf_token IS NULL");
+    let _ = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS web_rate_limits (
+            bucket       TEXT PRIMARY KEY,
+            hits         INTEGER NOT NULL,
+            window_start INTEGER NOT NULL
+        )",
+    );
+}
+
+/// Highest migration version embedded in this binary. Restores refuse
+/// backups taken by a newer schema than this.
+pub fn latest_schema_version() -> i32 {
+    embedded::migrations::runner()
+        .get_migrations()
+        .iter()
+        .map(|m| m.version())
+        .max()
+        .unwrap_or(0)
 }
 
 /// Run migrations for tests without the outer transaction wrapper
@@ -6,6 +6,7 @@ pub mod db;
 pub mod migrations;
 pub mod shared;
 pub mod subtitle_cache;
+pub mod transfer;
 pub mod uploads;
 
 // Re-exports for convenience
@@ -0,0 +1,121 @@
+//! `SharedStorage` dispatch for the V58 `admin_roles` table. SQLite branch
+//! delegates to `storage/db/admin_roles.rs`; Postgres is inline, so a grant or
+//! revoke made on one instance reaches every instance behind a load balancer.
+
+use anyhow::{Context, Result};
+use sqlx::Row;
+
+use crate::storage::db::{self, AdminRoleGrant};
+
+use super::SharedStorage;
+
+impl SharedStorage {
+    /// All granted roles, oldest first.
+    pub async fn list_admin_roles(&self) -> Result<Vec<AdminRoleGrant>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite list_admin_roles connection")?;
+                db::list_admin_roles(&conn).context("sqlite list_admin_roles")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let rows = sqlx::query(
+                    "SELECT user_id, role, granted_by,
+                            to_char(granted_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS granted_at
+                     FROM admin_roles ORDER BY granted_at, user_id",
+                )
+                .fetch_all(pg_pool)
+                .await
+                .context("postgres list_admin_roles")?;
+                Ok(rows
+                    .iter()
+                    .map(|row| AdminRoleGrant {
+                        user_id: row.get("user_id"),
+                        role: row.get("role"),
+                        granted_by: row.get("granted_by"),
+                        granted_at: row.get("granted_at"),
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Grant `role` to `user_id`, replacing any previous role.
+    pub async fn set_admin_role(&self, user_id: i64, role: &str, granted_by: i64) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite set_admin_role connection")?;
+                db::set_admin_role(&conn, user_id, role, granted_by).context("sqlite set_admin_role")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "INSERT INTO admin_roles (user_id, role, granted_by) VALUES ($1, $2, $3)
+                     ON CONFLICT (user_id) DO UPDATE
+                     SET role = EXCLUDED.role, granted_by = EXCLUDED.granted_by, granted_at = NOW()",
+                )
+                .bind(user_id)
+                .bind(role)
+                .bind(granted_by)
+                .execute(pg_pool)
+                .await
+                .context("postgres set_admin_role")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Revoke the role of `user_id`. Returns whether there was one.
+    pub async fn remove_admin_role(&self, user_id: i64) -> Result<bool> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite remove_admin_role connection")?;
+                db::remove_admin_role(&conn, user_id).context("sqlite remove_admin_role")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let result = sqlx::query("DELETE FROM admin_roles WHERE user_id = $1")
+                    .bind(user_id)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres remove_admin_role")?;
+                Ok(result.rows_affected() > 0)
+            }
+        }
+    }
+
+    /// Move roles granted while they lived in this instance's SQLite file to
+    /// Postgres. A role already set in Postgres wins. Returns the number of
+    /// rows moved; a no-op on the SQLite backend.
+    pub async fn import_sqlite_admin_roles(&self) -> Result<u64> {
+        let Self::Postgres { sqlite_pool, pg_pool } = self else {
+            return Ok(0);
+        };
+        let grants = {
+            let conn = db::get_connection(sqlite_pool).context("sqlite import_admin_roles connection")?;
+            db::list_admin_roles(&conn).context("sqlite import_admin_roles")?
+        };
+        if grants.is_empty() {
+            return Ok(0);
+        }
+
+        let mut moved = 0;
+        for grant in &grants {
+            let result = sqlx::query(
+                "INSERT INTO admin_roles (user_id, role, granted_by, granted_at)
+                 VALUES ($1, $2, $3, $4::timestamp AT TIME ZONE 'UTC')
+                 ON CONFLICT (user_id) DO NOTHING",
+            )
+            .bind(grant.user_id)
+            .bind(&grant.role)
+            .bind(grant.granted_by)
+            .bind(&grant.granted_at)
+            .execute(pg_pool)
+            .await
+            .context("postgres import_admin_roles")?;
+            moved += result.rows_affected();
+        }
+
+        let conn = db::get_connection(sqlite_pool).context("sqlite import_admin_roles connection")?;
+        conn.execute("DELETE FROM admin_roles", [])
+            .context("sqlite clear imported admin_roles")?;
+        Ok(moved)
+    }
+}
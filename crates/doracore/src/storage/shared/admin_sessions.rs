@@ -0,0 +1,302 @@
+//! `SharedStorage` dispatch for admin dashboard auth state: the
+//! `admin_sessions` table and the `web_rate_limits` login counters. SQLite
+//! branch delegates to `storage/db/admin_sessions.rs`; Postgres is inline, so
+//! admin logins work on every instance behind a load balancer.
+
+use anyhow::{Context, Result};
+use sqlx::Row;
+
+use crate::storage::db::{self, AdminSession, AdminSessionInfo};
+
+use super::SharedStorage;
+
+const PG_SESSION_COLUMNS: &str = "token_hash, admin_id,
+    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS created_at,
+    to_char(last_seen AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS last_seen,
+    to_char(expires_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS expires_at,
+    user_agent, ip";
+
+fn map_pg_session(row: &sqlx::postgres::PgRow) -> AdminSessionInfo {
+    AdminSessionInfo {
+        token_hash: row.get("token_hash"),
+        admin_id: row.get("admin_id"),
+        created_at: row.get("created_at"),
+        last_seen: row.get("last_seen"),
+        expires_at: row.get("expires_at"),
+        user_agent: row.get("user_agent"),
+        ip: row.get("ip"),
+    }
+}
+
+impl SharedStorage {
+    /// Persist a new admin session valid for `ttl_hours`. Only the SHA-256 of
+    /// the cookie token is stored.
+    pub async fn create_admin_session(
+        &self,
+        token_hash: &[u8],
+        admin_id: i64,
+        csrf_token: &str,
+        ttl_hours: i64,
+        user_agent: Option<&str>,
+        ip: Option<&str>,
+    ) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite create_admin_session connection")?;
+                db::create_admin_session(&conn, token_hash, admin_id, csrf_token, ttl_hours, user_agent, ip)
+                    .context("sqlite create_admin_session")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "INSERT INTO admin_sessions (token_hash, admin_id, csrf_token, expires_at, user_agent, ip)
+                     VALUES ($1, $2, $3, NOW() + make_interval(hours => $4), $5, $6)",
+                )
+                .bind(token_hash)
+                .bind(admin_id)
+                .bind(csrf_token)
+                .bind(ttl_hours as i32)
+                .bind(user_agent)
+                .bind(ip)
+                .execute(pg_pool)
+                .await
+                .context("postgres create_admin_session")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Resolve an unexpired session by token hash, bumping `last_seen`.
+    pub async fn get_admin_session(&self, token_hash: &[u8]) -> Result<Option<AdminSession>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_admin_session connection")?;
+                db::get_admin_session(&conn, token_hash).context("sqlite get_admin_session")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(
+                    "UPDATE admin_sessions SET last_seen = NOW()
+                     WHERE token_hash = $1 AND expires_at > NOW()
+                     RETURNING admin_id, csrf_token",
+                )
+                .bind(token_hash)
+                .fetch_optional(pg_pool)
+                .await
+                .context("postgres get_admin_session")?;
+                Ok(row.map(|r| AdminSession {
+                    admin_id: r.get("admin_id"),
+                    csrf_token: r.get("csrf_token"),
+                }))
+            }
+        }
+    }
+
+    /// All unexpired admin sessions, most recently active first.
+    pub async fn list_admin_sessions(&self) -> Result<Vec<AdminSessionInfo>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite list_admin_sessions connection")?;
+                db::list_admin_sessions(&conn).context("sqlite list_admin_sessions")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let rows = sqlx::query(&format!(
+                    "SELECT {} FROM admin_sessions WHERE expires_at > NOW() ORDER BY last_seen DESC",
+                    PG_SESSION_COLUMNS
+                ))
+                .fetch_all(pg_pool)
+                .await
+                .context("postgres list_admin_sessions")?;
+                Ok(rows.iter().map(map_pg_session).collect())
+            }
+        }
+    }
+
+    /// Revoke one session (logout / force-logout). Returns whether it existed.
+    pub async fn delete_admin_session(&self, token_hash: &[u8]) -> Result<bool> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite delete_admin_session connection")?;
+                db::delete_admin_session(&conn, token_hash).context("sqlite delete_admin_session")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let result = sqlx::query("DELETE FROM admin_sessions WHERE token_hash = $1")
+                    .bind(token_hash)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres delete_admin_session")?;
+                Ok(result.rows_affected() > 0)
+            }
+        }
+    }
+
+    /// Revoke every session of `admin_id`. Returns the number removed.
+    pub async fn delete_admin_sessions_for(&self, admin_id: i64) -> Result<u64> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite delete_admin_sessions_for connection")?;
+                db::delete_admin_sessions_for(&conn, admin_id)
+                    .map(|n| n as u64)
+                    .context("sqlite delete_admin_sessions_for")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let result = sqlx::query("DELETE FROM admin_sessions WHERE admin_id = $1")
+                    .bind(admin_id)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres delete_admin_sessions_for")?;
+                Ok(result.rows_affected())
+            }
+        }
+    }
+
+    /// Drop expired sessions. Returns the number of rows removed.
+    pub async fn purge_expired_admin_sessions(&self) -> Result<u64> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite purge_expired_admin_sessions connection")?;
+                db::purge_expired_admin_sessions(&conn)
+                    .map(|n| n as u64)
+                    .context("sqlite purge_expired_admin_sessions")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let result = sqlx::query("DELETE FROM admin_sessions WHERE expires_at <= NOW()")
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres purge_expired_admin_sessions")?;
+                Ok(result.rows_affected())
+            }
+        }
+    }
+
+    /// Postgres only: move admin sessions still in the local SQLite file (from
+    /// before sessions were shared) into Postgres, so nobody is logged out by
+    /// the switch. Rows are deleted from SQLite once copied. Returns the
+    /// number moved.
+    pub async fn import_sqlite_admin_sessions(&self) -> Result<u64> {
+        let Self::Postgres { sqlite_pool, pg_pool } = self else {
+            return Ok(0);
+        };
+        let sessions = {
+            let conn = db::get_connection(sqlite_pool).context("sqlite import_admin_sessions connection")?;
+            let mut stmt = conn.prepare(
+                "SELECT token_hash, admin_id, csrf_token, created_at, expires_at, user_agent, ip
+                 FROM admin_sessions WHERE expires_at > datetime('now') AND csrf_token IS NOT NULL",
+            )?;
+            stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, Vec<u8>>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("sqlite import_admin_sessions")?
+        };
+        if sessions.is_empty() {
+            return Ok(0);
+        }
+
+        let mut moved = 0;
+        for (token_hash, admin_id, csrf_token, created_at, expires_at, user_agent, ip) in &sessions {
+            let result = sqlx::query(
+                "INSERT INTO admin_sessions (token_hash, admin_id, csrf_token, created_at, expires_at, user_agent, ip)
+                 VALUES ($1, $2, $3, $4::timestamp AT TIME ZONE 'UTC', $5::timestamp AT TIME ZONE 'UTC', $6, $7)
+                 ON CONFLICT (token_hash) DO NOTHING",
+            )
+            .bind(token_hash)
+            .bind(admin_id)
+            .bind(csrf_token)
+            .bind(created_at)
+            .bind(expires_at)
+            .bind(user_agent)
+            .bind(ip)
+            .execute(pg_pool)
+            .await
+            .context("postgres import_admin_sessions")?;
+            moved += result.rows_affected();
+        }
+
+        let conn = db::get_connection(sqlite_pool).context("sqlite import_admin_sessions connection")?;
+        conn.execute("DELETE FROM admin_sessions", [])
+            .context("sqlite clear imported admin_sessions")?;
+        Ok(moved)
+    }
+
+    /// Count a login attempt (or any other hit) against `bucket` in a fixed
+    /// window of `window_secs`. Returns the hits in the current window,
+    /// including this one.
+    pub async fn hit_rate_limit(&self, bucket: &str, window_secs: i64) -> Result<u32> {
+        let now = chrono::Utc::now().timestamp();
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite hit_rate_limit connection")?;
+                db::hit_rate_limit(&conn, bucket, window_secs, now).context("sqlite hit_rate_limit")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let hits: i32 = sqlx::query_scalar(
+                    "INSERT INTO web_rate_limits (bucket, hits, window_start) VALUES ($1, 1, $2)
+                     ON CONFLICT (bucket) DO UPDATE SET
+                         hits = CASE WHEN web_rate_limits.window_start <= $2 - $3 THEN 1
+                                     ELSE web_rate_limits.hits + 1 END,
+                         window_start = CASE WHEN web_rate_limits.window_start <= $2 - $3 THEN $2
+                                             ELSE web_rate_limits.window_start END
+                     RETURNING hits",
+                )
+                .bind(bucket)
+                .bind(now)
+                .bind(window_secs)
+                .fetch_one(pg_pool)
+                .await
+                .context("postgres hit_rate_limit")?;
+                Ok(u32::try_from(hits).unwrap_or(u32::MAX))
+            }
+        }
+    }
+
+    /// Hits already counted against `bucket` in its current window of
+    /// `window_secs`, without adding one.
+    pub async fn rate_limit_hits(&self, bucket: &str, window_secs: i64) -> Result<u32> {
+        let now = chrono::Utc::now().timestamp();
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite rate_limit_hits connection")?;
+                db::rate_limit_hits(&conn, bucket, window_secs, now).context("sqlite rate_limit_hits")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let hits: Option<i32> =
+                    sqlx::query_scalar("SELECT hits FROM web_rate_limits WHERE bucket = $1 AND window_start > $2 - $3")
+                        .bind(bucket)
+                        .bind(now)
+                        .bind(window_secs)
+                        .fetch_optional(pg_pool)
+                        .await
+                        .context("postgres rate_limit_hits")?;
+                Ok(hits.map_or(0, |h| u32::try_from(h).unwrap_or(u32::MAX)))
+            }
+        }
+    }
+
+    /// Drop rate-limit buckets idle for longer than `max_age_secs`.
+    pub async fn purge_rate_limits(&self, max_age_secs: i64) -> Result<u64> {
+        let cutoff = chrono::Utc::now().timestamp() - max_age_secs;
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite purge_rate_limits connection")?;
+                db::purge_rate_limits(&conn, cutoff)
+                    .map(|n| n as u64)
+                    .context("sqlite purge_rate_limits")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let result = sqlx::query("DELETE FROM web_rate_limits WHERE window_start < $1")
+                    .bind(cutoff)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres purge_rate_limits")?;
+                Ok(result.rows_affected())
+            }
+        }
+    }
+}
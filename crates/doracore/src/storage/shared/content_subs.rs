@@ -6,7 +6,7 @@ use sqlx::Row;
 use crate::storage::db::{self, DbConnection};
 
 use super::SharedStorage;
-use super::types::{ContentSourceGroup, ContentSubscriptionRecord};
+use super::types::{ContentFilters, ContentSourceGroup, ContentSubscriptionRecord};
 
 impl SharedStorage {
     pub async fn get_user_content_subscriptions(&self, user_id: i64) -> Result<Vec<ContentSubscriptionRecord>> {
@@ -22,7 +22,8 @@ impl SharedStorage {
                     "SELECT id, user_id, source_type, source_id, display_name, watch_mask, is_active,
                             last_seen_state, source_meta, CAST(last_checked_at AS TEXT) AS last_checked_at,
                             last_error, consecutive_errors, CAST(created_at AS TEXT) AS created_at,
-                            CAST(updated_at AS TEXT) AS updated_at
+                            CAST(updated_at AS TEXT) AS updated_at, auto_download, auto_format,
+                            auto_quality, filters, daily_cap
                      FROM content_subscriptions
                      WHERE user_id = $1 AND is_active = 1
                      ORDER BY created_at ASC",
@@ -72,7 +73,8 @@ impl SharedStorage {
                     "SELECT id, user_id, source_type, source_id, display_name, watch_mask, is_active,
                             last_seen_state, source_meta, CAST(last_checked_at AS TEXT) AS last_checked_at,
                             last_error, consecutive_errors, CAST(created_at AS TEXT) AS created_at,
-                            CAST(updated_at AS TEXT) AS updated_at
+                            CAST(updated_at AS TEXT) AS updated_at, auto_download, auto_format,
+                            auto_quality, filters, daily_cap
                      FROM content_subscriptions
                      WHERE id = $1",
                 )
@@ -103,7 +105,8 @@ impl SharedStorage {
                     "SELECT id, user_id, source_type, source_id, display_name, watch_mask, is_active,
                             last_seen_state, source_meta, CAST(last_checked_at AS TEXT) AS last_checked_at,
                             last_error, consecutive_errors, CAST(created_at AS TEXT) AS created_at,
-                            CAST(updated_at AS TEXT) AS updated_at
+                            CAST(updated_at AS TEXT) AS updated_at, auto_download, auto_format,
+                            auto_quality, filters, daily_cap
                      FROM content_subscriptions
                      WHERE user_id = $1 AND source_type = $2 AND source_id = $3",
                 )
@@ -244,6 +247,126 @@ impl SharedStorage {
         }
     }
 
+    /// V54: auto-download switch, format, quality and daily cap.
+    pub async fn update_content_auto_download(
+        &self,
+        id: i64,
+        enabled: bool,
+        format: &str,
+        quality: Option<&str>,
+        daily_cap: u32,
+    ) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite update_content_auto_download connection")?;
+                sqlite_update_content_auto_download(&conn, id, enabled, format, quality, daily_cap)
+                    .map_err(anyhow::Error::msg)
+                    .context("sqlite update_content_auto_download")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "UPDATE content_subscriptions
+                     SET auto_download = $1, auto_format = $2, auto_quality = $3, daily_cap = $4, updated_at = NOW()
+                     WHERE id = $5",
+                )
+                .bind(i32::from(enabled))
+                .bind(format)
+                .bind(quality)
+                .bind(daily_cap as i32)
+                .bind(id)
+                .execute(pg_pool)
+                .await
+                .context("postgres update_content_auto_download")?;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn update_content_filters(&self, id: i64, filters: &ContentFilters) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite update_content_filters connection")?;
+                sqlite_update_content_filters(&conn, id, filters)
+                    .map_err(anyhow::Error::msg)
+                    .context("sqlite update_content_filters")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "UPDATE content_subscriptions
+                     SET filters = $1, updated_at = NOW()
+                     WHERE id = $2",
+                )
+                .bind(filters_json(filters))
+                .bind(id)
+                .execute(pg_pool)
+                .await
+                .context("postgres update_content_filters")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Take one slot of today's (UTC) auto-download allowance. Returns `false`
+    /// once `daily_cap` items were queued today; the counter resets on the
+    /// first reservation of a new day.
+    pub async fn try_reserve_content_auto_download(&self, id: i64) -> Result<bool> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn =
+                    db::get_connection(db_pool).context("sqlite try_reserve_content_auto_download connection")?;
+                sqlite_try_reserve_content_auto_download(&conn, id)
+                    .map_err(anyhow::Error::msg)
+                    .context("sqlite try_reserve_content_auto_download")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let result = sqlx::query(
+                    "UPDATE content_subscriptions
+                     SET auto_count = CASE
+                             WHEN auto_day = to_char(NOW() AT TIME ZONE 'UTC', 'YYYY-MM-DD') THEN auto_count + 1
+                             ELSE 1
+                         END,
+                         auto_day = to_char(NOW() AT TIME ZONE 'UTC', 'YYYY-MM-DD')
+                     WHERE id = $1
+                       AND (auto_day IS DISTINCT FROM to_char(NOW() AT TIME ZONE 'UTC', 'YYYY-MM-DD')
+                            OR auto_count < daily_cap)",
+                )
+                .bind(id)
+                .execute(pg_pool)
+                .await
+                .context("postgres try_reserve_content_auto_download")?;
+                Ok(result.rows_affected() == 1)
+            }
+        }
+    }
+
+    /// Give back a slot taken by [`Self::try_reserve_content_auto_download`]
+    /// when the item was not queued after all. A reservation from a previous
+    /// day is left alone.
+    pub async fn release_content_auto_download(&self, id: i64) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite release_content_auto_download connection")?;
+                sqlite_release_content_auto_download(&conn, id)
+                    .map_err(anyhow::Error::msg)
+                    .context("sqlite release_content_auto_download")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "UPDATE content_subscriptions
+                     SET auto_count = auto_count - 1
+                     WHERE id = $1
+                       AND auto_day = to_char(NOW() AT TIME ZONE 'UTC', 'YYYY-MM-DD')
+                       AND auto_count > 0",
+                )
+                .bind(id)
+                .execute(pg_pool)
+                .await
+                .context("postgres release_content_auto_download")?;
+                Ok(())
+            }
+        }
+    }
+
     pub async fn get_active_content_source_groups(&self) -> Result<Vec<ContentSourceGroup>> {
         match self {
             Self::Sqlite { db_pool } => {
@@ -257,7 +380,8 @@ impl SharedStorage {
                     "SELECT id, user_id, source_type, source_id, display_name, watch_mask, is_active,
                             last_seen_state, source_meta, CAST(last_checked_at AS TEXT) AS last_checked_at,
                             last_error, consecutive_errors, CAST(created_at AS TEXT) AS created_at,
-                            CAST(updated_at AS TEXT) AS updated_at
+                            CAST(updated_at AS TEXT) AS updated_at, auto_download, auto_format,
+                            auto_quality, filters, daily_cap
                      FROM content_subscriptions
                      WHERE is_active = 1
                      ORDER BY last_checked_at ASC NULLS FIRST, source_type, source_id",
@@ -385,6 +509,16 @@ fn parse_json_value(value: Option<String>) -> Option<JsonValue> {
     value.and_then(|raw| serde_json::from_str(&raw).ok())
 }
 
+fn parse_filters(value: Option<String>) -> ContentFilters {
+    value
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn filters_json(filters: &ContentFilters) -> Option<String> {
+    (!filters.is_empty()).then(|| serde_json::to_string(filters).unwrap_or_default())
+}
+
 fn map_pg_content_subscription(row: sqlx::postgres::PgRow) -> Result<ContentSubscriptionRecord> {
     Ok(ContentSubscriptionRecord {
         id: row.get("id"),
@@ -401,6 +535,11 @@ fn map_pg_content_subscription(row: sqlx::postgres::PgRow) -> Result<ContentSubs
         consecutive_errors: row.get::<i32, _>("consecutive_errors") as u32,
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
+        auto_download: row.get::<i32, _>("auto_download") != 0,
+        auto_format: row.get("auto_format"),
+        auto_quality: row.get("auto_quality"),
+        filters: parse_filters(row.get("filters")),
+        daily_cap: row.get::<i32, _>("daily_cap") as u32,
     })
 }
 
@@ -444,6 +583,11 @@ fn sqlite_parse_content_subscription_row(row: &rusqlite::Row<'_>) -> rusqlite::R
         consecutive_errors: row.get::<_, u32>(11)?,
         created_at: row.get(12)?,
         updated_at: row.get(13)?,
+        auto_download: row.get::<_, i32>(14)? != 0,
+        auto_format: row.get(15)?,
+        auto_quality: row.get(16)?,
+        filters: parse_filters(row.get(17)?),
+        daily_cap: row.get::<_, u32>(18)?,
     })
 }
 
@@ -484,7 +628,7 @@ fn sqlite_get_content_subscription(
     conn.query_row(
         "SELECT id, user_id, source_type, source_id, display_name, watch_mask, is_active,
                 last_seen_state, source_meta, last_checked_at, last_error, consecutive_errors,
-                created_at, updated_at
+                created_at, updated_at, auto_download, auto_format, auto_quality, filters, daily_cap
          FROM content_subscriptions WHERE id = ?1",
         rusqlite::params![id],
         sqlite_parse_content_subscription_row,
@@ -499,7 +643,7 @@ fn sqlite_get_user_content_subscriptions(
     let mut stmt = conn.prepare(
         "SELECT id, user_id, source_type, source_id, display_name, watch_mask, is_active,
                 last_seen_state, source_meta, last_checked_at, last_error, consecutive_errors,
-                created_at, updated_at
+                created_at, updated_at, auto_download, auto_format, auto_quality, filters, daily_cap
          FROM content_subscriptions
          WHERE user_id = ?1 AND is_active = 1
          ORDER BY created_at ASC",
@@ -525,7 +669,7 @@ fn sqlite_has_content_subscription(
     conn.query_row(
         "SELECT id, user_id, source_type, source_id, display_name, watch_mask, is_active,
                 last_seen_state, source_meta, last_checked_at, last_error, consecutive_errors,
-                created_at, updated_at
+                created_at, updated_at, auto_download, auto_format, auto_quality, filters, daily_cap
          FROM content_subscriptions
          WHERE user_id = ?1 AND source_type = ?2 AND source_id = ?3",
         rusqlite::params![user_id, source_type, source_id],
@@ -558,11 +702,57 @@ fn sqlite_update_content_watch_mask(conn: &DbConnection, id: i64, new_mask: u32)
     Ok(())
 }
 
+fn sqlite_update_content_auto_download(
+    conn: &DbConnection,
+    id: i64,
+    enabled: bool,
+    format: &str,
+    quality: Option<&str>,
+    daily_cap: u32,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE content_subscriptions
+         SET auto_download = ?1, auto_format = ?2, auto_quality = ?3, daily_cap = ?4, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?5",
+        rusqlite::params![enabled as i32, format, quality, daily_cap, id],
+    )?;
+    Ok(())
+}
+
+fn sqlite_update_content_filters(conn: &DbConnection, id: i64, filters: &ContentFilters) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE content_subscriptions SET filters = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+        rusqlite::params![filters_json(filters), id],
+    )?;
+    Ok(())
+}
+
+fn sqlite_try_reserve_content_auto_download(conn: &DbConnection, id: i64) -> rusqlite::Result<bool> {
+    let changed = conn.execute(
+        "UPDATE content_subscriptions
+         SET auto_count = CASE WHEN auto_day = date('now') THEN auto_count + 1 ELSE 1 END,
+             auto_day = date('now')
+         WHERE id = ?1 AND (auto_day IS NOT date('now') OR auto_count < daily_cap)",
+        rusqlite::params![id],
+    )?;
+    Ok(changed == 1)
+}
+
+fn sqlite_release_content_auto_download(conn: &DbConnection, id: i64) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE content_subscriptions
+         SET auto_count = auto_count - 1
+         WHERE id = ?1 AND auto_day = date('now') AND auto_count > 0",
+        rusqlite::params![id],
+    )?;
+    Ok(())
+}
+
 fn sqlite_get_active_content_source_groups(conn: &DbConnection) -> rusqlite::Result<Vec<ContentSourceGroup>> {
     let mut stmt = conn.prepare(
         "SELECT id, user_id, source_type, source_id, display_name, watch_mask, is_active,
                 last_seen_state, source_meta, last_checked_at, last_error, consecutive_errors,
-                created_at, updated_at
+                created_at, updated_at, auto_download, auto_format, auto_quality, filters, daily_cap
          FROM content_subscriptions
          WHERE is_active = 1
          ORDER BY last_checked_at ASC NULLS FIRST, source_type, source_id",
@@ -635,3 +825,60 @@ fn sqlite_auto_disable_errored_content(
         rusqlite::params![source_type, source_id, max_errors],
     )? as u32)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{create_pool, get_connection};
+
+    fn setup_conn() -> DbConnection {
+        let path = std::env::temp_dir().join(format!("content_subs_test_{}.db", std::process::id()));
+        let _ = fs_err::remove_file(&path);
+        let pool = create_pool(path.to_string_lossy().as_ref()).unwrap();
+        get_connection(&pool).unwrap()
+    }
+
+    #[test]
+    fn auto_download_settings_round_trip_and_daily_cap() {
+        let conn = setup_conn();
+        db::create_user(&conn, 42, None).unwrap();
+        let id = sqlite_upsert_content_subscription(&conn, 42, "youtube", "@chan", "Chan", 1, None).unwrap();
+
+        let sub = sqlite_get_content_subscription(&conn, id).unwrap().unwrap();
+        assert!(!sub.auto_download);
+        assert_eq!(sub.auto_format, "mp3");
+        assert!(sub.filters.is_empty());
+
+        let filters = ContentFilters {
+            include: vec!["live".to_string()],
+            max_duration_secs: Some(3600),
+            ..Default::default()
+        };
+        sqlite_update_content_filters(&conn, id, &filters).unwrap();
+        sqlite_update_content_auto_download(&conn, id, true, "mp4", Some("720p"), 2).unwrap();
+
+        let sub = sqlite_get_content_subscription(&conn, id).unwrap().unwrap();
+        assert!(sub.auto_download);
+        assert_eq!(sub.auto_format, "mp4");
+        assert_eq!(sub.auto_quality.as_deref(), Some("720p"));
+        assert_eq!(sub.daily_cap, 2);
+        assert_eq!(sub.filters, filters);
+
+        assert!(sqlite_try_reserve_content_auto_download(&conn, id).unwrap());
+        assert!(sqlite_try_reserve_content_auto_download(&conn, id).unwrap());
+        assert!(!sqlite_try_reserve_content_auto_download(&conn, id).unwrap());
+
+        // A released slot can be taken again
+        sqlite_release_content_auto_download(&conn, id).unwrap();
+        assert!(sqlite_try_reserve_content_auto_download(&conn, id).unwrap());
+        assert!(!sqlite_try_reserve_content_auto_download(&conn, id).unwrap());
+
+        // A new day resets the allowance
+        conn.execute(
+            "UPDATE content_subscriptions SET auto_day = '2000-01-01' WHERE id = ?1",
+            [id],
+        )
+        .unwrap();
+        assert!(sqlite_try_reserve_content_auto_download(&conn, id).unwrap());
+    }
+}
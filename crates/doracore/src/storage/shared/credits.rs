@@ -0,0 +1,469 @@
+//! `SharedStorage` dispatch for the V51 credits ledger. SQLite branch
+//! delegates to `storage/db/credits.rs`; Postgres is inline and serializes
+//! per-user balance changes with `SELECT … FOR UPDATE` on `credit_balances`.
+
+use anyhow::{Context, Result};
+use sqlx::{PgConnection, Row};
+
+use crate::storage::db::{self, CreditCharge, CreditStats, CreditTransaction, DebitOutcome};
+
+use super::SharedStorage;
+
+/// Lock (creating if needed) the user's balance row and return the balance.
+async fn pg_lock_balance(conn: &mut PgConnection, user_id: i64) -> Result<i64> {
+    sqlx::query("INSERT INTO credit_balances (user_id, balance) VALUES ($1, 0) ON CONFLICT (user_id) DO NOTHING")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await
+        .context("postgres credit_balances ensure row")?;
+    let balance: i64 = sqlx::query_scalar("SELECT balance FROM credit_balances WHERE user_id = $1 FOR UPDATE")
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+        .context("postgres credit_balances lock")?;
+    Ok(balance)
+}
+
+/// Add `delta` to a locked balance row and return the new balance.
+async fn pg_bump_balance(conn: &mut PgConnection, user_id: i64, delta: i64) -> Result<i64> {
+    let balance: i64 = sqlx::query_scalar(
+        "UPDATE credit_balances SET balance = balance + $2, updated_at = NOW()
+         WHERE user_id = $1 RETURNING balance",
+    )
+    .bind(user_id)
+    .bind(delta)
+    .fetch_one(&mut *conn)
+    .await
+    .context("postgres credit_balances update")?;
+    Ok(balance)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn pg_insert_ledger_row(
+    conn: &mut PgConnection,
+    user_id: i64,
+    delta: i64,
+    balance_after: i64,
+    kind: &str,
+    action: Option<&str>,
+    task_id: Option<&str>,
+    telegram_charge_id: Option<&str>,
+    stars_amount: Option<i64>,
+    note: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO credit_ledger
+            (user_id, delta, balance_after, kind, action, task_id, telegram_charge_id, stars_amount, note)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+    )
+    .bind(user_id)
+    .bind(delta)
+    .bind(balance_after)
+    .bind(kind)
+    .bind(action)
+    .bind(task_id)
+    .bind(telegram_charge_id)
+    .bind(stars_amount)
+    .bind(note)
+    .execute(&mut *conn)
+    .await
+    .context("postgres credit_ledger insert")?;
+    Ok(())
+}
+
+/// Postgres twin of [`db::apply_credit_debit`]. Runs on the caller's
+/// transaction so the task insert in `save_task_to_queue` shares it.
+pub(super) async fn pg_apply_credit_debit(
+    conn: &mut PgConnection,
+    user_id: i64,
+    task_id: &str,
+    charge: &CreditCharge,
+) -> Result<DebitOutcome> {
+    let action = charge.action.as_str();
+    let balance = pg_lock_balance(conn, user_id).await?;
+    if charge.free_per_day > 0 {
+        let used_today: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM credit_ledger l
+             WHERE l.user_id = $1 AND l.kind = 'allowance' AND l.action = $2
+               AND l.created_at >= date_trunc('day', NOW())
+               AND NOT EXISTS (
+                   SELECT 1 FROM credit_ledger r WHERE r.task_id = l.task_id AND r.kind = 'refund'
+               )",
+        )
+        .bind(user_id)
+        .bind(action)
+        .fetch_one(&mut *conn)
+        .await
+        .context("postgres credit allowance count")?;
+        if used_today < i64::from(charge.free_per_day) {
+            pg_insert_ledger_row(
+                conn,
+                user_id,
+                0,
+                balance,
+                "allowance",
+                Some(action),
+                Some(task_id),
+                None,
+                None,
+                None,
+            )
+            .await?;
+            return Ok(DebitOutcome::Allowance);
+        }
+    }
+
+    if balance < charge.cost {
+        return Ok(DebitOutcome::Insufficient { balance });
+    }
+    let balance_after = pg_bump_balance(conn, user_id, -charge.cost).await?;
+    pg_insert_ledger_row(
+        conn,
+        user_id,
+        -charge.cost,
+        balance_after,
+        "debit",
+        Some(action),
+        Some(task_id),
+        None,
+        None,
+        None,
+    )
+    .await?;
+    Ok(DebitOutcome::Charged { balance_after })
+}
+
+impl SharedStorage {
+    /// Current credit balance (0 for users that never bought credits).
+    pub async fn get_credit_balance(&self, user_id: i64) -> Result<i64> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_credit_balance connection")?;
+                db::get_credit_balance(&conn, user_id).context("sqlite get_credit_balance")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let balance: Option<i64> = sqlx::query_scalar("SELECT balance FROM credit_balances WHERE user_id = $1")
+                    .bind(user_id)
+                    .fetch_optional(pg_pool)
+                    .await
+                    .context("postgres get_credit_balance")?;
+                Ok(balance.unwrap_or(0))
+            }
+        }
+    }
+
+    /// Debit credits for work that isn't a `task_queue` row (stories render).
+    /// Queue tasks carry their charge in `QueueTaskInput::credit_charge` instead.
+    pub async fn debit_credits(&self, user_id: i64, task_id: &str, charge: &CreditCharge) -> Result<DebitOutcome> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite debit_credits connection")?;
+                db::debit_credits(&conn, user_id, task_id, charge).context("sqlite debit_credits")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let mut tx = pg_pool.begin().await.context("begin pg debit_credits")?;
+                let outcome = pg_apply_credit_debit(&mut tx, user_id, task_id, charge).await?;
+                if matches!(outcome, DebitOutcome::Insufficient { .. }) {
+                    tx.rollback().await.context("rollback pg debit_credits")?;
+                } else {
+                    tx.commit().await.context("commit pg debit_credits")?;
+                }
+                Ok(outcome)
+            }
+        }
+    }
+
+    /// Refund whatever was debited for `task_id`. Idempotent — `None` when the
+    /// task was never charged or is already refunded.
+    pub async fn refund_task_credits(&self, task_id: &str, reason: &str) -> Result<Option<i64>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite refund_task_credits connection")?;
+                db::refund_task_credits(&conn, task_id, reason).context("sqlite refund_task_credits")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let mut tx = pg_pool.begin().await.context("begin pg refund_task_credits")?;
+                let charged = sqlx::query(
+                    "SELECT user_id, delta, action FROM credit_ledger
+                     WHERE task_id = $1 AND kind IN ('debit', 'allowance')",
+                )
+                .bind(task_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .context("postgres refund_task_credits lookup")?;
+                let Some(row) = charged else {
+                    return Ok(None);
+                };
+                let user_id: i64 = row.get("user_id");
+                let delta: i64 = row.get("delta");
+                let action: Option<String> = row.get("action");
+                // Lock first so two concurrent refunds serialize on the balance row.
+                pg_lock_balance(&mut tx, user_id).await?;
+                let already: bool = sqlx::query_scalar(
+                    "SELECT EXISTS(SELECT 1 FROM credit_ledger WHERE task_id = $1 AND kind = 'refund')",
+                )
+                .bind(task_id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("postgres refund_task_credits check")?;
+                if already {
+                    return Ok(None);
+                }
+                let amount = -delta;
+                let balance_after = pg_bump_balance(&mut tx, user_id, amount).await?;
+                pg_insert_ledger_row(
+                    &mut tx,
+                    user_id,
+                    amount,
+                    balance_after,
+                    "refund",
+                    action.as_deref(),
+                    Some(task_id),
+                    None,
+                    None,
+                    Some(reason),
+                )
+                .await?;
+                tx.commit().await.context("commit pg refund_task_credits")?;
+                Ok(Some(amount))
+            }
+        }
+    }
+
+    /// Credit a Star-pack purchase. `None` if the charge was already credited.
+    pub async fn add_purchased_credits(
+        &self,
+        user_id: i64,
+        credits: i64,
+        stars_amount: i64,
+        telegram_charge_id: &str,
+    ) -> Result<Option<i64>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite add_purchased_credits connection")?;
+                db::add_purchased_credits(&conn, user_id, credits, stars_amount, telegram_charge_id)
+                    .context("sqlite add_purchased_credits")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let mut tx = pg_pool.begin().await.context("begin pg add_purchased_credits")?;
+                pg_lock_balance(&mut tx, user_id).await?;
+                let already: bool = sqlx::query_scalar(
+                    "SELECT EXISTS(SELECT 1 FROM credit_ledger WHERE telegram_charge_id = $1 AND kind = 'purchase')",
+                )
+                .bind(telegram_charge_id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("postgres add_purchased_credits check")?;
+                if already {
+                    return Ok(None);
+                }
+                let balance_after = pg_bump_balance(&mut tx, user_id, credits).await?;
+                pg_insert_ledger_row(
+                    &mut tx,
+                    user_id,
+                    credits,
+                    balance_after,
+                    "purchase",
+                    None,
+                    None,
+                    Some(telegram_charge_id),
+                    Some(stars_amount),
+                    None,
+                )
+                .await?;
+                tx.commit().await.context("commit pg add_purchased_credits")?;
+                Ok(Some(balance_after))
+            }
+        }
+    }
+
+    /// Give free credits once per `grant_key` (promo code, referral reward).
+    /// `None` if that grant was already applied.
+    pub async fn grant_credits(
+        &self,
+        user_id: i64,
+        credits: i64,
+        grant_key: &str,
+        note: Option<&str>,
+    ) -> Result<Option<i64>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite grant_credits connection")?;
+                db::grant_credits(&conn, user_id, credits, grant_key, note).context("sqlite grant_credits")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let mut tx = pg_pool.begin().await.context("begin pg grant_credits")?;
+                pg_lock_balance(&mut tx, user_id).await?;
+                let already: bool = sqlx::query_scalar(
+                    "SELECT EXISTS(SELECT 1 FROM credit_ledger WHERE task_id = $1 AND kind = 'grant')",
+                )
+                .bind(grant_key)
+                .fetch_one(&mut *tx)
+                .await
+                .context("postgres grant_credits check")?;
+                if already {
+                    return Ok(None);
+                }
+                let balance_after = pg_bump_balance(&mut tx, user_id, credits).await?;
+                pg_insert_ledger_row(
+                    &mut tx,
+                    user_id,
+                    credits,
+                    balance_after,
+                    "grant",
+                    None,
+                    Some(grant_key),
+                    None,
+                    None,
+                    note,
+                )
+                .await?;
+                tx.commit().await.context("commit pg grant_credits")?;
+                Ok(Some(balance_after))
+            }
+        }
+    }
+
+    /// Take back the credits of a refunded Star-pack purchase (never below 0).
+    pub async fn revoke_purchased_credits(&self, telegram_charge_id: &str) -> Result<Option<i64>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite revoke_purchased_credits connection")?;
+                db::revoke_purchased_credits(&conn, telegram_charge_id).context("sqlite revoke_purchased_credits")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let mut tx = pg_pool.begin().await.context("begin pg revoke_purchased_credits")?;
+                let purchase = sqlx::query(
+                    "SELECT user_id, delta FROM credit_ledger WHERE telegram_charge_id = $1 AND kind = 'purchase'",
+                )
+                .bind(telegram_charge_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .context("postgres revoke_purchased_credits lookup")?;
+                let Some(row) = purchase else {
+                    return Ok(None);
+                };
+                let user_id: i64 = row.get("user_id");
+                let credits: i64 = row.get("delta");
+                let balance = pg_lock_balance(&mut tx, user_id).await?;
+                let already: bool = sqlx::query_scalar(
+                    "SELECT EXISTS(SELECT 1 FROM credit_ledger WHERE telegram_charge_id = $1 AND kind = 'revoke')",
+                )
+                .bind(telegram_charge_id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("postgres revoke_purchased_credits check")?;
+                if already {
+                    return Ok(None);
+                }
+                let revoked = credits.min(balance).max(0);
+                let balance_after = pg_bump_balance(&mut tx, user_id, -revoked).await?;
+                pg_insert_ledger_row(
+                    &mut tx,
+                    user_id,
+                    -revoked,
+                    balance_after,
+                    "revoke",
+                    None,
+                    None,
+                    Some(telegram_charge_id),
+                    None,
+                    None,
+                )
+                .await?;
+                tx.commit().await.context("commit pg revoke_purchased_credits")?;
+                Ok(Some(revoked))
+            }
+        }
+    }
+
+    /// Most recent ledger rows for a user, newest first.
+    pub async fn list_credit_transactions(&self, user_id: i64, limit: i64) -> Result<Vec<CreditTransaction>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite list_credit_transactions connection")?;
+                db::list_credit_transactions(&conn, user_id, limit).context("sqlite list_credit_transactions")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let rows = sqlx::query(
+                    "SELECT id, delta, balance_after, kind, action, task_id, stars_amount, note,
+                            to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS created_at
+                     FROM credit_ledger WHERE user_id = $1
+                     ORDER BY id DESC LIMIT $2",
+                )
+                .bind(user_id)
+                .bind(limit)
+                .fetch_all(pg_pool)
+                .await
+                .context("postgres list_credit_transactions")?;
+                Ok(rows
+                    .into_iter()
+                    .map(|r| CreditTransaction {
+                        id: r.get("id"),
+                        delta: r.get("delta"),
+                        balance_after: r.get("balance_after"),
+                        kind: r.get("kind"),
+                        action: r.get("action"),
+                        task_id: r.get("task_id"),
+                        stars_amount: r.get("stars_amount"),
+                        note: r.get("note"),
+                        created_at: r.get("created_at"),
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Aggregate credit sales and burn across all users.
+    pub async fn get_credit_stats(&self) -> Result<CreditStats> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_credit_stats connection")?;
+                db::get_credit_stats(&conn).context("sqlite get_credit_stats")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(
+                    "SELECT
+                        COALESCE(SUM(CASE WHEN kind = 'purchase' THEN 1 ELSE 0 END), 0)::BIGINT AS purchases,
+                        COALESCE(SUM(CASE WHEN kind = 'purchase' THEN stars_amount ELSE 0 END), 0)::BIGINT AS stars,
+                        COALESCE(SUM(CASE WHEN kind = 'purchase' THEN delta ELSE 0 END), 0)::BIGINT AS sold,
+                        COALESCE(SUM(CASE WHEN kind = 'debit' THEN -delta ELSE 0 END), 0)::BIGINT AS debited,
+                        COALESCE(SUM(CASE WHEN kind = 'refund' THEN delta ELSE 0 END), 0)::BIGINT AS refunded,
+                        COALESCE(SUM(CASE WHEN kind = 'revoke' THEN -delta ELSE 0 END), 0)::BIGINT AS revoked
+                     FROM credit_ledger",
+                )
+                .fetch_one(pg_pool)
+                .await
+                .context("postgres get_credit_stats")?;
+                let outstanding: i64 =
+                    sqlx::query_scalar("SELECT COALESCE(SUM(balance), 0)::BIGINT FROM credit_balances")
+                        .fetch_one(pg_pool)
+                        .await
+                        .context("postgres get_credit_stats outstanding")?;
+                let burn_by_action = sqlx::query(
+                    "SELECT action, COALESCE(SUM(-delta), 0)::BIGINT AS burned FROM credit_ledger
+                     WHERE kind IN ('debit', 'refund') AND action IS NOT NULL
+                     GROUP BY action ORDER BY 2 DESC",
+                )
+                .fetch_all(pg_pool)
+                .await
+                .context("postgres get_credit_stats by action")?
+                .into_iter()
+                .map(|r| (r.get("action"), r.get("burned")))
+                .collect();
+                let debited: i64 = row.get("debited");
+                let refunded: i64 = row.get("refunded");
+                Ok(CreditStats {
+                    purchases: row.get("purchases"),
+                    stars_total: row.get("stars"),
+                    credits_sold: row.get("sold"),
+                    credits_burned: debited - refunded,
+                    credits_refunded: refunded,
+                    credits_revoked: row.get("revoked"),
+                    outstanding,
+                    burn_by_action,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! `SharedStorage` dispatch for the V53 `feed_episodes` table. SQLite branch
+//! delegates to `storage/db/feed_episodes.rs`; Postgres is inline.
+
+use anyhow::{Context, Result};
+use sqlx::Row;
+
+use crate::storage::db::{self, FeedEpisode};
+
+use super::SharedStorage;
+
+impl SharedStorage {
+    /// Podcast metadata stored for an enclosure URL, if the RSS watcher saw it.
+    pub async fn get_feed_episode(&self, enclosure_url: &str) -> Result<Option<FeedEpisode>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_feed_episode connection")?;
+                db::get_feed_episode(&conn, enclosure_url).context("sqlite get_feed_episode")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(
+                    "SELECT enclosure_url, feed_url, title, podcast, cover_url, duration_secs, chapters_json, chapters_url
+                     FROM feed_episodes WHERE enclosure_url = $1",
+                )
+                .bind(enclosure_url)
+                .fetch_optional(pg_pool)
+                .await
+                .context("postgres get_feed_episode")?;
+                Ok(row.map(|r| FeedEpisode {
+                    enclosure_url: r.get("enclosure_url"),
+                    feed_url: r.get("feed_url"),
+                    title: r.get("title"),
+                    podcast: r.get("podcast"),
+                    cover_url: r.get("cover_url"),
+                    duration_secs: r.get("duration_secs"),
+                    chapters: FeedEpisode::parse_chapters(r.get("chapters_json")),
+                    chapters_url: r.get("chapters_url"),
+                }))
+            }
+        }
+    }
+
+    /// Insert or refresh the metadata for an enclosure URL.
+    pub async fn upsert_feed_episode(&self, episode: &FeedEpisode) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite upsert_feed_episode connection")?;
+                db::upsert_feed_episode(&conn, episode).context("sqlite upsert_feed_episode")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "INSERT INTO feed_episodes
+                        (enclosure_url, feed_url, title, podcast, cover_url, duration_secs, chapters_json, chapters_url)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                     ON CONFLICT(enclosure_url) DO UPDATE SET
+                        feed_url = excluded.feed_url,
+                        title = excluded.title,
+                        podcast = excluded.podcast,
+                        cover_url = excluded.cover_url,
+                        duration_secs = excluded.duration_secs,
+                        chapters_json = excluded.chapters_json,
+                        chapters_url = excluded.chapters_url",
+                )
+                .bind(&episode.enclosure_url)
+                .bind(&episode.feed_url)
+                .bind(&episode.title)
+                .bind(&episode.podcast)
+                .bind(&episode.cover_url)
+                .bind(episode.duration_secs)
+                .bind(episode.chapters_json())
+                .bind(&episode.chapters_url)
+                .execute(pg_pool)
+                .await
+                .context("postgres upsert_feed_episode")?;
+                Ok(())
+            }
+        }
+    }
+}
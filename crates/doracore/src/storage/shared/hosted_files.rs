@@ -0,0 +1,143 @@
+//! `SharedStorage` dispatch for the V56 `hosted_files` table. SQLite branch
+//! delegates to `storage/db/hosted_files.rs`; Postgres is inline.
+
+use anyhow::{Context, Result};
+use sqlx::Row;
+
+use crate::storage::db::{self, HostedFile};
+
+use super::SharedStorage;
+
+impl SharedStorage {
+    /// Register a hosted file that stays downloadable until `expires_at_unix`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_hosted_file(
+        &self,
+        id: &str,
+        user_id: i64,
+        file_path: &str,
+        file_name: &str,
+        content_type: &str,
+        file_size: i64,
+        expires_at_unix: i64,
+    ) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite create_hosted_file connection")?;
+                db::create_hosted_file(
+                    &conn,
+                    id,
+                    user_id,
+                    file_path,
+                    file_name,
+                    content_type,
+                    file_size,
+                    expires_at_unix,
+                )
+                .context("sqlite create_hosted_file")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "INSERT INTO hosted_files (id, user_id, file_path, file_name, content_type, file_size, expires_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, to_timestamp($7))",
+                )
+                .bind(id)
+                .bind(user_id)
+                .bind(file_path)
+                .bind(file_name)
+                .bind(content_type)
+                .bind(file_size)
+                .bind(expires_at_unix as f64)
+                .execute(pg_pool)
+                .await
+                .context("postgres create_hosted_file")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Look up an unexpired hosted file.
+    pub async fn get_hosted_file(&self, id: &str) -> Result<Option<HostedFile>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_hosted_file connection")?;
+                db::get_hosted_file(&conn, id).context("sqlite get_hosted_file")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(
+                    "SELECT id, user_id, file_path, file_name, content_type, file_size, download_count
+                     FROM hosted_files WHERE id = $1 AND expires_at > NOW()",
+                )
+                .bind(id)
+                .fetch_optional(pg_pool)
+                .await
+                .context("postgres get_hosted_file")?;
+                Ok(row.map(|r| HostedFile {
+                    id: r.get("id"),
+                    user_id: r.get("user_id"),
+                    file_path: r.get("file_path"),
+                    file_name: r.get("file_name"),
+                    content_type: r.get("content_type"),
+                    file_size: r.get("file_size"),
+                    download_count: r.get("download_count"),
+                }))
+            }
+        }
+    }
+
+    /// Count one download of a hosted file.
+    pub async fn record_hosted_download(&self, id: &str) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite record_hosted_download connection")?;
+                db::record_hosted_download(&conn, id).context("sqlite record_hosted_download")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "UPDATE hosted_files SET download_count = download_count + 1, last_download_at = NOW()
+                     WHERE id = $1",
+                )
+                .bind(id)
+                .execute(pg_pool)
+                .await
+                .context("postgres record_hosted_download")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Drop one hosted file's row once its file has been removed.
+    pub async fn delete_hosted_file(&self, id: &str) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite delete_hosted_file connection")?;
+                db::delete_hosted_file(&conn, id).context("sqlite delete_hosted_file")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query("DELETE FROM hosted_files WHERE id = $1")
+                    .bind(id)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres delete_hosted_file")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Delete expired rows and return their file paths.
+    pub async fn take_expired_hosted_files(&self) -> Result<Vec<String>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite take_expired_hosted_files connection")?;
+                db::take_expired_hosted_files(&conn).context("sqlite take_expired_hosted_files")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let rows = sqlx::query("DELETE FROM hosted_files WHERE expires_at <= NOW() RETURNING file_path")
+                    .fetch_all(pg_pool)
+                    .await
+                    .context("postgres take_expired_hosted_files")?;
+                Ok(rows.iter().map(|r| r.get("file_path")).collect())
+            }
+        }
+    }
+}
@@ -8,17 +8,26 @@ use sqlx::postgres::PgPoolOptions;
 use crate::core::config::{self, DatabaseDriver};
 use crate::storage::db::DbPool;
 
-mod pg_bootstrap;
+mod pg_migrations;
+mod schema_check;
 mod types;
 
+mod admin_roles;
+mod admin_sessions;
 mod analytics;
 mod content_subs;
+mod credits;
 pub mod download_history;
 mod errors;
+mod feed_episodes;
 mod helpers;
+mod hosted_files;
 mod lyrics_overrides;
 mod playlists;
 mod popular_files;
+mod portal_sessions;
+mod promo;
+mod public_api;
 mod search;
 mod sessions;
 mod share_pages;
@@ -32,10 +41,13 @@ mod users;
 mod vault;
 
 pub use download_history::{HistorySearch, period_cutoff};
-pub use types::{ContentSourceGroup, ContentSubscriptionRecord, PreviewContext, QueueTaskInput, SharePageRecord};
+pub use types::{
+    ContentFilters, ContentSourceGroup, ContentSubscriptionRecord, PreviewContext, QueueTaskInput, SharePageRecord,
+};
 pub use user_settings::{SubtitleFlags, VideoDownloadSettings};
 
-use pg_bootstrap::POSTGRES_BOOTSTRAP_SQL;
+pub use pg_migrations::latest_pg_schema_version;
+pub use schema_check::SchemaDrift;
 
 #[derive(Clone)]
 pub enum SharedStorage {
@@ -51,17 +63,7 @@ impl SharedStorage {
                 let database_url = config::DATABASE_URL
                     .clone()
                     .ok_or_else(|| anyhow!("DATABASE_URL must be set when DATABASE_DRIVER=postgres"))?;
-                let pg_pool = PgPoolOptions::new()
-                    .max_connections(50)
-                    .min_connections(5)
-                    .acquire_timeout(Duration::from_secs(3))
-                    .connect(&database_url)
-                    .await
-                    .context("connect postgres shared storage")?;
-                sqlx::query(POSTGRES_BOOTSTRAP_SQL)
-                    .execute(&pg_pool)
-                    .await
-                    .context("bootstrap postgres shared storage schema")?;
+                let pg_pool = connect_postgres(&database_url, 50).await?;
                 Ok(Arc::new(Self::Postgres {
                     sqlite_pool: db_pool,
                     pg_pool,
@@ -81,3 +83,18 @@ impl SharedStorage {
         matches!(self, Self::Postgres { .. })
     }
 }
+
+/// Connect to Postgres and bring the shared-storage schema up to date.
+pub async fn connect_postgres(database_url: &str, max_connections: u32) -> Result<PgPool> {
+    let pg_pool = PgPoolOptions::new()
+        .max_connections(max_connections)
+        .min_connections(max_connections.min(5))
+        .acquire_timeout(Duration::from_secs(3))
+        .connect(database_url)
+        .await
+        .context("connect postgres shared storage")?;
+    pg_migrations::run_pg_migrations(&pg_pool)
+        .await
+        .context("migrate postgres shared storage schema")?;
+    Ok(pg_pool)
+}
@@ -0,0 +1,215 @@
+//! Versioned Postgres migrations (`migrations/postgres/PG*.sql`).
+//!
+//! Versions follow the SQLite `V*` numbering so both backends move together;
+//! `PG52__baseline.sql` is the former monolithic bootstrap. Applied versions
+//! and their checksums live in `pg_schema_history`. All pending migrations run
+//! in one transaction under an advisory lock, so concurrent instances starting
+//! at the same time apply them exactly once.
+
+use anyhow::{Context, Result, bail};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+
+/// `pg_advisory_xact_lock` key serializing migration runners across instances.
+const PG_MIGRATION_LOCK: i64 = 1000;
+
+pub struct PgMigration {
+    pub version: i32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+macro_rules! pg_migration {
+    ($version:literal, $name:literal) => {
+        PgMigration {
+            version: $version,
+            name: $name,
+            sql: include_str!(concat!(
+                "../../../migrations/postgres/PG",
+                $version,
+                "__",
+                $name,
+                ".sql"
+            )),
+        }
+    };
+}
+
+/// Every Postgres migration, oldest first. Add new files here.
+pub const PG_MIGRATIONS: &[PgMigration] = &[
+    pg_migration!(52, "baseline"),
+    pg_migration!(53, "feed_episodes"),
+    pg_migration!(54, "content_auto_download"),
+    pg_migration!(55, "portal_sessions"),
+    pg_migration!(56, "hosted_files"),
+    pg_migration!(57, "public_api"),
+    pg_migration!(58, "admin_roles"),
+    pg_migration!(59, "admin_auth_state"),
+];
+
+impl PgMigration {
+    fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.sql.as_bytes()))
+    }
+
+    /// Comment-only files mark SQLite-only versions.
+    fn is_noop(&self) -> bool {
+        self.sql
+            .lines()
+            .map(str::trim)
+            .all(|line| line.is_empty() || line.starts_with("--"))
+    }
+}
+
+/// Highest Postgres migration version embedded in this binary.
+pub fn latest_pg_schema_version() -> i32 {
+    PG_MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Execute every migration's SQL inside `tx` without recording history.
+/// Used to build the expected schema in a scratch namespace.
+pub(crate) async fn apply_all(tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+    for migration in PG_MIGRATIONS.iter().filter(|m| !m.is_noop()) {
+        sqlx::query(migration.sql)
+            .execute(&mut **tx)
+            .await
+            .with_context(|| format!("apply PG{}__{}", migration.version, migration.name))?;
+    }
+    Ok(())
+}
+
+/// Apply pending migrations. Refuses to continue when an applied migration
+/// was edited afterwards or the database is ahead of this binary.
+pub async fn run_pg_migrations(pool: &PgPool) -> Result<()> {
+    let mut tx = pool.begin().await.context("begin postgres migrations")?;
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(PG_MIGRATION_LOCK)
+        .execute(&mut *tx)
+        .await
+        .context("lock postgres migrations")?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS pg_schema_history (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )",
+    )
+    .execute(&mut *tx)
+    .await
+    .context("create pg_schema_history")?;
+
+    let applied: Vec<(i32, String)> = sqlx::query("SELECT version, checksum FROM pg_schema_history ORDER BY version")
+        .fetch_all(&mut *tx)
+        .await
+        .context("read pg_schema_history")?
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect();
+
+    let latest = latest_pg_schema_version();
+    if let Some(&(newest, _)) = applied.last()
+        && newest > latest
+    {
+        bail!(
+            "Postgres schema is at PG{} but this build only knows migrations up to PG{}; refusing to start an older binary",
+            newest,
+            latest
+        );
+    }
+
+    let mut count = 0;
+    for migration in PG_MIGRATIONS {
+        if let Some((_, checksum)) = applied.iter().find(|(v, _)| *v == migration.version) {
+            if *checksum != migration.checksum() {
+                bail!(
+                    "Postgres migration PG{}__{} was modified after it was applied; add a new migration instead",
+                    migration.version,
+                    migration.name
+                );
+            }
+            continue;
+        }
+        if !migration.is_noop() {
+            sqlx::query(migration.sql)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("apply PG{}__{}", migration.version, migration.name))?;
+        }
+        sqlx::query("INSERT INTO pg_schema_history (version, name, checksum) VALUES ($1, $2, $3)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(migration.checksum())
+            .execute(&mut *tx)
+            .await
+            .context("record postgres migration")?;
+        log::info!("Applied Postgres migration PG{}__{}", migration.version, migration.name);
+        count += 1;
+    }
+
+    tx.commit().await.context("commit postgres migrations")?;
+    if count == 0 {
+        log::info!("Postgres schema up to date (PG{})", latest);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    fn versions_in(dir: &Path, prefix: &str) -> BTreeSet<i32> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .filter(|name| name.ends_with(".sql"))
+            .filter_map(|name| name.strip_prefix(prefix)?.split("__").next()?.parse().ok())
+            .collect()
+    }
+
+    #[test]
+    fn test_pg_migrations_are_ordered() {
+        let versions: Vec<i32> = PG_MIGRATIONS.iter().map(|m| m.version).collect();
+        assert!(versions.windows(2).all(|w| w[0] < w[1]), "{:?}", versions);
+        assert_eq!(latest_pg_schema_version(), *versions.last().unwrap());
+    }
+
+    #[test]
+    fn test_every_pg_file_is_registered() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations/postgres");
+        let on_disk = versions_in(&dir, "PG");
+        let registered: BTreeSet<i32> = PG_MIGRATIONS.iter().map(|m| m.version).collect();
+        assert_eq!(on_disk, registered, "add new PG*.sql files to PG_MIGRATIONS");
+    }
+
+    #[test]
+    fn test_pg_migrations_track_sqlite_versions() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+        let baseline = PG_MIGRATIONS[0].version;
+        let sqlite: BTreeSet<i32> = versions_in(&dir, "V").into_iter().filter(|v| *v > baseline).collect();
+        let pg: BTreeSet<i32> = PG_MIGRATIONS
+            .iter()
+            .map(|m| m.version)
+            .filter(|v| *v > baseline)
+            .collect();
+        assert_eq!(
+            sqlite, pg,
+            "each SQLite V* after the baseline needs a PG* counterpart (comment-only if SQLite-only)"
+        );
+        assert!(crate::storage::migrations::latest_schema_version() <= latest_pg_schema_version());
+    }
+
+    #[test]
+    fn test_noop_detection() {
+        let noop = PgMigration {
+            version: 1,
+            name: "noop",
+            sql: "-- SQLite-only: rebuilds an FTS index\n\n",
+        };
+        assert!(noop.is_noop());
+        assert!(!PG_MIGRATIONS[0].is_noop());
+    }
+}
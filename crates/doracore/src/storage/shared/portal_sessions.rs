@@ -0,0 +1,110 @@
+//! `SharedStorage` dispatch for the V55 `portal_sessions` table. SQLite branch
+//! delegates to `storage/db/portal_sessions.rs`; Postgres is inline, so user
+//! portal logins survive behind a load balancer.
+
+use anyhow::{Context, Result};
+use sqlx::Row;
+
+use crate::storage::db::{self, PortalSession};
+
+use super::SharedStorage;
+
+impl SharedStorage {
+    /// Persist a new portal session valid for `ttl_hours`. Only the SHA-256 of
+    /// the cookie token is stored.
+    pub async fn create_portal_session(
+        &self,
+        token_hash: &[u8],
+        user_id: i64,
+        csrf_token: &str,
+        ttl_hours: i64,
+        user_agent: Option<&str>,
+        ip: Option<&str>,
+    ) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite create_portal_session connection")?;
+                db::create_portal_session(&conn, token_hash, user_id, csrf_token, ttl_hours, user_agent, ip)
+                    .context("sqlite create_portal_session")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "INSERT INTO portal_sessions (token_hash, user_id, csrf_token, expires_at, user_agent, ip)
+                     VALUES ($1, $2, $3, NOW() + make_interval(hours => $4), $5, $6)",
+                )
+                .bind(token_hash)
+                .bind(user_id)
+                .bind(csrf_token)
+                .bind(ttl_hours as i32)
+                .bind(user_agent)
+                .bind(ip)
+                .execute(pg_pool)
+                .await
+                .context("postgres create_portal_session")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Resolve an unexpired session by token hash, bumping `last_seen`.
+    pub async fn get_portal_session(&self, token_hash: &[u8]) -> Result<Option<PortalSession>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_portal_session connection")?;
+                db::get_portal_session(&conn, token_hash).context("sqlite get_portal_session")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(
+                    "UPDATE portal_sessions SET last_seen = NOW()
+                     WHERE token_hash = $1 AND expires_at > NOW()
+                     RETURNING user_id, csrf_token",
+                )
+                .bind(token_hash)
+                .fetch_optional(pg_pool)
+                .await
+                .context("postgres get_portal_session")?;
+                Ok(row.map(|r| PortalSession {
+                    user_id: r.get("user_id"),
+                    csrf_token: r.get("csrf_token"),
+                }))
+            }
+        }
+    }
+
+    /// Revoke a session (logout).
+    pub async fn delete_portal_session(&self, token_hash: &[u8]) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite delete_portal_session connection")?;
+                db::delete_portal_session(&conn, token_hash).context("sqlite delete_portal_session")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query("DELETE FROM portal_sessions WHERE token_hash = $1")
+                    .bind(token_hash)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres delete_portal_session")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Drop expired sessions. Returns the number of rows removed.
+    pub async fn purge_expired_portal_sessions(&self) -> Result<u64> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite purge_expired_portal_sessions connection")?;
+                db::purge_expired_portal_sessions(&conn)
+                    .map(|n| n as u64)
+                    .context("sqlite purge_expired_portal_sessions")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let result = sqlx::query("DELETE FROM portal_sessions WHERE expires_at <= NOW()")
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres purge_expired_portal_sessions")?;
+                Ok(result.rows_affected())
+            }
+        }
+    }
+}
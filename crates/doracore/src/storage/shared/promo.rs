@@ -0,0 +1,289 @@
+//! `SharedStorage` dispatch for V52 promo codes, gift codes and referrals.
+//! SQLite branch delegates to `storage/db/promo.rs`; Postgres is inline.
+
+use anyhow::{Context, Result};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+
+use crate::storage::db::{self, NewPromoCode, PromoCode, PromoKind, RedeemOutcome};
+
+use super::SharedStorage;
+
+const PG_PROMO_COLUMNS: &str = "code, kind, plan, days, percent, credits, max_uses, used_count,
+    to_char(expires_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS expires_at, source, created_by, is_active,
+    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS created_at";
+
+fn map_pg_promo_code(r: &PgRow) -> PromoCode {
+    let kind: String = r.get("kind");
+    PromoCode {
+        code: r.get("code"),
+        kind: kind.parse().unwrap_or(PromoKind::Credits),
+        plan: r.get("plan"),
+        days: r.get("days"),
+        percent: r.get("percent"),
+        credits: r.get("credits"),
+        max_uses: r.get("max_uses"),
+        used_count: r.get("used_count"),
+        expires_at: r.get("expires_at"),
+        source: r.get("source"),
+        created_by: r.get("created_by"),
+        is_active: r.get::<i32, _>("is_active") != 0,
+        created_at: r.get::<Option<String>, _>("created_at").unwrap_or_default(),
+    }
+}
+
+impl SharedStorage {
+    /// Insert a new code. `false` if the code already exists.
+    pub async fn create_promo_code(&self, new: &NewPromoCode) -> Result<bool> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite create_promo_code connection")?;
+                db::create_promo_code(&conn, new).context("sqlite create_promo_code")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let result = sqlx::query(
+                    "INSERT INTO promo_codes
+                        (code, kind, plan, days, percent, credits, max_uses, expires_at, source, created_by)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8::text::timestamp AT TIME ZONE 'UTC', $9, $10)
+                     ON CONFLICT (code) DO NOTHING",
+                )
+                .bind(&new.code)
+                .bind(new.kind.as_str())
+                .bind(&new.plan)
+                .bind(new.days)
+                .bind(new.percent)
+                .bind(new.credits)
+                .bind(new.max_uses)
+                .bind(&new.expires_at)
+                .bind(new.source)
+                .bind(new.created_by)
+                .execute(pg_pool)
+                .await
+                .context("postgres create_promo_code")?;
+                Ok(result.rows_affected() > 0)
+            }
+        }
+    }
+
+    pub async fn get_promo_code(&self, code: &str) -> Result<Option<PromoCode>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_promo_code connection")?;
+                db::get_promo_code(&conn, code).context("sqlite get_promo_code")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(&format!("SELECT {PG_PROMO_COLUMNS} FROM promo_codes WHERE code = $1"))
+                    .bind(code)
+                    .fetch_optional(pg_pool)
+                    .await
+                    .context("postgres get_promo_code")?;
+                Ok(row.as_ref().map(map_pg_promo_code))
+            }
+        }
+    }
+
+    /// Most recently created codes first.
+    pub async fn list_promo_codes(&self, limit: i64) -> Result<Vec<PromoCode>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite list_promo_codes connection")?;
+                db::list_promo_codes(&conn, limit).context("sqlite list_promo_codes")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let rows = sqlx::query(&format!(
+                    "SELECT {PG_PROMO_COLUMNS} FROM promo_codes ORDER BY created_at DESC, code LIMIT $1"
+                ))
+                .bind(limit)
+                .fetch_all(pg_pool)
+                .await
+                .context("postgres list_promo_codes")?;
+                Ok(rows.iter().map(map_pg_promo_code).collect())
+            }
+        }
+    }
+
+    /// Enable or disable a code. `false` if it doesn't exist.
+    pub async fn set_promo_code_active(&self, code: &str, active: bool) -> Result<bool> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite set_promo_code_active connection")?;
+                db::set_promo_code_active(&conn, code, active).context("sqlite set_promo_code_active")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let result = sqlx::query("UPDATE promo_codes SET is_active = $2 WHERE code = $1")
+                    .bind(code)
+                    .bind(i32::from(active))
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres set_promo_code_active")?;
+                Ok(result.rows_affected() > 0)
+            }
+        }
+    }
+
+    /// Record a redemption, enforcing expiry, `max_uses` and one use per user.
+    pub async fn redeem_promo_code(&self, code: &str, user_id: i64) -> Result<RedeemOutcome> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite redeem_promo_code connection")?;
+                db::redeem_promo_code(&conn, code, user_id).context("sqlite redeem_promo_code")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let mut tx = pg_pool.begin().await.context("begin pg redeem_promo_code")?;
+                let row = sqlx::query(&format!(
+                    "SELECT {PG_PROMO_COLUMNS}, (expires_at IS NOT NULL AND expires_at <= NOW()) AS expired
+                     FROM promo_codes WHERE code = $1 AND is_active = 1 FOR UPDATE"
+                ))
+                .bind(code)
+                .fetch_optional(&mut *tx)
+                .await
+                .context("postgres redeem_promo_code lookup")?;
+                let Some(row) = row else {
+                    return Ok(RedeemOutcome::NotFound);
+                };
+                let promo = map_pg_promo_code(&row);
+                if row.get::<bool, _>("expired") {
+                    return Ok(RedeemOutcome::Expired);
+                }
+                if promo.max_uses.is_some_and(|max| promo.used_count >= max) {
+                    return Ok(RedeemOutcome::Exhausted);
+                }
+                let inserted = sqlx::query(
+                    "INSERT INTO promo_redemptions (code, user_id) VALUES ($1, $2)
+                     ON CONFLICT (code, user_id) DO NOTHING",
+                )
+                .bind(code)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await
+                .context("postgres redeem_promo_code insert")?;
+                if inserted.rows_affected() == 0 {
+                    return Ok(RedeemOutcome::AlreadyRedeemed);
+                }
+                sqlx::query("UPDATE promo_codes SET used_count = used_count + 1 WHERE code = $1")
+                    .bind(code)
+                    .execute(&mut *tx)
+                    .await
+                    .context("postgres redeem_promo_code bump")?;
+                tx.commit().await.context("commit pg redeem_promo_code")?;
+                Ok(RedeemOutcome::Redeemed(PromoCode {
+                    used_count: promo.used_count + 1,
+                    ..promo
+                }))
+            }
+        }
+    }
+
+    /// Best unconsumed discount the user has redeemed: `(code, percent)`.
+    pub async fn get_pending_discount(&self, user_id: i64) -> Result<Option<(String, i32)>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_pending_discount connection")?;
+                db::get_pending_discount(&conn, user_id).context("sqlite get_pending_discount")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(
+                    "SELECT p.code, p.percent FROM promo_redemptions r
+                     JOIN promo_codes p ON p.code = r.code
+                     WHERE r.user_id = $1 AND r.consumed_at IS NULL AND p.kind = 'discount' AND p.percent IS NOT NULL
+                     ORDER BY p.percent DESC LIMIT 1",
+                )
+                .bind(user_id)
+                .fetch_optional(pg_pool)
+                .await
+                .context("postgres get_pending_discount")?;
+                Ok(row.map(|r| (r.get("code"), r.get("percent"))))
+            }
+        }
+    }
+
+    /// Mark a discount redemption as used by a paid invoice.
+    pub async fn consume_discount(&self, user_id: i64, code: &str) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite consume_discount connection")?;
+                db::consume_discount(&conn, user_id, code).context("sqlite consume_discount")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "UPDATE promo_redemptions SET consumed_at = NOW()
+                     WHERE user_id = $1 AND code = $2 AND consumed_at IS NULL",
+                )
+                .bind(user_id)
+                .bind(code)
+                .execute(pg_pool)
+                .await
+                .context("postgres consume_discount")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Remember who invited `invitee_id` (first referrer wins).
+    pub async fn record_referral(&self, invitee_id: i64, referrer_id: i64) -> Result<bool> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite record_referral connection")?;
+                db::record_referral(&conn, invitee_id, referrer_id).context("sqlite record_referral")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                if invitee_id == referrer_id {
+                    return Ok(false);
+                }
+                let result = sqlx::query(
+                    "INSERT INTO referrals (invitee_id, referrer_id) VALUES ($1, $2)
+                     ON CONFLICT (invitee_id) DO NOTHING",
+                )
+                .bind(invitee_id)
+                .bind(referrer_id)
+                .execute(pg_pool)
+                .await
+                .context("postgres record_referral")?;
+                Ok(result.rows_affected() > 0)
+            }
+        }
+    }
+
+    /// Returns the referrer exactly once, the first time the invitee qualifies.
+    pub async fn claim_referral_reward(&self, invitee_id: i64) -> Result<Option<i64>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite claim_referral_reward connection")?;
+                db::claim_referral_reward(&conn, invitee_id).context("sqlite claim_referral_reward")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let referrer = sqlx::query_scalar(
+                    "UPDATE referrals SET rewarded_at = NOW()
+                     WHERE invitee_id = $1 AND rewarded_at IS NULL
+                     RETURNING referrer_id",
+                )
+                .bind(invitee_id)
+                .fetch_optional(pg_pool)
+                .await
+                .context("postgres claim_referral_reward")?;
+                Ok(referrer)
+            }
+        }
+    }
+
+    /// `(invited, rewarded)` counts for a referrer.
+    pub async fn count_referrals(&self, referrer_id: i64) -> Result<(i64, i64)> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite count_referrals connection")?;
+                db::count_referrals(&conn, referrer_id).context("sqlite count_referrals")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(
+                    "SELECT COUNT(*) AS invited, COUNT(rewarded_at) AS rewarded
+                     FROM referrals WHERE referrer_id = $1",
+                )
+                .bind(referrer_id)
+                .fetch_one(pg_pool)
+                .await
+                .context("postgres count_referrals")?;
+                Ok((row.get("invited"), row.get("rewarded")))
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! `SharedStorage` dispatch for the V57 public API tables. SQLite branch
+//! delegates to `storage/db/public_api.rs`; Postgres is inline, so keys and
+//! job results work on every instance.
+
+use anyhow::{Context, Result};
+use sqlx::Row;
+
+use crate::storage::db::{self, ApiJob, ApiKey};
+
+use super::SharedStorage;
+
+const PG_API_KEY_COLUMNS: &str = "id, user_id, name, key_prefix,
+    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS created_at,
+    to_char(last_used_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS last_used_at";
+
+fn pg_api_key(row: &sqlx::postgres::PgRow) -> ApiKey {
+    ApiKey {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        name: row.get("name"),
+        key_prefix: row.get("key_prefix"),
+        created_at: row.get("created_at"),
+        last_used_at: row.get("last_used_at"),
+    }
+}
+
+impl SharedStorage {
+    /// Store a new API key. Only the SHA-256 of the raw key is kept.
+    pub async fn create_api_key(&self, user_id: i64, name: &str, key_hash: &[u8], key_prefix: &str) -> Result<i64> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite create_api_key connection")?;
+                db::create_api_key(&conn, user_id, name, key_hash, key_prefix).context("sqlite create_api_key")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(
+                    "INSERT INTO api_keys (user_id, name, key_hash, key_prefix) VALUES ($1, $2, $3, $4) RETURNING id",
+                )
+                .bind(user_id)
+                .bind(name)
+                .bind(key_hash)
+                .bind(key_prefix)
+                .fetch_one(pg_pool)
+                .await
+                .context("postgres create_api_key")?;
+                Ok(row.get("id"))
+            }
+        }
+    }
+
+    /// The user's active keys, oldest first.
+    pub async fn list_api_keys(&self, user_id: i64) -> Result<Vec<ApiKey>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite list_api_keys connection")?;
+                db::list_api_keys(&conn, user_id).context("sqlite list_api_keys")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let rows = sqlx::query(&format!(
+                    "SELECT {} FROM api_keys WHERE user_id = $1 AND revoked_at IS NULL ORDER BY id",
+                    PG_API_KEY_COLUMNS
+                ))
+                .bind(user_id)
+                .fetch_all(pg_pool)
+                .await
+                .context("postgres list_api_keys")?;
+                Ok(rows.iter().map(pg_api_key).collect())
+            }
+        }
+    }
+
+    /// Revoke one of the user's keys. Returns false if there was nothing to revoke.
+    pub async fn revoke_api_key(&self, user_id: i64, key_id: i64) -> Result<bool> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite revoke_api_key connection")?;
+                db::revoke_api_key(&conn, user_id, key_id).context("sqlite revoke_api_key")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let rows = sqlx::query(
+                    "UPDATE api_keys SET revoked_at = NOW()
+                     WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+                )
+                .bind(key_id)
+                .bind(user_id)
+                .execute(pg_pool)
+                .await
+                .context("postgres revoke_api_key")?
+                .rows_affected();
+                Ok(rows > 0)
+            }
+        }
+    }
+
+    /// Resolve an active key by the SHA-256 of the raw key.
+    pub async fn get_api_key_by_hash(&self, key_hash: &[u8]) -> Result<Option<ApiKey>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_api_key_by_hash connection")?;
+                db::get_api_key_by_hash(&conn, key_hash).context("sqlite get_api_key_by_hash")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(&format!(
+                    "SELECT {} FROM api_keys WHERE key_hash = $1 AND revoked_at IS NULL",
+                    PG_API_KEY_COLUMNS
+                ))
+                .bind(key_hash)
+                .fetch_optional(pg_pool)
+                .await
+                .context("postgres get_api_key_by_hash")?;
+                Ok(row.as_ref().map(pg_api_key))
+            }
+        }
+    }
+
+    /// Log one API call and bump the key's `last_used_at`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record_api_request(
+        &self,
+        key_id: i64,
+        user_id: i64,
+        method: &str,
+        endpoint: &str,
+        status: u16,
+        duration_ms: i64,
+    ) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite record_api_request connection")?;
+                db::record_api_request(&conn, key_id, user_id, method, endpoint, status, duration_ms)
+                    .context("sqlite record_api_request")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "INSERT INTO api_requests (key_id, user_id, method, endpoint, status, duration_ms)
+                     VALUES ($1, $2, $3, $4, $5, $6)",
+                )
+                .bind(key_id)
+                .bind(user_id)
+                .bind(method)
+                .bind(endpoint)
+                .bind(status as i32)
+                .bind(duration_ms)
+                .execute(pg_pool)
+                .await
+                .context("postgres record_api_request")?;
+                sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1")
+                    .bind(key_id)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres record_api_request touch key")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Drop request log rows older than `days`. Returns the number removed.
+    pub async fn cleanup_old_api_requests(&self, days: i64) -> Result<usize> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite cleanup_old_api_requests connection")?;
+                db::cleanup_old_api_requests(&conn, days).context("sqlite cleanup_old_api_requests")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let rows = sqlx::query("DELETE FROM api_requests WHERE created_at < NOW() - make_interval(days => $1)")
+                    .bind(days as i32)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres cleanup_old_api_requests")?
+                    .rows_affected();
+                Ok(rows as usize)
+            }
+        }
+    }
+
+    /// Link a freshly enqueued task to the key that submitted it.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_api_job(
+        &self,
+        task_id: &str,
+        key_id: i64,
+        user_id: i64,
+        url: &str,
+        format: &str,
+    ) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite create_api_job connection")?;
+                db::create_api_job(&conn, task_id, key_id, user_id, url, format).context("sqlite create_api_job")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query("INSERT INTO api_jobs (task_id, key_id, user_id, url, format) VALUES ($1, $2, $3, $4, $5)")
+                    .bind(task_id)
+                    .bind(key_id)
+                    .bind(user_id)
+                    .bind(url)
+                    .bind(format)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres create_api_job")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// One of the user's API jobs, with the live task_queue status.
+    pub async fn get_api_job(&self, user_id: i64, task_id: &str) -> Result<Option<ApiJob>> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite get_api_job connection")?;
+                db::get_api_job(&conn, user_id, task_id).context("sqlite get_api_job")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query(
+                    "SELECT j.task_id, j.key_id, j.user_id, j.url, j.format, j.hosted_file_id, j.result_expires_at,
+                            j.error, to_char(j.created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') AS created_at,
+                            t.status AS task_status, t.error_message AS task_error
+                     FROM api_jobs j LEFT JOIN task_queue t ON t.id = j.task_id
+                     WHERE j.task_id = $1 AND j.user_id = $2",
+                )
+                .bind(task_id)
+                .bind(user_id)
+                .fetch_optional(pg_pool)
+                .await
+                .context("postgres get_api_job")?;
+                Ok(row.map(|r| ApiJob {
+                    task_id: r.get("task_id"),
+                    key_id: r.get("key_id"),
+                    user_id: r.get("user_id"),
+                    url: r.get("url"),
+                    format: r.get("format"),
+                    hosted_file_id: r.get("hosted_file_id"),
+                    result_expires_at: r.get("result_expires_at"),
+                    error: r.get("error"),
+                    created_at: r.get("created_at"),
+                    task_status: r.get("task_status"),
+                    task_error: r.get("task_error"),
+                }))
+            }
+        }
+    }
+
+    /// Whether `task_id` was submitted through the API.
+    pub async fn is_api_job(&self, task_id: &str) -> Result<bool> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite is_api_job connection")?;
+                db::is_api_job(&conn, task_id).context("sqlite is_api_job")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                let row = sqlx::query("SELECT 1 FROM api_jobs WHERE task_id = $1")
+                    .bind(task_id)
+                    .fetch_optional(pg_pool)
+                    .await
+                    .context("postgres is_api_job")?;
+                Ok(row.is_some())
+            }
+        }
+    }
+
+    /// Record the published result of a job.
+    pub async fn set_api_job_result(&self, task_id: &str, hosted_file_id: &str, expires_at: i64) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite set_api_job_result connection")?;
+                db::set_api_job_result(&conn, task_id, hosted_file_id, expires_at).context("sqlite set_api_job_result")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query(
+                    "UPDATE api_jobs SET hosted_file_id = $2, result_expires_at = $3, error = NULL, finished_at = NOW()
+                     WHERE task_id = $1",
+                )
+                .bind(task_id)
+                .bind(hosted_file_id)
+                .bind(expires_at)
+                .execute(pg_pool)
+                .await
+                .context("postgres set_api_job_result")?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Record why a job failed.
+    pub async fn set_api_job_error(&self, task_id: &str, error: &str) -> Result<()> {
+        match self {
+            Self::Sqlite { db_pool } => {
+                let conn = db::get_connection(db_pool).context("sqlite set_api_job_error connection")?;
+                db::set_api_job_error(&conn, task_id, error).context("sqlite set_api_job_error")
+            }
+            Self::Postgres { pg_pool, .. } => {
+                sqlx::query("UPDATE api_jobs SET error = $2, finished_at = NOW() WHERE task_id = $1")
+                    .bind(task_id)
+                    .bind(error)
+                    .execute(pg_pool)
+                    .await
+                    .context("postgres set_api_job_error")?;
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Startup schema-drift check.
+//!
+//! The expected schema is whatever the migrations embedded in this binary
+//! produce: SQLite migrations are replayed into an in-memory database, and
+//! Postgres migrations into a scratch schema inside a transaction that is
+//! rolled back. Live columns missing from a backend (or with a different
+//! Postgres type) are drift; columns the live database has on top of the
+//! expected ones are only reported.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use sqlx::{PgPool, Row};
+
+use crate::storage::db::get_connection;
+use crate::storage::migrations;
+
+use super::SharedStorage;
+use super::pg_migrations;
+
+/// table → column → declared type
+type TableColumns = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Tables owned by the migration runners themselves.
+const IGNORED_TABLES: &[&str] = &["refinery_schema_history", "pg_schema_history", "sqlite_sequence"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDrift {
+    pub backend: &'static str,
+    pub missing_tables: Vec<String>,
+    /// `table.column`
+    pub missing_columns: Vec<String>,
+    /// `table.column: expected X, found Y`
+    pub type_mismatches: Vec<String>,
+    /// `table.column` present live but not expected
+    pub extra_columns: Vec<String>,
+}
+
+impl SchemaDrift {
+    /// Drift the bot cannot safely run with.
+    pub fn is_blocking(&self) -> bool {
+        !self.missing_tables.is_empty() || !self.missing_columns.is_empty() || !self.type_mismatches.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.is_blocking() && self.extra_columns.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} schema drift:", self.backend)?;
+        let sections = [
+            ("missing tables", &self.missing_tables),
+            ("missing columns", &self.missing_columns),
+            ("type mismatches", &self.type_mismatches),
+            ("unexpected columns", &self.extra_columns),
+        ];
+        for (label, items) in sections {
+            if !items.is_empty() {
+                write!(f, " {}: {};", label, items.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare live columns against the expected ones. SQLite declared types are
+/// advisory, so types are only compared when `compare_types` is set.
+fn compare(backend: &'static str, expected: &TableColumns, live: &TableColumns, compare_types: bool) -> SchemaDrift {
+    let mut drift = SchemaDrift {
+        backend,
+        ..SchemaDrift::default()
+    };
+    for (table, columns) in expected {
+        if IGNORED_TABLES.contains(&table.as_str()) {
+            continue;
+        }
+        let Some(live_columns) = live.get(table) else {
+            drift.missing_tables.push(table.clone());
+            continue;
+        };
+        for (column, expected_type) in columns {
+            match live_columns.get(column) {
+                None => drift.missing_columns.push(format!("{}.{}", table, column)),
+                Some(live_type) if compare_types && live_type != expected_type => drift.type_mismatches.push(format!(
+                    "{}.{}: expected {}, found {}",
+                    table, column, expected_type, live_type
+                )),
+                Some(_) => {}
+            }
+        }
+        drift.extra_columns.extend(
+            live_columns
+                .keys()
+                .filter(|c| !columns.contains_key(*c))
+                .map(|c| format!("{}.{}", table, c)),
+        );
+    }
+    drift
+}
+
+fn sqlite_columns(conn: &Connection) -> Result<TableColumns> {
+    let tables: Vec<String> = {
+        let mut stmt =
+            conn.prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")?;
+        stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?
+    };
+    let mut out = TableColumns::new();
+    for table in tables {
+        let mut stmt = conn.prepare("SELECT name, type FROM pragma_table_info(?1)")?;
+        let columns = stmt
+            .query_map([&table], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?.to_ascii_lowercase()))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        out.insert(table, columns);
+    }
+    Ok(out)
+}
+
+fn expected_sqlite_columns() -> Result<TableColumns> {
+    let mut conn = Connection::open_in_memory().context("open in-memory sqlite")?;
+    migrations::run_migrations(&mut conn).context("replay sqlite migrations")?;
+    sqlite_columns(&conn)
+}
+
+async fn pg_columns<'e>(executor: impl sqlx::PgExecutor<'e>, schema: &str) -> Result<TableColumns> {
+    let rows = sqlx::query(
+        "SELECT c.table_name::text, c.column_name::text, c.data_type::text
+         FROM information_schema.columns c
+         JOIN information_schema.tables t
+           ON t.table_schema = c.table_schema AND t.table_name = c.table_name
+         WHERE c.table_schema = $1 AND t.table_type = 'BASE TABLE'",
+    )
+    .bind(schema)
+    .fetch_all(executor)
+    .await
+    .context("read postgres columns")?;
+    let mut out = TableColumns::new();
+    for row in rows {
+        out.entry(row.get(0)).or_default().insert(row.get(1), row.get(2));
+    }
+    Ok(out)
+}
+
+/// Replay the Postgres migrations into a throwaway schema and read it back.
+async fn expected_pg_columns(pool: &PgPool) -> Result<TableColumns> {
+    let scratch = format!("doradura_expected_{}", std::process::id());
+    let mut tx = pool.begin().await?;
+    sqlx::query(&format!("CREATE SCHEMA {}", scratch))
+        .execute(&mut *tx)
+        .await
+        .context("create scratch schema")?;
+    sqlx::query(&format!("SET LOCAL search_path TO {}", scratch))
+        .execute(&mut *tx)
+        .await?;
+    pg_migrations::apply_all(&mut tx).await?;
+    let columns = pg_columns(&mut *tx, &scratch).await?;
+    tx.rollback().await?;
+    Ok(columns)
+}
+
+impl SharedStorage {
+    /// Compare each backend's live columns with the schema this build expects.
+    /// Returns one entry per backend that deviates.
+    pub async fn check_schema(&self) -> Result<Vec<SchemaDrift>> {
+        let mut drifts = Vec::new();
+
+        let live = {
+            let conn = get_connection(&self.sqlite_pool()).context("sqlite schema check connection")?;
+            sqlite_columns(&conn)?
+        };
+        let expected = tokio::task::spawn_blocking(expected_sqlite_columns)
+            .await
+            .context("sqlite schema check panicked")??;
+        drifts.push(compare("sqlite", &expected, &live, false));
+
+        if let Self::Postgres { pg_pool, .. } = self {
+            let expected = expected_pg_columns(pg_pool).await?;
+            let schema: String = sqlx::query_scalar("SELECT current_schema()::text")
+                .fetch_one(pg_pool)
+                .await
+                .context("read postgres current_schema")?;
+            let live = pg_columns(pg_pool, &schema).await?;
+            drifts.push(compare("postgres", &expected, &live, true));
+        }
+
+        drifts.retain(|d| !d.is_empty());
+        Ok(drifts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(spec: &[(&str, &[(&str, &str)])]) -> TableColumns {
+        spec.iter()
+            .map(|(table, cols)| {
+                (
+                    table.to_string(),
+                    cols.iter().map(|(c, t)| (c.to_string(), t.to_string())).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compare_reports_missing_and_extra() {
+        let expected = columns(&[
+            ("users", &[("telegram_id", "bigint"), ("plan", "text")]),
+            ("charges", &[("id", "bigint")]),
+            ("pg_schema_history", &[("version", "integer")]),
+        ]);
+        let live = columns(&[("users", &[("telegram_id", "integer"), ("legacy", "text")])]);
+
+        let drift = compare("postgres", &expected, &live, true);
+        assert_eq!(drift.missing_tables, vec!["charges"]);
+        assert_eq!(drift.missing_columns, vec!["users.plan"]);
+        assert_eq!(
+            drift.type_mismatches,
+            vec!["users.telegram_id: expected bigint, found integer"]
+        );
+        assert_eq!(drift.extra_columns, vec!["users.legacy"]);
+        assert!(drift.is_blocking());
+
+        let loose = compare("sqlite", &expected, &live, false);
+        assert!(loose.type_mismatches.is_empty());
+    }
+
+    #[test]
+    fn test_extra_columns_are_not_blocking() {
+        let expected = columns(&[("users", &[("telegram_id", "bigint")])]);
+        let live = columns(&[("users", &[("telegram_id", "bigint"), ("old_flag", "integer")])]);
+        let drift = compare("postgres", &expected, &live, true);
+        assert!(!drift.is_blocking());
+        assert!(!drift.is_empty());
+    }
+
+    #[test]
+    fn test_migrated_sqlite_has_no_drift() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrations::run_migrations(&mut conn).unwrap();
+        let live = sqlite_columns(&conn).unwrap();
+        let expected = expected_sqlite_columns().unwrap();
+        assert!(compare("sqlite", &expected, &live, false).is_empty());
+        assert!(expected.contains_key("users"));
+    }
+}
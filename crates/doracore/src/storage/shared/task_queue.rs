@@ -1,11 +1,33 @@
 use anyhow::{Context, Result};
 use sqlx::Row;
 
-use crate::storage::db::{self, EnqueueResult, TaskQueueEntry};
+use crate::core::admin_events::{self, AdminEvent};
+use crate::storage::db::{self, DbConnection, DebitOutcome, EnqueueResult, TaskQueueEntry};
 
 use super::SharedStorage;
+use super::credits::pg_apply_credit_debit;
 use super::types::QueueTaskInput;
 
+fn sqlite_insert_task(conn: &DbConnection, input: &QueueTaskInput<'_>) -> rusqlite::Result<EnqueueResult> {
+    db::save_task_to_queue(
+        conn,
+        input.task_id,
+        input.user_id,
+        input.url,
+        input.message_id,
+        input.format,
+        input.is_video,
+        input.video_quality,
+        input.audio_bitrate,
+        input.time_range_start,
+        input.time_range_end,
+        input.carousel_mask,
+        input.with_lyrics,
+        input.priority,
+        input.idempotency_key,
+    )
+}
+
 /// Task status transitions used by `run_task_status_update`.
 enum TaskStatusUpdate {
     Processing,
@@ -14,29 +36,49 @@ enum TaskStatusUpdate {
 
 impl SharedStorage {
     pub async fn save_task_to_queue(&self, input: QueueTaskInput<'_>) -> Result<EnqueueResult> {
+        let (task_id, user_id) = (input.task_id, input.user_id);
+        let result = self.insert_task(input).await;
+        if matches!(result, Ok(EnqueueResult::Enqueued)) {
+            admin_events::publish(AdminEvent::queue(task_id, Some(user_id), "pending"));
+        }
+        result
+    }
+
+    async fn insert_task(&self, input: QueueTaskInput<'_>) -> Result<EnqueueResult> {
         match self {
             Self::Sqlite { db_pool } => {
                 let conn = db::get_connection(db_pool).context("sqlite save_task_to_queue connection")?;
-                db::save_task_to_queue(
-                    &conn,
-                    input.task_id,
-                    input.user_id,
-                    input.url,
-                    input.message_id,
-                    input.format,
-                    input.is_video,
-                    input.video_quality,
-                    input.audio_bitrate,
-                    input.time_range_start,
-                    input.time_range_end,
-                    input.carousel_mask,
-                    input.with_lyrics,
-                    input.priority,
-                    input.idempotency_key,
-                )
-                .context("sqlite save_task_to_queue")
+                let Some(charge) = input.credit_charge else {
+                    return sqlite_insert_task(&conn, &input).context("sqlite save_task_to_queue");
+                };
+                // V51: debit and task row commit together — a duplicate task or
+                // an unaffordable charge rolls both back.
+                conn.execute_batch("BEGIN IMMEDIATE")
+                    .context("sqlite save_task_to_queue begin")?;
+                let result = (|| -> Result<EnqueueResult> {
+                    if let DebitOutcome::Insufficient { balance } =
+                        db::apply_credit_debit(&conn, input.user_id, input.task_id, &charge)?
+                    {
+                        return Ok(EnqueueResult::InsufficientCredits { balance });
+                    }
+                    Ok(sqlite_insert_task(&conn, &input)?)
+                })();
+                if matches!(result, Ok(EnqueueResult::Enqueued)) {
+                    conn.execute_batch("COMMIT")
+                        .context("sqlite save_task_to_queue commit")?;
+                } else {
+                    let _ = conn.execute_batch("ROLLBACK");
+                }
+                result.context("sqlite save_task_to_queue")
             }
             Self::Postgres { pg_pool, .. } => {
+                let mut tx = pg_pool.begin().await.context("begin pg save_task_to_queue")?;
+                if let Some(charge) = input.credit_charge.as_ref()
+                    && let DebitOutcome::Insufficient { balance } =
+                        pg_apply_credit_debit(&mut tx, input.user_id, input.task_id, charge).await?
+                {
+                    return Ok(EnqueueResult::InsufficientCredits { balance });
+                }
                 let rows = sqlx::query(
                     "INSERT INTO task_queue (
                         id, user_id, url, message_id, format, is_video, video_quality, audio_bitrate,
@@ -58,20 +100,29 @@ impl SharedStorage {
                 .bind(if input.with_lyrics { 1_i32 } else { 0_i32 })
                 .bind(input.priority)
                 .bind(input.idempotency_key)
-                .execute(pg_pool)
+                .execute(&mut *tx)
                 .await
                 .context("postgres save_task_to_queue")?
                 .rows_affected();
-                Ok(if rows == 0 {
-                    EnqueueResult::Duplicate
-                } else {
-                    EnqueueResult::Enqueued
-                })
+                if rows == 0 {
+                    // Duplicate: drop the transaction so any debit rolls back too.
+                    return Ok(EnqueueResult::Duplicate);
+                }
+                tx.commit().await.context("commit pg save_task_to_queue")?;
+                Ok(EnqueueResult::Enqueued)
             }
         }
     }
 
     pub async fn claim_next_task(&self, worker_id: &str, lease_seconds: i64) -> Result<Option<TaskQueueEntry>> {
+        let claimed = self.lease_next_task(worker_id, lease_seconds).await;
+        if let Ok(Some(task)) = &claimed {
+            admin_events::publish(AdminEvent::queue(&task.id, Some(task.user_id), "leased"));
+        }
+        claimed
+    }
+
+    async fn lease_next_task(&self, worker_id: &str, lease_seconds: i64) -> Result<Option<TaskQueueEntry>> {
         match self {
             Self::Sqlite { db_pool } => {
                 let conn = db::get_connection(db_pool).context("sqlite claim_next_task connection")?;
@@ -146,6 +197,12 @@ impl SharedStorage {
     }
 
     pub async fn mark_task_completed(&self, task_id: &str, worker_id: &str) -> Result<()> {
+        self.complete_task(task_id, worker_id).await?;
+        admin_events::publish(AdminEvent::queue(task_id, None, "completed"));
+        Ok(())
+    }
+
+    async fn complete_task(&self, task_id: &str, worker_id: &str) -> Result<()> {
         match self {
             Self::Sqlite { db_pool } => {
                 let conn = db::get_connection(db_pool).context("sqlite mark_task_completed connection")?;
@@ -175,6 +232,8 @@ impl SharedStorage {
         }
     }
 
+    /// Returns whether a retry was scheduled (`pending` again) rather than
+    /// the task being dead-lettered.
     pub async fn mark_task_failed(
         &self,
         task_id: &str,
@@ -182,6 +241,22 @@ impl SharedStorage {
         error_message: &str,
         retryable: bool,
         max_retries: i32,
+    ) -> Result<bool> {
+        let retry_scheduled = self
+            .fail_task(task_id, worker_id, error_message, retryable, max_retries)
+            .await?;
+        let status = if retry_scheduled { "pending" } else { "dead_letter" };
+        admin_events::publish(AdminEvent::queue(task_id, None, status));
+        Ok(retry_scheduled)
+    }
+
+    async fn fail_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        error_message: &str,
+        retryable: bool,
+        max_retries: i32,
     ) -> Result<bool> {
         match self {
             Self::Sqlite { db_pool } => {
@@ -394,6 +469,16 @@ impl SharedStorage {
     }
 
     async fn run_task_status_update(&self, status: TaskStatusUpdate, task_id: &str, worker_id: &str) -> Result<()> {
+        let event_status = match status {
+            TaskStatusUpdate::Processing => "processing",
+            TaskStatusUpdate::Uploading => "uploading",
+        };
+        self.write_task_status(status, task_id, worker_id).await?;
+        admin_events::publish(AdminEvent::queue(task_id, None, event_status));
+        Ok(())
+    }
+
+    async fn write_task_status(&self, status: TaskStatusUpdate, task_id: &str, worker_id: &str) -> Result<()> {
         match self {
             Self::Sqlite { db_pool } => {
                 let conn = db::get_connection(db_pool).context("sqlite run_task_status_update connection")?;
@@ -1,5 +1,8 @@
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
+use crate::storage::db::CreditCharge;
+
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct SharePageRecord {
     pub id: String,
@@ -35,6 +38,10 @@ pub struct QueueTaskInput<'a> {
     pub with_lyrics: bool,
     pub priority: i32,
     pub idempotency_key: &'a str,
+    /// Premium-action price (V51 credits). When set, the debit and the task
+    /// row are written in one transaction; an unaffordable charge enqueues
+    /// nothing and yields `EnqueueResult::InsufficientCredits`.
+    pub credit_charge: Option<CreditCharge>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -62,6 +69,43 @@ pub struct ContentSubscriptionRecord {
     pub consecutive_errors: u32,
     pub created_at: String,
     pub updated_at: String,
+    /// V54: queue matching items automatically instead of only notifying.
+    pub auto_download: bool,
+    /// `"mp3"` or `"mp4"`.
+    pub auto_format: String,
+    /// Video quality or audio bitrate; `None` uses the user's default.
+    pub auto_quality: Option<String>,
+    pub filters: ContentFilters,
+    /// Max items auto-queued per UTC day.
+    pub daily_cap: u32,
+}
+
+/// Per-subscription item filters (V54 `content_subscriptions.filters` JSON).
+/// Keywords match the item title case-insensitively; empty lists and `None`
+/// bounds don't filter.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentFilters {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_duration_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration_secs: Option<u64>,
+    /// Content types that auto-download (watch-mask bits); `0` = every watched type.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub auto_mask: u32,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+impl ContentFilters {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,1164 @@
+//! SQLite ⇄ Postgres data migration (`doradura migrate-storage`).
+//!
+//! The copy is schema-driven: the table list, column types, primary keys and
+//! foreign keys come from Postgres `information_schema` (the Postgres schema
+//! is bootstrapped first), intersected with the columns SQLite actually has.
+//! Every value passes through one canonical text form so both directions,
+//! the type mapping and the verification share the same rules:
+//!
+//! | Postgres type            | SQLite value            | canonical               |
+//! |--------------------------|-------------------------|-------------------------|
+//! | integer types            | INTEGER                 | decimal                 |
+//! | `boolean`                | INTEGER 0/1             | `0` / `1`               |
+//! | `timestamp[tz]`          | TEXT / unix INTEGER     | `YYYY-MM-DD HH:MM:SS` UTC |
+//! | `real` / `double` / `numeric` | REAL               | shortest `f64`          |
+//! | `bytea`                  | BLOB                    | lowercase hex           |
+//! | everything else          | TEXT                    | as-is                   |
+//!
+//! Tables are copied parent-first in batches, paged by position (SQLite
+//! `rowid`, Postgres `ctid`) rather than `OFFSET`. The last position read is
+//! saved to a state file after each batch so an interrupted run resumes where
+//! it stopped; inserts ignore rows that already exist. A table without a
+//! primary or unique key can't tell a copied row from a new one, so an
+//! interrupted copy of it is cleared and started over instead. Each table is
+//! then verified by row count and an order-independent checksum over
+//! canonical rows, read in the same batches. The bot must be stopped while
+//! migrating.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use fs_err as fs;
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::storage::db::{DbPool, get_connection};
+
+/// Tables that belong to a single backend's bookkeeping.
+const SKIPPED_TABLES: &[&str] = &["refinery_schema_history", "sqlite_sequence"];
+
+/// Postgres caps a statement at 65535 bind parameters.
+const PG_MAX_PARAMS: usize = 65_535;
+
+const CANONICAL_TIMESTAMP: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Direction {
+    SqliteToPostgres,
+    PostgresToSqlite,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferOptions {
+    pub direction: Direction,
+    pub batch_size: usize,
+    /// Report what would be copied without writing anything
+    pub dry_run: bool,
+    /// Where progress is kept between runs
+    pub state_path: PathBuf,
+    /// Ignore saved progress and start over
+    pub restart: bool,
+    /// Only these tables (all when empty)
+    pub tables: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnClass {
+    Integer,
+    Boolean,
+    /// `timestamp with time zone`; stored as UTC
+    TimestampTz,
+    Timestamp,
+    Float,
+    /// `bytea`; BLOB in SQLite
+    Binary,
+    Text,
+}
+
+impl ColumnClass {
+    fn from_pg(data_type: &str) -> Self {
+        match data_type {
+            "smallint" | "integer" | "bigint" => Self::Integer,
+            "boolean" => Self::Boolean,
+            "timestamp with time zone" => Self::TimestampTz,
+            "timestamp without time zone" => Self::Timestamp,
+            "real" | "double precision" | "numeric" => Self::Float,
+            "bytea" => Self::Binary,
+            _ => Self::Text,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Column {
+    name: String,
+    class: ColumnClass,
+    /// Postgres type name used for casts (`int8`, `timestamptz`, …)
+    udt_name: String,
+    /// Backed by a sequence that must be moved past copied ids
+    serial: bool,
+}
+
+#[derive(Debug, Clone)]
+struct TablePlan {
+    name: String,
+    columns: Vec<Column>,
+    /// Has a primary or unique key, so re-inserting a copied row is a no-op
+    keyed: bool,
+    /// Columns only one side has; not copied
+    skipped_columns: Vec<String>,
+}
+
+/// Outcome for one table.
+#[derive(Debug, Clone)]
+pub struct TableReport {
+    pub table: String,
+    pub source_rows: u64,
+    /// Rows in the destination before this run touched it
+    pub dest_rows_before: u64,
+    pub copied: u64,
+    pub dest_rows: u64,
+    pub source_checksum: String,
+    pub dest_checksum: String,
+    pub skipped_columns: Vec<String>,
+}
+
+impl TableReport {
+    pub fn verified(&self) -> bool {
+        self.source_rows == self.dest_rows && self.source_checksum == self.dest_checksum
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferReport {
+    pub direction: Direction,
+    pub dry_run: bool,
+    pub tables: Vec<TableReport>,
+    /// Tables present on only one side, with the side that has them
+    pub skipped_tables: Vec<(String, &'static str)>,
+}
+
+impl TransferReport {
+    pub fn verified(&self) -> bool {
+        self.dry_run || self.tables.iter().all(TableReport::verified)
+    }
+}
+
+impl fmt::Display for TransferReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} ({})",
+            self.direction,
+            if self.dry_run { "dry run" } else { "copied" }
+        )?;
+        writeln!(
+            f,
+            "{:<28} {:>10} {:>11} {:>10} {:>10}  status",
+            "table", "source", "dest-before", "copied", "dest"
+        )?;
+        for t in &self.tables {
+            let status = if self.dry_run {
+                format!("would copy {}", t.source_rows.saturating_sub(t.copied))
+            } else if t.verified() {
+                "ok".to_string()
+            } else if t.source_rows != t.dest_rows {
+                "ROW COUNT MISMATCH".to_string()
+            } else {
+                "CHECKSUM MISMATCH".to_string()
+            };
+            writeln!(
+                f,
+                "{:<28} {:>10} {:>11} {:>10} {:>10}  {}",
+                t.table, t.source_rows, t.dest_rows_before, t.copied, t.dest_rows, status
+            )?;
+            if !t.skipped_columns.is_empty() {
+                writeln!(f, "    skipped columns: {}", t.skipped_columns.join(", "))?;
+            }
+        }
+        for (table, side) in &self.skipped_tables {
+            writeln!(f, "skipped table {} (only in {})", table, side)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Progress {
+    direction: Option<Direction>,
+    /// Rows read from the source so far, per table
+    copied: BTreeMap<String, u64>,
+    /// Position of the last source row read, per table: SQLite `rowid` or
+    /// Postgres `ctid`
+    #[serde(default)]
+    cursors: BTreeMap<String, String>,
+}
+
+impl Progress {
+    fn load(opts: &TransferOptions) -> Result<Self> {
+        if opts.restart || !opts.state_path.exists() {
+            return Ok(Self::default());
+        }
+        let progress: Self = serde_json::from_slice(&fs::read(&opts.state_path)?)
+            .with_context(|| format!("parse {}", opts.state_path.display()))?;
+        match progress.direction {
+            Some(direction) if direction != opts.direction => bail!(
+                "{} holds progress for {}; pass --restart to start {} from scratch",
+                opts.state_path.display(),
+                direction,
+                opts.direction
+            ),
+            _ => Ok(progress),
+        }
+    }
+
+    fn save(&self, opts: &TransferOptions) -> Result<()> {
+        fs::write(&opts.state_path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Copy every shared table between the two backends and verify the result.
+pub async fn transfer(sqlite: &DbPool, pg: &PgPool, opts: &TransferOptions) -> Result<TransferReport> {
+    if opts.batch_size == 0 {
+        bail!("batch size must be positive");
+    }
+    let (plans, skipped_tables) = plan_tables(sqlite, pg, &opts.tables).await?;
+    let mut progress = Progress::load(opts)?;
+    progress.direction = Some(opts.direction);
+
+    let mut tables = Vec::with_capacity(plans.len());
+    for plan in &plans {
+        let source_rows = count_rows(sqlite, pg, plan, opts.direction, Side::Source).await?;
+        let dest_rows_before = count_rows(sqlite, pg, plan, opts.direction, Side::Dest).await?;
+        let started = progress.copied.contains_key(&plan.name);
+        let mut copied = progress.copied.get(&plan.name).copied().unwrap_or(0);
+        let mut cursor = progress.cursors.get(&plan.name).cloned();
+
+        if !opts.dry_run {
+            if started && !plan.keyed && copied < source_rows {
+                log::warn!(
+                    "migrate-storage: {} has no primary or unique key; clearing the partial copy and starting over",
+                    plan.name
+                );
+                clear_table(sqlite, pg, plan, opts.direction).await?;
+                copied = 0;
+                cursor = None;
+                progress.cursors.remove(&plan.name);
+            }
+            if copied < source_rows {
+                // Mark the table as started before its first write.
+                progress.copied.insert(plan.name.clone(), copied);
+                progress.save(opts)?;
+            }
+            let on_pg = is_pg(opts.direction, Side::Source);
+            let batch_size = match opts.direction {
+                Direction::SqliteToPostgres => opts.batch_size.min(PG_MAX_PARAMS / plan.columns.len().max(1)),
+                Direction::PostgresToSqlite => opts.batch_size,
+            };
+            while copied < source_rows {
+                let (batch, last) = read_page(sqlite, pg, plan, on_pg, cursor.as_deref(), batch_size).await?;
+                if batch.is_empty() {
+                    break;
+                }
+                write_batch(sqlite, pg, plan, opts.direction, &batch).await?;
+                copied += batch.len() as u64;
+                cursor = last;
+                progress.copied.insert(plan.name.clone(), copied);
+                if let Some(cursor) = &cursor {
+                    progress.cursors.insert(plan.name.clone(), cursor.clone());
+                }
+                progress.save(opts)?;
+            }
+            if opts.direction == Direction::SqliteToPostgres {
+                reset_sequences(pg, plan).await?;
+            }
+            log::info!("migrate-storage: {} copied {} row(s)", plan.name, copied);
+        }
+
+        let (dest_rows, dest_checksum, source_checksum) = if opts.dry_run {
+            (dest_rows_before, String::new(), String::new())
+        } else {
+            (
+                count_rows(sqlite, pg, plan, opts.direction, Side::Dest).await?,
+                table_checksum(sqlite, pg, plan, is_pg(opts.direction, Side::Dest), opts.batch_size).await?,
+                table_checksum(sqlite, pg, plan, is_pg(opts.direction, Side::Source), opts.batch_size).await?,
+            )
+        };
+        tables.push(TableReport {
+            table: plan.name.clone(),
+            source_rows,
+            dest_rows_before,
+            copied,
+            dest_rows,
+            source_checksum,
+            dest_checksum,
+            skipped_columns: plan.skipped_columns.clone(),
+        });
+    }
+
+    Ok(TransferReport {
+        direction: opts.direction,
+        dry_run: opts.dry_run,
+        tables,
+        skipped_tables,
+    })
+}
+
+/// One row as canonical text, in plan column order.
+type CanonicalRow = Vec<Option<String>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Source,
+    Dest,
+}
+
+/// Which backend `side` is for `direction`: `true` for Postgres.
+fn is_pg(direction: Direction, side: Side) -> bool {
+    matches!(
+        (direction, side),
+        (Direction::SqliteToPostgres, Side::Dest) | (Direction::PostgresToSqlite, Side::Source)
+    )
+}
+
+fn quote(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+async fn plan_tables(
+    sqlite: &DbPool,
+    pg: &PgPool,
+    only: &[String],
+) -> Result<(Vec<TablePlan>, Vec<(String, &'static str)>)> {
+    let column_rows = sqlx::query(
+        "SELECT table_name::text, column_name::text, data_type::text, udt_name::text,
+                COALESCE(column_default LIKE 'nextval(%', false) AS serial
+         FROM information_schema.columns
+         WHERE table_schema = current_schema()
+         ORDER BY table_name, ordinal_position",
+    )
+    .fetch_all(pg)
+    .await
+    .context("read postgres columns")?;
+    let mut pg_columns: BTreeMap<String, Vec<Column>> = BTreeMap::new();
+    for row in column_rows {
+        let data_type: String = row.get(2);
+        pg_columns.entry(row.get(0)).or_default().push(Column {
+            name: row.get(1),
+            class: ColumnClass::from_pg(&data_type),
+            udt_name: row.get(3),
+            serial: row.get(4),
+        });
+    }
+
+    let key_rows = sqlx::query(
+        "SELECT tc.table_name::text, tc.constraint_name::text, kcu.column_name::text
+         FROM information_schema.table_constraints tc
+         JOIN information_schema.key_column_usage kcu
+           ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+         WHERE tc.constraint_type IN ('PRIMARY KEY', 'UNIQUE') AND tc.table_schema = current_schema()",
+    )
+    .fetch_all(pg)
+    .await
+    .context("read postgres keys")?;
+    // table -> constraint -> columns
+    let mut keys: HashMap<String, HashMap<String, Vec<String>>> = HashMap::new();
+    for row in key_rows {
+        keys.entry(row.get(0))
+            .or_default()
+            .entry(row.get(1))
+            .or_default()
+            .push(row.get(2));
+    }
+
+    let fk_rows = sqlx::query(
+        "SELECT DISTINCT tc.table_name::text, ccu.table_name::text
+         FROM information_schema.table_constraints tc
+         JOIN information_schema.constraint_column_usage ccu
+           ON tc.constraint_name = ccu.constraint_name AND tc.table_schema = ccu.table_schema
+         WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_schema = current_schema()",
+    )
+    .fetch_all(pg)
+    .await
+    .context("read postgres foreign keys")?;
+    let mut parents: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for row in fk_rows {
+        let (child, parent): (String, String) = (row.get(0), row.get(1));
+        if child != parent {
+            parents.entry(child).or_default().insert(parent);
+        }
+    }
+
+    let conn = get_connection(sqlite).context("sqlite connection")?;
+    let sqlite_tables: BTreeSet<String> = {
+        let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type = 'table'")?;
+        stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?
+    };
+
+    let mut skipped_tables = Vec::new();
+    let mut plans = BTreeMap::new();
+    let wanted = |name: &str| only.is_empty() || only.iter().any(|t| t == name);
+    for (table, columns) in pg_columns {
+        if SKIPPED_TABLES.contains(&table.as_str()) || !wanted(&table) {
+            continue;
+        }
+        if !sqlite_tables.contains(&table) {
+            skipped_tables.push((table, "postgres"));
+            continue;
+        }
+        let sqlite_columns: BTreeSet<String> = {
+            let mut stmt = conn.prepare(&format!("SELECT name FROM pragma_table_info({})", sql_string(&table)))?;
+            stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?
+        };
+        let mut skipped_columns: Vec<String> = columns
+            .iter()
+            .filter(|c| !sqlite_columns.contains(&c.name))
+            .map(|c| format!("{} (postgres only)", c.name))
+            .collect();
+        skipped_columns.extend(
+            sqlite_columns
+                .iter()
+                .filter(|name| !columns.iter().any(|c| &c.name == *name))
+                .map(|name| format!("{} (sqlite only)", name)),
+        );
+        let columns: Vec<Column> = columns
+            .into_iter()
+            .filter(|c| sqlite_columns.contains(&c.name))
+            .collect();
+        let keyed = keys.remove(&table).is_some_and(|constraints| {
+            constraints
+                .values()
+                .any(|key| key.iter().all(|c| columns.iter().any(|col| &col.name == c)))
+        });
+        plans.insert(
+            table.clone(),
+            TablePlan {
+                name: table,
+                columns,
+                keyed,
+                skipped_columns,
+            },
+        );
+    }
+    for table in sqlite_tables {
+        if !plans.contains_key(&table)
+            && !SKIPPED_TABLES.contains(&table.as_str())
+            && !table.starts_with("sqlite_")
+            && wanted(&table)
+            && !skipped_tables.iter().any(|(t, _)| *t == table)
+        {
+            skipped_tables.push((table, "sqlite"));
+        }
+    }
+
+    let order = parents_first(plans.keys().cloned().collect(), &parents);
+    let plans = order.into_iter().filter_map(|t| plans.remove(&t)).collect();
+    Ok((plans, skipped_tables))
+}
+
+/// Topological order (parents before children); cycles fall back to name order.
+fn parents_first(tables: Vec<String>, parents: &HashMap<String, BTreeSet<String>>) -> Vec<String> {
+    let mut ordered: Vec<String> = Vec::with_capacity(tables.len());
+    let mut remaining: Vec<String> = tables;
+    while !remaining.is_empty() {
+        let ready: Vec<String> = remaining
+            .iter()
+            .filter(|t| {
+                parents
+                    .get(*t)
+                    .is_none_or(|ps| ps.iter().all(|p| ordered.contains(p) || !remaining.contains(p)))
+            })
+            .cloned()
+            .collect();
+        let next = if ready.is_empty() {
+            vec![remaining[0].clone()]
+        } else {
+            ready
+        };
+        remaining.retain(|t| !next.contains(t));
+        ordered.extend(next);
+    }
+    ordered
+}
+
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+async fn count_rows(sqlite: &DbPool, pg: &PgPool, plan: &TablePlan, direction: Direction, side: Side) -> Result<u64> {
+    let sql = format!("SELECT COUNT(*) FROM {}", quote(&plan.name));
+    let count: i64 = if is_pg(direction, side) {
+        sqlx::query_scalar(&sql).fetch_one(pg).await?
+    } else {
+        let conn = get_connection(sqlite).context("sqlite connection")?;
+        conn.query_row(&sql, [], |row| row.get(0))?
+    };
+    Ok(count as u64)
+}
+
+/// Postgres select expression producing the canonical text of a column.
+fn pg_select_expr(column: &Column) -> String {
+    let name = quote(&column.name);
+    match column.class {
+        ColumnClass::TimestampTz => format!("to_char({} AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')", name),
+        ColumnClass::Timestamp => format!("to_char({}, 'YYYY-MM-DD HH24:MI:SS')", name),
+        ColumnClass::Boolean => format!("({}::int)::text", name),
+        ColumnClass::Binary => format!("encode({}, 'hex')", name),
+        _ => format!("{}::text", name),
+    }
+}
+
+/// Postgres value expression for bind parameter `n` holding canonical text.
+fn pg_insert_expr(column: &Column, n: usize) -> String {
+    match column.class {
+        ColumnClass::TimestampTz => format!("(${}::text::timestamp AT TIME ZONE 'UTC')", n),
+        ColumnClass::Binary => format!("decode(${}::text, 'hex')", n),
+        _ => format!("${}::text::{}", n, column.udt_name),
+    }
+}
+
+/// Page query after a `ctid` bound in `$1` (or from the start), in `ctid` order.
+fn pg_page_sql(plan: &TablePlan, from_start: bool, limit: usize) -> String {
+    let exprs: Vec<String> = plan.columns.iter().map(pg_select_expr).collect();
+    format!(
+        "SELECT ctid::text, {} FROM {} {}ORDER BY ctid LIMIT {}",
+        exprs.join(", "),
+        quote(&plan.name),
+        if from_start { "" } else { "WHERE ctid > $1::text::tid " },
+        limit
+    )
+}
+
+/// Page query after the `rowid` in `?1`, in `rowid` order.
+fn sqlite_page_sql(plan: &TablePlan, limit: usize) -> String {
+    let names: Vec<String> = plan.columns.iter().map(|c| quote(&c.name)).collect();
+    format!(
+        "SELECT rowid, {} FROM {} WHERE rowid > ?1 ORDER BY rowid LIMIT {}",
+        names.join(", "),
+        quote(&plan.name),
+        limit
+    )
+}
+
+/// Up to `limit` rows after position `after` (from the start when `None`),
+/// with the position of the last one.
+async fn read_page(
+    sqlite: &DbPool,
+    pg: &PgPool,
+    plan: &TablePlan,
+    on_pg: bool,
+    after: Option<&str>,
+    limit: usize,
+) -> Result<(Vec<CanonicalRow>, Option<String>)> {
+    if on_pg {
+        read_pg_page(pg, plan, after, limit).await
+    } else {
+        read_sqlite_page(sqlite, plan, after, limit)
+    }
+}
+
+async fn read_pg_page(
+    pg: &PgPool,
+    plan: &TablePlan,
+    after: Option<&str>,
+    limit: usize,
+) -> Result<(Vec<CanonicalRow>, Option<String>)> {
+    let sql = pg_page_sql(plan, after.is_none(), limit);
+    let mut query = sqlx::query(&sql);
+    if let Some(after) = after {
+        query = query.bind(after);
+    }
+    let rows = query
+        .fetch_all(pg)
+        .await
+        .with_context(|| format!("read {} from postgres", plan.name))?;
+    let last = rows.last().map(|row| row.try_get::<String, _>(0)).transpose()?;
+    let rows = rows
+        .iter()
+        .map(|row| {
+            plan.columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| -> Result<Option<String>> {
+                    let value: Option<String> = row.try_get(i + 1)?;
+                    match (column.class, value) {
+                        (ColumnClass::Float, Some(v)) => canonical_float(&v).map(Some),
+                        (_, value) => Ok(value),
+                    }
+                })
+                .collect()
+        })
+        .collect::<Result<_>>()?;
+    Ok((rows, last))
+}
+
+fn read_sqlite_page(
+    sqlite: &DbPool,
+    plan: &TablePlan,
+    after: Option<&str>,
+    limit: usize,
+) -> Result<(Vec<CanonicalRow>, Option<String>)> {
+    let after: i64 = match after {
+        Some(rowid) => rowid.parse().with_context(|| format!("bad rowid cursor: {}", rowid))?,
+        None => i64::MIN,
+    };
+    let conn = get_connection(sqlite).context("sqlite connection")?;
+    let mut stmt = conn.prepare(&sqlite_page_sql(plan, limit))?;
+    let mut rows = stmt.query([after])?;
+    let mut out = Vec::new();
+    let mut last = None;
+    while let Some(row) = rows.next()? {
+        last = Some(row.get::<_, i64>(0)?);
+        let mut values = Vec::with_capacity(plan.columns.len());
+        for (i, column) in plan.columns.iter().enumerate() {
+            let value: Value = row.get(i + 1)?;
+            values
+                .push(canonical_sqlite(value, column.class).with_context(|| format!("{}.{}", plan.name, column.name))?);
+        }
+        out.push(values);
+    }
+    Ok((out, last.map(|rowid| rowid.to_string())))
+}
+
+/// Empty the destination copy of `plan` (used to restart keyless tables).
+async fn clear_table(sqlite: &DbPool, pg: &PgPool, plan: &TablePlan, direction: Direction) -> Result<()> {
+    let sql = format!("DELETE FROM {}", quote(&plan.name));
+    if is_pg(direction, Side::Dest) {
+        sqlx::query(&sql)
+            .execute(pg)
+            .await
+            .with_context(|| format!("clear postgres {}", plan.name))?;
+    } else {
+        let conn = get_connection(sqlite).context("sqlite connection")?;
+        conn.execute(&sql, [])
+            .with_context(|| format!("clear sqlite {}", plan.name))?;
+    }
+    Ok(())
+}
+
+async fn write_batch(
+    sqlite: &DbPool,
+    pg: &PgPool,
+    plan: &TablePlan,
+    direction: Direction,
+    batch: &[CanonicalRow],
+) -> Result<()> {
+    let names: Vec<String> = plan.columns.iter().map(|c| quote(&c.name)).collect();
+    match direction {
+        Direction::SqliteToPostgres => {
+            let width = plan.columns.len();
+            let tuples: Vec<String> = (0..batch.len())
+                .map(|r| {
+                    let exprs: Vec<String> = plan
+                        .columns
+                        .iter()
+                        .enumerate()
+                        .map(|(c, column)| pg_insert_expr(column, r * width + c + 1))
+                        .collect();
+                    format!("({})", exprs.join(", "))
+                })
+                .collect();
+            let sql = format!(
+                "INSERT INTO {} ({}) VALUES {} ON CONFLICT DO NOTHING",
+                quote(&plan.name),
+                names.join(", "),
+                tuples.join(", ")
+            );
+            let mut query = sqlx::query(&sql);
+            for value in batch.iter().flatten() {
+                query = query.bind(value.as_deref());
+            }
+            query
+                .execute(pg)
+                .await
+                .with_context(|| format!("insert into postgres {}", plan.name))?;
+        }
+        Direction::PostgresToSqlite => write_sqlite_batch(sqlite, plan, batch)?,
+    }
+    Ok(())
+}
+
+fn write_sqlite_batch(sqlite: &DbPool, plan: &TablePlan, batch: &[CanonicalRow]) -> Result<()> {
+    let names: Vec<String> = plan.columns.iter().map(|c| quote(&c.name)).collect();
+    let placeholders = vec!["?"; names.len()].join(", ");
+    let sql = format!(
+        "INSERT OR IGNORE INTO {} ({}) VALUES ({})",
+        quote(&plan.name),
+        names.join(", "),
+        placeholders
+    );
+    let mut conn = get_connection(sqlite).context("sqlite connection")?;
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare(&sql)?;
+        for row in batch {
+            let values = row
+                .iter()
+                .zip(&plan.columns)
+                .map(|(value, column)| sqlite_value(value.as_deref(), column.class))
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| format!("convert row for sqlite {}", plan.name))?;
+            stmt.execute(rusqlite::params_from_iter(values))
+                .with_context(|| format!("insert into sqlite {}", plan.name))?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Move `BIGSERIAL` sequences past the ids copied in explicitly.
+async fn reset_sequences(pg: &PgPool, plan: &TablePlan) -> Result<()> {
+    for column in plan.columns.iter().filter(|c| c.serial) {
+        let sql = format!(
+            "SELECT setval(pg_get_serial_sequence($1, $2), COALESCE((SELECT MAX({col}) FROM {table}), 0) + 1, false)",
+            col = quote(&column.name),
+            table = quote(&plan.name)
+        );
+        sqlx::query(&sql)
+            .bind(&plan.name)
+            .bind(&column.name)
+            .execute(pg)
+            .await
+            .with_context(|| format!("reset sequence {}.{}", plan.name, column.name))?;
+    }
+    Ok(())
+}
+
+/// Order-independent checksum: wrapping sum of per-row SHA-256 prefixes.
+/// Collation differences between the backends therefore don't matter, and
+/// the table can be read in batches instead of all at once.
+async fn table_checksum(
+    sqlite: &DbPool,
+    pg: &PgPool,
+    plan: &TablePlan,
+    on_pg: bool,
+    batch_size: usize,
+) -> Result<String> {
+    let mut sum = 0u128;
+    let mut cursor: Option<String> = None;
+    loop {
+        let (rows, last) = read_page(sqlite, pg, plan, on_pg, cursor.as_deref(), batch_size)
+            .await
+            .with_context(|| format!("checksum {}", plan.name))?;
+        if rows.is_empty() {
+            break;
+        }
+        sum = add_rows(sum, &rows);
+        cursor = last;
+    }
+    Ok(format!("{:032x}", sum))
+}
+
+fn add_rows(sum: u128, rows: &[CanonicalRow]) -> u128 {
+    rows.iter().fold(sum, |acc, row| acc.wrapping_add(row_hash(row)))
+}
+
+fn row_hash(row: &[Option<String>]) -> u128 {
+    let mut hasher = Sha256::new();
+    for value in row {
+        match value {
+            None => hasher.update(b"N"),
+            Some(v) => {
+                hasher.update(b"S");
+                hasher.update((v.len() as u64).to_le_bytes());
+                hasher.update(v.as_bytes());
+            }
+        }
+    }
+    let digest = hasher.finalize();
+    let mut prefix = [0u8; 16];
+    prefix.copy_from_slice(&digest[..16]);
+    u128::from_le_bytes(prefix)
+}
+
+fn canonical_float(value: &str) -> Result<String> {
+    let f: f64 = value
+        .trim()
+        .parse()
+        .with_context(|| format!("not a number: {}", value))?;
+    Ok(f.to_string())
+}
+
+fn canonical_timestamp(value: &str) -> Result<String> {
+    let value = value.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Ok(t.with_timezone(&Utc).format(CANONICAL_TIMESTAMP).to_string());
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M"] {
+        if let Ok(t) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(t.format(CANONICAL_TIMESTAMP).to_string());
+        }
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date
+            .and_time(chrono::NaiveTime::MIN)
+            .format(CANONICAL_TIMESTAMP)
+            .to_string());
+    }
+    bail!("unrecognised timestamp: {}", value)
+}
+
+/// Canonical text of a SQLite value destined for a column of `class`.
+fn canonical_sqlite(value: Value, class: ColumnClass) -> Result<Option<String>> {
+    Ok(Some(match (class, value) {
+        (_, Value::Null) => return Ok(None),
+        (ColumnClass::Integer, Value::Integer(i)) => i.to_string(),
+        (ColumnClass::Integer, Value::Real(f)) if f.fract() == 0.0 => (f as i64).to_string(),
+        (ColumnClass::Integer, Value::Text(s)) => s
+            .trim()
+            .parse::<i64>()
+            .with_context(|| format!("not an integer: {}", s))?
+            .to_string(),
+        (ColumnClass::Boolean, Value::Integer(i)) => if i != 0 { "1" } else { "0" }.to_string(),
+        (ColumnClass::Boolean, Value::Text(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "1" | "t" | "true" => "1".to_string(),
+            "0" | "f" | "false" => "0".to_string(),
+            other => bail!("not a boolean: {}", other),
+        },
+        (ColumnClass::TimestampTz | ColumnClass::Timestamp, Value::Integer(secs)) => DateTime::from_timestamp(secs, 0)
+            .with_context(|| format!("timestamp out of range: {}", secs))?
+            .format(CANONICAL_TIMESTAMP)
+            .to_string(),
+        (ColumnClass::TimestampTz | ColumnClass::Timestamp, Value::Text(s)) => canonical_timestamp(&s)?,
+        (ColumnClass::Float, Value::Real(f)) => f.to_string(),
+        (ColumnClass::Float, Value::Integer(i)) => (i as f64).to_string(),
+        (ColumnClass::Float, Value::Text(s)) => canonical_float(&s)?,
+        (ColumnClass::Text, Value::Text(s)) => s,
+        (ColumnClass::Text, Value::Integer(i)) => i.to_string(),
+        (ColumnClass::Text, Value::Real(f)) => f.to_string(),
+        (ColumnClass::Binary, Value::Blob(bytes)) => hex::encode(bytes),
+        (ColumnClass::Binary, Value::Text(s)) => hex::encode(s.as_bytes()),
+        (_, Value::Blob(_)) => bail!("BLOB value in a non-binary column"),
+        (class, value) => bail!("cannot map {:?} to {:?}", value, class),
+    }))
+}
+
+/// SQLite value for canonical text in a column of `class`.
+fn sqlite_value(value: Option<&str>, class: ColumnClass) -> Result<Value> {
+    let Some(value) = value else { return Ok(Value::Null) };
+    Ok(match class {
+        ColumnClass::Integer | ColumnClass::Boolean => {
+            Value::Integer(value.parse().with_context(|| format!("not an integer: {}", value))?)
+        }
+        ColumnClass::Float => Value::Real(value.parse().with_context(|| format!("not a number: {}", value))?),
+        ColumnClass::Binary => Value::Blob(hex::decode(value).with_context(|| format!("not hex: {}", value))?),
+        _ => Value::Text(value.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::create_pool;
+
+    fn column(name: &str, data_type: &str, udt_name: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            class: ColumnClass::from_pg(data_type),
+            udt_name: udt_name.to_string(),
+            serial: false,
+        }
+    }
+
+    #[test]
+    fn test_canonical_sqlite_values() {
+        use ColumnClass::*;
+        let c = |v, class| canonical_sqlite(v, class).unwrap();
+        assert_eq!(c(Value::Integer(42), Integer), Some("42".into()));
+        assert_eq!(c(Value::Text(" 7 ".into()), Integer), Some("7".into()));
+        assert_eq!(c(Value::Integer(5), Boolean), Some("1".into()));
+        assert_eq!(c(Value::Text("false".into()), Boolean), Some("0".into()));
+        assert_eq!(c(Value::Null, TimestampTz), None);
+        assert_eq!(
+            c(Value::Text("2026-03-01 12:30:45".into()), TimestampTz),
+            Some("2026-03-01 12:30:45".into())
+        );
+        assert_eq!(
+            c(Value::Text("2026-03-01T12:30:45.123+02:00".into()), TimestampTz),
+            Some("2026-03-01 10:30:45".into())
+        );
+        assert_eq!(c(Value::Integer(0), TimestampTz), Some("1970-01-01 00:00:00".into()));
+        assert_eq!(c(Value::Real(1.5), Float), Some("1.5".into()));
+        assert!(canonical_sqlite(Value::Text("yesterday".into()), TimestampTz).is_err());
+        assert_eq!(c(Value::Blob(vec![0x00, 0xab]), Binary), Some("00ab".into()));
+        assert!(canonical_sqlite(Value::Blob(vec![1]), Text).is_err());
+    }
+
+    #[test]
+    fn test_sqlite_value_roundtrip() {
+        assert_eq!(
+            sqlite_value(Some("12"), ColumnClass::Integer).unwrap(),
+            Value::Integer(12)
+        );
+        assert_eq!(
+            sqlite_value(Some("1"), ColumnClass::Boolean).unwrap(),
+            Value::Integer(1)
+        );
+        assert_eq!(sqlite_value(None, ColumnClass::Text).unwrap(), Value::Null);
+        assert_eq!(
+            sqlite_value(Some("00ab"), ColumnClass::Binary).unwrap(),
+            Value::Blob(vec![0x00, 0xab])
+        );
+        assert!(sqlite_value(Some("\\x00ab"), ColumnClass::Binary).is_err());
+        assert_eq!(
+            sqlite_value(Some("2026-03-01 12:30:45"), ColumnClass::TimestampTz).unwrap(),
+            Value::Text("2026-03-01 12:30:45".into())
+        );
+    }
+
+    #[test]
+    fn test_pg_expressions() {
+        let ts = column("created_at", "timestamp with time zone", "timestamptz");
+        assert_eq!(
+            pg_select_expr(&ts),
+            "to_char(\"created_at\" AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')"
+        );
+        assert_eq!(pg_insert_expr(&ts, 3), "($3::text::timestamp AT TIME ZONE 'UTC')");
+        let id = column("user_id", "bigint", "int8");
+        assert_eq!(pg_select_expr(&id), "\"user_id\"::text");
+        assert_eq!(pg_insert_expr(&id, 1), "$1::text::int8");
+        let flag = column("is_active", "boolean", "bool");
+        assert_eq!(pg_select_expr(&flag), "(\"is_active\"::int)::text");
+        let hash = column("token_hash", "bytea", "bytea");
+        assert_eq!(pg_select_expr(&hash), "encode(\"token_hash\", 'hex')");
+        assert_eq!(pg_insert_expr(&hash, 2), "decode($2::text, 'hex')");
+    }
+
+    #[test]
+    fn test_checksum_is_order_independent() {
+        let a = vec![Some("1".to_string()), None];
+        let b = vec![Some("2".to_string()), Some("x".to_string())];
+        assert_eq!(
+            add_rows(0, &[a.clone(), b.clone()]),
+            add_rows(0, &[b.clone(), a.clone()])
+        );
+        assert_ne!(add_rows(0, &[a.clone()]), add_rows(0, &[a.clone(), a.clone()]));
+        // Summing batch by batch matches summing the whole table.
+        assert_eq!(
+            add_rows(add_rows(0, &[b.clone()]), &[a.clone()]),
+            add_rows(0, &[a.clone(), b.clone()])
+        );
+        // NULL and empty string must not collide.
+        assert_ne!(add_rows(0, &[vec![None]]), add_rows(0, &[vec![Some(String::new())]]));
+    }
+
+    #[test]
+    fn test_sqlite_pages_by_rowid() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pool = create_pool(dir.path().join("transfer.db").to_string_lossy().as_ref()).unwrap();
+        let conn = get_connection(&pool).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE transfer_pages (v TEXT);
+             INSERT INTO transfer_pages (v) VALUES ('a'), ('b'), ('c'), ('d'), ('e');
+             DELETE FROM transfer_pages WHERE v = 'b';",
+        )
+        .unwrap();
+        let plan = TablePlan {
+            name: "transfer_pages".to_string(),
+            columns: vec![column("v", "text", "text")],
+            keyed: false,
+            skipped_columns: Vec::new(),
+        };
+
+        let mut seen = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let (rows, last) = read_sqlite_page(&pool, &plan, cursor.as_deref(), 2).unwrap();
+            if rows.is_empty() {
+                break;
+            }
+            assert!(rows.len() <= 2);
+            seen.extend(rows.into_iter().map(|row| row[0].clone().unwrap()));
+            cursor = last;
+        }
+        assert_eq!(seen, ["a", "c", "d", "e"]);
+    }
+
+    fn plan(name: &str, columns: &[(&str, &str, &str)]) -> TablePlan {
+        TablePlan {
+            name: name.to_string(),
+            columns: columns.iter().map(|(n, t, u)| column(n, t, u)).collect(),
+            keyed: true,
+            skipped_columns: Vec::new(),
+        }
+    }
+
+    /// Copy `plan` from `src` into `dst` through canonical rows, as
+    /// `PostgresToSqlite` writes them, and check both sides checksum the same.
+    fn assert_roundtrip(plan: &TablePlan, src: &DbPool, dst: &DbPool) -> Vec<CanonicalRow> {
+        let (rows, _) = read_sqlite_page(src, plan, None, 1000).unwrap();
+        assert!(!rows.is_empty());
+        write_sqlite_batch(dst, plan, &rows).unwrap();
+        let (copied, _) = read_sqlite_page(dst, plan, None, 1000).unwrap();
+        assert_eq!(add_rows(0, &copied), add_rows(0, &rows));
+        rows
+    }
+
+    #[test]
+    fn test_portal_sessions_roundtrip() {
+        use crate::storage::db::{create_portal_session, get_portal_session};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = create_pool(dir.path().join("src.db").to_string_lossy().as_ref()).unwrap();
+        let dst = create_pool(dir.path().join("dst.db").to_string_lossy().as_ref()).unwrap();
+        let hash = [0x00, 0xff, 0x10, 0x7f];
+        create_portal_session(
+            &get_connection(&src).unwrap(),
+            &hash,
+            7,
+            "csrf-1",
+            24,
+            Some("agent"),
+            None,
+        )
+        .unwrap();
+
+        // Column types as PG55 declares them.
+        let plan = plan(
+            "portal_sessions",
+            &[
+                ("token_hash", "bytea", "bytea"),
+                ("user_id", "bigint", "int8"),
+                ("csrf_token", "text", "text"),
+                ("created_at", "timestamp with time zone", "timestamptz"),
+                ("expires_at", "timestamp with time zone", "timestamptz"),
+                ("last_seen", "timestamp with time zone", "timestamptz"),
+                ("user_agent", "text", "text"),
+                ("ip", "text", "text"),
+            ],
+        );
+        let rows = assert_roundtrip(&plan, &src, &dst);
+        assert_eq!(rows[0][0].as_deref(), Some("00ff107f"));
+        let session = get_portal_session(&get_connection(&dst).unwrap(), &hash)
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.user_id, 7);
+    }
+
+    #[test]
+    fn test_api_keys_roundtrip() {
+        use crate::storage::db::{create_api_key, get_api_key_by_hash};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = create_pool(dir.path().join("src.db").to_string_lossy().as_ref()).unwrap();
+        let dst = create_pool(dir.path().join("dst.db").to_string_lossy().as_ref()).unwrap();
+        let hash = Sha256::digest(b"dora_live_key").to_vec();
+        let id = create_api_key(&get_connection(&src).unwrap(), 7, "laptop", &hash, "dora_liv").unwrap();
+
+        // Column types as PG57 declares them.
+        let plan = plan(
+            "api_keys",
+            &[
+                ("id", "bigint", "int8"),
+                ("user_id", "bigint", "int8"),
+                ("name", "text", "text"),
+                ("key_hash", "bytea", "bytea"),
+                ("key_prefix", "text", "text"),
+                ("created_at", "timestamp with time zone", "timestamptz"),
+                ("last_used_at", "timestamp with time zone", "timestamptz"),
+                ("revoked_at", "timestamp with time zone", "timestamptz"),
+            ],
+        );
+        let rows = assert_roundtrip(&plan, &src, &dst);
+        assert_eq!(rows[0][3], Some(hex::encode(&hash)));
+        // The copied key still authenticates.
+        let key = get_api_key_by_hash(&get_connection(&dst).unwrap(), &hash)
+            .unwrap()
+            .unwrap();
+        assert_eq!((key.id, key.user_id), (id, 7));
+    }
+
+    #[test]
+    fn test_admin_sessions_roundtrip() {
+        use crate::storage::db::{create_admin_session, get_admin_session};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = create_pool(dir.path().join("src.db").to_string_lossy().as_ref()).unwrap();
+        let dst = create_pool(dir.path().join("dst.db").to_string_lossy().as_ref()).unwrap();
+        let hash = Sha256::digest(b"admin-cookie").to_vec();
+        create_admin_session(
+            &get_connection(&src).unwrap(),
+            &hash,
+            42,
+            "csrf-1",
+            24,
+            None,
+            Some("10.0.0.1"),
+        )
+        .unwrap();
+
+        // Column types as PG59 declares them.
+        let plan = plan(
+            "admin_sessions",
+            &[
+                ("token_hash", "bytea", "bytea"),
+                ("admin_id", "bigint", "int8"),
+                ("csrf_token", "text", "text"),
+                ("created_at", "timestamp with time zone", "timestamptz"),
+                ("expires_at", "timestamp with time zone", "timestamptz"),
+                ("last_seen", "timestamp with time zone", "timestamptz"),
+                ("user_agent", "text", "text"),
+                ("ip", "text", "text"),
+            ],
+        );
+        let rows = assert_roundtrip(&plan, &src, &dst);
+        assert_eq!(rows[0][0], Some(hex::encode(&hash)));
+        let session = get_admin_session(&get_connection(&dst).unwrap(), &hash)
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.admin_id, 42);
+    }
+
+    #[test]
+    fn test_parents_first() {
+        let parents = HashMap::from([
+            ("charges".to_string(), BTreeSet::from(["users".to_string()])),
+            ("playlist_items".to_string(), BTreeSet::from(["playlists".to_string()])),
+            ("playlists".to_string(), BTreeSet::from(["users".to_string()])),
+        ]);
+        let order = parents_first(
+            vec![
+                "charges".to_string(),
+                "playlist_items".to_string(),
+                "playlists".to_string(),
+                "users".to_string(),
+            ],
+            &parents,
+        );
+        let pos = |t: &str| order.iter().position(|o| o == t).unwrap();
+        assert!(pos("users") < pos("charges"));
+        assert!(pos("users") < pos("playlists"));
+        assert!(pos("playlists") < pos("playlist_items"));
+        assert_eq!(order.len(), 4);
+    }
+
+    #[test]
+    fn test_progress_rejects_other_direction() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut opts = TransferOptions {
+            direction: Direction::SqliteToPostgres,
+            batch_size: 100,
+            dry_run: false,
+            state_path: dir.path().join("state.json"),
+            restart: false,
+            tables: Vec::new(),
+        };
+        let progress = Progress {
+            direction: Some(Direction::SqliteToPostgres),
+            copied: BTreeMap::from([("users".to_string(), 10)]),
+        };
+        progress.save(&opts).unwrap();
+        assert_eq!(Progress::load(&opts).unwrap().copied["users"], 10);
+
+        opts.direction = Direction::PostgresToSqlite;
+        assert!(Progress::load(&opts).is_err());
+        opts.restart = true;
+        assert!(Progress::load(&opts).unwrap().copied.is_empty());
+    }
+}
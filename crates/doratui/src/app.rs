@@ -1,19 +1,28 @@
 //! Application state for the dora TUI.
 
 use std::collections::{HashMap, VecDeque};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use chrono::Local;
 use ratatui::layout::Rect;
 use ratatui::style::Color;
 
+use crate::download_options::DownloadOptions;
+use crate::history_db::{HistoryDb, HistoryFilters, HistoryQuery, HistoryStats};
+use crate::keymap::Keymap;
+use crate::player::{Player, PlayerEvent, Track};
+use crate::playlist_import::ImportList;
 use crate::settings::DoraSettings;
-use crate::theme::{ThemeColors, palette};
+use crate::subscriptions::{self, Checker, Subscription};
+use crate::theme::{PaletteOverrides, ThemeColors};
 
 /// Re-export LogoScheme so that `ui/logo.rs` and other modules can import from `crate::app`.
 pub use crate::theme::LogoScheme;
 use crate::video_info::{ThumbnailArt, VideoInfo};
 
+/// Days charted in the history Stats panel.
+const STATS_DAYS: usize = 30;
+
 /// Which tab is currently active.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Tab {
@@ -21,6 +30,7 @@ pub enum Tab {
     Downloads = 0,
     Lyrics = 1,
     Settings = 2,
+    Subscriptions = 3,
 }
 
 impl Tab {
@@ -29,6 +39,7 @@ impl Tab {
             Tab::Downloads => "[1] ⬇  Downloads",
             Tab::Lyrics => "[2] 🎵 Lyrics",
             Tab::Settings => "[3] ⚙  Settings",
+            Tab::Subscriptions => "[4] 📡 Subscriptions",
         }
     }
 
@@ -57,6 +68,25 @@ impl PreviewState {
     }
 }
 
+/// Visibility / loading state of the playlist & batch import checklist.
+#[derive(Debug, Clone, Default)]
+pub enum ImportState {
+    #[default]
+    Hidden,
+    /// Playlist entries are being extracted in the background.
+    Loading { url: String },
+    /// Checklist ready for selection.
+    Ready(ImportList),
+    /// Extraction failed.
+    Failed(String),
+}
+
+impl ImportState {
+    pub fn is_visible(&self) -> bool {
+        !matches!(self, ImportState::Hidden)
+    }
+}
+
 /// State of the yt-dlp startup check/update.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum YtdlpStartup {
@@ -146,6 +176,22 @@ pub enum ClickTarget {
     PreviewToggleSubsEnabled,
     /// Select subtitle language by index.
     PreviewSubsLang(usize),
+    /// Toggle the download options menu (conversion, cut, effects) in preview popup.
+    PreviewToggleOptionsMenu,
+    /// Tick / untick an item in the import checklist (absolute item index).
+    ImportToggleItem(usize),
+    /// Queue the selected import items (same as Enter in the checklist).
+    ImportEnqueue,
+    /// Close the import checklist.
+    ImportClose,
+    /// Answer the startup prompt: restore (`true`) or discard the saved queue.
+    ResumeQueue(bool),
+    /// Select a subscription in the Subscriptions tab (index into the list).
+    SubscriptionSelect(usize),
+    /// Pause / resume the preview player.
+    PlayerToggle,
+    /// Seek the preview player to a point of the track, in thousandths.
+    PlayerSeek(u16),
 }
 
 /// State of a single download slot.
@@ -158,6 +204,15 @@ pub enum SlotState {
         speed_mbs: f64,
         eta_secs: u64,
     },
+    /// Download finished; ffmpeg is muxing the streams. `position_secs` is
+    /// how far into the media it has got.
+    Merging {
+        position_secs: f32,
+    },
+    /// Post-download pass: highres recode, subtitle burn, effects or conversion.
+    Postprocessing {
+        label: &'static str,
+    },
     /// 1-second colour burst animation played when a download completes.
     Celebrating {
         path: String,
@@ -171,6 +226,24 @@ pub enum SlotState {
     },
 }
 
+impl SlotState {
+    /// Finished one way or another — the slot no longer holds a download task.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            SlotState::Celebrating { .. } | SlotState::Done { .. } | SlotState::Failed { .. }
+        )
+    }
+
+    /// Downloading or processing — the slot's task is doing work.
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            SlotState::Downloading { .. } | SlotState::Merging { .. } | SlotState::Postprocessing { .. }
+        )
+    }
+}
+
 /// Output format requested by the user.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub enum DownloadFormat {
@@ -207,11 +280,64 @@ pub struct DownloadSlot {
     pub speed_history: VecDeque<f64>,
     /// Handle to abort the background download task (set when task is spawned).
     pub cancel: Option<tokio::task::AbortHandle>,
+    /// Conversion / cut / effects chosen in the preview options popup.
+    pub options: DownloadOptions,
+    /// Quality picked in the preview popup; `None` uses the settings default.
+    pub video_quality: Option<String>,
+    /// Subtitle language to burn in (MP4 only).
+    pub subtitle_lang: Option<String>,
+    /// Where the download is being written, once the task has chosen it.
+    /// A restored slot resumes into this file instead of starting over.
+    pub output_path: Option<String>,
+    /// Daemon job running this slot when the TUI is attached to `dora daemon`.
+    pub remote_id: Option<usize>,
+}
+
+impl DownloadSlot {
+    /// Snapshot of what is needed to re-queue this slot after a restart.
+    pub fn to_queued(&self) -> QueuedDownload {
+        QueuedDownload {
+            url: self.url.clone(),
+            title: self.title.clone(),
+            artist: self.artist.clone(),
+            thumbnail_url: self.thumbnail_url.clone(),
+            format: self.format,
+            options: self.options.clone(),
+            video_quality: self.video_quality.clone(),
+            subtitle_lang: self.subtitle_lang.clone(),
+            output_path: self.output_path.clone(),
+        }
+    }
+}
+
+/// A pending or interrupted download persisted in `queue.json`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct QueuedDownload {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
+    pub format: DownloadFormat,
+    #[serde(default)]
+    pub options: DownloadOptions,
+    #[serde(default)]
+    pub video_quality: Option<String>,
+    #[serde(default)]
+    pub subtitle_lang: Option<String>,
+    /// Partial file left by the interrupted run, if it had started.
+    #[serde(default)]
+    pub output_path: Option<String>,
 }
 
 /// A completed-download entry kept in history.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HistoryEntry {
+    /// Row id in the history database (0 until stored).
+    #[serde(default)]
+    pub id: i64,
     pub title: String,
     pub artist: String,
     pub format: DownloadFormat,
@@ -224,6 +350,9 @@ pub struct HistoryEntry {
     /// Thumbnail URL for image preview.
     #[serde(default)]
     pub thumbnail_url: Option<String>,
+    /// Options the download was made with (conversion, cut, effects).
+    #[serde(default)]
+    pub options: DownloadOptions,
 }
 
 /// A lyrics search result.
@@ -237,6 +366,11 @@ pub struct LyricsResult {
     pub release_date: Option<String>,
     pub thumbnail_url: Option<String>,
     pub lyrics: String,
+    /// Timed lines when LRC timing is available (LRCLIB), else empty.
+    pub synced: Vec<doracore::lyrics::synced::LrcLine>,
+    /// File the player was on when these lyrics were fetched for it; the
+    /// synced view only highlights while that file plays.
+    pub for_track: Option<std::path::PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -300,6 +434,20 @@ pub struct App {
     pub history_selected: std::collections::HashSet<usize>,
     /// Cached indices of history entries matching the current filter.
     pub history_filtered_indices: Vec<usize>,
+    /// Format / date / platform / size facets narrowing the list.
+    pub history_facets: HistoryFilters,
+    /// Show the Stats panel in place of the history list.
+    pub history_stats_visible: bool,
+    /// Stats for the current filter; `None` until the panel asks for them.
+    history_stats_cache: Option<HistoryStats>,
+    /// Query and revision `history_filtered_indices` was computed for.
+    history_filter_key: Option<(HistoryQuery, u64)>,
+    /// Bumped whenever `history` changes.
+    history_revision: u64,
+    /// Local history database; `history` mirrors its rows.
+    history_db: HistoryDb,
+    /// `data_version` of the database when `history` was last loaded.
+    history_db_version: Option<i64>,
 
     // ── Lyrics tab ────────────────────────────────────────────────────────────
     pub lyrics_query: String,
@@ -351,6 +499,57 @@ pub struct App {
     pub preview_subs_editing: bool,
     /// Text buffer for custom language input.
     pub preview_subs_edit_buf: String,
+    // ── Download options sub-menu in preview ───────────────────────────────────
+    /// Conversion / cut / effects for the download being previewed.
+    pub preview_options: DownloadOptions,
+    /// Whether the options sub-menu is currently showing.
+    pub preview_options_menu: bool,
+    /// Selected row in the options sub-menu (index into `OptionRow::for_format`).
+    pub preview_options_cursor: usize,
+    /// Whether user is typing a cut time.
+    pub preview_options_editing: bool,
+    /// Text buffer for cut time input.
+    pub preview_options_edit_buf: String,
+
+    // ── Playlist / batch import checklist ──────────────────────────────────────
+    pub import_state: ImportState,
+    /// Highlighted row (absolute index into the item list).
+    pub import_cursor: usize,
+    /// Format the selected items are queued as.
+    pub import_format: DownloadFormat,
+    /// Playlist URL waiting to be expanded by the run loop.
+    pub import_pending_url: Option<String>,
+
+    // ── Subscriptions tab ─────────────────────────────────────────────────────
+    /// Followed channels / playlists / feeds, mirrored from `subscriptions.json`.
+    pub subscriptions: Vec<Subscription>,
+    /// Highlighted subscription (index into `subscriptions`).
+    pub subs_cursor: usize,
+    /// Link or @handle being typed into the follow bar.
+    pub sub_input: String,
+    /// Checks started by this TUI (unused while attached to a daemon).
+    pub subs_checker: Checker,
+    /// Modification time of `subscriptions.json` when last read.
+    subs_file_mtime: Option<SystemTime>,
+
+    // ── Preview player ────────────────────────────────────────────────────────
+    pub player: Player,
+    /// Track whose synced lyrics the run loop should fetch.
+    pub synced_lyrics_pending: Option<Track>,
+
+    // ── Persistent queue ──────────────────────────────────────────────────────
+    /// Downloads left over from the last session, shown in the "resume?"
+    /// prompt until the user answers.
+    pub resume_prompt: Option<Vec<QueuedDownload>>,
+    /// Queue as last written to `queue.json` (skip rewriting when unchanged).
+    pub queue_snapshot: Vec<QueuedDownload>,
+
+    // ── Daemon attach ─────────────────────────────────────────────────────────
+    /// True while a `dora daemon` runs the downloads; slots mirror its jobs
+    /// and the daemon owns `queue.json` and records finished downloads.
+    pub daemon_attached: bool,
+    /// Daemon job IDs waiting for the run loop to send a cancel request.
+    pub daemon_pending_cancels: Vec<usize>,
 
     /// True when the run loop should spawn the video-info fetch task.
     pub preview_fetch_needed: bool,
@@ -407,6 +606,16 @@ pub struct App {
     // ── Active theme ──────────────────────────────────────────────────────────
     /// Current Catppuccin colour palette.  Cycled with `[T]`.
     pub theme: ThemeColors,
+    /// Colour overrides from `~/.config/dora/theme.toml`.
+    palette_overrides: PaletteOverrides,
+    /// Modification time of `theme.toml` when last read.
+    theme_file_mtime: Option<SystemTime>,
+    /// When `tick()` last polled the theme and subscription files.
+    config_files_checked: Instant,
+
+    // ── Key bindings ──────────────────────────────────────────────────────────
+    /// Active hotkeys, from `~/.config/dora/keys.toml` over the defaults.
+    pub keymap: Keymap,
 
     // ── Supernova particle system ─────────────────────────────────────────────
     /// Particles spawned when a download completes its 1-second celebration.
@@ -427,12 +636,27 @@ impl App {
             .output()
             .is_ok();
         let now = Instant::now();
-        let theme = palette(settings.theme_flavour);
+        let (keymap, mut config_warnings) = Keymap::load();
+        let (palette_overrides, theme_warnings) = PaletteOverrides::load();
+        config_warnings.extend(theme_warnings);
+        let theme = palette_overrides.palette(settings.theme_flavour);
         let logo_scheme = settings.logo_scheme; // Copy before settings is moved into Self
+        let history_db = match HistoryDb::open_default() {
+            Ok(db) => db,
+            Err(e) => {
+                log::warn!("history: {}", e);
+                config_warnings.push(format!("History database unavailable, not saving history — {}", e));
+                HistoryDb::open_in_memory().expect("in-memory SQLite database")
+            }
+        };
+        let history = history_db.load_all().unwrap_or_else(|e| {
+            log::warn!("history: failed to load: {}", e);
+            Vec::new()
+        });
         let mut app = Self {
             active_tab: Tab::Downloads,
             slots: Vec::new(),
-            history: history_load(),
+            history,
             history_scroll: 0,
             history_index: 0,
             history_popup: None,
@@ -443,6 +667,13 @@ impl App {
             history_sort: HistorySortMode::default(),
             history_selected: std::collections::HashSet::new(),
             history_filtered_indices: Vec::new(),
+            history_facets: HistoryFilters::default(),
+            history_stats_visible: false,
+            history_stats_cache: None,
+            history_filter_key: None,
+            history_revision: 0,
+            history_db_version: history_db.data_version(),
+            history_db,
             lyrics_query: String::new(),
             lyrics_result: None,
             lyrics_loading: false,
@@ -474,6 +705,26 @@ impl App {
             preview_subs_custom_lang: None,
             preview_subs_editing: false,
             preview_subs_edit_buf: String::new(),
+            preview_options: DownloadOptions::default(),
+            preview_options_menu: false,
+            preview_options_cursor: 0,
+            preview_options_editing: false,
+            preview_options_edit_buf: String::new(),
+            import_state: ImportState::Hidden,
+            import_cursor: 0,
+            import_format: DownloadFormat::Mp3,
+            import_pending_url: None,
+            subscriptions: subscriptions::load(),
+            subs_cursor: 0,
+            sub_input: String::new(),
+            subs_checker: Checker::default(),
+            subs_file_mtime: subscriptions::file_mtime(),
+            player: Player::default(),
+            synced_lyrics_pending: None,
+            resume_prompt: None,
+            queue_snapshot: Vec::new(),
+            daemon_attached: false,
+            daemon_pending_cancels: Vec::new(),
             preview_fetch_needed: false,
             preview_pending_url: None,
             preview_debounce: now,
@@ -503,11 +754,18 @@ impl App {
             },
             session_start: now,
             theme,
+            palette_overrides,
+            theme_file_mtime: theme_file_mtime(),
+            config_files_checked: now,
+            keymap,
             particles: Vec::new(),
             slot_screen_rects: HashMap::new(),
             next_slot_id: 0,
         };
         app.update_history_filter();
+        for warning in config_warnings {
+            app.add_toast(&warning, ToastKind::Error);
+        }
         app
     }
 
@@ -516,7 +774,8 @@ impl App {
         let mut app = Self::new();
         app.demo_mode = true;
         app.image_picker = ratatui_image::picker::Picker::from_query_stdio().ok();
-        app.history.clear(); // Replace loaded history with demo data below
+        // Demo history lives in a throwaway database, not the user's.
+        app.history_db = HistoryDb::open_in_memory().expect("in-memory SQLite database");
 
         let now = Local::now();
         app.slots = vec![
@@ -543,6 +802,11 @@ impl App {
                     dq
                 },
                 cancel: None,
+                options: DownloadOptions::default(),
+                video_quality: None,
+                subtitle_lang: None,
+                output_path: None,
+                remote_id: None,
             },
             DownloadSlot {
                 id: 1,
@@ -556,6 +820,11 @@ impl App {
                 task_spawned: true,
                 speed_history: VecDeque::new(),
                 cancel: None,
+                options: DownloadOptions::default(),
+                video_quality: None,
+                subtitle_lang: None,
+                output_path: None,
+                remote_id: None,
             },
             DownloadSlot {
                 id: 2,
@@ -569,6 +838,11 @@ impl App {
                 task_spawned: true, // demo: don't auto-spawn real tasks
                 speed_history: VecDeque::new(),
                 cancel: None,
+                options: DownloadOptions::default(),
+                video_quality: None,
+                subtitle_lang: None,
+                output_path: None,
+                remote_id: None,
             },
             DownloadSlot {
                 id: 3,
@@ -584,6 +858,11 @@ impl App {
                 task_spawned: true,
                 speed_history: VecDeque::new(),
                 cancel: None,
+                options: DownloadOptions::default(),
+                video_quality: None,
+                subtitle_lang: None,
+                output_path: None,
+                remote_id: None,
             },
             DownloadSlot {
                 id: 4,
@@ -599,12 +878,18 @@ impl App {
                 task_spawned: true,
                 speed_history: VecDeque::new(),
                 cancel: None,
+                options: DownloadOptions::default(),
+                video_quality: None,
+                subtitle_lang: None,
+                output_path: None,
+                remote_id: None,
             },
         ];
         app.next_slot_id = 5;
 
-        app.history = vec![
+        let demo_history = vec![
             HistoryEntry {
+                id: 0,
                 title: "Bohemian Rhapsody".to_string(),
                 artist: "Queen".to_string(),
                 format: DownloadFormat::Mp3,
@@ -613,8 +898,10 @@ impl App {
                 finished_at: now - chrono::Duration::seconds(3600),
                 url: "https://www.youtube.com/watch?v=fJ9rUzIMcZQ".to_string(),
                 thumbnail_url: None,
+                options: DownloadOptions::default(),
             },
             HistoryEntry {
+                id: 0,
                 title: "Hotel California".to_string(),
                 artist: "Eagles".to_string(),
                 format: DownloadFormat::Mp3,
@@ -623,8 +910,10 @@ impl App {
                 finished_at: now - chrono::Duration::seconds(10800),
                 url: "https://www.youtube.com/watch?v=BciS5krYL80".to_string(),
                 thumbnail_url: None,
+                options: DownloadOptions::default(),
             },
             HistoryEntry {
+                id: 0,
                 title: "Comfortably Numb".to_string(),
                 artist: "Pink Floyd".to_string(),
                 format: DownloadFormat::Mp4,
@@ -633,8 +922,10 @@ impl App {
                 finished_at: now - chrono::Duration::seconds(18000),
                 url: "https://www.youtube.com/watch?v=_FrOQC-zEog".to_string(),
                 thumbnail_url: None,
+                options: DownloadOptions::default(),
             },
             HistoryEntry {
+                id: 0,
                 title: "Stairway to Heaven".to_string(),
                 artist: "Led Zeppelin".to_string(),
                 format: DownloadFormat::Mp3,
@@ -643,8 +934,15 @@ impl App {
                 finished_at: now - chrono::Duration::seconds(86400),
                 url: "https://www.youtube.com/watch?v=QkF3oxziUI4".to_string(),
                 thumbnail_url: None,
+                options: DownloadOptions::default(),
             },
         ];
+        // Listed newest first above; the database keeps insertion order.
+        for entry in demo_history.iter().rev() {
+            let _ = app.history_db.insert(entry);
+        }
+        app.history = app.history_db.load_all().unwrap_or_default();
+        app.history_db_version = app.history_db.data_version();
 
         app.lyrics_result = Some(LyricsResult {
             artist: "Dora Demo".to_string(),
@@ -654,6 +952,8 @@ impl App {
             release_date: Some("2026-03-04".to_string()),
             thumbnail_url: None,
             lyrics: "Hello and welcome to the demo!\n\n[Verse 1]\nYou are exploring the Lyrics tab.\nTry clicking on the artist name above!\nIt will show you a grid of cards.\n\n[Chorus]\nEverything is fast and fluid.\nNo real API calls are made here.\nJust smooth, local demo data.\n\n[Outro]\nEnjoy the beautiful TUI design!".to_string(),
+            synced: Vec::new(),
+            for_track: None,
         });
 
         app.update_history_filter();
@@ -672,6 +972,29 @@ impl App {
             self.last_blink = Instant::now();
         }
 
+        // Pick up edits to theme.toml, and subscription changes and downloads
+        // recorded by a daemon, polling once a second.
+        if self.config_files_checked.elapsed() >= Duration::from_secs(1) {
+            self.config_files_checked = Instant::now();
+            self.reload_palette_if_changed();
+            if subscriptions::file_mtime() != self.subs_file_mtime {
+                self.set_subscriptions(subscriptions::load());
+            }
+            let version = self.history_db.data_version();
+            if version != self.history_db_version {
+                self.history_db_version = version;
+                self.reload_history();
+            }
+        }
+
+        // Advance the play queue; fetch synced lyrics for each new track.
+        while let Some(event) = self.player.poll() {
+            match event {
+                PlayerEvent::TrackStarted(track) => self.synced_lyrics_pending = Some(track),
+                PlayerEvent::Failed(message) => self.add_toast(&message, ToastKind::Error),
+            }
+        }
+
         // Feature: TUI Toasts decay
         self.toasts.retain(|t| t.added_at.elapsed() < Duration::from_secs(5));
 
@@ -756,13 +1079,16 @@ impl App {
         let has_download = self
             .slots
             .iter()
-            .any(|s| matches!(s.state, SlotState::Downloading { .. } | SlotState::Celebrating { .. }));
+            .any(|s| s.state.is_active() || matches!(s.state, SlotState::Celebrating { .. }));
         has_download
             || !self.particles.is_empty()
             || self.logo_burst > 0
             || self.lyrics_loading
             || matches!(self.preview_state, PreviewState::Loading)
+            || matches!(self.import_state, ImportState::Loading { .. })
             || matches!(self.ytdlp_startup, YtdlpStartup::FadingOut { .. })
+            || (self.active_tab == Tab::Subscriptions && !self.subs_checker.is_idle())
+            || self.player.is_playing()
             || self.demo_mode
     }
 
@@ -774,7 +1100,51 @@ impl App {
             || self.show_cookies_input
             || self.history_search_mode
             || self.preview_subs_editing
+            || self.preview_options_editing
             || (self.active_tab == Tab::Lyrics && !self.lyrics_query.is_empty())
+            || (self.active_tab == Tab::Subscriptions && !self.sub_input.is_empty())
+    }
+
+    /// Rebuild `theme` from the current flavour and the user palette file.
+    pub fn apply_theme(&mut self) {
+        self.theme = self.palette_overrides.palette(self.settings.theme_flavour);
+    }
+
+    /// Re-read `theme.toml` when its modification time changes, so palette
+    /// edits show up without a restart.
+    fn reload_palette_if_changed(&mut self) {
+        let mtime = theme_file_mtime();
+        if mtime == self.theme_file_mtime {
+            return;
+        }
+        self.theme_file_mtime = mtime;
+
+        let (overrides, warnings) = PaletteOverrides::load();
+        self.palette_overrides = overrides;
+        self.apply_theme();
+        if warnings.is_empty() {
+            self.add_toast("Theme file reloaded", ToastKind::Info);
+        }
+        for warning in warnings {
+            self.add_toast(&warning, ToastKind::Error);
+        }
+    }
+
+    /// Replace the subscription list with what was just read or written.
+    pub fn set_subscriptions(&mut self, subs: Vec<Subscription>) {
+        self.subscriptions = subs;
+        self.subs_file_mtime = subscriptions::file_mtime();
+        self.subs_cursor = self.subs_cursor.min(self.subscriptions.len().saturating_sub(1));
+    }
+
+    /// Subscription highlighted in the Subscriptions tab.
+    pub fn selected_subscription(&self) -> Option<&Subscription> {
+        self.subscriptions.get(self.subs_cursor)
+    }
+
+    /// New items across all subscriptions (the tab badge).
+    pub fn subscription_new_count(&self) -> usize {
+        self.subscriptions.iter().map(Subscription::new_count).sum()
     }
 
     pub fn add_toast(&mut self, message: &str, kind: ToastKind) {
@@ -805,10 +1175,39 @@ impl App {
             task_spawned: false,
             speed_history: VecDeque::new(),
             cancel: None,
+            options: DownloadOptions::default(),
+            video_quality: None,
+            subtitle_lang: None,
+            output_path: None,
+            remote_id: None,
         });
         id
     }
 
+    /// Re-add a download saved by a previous session. Returns the slot's ID.
+    pub fn restore_download(&mut self, queued: QueuedDownload) -> usize {
+        let id = self.add_download(queued.url, queued.format);
+        if let Some(slot) = self.slot_mut(id) {
+            slot.title = queued.title;
+            slot.artist = queued.artist;
+            slot.thumbnail_url = queued.thumbnail_url;
+            slot.options = queued.options;
+            slot.video_quality = queued.video_quality;
+            slot.subtitle_lang = queued.subtitle_lang;
+            slot.output_path = queued.output_path;
+        }
+        id
+    }
+
+    /// Unfinished slots in queue order, as they would be saved.
+    pub fn queued_downloads(&self) -> Vec<QueuedDownload> {
+        self.slots
+            .iter()
+            .filter(|s| !s.state.is_finished())
+            .map(DownloadSlot::to_queued)
+            .collect()
+    }
+
     /// Find a slot by its stable ID and apply a closure to it.
     pub fn slot_mut(&mut self, id: usize) -> Option<&mut DownloadSlot> {
         self.slots.iter_mut().find(|s| s.id == id)
@@ -820,58 +1219,85 @@ impl App {
         self.history_scroll = self.history_scroll.min(max);
     }
 
-    /// Push a history entry and persist to disk.
-    pub fn push_history(&mut self, entry: HistoryEntry) {
+    /// Record a finished download (unless attached to a daemon, which
+    /// records its downloads itself; they show up on the next reload).
+    pub fn push_history(&mut self, mut entry: HistoryEntry) {
+        if self.daemon_attached {
+            return;
+        }
+        match self.history_db.insert(&entry) {
+            Ok(id) => entry.id = id,
+            Err(e) => log::warn!("history: failed to record {}: {}", entry.title, e),
+        }
         self.history.push(entry);
-        history_save(&self.history);
+        self.history_revision += 1;
     }
 
-    /// Persist the current history to disk.
-    pub fn history_save(&self) {
-        history_save(&self.history);
+    /// Delete the entry at `display_idx` (0 = newest) from the list and the
+    /// database.
+    pub fn delete_history(&mut self, display_idx: usize) -> Option<HistoryEntry> {
+        let pos = self.history.len().checked_sub(display_idx + 1)?;
+        let entry = self.history.remove(pos);
+        if let Err(e) = self.history_db.delete(entry.id) {
+            log::warn!("history: failed to delete {}: {}", entry.title, e);
+        }
+        self.history_revision += 1;
+        Some(entry)
     }
 
-    pub fn update_history_filter(&mut self) {
-        let filter = self.history_filter.to_lowercase();
-        let mut indices: Vec<usize> = if filter.is_empty() {
-            (0..self.history.len()).collect()
-        } else {
-            self.history
-                .iter()
-                .rev()
-                .enumerate()
-                .filter_map(|(display_idx, e)| {
-                    let haystack = format!("{} {}", e.title, e.artist).to_lowercase();
-                    if fuzzy_match(&filter, &haystack) {
-                        Some(display_idx)
-                    } else {
-                        None
-                    }
-                })
-                .collect()
+    /// Re-read the database after another process changed it, keeping the
+    /// open popup and the selection on the same entries.
+    fn reload_history(&mut self) {
+        let history = match self.history_db.load_all() {
+            Ok(history) => history,
+            Err(e) => {
+                log::warn!("history: failed to reload: {}", e);
+                return;
+            }
+        };
+        let id_at = |list: &[HistoryEntry], display_idx: usize| {
+            list.len()
+                .checked_sub(display_idx + 1)
+                .and_then(|pos| list.get(pos))
+                .map(|e| e.id)
         };
+        let display: HashMap<i64, usize> = history.iter().rev().enumerate().map(|(i, e)| (e.id, i)).collect();
+        let remap = |display_idx: usize| id_at(&self.history, display_idx).and_then(|id| display.get(&id).copied());
+
+        let popup = self.history_popup.and_then(remap);
+        let selected = self.history_selected.iter().filter_map(|&i| remap(i)).collect();
+        self.history_popup = popup;
+        self.history_selected = selected;
+        self.history = history;
+        self.history_revision += 1;
+        self.clamp_history_scroll();
+    }
 
-        match self.history_sort {
-            HistorySortMode::DateDesc => {}
-            HistorySortMode::DateAsc => indices.reverse(),
-            HistorySortMode::SizeDesc => {
-                let history = &self.history;
-                indices.sort_by(|&a, &b| {
-                    let ea = history.iter().rev().nth(a).map_or(0.0, |e| e.size_mb);
-                    let eb = history.iter().rev().nth(b).map_or(0.0, |e| e.size_mb);
-                    eb.partial_cmp(&ea).unwrap_or(std::cmp::Ordering::Equal)
-                });
-            }
-            HistorySortMode::TitleAsc => {
-                let history = &self.history;
-                indices.sort_by(|&a, &b| {
-                    let ta = history.iter().rev().nth(a).map_or("", |e| e.title.as_str());
-                    let tb = history.iter().rev().nth(b).map_or("", |e| e.title.as_str());
-                    ta.to_lowercase().cmp(&tb.to_lowercase())
-                });
-            }
+    pub fn history_query(&self) -> HistoryQuery {
+        HistoryQuery {
+            text: self.history_filter.clone(),
+            filters: self.history_facets.clone(),
+            sort: self.history_sort,
         }
-        self.history_filtered_indices = indices;
+    }
+
+    /// Recompute `history_filtered_indices` when the query or the history
+    /// changed since the last call.
+    pub fn update_history_filter(&mut self) {
+        let key = (self.history_query(), self.history_revision);
+        if self.history_filter_key.as_ref() == Some(&key) {
+            return;
+        }
+        let display: HashMap<i64, usize> = self.history.iter().rev().enumerate().map(|(i, e)| (e.id, i)).collect();
+        self.history_filtered_indices = match self.history_db.search_ids(&key.0) {
+            Ok(ids) => ids.iter().filter_map(|id| display.get(id).copied()).collect(),
+            Err(e) => {
+                log::warn!("history: search failed: {}", e);
+                Vec::new()
+            }
+        };
+        self.history_stats_cache = None;
+        self.history_filter_key = Some(key);
 
         // Clamp index to new bounds
         let max = self.history_filtered_indices.len().saturating_sub(1);
@@ -879,6 +1305,28 @@ impl App {
             self.history_index = max;
         }
     }
+
+    /// Stats over the entries the history panel currently lists.
+    pub fn history_stats(&mut self) -> HistoryStats {
+        self.update_history_filter();
+        if self.history_stats_cache.is_none() {
+            let stats = self
+                .history_db
+                .stats(&self.history_query(), STATS_DAYS, Local::now().date_naive())
+                .unwrap_or_else(|e| {
+                    log::warn!("history: stats failed: {}", e);
+                    HistoryStats::default()
+                });
+            self.history_stats_cache = Some(stats);
+        }
+        self.history_stats_cache.clone().unwrap_or_default()
+    }
+
+    /// Cycle the platform facet through the platforms present in history.
+    pub fn cycle_history_platform(&mut self) {
+        let platforms = self.history_db.platforms().unwrap_or_default();
+        self.history_facets.next_platform(&platforms);
+    }
 }
 
 impl Default for App {
@@ -887,17 +1335,6 @@ impl Default for App {
     }
 }
 
-fn fuzzy_match(query: &str, haystack: &str) -> bool {
-    let mut haystack_chars = haystack.chars();
-    for q_char in query.chars() {
-        match haystack_chars.find(|&h_char| h_char == q_char) {
-            Some(_) => (),
-            None => return false,
-        }
-    }
-    true
-}
-
 // ── Supernova particle burst ──────────────────────────────────────────────────
 
 /// Spawn a sparkle burst of 8 particles from (cx, cy) on mouse click.
@@ -954,30 +1391,40 @@ fn spawn_burst_particles(cx: f32, cy: f32, theme: &ThemeColors) -> Vec<Particle>
     out
 }
 
-// ── History persistence ───────────────────────────────────────────────────────
+fn theme_file_mtime() -> Option<SystemTime> {
+    fs_err::metadata(PaletteOverrides::path())
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+// ── Queue persistence ─────────────────────────────────────────────────────────
 
-fn history_path() -> std::path::PathBuf {
+fn queue_path() -> std::path::PathBuf {
     let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
     std::path::PathBuf::from(home)
         .join(".config")
         .join("dora")
-        .join("history.json")
+        .join("queue.json")
 }
 
-/// Load history from disk; returns empty vec on error.
-pub fn history_load() -> Vec<HistoryEntry> {
-    let path = history_path();
-    if let Ok(content) = fs_err::read_to_string(&path)
-        && let Ok(entries) = serde_json::from_str::<Vec<HistoryEntry>>(&content)
+/// Load the saved queue; returns empty vec on error.
+pub fn queue_load() -> Vec<QueuedDownload> {
+    if let Ok(content) = fs_err::read_to_string(queue_path())
+        && let Ok(entries) = serde_json::from_str::<Vec<QueuedDownload>>(&content)
     {
         return entries;
     }
     Vec::new()
 }
 
-/// Overwrite the history file (best-effort; silently ignores errors).
-pub fn history_save(entries: &[HistoryEntry]) {
-    let path = history_path();
+/// Overwrite the queue file, or remove it when the queue is empty
+/// (best-effort; silently ignores errors).
+pub fn queue_save(entries: &[QueuedDownload]) {
+    let path = queue_path();
+    if entries.is_empty() {
+        let _ = fs_err::remove_file(path);
+        return;
+    }
     if let Some(parent) = path.parent() {
         let _ = fs_err::create_dir_all(parent);
     }
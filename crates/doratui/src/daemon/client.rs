@@ -0,0 +1,228 @@
+//! Client side of the control socket: the `dora add` / `ls` / `watch`
+//! subcommands and the bridge a TUI uses to attach to a running daemon.
+
+use std::collections::HashMap;
+
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+use tokio::net::UnixStream;
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::sync::mpsc;
+
+use super::protocol::{JobInfo, JobState, Reply, Request, write_line};
+use super::socket_path;
+use crate::app::DownloadFormat;
+
+/// One connection to the daemon.
+pub struct Client {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    write: OwnedWriteHalf,
+}
+
+impl Client {
+    pub async fn connect() -> anyhow::Result<Self> {
+        let stream = UnixStream::connect(socket_path())
+            .await
+            .map_err(|_| anyhow::anyhow!("dora daemon is not running — start it with `dora daemon`"))?;
+        let (read, write) = stream.into_split();
+        Ok(Self {
+            lines: BufReader::new(read).lines(),
+            write,
+        })
+    }
+
+    pub async fn send(&mut self, request: &Request) -> anyhow::Result<()> {
+        write_line(&mut self.write, request).await
+    }
+
+    /// Next reply or update; `None` once the daemon closes the connection.
+    pub async fn recv(&mut self) -> anyhow::Result<Option<Reply>> {
+        match self.lines.next_line().await? {
+            Some(line) => Ok(Some(serde_json::from_str(&line)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Send `request` and wait for its reply; `Reply::Error` becomes `Err`.
+    pub async fn request(&mut self, request: &Request) -> anyhow::Result<Reply> {
+        self.send(request).await?;
+        match self.recv().await? {
+            Some(Reply::Error { message }) => anyhow::bail!(message),
+            Some(reply) => Ok(reply),
+            None => anyhow::bail!("daemon closed the connection"),
+        }
+    }
+}
+
+// ── CLI subcommands ───────────────────────────────────────────────────────────
+
+/// `dora add [--mp3|--mp4] <url>...`
+pub async fn cmd_add(args: &[String]) -> anyhow::Result<()> {
+    let mut format = None;
+    let mut urls = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--mp3" => format = Some(DownloadFormat::Mp3),
+            "--mp4" => format = Some(DownloadFormat::Mp4),
+            flag if flag.starts_with('-') => anyhow::bail!("unknown option {}", flag),
+            url => urls.push(url.to_string()),
+        }
+    }
+    if urls.is_empty() {
+        anyhow::bail!("usage: dora add [--mp3|--mp4] <url>...");
+    }
+
+    let mut client = Client::connect().await?;
+    let mut failed = 0;
+    for url in urls {
+        let request = Request::Add {
+            url: url.clone(),
+            format,
+            options: Default::default(),
+            video_quality: None,
+            subtitle_lang: None,
+            tag: None,
+        };
+        match client.request(&request).await {
+            Ok(Reply::Added { job }) => println!("queued #{} [{}] {}", job.id, job.format.label(), job.url),
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("{}: {:#}", url, e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} link(s) were not queued", failed);
+    }
+    Ok(())
+}
+
+/// `dora ls` — one line per job the daemon knows about.
+pub async fn cmd_ls() -> anyhow::Result<()> {
+    let mut client = Client::connect().await?;
+    let Reply::Jobs { jobs } = client.request(&Request::List).await? else {
+        anyhow::bail!("unexpected reply from daemon");
+    };
+    if jobs.is_empty() {
+        println!("Queue is empty.");
+    }
+    for job in &jobs {
+        println!("{}", job_line(job));
+    }
+    Ok(())
+}
+
+/// `dora watch [--json]` — follow the queue until the daemon stops.
+///
+/// Prints a line whenever a job changes state (and every 10% while
+/// downloading); `--json` prints every update as the raw protocol line.
+pub async fn cmd_watch(args: &[String]) -> anyhow::Result<()> {
+    let json = args.iter().any(|a| a == "--json");
+    let mut client = Client::connect().await?;
+    let Reply::Subscribed { jobs } = client.request(&Request::Subscribe).await? else {
+        anyhow::bail!("unexpected reply from daemon");
+    };
+
+    let mut last_seen: HashMap<usize, (&'static str, u8)> = HashMap::new();
+    let mut show = |job: &JobInfo| {
+        if json {
+            if let Ok(line) = serde_json::to_string(&Reply::Job { job: job.clone() }) {
+                println!("{}", line);
+            }
+            return;
+        }
+        let step = match job.state {
+            JobState::Downloading { percent, .. } => percent / 10,
+            _ => 0,
+        };
+        let key = (job.state.name(), step);
+        if last_seen.insert(job.id, key) != Some(key) {
+            println!("{}  {}", chrono::Local::now().format("%H:%M:%S"), job_line(job));
+        }
+    };
+    for job in jobs.iter().filter(|j| !j.state.is_finished()) {
+        show(job);
+    }
+    while let Some(reply) = client.recv().await? {
+        if let Reply::Job { job } = reply {
+            show(&job);
+        }
+    }
+    eprintln!("dora daemon stopped");
+    Ok(())
+}
+
+fn job_line(job: &JobInfo) -> String {
+    let status = match &job.state {
+        JobState::Done { .. } | JobState::Failed { .. } => job.state.name().to_string(),
+        other => other.describe(),
+    };
+    let mut line = format!(
+        "#{:<4} {}  {:<30} {}",
+        job.id,
+        job.format.label(),
+        status,
+        job.display_name()
+    );
+    match &job.state {
+        JobState::Done { path, .. } => line.push_str(&format!("\n      → {}", path)),
+        JobState::Failed { reason } => line.push_str(&format!("\n      ✗ {}", reason)),
+        _ => {}
+    }
+    line
+}
+
+// ── TUI attach ────────────────────────────────────────────────────────────────
+
+/// What the attach bridge forwards to the TUI's main loop.
+#[derive(Debug)]
+pub enum AttachEvent {
+    /// A job's current state (snapshot on attach, then every change).
+    Job(JobInfo),
+    /// The daemon rejected a request.
+    Error(String),
+    /// The daemon went away; the TUI falls back to running downloads itself.
+    Disconnected,
+}
+
+/// Subscribe to a running daemon. Returns `None` when none is running.
+///
+/// Requests sent on the returned sender go to the daemon; job updates and
+/// errors come back on the receiver.
+pub async fn attach() -> Option<(mpsc::Sender<Request>, mpsc::Receiver<AttachEvent>)> {
+    let mut client = Client::connect().await.ok()?;
+    let Ok(Reply::Subscribed { jobs }) = client.request(&Request::Subscribe).await else {
+        return None;
+    };
+
+    let (req_tx, mut req_rx) = mpsc::channel::<Request>(64);
+    let (ev_tx, ev_rx) = mpsc::channel::<AttachEvent>(256);
+    tokio::spawn(async move {
+        for job in jobs {
+            let _ = ev_tx.send(AttachEvent::Job(job)).await;
+        }
+        loop {
+            tokio::select! {
+                Some(request) = req_rx.recv() => {
+                    if client.send(&request).await.is_err() {
+                        break;
+                    }
+                }
+                reply = client.recv() => match reply {
+                    Ok(Some(Reply::Job { job } | Reply::Added { job })) => {
+                        if ev_tx.send(AttachEvent::Job(job)).await.is_err() {
+                            return; // TUI exited
+                        }
+                    }
+                    Ok(Some(Reply::Error { message })) => {
+                        let _ = ev_tx.send(AttachEvent::Error(message)).await;
+                    }
+                    Ok(Some(_)) => {}
+                    Ok(None) | Err(_) => break,
+                },
+            }
+        }
+        let _ = ev_tx.send(AttachEvent::Disconnected).await;
+    });
+    Some((req_tx, ev_rx))
+}
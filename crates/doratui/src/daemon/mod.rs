@@ -0,0 +1,21 @@
+//! Headless `dora daemon`: runs the download queue in the background and
+//! serves a JSON-lines control API on a Unix socket.
+//!
+//! `dora add` / `ls` / `watch`, scripts and file-manager actions talk to it
+//! through [`socket_path`]; a TUI started while the daemon runs attaches to
+//! it instead of spawning downloads itself. See [`protocol`] for the
+//! message shapes.
+
+use std::path::PathBuf;
+
+pub mod client;
+pub mod protocol;
+mod server;
+
+pub use server::run;
+
+/// `~/.config/dora/dora.sock`, next to `settings.json` and `queue.json`.
+pub fn socket_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(home).join(".config").join("dora").join("dora.sock")
+}
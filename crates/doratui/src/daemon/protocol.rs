@@ -0,0 +1,224 @@
+//! Wire format of the daemon control socket.
+//!
+//! Newline-delimited JSON: each line a client writes is one [`Request`]
+//! (tagged by `cmd`), each line the daemon writes is one [`Reply`] (tagged by
+//! `type`). A subscribed connection receives [`Reply::Job`] updates between
+//! replies to its own requests.
+//!
+//! ```text
+//! → {"cmd":"add","url":"https://youtu.be/dQw4w9WgXcQ","format":"Mp4"}
+//! ← {"type":"added","job":{"id":3,"url":"…","state":{"state":"pending"},…}}
+//! ```
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::app::{DownloadFormat, HistoryEntry};
+use crate::download_options::DownloadOptions;
+
+/// Client → daemon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    /// Queue a download. Unset fields fall back to the daemon's settings.
+    Add {
+        url: String,
+        #[serde(default)]
+        format: Option<DownloadFormat>,
+        #[serde(default)]
+        options: DownloadOptions,
+        #[serde(default)]
+        video_quality: Option<String>,
+        #[serde(default)]
+        subtitle_lang: Option<String>,
+        /// Opaque client label echoed back on the job; the TUI uses it to
+        /// match jobs to the slots it queued.
+        #[serde(default)]
+        tag: Option<String>,
+    },
+    /// Snapshot of every job the daemon knows about.
+    List,
+    /// Cancel a queued or running job.
+    Cancel { id: usize },
+    /// Search finished downloads by title, artist or URL, newest first.
+    History {
+        #[serde(default)]
+        query: String,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    /// Stream [`Reply::Job`] updates on this connection until it closes.
+    Subscribe,
+}
+
+/// Daemon → client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Reply {
+    Added {
+        job: JobInfo,
+    },
+    Jobs {
+        jobs: Vec<JobInfo>,
+    },
+    Cancelled {
+        id: usize,
+    },
+    History {
+        entries: Vec<HistoryEntry>,
+    },
+    /// Subscription started; `jobs` is the state updates are relative to.
+    Subscribed {
+        jobs: Vec<JobInfo>,
+    },
+    /// A job changed (subscribed connections only).
+    Job {
+        job: JobInfo,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Public view of one queued, running or finished download.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub id: usize,
+    pub url: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub format: DownloadFormat,
+    pub options: DownloadOptions,
+    pub state: JobState,
+    pub tag: Option<String>,
+}
+
+impl JobInfo {
+    /// "Artist — Title", or the URL until metadata arrives.
+    pub fn display_name(&self) -> String {
+        let title = self.title.as_deref().unwrap_or(&self.url);
+        match &self.artist {
+            Some(a) => format!("{} — {}", a, title),
+            None => title.to_string(),
+        }
+    }
+}
+
+/// Download progress, mirroring the TUI's `SlotState`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum JobState {
+    Pending,
+    Fetching,
+    Downloading { percent: u8, speed_mbs: f64, eta_secs: u64 },
+    Merging { position_secs: f32 },
+    Postprocessing { label: String },
+    Done { path: String, size_mb: f64 },
+    Failed { reason: String },
+    Cancelled,
+}
+
+impl JobState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Done { .. } | Self::Failed { .. } | Self::Cancelled)
+    }
+
+    /// One-word state for listings and change detection.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Fetching => "fetching",
+            Self::Downloading { .. } => "downloading",
+            Self::Merging { .. } => "merging",
+            Self::Postprocessing { .. } => "processing",
+            Self::Done { .. } => "done",
+            Self::Failed { .. } => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Human-readable status for `dora ls` / `dora watch`.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Downloading {
+                percent,
+                speed_mbs,
+                eta_secs,
+            } => format!(
+                "{}% · {:.1} MB/s · ETA {}:{:02}",
+                percent,
+                speed_mbs,
+                eta_secs / 60,
+                eta_secs % 60
+            ),
+            Self::Postprocessing { label } => label.clone(),
+            Self::Done { path, .. } => format!("done → {}", path),
+            Self::Failed { reason } => format!("failed: {}", reason),
+            other => other.name().to_string(),
+        }
+    }
+}
+
+/// Write `msg` as one JSON line.
+pub async fn write_line<W, T>(writer: &mut W, msg: &T) -> anyhow::Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut line = serde_json::to_vec(msg)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_add_uses_defaults() {
+        let req: Request = serde_json::from_str(r#"{"cmd":"add","url":"https://youtu.be/x"}"#).unwrap();
+        let Request::Add {
+            url,
+            format,
+            options,
+            tag,
+            ..
+        } = req
+        else {
+            panic!("expected add, got {:?}", req);
+        };
+        assert_eq!(url, "https://youtu.be/x");
+        assert_eq!(format, None);
+        assert_eq!(options, DownloadOptions::default());
+        assert_eq!(tag, None);
+    }
+
+    #[test]
+    fn reply_wire_shape() {
+        let reply = Reply::Job {
+            job: JobInfo {
+                id: 7,
+                url: "https://youtu.be/x".into(),
+                title: None,
+                artist: None,
+                format: DownloadFormat::Mp3,
+                options: DownloadOptions::default(),
+                state: JobState::Downloading {
+                    percent: 42,
+                    speed_mbs: 1.5,
+                    eta_secs: 10,
+                },
+                tag: None,
+            },
+        };
+        let json: serde_json::Value = serde_json::to_value(&reply).unwrap();
+        assert_eq!(json["type"], "job");
+        assert_eq!(json["job"]["id"], 7);
+        assert_eq!(json["job"]["state"]["state"], "downloading");
+        assert_eq!(json["job"]["state"]["percent"], 42);
+
+        let back: Reply = serde_json::from_value(json).unwrap();
+        assert!(matches!(back, Reply::Job { job } if job.id == 7));
+    }
+}
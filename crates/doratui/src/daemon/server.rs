@@ -0,0 +1,530 @@
+//! The daemon process: owns the queue and serves the control socket.
+//!
+//! All state lives in [`Daemon`], driven by one select loop. Connection
+//! tasks talk to it through [`Command`]s; download tasks report through the
+//! same `(slot_id, SlotEvent)` channel the TUI uses. Every job change is
+//! broadcast to subscribed connections as a full [`JobInfo`].
+//!
+//! While it runs, the daemon also checks followed channels and feeds (the
+//! TUI leaves that to it when attached) and queues new items of
+//! auto-download subscriptions as ordinary jobs.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+use super::protocol::{JobInfo, JobState, Reply, Request, write_line};
+use super::socket_path;
+use crate::app::{DownloadFormat, HistoryEntry, QueuedDownload, queue_load, queue_save};
+use crate::download_runner::{SlotEvent, SubtitleOptions, spawn_download};
+use crate::history_db::{HistoryDb, HistoryQuery};
+use crate::settings::DoraSettings;
+use crate::subscriptions::{self, CheckReport, Checker, Subscription};
+
+/// Finished jobs kept for `ls` / late subscribers before the oldest is dropped.
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// Connection task → daemon loop.
+enum Command {
+    Request(Request, oneshot::Sender<Reply>),
+    /// Snapshot and receiver are taken together so no update falls between.
+    Subscribe(oneshot::Sender<(Vec<JobInfo>, broadcast::Receiver<Reply>)>),
+}
+
+struct Job {
+    info: JobInfo,
+    video_quality: Option<String>,
+    subtitle_lang: Option<String>,
+    /// File the download writes to; reused to resume after a restart.
+    output_path: Option<String>,
+    spawned: bool,
+    cancel: Option<tokio::task::AbortHandle>,
+}
+
+impl Job {
+    fn to_queued(&self) -> QueuedDownload {
+        QueuedDownload {
+            url: self.info.url.clone(),
+            title: self.info.title.clone(),
+            artist: self.info.artist.clone(),
+            thumbnail_url: None,
+            format: self.info.format,
+            options: self.info.options.clone(),
+            video_quality: self.video_quality.clone(),
+            subtitle_lang: self.subtitle_lang.clone(),
+            output_path: self.output_path.clone(),
+        }
+    }
+}
+
+struct Daemon {
+    jobs: Vec<Job>,
+    next_id: usize,
+    settings: DoraSettings,
+    settings_mtime: Option<SystemTime>,
+    /// Followed sources as last read from `subscriptions.json`.
+    subscriptions: Vec<Subscription>,
+    subs_mtime: Option<SystemTime>,
+    updates: broadcast::Sender<Reply>,
+    dl_tx: mpsc::Sender<(usize, SlotEvent)>,
+    /// Queue as last written to `queue.json`.
+    queue_snapshot: Vec<QueuedDownload>,
+    /// Subscription checks in flight.
+    checker: Checker,
+    /// Finished downloads; an attached TUI reads the same database.
+    history: HistoryDb,
+}
+
+/// Run the daemon until SIGINT / SIGTERM. Unfinished jobs stay in
+/// `queue.json` and resume on the next start.
+pub async fn run() -> anyhow::Result<()> {
+    let path = socket_path();
+    let listener = bind_socket(&path).await?;
+    log::info!("dora daemon listening on {}", path.display());
+
+    let settings_mtime = DoraSettings::file_mtime();
+    let settings = DoraSettings::load();
+    let subs_mtime = subscriptions::file_mtime();
+
+    let (cmd_tx, mut cmd_rx) = mpsc::channel::<Command>(64);
+    let (dl_tx, mut dl_rx) = mpsc::channel::<(usize, SlotEvent)>(256);
+    let (updates, _) = broadcast::channel::<Reply>(256);
+    let (sub_tx, mut sub_rx) = mpsc::channel::<CheckReport>(16);
+    let mut daemon = Daemon {
+        jobs: Vec::new(),
+        next_id: 1,
+        settings,
+        settings_mtime,
+        subscriptions: subscriptions::load(),
+        subs_mtime,
+        updates,
+        dl_tx,
+        queue_snapshot: Vec::new(),
+        checker: Checker::default(),
+        history: HistoryDb::open_default().context("cannot open the history database")?,
+    };
+    let restored = queue_load();
+    if !restored.is_empty() {
+        log::info!("resuming {} queued downloads", restored.len());
+    }
+    for queued in restored {
+        daemon.restore(queued);
+    }
+
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let tx = cmd_tx.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = serve_connection(stream, tx).await {
+                            log::debug!("connection closed: {:#}", e);
+                        }
+                    });
+                }
+                Err(e) => log::warn!("accept failed: {}", e),
+            },
+            Some(cmd) = cmd_rx.recv() => daemon.handle_command(cmd),
+            Some((id, event)) = dl_rx.recv() => daemon.handle_slot_event(id, event),
+            Some(report) = sub_rx.recv() => daemon.handle_check_report(report),
+            _ = tick.tick() => {
+                daemon.reload_if_changed();
+                let interval = daemon.settings.subscription_interval();
+                daemon.checker.start_due(&daemon.subscriptions, interval, &sub_tx);
+            }
+            _ = tokio::signal::ctrl_c() => break,
+            _ = sigterm.recv() => break,
+        }
+        daemon.dispatch();
+        daemon.persist_queue();
+    }
+
+    log::info!("dora daemon shutting down");
+    for job in &mut daemon.jobs {
+        if let Some(handle) = job.cancel.take() {
+            handle.abort();
+        }
+    }
+    let _ = fs_err::remove_file(&path);
+    Ok(())
+}
+
+/// Bind the control socket, replacing a stale one left by a crashed daemon.
+async fn bind_socket(path: &Path) -> anyhow::Result<UnixListener> {
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            anyhow::bail!("dora daemon is already running ({})", path.display());
+        }
+        fs_err::remove_file(path)?;
+    }
+    if let Some(parent) = path.parent() {
+        fs_err::create_dir_all(parent)?;
+    }
+    UnixListener::bind(path).with_context(|| format!("cannot listen on {}", path.display()))
+}
+
+impl Daemon {
+    fn add(&mut self, url: String, format: DownloadFormat, tag: Option<String>) -> &mut Job {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.jobs.push(Job {
+            info: JobInfo {
+                id,
+                url,
+                title: None,
+                artist: None,
+                format,
+                options: Default::default(),
+                state: JobState::Pending,
+                tag,
+            },
+            video_quality: None,
+            subtitle_lang: None,
+            output_path: None,
+            spawned: false,
+            cancel: None,
+        });
+        self.jobs.last_mut().expect("job was just pushed")
+    }
+
+    fn restore(&mut self, queued: QueuedDownload) {
+        let job = self.add(queued.url, queued.format, None);
+        job.info.title = queued.title;
+        job.info.artist = queued.artist;
+        job.info.options = queued.options;
+        job.video_quality = queued.video_quality;
+        job.subtitle_lang = queued.subtitle_lang;
+        job.output_path = queued.output_path;
+    }
+
+    fn snapshot(&self) -> Vec<JobInfo> {
+        self.jobs.iter().map(|j| j.info.clone()).collect()
+    }
+
+    fn job_mut(&mut self, id: usize) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|j| j.info.id == id)
+    }
+
+    /// Send a job's current state to subscribers (no-op when nobody listens).
+    fn broadcast(&self, id: usize) {
+        if let Some(job) = self.jobs.iter().find(|j| j.info.id == id) {
+            let _ = self.updates.send(Reply::Job { job: job.info.clone() });
+        }
+    }
+
+    fn handle_command(&mut self, cmd: Command) {
+        match cmd {
+            Command::Subscribe(reply) => {
+                let _ = reply.send((self.snapshot(), self.updates.subscribe()));
+            }
+            Command::Request(request, reply) => {
+                let _ = reply.send(self.handle_request(request));
+            }
+        }
+    }
+
+    fn handle_request(&mut self, request: Request) -> Reply {
+        match request {
+            Request::Add {
+                url,
+                format,
+                options,
+                video_quality,
+                subtitle_lang,
+                tag,
+            } => {
+                let url = crate::normalize_url(url.trim());
+                if url.parse::<url::Url>().is_err() {
+                    return Reply::Error {
+                        message: format!("Not a valid link: {}", url),
+                    };
+                }
+                let format = format.unwrap_or_else(|| crate::default_format(&self.settings));
+                let job = self.add(url, format, tag);
+                let mut options = options;
+                options.fit_to(format);
+                job.info.options = options;
+                job.video_quality = video_quality;
+                job.subtitle_lang = subtitle_lang;
+                let info = job.info.clone();
+                log::info!("[job {}] queued {}", info.id, info.url);
+                self.broadcast(info.id);
+                Reply::Added { job: info }
+            }
+            Request::List => Reply::Jobs { jobs: self.snapshot() },
+            Request::Cancel { id } => {
+                let Some(job) = self.job_mut(id) else {
+                    return Reply::Error {
+                        message: format!("No job {}", id),
+                    };
+                };
+                if job.info.state.is_finished() {
+                    return Reply::Error {
+                        message: format!("Job {} already finished", id),
+                    };
+                }
+                if let Some(handle) = job.cancel.take() {
+                    handle.abort();
+                }
+                job.info.state = JobState::Cancelled;
+                log::info!("[job {}] cancelled", id);
+                self.broadcast(id);
+                self.prune_finished();
+                Reply::Cancelled { id }
+            }
+            Request::History { query, limit } => {
+                let query = HistoryQuery {
+                    text: query,
+                    ..Default::default()
+                };
+                match self.history.search(&query, limit.unwrap_or(20)) {
+                    Ok(entries) => Reply::History { entries },
+                    Err(e) => Reply::Error {
+                        message: format!("History search failed: {}", e),
+                    },
+                }
+            }
+            // Handled by the connection task before it reaches the daemon loop.
+            Request::Subscribe => Reply::Error {
+                message: "subscribe is handled per connection".to_string(),
+            },
+        }
+    }
+
+    fn handle_slot_event(&mut self, id: usize, event: SlotEvent) {
+        let Some(job) = self.job_mut(id) else {
+            return;
+        };
+        // A cancelled task can still flush a last event before it stops.
+        if job.info.state.is_finished() {
+            return;
+        }
+        match event {
+            SlotEvent::Fetching => job.info.state = JobState::Fetching,
+            SlotEvent::Metadata { title, artist } => {
+                if title.is_some() {
+                    job.info.title = title;
+                }
+                if artist.is_some() {
+                    job.info.artist = artist;
+                }
+            }
+            SlotEvent::Destination { path } => {
+                // Persisted with the queue; not interesting to subscribers.
+                job.output_path = Some(path);
+                return;
+            }
+            SlotEvent::Progress {
+                percent,
+                speed_mbs,
+                eta_secs,
+            } => {
+                job.info.state = JobState::Downloading {
+                    percent,
+                    speed_mbs,
+                    eta_secs,
+                }
+            }
+            SlotEvent::Merging { position_secs } => job.info.state = JobState::Merging { position_secs },
+            SlotEvent::Postprocessing { label } => {
+                job.info.state = JobState::Postprocessing {
+                    label: label.to_string(),
+                }
+            }
+            SlotEvent::BurningSubtitles => {
+                job.info.state = JobState::Postprocessing {
+                    label: "burning subtitles".to_string(),
+                }
+            }
+            SlotEvent::Done { path, size_mb } => {
+                job.cancel = None;
+                job.info.state = JobState::Done {
+                    path: path.clone(),
+                    size_mb,
+                };
+                log::info!("[job {}] done: {}", id, path);
+                let entry = HistoryEntry {
+                    id: 0,
+                    title: job.info.title.clone().unwrap_or_else(|| "Unknown".to_string()),
+                    artist: job.info.artist.clone().unwrap_or_default(),
+                    format: job.info.format,
+                    size_mb,
+                    path,
+                    finished_at: chrono::Local::now(),
+                    url: job.info.url.clone(),
+                    thumbnail_url: None,
+                    options: job.info.options.clone(),
+                };
+                if let Err(e) = self.history.insert(&entry) {
+                    log::warn!("[job {}] failed to record history: {}", id, e);
+                }
+            }
+            SlotEvent::Failed { reason } => {
+                job.cancel = None;
+                log::warn!("[job {}] failed: {}", id, reason);
+                job.info.state = JobState::Failed { reason };
+            }
+        }
+        self.broadcast(id);
+        self.prune_finished();
+    }
+
+    /// Pick up edits made in the TUI's Settings and Subscriptions tabs (and
+    /// the daemon's own check results), re-reading a file only when its
+    /// modification time moved.
+    fn reload_if_changed(&mut self) {
+        let mtime = DoraSettings::file_mtime();
+        if mtime != self.settings_mtime {
+            self.settings_mtime = mtime;
+            self.settings = DoraSettings::load();
+        }
+        let mtime = subscriptions::file_mtime();
+        if mtime != self.subs_mtime {
+            self.subs_mtime = mtime;
+            self.subscriptions = subscriptions::load();
+        }
+    }
+
+    /// Record a finished subscription check and queue the new items of
+    /// auto-download subscriptions.
+    fn handle_check_report(&mut self, report: CheckReport) {
+        self.checker.finish(report.id);
+        let (_, summary) = subscriptions::record(report);
+        let Some(summary) = summary.filter(|s| s.fresh > 0) else {
+            return;
+        };
+        log::info!("{} new from {}", summary.fresh, summary.name);
+        for item in summary.queue {
+            let job = self.add(item.url, summary.format, None);
+            job.info.title = Some(item.title);
+            let id = job.info.id;
+            log::info!("[job {}] queued from {}", id, summary.name);
+            self.broadcast(id);
+        }
+    }
+
+    /// Start Pending jobs up to `max_parallel`, inside the active-hours window.
+    fn dispatch(&mut self) {
+        let settings = &self.settings;
+        if !settings.in_active_window(chrono::Local::now().time()) {
+            return;
+        }
+        let max_parallel = settings.max_parallel.max(1);
+        let mut running = self
+            .jobs
+            .iter()
+            .filter(|j| j.spawned && !j.info.state.is_finished())
+            .count();
+        for job in &mut self.jobs {
+            if running >= max_parallel {
+                break;
+            }
+            if job.spawned || job.info.state != JobState::Pending {
+                continue;
+            }
+            running += 1;
+            job.spawned = true;
+            let mut s = settings.clone();
+            if let Some(ref q) = job.video_quality {
+                s.video_quality = q.clone();
+            }
+            let subtitle_opts = job.subtitle_lang.clone().map(|lang| SubtitleOptions { lang });
+            job.cancel = Some(spawn_download(
+                job.info.id,
+                job.info.url.clone(),
+                job.info.format,
+                s,
+                self.dl_tx.clone(),
+                subtitle_opts,
+                job.info.options.clone(),
+                job.output_path.clone(),
+            ));
+        }
+    }
+
+    /// Drop the oldest finished jobs beyond [`MAX_FINISHED_JOBS`].
+    fn prune_finished(&mut self) {
+        let finished = self.jobs.iter().filter(|j| j.info.state.is_finished()).count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+        self.jobs.retain(|j| {
+            if excess > 0 && j.info.state.is_finished() {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn persist_queue(&mut self) {
+        let queue: Vec<QueuedDownload> = self
+            .jobs
+            .iter()
+            .filter(|j| !j.info.state.is_finished())
+            .map(Job::to_queued)
+            .collect();
+        if queue != self.queue_snapshot {
+            queue_save(&queue);
+            self.queue_snapshot = queue;
+        }
+    }
+}
+
+// ── Connections ───────────────────────────────────────────────────────────────
+
+async fn serve_connection(stream: UnixStream, cmd_tx: mpsc::Sender<Command>) -> anyhow::Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    let mut updates: Option<broadcast::Receiver<Reply>> = None;
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    return Ok(());
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let reply = match serde_json::from_str::<Request>(&line) {
+                    Ok(Request::Subscribe) => {
+                        let (tx, rx) = oneshot::channel();
+                        cmd_tx.send(Command::Subscribe(tx)).await?;
+                        let (jobs, receiver) = rx.await?;
+                        updates = Some(receiver);
+                        Reply::Subscribed { jobs }
+                    }
+                    Ok(request) => {
+                        let (tx, rx) = oneshot::channel();
+                        cmd_tx.send(Command::Request(request, tx)).await?;
+                        rx.await?
+                    }
+                    Err(e) => Reply::Error {
+                        message: format!("bad request: {}", e),
+                    },
+                };
+                write_line(&mut write, &reply).await?;
+            }
+            update = next_update(&mut updates) => match update {
+                Ok(reply) => write_line(&mut write, &reply).await?,
+                // Each update carries the whole job, so a slow reader only
+                // misses intermediate progress.
+                Err(broadcast::error::RecvError::Lagged(n)) => log::debug!("subscriber lagged by {} updates", n),
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+        }
+    }
+}
+
+async fn next_update(updates: &mut Option<broadcast::Receiver<Reply>>) -> Result<Reply, broadcast::error::RecvError> {
+    match updates {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
@@ -0,0 +1,363 @@
+//! Per-download options for dora: output conversion, time-range cut and
+//! audio effects.
+//!
+//! Chosen in the preview's options popup (`[o]`), applied by
+//! `download_runner` once the source has finished, and stored with the
+//! finished [`HistoryEntry`](crate::app::HistoryEntry). The heavy lifting is
+//! doracore's `conversion`, `ringtone` and `audio_effects` modules — the same
+//! code paths the bot uses.
+
+use doracore::conversion::audio::AudioFormat;
+use doracore::download::audio_effects::{AudioEffectSettings, MorphProfile};
+use doracore::timestamps::{format_timestamp, parse_timestamp_to_secs};
+use serde::{Deserialize, Serialize};
+
+use crate::app::DownloadFormat;
+
+/// Morph presets in cycle order (see `MorphProfile`).
+pub const MORPH_PROFILES: &[&str] = &["none", "soft", "aggressive", "lofi", "wide"];
+
+/// What the downloaded file is turned into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutputKind {
+    /// Keep the MP3 / MP4 as downloaded.
+    #[default]
+    Original,
+    Flac,
+    Wav,
+    Opus,
+    M4a,
+    /// 30-second `.m4r`.
+    IphoneRingtone,
+    /// 40-second MP3.
+    AndroidRingtone,
+    Gif,
+}
+
+impl OutputKind {
+    const AUDIO: &[OutputKind] = &[
+        OutputKind::Original,
+        OutputKind::Flac,
+        OutputKind::Wav,
+        OutputKind::Opus,
+        OutputKind::M4a,
+        OutputKind::IphoneRingtone,
+        OutputKind::AndroidRingtone,
+    ];
+    const VIDEO: &[OutputKind] = &[OutputKind::Original, OutputKind::Gif];
+
+    /// Outputs that make sense for a download of `format`.
+    pub fn choices(format: DownloadFormat) -> &'static [OutputKind] {
+        match format {
+            DownloadFormat::Mp3 => Self::AUDIO,
+            DownloadFormat::Mp4 => Self::VIDEO,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputKind::Original => "as downloaded",
+            OutputKind::Flac => "FLAC",
+            OutputKind::Wav => "WAV",
+            OutputKind::Opus => "Opus",
+            OutputKind::M4a => "M4A",
+            OutputKind::IphoneRingtone => "iPhone ringtone",
+            OutputKind::AndroidRingtone => "Android ringtone",
+            OutputKind::Gif => "GIF",
+        }
+    }
+
+    /// Target of a plain audio conversion, if this is one.
+    pub fn audio_format(self) -> Option<AudioFormat> {
+        match self {
+            OutputKind::Flac => Some(AudioFormat::Flac),
+            OutputKind::Wav => Some(AudioFormat::Wav),
+            OutputKind::Opus => Some(AudioFormat::Opus),
+            OutputKind::M4a => Some(AudioFormat::M4a),
+            _ => None,
+        }
+    }
+}
+
+/// Pitch / tempo / bass / morph, mirroring doracore's `AudioEffectSettings`
+/// in a serialisable form. Audio downloads only.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioEffects {
+    pub pitch_semitones: i8,
+    pub tempo_factor: f32,
+    pub bass_gain_db: i8,
+    pub morph: String,
+}
+
+impl Default for AudioEffects {
+    fn default() -> Self {
+        Self {
+            pitch_semitones: 0,
+            tempo_factor: 1.0,
+            bass_gain_db: 0,
+            morph: "none".to_string(),
+        }
+    }
+}
+
+impl AudioEffects {
+    /// True when applying these would leave the audio untouched.
+    pub fn is_identity(&self) -> bool {
+        self.pitch_semitones == 0
+            && (self.tempo_factor - 1.0).abs() < 0.01
+            && self.bass_gain_db == 0
+            && MorphProfile::parse(&self.morph) == MorphProfile::None
+    }
+
+    pub fn to_core(&self) -> AudioEffectSettings {
+        AudioEffectSettings {
+            pitch_semitones: self.pitch_semitones,
+            tempo_factor: self.tempo_factor,
+            bass_gain_db: self.bass_gain_db,
+            morph_profile: MorphProfile::parse(&self.morph),
+        }
+    }
+}
+
+/// Everything chosen in the options popup for one download.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DownloadOptions {
+    #[serde(default)]
+    pub output: OutputKind,
+    /// Cut start in seconds (None = from the beginning).
+    #[serde(default)]
+    pub cut_start: Option<u32>,
+    /// Cut end in seconds (None = to the end).
+    #[serde(default)]
+    pub cut_end: Option<u32>,
+    #[serde(default)]
+    pub effects: AudioEffects,
+}
+
+/// Rows of the options popup, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionRow {
+    Output,
+    CutStart,
+    CutEnd,
+    Pitch,
+    Tempo,
+    Bass,
+    Morph,
+}
+
+impl OptionRow {
+    const AUDIO: &[OptionRow] = &[
+        OptionRow::Output,
+        OptionRow::CutStart,
+        OptionRow::CutEnd,
+        OptionRow::Pitch,
+        OptionRow::Tempo,
+        OptionRow::Bass,
+        OptionRow::Morph,
+    ];
+    const VIDEO: &[OptionRow] = &[OptionRow::Output, OptionRow::CutStart, OptionRow::CutEnd];
+
+    /// Rows shown for a download of `format` (effects are audio-only).
+    pub fn for_format(format: DownloadFormat) -> &'static [OptionRow] {
+        match format {
+            DownloadFormat::Mp3 => Self::AUDIO,
+            DownloadFormat::Mp4 => Self::VIDEO,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OptionRow::Output => "Output",
+            OptionRow::CutStart => "Cut from",
+            OptionRow::CutEnd => "Cut to",
+            OptionRow::Pitch => "Pitch",
+            OptionRow::Tempo => "Tempo",
+            OptionRow::Bass => "Bass",
+            OptionRow::Morph => "Morph",
+        }
+    }
+
+    /// Free-text rows (edited with Enter) as opposed to ←→ adjusted ones.
+    pub fn is_text(self) -> bool {
+        matches!(self, OptionRow::CutStart | OptionRow::CutEnd)
+    }
+}
+
+impl DownloadOptions {
+    /// Current value of `row`, formatted for the popup.
+    pub fn value(&self, row: OptionRow) -> String {
+        match row {
+            OptionRow::Output => self.output.label().to_string(),
+            OptionRow::CutStart => self
+                .cut_start
+                .map_or_else(|| "start".to_string(), |s| format_timestamp(s.into())),
+            OptionRow::CutEnd => self
+                .cut_end
+                .map_or_else(|| "end".to_string(), |s| format_timestamp(s.into())),
+            OptionRow::Pitch => format!("{:+} st", self.effects.pitch_semitones),
+            OptionRow::Tempo => format!("{:.2}×", self.effects.tempo_factor),
+            OptionRow::Bass => format!("{:+} dB", self.effects.bass_gain_db),
+            OptionRow::Morph => self.effects.morph.clone(),
+        }
+    }
+
+    /// Step a ←→ row by `delta`, clamped to the ranges doracore accepts.
+    pub fn adjust(&mut self, row: OptionRow, delta: i32, format: DownloadFormat) {
+        match row {
+            OptionRow::Output => {
+                let choices = OutputKind::choices(format);
+                let pos = choices.iter().position(|k| *k == self.output).unwrap_or(0);
+                self.output = choices[cycle(pos, choices.len(), delta)];
+            }
+            OptionRow::Pitch => {
+                self.effects.pitch_semitones = (self.effects.pitch_semitones as i32 + delta).clamp(-12, 12) as i8;
+            }
+            OptionRow::Tempo => {
+                let tempo = self.effects.tempo_factor + delta as f32 * 0.05;
+                self.effects.tempo_factor = ((tempo * 100.0).round() / 100.0).clamp(0.5, 2.0);
+            }
+            OptionRow::Bass => {
+                self.effects.bass_gain_db = (self.effects.bass_gain_db as i32 + delta).clamp(-12, 12) as i8;
+            }
+            OptionRow::Morph => {
+                let pos = MORPH_PROFILES
+                    .iter()
+                    .position(|m| *m == self.effects.morph)
+                    .unwrap_or(0);
+                self.effects.morph = MORPH_PROFILES[cycle(pos, MORPH_PROFILES.len(), delta)].to_string();
+            }
+            OptionRow::CutStart | OptionRow::CutEnd => {}
+        }
+    }
+
+    /// Set a cut bound from user text (`m:ss` / `h:mm:ss`, blank clears).
+    pub fn set_cut(&mut self, row: OptionRow, text: &str) -> Result<(), &'static str> {
+        let text = text.trim();
+        let secs = if text.is_empty() {
+            None
+        } else {
+            let secs = parse_timestamp_to_secs(text).ok_or("Use m:ss or h:mm:ss")?;
+            Some(u32::try_from(secs).map_err(|_| "Time is out of range")?)
+        };
+        let (start, end) = match row {
+            OptionRow::CutStart => (secs, self.cut_end),
+            OptionRow::CutEnd => (self.cut_start, secs),
+            _ => return Ok(()),
+        };
+        if let (Some(s), Some(e)) = (start, end)
+            && e <= s
+        {
+            return Err("Cut end must be after its start");
+        }
+        self.cut_start = start;
+        self.cut_end = end;
+        Ok(())
+    }
+
+    /// Drop choices that don't apply to `format` (after an MP3 ↔ MP4 toggle).
+    pub fn fit_to(&mut self, format: DownloadFormat) {
+        if !OutputKind::choices(format).contains(&self.output) {
+            self.output = OutputKind::Original;
+        }
+        if format == DownloadFormat::Mp4 {
+            self.effects = AudioEffects::default();
+        }
+    }
+
+    /// `(start, end)` for `DownloadRequest::time_range` — yt-dlp's
+    /// `--download-sections` syntax, with `inf` for an open end.
+    pub fn time_range(&self) -> Option<(String, String)> {
+        if self.cut_start.is_none() && self.cut_end.is_none() {
+            return None;
+        }
+        let start = format_timestamp(self.cut_start.unwrap_or(0).into());
+        let end = self
+            .cut_end
+            .map_or_else(|| "inf".to_string(), |e| format_timestamp(e.into()));
+        Some((start, end))
+    }
+
+    /// Length of the cut in seconds, when both ends are known.
+    pub fn cut_secs(&self) -> Option<u32> {
+        self.cut_end.map(|end| end.saturating_sub(self.cut_start.unwrap_or(0)))
+    }
+
+    /// Short tag for the queue and history, e.g. `MP3`, `FLAC ✂`, `MP3 fx`.
+    pub fn label(&self, format: DownloadFormat) -> String {
+        let mut label = match self.output {
+            OutputKind::Original => format.label().to_string(),
+            OutputKind::IphoneRingtone | OutputKind::AndroidRingtone => "RING".to_string(),
+            other => other.label().to_uppercase(),
+        };
+        if !self.effects.is_identity() {
+            label.push_str(" fx");
+        }
+        if self.time_range().is_some() {
+            label.push_str(" ✂");
+        }
+        label
+    }
+}
+
+fn cycle(pos: usize, len: usize, delta: i32) -> usize {
+    if delta >= 0 {
+        (pos + 1) % len
+    } else {
+        (pos + len - 1) % len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_choices_follow_format() {
+        let mut opts = DownloadOptions::default();
+        opts.adjust(OptionRow::Output, 1, DownloadFormat::Mp4);
+        assert_eq!(opts.output, OutputKind::Gif);
+        opts.fit_to(DownloadFormat::Mp3);
+        assert_eq!(opts.output, OutputKind::Original);
+        opts.adjust(OptionRow::Output, -1, DownloadFormat::Mp3);
+        assert_eq!(opts.output, OutputKind::AndroidRingtone);
+    }
+
+    #[test]
+    fn effects_are_clamped_to_core_ranges() {
+        let mut opts = DownloadOptions::default();
+        for _ in 0..20 {
+            opts.adjust(OptionRow::Pitch, 1, DownloadFormat::Mp3);
+            opts.adjust(OptionRow::Tempo, -1, DownloadFormat::Mp3);
+        }
+        assert_eq!(opts.effects.pitch_semitones, 12);
+        assert!((opts.effects.tempo_factor - 0.5).abs() < f32::EPSILON);
+        assert!(doracore::download::audio_effects::validate_settings(&opts.effects.to_core()).is_ok());
+        assert!(!opts.effects.is_identity());
+    }
+
+    #[test]
+    fn cut_builds_section_range() {
+        let mut opts = DownloadOptions::default();
+        assert_eq!(opts.time_range(), None);
+        opts.set_cut(OptionRow::CutStart, "1:30").unwrap();
+        assert_eq!(opts.time_range(), Some(("1:30".to_string(), "inf".to_string())));
+        opts.set_cut(OptionRow::CutEnd, "2:00").unwrap();
+        assert_eq!(opts.time_range(), Some(("1:30".to_string(), "2:00".to_string())));
+        assert_eq!(opts.cut_secs(), Some(30));
+        assert!(opts.set_cut(OptionRow::CutEnd, "1:00").is_err());
+        assert!(opts.set_cut(OptionRow::CutStart, "abc").is_err());
+        opts.set_cut(OptionRow::CutStart, "").unwrap();
+        assert_eq!(opts.cut_start, None);
+    }
+
+    #[test]
+    fn label_marks_conversion_effects_and_cut() {
+        let mut opts = DownloadOptions::default();
+        assert_eq!(opts.label(DownloadFormat::Mp3), "MP3");
+        opts.output = OutputKind::Flac;
+        opts.effects.bass_gain_db = 3;
+        opts.cut_end = Some(60);
+        assert_eq!(opts.label(DownloadFormat::Mp3), "FLAC fx ✂");
+    }
+}
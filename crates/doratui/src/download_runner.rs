@@ -1,21 +1,32 @@
 //! Background download runner for dora TUI.
 //!
-//! Spawns yt-dlp as a child process, streams progress events back to the
-//! main loop via an mpsc channel. Handles MP3 and MP4 downloads.
-//!
-//! Uses `doradura-core` for metadata resolution and progress parsing,
-//! but calls yt-dlp directly (no Telegram/proxy overhead).
+//! Resolves each URL through doracore's `SourceRegistry` and drives the
+//! matching `DownloadSource` (yt-dlp, Instagram, direct HTTP, ...), so the
+//! TUI gets the same proxy chain, cookie tiers, error classification and
+//! highres recode as the bot. `SourceProgress` events are translated into
+//! [`SlotEvent`]s and streamed back to the main loop via an mpsc channel.
 
-use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::Context;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use doracore::core::error::AppError;
+use doracore::download::builder::DownloadConfigBuilder;
+use doracore::download::filename_template::{TemplateFields, fetch_info_json, unique_path};
+use doracore::download::source::{ProgressPhase, SourceProgress, SourceRegistry, VideoQualityPreset};
+use doracore::download::ytdlp_errors::sanitize_user_error_message;
 use tokio::sync::mpsc;
 
 use crate::app::DownloadFormat;
+use crate::download_options::{DownloadOptions, OutputKind};
 use crate::settings::DoraSettings;
 
+/// Encoding tier for 1440p+ downloads. The bot defaults to `Master`
+/// (`veryslow`), which is tuned for a server; on a laptop `Balanced` keeps a
+/// 4K recode in the minutes range.
+const HIGHRES_PRESET: VideoQualityPreset = VideoQualityPreset::Balanced;
+
 /// Options for burning subtitles into a video download.
 #[derive(Debug, Clone)]
 pub struct SubtitleOptions {
@@ -26,15 +37,22 @@ pub struct SubtitleOptions {
 /// Each event is tagged with the slot's stable ID.
 #[derive(Debug)]
 pub enum SlotEvent {
-    /// yt-dlp is fetching metadata.
+    /// Resolving the source and fetching metadata.
     Fetching,
     /// Metadata resolved — update slot title/artist.
     Metadata {
         title: Option<String>,
         artist: Option<String>,
     },
+    /// Output file chosen — saved with the queue so a restart can resume it.
+    Destination { path: String },
     /// Progress update.
     Progress { percent: u8, speed_mbs: f64, eta_secs: u64 },
+    /// ffmpeg is muxing the downloaded streams; position is how far into
+    /// the media it has got.
+    Merging { position_secs: f32 },
+    /// Post-download pass: highres recode, effects or conversion.
+    Postprocessing { label: &'static str },
     /// Download finished successfully.
     Done { path: String, size_mb: f64 },
     /// Subtitle burn in progress.
@@ -45,7 +63,9 @@ pub enum SlotEvent {
 
 /// Spawn a background download for the given slot ID and return immediately.
 ///
-/// Progress events are sent on `tx` as `(slot_id, SlotEvent)`.
+/// Progress events are sent on `tx` as `(slot_id, SlotEvent)`. When
+/// `resume_path` is set (a slot restored from the saved queue), the download
+/// continues the partial file there instead of picking a new name.
 /// Returns an [`AbortHandle`] that can be used to cancel the download task.
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_download(
@@ -55,15 +75,28 @@ pub fn spawn_download(
     settings: DoraSettings,
     tx: mpsc::Sender<(usize, SlotEvent)>,
     subtitle_opts: Option<SubtitleOptions>,
+    options: DownloadOptions,
+    resume_path: Option<String>,
 ) -> tokio::task::AbortHandle {
     tokio::spawn(async move {
-        run_download(slot_id, url, format, settings, tx, subtitle_opts).await;
+        run_download(slot_id, url, format, settings, tx, subtitle_opts, options, resume_path).await;
     })
     .abort_handle()
 }
 
 // ── Internal implementation ───────────────────────────────────────────────────
 
+/// Raises the request's cancel flag when dropped, so aborting the task
+/// (`AbortHandle::abort`) also stops the yt-dlp / ffmpeg child that the
+/// source runs on a blocking thread.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn run_download(
     slot_id: usize,
@@ -72,14 +105,59 @@ async fn run_download(
     settings: DoraSettings,
     tx: mpsc::Sender<(usize, SlotEvent)>,
     subtitle_opts: Option<SubtitleOptions>,
+    options: DownloadOptions,
+    resume_path: Option<String>,
 ) {
-    log::info!("[slot {}] start download: {} ({:?})", slot_id, url, format);
+    log::info!(
+        "[slot {}] start download: {} ({:?}, {:?})",
+        slot_id,
+        url,
+        format,
+        options
+    );
 
     // Signal that we are fetching metadata
     let _ = tx.send((slot_id, SlotEvent::Fetching)).await;
 
-    // 1. Resolve title + artist via core's SourceRegistry (best-effort)
-    let (title, artist) = fetch_metadata(&url).await;
+    // 1. Resolve the backend for this URL
+    let parsed = match url.trim().parse::<url::Url>() {
+        Ok(u) => u,
+        Err(_) => {
+            let _ = tx
+                .send((
+                    slot_id,
+                    SlotEvent::Failed {
+                        reason: "Not a valid link".to_string(),
+                    },
+                ))
+                .await;
+            return;
+        }
+    };
+    let Some(source) = SourceRegistry::global().resolve(&parsed) else {
+        let _ = tx
+            .send((
+                slot_id,
+                SlotEvent::Failed {
+                    reason: "Unsupported link".to_string(),
+                },
+            ))
+            .await;
+        return;
+    };
+    log::debug!("[slot {}] source: {}", slot_id, source.name());
+
+    // 2. Title + artist (best-effort — only used for the slot and filename)
+    let (title, artist) = match source.get_metadata(&parsed).await {
+        Ok(meta) => (
+            Some(meta.title).filter(|s| !s.is_empty()),
+            Some(meta.artist).filter(|s| !s.is_empty()),
+        ),
+        Err(e) => {
+            log::debug!("[slot {}] metadata unavailable: {}", slot_id, e);
+            (None, None)
+        }
+    };
     log::debug!("[slot {}] metadata: title={:?} artist={:?}", slot_id, title, artist);
     let _ = tx
         .send((
@@ -91,96 +169,119 @@ async fn run_download(
         ))
         .await;
 
-    // 2. Prepare output directory (from settings, or ~/Downloads fallback)
-    let out_dir = PathBuf::from(settings.output_dir());
-    if !out_dir.exists() {
-        let _ = fs_err::tokio::create_dir_all(&out_dir).await;
-    }
-    let template = format!("{}/%(title)s.%(ext)s", out_dir.to_string_lossy());
-
-    // 3. Build yt-dlp command args
-    let ytdlp = if settings.ytdlp_bin.trim().is_empty() {
-        ytdlp_bin()
-    } else {
-        settings.ytdlp_bin.clone()
+    // 3. Output path inside the configured folder (or ~/Downloads fallback),
+    //    or the one an interrupted run was writing to.
+    let ext = match format {
+        DownloadFormat::Mp3 => "mp3",
+        DownloadFormat::Mp4 => "mp4",
+    };
+    let resume = resume_path.is_some();
+    let output_path = match resume_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let out_dir = PathBuf::from(settings.output_dir());
+            let template = settings.filename_template();
+            let mut fields = TemplateFields::for_url(&parsed);
+            fields.title = title.clone();
+            fields.artist = artist.clone();
+            fields.format = Some(ext.to_string());
+            fields.quality = Some(match format {
+                DownloadFormat::Mp3 => settings.audio_bitrate.clone(),
+                DownloadFormat::Mp4 => settings.video_quality.clone(),
+            })
+            .filter(|q| !q.is_empty());
+            // Album, upload date etc. are not in the quick metadata.
+            if template.needs_info_json() {
+                let ytdl_bin = settings.ytdlp_bin_opt();
+                let cookies = settings.cookies_opt();
+                match fetch_info_json(&parsed, ytdl_bin.as_deref(), cookies.as_deref()).await {
+                    Ok(info) => fields.merge_info_json(&info),
+                    Err(e) => log::debug!("[slot {}] info JSON unavailable: {}", slot_id, e),
+                }
+            }
+            let rel = template
+                .render(&fields, ext)
+                .unwrap_or_else(|| PathBuf::from(format!("Unknown.{}", ext)));
+            let path = unique_path(&out_dir, rel);
+            if let Some(parent) = path.parent()
+                && !parent.exists()
+            {
+                let _ = fs_err::tokio::create_dir_all(parent).await;
+            }
+            let _ = tx
+                .send((
+                    slot_id,
+                    SlotEvent::Destination {
+                        path: path.to_string_lossy().into_owned(),
+                    },
+                ))
+                .await;
+            path
+        }
     };
-    let mut args: Vec<String> = vec![
-        "-o".to_string(),
-        template,
-        "--newline".to_string(),
-        "--no-playlist".to_string(),
-        "--no-check-certificate".to_string(),
-    ];
 
-    // Rate limit
-    if let Some(rate) = settings.rate_limit_arg() {
-        args.push("--limit-rate".to_string());
-        args.push(rate.to_string());
+    // 4. Build the request the same way the bot does
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let _cancel_guard = CancelOnDrop(Arc::clone(&cancel_flag));
+    let mut builder = DownloadConfigBuilder::new(parsed)
+        .format(ext)
+        .output_path(&output_path.to_string_lossy())
+        .quality_preset(HIGHRES_PRESET)
+        .resume_partial(resume)
+        .cancel_flag(cancel_flag);
+    if let Some(rate) = settings.slot_rate_limit() {
+        builder = builder.limit_rate(&rate);
     }
-
-    // Cookies file
-    if let Some(cf) = settings.cookies_opt() {
-        args.push("--cookies".to_string());
-        args.push(cf);
-    }
-
-    // Modern yt-dlp args for YouTube age-restriction / bot detection bypass
-    args.push("--extractor-args".to_string());
-    args.push("youtube:player_client=android_vr,web_safari;formats=missing_pot".to_string());
-    args.push("--js-runtimes".to_string());
-    args.push("deno".to_string());
-
-    match format {
-        DownloadFormat::Mp3 => {
-            let bitrate = if settings.audio_bitrate.is_empty() {
-                "320k".to_string()
-            } else {
-                settings.audio_bitrate.clone()
-            };
-            args.extend([
-                "--extract-audio".to_string(),
-                "--audio-format".to_string(),
-                "mp3".to_string(),
-                "--audio-quality".to_string(),
-                bitrate,
-                "--add-metadata".to_string(),
-            ]);
-        }
-        DownloadFormat::Mp4 => {
-            let fmt_filter = match settings.video_quality.as_str() {
-                "1080p" => "bestvideo[height<=1080][ext=mp4]+bestaudio[ext=m4a]/best[height<=1080][ext=mp4]/best",
-                "720p" => "bestvideo[height<=720][ext=mp4]+bestaudio[ext=m4a]/best[height<=720][ext=mp4]/best",
-                "480p" => "bestvideo[height<=480][ext=mp4]+bestaudio[ext=m4a]/best[height<=480][ext=mp4]/best",
-                "360p" => "bestvideo[height<=360][ext=mp4]+bestaudio[ext=m4a]/best[height<=360][ext=mp4]/best",
-                _ => "bestvideo[ext=mp4]+bestaudio[ext=m4a]/best[ext=mp4]/best",
-            };
-            args.extend([
-                "--format".to_string(),
-                fmt_filter.to_string(),
-                "--merge-output-format".to_string(),
-                "mp4".to_string(),
-            ]);
-        }
+    // Read from the settings on every download, so edits apply without a restart.
+    if let Some(bin) = settings.ytdlp_bin_opt() {
+        builder = builder.ytdl_bin(&bin);
     }
+    if let Some(cookies) = settings.cookies_opt() {
+        builder = builder.cookies_file(&cookies);
+    }
+    if let Some(cookies) = settings.instagram_cookies_opt() {
+        builder = builder.instagram_cookies_file(&cookies);
+    }
+    if let Some(doc_id) = settings.instagram_doc_id_opt() {
+        builder = builder.instagram_doc_id(&doc_id);
+    }
+    builder = match format {
+        DownloadFormat::Mp3 if !settings.audio_bitrate.is_empty() => builder.audio_bitrate(&settings.audio_bitrate),
+        DownloadFormat::Mp4 if !settings.video_quality.is_empty() => builder.video_quality(&settings.video_quality),
+        _ => builder,
+    };
+    if let Some((start, end)) = options.time_range() {
+        builder = builder.time_range(&start, &end);
+    }
+    let request = builder.build(
+        title.as_deref().unwrap_or_default(),
+        artist.as_deref().unwrap_or_default(),
+    );
 
-    args.push(url.clone());
-
-    log::info!("[slot {}] yt-dlp cmd: {} {}", slot_id, ytdlp, args.join(" "));
+    // 5. Run the source, forwarding its progress until it finishes
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel::<SourceProgress>();
+    let download = source.download(&request, progress_tx);
+    tokio::pin!(download);
+    let result = loop {
+        tokio::select! {
+            Some(sp) = progress_rx.recv() => {
+                if let Some(ev) = progress_event(&sp) {
+                    let _ = tx.send((slot_id, ev)).await;
+                }
+            }
+            result = &mut download => break result,
+        }
+    };
 
-    // 4. Spawn yt-dlp
-    let mut child = match tokio::process::Command::new(&ytdlp)
-        .args(&args)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-    {
-        Ok(c) => c,
+    let output = match result {
+        Ok(output) => output,
         Err(e) => {
+            log::warn!("[slot {}] failed: {}", slot_id, e);
             let _ = tx
                 .send((
                     slot_id,
                     SlotEvent::Failed {
-                        reason: format!("Cannot start yt-dlp: {}", e),
+                        reason: failure_reason(&e),
                     },
                 ))
                 .await;
@@ -188,146 +289,215 @@ async fn run_download(
         }
     };
 
-    let Some(stdout) = child.stdout.take() else {
-        let _ = tx
-            .send((
-                slot_id,
-                SlotEvent::Failed {
-                    reason: "yt-dlp stdout pipe was not available".to_string(),
-                },
-            ))
-            .await;
-        return;
-    };
-    let Some(stderr) = child.stderr.take() else {
-        let _ = tx
-            .send((
-                slot_id,
-                SlotEvent::Failed {
-                    reason: "yt-dlp stderr pipe was not available".to_string(),
-                },
-            ))
-            .await;
-        return;
-    };
+    let mut path_str = output.file_path;
 
-    // Track the final file path as yt-dlp announces destinations
-    let captured_path = std::sync::Arc::new(tokio::sync::Mutex::new(None::<String>));
-
-    // Stream stdout: progress events + destination capture
-    let tx_out = tx.clone();
-    let path_out = captured_path.clone();
-    let stdout_task = tokio::spawn(async move {
-        let mut lines = BufReader::new(stdout).lines();
-        while let Ok(Some(line)) = lines.next_line().await {
-            if let Some(dest) = parse_destination(&line) {
-                *path_out.lock().await = Some(dest);
+    // Burn subtitles if requested and format is MP4
+    if let (Some(sub_opts), DownloadFormat::Mp4) = (&subtitle_opts, format) {
+        match burn_subtitles(slot_id, &url, &path_str, sub_opts, &settings, &tx).await {
+            Ok(burned_path) => {
+                path_str = burned_path;
             }
-            if let Some(ev) = parse_yt_progress(&line) {
-                let _ = tx_out.send((slot_id, ev)).await;
+            Err(e) => {
+                log::warn!("[slot {}] subtitle burn failed: {}", slot_id, e);
+                // Continue with the original video (non-fatal)
             }
         }
-    });
-
-    // Stream stderr: progress events + destination capture + error collection
-    let tx_err = tx.clone();
-    let path_err = captured_path.clone();
-    let stderr_task = tokio::spawn(async move {
-        let mut lines = BufReader::new(stderr).lines();
-        let mut tail: VecDeque<String> = VecDeque::new();
-        while let Ok(Some(line)) = lines.next_line().await {
-            log::debug!("yt-dlp stderr: {}", line);
-            if let Some(dest) = parse_destination(&line) {
-                *path_err.lock().await = Some(dest);
-            }
-            if let Some(ev) = parse_yt_progress(&line) {
-                let _ = tx_err.send((slot_id, ev)).await;
-            }
-            tail.push_back(line);
-            if tail.len() > 60 {
-                tail.pop_front();
+    }
+
+    // Effects and output conversion chosen in the options popup
+    if options != DownloadOptions::default() {
+        match apply_options(slot_id, &path_str, format, &options, &settings, &tx).await {
+            Ok(final_path) => path_str = final_path,
+            Err(e) => {
+                log::warn!("[slot {}] options failed: {:#}", slot_id, e);
+                let _ = tx
+                    .send((
+                        slot_id,
+                        SlotEvent::Failed {
+                            reason: format!("{:#}", e),
+                        },
+                    ))
+                    .await;
+                return;
             }
         }
-        tail
-    });
+    }
 
-    let status = match child.wait().await {
-        Ok(s) => s,
-        Err(e) => {
-            let _ = tx.send((slot_id, SlotEvent::Failed { reason: e.to_string() })).await;
-            return;
-        }
-    };
+    let size_mb = fs_err::metadata(&path_str).map(|m| m.len()).unwrap_or(output.file_size) as f64 / 1_048_576.0;
+    log::info!("[slot {}] done: {} ({:.1} MB)", slot_id, path_str, size_mb);
+    let _ = tx
+        .send((
+            slot_id,
+            SlotEvent::Done {
+                path: path_str,
+                size_mb,
+            },
+        ))
+        .await;
+}
 
-    let _ = stdout_task.await;
-    let tail = stderr_task.await.unwrap_or_default();
+// ── Helpers ───────────────────────────────────────────────────────────────────
 
-    if status.success() {
-        let path = captured_path.lock().await.clone();
-        let mut path_str = path.unwrap_or_else(|| out_dir.to_string_lossy().to_string());
+/// Map a core [`SourceProgress`] onto a slot event.
+///
+/// Bare `[Merger]` notices (no ffmpeg position yet) are dropped, like the
+/// bot does, so the slot doesn't flip to "merging 0:00" and sit there when
+/// yt-dlp swallows ffmpeg's per-second output.
+fn progress_event(sp: &SourceProgress) -> Option<SlotEvent> {
+    match sp.phase {
+        ProgressPhase::Download => Some(SlotEvent::Progress {
+            percent: sp.percent,
+            speed_mbs: sp.speed_bytes_sec.unwrap_or(0.0) / 1_048_576.0,
+            eta_secs: sp.eta_seconds.unwrap_or(0),
+        }),
+        ProgressPhase::Merging => sp
+            .merge_position_secs
+            .filter(|pos| *pos > 0.5)
+            .map(|position_secs| SlotEvent::Merging { position_secs }),
+        ProgressPhase::Postprocessing => Some(SlotEvent::Postprocessing { label: "recoding" }),
+    }
+}
 
-        // Burn subtitles if requested and format is MP4
-        if let (Some(sub_opts), DownloadFormat::Mp4) = (&subtitle_opts, format) {
-            match burn_subtitles(slot_id, &url, &path_str, sub_opts, &settings, &tx).await {
-                Ok(burned_path) => {
-                    path_str = burned_path;
-                }
-                Err(e) => {
-                    log::warn!("[slot {}] subtitle burn failed: {}", slot_id, e);
-                    // Continue with the original video (non-fatal)
-                }
-            }
-        }
+/// One-line failure text for the slot: the classified `YtDlpErrorType`
+/// message for yt-dlp failures, otherwise the source's own error, with the
+/// Telegram-style "❌ …\n\n…" layout flattened.
+fn failure_reason(err: &AppError) -> String {
+    let raw = match err {
+        AppError::Download(e) => e.to_string(),
+        other => other.to_string(),
+    };
+    let message = sanitize_user_error_message(&raw);
+    message
+        .trim_start_matches('❌')
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" — ")
+}
 
-        let size_mb = fs_err::metadata(&path_str)
-            .map(|m| m.len() as f64 / 1_048_576.0)
-            .unwrap_or(0.0);
-        log::info!("[slot {}] done: {} ({:.1} MB)", slot_id, path_str, size_mb);
+// ── Download options pipeline ────────────────────────────────────────────────
+
+/// Apply the popup's audio effects, then its output conversion, to the
+/// downloaded file. Returns the final path; the intermediate download is
+/// removed once the converted file is in place.
+async fn apply_options(
+    slot_id: usize,
+    path: &str,
+    format: DownloadFormat,
+    options: &DownloadOptions,
+    settings: &DoraSettings,
+    tx: &mpsc::Sender<(usize, SlotEvent)>,
+) -> anyhow::Result<String> {
+    let source = PathBuf::from(path);
+    let dir = source.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+    let stem = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("download")
+        .to_string();
+
+    // 1. Pitch / tempo / bass / morph (audio only), rewritten in place
+    if format == DownloadFormat::Mp3 && !options.effects.is_identity() {
         let _ = tx
             .send((
                 slot_id,
-                SlotEvent::Done {
-                    path: path_str,
-                    size_mb,
+                SlotEvent::Postprocessing {
+                    label: "applying effects",
                 },
             ))
             .await;
-    } else {
-        // Find the last meaningful error line (skip warnings and empty lines)
-        let reason = tail
-            .iter()
-            .rev()
-            .find(|l| {
-                let t = l.trim();
-                !t.is_empty() && !t.starts_with("WARNING") && !t.starts_with("[download]")
-            })
-            .cloned()
-            .unwrap_or_else(|| "Download failed".to_string());
-        let reason = reason.trim_start_matches("ERROR: ").trim().to_string();
-        log::warn!("[slot {}] failed: {}", slot_id, reason);
-        let _ = tx.send((slot_id, SlotEvent::Failed { reason })).await;
+        let fx_path = dir.join(format!("{}_fx.mp3", stem));
+        doracore::download::audio_effects::apply_audio_effects(
+            path,
+            &fx_path.to_string_lossy(),
+            &options.effects.to_core(),
+            None::<fn(std::time::Duration)>,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Audio effects failed: {}", e))?;
+        fs_err::tokio::rename(&fx_path, &source)
+            .await
+            .context("Cannot replace file with processed audio")?;
     }
-}
 
-// ── Helpers ───────────────────────────────────────────────────────────────────
+    if options.output == OutputKind::Original {
+        return Ok(path.to_string());
+    }
 
-/// Fetch title + artist using doradura-core's SourceRegistry (best-effort).
-async fn fetch_metadata(url: &str) -> (Option<String>, Option<String>) {
-    let parsed = match url.parse::<url::Url>() {
-        Ok(u) => u,
-        Err(_) => return (None, None),
+    // 2. Output conversion
+    let _ = tx
+        .send((slot_id, SlotEvent::Postprocessing { label: "converting" }))
+        .await;
+    let target = match options.output {
+        OutputKind::IphoneRingtone => {
+            let target = unique_path(&dir, format!("{}.m4r", stem));
+            doracore::download::ringtone::create_iphone_ringtone(
+                &source,
+                &target,
+                0,
+                options.cut_secs().unwrap_or(u32::MAX),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Ringtone failed: {}", e))?;
+            target
+        }
+        OutputKind::AndroidRingtone => {
+            let target = unique_path(&dir, format!("{}_ringtone.mp3", stem));
+            doracore::download::ringtone::create_android_ringtone(
+                &source,
+                &target,
+                0,
+                options.cut_secs().unwrap_or(u32::MAX),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Ringtone failed: {}", e))?;
+            target
+        }
+        OutputKind::Gif => {
+            let gif_opts = doracore::conversion::video::GifOptions {
+                duration: options.cut_secs().map(u64::from).or(Some(10)),
+                ..Default::default()
+            };
+            let tmp = doracore::conversion::video::to_gif(&source, gif_opts)
+                .await
+                .context("GIF conversion failed")?;
+            let target = unique_path(&dir, format!("{}.gif", stem));
+            move_file(&tmp, &target).await?;
+            target
+        }
+        kind => {
+            let audio_format = kind
+                .audio_format()
+                .context("Output does not match the downloaded format")?;
+            let tmp = doracore::conversion::audio::convert_audio(
+                &source,
+                audio_format,
+                Some(settings.audio_bitrate.as_str()).filter(|b| !b.is_empty()),
+            )
+            .await
+            .with_context(|| format!("{} conversion failed", audio_format.display_name()))?;
+            let target = unique_path(&dir, format!("{}.{}", stem, audio_format.extension()));
+            move_file(&tmp, &target).await?;
+            target
+        }
     };
-    let registry = doracore::download::source::SourceRegistry::global();
-    match registry.resolve(&parsed) {
-        Some(source) => match source.get_metadata(&parsed).await {
-            Ok(meta) => (
-                Some(meta.title).filter(|s| !s.is_empty()),
-                Some(meta.artist).filter(|s| !s.is_empty()),
-            ),
-            Err(_) => (None, None),
-        },
-        None => (None, None),
+
+    let _ = fs_err::tokio::remove_file(&source).await;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Rename, falling back to copy + delete when `from` is on another
+/// filesystem (doracore converts into the system temp dir).
+async fn move_file(from: &Path, to: &Path) -> anyhow::Result<()> {
+    if fs_err::tokio::rename(from, to).await.is_ok() {
+        return Ok(());
     }
+    fs_err::tokio::copy(from, to)
+        .await
+        .context("Cannot move converted file into the output folder")?;
+    let _ = fs_err::tokio::remove_file(from).await;
+    Ok(())
 }
 
 /// Return the yt-dlp binary name, honouring the `YTDL_BIN` env var (same as doradura-core).
@@ -335,41 +505,6 @@ fn ytdlp_bin() -> String {
     doracore::config::YTDL_BIN.clone()
 }
 
-/// Parse a yt-dlp stdout/stderr line for a progress update.
-/// Delegates to doradura-core's `parse_progress` for consistency.
-fn parse_yt_progress(line: &str) -> Option<SlotEvent> {
-    let sp = doracore::download::parse_progress(line)?;
-    Some(SlotEvent::Progress {
-        percent: sp.percent,
-        speed_mbs: sp.speed_bytes_sec.unwrap_or(0.0) / 1_048_576.0,
-        eta_secs: sp.eta_seconds.unwrap_or(0),
-    })
-}
-
-/// Extract a file path from known yt-dlp destination announcement lines:
-///
-/// - `[download] Destination: /path/to/file.ext`
-/// - `[ExtractAudio] Destination: /path/to/file.mp3`
-/// - `[Merger] Merging formats into "/path/to/file.mp4"`
-fn parse_destination(line: &str) -> Option<String> {
-    for prefix in &["[download] Destination: ", "[ExtractAudio] Destination: "] {
-        if let Some(rest) = line.strip_prefix(prefix) {
-            let path = rest.trim();
-            if !path.is_empty() {
-                return Some(path.to_string());
-            }
-        }
-    }
-    // [Merger] Merging formats into "/path/to/file.mp4"
-    if let Some(rest) = line.strip_prefix("[Merger] Merging formats into \"") {
-        let path = rest.trim_end_matches('"').trim();
-        if !path.is_empty() {
-            return Some(path.to_string());
-        }
-    }
-    None
-}
-
 // ── Subtitle burn pipeline ────────────────────────────────────────────────────
 
 /// Download SRT subtitles via yt-dlp and burn them into the video with ffmpeg.
@@ -598,31 +733,81 @@ fn find_srt_file(dir: &std::path::Path, stem: &str, lang: &str) -> anyhow::Resul
 mod tests {
     use super::*;
 
-    // ── parse_destination tests ──────────────────────────────────────────────
+    // ── progress_event tests ────────────────────────────────────────────────
+
+    #[test]
+    fn progress_event_maps_download_phase() {
+        let sp = SourceProgress {
+            percent: 42,
+            speed_bytes_sec: Some(2.0 * 1_048_576.0),
+            eta_seconds: Some(30),
+            ..Default::default()
+        };
+        match progress_event(&sp) {
+            Some(SlotEvent::Progress {
+                percent,
+                speed_mbs,
+                eta_secs,
+            }) => {
+                assert_eq!(percent, 42);
+                assert!((speed_mbs - 2.0).abs() < f64::EPSILON);
+                assert_eq!(eta_secs, 30);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
 
     #[test]
-    fn parse_destination_download() {
-        let line = "[download] Destination: /tmp/video.mp4";
-        assert_eq!(parse_destination(line), Some("/tmp/video.mp4".to_string()));
+    fn progress_event_skips_merge_without_position() {
+        let bare = SourceProgress {
+            phase: ProgressPhase::Merging,
+            ..Default::default()
+        };
+        assert!(progress_event(&bare).is_none());
+
+        let started = SourceProgress {
+            phase: ProgressPhase::Merging,
+            merge_position_secs: Some(12.5),
+            ..Default::default()
+        };
+        assert!(matches!(
+            progress_event(&started),
+            Some(SlotEvent::Merging { position_secs }) if position_secs == 12.5
+        ));
     }
 
     #[test]
-    fn parse_destination_extract_audio() {
-        let line = "[ExtractAudio] Destination: /home/user/song.mp3";
-        assert_eq!(parse_destination(line), Some("/home/user/song.mp3".to_string()));
+    fn progress_event_maps_postprocessing() {
+        let sp = SourceProgress {
+            percent: 100,
+            phase: ProgressPhase::Postprocessing,
+            ..Default::default()
+        };
+        assert!(matches!(
+            progress_event(&sp),
+            Some(SlotEvent::Postprocessing { label: "recoding" })
+        ));
     }
 
+    // ── failure_reason tests ─────────────────────────────────────────────────
+
     #[test]
-    fn parse_destination_merger() {
-        let line = "[Merger] Merging formats into \"/tmp/video.mp4\"";
-        assert_eq!(parse_destination(line), Some("/tmp/video.mp4".to_string()));
+    fn failure_reason_uses_classified_message() {
+        let err = AppError::Download(doracore::download::DownloadError::YtDlp(
+            "ERROR: [youtube] abc: Sign in to confirm you're not a bot".to_string(),
+        ));
+        assert_eq!(
+            failure_reason(&err),
+            "YouTube blocked the request. — Try a different video or retry later."
+        );
     }
 
     #[test]
-    fn parse_destination_unrelated_line() {
-        assert_eq!(parse_destination("[download] 50.0% of 10MiB"), None);
-        assert_eq!(parse_destination("WARNING: something"), None);
-        assert_eq!(parse_destination(""), None);
+    fn failure_reason_keeps_plain_errors() {
+        let err = AppError::Download(doracore::download::DownloadError::Instagram(
+            "Account is private".to_string(),
+        ));
+        assert_eq!(failure_reason(&err), "Account is private");
     }
 
     // ── find_srt_file tests ─────────────────────────────────────────────────
@@ -0,0 +1,654 @@
+//! Download history, stored in `~/.config/dora/history.db` (SQLite).
+//!
+//! Title, artist and URL are indexed with FTS5, so searching stays instant
+//! with years of downloads; format, date range, platform and size are plain
+//! columns the history panel filters on. The same queries feed the Stats
+//! panel.
+//!
+//! The TUI and `dora daemon` each hold their own connection; the TUI polls
+//! [`HistoryDb::data_version`] to notice downloads the daemon recorded. The
+//! old `history.json` is imported on first open and renamed to
+//! `history.json.migrated`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate, TimeDelta};
+use doracore::core::metrics::extract_platform;
+use rusqlite::types::{Type, Value};
+use rusqlite::{Connection, Row, TransactionBehavior, params, params_from_iter};
+
+use crate::app::{DownloadFormat, HistoryEntry, HistorySortMode};
+
+/// Bumped whenever [`SCHEMA`] changes.
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    id            INTEGER PRIMARY KEY,
+    title         TEXT NOT NULL,
+    artist        TEXT NOT NULL,
+    format        TEXT NOT NULL,
+    size_mb       REAL NOT NULL,
+    path          TEXT NOT NULL,
+    finished_at   TEXT NOT NULL,
+    finished_ts   INTEGER NOT NULL,
+    day           TEXT NOT NULL,
+    url           TEXT NOT NULL,
+    platform      TEXT NOT NULL,
+    thumbnail_url TEXT,
+    options       TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS history_finished_ts ON history(finished_ts);
+CREATE INDEX IF NOT EXISTS history_day ON history(day);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+    title, artist, url,
+    content='history', content_rowid='id',
+    tokenize='unicode61 remove_diacritics 2'
+);
+CREATE TRIGGER IF NOT EXISTS history_ai AFTER INSERT ON history BEGIN
+    INSERT INTO history_fts(rowid, title, artist, url) VALUES (new.id, new.title, new.artist, new.url);
+END;
+CREATE TRIGGER IF NOT EXISTS history_ad AFTER DELETE ON history BEGIN
+    INSERT INTO history_fts(history_fts, rowid, title, artist, url)
+    VALUES ('delete', old.id, old.title, old.artist, old.url);
+END;
+";
+
+const COLUMNS: &str = "id, title, artist, format, size_mb, path, finished_at, url, thumbnail_url, options";
+
+/// Artists listed in the Stats panel.
+const TOP_ARTISTS: i64 = 5;
+
+// ── Facets ────────────────────────────────────────────────────────────────────
+
+/// Date-range facet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Period {
+    #[default]
+    All,
+    Today,
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    pub fn next(self) -> Self {
+        match self {
+            Self::All => Self::Today,
+            Self::Today => Self::Week,
+            Self::Week => Self::Month,
+            Self::Month => Self::Year,
+            Self::Year => Self::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::All => "Any time",
+            Self::Today => "Today",
+            Self::Week => "7 days",
+            Self::Month => "30 days",
+            Self::Year => "1 year",
+        }
+    }
+
+    /// Earliest finish time included, or `None` for no limit.
+    fn since(self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Self::All => None,
+            Self::Today => now
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .and_then(|midnight| midnight.and_local_timezone(Local).earliest()),
+            Self::Week => Some(now - TimeDelta::days(7)),
+            Self::Month => Some(now - TimeDelta::days(30)),
+            Self::Year => Some(now - TimeDelta::days(365)),
+        }
+    }
+}
+
+/// File-size facet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeRange {
+    #[default]
+    Any,
+    /// Under 10 MB.
+    Small,
+    /// 10–100 MB.
+    Medium,
+    /// 100 MB and up.
+    Large,
+}
+
+impl SizeRange {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Any => Self::Small,
+            Self::Small => Self::Medium,
+            Self::Medium => Self::Large,
+            Self::Large => Self::Any,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Any => "Any size",
+            Self::Small => "< 10 MB",
+            Self::Medium => "10–100 MB",
+            Self::Large => "≥ 100 MB",
+        }
+    }
+
+    /// `[min, max)` in MB.
+    fn bounds(self) -> (Option<f64>, Option<f64>) {
+        match self {
+            Self::Any => (None, None),
+            Self::Small => (None, Some(10.0)),
+            Self::Medium => (Some(10.0), Some(100.0)),
+            Self::Large => (Some(100.0), None),
+        }
+    }
+}
+
+/// Facets narrowing the history panel, on top of the search text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryFilters {
+    pub format: Option<DownloadFormat>,
+    pub period: Period,
+    /// Platform name as returned by `extract_platform` (`"youtube"`, …).
+    pub platform: Option<String>,
+    pub size: SizeRange,
+}
+
+impl HistoryFilters {
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Cycle the format facet: any → MP3 → MP4 → any.
+    pub fn next_format(&mut self) {
+        self.format = match self.format {
+            None => Some(DownloadFormat::Mp3),
+            Some(DownloadFormat::Mp3) => Some(DownloadFormat::Mp4),
+            Some(DownloadFormat::Mp4) => None,
+        };
+    }
+
+    /// Cycle the platform facet through `available`, then back to any.
+    pub fn next_platform(&mut self, available: &[String]) {
+        let next = match &self.platform {
+            None => 0,
+            Some(current) => available.iter().position(|p| p == current).map_or(0, |i| i + 1),
+        };
+        self.platform = available.get(next).cloned();
+    }
+}
+
+/// Search text, facets and order — everything that decides which history
+/// entries are listed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryQuery {
+    pub text: String,
+    pub filters: HistoryFilters,
+    pub sort: HistorySortMode,
+}
+
+/// Aggregates for the Stats panel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryStats {
+    pub count: u64,
+    pub total_mb: f64,
+    /// Downloads per local day, oldest first, zero-filled.
+    pub per_day: Vec<(NaiveDate, u64)>,
+    /// Most downloaded artists, most first.
+    pub top_artists: Vec<(String, u64)>,
+    /// Downloads per platform, most first.
+    pub platforms: Vec<(String, u64)>,
+}
+
+// ── Store ─────────────────────────────────────────────────────────────────────
+
+pub struct HistoryDb {
+    conn: Connection,
+}
+
+impl HistoryDb {
+    /// Open (creating if needed) the default database and import the legacy
+    /// `history.json` if one is still around.
+    pub fn open_default() -> anyhow::Result<Self> {
+        let path = config_dir().join("history.db");
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        let mut db = Self::open(&path)?;
+        match db.import_json(&config_dir().join("history.json")) {
+            Ok(0) => {}
+            Ok(n) => log::info!("history: imported {} entries from history.json", n),
+            Err(e) => log::warn!("history: could not import history.json: {}", e),
+        }
+        Ok(db)
+    }
+
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::init(conn)
+    }
+
+    /// A throwaway database (demo mode, or when the file can't be opened).
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> anyhow::Result<Self> {
+        let version: i64 = conn.pragma_query_value(None, "user_version", |r| r.get(0))?;
+        if version < SCHEMA_VERSION {
+            conn.execute_batch(SCHEMA)?;
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+        Ok(Self { conn })
+    }
+
+    /// Import a legacy `history.json` (oldest first) and rename it to
+    /// `history.json.migrated`. Returns how many entries were imported; 0 if
+    /// there was no file. Runs under a write lock, so the TUI and the daemon
+    /// starting together import it once.
+    pub fn import_json(&mut self, json: &Path) -> anyhow::Result<usize> {
+        let tx = self.conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        if !json.exists() {
+            return Ok(0);
+        }
+        let entries: Vec<HistoryEntry> = serde_json::from_str(&fs_err::read_to_string(json)?)?;
+        for entry in &entries {
+            insert_entry(&tx, entry)?;
+        }
+        let mut migrated = json.as_os_str().to_owned();
+        migrated.push(".migrated");
+        fs_err::rename(json, PathBuf::from(migrated))?;
+        tx.commit()?;
+        Ok(entries.len())
+    }
+
+    /// Record a finished download; returns its id.
+    pub fn insert(&self, entry: &HistoryEntry) -> anyhow::Result<i64> {
+        Ok(insert_entry(&self.conn, entry)?)
+    }
+
+    pub fn delete(&self, id: i64) -> anyhow::Result<()> {
+        self.conn.execute("DELETE FROM history WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// Every entry, oldest first.
+    pub fn load_all(&self) -> anyhow::Result<Vec<HistoryEntry>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {} FROM history ORDER BY id", COLUMNS))?;
+        let entries = stmt.query_map([], entry_from_row)?.collect::<Result<_, _>>()?;
+        Ok(entries)
+    }
+
+    /// Ids of the entries matching `query`, in its sort order.
+    pub fn search_ids(&self, query: &HistoryQuery) -> anyhow::Result<Vec<i64>> {
+        let (clause, args) = where_clause(query, Local::now());
+        let sql = format!(
+            "SELECT id FROM history WHERE {} ORDER BY {}",
+            clause,
+            order_by(query.sort)
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let ids = stmt
+            .query_map(params_from_iter(args), |r| r.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(ids)
+    }
+
+    /// Up to `limit` entries matching `query`, in its sort order.
+    pub fn search(&self, query: &HistoryQuery, limit: usize) -> anyhow::Result<Vec<HistoryEntry>> {
+        let (clause, mut args) = where_clause(query, Local::now());
+        args.push(Value::Integer(limit as i64));
+        let sql = format!(
+            "SELECT {} FROM history WHERE {} ORDER BY {} LIMIT ?",
+            COLUMNS,
+            clause,
+            order_by(query.sort)
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let entries = stmt
+            .query_map(params_from_iter(args), entry_from_row)?
+            .collect::<Result<_, _>>()?;
+        Ok(entries)
+    }
+
+    /// Aggregates over the entries matching `query`, with a per-day series
+    /// covering the `days` days up to `today`.
+    pub fn stats(&self, query: &HistoryQuery, days: usize, today: NaiveDate) -> anyhow::Result<HistoryStats> {
+        let (clause, args) = where_clause(query, Local::now());
+        let (count, total_mb): (i64, f64) = self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM(size_mb), 0) FROM history WHERE {}",
+                clause
+            ),
+            params_from_iter(args.iter()),
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+
+        let first = today - TimeDelta::days(days.saturating_sub(1) as i64);
+        let mut day_args = args.clone();
+        day_args.push(Value::Text(first.to_string()));
+        let counts: HashMap<String, u64> = self
+            .conn
+            .prepare(&format!(
+                "SELECT day, COUNT(*) FROM history WHERE {} AND day >= ? GROUP BY day",
+                clause
+            ))?
+            .query_map(params_from_iter(day_args), |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        let per_day = first
+            .iter_days()
+            .take(days)
+            .map(|d| (d, counts.get(&d.to_string()).copied().unwrap_or(0)))
+            .collect();
+
+        // A negative LIMIT means no limit.
+        let grouped = |column: &str, extra: &str, limit: i64| -> anyhow::Result<Vec<(String, u64)>> {
+            let mut group_args = args.clone();
+            group_args.push(Value::Integer(limit));
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {col}, COUNT(*) AS n FROM history WHERE {} {} GROUP BY {col} ORDER BY n DESC, {col} LIMIT ?",
+                clause,
+                extra,
+                col = column
+            ))?;
+            let rows = stmt
+                .query_map(params_from_iter(group_args), |r| Ok((r.get(0)?, r.get(1)?)))?
+                .collect::<Result<_, _>>()?;
+            Ok(rows)
+        };
+
+        Ok(HistoryStats {
+            count: count as u64,
+            total_mb,
+            per_day,
+            top_artists: grouped("artist", "AND artist != ''", TOP_ARTISTS)?,
+            platforms: grouped("platform", "", -1)?,
+        })
+    }
+
+    /// Platforms present in the history, most downloaded first.
+    pub fn platforms(&self) -> anyhow::Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT platform FROM history GROUP BY platform ORDER BY COUNT(*) DESC, platform")?;
+        let platforms = stmt.query_map([], |r| r.get(0))?.collect::<Result<_, _>>()?;
+        Ok(platforms)
+    }
+
+    /// Changes whenever another connection (the daemon) commits.
+    pub fn data_version(&self) -> Option<i64> {
+        self.conn.pragma_query_value(None, "data_version", |r| r.get(0)).ok()
+    }
+}
+
+fn config_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(home).join(".config").join("dora")
+}
+
+fn insert_entry(conn: &Connection, entry: &HistoryEntry) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT INTO history (title, artist, format, size_mb, path, finished_at, finished_ts, day, url, platform, thumbnail_url, options)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            entry.title,
+            entry.artist,
+            format_key(entry.format),
+            entry.size_mb,
+            entry.path,
+            entry.finished_at.to_rfc3339(),
+            entry.finished_at.timestamp(),
+            entry.finished_at.date_naive().to_string(),
+            entry.url,
+            extract_platform(&entry.url),
+            entry.thumbnail_url,
+            serde_json::to_string(&entry.options).unwrap_or_default(),
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    let finished_at: String = row.get(6)?;
+    let finished_at = DateTime::parse_from_rfc3339(&finished_at)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(6, Type::Text, Box::new(e)))?
+        .with_timezone(&Local);
+    let options: String = row.get(9)?;
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        artist: row.get(2)?,
+        format: parse_format(&row.get::<_, String>(3)?),
+        size_mb: row.get(4)?,
+        path: row.get(5)?,
+        finished_at,
+        url: row.get(7)?,
+        thumbnail_url: row.get(8)?,
+        options: serde_json::from_str(&options).unwrap_or_default(),
+    })
+}
+
+fn format_key(format: DownloadFormat) -> &'static str {
+    match format {
+        DownloadFormat::Mp3 => "mp3",
+        DownloadFormat::Mp4 => "mp4",
+    }
+}
+
+fn parse_format(key: &str) -> DownloadFormat {
+    match key {
+        "mp4" => DownloadFormat::Mp4,
+        _ => DownloadFormat::Mp3,
+    }
+}
+
+/// FTS5 query matching entries that contain every word of `text` as a word
+/// prefix. Words are quoted, so FTS syntax in the input is taken literally.
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// SQL condition (and its arguments) selecting the entries `query` lists.
+fn where_clause(query: &HistoryQuery, now: DateTime<Local>) -> (String, Vec<Value>) {
+    let mut clauses = vec!["1".to_string()];
+    let mut args = Vec::new();
+    if let Some(fts) = fts_query(&query.text) {
+        clauses.push("id IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)".into());
+        args.push(Value::Text(fts));
+    }
+    let filters = &query.filters;
+    if let Some(format) = filters.format {
+        clauses.push("format = ?".into());
+        args.push(Value::Text(format_key(format).into()));
+    }
+    if let Some(since) = filters.period.since(now) {
+        clauses.push("finished_ts >= ?".into());
+        args.push(Value::Integer(since.timestamp()));
+    }
+    if let Some(platform) = &filters.platform {
+        clauses.push("platform = ?".into());
+        args.push(Value::Text(platform.clone()));
+    }
+    let (min, max) = filters.size.bounds();
+    if let Some(min) = min {
+        clauses.push("size_mb >= ?".into());
+        args.push(Value::Real(min));
+    }
+    if let Some(max) = max {
+        clauses.push("size_mb < ?".into());
+        args.push(Value::Real(max));
+    }
+    (clauses.join(" AND "), args)
+}
+
+fn order_by(sort: HistorySortMode) -> &'static str {
+    match sort {
+        HistorySortMode::DateDesc => "id DESC",
+        HistorySortMode::DateAsc => "id ASC",
+        HistorySortMode::SizeDesc => "size_mb DESC, id DESC",
+        HistorySortMode::TitleAsc => "title COLLATE NOCASE, id DESC",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::download_options::DownloadOptions;
+
+    fn entry(title: &str, artist: &str, url: &str, size_mb: f64, days_ago: i64) -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            title: title.into(),
+            artist: artist.into(),
+            format: DownloadFormat::Mp3,
+            size_mb,
+            path: String::new(),
+            finished_at: Local::now() - TimeDelta::days(days_ago),
+            url: url.into(),
+            thumbnail_url: None,
+            options: DownloadOptions::default(),
+        }
+    }
+
+    fn sample_db() -> HistoryDb {
+        let db = HistoryDb::open_in_memory().unwrap();
+        for e in [
+            entry("Bohemian Rhapsody", "Queen", "https://youtu.be/fJ9rUzIMcZQ", 12.4, 40),
+            entry(
+                "Hotel California",
+                "Eagles",
+                "https://soundcloud.com/eagles/hotel",
+                9.8,
+                3,
+            ),
+            entry(
+                "Under Pressure",
+                "Queen",
+                "https://www.youtube.com/watch?v=a01QQZyl-_I",
+                150.0,
+                0,
+            ),
+        ] {
+            db.insert(&e).unwrap();
+        }
+        db
+    }
+
+    fn titles(db: &HistoryDb, query: &HistoryQuery) -> Vec<String> {
+        db.search(query, 100).unwrap().into_iter().map(|e| e.title).collect()
+    }
+
+    fn text(s: &str) -> HistoryQuery {
+        HistoryQuery {
+            text: s.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn history_search_is_case_insensitive_and_newest_first() {
+        let db = sample_db();
+        assert_eq!(titles(&db, &text("queen")), ["Under Pressure", "Bohemian Rhapsody"]);
+        assert_eq!(titles(&db, &text("PRESS que")), ["Under Pressure"]);
+        assert_eq!(titles(&db, &text("soundcloud")), ["Hotel California"]);
+        // FTS syntax is taken literally.
+        assert!(titles(&db, &text("\"queen OR")).is_empty());
+        assert_eq!(db.search(&text(""), 2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn facets_narrow_results_and_sort_applies() {
+        let db = sample_db();
+        let mut query = HistoryQuery::default();
+        query.filters.period = Period::Week;
+        assert_eq!(titles(&db, &query), ["Under Pressure", "Hotel California"]);
+
+        query.filters.platform = Some("youtube".into());
+        assert_eq!(titles(&db, &query), ["Under Pressure"]);
+
+        query.filters = HistoryFilters {
+            size: SizeRange::Small,
+            ..Default::default()
+        };
+        assert_eq!(titles(&db, &query), ["Hotel California"]);
+
+        query.filters = HistoryFilters::default();
+        query.sort = HistorySortMode::SizeDesc;
+        assert_eq!(
+            titles(&db, &query),
+            ["Under Pressure", "Bohemian Rhapsody", "Hotel California"]
+        );
+        query.filters.format = Some(DownloadFormat::Mp4);
+        assert!(titles(&db, &query).is_empty());
+        assert_eq!(db.platforms().unwrap(), ["youtube", "soundcloud"]);
+    }
+
+    #[test]
+    fn delete_removes_entry_from_search() {
+        let db = sample_db();
+        let id = db.search_ids(&text("bohemian")).unwrap()[0];
+        db.delete(id).unwrap();
+        assert!(db.search_ids(&text("bohemian")).unwrap().is_empty());
+        assert_eq!(db.load_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn stats_count_days_artists_and_platforms() {
+        let db = sample_db();
+        let today = Local::now().date_naive();
+        let stats = db.stats(&HistoryQuery::default(), 7, today).unwrap();
+        assert_eq!(stats.count, 3);
+        assert!((stats.total_mb - 172.2).abs() < 1e-9);
+        assert_eq!(stats.per_day.len(), 7);
+        assert_eq!(stats.per_day.last(), Some(&(today, 1)));
+        assert_eq!(stats.per_day.iter().map(|(_, n)| n).sum::<u64>(), 2);
+        assert_eq!(stats.top_artists[0], ("Queen".to_string(), 2));
+        assert_eq!(
+            stats.platforms,
+            [("youtube".to_string(), 2), ("soundcloud".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn imports_legacy_json_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("history.json");
+        let legacy = vec![
+            entry("Old Song", "Someone", "", 3.0, 100),
+            entry("Newer Song", "", "", 4.0, 1),
+        ];
+        fs_err::write(&json, serde_json::to_string(&legacy).unwrap()).unwrap();
+
+        let mut db = HistoryDb::open(&dir.path().join("history.db")).unwrap();
+        assert_eq!(db.import_json(&json).unwrap(), 2);
+        assert!(!json.exists());
+        assert!(dir.path().join("history.json.migrated").exists());
+        assert_eq!(db.import_json(&json).unwrap(), 0);
+
+        let loaded = db.load_all().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].title, "Old Song");
+        assert_eq!(loaded[0].finished_at, legacy[0].finished_at);
+        assert_eq!(db.platforms().unwrap(), ["other"]);
+    }
+}
@@ -0,0 +1,763 @@
+//! Rebindable keys for the dora TUI.
+//!
+//! Every hotkey the handlers in `main.rs` react to is a named [`Action`] in a
+//! [`KeyContext`]. Defaults can be overridden in `~/.config/dora/keys.toml`,
+//! one table per context, each action bound to a key or a list of keys:
+//!
+//! ```toml
+//! [main]
+//! help = "f1"
+//! remove_slot = ["x", "delete"]
+//!
+//! [preview]
+//! options = "ctrl+o"
+//! ```
+//!
+//! Navigation keys (arrows, Enter, Esc, Backspace) are fixed. The help
+//! overlay is rendered from the active map, so it always shows real bindings.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Where a key is interpreted. Bindings only conflict within one context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyContext {
+    /// Tabs, with no popup open.
+    Main,
+    /// History entry details popup.
+    HistoryPopup,
+    /// Preview popup before a download starts.
+    Preview,
+    /// Playlist / batch import checklist.
+    Import,
+    /// Subscriptions tab, when the follow bar is empty.
+    Subscriptions,
+}
+
+impl KeyContext {
+    pub const ALL: [Self; 5] = [
+        Self::Main,
+        Self::HistoryPopup,
+        Self::Preview,
+        Self::Import,
+        Self::Subscriptions,
+    ];
+
+    /// Table name in `keys.toml`.
+    pub fn section(self) -> &'static str {
+        match self {
+            Self::Main => "main",
+            Self::HistoryPopup => "history_popup",
+            Self::Preview => "preview",
+            Self::Import => "import",
+            Self::Subscriptions => "subscriptions",
+        }
+    }
+}
+
+/// A rebindable command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    // Main
+    TabDownloads,
+    TabLyrics,
+    TabSettings,
+    TabSubscriptions,
+    CycleTheme,
+    Help,
+    HistorySearch,
+    Cookies,
+    HistorySort,
+    HistorySelect,
+    HistoryFormat,
+    HistoryPeriod,
+    HistoryPlatform,
+    HistorySize,
+    HistoryStats,
+    Reveal,
+    RemoveSlot,
+    PlayEntry,
+    PlayPause,
+    SeekBack,
+    SeekForward,
+    PrevTrack,
+    NextTrack,
+    StopPlayback,
+    // History popup
+    EntryReveal,
+    EntryOpenUrl,
+    EntryDelete,
+    EntryPlay,
+    // Preview
+    PreviewFormat,
+    PreviewOptions,
+    PreviewSubtitles,
+    // Import
+    ImportToggle,
+    ImportToggleAll,
+    ImportFormat,
+    // Subscriptions
+    SubFormat,
+    SubAutoDownload,
+    SubCheckNow,
+    SubMarkSeen,
+    SubUnfollow,
+}
+
+struct Spec {
+    action: Action,
+    context: KeyContext,
+    name: &'static str,
+    description: &'static str,
+    defaults: &'static [&'static str],
+}
+
+/// Declaration order is help-overlay order and conflict priority.
+const SPECS: &[Spec] = &[
+    Spec {
+        action: Action::TabDownloads,
+        context: KeyContext::Main,
+        name: "tab_downloads",
+        description: "Downloads tab",
+        defaults: &["1"],
+    },
+    Spec {
+        action: Action::TabLyrics,
+        context: KeyContext::Main,
+        name: "tab_lyrics",
+        description: "Lyrics tab",
+        defaults: &["2"],
+    },
+    Spec {
+        action: Action::TabSettings,
+        context: KeyContext::Main,
+        name: "tab_settings",
+        description: "Settings tab",
+        defaults: &["3"],
+    },
+    Spec {
+        action: Action::TabSubscriptions,
+        context: KeyContext::Main,
+        name: "tab_subscriptions",
+        description: "Subscriptions tab",
+        defaults: &["4"],
+    },
+    Spec {
+        action: Action::CycleTheme,
+        context: KeyContext::Main,
+        name: "cycle_theme",
+        description: "Cycle Catppuccin theme",
+        defaults: &["T"],
+    },
+    Spec {
+        action: Action::Help,
+        context: KeyContext::Main,
+        name: "help",
+        description: "Open this help overlay",
+        defaults: &["?"],
+    },
+    Spec {
+        action: Action::HistorySearch,
+        context: KeyContext::Main,
+        name: "history_search",
+        description: "Search history",
+        defaults: &["/"],
+    },
+    Spec {
+        action: Action::Cookies,
+        context: KeyContext::Main,
+        name: "cookies",
+        description: "Set cookies file (authenticated sites)",
+        defaults: &["c"],
+    },
+    Spec {
+        action: Action::HistorySort,
+        context: KeyContext::Main,
+        name: "history_sort",
+        description: "Cycle history sort order",
+        defaults: &["s"],
+    },
+    Spec {
+        action: Action::HistorySelect,
+        context: KeyContext::Main,
+        name: "history_select",
+        description: "Toggle selection in History",
+        defaults: &["space"],
+    },
+    Spec {
+        action: Action::HistoryFormat,
+        context: KeyContext::Main,
+        name: "history_format",
+        description: "Filter history by format",
+        defaults: &["f"],
+    },
+    Spec {
+        action: Action::HistoryPeriod,
+        context: KeyContext::Main,
+        name: "history_period",
+        description: "Filter history by date range",
+        defaults: &["w"],
+    },
+    Spec {
+        action: Action::HistoryPlatform,
+        context: KeyContext::Main,
+        name: "history_platform",
+        description: "Filter history by platform",
+        defaults: &["o"],
+    },
+    Spec {
+        action: Action::HistorySize,
+        context: KeyContext::Main,
+        name: "history_size",
+        description: "Filter history by file size",
+        defaults: &["z"],
+    },
+    Spec {
+        action: Action::HistoryStats,
+        context: KeyContext::Main,
+        name: "history_stats",
+        description: "Toggle history stats panel",
+        defaults: &["i"],
+    },
+    Spec {
+        action: Action::Reveal,
+        context: KeyContext::Main,
+        name: "reveal",
+        description: "Open history entry / reveal last file",
+        defaults: &["r"],
+    },
+    Spec {
+        action: Action::RemoveSlot,
+        context: KeyContext::Main,
+        name: "remove_slot",
+        description: "Remove finished slot / cancel last",
+        defaults: &["d", "delete"],
+    },
+    Spec {
+        action: Action::PlayEntry,
+        context: KeyContext::Main,
+        name: "play",
+        description: "Play history entry, queueing the rest",
+        defaults: &["p"],
+    },
+    Spec {
+        action: Action::PlayPause,
+        context: KeyContext::Main,
+        name: "play_pause",
+        description: "Pause / resume playback",
+        defaults: &["P"],
+    },
+    Spec {
+        action: Action::SeekBack,
+        context: KeyContext::Main,
+        name: "seek_back",
+        description: "Seek back 10 s",
+        defaults: &["["],
+    },
+    Spec {
+        action: Action::SeekForward,
+        context: KeyContext::Main,
+        name: "seek_forward",
+        description: "Seek forward 10 s",
+        defaults: &["]"],
+    },
+    Spec {
+        action: Action::PrevTrack,
+        context: KeyContext::Main,
+        name: "prev_track",
+        description: "Previous track / restart",
+        defaults: &["<"],
+    },
+    Spec {
+        action: Action::NextTrack,
+        context: KeyContext::Main,
+        name: "next_track",
+        description: "Next track",
+        defaults: &[">"],
+    },
+    Spec {
+        action: Action::StopPlayback,
+        context: KeyContext::Main,
+        name: "stop",
+        description: "Stop playback",
+        defaults: &["S"],
+    },
+    Spec {
+        action: Action::EntryReveal,
+        context: KeyContext::HistoryPopup,
+        name: "reveal",
+        description: "Reveal file in file manager",
+        defaults: &["r"],
+    },
+    Spec {
+        action: Action::EntryOpenUrl,
+        context: KeyContext::HistoryPopup,
+        name: "open_url",
+        description: "Open source URL in browser",
+        defaults: &["b"],
+    },
+    Spec {
+        action: Action::EntryDelete,
+        context: KeyContext::HistoryPopup,
+        name: "delete",
+        description: "Remove entry from history",
+        defaults: &["d"],
+    },
+    Spec {
+        action: Action::EntryPlay,
+        context: KeyContext::HistoryPopup,
+        name: "play",
+        description: "Play file",
+        defaults: &["p"],
+    },
+    Spec {
+        action: Action::PreviewFormat,
+        context: KeyContext::Preview,
+        name: "toggle_format",
+        description: "Toggle MP3 / MP4 in preview",
+        defaults: &["tab"],
+    },
+    Spec {
+        action: Action::PreviewOptions,
+        context: KeyContext::Preview,
+        name: "options",
+        description: "Download options (convert, cut, effects)",
+        defaults: &["o", "O"],
+    },
+    Spec {
+        action: Action::PreviewSubtitles,
+        context: KeyContext::Preview,
+        name: "subtitles",
+        description: "Subtitle menu (MP4)",
+        defaults: &["s", "S"],
+    },
+    Spec {
+        action: Action::ImportToggle,
+        context: KeyContext::Import,
+        name: "toggle",
+        description: "Tick / untick item",
+        defaults: &["space"],
+    },
+    Spec {
+        action: Action::ImportToggleAll,
+        context: KeyContext::Import,
+        name: "toggle_all",
+        description: "Tick all or none",
+        defaults: &["a", "A"],
+    },
+    Spec {
+        action: Action::ImportFormat,
+        context: KeyContext::Import,
+        name: "toggle_format",
+        description: "Toggle MP3 / MP4",
+        defaults: &["tab"],
+    },
+    Spec {
+        action: Action::SubFormat,
+        context: KeyContext::Subscriptions,
+        name: "toggle_format",
+        description: "Toggle MP3 / MP4 for new items",
+        defaults: &["f"],
+    },
+    Spec {
+        action: Action::SubAutoDownload,
+        context: KeyContext::Subscriptions,
+        name: "auto_download",
+        description: "Auto-download new items on / off",
+        defaults: &["a"],
+    },
+    Spec {
+        action: Action::SubCheckNow,
+        context: KeyContext::Subscriptions,
+        name: "check_now",
+        description: "Check for new items now",
+        defaults: &["u"],
+    },
+    Spec {
+        action: Action::SubMarkSeen,
+        context: KeyContext::Subscriptions,
+        name: "mark_seen",
+        description: "Dismiss new items",
+        defaults: &["m"],
+    },
+    Spec {
+        action: Action::SubUnfollow,
+        context: KeyContext::Subscriptions,
+        name: "unfollow",
+        description: "Unfollow",
+        defaults: &["d", "delete"],
+    },
+];
+
+impl Action {
+    fn spec(self) -> &'static Spec {
+        SPECS
+            .iter()
+            .find(|s| s.action == self)
+            .expect("every action has a spec")
+    }
+
+    /// Key in `keys.toml`.
+    pub fn name(self) -> &'static str {
+        self.spec().name
+    }
+
+    pub fn description(self) -> &'static str {
+        self.spec().description
+    }
+
+    /// Actions of `context`, in help-overlay order.
+    pub fn in_context(context: KeyContext) -> impl Iterator<Item = Action> {
+        SPECS.iter().filter(move |s| s.context == context).map(|s| s.action)
+    }
+}
+
+// ── Key bindings ──────────────────────────────────────────────────────────────
+
+/// One key, optionally with Ctrl and/or Alt. Shift is part of the character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+impl KeyBinding {
+    /// Parse specs like `"d"`, `"T"`, `"space"`, `"f1"`, `"ctrl+o"` or
+    /// `"alt++"`. Navigation keys are rejected.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        // "+" and "ctrl++" bind the plus key itself.
+        let (mods, key) = match spec.strip_suffix("++") {
+            Some(mods) => (mods, "+"),
+            None => match spec.rsplit_once('+') {
+                Some((mods, key)) if !key.is_empty() => (mods, key),
+                _ => ("", spec),
+            },
+        };
+
+        let mut binding = Self {
+            code: KeyCode::Null,
+            ctrl: false,
+            alt: false,
+        };
+        for m in mods.split('+').filter(|m| !m.is_empty()) {
+            match m.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => binding.ctrl = true,
+                "alt" | "option" => binding.alt = true,
+                other => return Err(format!("unknown modifier '{}' in \"{}\"", other, spec)),
+            }
+        }
+
+        let mut chars = key.chars();
+        binding.code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "enter" | "return" | "esc" | "escape" | "backspace" | "up" | "down" | "left" | "right" => {
+                    return Err(format!("\"{}\" is reserved for navigation", spec));
+                }
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key \"{}\"", spec)),
+                },
+            },
+        };
+        Ok(binding)
+    }
+
+    /// Shift is ignored: it already shows up in the character (`T` vs `t`).
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        key.code == self.code
+            && key.modifiers.contains(KeyModifiers::CONTROL) == self.ctrl
+            && key.modifiers.contains(KeyModifiers::ALT) == self.alt
+    }
+
+    /// Display form for hints and the help overlay, e.g. `Ctrl+O`, `Space`.
+    pub fn label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Insert => "Ins".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+        let mut label = String::new();
+        if self.ctrl {
+            label.push_str("Ctrl+");
+        }
+        if self.alt {
+            label.push_str("Alt+");
+        }
+        label.push_str(&key);
+        label
+    }
+}
+
+// ── Keymap ────────────────────────────────────────────────────────────────────
+
+/// Active bindings for every [`Action`].
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyBinding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = SPECS
+            .iter()
+            .map(|s| {
+                let keys = s.defaults.iter().filter_map(|k| KeyBinding::parse(k).ok()).collect();
+                (s.action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    pub fn path() -> PathBuf {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+        PathBuf::from(home).join(".config").join("dora").join("keys.toml")
+    }
+
+    /// Read [`Self::path`]. A missing file means the defaults; problems are
+    /// returned as warnings and the offending entries skipped.
+    pub fn load() -> (Self, Vec<String>) {
+        match fs_err::read_to_string(Self::path()) {
+            Ok(text) => Self::from_toml(&text),
+            Err(_) => (Self::default(), Vec::new()),
+        }
+    }
+
+    /// Apply `keys.toml` overrides to the defaults.
+    ///
+    /// When two actions in one context end up on the same key, a user binding
+    /// beats a default and otherwise the action listed first in the help
+    /// wins; the loser drops that key and a warning is returned.
+    pub fn from_toml(text: &str) -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let mut warnings = Vec::new();
+        let table = match text.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(e) => return (keymap, vec![format!("keys.toml: {}", e.message())]),
+        };
+
+        let mut configured = Vec::new();
+        for (section, value) in &table {
+            let Some(context) = KeyContext::ALL.into_iter().find(|c| c.section() == section.as_str()) else {
+                warnings.push(format!("keys.toml: unknown section [{}]", section));
+                continue;
+            };
+            let Some(actions) = value.as_table() else {
+                warnings.push(format!("keys.toml: [{}] must be a table", section));
+                continue;
+            };
+            for (name, value) in actions {
+                let Some(action) = Action::in_context(context).find(|a| a.name() == name.as_str()) else {
+                    warnings.push(format!("keys.toml: unknown action {}.{}", section, name));
+                    continue;
+                };
+                let specs: Vec<&str> = match value {
+                    toml::Value::String(s) => vec![s.as_str()],
+                    toml::Value::Array(items) => items.iter().filter_map(|v| v.as_str()).collect(),
+                    _ => {
+                        warnings.push(format!(
+                            "keys.toml: {}.{} must be a key or a list of keys",
+                            section, name
+                        ));
+                        continue;
+                    }
+                };
+                let mut keys = Vec::new();
+                for spec in specs {
+                    match KeyBinding::parse(spec) {
+                        Ok(key) if !keys.contains(&key) => keys.push(key),
+                        Ok(_) => {}
+                        Err(e) => warnings.push(format!("keys.toml: {}.{}: {}", section, name, e)),
+                    }
+                }
+                keymap.bindings.insert(action, keys);
+                configured.push(action);
+            }
+        }
+
+        warnings.extend(keymap.resolve_conflicts(&configured));
+        (keymap, warnings)
+    }
+
+    fn resolve_conflicts(&mut self, configured: &[Action]) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for context in KeyContext::ALL {
+            let mut order: Vec<Action> = Action::in_context(context).collect();
+            order.sort_by_key(|a| !configured.contains(a)); // stable: keeps help order within each group
+
+            let mut owners: HashMap<KeyBinding, Action> = HashMap::new();
+            for action in order {
+                let keys = self.bindings.entry(action).or_default();
+                keys.retain(|key| match owners.get(key) {
+                    Some(owner) => {
+                        warnings.push(format!(
+                            "keys.toml: {} is bound to both {}.{} and {}.{} — keeping {}",
+                            key.label(),
+                            context.section(),
+                            owner.name(),
+                            context.section(),
+                            action.name(),
+                            owner.name()
+                        ));
+                        false
+                    }
+                    None => true,
+                });
+                for key in keys.iter() {
+                    owners.insert(*key, action);
+                }
+            }
+        }
+        warnings
+    }
+
+    /// The action `key` triggers in `context`, if any.
+    pub fn action(&self, context: KeyContext, key: &KeyEvent) -> Option<Action> {
+        Action::in_context(context).find(|a| self.keys(*a).iter().any(|k| k.matches(key)))
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// First binding of `action` only, for the status bar; `—` when unbound.
+    pub fn key_hint(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map_or_else(|| "—".to_string(), KeyBinding::label)
+    }
+
+    /// All bindings of `action` for display, e.g. `d / Del`; `—` when unbound.
+    pub fn label(&self, action: Action) -> String {
+        let keys = self.keys(action);
+        if keys.is_empty() {
+            return "—".to_string();
+        }
+        let mut labels: Vec<String> = Vec::new();
+        for key in keys {
+            let label = key.label();
+            // "o" and "O" read as one key in hints.
+            if !labels.iter().any(|l| l.eq_ignore_ascii_case(&label)) {
+                labels.push(label);
+            }
+        }
+        labels.join(" / ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn defaults_match_builtin_keys() {
+        let keymap = Keymap::default();
+        let main = KeyContext::Main;
+        assert_eq!(
+            keymap.action(main, &press(KeyCode::Char('T'), KeyModifiers::SHIFT)),
+            Some(Action::CycleTheme)
+        );
+        assert_eq!(
+            keymap.action(main, &press(KeyCode::Delete, KeyModifiers::NONE)),
+            Some(Action::RemoveSlot)
+        );
+        assert_eq!(
+            keymap.action(main, &press(KeyCode::Char('t'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keymap.action(KeyContext::HistoryPopup, &press(KeyCode::Char('d'), KeyModifiers::NONE)),
+            Some(Action::EntryDelete)
+        );
+        assert_eq!(
+            keymap.action(main, &press(KeyCode::Char(']'), KeyModifiers::NONE)),
+            Some(Action::SeekForward)
+        );
+        assert_eq!(
+            keymap.action(main, &press(KeyCode::Char('o'), KeyModifiers::NONE)),
+            Some(Action::HistoryPlatform)
+        );
+        assert_eq!(keymap.label(Action::RemoveSlot), "d / Del");
+        assert_eq!(keymap.label(Action::PreviewOptions), "o");
+    }
+
+    #[test]
+    fn parse_names_and_modifiers() {
+        let key = KeyBinding::parse("ctrl+o").unwrap();
+        assert!(key.matches(&press(KeyCode::Char('o'), KeyModifiers::CONTROL)));
+        assert!(!key.matches(&press(KeyCode::Char('o'), KeyModifiers::NONE)));
+        assert_eq!(key.label(), "Ctrl+o");
+
+        assert_eq!(KeyBinding::parse("F5").unwrap().label(), "F5");
+        assert_eq!(KeyBinding::parse("space").unwrap().label(), "Space");
+        assert_eq!(KeyBinding::parse("+").unwrap().label(), "+");
+        assert_eq!(KeyBinding::parse("alt++").unwrap().label(), "Alt++");
+        assert!(KeyBinding::parse("enter").is_err());
+        assert!(KeyBinding::parse("f13").is_err());
+        assert!(KeyBinding::parse("hyper+x").is_err());
+    }
+
+    #[test]
+    fn user_binding_beats_default_on_conflict() {
+        let (keymap, warnings) = Keymap::from_toml(
+            r#"
+            [main]
+            help = "d"
+            "#,
+        );
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        let d = press(KeyCode::Char('d'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(KeyContext::Main, &d), Some(Action::Help));
+        assert_eq!(keymap.label(Action::RemoveSlot), "Del");
+        // Same key in another context is not a conflict.
+        assert_eq!(keymap.action(KeyContext::HistoryPopup, &d), Some(Action::EntryDelete));
+    }
+
+    #[test]
+    fn bad_entries_are_reported_and_skipped() {
+        let (keymap, warnings) = Keymap::from_toml(
+            r#"
+            [main]
+            remove_slot = ["x", "enter"]
+            teleport = "t"
+
+            [preview]
+            options = []
+
+            [nowhere]
+            "#,
+        );
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert_eq!(keymap.label(Action::RemoveSlot), "x");
+        assert_eq!(keymap.label(Action::PreviewOptions), "—");
+
+        let (keymap, warnings) = Keymap::from_toml("[main");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(keymap.label(Action::Help), "?");
+    }
+}
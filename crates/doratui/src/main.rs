@@ -3,9 +3,16 @@
 //! # Usage
 //!
 //! ```text
-//! dora [--demo]
+//! dora [--demo]                     interactive TUI
+//! dora daemon [-v]                  run the queue headless on ~/.config/dora/dora.sock
+//! dora add [--mp3|--mp4] <url>...   queue links on the running daemon
+//! dora ls                           list the daemon's jobs
+//! dora watch [--json]               follow the daemon's progress
 //! ```
 //!
+//! A TUI started while the daemon runs attaches to it: downloads queued in
+//! the TUI run in the daemon and jobs added from scripts show up in the queue.
+//!
 //! # Controls
 //!
 //! | Key          | Action                           |
@@ -16,6 +23,11 @@
 //! | `c`          | Set cookies file (Queue tab)     |
 //! | `d` / `Del`  | Remove last finished/failed slot, or cancel active |
 //! | `/`          | Search history (Downloads tab)   |
+//! | `p`          | Play history entry, queue the rest |
+//! | `P` / `S`    | Pause / stop playback            |
+//! | `[` / `]`    | Seek back / forward 10 s         |
+//! | `f` `w` `o` `z` | Filter history by format / date / platform / size |
+//! | `i`          | Toggle history stats panel       |
 //! | `?`          | Open help overlay                |
 //! | `Esc`        | Close popup / clear input        |
 //! | `Ctrl+C`     | Quit                             |
@@ -37,7 +49,7 @@ use std::time::Duration;
 use simplelog::{Config as LogConfig, LevelFilter, WriteLogger};
 
 use crossterm::{
-    event::KeyCode,
+    event::{KeyCode, KeyModifiers},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -46,20 +58,32 @@ use ratatui::prelude::CrosstermBackend;
 use tokio::sync::mpsc;
 
 mod app;
+mod daemon;
+mod download_options;
 mod download_runner;
 mod events;
+mod history_db;
+mod keymap;
+mod player;
+mod playlist_import;
 mod settings;
+mod subscriptions;
 mod theme;
 mod ui;
 mod video_info;
 
 use app::{
-    App, DownloadFormat, HistoryEntry, LyricsResult, LyricsViewMode, PreviewState, SlotState, Tab, ToastKind,
-    YtdlpStartup,
+    App, DownloadFormat, HistoryEntry, ImportState, LyricsResult, LyricsViewMode, PreviewState, SlotState, Tab,
+    ToastKind, YtdlpStartup,
 };
+use download_options::{DownloadOptions, OptionRow};
 use download_runner::{SlotEvent, SubtitleOptions, spawn_download};
 use events::{InputEvent, next_event};
+use history_db::HistoryFilters;
+use keymap::{Action, KeyContext};
+use playlist_import::{ImportList, ImportResult, fetch_playlist};
 use settings::DoraSettings;
+use subscriptions::{CheckReport, SubItem};
 use video_info::{PreviewResult, fetch_thumbnail_art, fetch_video_info};
 
 type DownloadSender = mpsc::Sender<(usize, SlotEvent)>;
@@ -75,7 +99,13 @@ type PreviewSender = mpsc::Sender<PreviewResult>;
 type PreviewReceiver = mpsc::Receiver<PreviewResult>;
 type ThumbnailSender = mpsc::Sender<video_info::ThumbnailArt>;
 type ThumbnailReceiver = mpsc::Receiver<video_info::ThumbnailArt>;
+type ImportSender = mpsc::Sender<ImportResult>;
+type ImportReceiver = mpsc::Receiver<ImportResult>;
 type YtdlpReceiver = mpsc::Receiver<String>;
+type SubscriptionSender = mpsc::Sender<CheckReport>;
+type SubscriptionReceiver = mpsc::Receiver<CheckReport>;
+type DaemonSender = mpsc::Sender<daemon::protocol::Request>;
+type DaemonReceiver = mpsc::Receiver<daemon::client::AttachEvent>;
 
 #[derive(Clone)]
 struct UiSenders {
@@ -85,6 +115,10 @@ struct UiSenders {
     picker: PickerSender,
     preview: PreviewSender,
     thumbnails: ThumbnailSender,
+    import: ImportSender,
+    subscriptions: SubscriptionSender,
+    /// Requests to the daemon, when attached.
+    daemon: Option<DaemonSender>,
 }
 
 struct UiReceivers {
@@ -94,7 +128,10 @@ struct UiReceivers {
     picker: PickerReceiver,
     preview: PreviewReceiver,
     thumbnails: ThumbnailReceiver,
+    import: ImportReceiver,
+    subscriptions: SubscriptionReceiver,
     ytdlp: YtdlpReceiver,
+    daemon: Option<DaemonReceiver>,
 }
 
 #[tokio::main]
@@ -111,6 +148,19 @@ async fn main() -> anyhow::Result<()> {
     let demo = args.iter().any(|a| a == "--demo");
     let verbose = args.iter().any(|a| a == "--verbose" || a == "-v");
 
+    // ── Headless subcommands (no terminal UI) ─────────────────────
+    match args.get(1).map(String::as_str) {
+        Some("daemon") => {
+            let level = if verbose { LevelFilter::Debug } else { LevelFilter::Info };
+            let _ = WriteLogger::init(level, LogConfig::default(), io::stderr());
+            return daemon::run().await;
+        }
+        Some("add") => return daemon::client::cmd_add(&args[2..]).await,
+        Some("ls") => return daemon::client::cmd_ls().await,
+        Some("watch") => return daemon::client::cmd_watch(&args[2..]).await,
+        _ => {}
+    }
+
     if verbose {
         let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
         let log_dir = std::path::PathBuf::from(&home).join(".config").join("dora");
@@ -134,6 +184,18 @@ async fn main() -> anyhow::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     let mut app = if demo { App::new_demo() } else { App::new() };
+    // Hand downloads to a running daemon if there is one.
+    let attached = if demo { None } else { daemon::client::attach().await };
+    app.daemon_attached = attached.is_some();
+    let (daemon_tx, daemon_rx) = attached.unzip();
+    // Offer to resume whatever was queued when dora last exited (the daemon
+    // resumes its own queue).
+    if !demo && !app.daemon_attached {
+        let saved = app::queue_load();
+        if !saved.is_empty() {
+            app.resume_prompt = Some(saved);
+        }
+    }
     // tick_rate is computed dynamically inside the loop (see needs_fast_tick).
 
     // Channel: background download tasks → main loop
@@ -148,6 +210,10 @@ async fn main() -> anyhow::Result<()> {
     let (preview_tx, preview_rx) = mpsc::channel::<PreviewResult>(4);
     // Channel: thumbnail art (arrives separately, after info) → main loop
     let (thumb_tx, thumb_rx) = mpsc::channel::<video_info::ThumbnailArt>(4);
+    // Channel: playlist expansion for the import checklist → main loop
+    let (import_tx, import_rx) = mpsc::channel::<ImportResult>(4);
+    // Channel: finished subscription checks → main loop
+    let (subs_tx, subs_rx) = mpsc::channel::<CheckReport>(16);
     // Channel: yt-dlp update status lines → main loop
     let (ytdlp_tx, ytdlp_rx) = mpsc::channel::<String>(16);
 
@@ -158,6 +224,9 @@ async fn main() -> anyhow::Result<()> {
         picker: picker_tx,
         preview: preview_tx,
         thumbnails: thumb_tx,
+        import: import_tx,
+        subscriptions: subs_tx,
+        daemon: daemon_tx,
     };
     let receivers = UiReceivers {
         downloads: dl_rx,
@@ -166,7 +235,10 @@ async fn main() -> anyhow::Result<()> {
         picker: picker_rx,
         preview: preview_rx,
         thumbnails: thumb_rx,
+        import: import_rx,
+        subscriptions: subs_rx,
         ytdlp: ytdlp_rx,
+        daemon: daemon_rx,
     };
 
     // Kick off yt-dlp check / update in the background
@@ -209,6 +281,9 @@ fn run_loop(
         // ── Dispatch pending fetches + spawn tasks for queued slots ──────────
         dispatch_pending_spawns(app, &senders);
 
+        // ── Save the queue whenever it changed ────────────────────────────────
+        persist_queue(app);
+
         // ── Input event (blocks up to tick_rate) ─────────────────────────────
         // Three tiers to avoid burning CPU when idle:
         //   • 33 ms  — active animation (downloads, particles, burst, spinners)
@@ -260,6 +335,13 @@ fn drain_background_events(
         handle_slot_event(app, slot_id, event);
     }
 
+    // ── Drain daemon job updates when attached ────────────────────────────
+    if let Some(rx) = receivers.daemon.as_mut() {
+        while let Ok(event) = rx.try_recv() {
+            handle_daemon_event(app, event);
+        }
+    }
+
     // ── Drain artist songs events (non-blocking) ──────────────────────────
     while let Ok((id_opt, result)) = receivers.artist_songs.try_recv() {
         app.lyrics_loading = false;
@@ -285,6 +367,9 @@ fn drain_background_events(
         if result.is_none() && !app.lyrics_query.is_empty() {
             app.add_toast("No lyrics found", ToastKind::Error);
         }
+        if result.as_ref().is_some_and(|r| r.for_track.is_some()) {
+            app.lyrics_scroll = 0;
+        }
         app.lyrics_result = result;
     }
 
@@ -315,6 +400,24 @@ fn drain_background_events(
         app.preview_thumbnail = Some(art);
     }
 
+    // ── Drain playlist import results ─────────────────────────────────────
+    while let Ok(result) = receivers.import.try_recv() {
+        // Ignore late results for a checklist the user already dismissed.
+        if !matches!(app.import_state, ImportState::Loading { .. }) {
+            continue;
+        }
+        app.import_cursor = 0;
+        app.import_state = match result {
+            Ok(list) => ImportState::Ready(list),
+            Err(msg) => ImportState::Failed(msg),
+        };
+    }
+
+    // ── Drain subscription check results ──────────────────────────────────
+    while let Ok(report) = receivers.subscriptions.try_recv() {
+        handle_check_report(app, report);
+    }
+
     // ── Drain yt-dlp startup update lines ────────────────────────────────
     while let Ok(msg) = receivers.ytdlp.try_recv() {
         if msg == "__done__" {
@@ -416,24 +519,93 @@ fn dispatch_pending_spawns(app: &mut App, senders: &UiSenders) {
         });
     }
 
-    // ── Spawn tasks for newly-queued Pending slots ────────────────────────
+    // ── Expand a playlist URL for the import checklist ────────────────────
+    if let Some(url) = app.import_pending_url.take() {
+        let i_tx = senders.import.clone();
+        tokio::spawn(async move {
+            let result = fetch_playlist(&url).await.map_err(|e| format!("{:#}", e));
+            let _ = i_tx.send(result).await;
+        });
+    }
+
+    // ── Synced lyrics for the track the player just started ───────────────
+    if let Some(track) = app.synced_lyrics_pending.take()
+        && !app.demo_mode
+    {
+        let l_tx = senders.lyrics.clone();
+        tokio::spawn(async move {
+            if let Some(result) = fetch_track_lyrics(&track).await {
+                let _ = l_tx.send(Some(result)).await;
+            }
+        });
+    }
+
+    // ── Check due subscriptions (a running daemon checks them itself) ─────
+    if !app.demo_mode && !app.daemon_attached {
+        let interval = app.settings.subscription_interval();
+        app.subs_checker
+            .start_due(&app.subscriptions, interval, &senders.subscriptions);
+    }
+
+    // ── Attached: the daemon schedules, so hand over every new slot ──────
+    if app.daemon_attached
+        && let Some(ref tx) = senders.daemon
+    {
+        for job_id in app.daemon_pending_cancels.drain(..) {
+            let _ = tx.try_send(daemon::protocol::Request::Cancel { id: job_id });
+        }
+        for slot in app.slots.iter_mut().filter(|s| !s.task_spawned) {
+            slot.task_spawned = true;
+            let _ = tx.try_send(daemon::protocol::Request::Add {
+                url: slot.url.clone(),
+                format: Some(slot.format),
+                options: slot.options.clone(),
+                video_quality: slot.video_quality.clone(),
+                subtitle_lang: slot.subtitle_lang.clone(),
+                tag: Some(daemon_tag(slot.id)),
+            });
+        }
+        return;
+    }
+
+    // ── Spawn tasks for newly-queued Pending slots (up to max_parallel,
+    //    inside the active-hours window) ──
     let settings_snap = app.settings.clone();
+    if !settings_snap.in_active_window(chrono::Local::now().time()) {
+        return;
+    }
     let cookies_override = app.cookies_file.clone(); // legacy cookie-popup override
+    let max_parallel = settings_snap.max_parallel.max(1);
+    let mut running = app
+        .slots
+        .iter()
+        .filter(|s| s.task_spawned && !s.state.is_finished())
+        .count();
     for slot in &mut app.slots {
+        if running >= max_parallel {
+            break;
+        }
         if matches!(slot.state, SlotState::Pending) && !slot.task_spawned {
+            running += 1;
             slot.task_spawned = true;
             // Legacy cookies popup overrides the settings field
             let mut s = settings_snap.clone();
             if let Some(ref c) = cookies_override {
                 s.ytdlp_cookies = c.clone();
             }
+            if let Some(ref q) = slot.video_quality {
+                s.video_quality = q.clone();
+            }
+            let subtitle_opts = slot.subtitle_lang.clone().map(|lang| SubtitleOptions { lang });
             let handle = spawn_download(
                 slot.id,
                 slot.url.clone(),
                 slot.format,
                 s,
                 senders.downloads.clone(),
-                None,
+                subtitle_opts,
+                slot.options.clone(),
+                slot.output_path.clone(),
             );
             slot.cancel = Some(handle);
         }
@@ -450,7 +622,9 @@ fn handle_paste_event(app: &mut App, text: String) {
         app.settings_edit_buf.push_str(&clean);
     } else if app.active_tab == Tab::Lyrics {
         app.lyrics_query.push_str(&clean);
-    } else if app.active_tab == Tab::Downloads && !app.preview_state.is_visible() {
+    } else if app.active_tab == Tab::Subscriptions {
+        app.sub_input.push_str(&clean);
+    } else if app.active_tab == Tab::Downloads && !app.preview_state.is_visible() && !app.import_state.is_visible() {
         let urls: Vec<String> = text
             .split('\n')
             .map(str::trim)
@@ -459,8 +633,17 @@ fn handle_paste_event(app: &mut App, text: String) {
             .collect();
         match urls.len() {
             0 => {
-                // No valid URLs — append raw text to url_input
-                app.url_input.push_str(&clean);
+                // A dropped text file of URLs opens the checklist;
+                // anything else is appended raw to url_input.
+                if let Some(path) = url_file_path(&clean) {
+                    open_import_file(app, &path);
+                } else {
+                    app.url_input.push_str(&clean);
+                }
+            }
+            1 if playlist_import::is_playlist(&urls[0]) => {
+                app.url_input.clear();
+                open_playlist_import(app, urls[0].clone());
             }
             1 => {
                 // Single URL: open preview like pressing Enter.
@@ -476,17 +659,9 @@ fn handle_paste_event(app: &mut App, text: String) {
                 app.url_input.clear();
             }
             count => {
-                // Multiple URLs: queue all directly without preview
-                let fmt = if app.settings.default_format == "MP4" {
-                    DownloadFormat::Mp4
-                } else {
-                    DownloadFormat::Mp3
-                };
-                for url in urls {
-                    app.add_download(url, fmt);
-                }
-                app.preview_image_protocol = None;
-                app.add_toast(&format!("Queued {} downloads", count), ToastKind::Success);
+                // Multiple URLs: pick which ones to queue in the checklist
+                app.url_input.clear();
+                open_import_list(app, ImportList::from_urls(format!("{} pasted links", count), urls));
             }
         }
     }
@@ -566,6 +741,16 @@ fn handle_key_event(app: &mut App, key: crossterm::event::KeyEvent, senders: &Ui
         return false;
     }
 
+    // ── Resume-queue prompt intercepts ALL keys ───────────────────
+    if app.resume_prompt.is_some() {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => answer_resume_prompt(app, true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => answer_resume_prompt(app, false),
+            _ => {}
+        }
+        return false;
+    }
+
     // ── Esc: universal dismiss ────────────────────────────────────
     if key.code == KeyCode::Esc {
         if app.show_cookies_input {
@@ -582,6 +767,13 @@ fn handle_key_event(app: &mut App, key: crossterm::event::KeyEvent, senders: &Ui
             app.preview_subs_edit_buf.clear();
         } else if app.preview_subs_menu {
             app.preview_subs_menu = false;
+        } else if app.preview_options_editing {
+            app.preview_options_editing = false;
+            app.preview_options_edit_buf.clear();
+        } else if app.preview_options_menu {
+            app.preview_options_menu = false;
+        } else if app.import_state.is_visible() {
+            close_import(app);
         } else if app.preview_state.is_visible() {
             // Cancel preview — restore URL input
             let url = std::mem::take(&mut app.preview_url);
@@ -596,6 +788,7 @@ fn handle_key_event(app: &mut App, key: crossterm::event::KeyEvent, senders: &Ui
             app.preview_subs_custom_lang = None;
             app.preview_subs_editing = false;
             app.preview_subs_edit_buf.clear();
+            reset_preview_options(app);
         } else if app.settings_editing {
             app.settings_editing = false;
             app.settings_edit_buf.clear();
@@ -605,9 +798,16 @@ fn handle_key_event(app: &mut App, key: crossterm::event::KeyEvent, senders: &Ui
             app.history_filter.clear();
         } else {
             match app.active_tab {
+                // With an empty URL bar, Esc closes the stats panel, then
+                // clears the history facets.
+                Tab::Downloads if app.url_input.is_empty() && app.history_stats_visible => {
+                    app.history_stats_visible = false;
+                }
+                Tab::Downloads if app.url_input.is_empty() => app.history_facets = HistoryFilters::default(),
                 Tab::Downloads => app.url_input.clear(),
                 Tab::Lyrics => app.lyrics_query.clear(),
                 Tab::Settings => {}
+                Tab::Subscriptions => app.sub_input.clear(),
             }
         }
         return false;
@@ -680,8 +880,8 @@ fn handle_key_event(app: &mut App, key: crossterm::event::KeyEvent, senders: &Ui
 
     // ── History detail popup intercepts keys ─────────────────────
     if let Some(idx) = app.history_popup {
-        match key.code {
-            KeyCode::Char('r') | KeyCode::Enter => {
+        match (key.code, app.keymap.action(KeyContext::HistoryPopup, &key)) {
+            (KeyCode::Enter, _) | (_, Some(Action::EntryReveal)) => {
                 if let Some(entry) = app.history.iter().rev().nth(idx) {
                     let path = entry.path.clone();
                     app.history_popup = None;
@@ -690,7 +890,7 @@ fn handle_key_event(app: &mut App, key: crossterm::event::KeyEvent, senders: &Ui
                     reveal_file(app, path);
                 }
             }
-            KeyCode::Char('b') => {
+            (_, Some(Action::EntryOpenUrl)) => {
                 if let Some(entry) = app.history.iter().rev().nth(idx)
                     && !entry.url.is_empty()
                 {
@@ -698,19 +898,20 @@ fn handle_key_event(app: &mut App, key: crossterm::event::KeyEvent, senders: &Ui
                     open_in_browser(&url);
                 }
             }
-            KeyCode::Char('d') => {
-                // Remove this entry from history (display-index → vec index)
-                let vec_idx = app.history.len().saturating_sub(1).saturating_sub(idx);
-                if vec_idx < app.history.len() {
-                    app.history.remove(vec_idx);
-                    app.history_save();
-                }
+            (_, Some(Action::EntryPlay)) => {
+                app.history_popup = None;
+                app.preview_thumbnail = None;
+                app.preview_image_protocol = None;
+                play_history(app, idx);
+            }
+            (_, Some(Action::EntryDelete)) => {
+                app.delete_history(idx);
                 app.history_popup = None;
                 app.preview_thumbnail = None;
                 app.preview_image_protocol = None;
                 app.clamp_history_scroll();
             }
-            KeyCode::Esc => {
+            (KeyCode::Esc, _) => {
                 app.history_popup = None;
                 app.preview_thumbnail = None;
                 app.preview_image_protocol = None;
@@ -726,9 +927,15 @@ fn handle_key_event(app: &mut App, key: crossterm::event::KeyEvent, senders: &Ui
         return false;
     }
 
+    // ── Import checklist intercepts all keys ──────────────────────
+    if app.import_state.is_visible() {
+        handle_import_key(app, key);
+        return false;
+    }
+
     // ── Preview popup intercepts all keys ─────────────────────────
     if app.preview_state.is_visible() {
-        handle_preview_key(app, key, senders.downloads.clone(), senders.thumbnails.clone());
+        handle_preview_key(app, key, senders.thumbnails.clone());
         return false;
     }
 
@@ -738,62 +945,108 @@ fn handle_key_event(app: &mut App, key: crossterm::event::KeyEvent, senders: &Ui
         return false;
     }
 
-    // ── 'T' cycles Catppuccin theme ──────────────────────────────
-    if key.code == KeyCode::Char('T') {
-        app.settings.theme_flavour = app.settings.theme_flavour.next();
-        app.theme = crate::theme::palette(app.settings.theme_flavour);
-        let _ = app.settings.save();
-        app.add_toast(
-            &format!("Theme: {}", app.settings.theme_flavour.label()),
-            ToastKind::Info,
-        );
-        return false;
-    }
-
-    // ── '?' opens help (only when not typing in a text input) ────
-    if key.code == KeyCode::Char('?') {
-        let typing = (app.active_tab == Tab::Downloads && !app.url_input.trim().is_empty())
-            || (app.active_tab == Tab::Lyrics && !app.lyrics_query.trim().is_empty());
-        if !typing {
+    // ── Rebindable hotkeys (keymap.rs) ───────────────────────────
+    // A printable hotkey is typed as text while an input has content.
+    let action = app
+        .keymap
+        .action(KeyContext::Main, &key)
+        .filter(|_| !(is_typing(app) && is_text_key(&key)));
+
+    match action {
+        Some(Action::CycleTheme) => {
+            app.settings.theme_flavour = app.settings.theme_flavour.next();
+            app.apply_theme();
+            let _ = app.settings.save();
+            app.add_toast(
+                &format!("Theme: {}", app.settings.theme_flavour.label()),
+                ToastKind::Info,
+            );
+            return false;
+        }
+        Some(Action::Help) => {
             app.help_visible = true;
             return false;
         }
+        // Player transport, while a track is loaded
+        Some(Action::PlayPause) if app.player.is_active() => {
+            app.player.toggle_pause();
+            return false;
+        }
+        Some(Action::SeekBack) if app.player.is_active() => {
+            app.player.seek_by(-player::SEEK_STEP_SECS);
+            return false;
+        }
+        Some(Action::SeekForward) if app.player.is_active() => {
+            app.player.seek_by(player::SEEK_STEP_SECS);
+            return false;
+        }
+        Some(Action::PrevTrack) if app.player.is_active() => {
+            app.player.prev();
+            return false;
+        }
+        Some(Action::NextTrack) if app.player.is_active() => {
+            app.player.next();
+            return false;
+        }
+        Some(Action::StopPlayback) if app.player.is_active() => {
+            app.player.stop();
+            return false;
+        }
+        // Downloads tab — activate history search
+        Some(Action::HistorySearch) if app.active_tab == Tab::Downloads => {
+            app.history_search_mode = true;
+            app.history_filter.clear();
+            return false;
+        }
+        // Downloads tab — open cookies popup
+        Some(Action::Cookies) if app.active_tab == Tab::Downloads => {
+            app.show_cookies_input = true;
+            app.cookies_input = app.cookies_file.clone().unwrap_or_default();
+            return false;
+        }
+        _ => {}
     }
 
-    // ── '/' in Downloads tab — activate history search ────────────
-    if app.active_tab == Tab::Downloads && app.url_input.trim().is_empty() && key.code == KeyCode::Char('/') {
-        app.history_search_mode = true;
-        app.history_filter.clear();
-        return false;
+    // ── Tab-specific handling ─────────────────────────────────────
+    match app.active_tab {
+        Tab::Downloads => handle_downloads_key(app, key, action, senders),
+        Tab::Lyrics => handle_lyrics_key(app, key, action, senders),
+        Tab::Settings => handle_settings_key(app, key, action, senders.picker.clone()),
+        Tab::Subscriptions => handle_subscriptions_key(app, key, action),
+    }
+
+    // ── Global tab-switching (suppressed while typing, see above) ──
+    match action {
+        Some(Action::TabDownloads) => app.active_tab = Tab::Downloads,
+        Some(Action::TabLyrics) => app.active_tab = Tab::Lyrics,
+        Some(Action::TabSettings) => app.active_tab = Tab::Settings,
+        Some(Action::TabSubscriptions) => app.active_tab = Tab::Subscriptions,
+        _ => {}
     }
 
-    // ── 'c' opens cookies popup (Downloads tab, only when not typing) ───
-    if app.active_tab == Tab::Downloads && app.url_input.trim().is_empty() && key.code == KeyCode::Char('c') {
-        app.show_cookies_input = true;
-        app.cookies_input = app.cookies_file.clone().unwrap_or_default();
-        return false;
-    }
+    false
+}
 
-    // ── Tab-specific handling ─────────────────────────────────────
+/// True while the active tab's text input has content (or a setting is being
+/// edited), so printable hotkeys go into the input instead.
+fn is_typing(app: &App) -> bool {
     match app.active_tab {
-        Tab::Downloads => handle_downloads_key(app, key, senders),
-        Tab::Lyrics => handle_lyrics_key(app, key, senders),
-        Tab::Settings => handle_settings_key(app, key, senders.picker.clone()),
+        Tab::Downloads => !app.url_input.trim().is_empty(),
+        Tab::Lyrics => !app.lyrics_query.trim().is_empty(),
+        Tab::Settings => app.settings_editing,
+        Tab::Subscriptions => !app.sub_input.trim().is_empty(),
     }
+}
 
-    // ── Global tab-switching (suppressed while typing in a text input) ──
-    let typing_in_input = (app.active_tab == Tab::Downloads && !app.url_input.trim().is_empty())
-        || (app.active_tab == Tab::Lyrics && !app.lyrics_query.trim().is_empty());
-    if !typing_in_input {
-        match key.code {
-            KeyCode::Char('1') => app.active_tab = Tab::Downloads,
-            KeyCode::Char('2') => app.active_tab = Tab::Lyrics,
-            KeyCode::Char('3') => app.active_tab = Tab::Settings,
-            _ => {}
-        }
-    }
+fn is_text_key(key: &crossterm::event::KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char(_)) && !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+}
 
-    false
+fn is_tab_switch(action: Option<Action>) -> bool {
+    matches!(
+        action,
+        Some(Action::TabDownloads | Action::TabLyrics | Action::TabSettings | Action::TabSubscriptions)
+    )
 }
 
 // ── Slot event handler ────────────────────────────────────────────────────────
@@ -815,6 +1068,11 @@ fn handle_slot_event(app: &mut App, slot_id: usize, event: SlotEvent) {
                 }
             }
         }
+        SlotEvent::Destination { path } => {
+            if let Some(slot) = app.slot_mut(slot_id) {
+                slot.output_path = Some(path);
+            }
+        }
         SlotEvent::Progress {
             percent,
             speed_mbs,
@@ -833,22 +1091,34 @@ fn handle_slot_event(app: &mut App, slot_id: usize, event: SlotEvent) {
                 }
             }
         }
+        SlotEvent::Merging { position_secs } => {
+            if let Some(slot) = app.slot_mut(slot_id) {
+                slot.state = SlotState::Merging { position_secs };
+            }
+        }
+        SlotEvent::Postprocessing { label } => {
+            if let Some(slot) = app.slot_mut(slot_id) {
+                slot.state = SlotState::Postprocessing { label };
+            }
+        }
         SlotEvent::BurningSubtitles => {
             if let Some(slot) = app.slot_mut(slot_id) {
-                slot.state = SlotState::Downloading {
-                    percent: 99,
-                    speed_mbs: 0.0,
-                    eta_secs: 0,
+                slot.state = SlotState::Postprocessing {
+                    label: "burning subtitles",
                 };
             }
             app.add_toast("Burning subtitles...", ToastKind::Info);
         }
         SlotEvent::Done { path, size_mb } => {
-            let info = app
-                .slots
-                .iter()
-                .find(|s| s.id == slot_id)
-                .map(|s| (s.title.clone(), s.artist.clone(), s.format, s.url.clone()));
+            let info = app.slots.iter().find(|s| s.id == slot_id).map(|s| {
+                (
+                    s.title.clone(),
+                    s.artist.clone(),
+                    s.format,
+                    s.url.clone(),
+                    s.options.clone(),
+                )
+            });
 
             if let Some(slot) = app.slot_mut(slot_id) {
                 // Transition through Celebrating first (1-second animation).
@@ -860,18 +1130,19 @@ fn handle_slot_event(app: &mut App, slot_id: usize, event: SlotEvent) {
             }
 
             // Feature: TUI Toasts (Done notification)
-            if let Some((title, _, _, _)) = &info {
+            if let Some((title, _, _, _, _)) = &info {
                 let name = title.as_deref().unwrap_or("Media");
                 app.add_toast(&format!("Done: {}", name), ToastKind::Success);
             }
 
-            if let Some((title, artist, format, url)) = info {
+            if let Some((title, artist, format, url, options)) = info {
                 let thumb_url = app
                     .slots
                     .iter()
                     .find(|s| s.id == slot_id)
                     .and_then(|s| s.thumbnail_url.clone());
                 app.push_history(HistoryEntry {
+                    id: 0,
                     title: title.unwrap_or_else(|| "Unknown".to_string()),
                     artist: artist.unwrap_or_default(),
                     format,
@@ -880,6 +1151,7 @@ fn handle_slot_event(app: &mut App, slot_id: usize, event: SlotEvent) {
                     finished_at: chrono::Local::now(),
                     url,
                     thumbnail_url: thumb_url,
+                    options,
                 });
             }
         }
@@ -897,61 +1169,102 @@ fn handle_slot_event(app: &mut App, slot_id: usize, event: SlotEvent) {
 // ── Per-tab key handlers ──────────────────────────────────────────────────────
 
 /// Combined Downloads tab handler — URL queue + history navigation.
-fn handle_downloads_key(app: &mut App, key: crossterm::event::KeyEvent, senders: &UiSenders) {
+fn handle_downloads_key(app: &mut App, key: crossterm::event::KeyEvent, action: Option<Action>, senders: &UiSenders) {
     // History interaction takes priority when URL bar is empty
     if app.url_input.trim().is_empty() && !app.history.is_empty() {
-        match key.code {
-            KeyCode::Up | KeyCode::Down | KeyCode::Char(' ') | KeyCode::Char('r') | KeyCode::Enter => {
-                handle_history_key(app, key, senders);
+        match (key.code, action) {
+            (KeyCode::Up | KeyCode::Down | KeyCode::Enter, _) | (_, Some(Action::HistorySelect | Action::Reveal)) => {
+                handle_history_key(app, key, action, senders);
                 return;
             }
-            // [s] — cycle history sort order
-            KeyCode::Char('s') => {
+            (_, Some(Action::PlayEntry)) => {
+                if let Some(&display_idx) = app.history_filtered_indices.get(app.history_index) {
+                    play_history(app, display_idx);
+                }
+                return;
+            }
+            // Cycle history sort order
+            (_, Some(Action::HistorySort)) => {
                 app.history_sort = app.history_sort.next();
                 app.update_history_filter();
                 return;
             }
+            // Facets: each key cycles one filter.
+            (_, Some(Action::HistoryFormat)) => {
+                app.history_facets.next_format();
+                app.update_history_filter();
+                return;
+            }
+            (_, Some(Action::HistoryPeriod)) => {
+                app.history_facets.period = app.history_facets.period.next();
+                app.update_history_filter();
+                return;
+            }
+            (_, Some(Action::HistoryPlatform)) => {
+                app.cycle_history_platform();
+                app.update_history_filter();
+                return;
+            }
+            (_, Some(Action::HistorySize)) => {
+                app.history_facets.size = app.history_facets.size.next();
+                app.update_history_filter();
+                return;
+            }
+            (_, Some(Action::HistoryStats)) => {
+                app.history_stats_visible = !app.history_stats_visible;
+                return;
+            }
             _ => {}
         }
     }
-    handle_queue_key(app, key);
+    handle_queue_key(app, key, action);
 }
 
-fn handle_queue_key(app: &mut App, key: crossterm::event::KeyEvent) {
-    match key.code {
-        KeyCode::Char(c) => {
-            // '1'-'3' switch tabs only when the URL bar is empty (handled globally).
-            // When the user is typing, digits are part of the URL — let them through.
-            if app.url_input.is_empty() && matches!(c, '1' | '2' | '3') {
-                return;
-            }
-            // [d] / [r] hotkeys only fire when the URL bar is empty.
-            if app.url_input.is_empty() {
-                if c == 'd' {
-                    remove_or_cancel_slot(app);
-                    return;
-                }
-                if c == 'r' {
-                    if let Some(path) = app.slots.iter().rev().find_map(|s| {
-                        if let SlotState::Done { path } = &s.state {
-                            Some(path.clone())
-                        } else {
-                            None
-                        }
-                    }) {
-                        reveal_file(app, path);
-                    }
-                    return;
+fn handle_queue_key(app: &mut App, key: crossterm::event::KeyEvent, action: Option<Action>) {
+    // Tab switching is handled globally. `action` is already `None` for
+    // printable keys while a URL is being typed, so letters and digits land
+    // in the URL bar.
+    if is_tab_switch(action) {
+        return;
+    }
+    match action {
+        Some(Action::RemoveSlot) => {
+            remove_or_cancel_slot(app);
+            return;
+        }
+        Some(Action::Reveal) => {
+            if let Some(path) = app.slots.iter().rev().find_map(|s| {
+                if let SlotState::Done { path } = &s.state {
+                    Some(path.clone())
+                } else {
+                    None
                 }
+            }) {
+                reveal_file(app, path);
             }
+            return;
+        }
+        _ => {}
+    }
+
+    match key.code {
+        KeyCode::Char(c) => {
             app.url_input.push(c);
         }
         KeyCode::Backspace => {
             app.url_input.pop();
         }
         KeyCode::Enter => {
+            if let Some(path) = url_file_path(app.url_input.trim()) {
+                app.url_input.clear();
+                open_import_file(app, &path);
+                return;
+            }
             let url = normalize_url(app.url_input.trim());
-            if !url.is_empty() {
+            if playlist_import::is_playlist(&url) {
+                app.url_input.clear();
+                open_playlist_import(app, url);
+            } else if !url.is_empty() {
                 // Set up debounced preview fetch (300ms).
                 app.preview_url = url.clone();
                 app.preview_format = DownloadFormat::Mp4;
@@ -964,57 +1277,78 @@ fn handle_queue_key(app: &mut App, key: crossterm::event::KeyEvent) {
                 app.url_input.clear();
             }
         }
-        KeyCode::Delete => {
-            remove_or_cancel_slot(app);
-        }
         _ => {}
     }
 }
 
+/// Play history entry `display_idx` (0 = newest) and queue the entries that
+/// follow it in the filtered history. Files that no longer exist are skipped.
+fn play_history(app: &mut App, display_idx: usize) {
+    let Some(first) = history_track(app, display_idx) else {
+        app.add_toast("File not found — moved or deleted?", ToastKind::Error);
+        return;
+    };
+    let rest = app
+        .history_filtered_indices
+        .iter()
+        .skip_while(|&&i| i != display_idx)
+        .skip(1)
+        .filter_map(|&i| history_track(app, i));
+    let tracks = std::iter::once(first).chain(rest).collect();
+    app.player.play_queue(tracks, 0);
+}
+
+fn history_track(app: &App, display_idx: usize) -> Option<player::Track> {
+    let entry = app.history.iter().rev().nth(display_idx)?;
+    let path = std::path::PathBuf::from(DoraSettings::expand_path(&entry.path));
+    path.is_file().then(|| player::Track {
+        path,
+        title: entry.title.clone(),
+        artist: entry.artist.clone(),
+    })
+}
+
 /// Remove the last finished/failed/celebrating slot, or abort + remove the last active slot.
 fn remove_or_cancel_slot(app: &mut App) {
     // Prefer removing a terminal-state slot first.
-    if let Some(pos) = app.slots.iter().rposition(|s| {
-        matches!(
-            s.state,
-            SlotState::Done { .. } | SlotState::Failed { .. } | SlotState::Celebrating { .. }
-        )
-    }) {
+    if let Some(pos) = app.slots.iter().rposition(|s| s.state.is_finished()) {
         app.slots.remove(pos);
         return;
     }
     // Otherwise abort the last active slot.
-    if let Some(pos) = app.slots.iter().rposition(|s| {
-        matches!(
-            s.state,
-            SlotState::Downloading { .. } | SlotState::Fetching | SlotState::Pending
-        )
-    }) {
+    if let Some(pos) = app
+        .slots
+        .iter()
+        .rposition(|s| s.state.is_active() || matches!(s.state, SlotState::Fetching | SlotState::Pending))
+    {
         if let Some(handle) = app.slots[pos].cancel.take() {
             handle.abort();
         }
+        if let Some(job_id) = app.slots[pos].remote_id {
+            app.daemon_pending_cancels.push(job_id);
+        }
         app.slots.remove(pos);
     }
 }
 
-fn handle_history_key(app: &mut App, key: crossterm::event::KeyEvent, senders: &UiSenders) {
+fn handle_history_key(app: &mut App, key: crossterm::event::KeyEvent, action: Option<Action>, senders: &UiSenders) {
     let num_entries = app.history_filtered_indices.len();
     if num_entries == 0 {
         return;
     }
 
-    match key.code {
-        KeyCode::Up => {
+    match (key.code, action) {
+        (KeyCode::Up, _) => {
             if app.history_index > 0 {
                 app.history_index -= 1;
             }
         }
-        KeyCode::Down => {
+        (KeyCode::Down, _) => {
             if app.history_index + 1 < num_entries {
                 app.history_index += 1;
             }
         }
-        KeyCode::Char(' ') => {
+        (_, Some(Action::HistorySelect)) => {
             let filtered_pos = app.history_index;
             if let Some(&display_idx) = app.history_filtered_indices.get(filtered_pos) {
                 if app.history_selected.contains(&display_idx) {
@@ -1025,7 +1359,7 @@ fn handle_history_key(app: &mut App, key: crossterm::event::KeyEvent, senders: &
             }
             return;
         }
-        KeyCode::Char('r') | KeyCode::Enter => {
+        (KeyCode::Enter, _) | (_, Some(Action::Reveal)) => {
             let filtered_pos = app.history_index;
             handle_click_internal(app, app::ClickTarget::HistoryOpenPopup(filtered_pos), senders);
             return;
@@ -1043,7 +1377,7 @@ fn handle_history_key(app: &mut App, key: crossterm::event::KeyEvent, senders: &
     }
 }
 
-fn handle_lyrics_key(app: &mut App, key: crossterm::event::KeyEvent, senders: &UiSenders) {
+fn handle_lyrics_key(app: &mut App, key: crossterm::event::KeyEvent, action: Option<Action>, senders: &UiSenders) {
     if app.lyrics_view_mode == app::LyricsViewMode::ArtistSongs {
         let card_w = 30usize;
         let cols = (80 / card_w).max(1);
@@ -1089,8 +1423,8 @@ fn handle_lyrics_key(app: &mut App, key: crossterm::event::KeyEvent, senders: &U
 
     match key.code {
         KeyCode::Char(c) => {
-            // Digits switch tabs only when the query is empty.
-            if app.lyrics_query.is_empty() && matches!(c, '1' | '2' | '3') {
+            // Tab keys switch tabs only when the query is empty.
+            if is_tab_switch(action) {
                 return;
             }
             app.lyrics_query.push(c);
@@ -1184,13 +1518,86 @@ fn handle_lyrics_key(app: &mut App, key: crossterm::event::KeyEvent, senders: &U
 fn handle_preview_key(
     app: &mut App,
     key: crossterm::event::KeyEvent,
-    dl_tx: mpsc::Sender<(usize, SlotEvent)>,
     _thumb_tx: mpsc::Sender<video_info::ThumbnailArt>,
 ) {
     // While loading: only Esc (handled globally before this fn is called)
     if matches!(app.preview_state, PreviewState::Loading) {
         return;
     }
+    let action = app.keymap.action(KeyContext::Preview, &key);
+
+    // Cut time text input mode
+    if app.preview_options_editing {
+        match key.code {
+            KeyCode::Enter => {
+                let rows = OptionRow::for_format(app.preview_format);
+                if let Some(&row) = rows.get(app.preview_options_cursor) {
+                    match app.preview_options.set_cut(row, &app.preview_options_edit_buf) {
+                        Ok(()) => {
+                            app.preview_options.fit_to(app.preview_format);
+                        }
+                        Err(msg) => {
+                            app.add_toast(msg, ToastKind::Error);
+                            return;
+                        }
+                    }
+                }
+                app.preview_options_editing = false;
+                app.preview_options_edit_buf.clear();
+            }
+            KeyCode::Esc => {
+                app.preview_options_editing = false;
+                app.preview_options_edit_buf.clear();
+            }
+            KeyCode::Backspace => {
+                app.preview_options_edit_buf.pop();
+            }
+            KeyCode::Char(c) => {
+                if app.preview_options_edit_buf.len() < 10 && (c.is_ascii_digit() || c == ':') {
+                    app.preview_options_edit_buf.push(c);
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    // Download options sub-menu keys
+    if app.preview_options_menu {
+        let rows = OptionRow::for_format(app.preview_format);
+        match key.code {
+            KeyCode::Up => {
+                app.preview_options_cursor = (app.preview_options_cursor + rows.len() - 1) % rows.len();
+            }
+            KeyCode::Down => {
+                app.preview_options_cursor = (app.preview_options_cursor + 1) % rows.len();
+            }
+            KeyCode::Left | KeyCode::Right => {
+                let delta = if key.code == KeyCode::Left { -1 } else { 1 };
+                if let Some(&row) = rows.get(app.preview_options_cursor) {
+                    app.preview_options.adjust(row, delta, app.preview_format);
+                }
+            }
+            KeyCode::Enter => match rows.get(app.preview_options_cursor) {
+                Some(&row) if row.is_text() => {
+                    app.preview_options_editing = true;
+                    app.preview_options_edit_buf.clear();
+                }
+                _ => app.preview_options_menu = false,
+            },
+            KeyCode::Char('r') => {
+                app.preview_options = DownloadOptions::default();
+            }
+            KeyCode::Esc => {
+                app.preview_options_menu = false;
+            }
+            _ if action == Some(Action::PreviewOptions) => {
+                app.preview_options_menu = false;
+            }
+            _ => {}
+        }
+        return;
+    }
 
     // Custom language text input mode
     if app.preview_subs_editing {
@@ -1259,9 +1666,9 @@ fn handle_preview_key(
         return;
     }
 
-    match key.code {
+    match (key.code, action) {
         // ← → ↑ ↓ — cycle quality for MP4
-        KeyCode::Left | KeyCode::Up => {
+        (KeyCode::Left | KeyCode::Up, _) => {
             if app.preview_format == DownloadFormat::Mp4
                 && let PreviewState::Ready { ref info } = app.preview_state
             {
@@ -1271,7 +1678,7 @@ fn handle_preview_key(
                 }
             }
         }
-        KeyCode::Right | KeyCode::Down => {
+        (KeyCode::Right | KeyCode::Down, _) => {
             if app.preview_format == DownloadFormat::Mp4
                 && let PreviewState::Ready { ref info } = app.preview_state
             {
@@ -1282,17 +1689,24 @@ fn handle_preview_key(
             }
         }
 
-        // Tab — toggle MP3 / MP4 format inside preview
-        KeyCode::Tab => {
+        // [Tab] — toggle MP3 / MP4 format inside preview
+        (_, Some(Action::PreviewFormat)) => {
             app.preview_format = match app.preview_format {
                 DownloadFormat::Mp3 => DownloadFormat::Mp4,
                 DownloadFormat::Mp4 => DownloadFormat::Mp3,
             };
             app.preview_quality_cursor = 0;
+            app.preview_options.fit_to(app.preview_format);
+        }
+
+        // [O] — open download options menu (conversion, cut, effects)
+        (_, Some(Action::PreviewOptions)) => {
+            app.preview_options_menu = true;
+            app.preview_options_cursor = 0;
         }
 
-        // S — open subtitle menu (MP4 only); auto-enables subs
-        KeyCode::Char('s') | KeyCode::Char('S') => {
+        // [S] — open subtitle menu (MP4 only); auto-enables subs
+        (_, Some(Action::PreviewSubtitles)) => {
             if app.preview_format == DownloadFormat::Mp4 {
                 app.preview_subs_menu = true;
                 app.preview_subs_enabled = true;
@@ -1300,21 +1714,75 @@ fn handle_preview_key(
         }
 
         // Enter — confirm download with selected quality
-        KeyCode::Enter => {
-            confirm_preview_download(app, dl_tx);
+        (KeyCode::Enter, _) => {
+            confirm_preview_download(app);
+        }
+
+        _ => {}
+    }
+}
+
+// ── Import checklist key handler ──────────────────────────────────────────────
+
+fn handle_import_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    let action = app.keymap.action(KeyContext::Import, &key);
+    // While loading: only Esc (handled globally). A failed fetch closes on Enter.
+    let ImportState::Ready(ref mut list) = app.import_state else {
+        if matches!(app.import_state, ImportState::Failed(_)) && key.code == KeyCode::Enter {
+            close_import(app);
         }
+        return;
+    };
+    let last = list.items.len().saturating_sub(1);
 
+    match (key.code, action) {
+        (KeyCode::Up, _) => {
+            app.import_cursor = app.import_cursor.saturating_sub(1);
+        }
+        (KeyCode::Down, _) => {
+            app.import_cursor = (app.import_cursor + 1).min(last);
+        }
+        // ← → / PgUp PgDn — previous / next page
+        (KeyCode::Left | KeyCode::PageUp, _) => {
+            app.import_cursor = app.import_cursor.saturating_sub(playlist_import::PAGE_SIZE);
+        }
+        (KeyCode::Right | KeyCode::PageDown, _) => {
+            app.import_cursor = (app.import_cursor + playlist_import::PAGE_SIZE).min(last);
+        }
+        (_, Some(Action::ImportToggle)) => {
+            if let Some(item) = list.items.get_mut(app.import_cursor) {
+                item.selected = !item.selected;
+            }
+        }
+        (_, Some(Action::ImportToggleAll)) => {
+            list.toggle_all();
+        }
+        (_, Some(Action::ImportFormat)) => {
+            app.import_format = match app.import_format {
+                DownloadFormat::Mp3 => DownloadFormat::Mp4,
+                DownloadFormat::Mp4 => DownloadFormat::Mp3,
+            };
+        }
+        (KeyCode::Enter, _) => {
+            confirm_import(app);
+        }
         _ => {}
     }
 }
 
 // ── Settings key handler ──────────────────────────────────────────────────────
 
-fn handle_settings_key(app: &mut App, key: crossterm::event::KeyEvent, picker_tx: mpsc::Sender<String>) {
+fn handle_settings_key(
+    app: &mut App,
+    key: crossterm::event::KeyEvent,
+    action: Option<Action>,
+    picker_tx: mpsc::Sender<String>,
+) {
     use ui::settings::{ITEMS, ItemKind, cycle_value, get_value, set_value};
 
-    // Global tab keys are handled above — ignore them here
-    if matches!(key.code, KeyCode::Char('1') | KeyCode::Char('2') | KeyCode::Char('3')) {
+    // Global tab keys are handled above — ignore them here (never while
+    // editing: `action` is `None` then, so digits reach the text field)
+    if is_tab_switch(action) {
         return;
     }
 
@@ -1326,12 +1794,21 @@ fn handle_settings_key(app: &mut App, key: crossterm::event::KeyEvent, picker_tx
         match key.code {
             KeyCode::Enter => {
                 let val = app.settings_edit_buf.clone();
-                set_value(app, cur, val);
+                set_value(app, cur, val.clone());
                 app.settings_editing = false;
                 app.settings_edit_buf.clear();
                 // Auto-save after confirming a text edit.
                 let _ = app.settings.save();
-                app.add_toast("Settings saved", ToastKind::Success);
+                if cur == 14 && get_value(app, cur) != val.trim() {
+                    app.add_toast("Invalid value — use HH:MM-HH:MM", ToastKind::Error);
+                } else if cur == 16
+                    && !val.trim().is_empty()
+                    && let Err(e) = doracore::download::filename_template::FilenameTemplate::parse(&val)
+                {
+                    app.add_toast(&format!("Invalid template — {}", e), ToastKind::Error);
+                } else {
+                    app.add_toast("Settings saved", ToastKind::Success);
+                }
             }
             KeyCode::Backspace => {
                 app.settings_edit_buf.pop();
@@ -1406,6 +1883,144 @@ fn handle_settings_key(app: &mut App, key: crossterm::event::KeyEvent, picker_tx
     }
 }
 
+// ── Subscriptions tab ─────────────────────────────────────────────────────────
+
+fn handle_subscriptions_key(app: &mut App, key: crossterm::event::KeyEvent, action: Option<Action>) {
+    if is_tab_switch(action) {
+        return;
+    }
+    let typing = is_typing(app);
+    let action = app
+        .keymap
+        .action(KeyContext::Subscriptions, &key)
+        .filter(|_| !(typing && is_text_key(&key)));
+
+    match (key.code, action) {
+        (_, Some(Action::SubFormat)) => {
+            update_selected_subscription(app, |sub| {
+                sub.format = match sub.format {
+                    DownloadFormat::Mp3 => DownloadFormat::Mp4,
+                    DownloadFormat::Mp4 => DownloadFormat::Mp3,
+                };
+            });
+        }
+        (_, Some(Action::SubAutoDownload)) => {
+            if let Some((name, on)) = update_selected_subscription(app, |sub| {
+                sub.auto_download = !sub.auto_download;
+                (sub.name().to_string(), sub.auto_download)
+            }) {
+                let state = if on { "on" } else { "off" };
+                app.add_toast(&format!("Auto-download {} for {}", state, name), ToastKind::Info);
+            }
+        }
+        // Clearing the check time makes it due; whichever of the TUI or the
+        // daemon schedules checks picks it up within a second.
+        (_, Some(Action::SubCheckNow)) => {
+            update_selected_subscription(app, |sub| sub.last_checked = None);
+        }
+        (_, Some(Action::SubMarkSeen)) => {
+            update_selected_subscription(app, |sub| sub.take_new());
+        }
+        (_, Some(Action::SubUnfollow)) => {
+            if let Some(sub) = app.selected_subscription() {
+                let (id, name) = (sub.id, sub.name().to_string());
+                let (subs, _) = subscriptions::update(|subs| subs.retain(|s| s.id != id));
+                app.set_subscriptions(subs);
+                app.add_toast(&format!("Unfollowed {}", name), ToastKind::Info);
+            }
+        }
+        (KeyCode::Enter, _) if typing => follow_subscription(app),
+        (KeyCode::Enter, _) => {
+            let Some(format) = app.selected_subscription().map(|s| s.format) else {
+                return;
+            };
+            let items = update_selected_subscription(app, |sub| sub.take_new()).unwrap_or_default();
+            if items.is_empty() {
+                app.add_toast("No new items", ToastKind::Info);
+            } else {
+                let count = items.len();
+                queue_subscription_items(app, items, format);
+                app.add_toast(&format!("Queued {} downloads", count), ToastKind::Success);
+            }
+        }
+        (KeyCode::Up, _) => {
+            app.subs_cursor = app.subs_cursor.saturating_sub(1);
+        }
+        (KeyCode::Down, _) => {
+            if app.subs_cursor + 1 < app.subscriptions.len() {
+                app.subs_cursor += 1;
+            }
+        }
+        (KeyCode::Backspace, _) => {
+            app.sub_input.pop();
+        }
+        (KeyCode::Char(c), _) => {
+            app.sub_input.push(c);
+        }
+        _ => {}
+    }
+}
+
+/// Follow the link typed into the follow bar; it is checked on the next loop
+/// iteration (or by the daemon when attached).
+fn follow_subscription(app: &mut App) {
+    let input = std::mem::take(&mut app.sub_input);
+    let format = default_format(&app.settings);
+    let (subs, result) = subscriptions::update(|subs| subscriptions::follow(subs, &input, format));
+    app.set_subscriptions(subs);
+    match result {
+        Ok(sub) => {
+            app.subs_cursor = app.subscriptions.iter().position(|s| s.id == sub.id).unwrap_or(0);
+            app.add_toast(&format!("Following {}", sub.url), ToastKind::Success);
+        }
+        Err(msg) => {
+            app.sub_input = input;
+            app.add_toast(&msg, ToastKind::Error);
+        }
+    }
+}
+
+/// Apply `change` to the highlighted subscription and save it.
+fn update_selected_subscription<R>(
+    app: &mut App,
+    change: impl FnOnce(&mut subscriptions::Subscription) -> R,
+) -> Option<R> {
+    let id = app.selected_subscription()?.id;
+    let (subs, result) = subscriptions::update(|subs| subs.iter_mut().find(|s| s.id == id).map(change));
+    app.set_subscriptions(subs);
+    result
+}
+
+fn queue_subscription_items(app: &mut App, items: Vec<SubItem>, format: DownloadFormat) {
+    for item in items {
+        let id = app.add_download(item.url, format);
+        if let Some(slot) = app.slot_mut(id) {
+            slot.title = Some(item.title);
+        }
+    }
+}
+
+/// Record a finished check, announce new items and queue them for
+/// auto-download subscriptions.
+fn handle_check_report(app: &mut App, report: CheckReport) {
+    app.subs_checker.finish(report.id);
+    let (subs, summary) = subscriptions::record(report);
+    app.set_subscriptions(subs);
+    let Some(summary) = summary.filter(|s| s.fresh > 0) else {
+        return;
+    };
+    if summary.queue.is_empty() {
+        app.add_toast(&format!("{} new from {}", summary.fresh, summary.name), ToastKind::Info);
+    } else {
+        let count = summary.queue.len();
+        queue_subscription_items(app, summary.queue, summary.format);
+        app.add_toast(
+            &format!("Queued {} new from {}", count, summary.name),
+            ToastKind::Success,
+        );
+    }
+}
+
 // ── URL normalizer ────────────────────────────────────────────────────────────
 
 /// Strip tracking/share parameters from social URLs so yt-dlp receives a clean
@@ -1539,7 +2154,7 @@ fn handle_click_internal(app: &mut App, target: app::ClickTarget, senders: &UiSe
             handle_preview_toggle_format_click(app);
         }
         ClickTarget::PreviewDownload => {
-            confirm_preview_download(app, senders.downloads.clone());
+            confirm_preview_download(app);
         }
         ClickTarget::PreviewClose => {
             close_preview(app);
@@ -1547,6 +2162,32 @@ fn handle_click_internal(app: &mut App, target: app::ClickTarget, senders: &UiSe
         ClickTarget::PreviewToggleSubsMenu => {
             app.preview_subs_menu = !app.preview_subs_menu;
         }
+        ClickTarget::PreviewToggleOptionsMenu => {
+            app.preview_options_menu = !app.preview_options_menu;
+            app.preview_options_cursor = 0;
+        }
+        ClickTarget::ImportToggleItem(idx) => {
+            if let ImportState::Ready(ref mut list) = app.import_state
+                && let Some(item) = list.items.get_mut(idx)
+            {
+                item.selected = !item.selected;
+                app.import_cursor = idx;
+            }
+        }
+        ClickTarget::ImportEnqueue => {
+            confirm_import(app);
+        }
+        ClickTarget::ImportClose => {
+            close_import(app);
+        }
+        ClickTarget::ResumeQueue(accept) => {
+            answer_resume_prompt(app, accept);
+        }
+        ClickTarget::SubscriptionSelect(idx) => {
+            app.subs_cursor = idx;
+        }
+        ClickTarget::PlayerToggle => app.player.toggle_pause(),
+        ClickTarget::PlayerSeek(permille) => app.player.seek_to_fraction(permille as f64 / 1000.0),
         ClickTarget::PreviewToggleSubsEnabled => {
             app.preview_subs_enabled = !app.preview_subs_enabled;
         }
@@ -1601,6 +2242,7 @@ fn handle_preview_toggle_format_click(app: &mut App) {
         DownloadFormat::Mp4 => DownloadFormat::Mp3,
     };
     app.preview_quality_cursor = 0;
+    app.preview_options.fit_to(app.preview_format);
 }
 
 fn close_preview(app: &mut App) {
@@ -1611,6 +2253,16 @@ fn close_preview(app: &mut App) {
     app.preview_pending_url = None;
     app.preview_subs_menu = false;
     app.preview_subs_editing = false;
+    reset_preview_options(app);
+}
+
+/// Forget the options chosen for the current preview.
+fn reset_preview_options(app: &mut App) {
+    app.preview_options = DownloadOptions::default();
+    app.preview_options_menu = false;
+    app.preview_options_cursor = 0;
+    app.preview_options_editing = false;
+    app.preview_options_edit_buf.clear();
 }
 
 fn handle_preview_subs_lang_click(app: &mut App, idx: usize) {
@@ -1730,6 +2382,8 @@ fn handle_artist_song_click(app: &mut App, artist: String, title: String, lyrics
                     release_date: Some("2026-03-04".to_string()),
                     thumbnail_url: None,
                     lyrics: "This is a demo lyrics text.\n\n[Verse 1]\nIt works without a token!\nIn demo mode you see this.\n\n[Chorus]\nCards are beautiful!\nGrid is responsive!\nLoad more is fun!\n\n[Outro]\nEnjoy dora-tui!".to_string(),
+                    synced: Vec::new(),
+                    for_track: None,
                 }))
                 .await;
         });
@@ -1753,12 +2407,43 @@ fn handle_artist_song_click(app: &mut App, artist: String, title: String, lyrics
                 release_date: r.release_date,
                 thumbnail_url: r.thumbnail_url,
                 lyrics,
+                synced: Vec::new(),
+                for_track: None,
             }
         });
         let _ = lyrics_tx.send(event).await;
     });
 }
 
+/// LRCLIB lyrics for a playing track, with LRC timing when available.
+async fn fetch_track_lyrics(track: &player::Track) -> Option<app::LyricsResult> {
+    let (artist, title) = if track.artist.is_empty() {
+        doracore::lyrics::parse_artist_title(&track.title)
+    } else {
+        (track.artist.as_str(), track.title.as_str())
+    };
+    let found = doracore::lyrics::synced::fetch_synced_lyrics(artist, title).await?;
+    let lyrics = found.plain.unwrap_or_else(|| {
+        found
+            .lines
+            .iter()
+            .map(|l| l.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    Some(app::LyricsResult {
+        artist: found.artist,
+        artist_id: None,
+        title: found.title,
+        album: found.album,
+        release_date: None,
+        thumbnail_url: None,
+        lyrics,
+        synced: found.lines,
+        for_track: Some(track.path.clone()),
+    })
+}
+
 fn handle_lyrics_load_more_click(app: &mut App, artist_tx: ArtistSongsSender) {
     if app.lyrics_loading {
         return;
@@ -1815,27 +2500,30 @@ fn handle_lyrics_load_more_click(app: &mut App, artist_tx: ArtistSongsSender) {
     });
 }
 
-fn confirm_preview_download(app: &mut App, dl_tx: mpsc::Sender<(usize, SlotEvent)>) {
+/// Queue the previewed URL with the chosen quality, subtitles and options.
+/// The slot starts Pending; `dispatch_pending_spawns` starts it when a
+/// parallel slot and the active-hours window allow.
+fn confirm_preview_download(app: &mut App) {
     let url = app.preview_url.clone();
     let fmt = app.preview_format;
 
-    let quality_str = if fmt == DownloadFormat::Mp4 {
+    let video_quality = if fmt == DownloadFormat::Mp4 {
         if let PreviewState::Ready { ref info } = app.preview_state {
             let cursor = app.preview_quality_cursor;
             if cursor < info.available_heights.len() {
-                format!("{}p", info.available_heights[cursor])
+                Some(format!("{}p", info.available_heights[cursor]))
             } else {
-                "best".to_string()
+                Some("best".to_string())
             }
         } else {
-            app.settings.video_quality.clone()
+            None
         }
     } else {
-        String::new()
+        None
     };
 
-    // Build subtitle options if enabled and format is MP4
-    let subtitle_opts = if app.preview_subs_enabled && fmt == DownloadFormat::Mp4 {
+    // Subtitle language if burning is enabled and format is MP4
+    let subtitle_lang = if app.preview_subs_enabled && fmt == DownloadFormat::Mp4 {
         let lang = if let Some(ref custom) = app.preview_subs_custom_lang {
             custom.clone()
         } else if let PreviewState::Ready { ref info } = app.preview_state {
@@ -1846,32 +2534,25 @@ fn confirm_preview_download(app: &mut App, dl_tx: mpsc::Sender<(usize, SlotEvent
         } else {
             "en".to_string()
         };
-        Some(SubtitleOptions { lang })
+        Some(lang)
     } else {
         None
     };
 
-    let mut s = app.settings.clone();
-    if !quality_str.is_empty() {
-        s.video_quality = quality_str;
-    }
-    if let Some(ref c) = app.cookies_file {
-        s.ytdlp_cookies = c.clone();
-    }
-
     let mut thumb_url = None;
     if let PreviewState::Ready { info, .. } = &app.preview_state {
         thumb_url = info.thumbnail_url.clone();
     }
 
-    let id = app.add_download(url.clone(), fmt);
+    let mut options = std::mem::take(&mut app.preview_options);
+    options.fit_to(fmt);
+
+    let id = app.add_download(url, fmt);
     if let Some(slot) = app.slot_mut(id) {
-        slot.task_spawned = true;
         slot.thumbnail_url = thumb_url;
-    }
-    let handle = spawn_download(id, url, fmt, s, dl_tx, subtitle_opts);
-    if let Some(slot) = app.slot_mut(id) {
-        slot.cancel = Some(handle);
+        slot.options = options;
+        slot.video_quality = video_quality;
+        slot.subtitle_lang = subtitle_lang;
     }
 
     // Reset preview + subtitle state
@@ -1884,6 +2565,224 @@ fn confirm_preview_download(app: &mut App, dl_tx: mpsc::Sender<(usize, SlotEvent
     app.preview_subs_custom_lang = None;
     app.preview_subs_editing = false;
     app.preview_subs_edit_buf.clear();
+    reset_preview_options(app);
+}
+
+// ── Persistent queue ─────────────────────────────────────────────────────────
+
+/// Write unfinished slots to `queue.json` when they differ from the last save.
+/// Held off while the resume prompt is up so the saved queue survives until
+/// the user answers.
+fn persist_queue(app: &mut App) {
+    if app.demo_mode || app.daemon_attached || app.resume_prompt.is_some() {
+        return;
+    }
+    let queue = app.queued_downloads();
+    if queue != app.queue_snapshot {
+        app::queue_save(&queue);
+        app.queue_snapshot = queue;
+    }
+}
+
+/// Restore the saved queue as Pending slots, or discard it.
+fn answer_resume_prompt(app: &mut App, accept: bool) {
+    let Some(saved) = app.resume_prompt.take() else {
+        return;
+    };
+    if accept {
+        let count = saved.len();
+        for queued in saved {
+            app.restore_download(queued);
+        }
+        app.add_toast(&format!("Resumed {} downloads", count), ToastKind::Success);
+    } else {
+        app::queue_save(&[]);
+    }
+}
+
+// ── Daemon attach ────────────────────────────────────────────────────────────
+
+/// Tag sent with a slot's `add` request so its job can be matched back.
+fn daemon_tag(slot_id: usize) -> String {
+    format!("tui-{}-{}", std::process::id(), slot_id)
+}
+
+/// Mirror one daemon job into the queue: update the slot running it, claim
+/// the slot this TUI queued it from, or add a slot for jobs queued elsewhere
+/// (`dora add`, another TUI).
+fn handle_daemon_event(app: &mut App, event: daemon::client::AttachEvent) {
+    use daemon::client::AttachEvent;
+    use daemon::protocol::JobState;
+
+    let job = match event {
+        AttachEvent::Job(job) => job,
+        AttachEvent::Error(message) => {
+            app.add_toast(&format!("Daemon: {}", message), ToastKind::Error);
+            return;
+        }
+        AttachEvent::Disconnected => {
+            app.daemon_attached = false;
+            for slot in app.slots.iter_mut().filter(|s| !s.state.is_finished()) {
+                if slot.remote_id.is_some() {
+                    slot.state = SlotState::Failed {
+                        reason: "dora daemon stopped".to_string(),
+                    };
+                } else {
+                    // Handed over but never picked up — run it locally.
+                    slot.task_spawned = false;
+                }
+            }
+            app.add_toast("Lost connection to dora daemon", ToastKind::Error);
+            return;
+        }
+    };
+
+    let own_tag = job.tag.as_deref().and_then(|t| {
+        let prefix = format!("tui-{}-", std::process::id());
+        t.strip_prefix(&prefix).and_then(|id| id.parse::<usize>().ok())
+    });
+    let slot_id = if let Some(slot) = app.slots.iter().find(|s| s.remote_id == Some(job.id)) {
+        slot.id
+    } else if let Some(id) = own_tag.filter(|id| app.slots.iter().any(|s| s.id == *id)) {
+        id
+    } else if job.state.is_finished() {
+        // Finished before we attached — already in history.
+        return;
+    } else {
+        app.add_download(job.url.clone(), job.format)
+    };
+
+    let Some(slot) = app.slot_mut(slot_id) else {
+        return;
+    };
+    slot.remote_id = Some(job.id);
+    slot.task_spawned = true;
+    slot.options = job.options;
+    if job.title.is_some() {
+        slot.title = job.title;
+    }
+    if job.artist.is_some() {
+        slot.artist = job.artist;
+    }
+    let already_finished = slot.state.is_finished();
+
+    let event = match job.state {
+        JobState::Pending => return,
+        JobState::Fetching => SlotEvent::Fetching,
+        JobState::Downloading {
+            percent,
+            speed_mbs,
+            eta_secs,
+        } => SlotEvent::Progress {
+            percent,
+            speed_mbs,
+            eta_secs,
+        },
+        JobState::Merging { position_secs } => SlotEvent::Merging { position_secs },
+        JobState::Postprocessing { label } => SlotEvent::Postprocessing {
+            label: postprocessing_label(&label),
+        },
+        JobState::Done { .. } | JobState::Failed { .. } if already_finished => return,
+        JobState::Done { path, size_mb } => SlotEvent::Done { path, size_mb },
+        JobState::Failed { reason } => SlotEvent::Failed { reason },
+        JobState::Cancelled => {
+            app.slots.retain(|s| s.id != slot_id);
+            return;
+        }
+    };
+    handle_slot_event(app, slot_id, event);
+}
+
+/// `SlotState` keeps static labels; map the daemon's back onto them.
+fn postprocessing_label(label: &str) -> &'static str {
+    match label {
+        "recoding" => "recoding",
+        "burning subtitles" => "burning subtitles",
+        "applying effects" => "applying effects",
+        "converting" => "converting",
+        _ => "processing",
+    }
+}
+
+// ── Playlist / batch import ──────────────────────────────────────────────────
+
+/// Show the checklist in its loading state and expand `url` in the background.
+fn open_playlist_import(app: &mut App, url: String) {
+    app.import_format = default_format(&app.settings);
+    app.import_cursor = 0;
+    app.import_state = ImportState::Loading { url: url.clone() };
+    app.import_pending_url = Some(url);
+}
+
+/// Show the checklist for an already-known list of URLs.
+fn open_import_list(app: &mut App, list: ImportList) {
+    app.import_format = default_format(&app.settings);
+    app.import_cursor = 0;
+    app.import_state = ImportState::Ready(list);
+}
+
+fn open_import_file(app: &mut App, path: &std::path::Path) {
+    match playlist_import::read_url_file(path) {
+        Ok(urls) => {
+            let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            let title = format!("{} links from {}", urls.len(), name);
+            let urls = urls.iter().map(|u| normalize_url(u)).collect();
+            open_import_list(app, ImportList::from_urls(title, urls));
+        }
+        Err(e) => {
+            app.add_toast(&format!("Import failed: {:#}", e), ToastKind::Error);
+        }
+    }
+}
+
+/// Local text file named by `input` (pasted or drag-and-dropped path), if any.
+fn url_file_path(input: &str) -> Option<std::path::PathBuf> {
+    // Unescape path from Terminal.app drag-and-drop (e.g. "\ " → " ")
+    let clean = input.replace("\\ ", " ");
+    let clean = clean.trim().trim_matches(|c| c == '"' || c == '\'');
+    if clean.is_empty() || clean.starts_with("http://") || clean.starts_with("https://") {
+        return None;
+    }
+    let path = std::path::PathBuf::from(DoraSettings::expand_path(clean));
+    path.is_file().then_some(path)
+}
+
+/// Queue every ticked checklist item as a Pending slot.
+fn confirm_import(app: &mut App) {
+    let selected = match &app.import_state {
+        ImportState::Ready(list) => list.selected_count(),
+        _ => return,
+    };
+    if selected == 0 {
+        app.add_toast("Nothing selected", ToastKind::Info);
+        return;
+    }
+    let ImportState::Ready(list) = std::mem::take(&mut app.import_state) else {
+        return;
+    };
+    let fmt = app.import_format;
+    for item in list.items.into_iter().filter(|i| i.selected) {
+        let id = app.add_download(item.url, fmt);
+        if let Some(slot) = app.slot_mut(id) {
+            slot.title = item.title;
+        }
+    }
+    app.import_cursor = 0;
+    app.add_toast(&format!("Queued {} downloads", selected), ToastKind::Success);
+}
+
+fn close_import(app: &mut App) {
+    app.import_state = ImportState::Hidden;
+    app.import_pending_url = None;
+    app.import_cursor = 0;
+}
+
+fn default_format(settings: &DoraSettings) -> DownloadFormat {
+    if settings.default_format == "MP4" {
+        DownloadFormat::Mp4
+    } else {
+        DownloadFormat::Mp3
+    }
 }
 
 fn open_in_browser(url: &str) {
@@ -0,0 +1,274 @@
+//! Pure-Rust audio decoding (symphonia): interleaved `f32` chunks, seeking
+//! and waveform peaks. Nothing here touches a sound device.
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::Duration;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use symphonia::core::units::{Time, TimeBase};
+
+/// An open audio file positioned somewhere in its first playable track.
+pub struct Decoder {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn symphonia::core::codecs::Decoder>,
+    track_id: u32,
+    time_base: Option<TimeBase>,
+    sample_buf: Option<SampleBuffer<f32>>,
+    pub sample_rate: u32,
+    pub channels: usize,
+    /// Track length, when the container reports it.
+    pub duration: Option<Duration>,
+}
+
+impl Decoder {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(ext);
+        }
+        let probed = symphonia::default::get_probe().format(
+            &hint,
+            stream,
+            &FormatOptions {
+                enable_gapless: true,
+                ..Default::default()
+            },
+            &MetadataOptions::default(),
+        )?;
+        let format = probed.format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or_else(|| anyhow::anyhow!("no audio track"))?;
+        let params = &track.codec_params;
+        let decoder = symphonia::default::get_codecs().make(params, &DecoderOptions::default())?;
+        let sample_rate = params
+            .sample_rate
+            .ok_or_else(|| anyhow::anyhow!("unknown sample rate"))?;
+        let channels = params.channels.map_or(2, |c| c.count()).max(1);
+        let time_base = params.time_base;
+        let duration = match (time_base, params.n_frames) {
+            (Some(tb), Some(n)) => Some(time_to_duration(tb.calc_time(n))),
+            (None, Some(n)) => Some(Duration::from_secs_f64(n as f64 / sample_rate as f64)),
+            _ => None,
+        };
+
+        Ok(Self {
+            track_id: track.id,
+            format,
+            decoder,
+            time_base,
+            sample_buf: None,
+            sample_rate,
+            channels,
+            duration,
+        })
+    }
+
+    /// Decode the next packet as interleaved samples. `None` at end of stream;
+    /// corrupt packets are skipped.
+    pub fn next_chunk(&mut self) -> anyhow::Result<Option<&[f32]>> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(p) => p,
+                Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+                Err(SymphoniaError::ResetRequired) => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            match self.decoder.decode(&packet) {
+                Ok(decoded) => {
+                    if decoded.frames() == 0 {
+                        continue;
+                    }
+                    let needs_new = self
+                        .sample_buf
+                        .as_ref()
+                        .is_none_or(|b| b.capacity() < decoded.capacity() * decoded.spec().channels.count());
+                    if needs_new {
+                        self.sample_buf = Some(SampleBuffer::new(decoded.capacity() as u64, *decoded.spec()));
+                    }
+                    if let Some(buf) = self.sample_buf.as_mut() {
+                        buf.copy_interleaved_ref(decoded);
+                    }
+                    break;
+                }
+                Err(SymphoniaError::DecodeError(e)) => {
+                    log::debug!("player: skipping bad packet: {}", e);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(self.sample_buf.as_ref().map(|b| b.samples()))
+    }
+
+    /// Jump to `pos`; returns where decoding actually resumes.
+    pub fn seek(&mut self, pos: Duration) -> anyhow::Result<Duration> {
+        let seeked = self.format.seek(
+            SeekMode::Accurate,
+            SeekTo::Time {
+                time: Time::new(pos.as_secs(), pos.subsec_nanos() as f64 / 1e9),
+                track_id: Some(self.track_id),
+            },
+        )?;
+        self.decoder.reset();
+        Ok(match self.time_base {
+            Some(tb) => time_to_duration(tb.calc_time(seeked.required_ts)),
+            None => pos,
+        })
+    }
+}
+
+fn time_to_duration(t: Time) -> Duration {
+    Duration::from_secs(t.seconds) + Duration::from_secs_f64(t.frac)
+}
+
+/// Peak amplitude (0.0–1.0) of `buckets` equal slices of the whole file, for
+/// the now-playing waveform. Decodes the file once, so run it off the UI
+/// thread.
+pub fn waveform(path: &Path, buckets: usize) -> anyhow::Result<Vec<f32>> {
+    let mut decoder = Decoder::open(path)?;
+    // Collect 20 ms peaks first; the length isn't always known up front.
+    let window = (decoder.sample_rate as usize / 50).max(1) * decoder.channels;
+    let mut peaks = Vec::new();
+    let mut current = 0.0f32;
+    let mut filled = 0usize;
+    while let Some(chunk) = decoder.next_chunk()? {
+        for s in chunk {
+            current = current.max(s.abs());
+            filled += 1;
+            if filled == window {
+                peaks.push(current);
+                current = 0.0;
+                filled = 0;
+            }
+        }
+    }
+    if filled > 0 {
+        peaks.push(current);
+    }
+    Ok(downsample_peaks(&peaks, buckets))
+}
+
+/// Reduce `peaks` to `buckets` values, each the max of its slice.
+pub fn downsample_peaks(peaks: &[f32], buckets: usize) -> Vec<f32> {
+    if peaks.is_empty() || buckets == 0 {
+        return Vec::new();
+    }
+    (0..buckets)
+        .map(|i| {
+            let start = i * peaks.len() / buckets;
+            let end = ((i + 1) * peaks.len() / buckets).max(start + 1).min(peaks.len());
+            peaks[start.min(peaks.len() - 1)..end]
+                .iter()
+                .fold(0.0f32, |m, p| m.max(*p))
+                .min(1.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Write a 16-bit PCM WAV: `seconds` of a 440 Hz tone whose volume rises
+    /// linearly from silence to full scale.
+    pub(crate) fn write_test_wav(path: &Path, sample_rate: u32, channels: u16, seconds: u32) {
+        let frames = sample_rate * seconds;
+        let data_len = frames * channels as u32 * 2;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * channels as u32 * 2).to_le_bytes());
+        bytes.extend_from_slice(&(channels * 2).to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for i in 0..frames {
+            let t = i as f32 / sample_rate as f32;
+            let volume = i as f32 / frames as f32;
+            let v = ((t * 440.0 * std::f32::consts::TAU).sin() * volume * i16::MAX as f32) as i16;
+            for _ in 0..channels {
+                bytes.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn decodes_wav_metadata_and_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.wav");
+        write_test_wav(&path, 8000, 2, 2);
+
+        let mut decoder = Decoder::open(&path).unwrap();
+        assert_eq!(decoder.sample_rate, 8000);
+        assert_eq!(decoder.channels, 2);
+        assert_eq!(decoder.duration, Some(Duration::from_secs(2)));
+
+        let mut total = 0;
+        while let Some(chunk) = decoder.next_chunk().unwrap() {
+            assert_eq!(chunk.len() % 2, 0);
+            total += chunk.len();
+        }
+        assert_eq!(total, 8000 * 2 * 2);
+    }
+
+    #[test]
+    fn seek_resumes_near_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.wav");
+        write_test_wav(&path, 8000, 1, 4);
+
+        let mut decoder = Decoder::open(&path).unwrap();
+        let at = decoder.seek(Duration::from_secs(3)).unwrap();
+        assert!(
+            at.abs_diff(Duration::from_secs(3)) < Duration::from_millis(50),
+            "{:?}",
+            at
+        );
+        let mut rest = 0;
+        while let Some(chunk) = decoder.next_chunk().unwrap() {
+            rest += chunk.len();
+        }
+        assert!((7000..=9000).contains(&rest), "{} samples after seek", rest);
+    }
+
+    #[test]
+    fn waveform_follows_volume() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.wav");
+        write_test_wav(&path, 8000, 1, 2);
+
+        let peaks = waveform(&path, 10).unwrap();
+        assert_eq!(peaks.len(), 10);
+        assert!(peaks[0] < 0.15);
+        assert!(peaks[9] > 0.85);
+        assert!(peaks.windows(2).all(|w| w[0] <= w[1] + 0.05));
+    }
+
+    #[test]
+    fn downsample_handles_short_input() {
+        assert_eq!(downsample_peaks(&[0.5], 3), vec![0.5, 0.5, 0.5]);
+        assert_eq!(downsample_peaks(&[0.1, 0.9, 0.2, 0.4], 2), vec![0.9, 0.4]);
+        assert!(downsample_peaks(&[], 4).is_empty());
+    }
+}
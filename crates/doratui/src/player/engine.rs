@@ -0,0 +1,515 @@
+//! Playback engine: a decode thread filling a sample buffer that the output
+//! callback drains.
+//!
+//! [`Shared`] is the only state both sides touch. It tracks how many frames
+//! the device consumed, which is where the playback position comes from, so
+//! timing can be tested by calling [`Shared::fill`] directly.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::decode::Decoder;
+use super::output;
+
+/// How far ahead of the device the decode thread runs.
+const BUFFER_AHEAD: Duration = Duration::from_millis(500);
+
+/// State shared between the decode thread, the output callback and the UI.
+pub struct Shared {
+    buffer: Mutex<VecDeque<f32>>,
+    sample_rate: u32,
+    channels: usize,
+    /// Bumped by every load and seek; chunks decoded for an older value are
+    /// dropped instead of being played.
+    generation: AtomicU64,
+    /// Position (ms) at which `frames_played` was last reset.
+    base_ms: AtomicU64,
+    frames_played: AtomicU64,
+    paused: AtomicBool,
+    decoding_done: AtomicBool,
+}
+
+impl Shared {
+    pub fn new(sample_rate: u32, channels: usize) -> Self {
+        Self {
+            buffer: Mutex::new(VecDeque::new()),
+            sample_rate: sample_rate.max(1),
+            channels: channels.max(1),
+            generation: AtomicU64::new(0),
+            base_ms: AtomicU64::new(0),
+            frames_played: AtomicU64::new(0),
+            paused: AtomicBool::new(false),
+            decoding_done: AtomicBool::new(true),
+        }
+    }
+
+    /// Output callback: copy buffered samples into `data`, padding with
+    /// silence. Plays nothing (and doesn't advance) while paused.
+    pub fn fill(&self, data: &mut [f32]) {
+        if self.paused.load(Ordering::Relaxed) {
+            data.fill(0.0);
+            return;
+        }
+        let Ok(mut buffer) = self.buffer.lock() else {
+            data.fill(0.0);
+            return;
+        };
+        let n = data.len().min(buffer.len()) / self.channels * self.channels;
+        for (out, sample) in data.iter_mut().zip(buffer.drain(..n)) {
+            *out = sample;
+        }
+        data[n..].fill(0.0);
+        self.frames_played
+            .fetch_add((n / self.channels) as u64, Ordering::Relaxed);
+    }
+
+    /// Where playback is now.
+    pub fn position(&self) -> Duration {
+        let frames = self.frames_played.load(Ordering::Relaxed);
+        Duration::from_millis(self.base_ms.load(Ordering::Relaxed))
+            + Duration::from_secs_f64(frames as f64 / self.sample_rate as f64)
+    }
+
+    /// The track has been decoded and every sample played.
+    pub fn finished(&self) -> bool {
+        self.decoding_done.load(Ordering::Relaxed) && self.buffer.lock().is_ok_and(|b| b.is_empty())
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Drop everything buffered and restart the clock at `pos`. Returns the
+    /// generation decoded chunks must carry from now on.
+    fn reset(&self, pos: Duration, done: bool) -> u64 {
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        buffer.clear();
+        self.base_ms.store(pos.as_millis() as u64, Ordering::Relaxed);
+        self.frames_played.store(0, Ordering::Relaxed);
+        self.decoding_done.store(done, Ordering::Relaxed);
+        self.generation.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    fn buffered(&self) -> Duration {
+        let samples = self.buffer.lock().map_or(0, |b| b.len());
+        Duration::from_secs_f64(samples as f64 / self.channels as f64 / self.sample_rate as f64)
+    }
+}
+
+/// Converts decoded audio to the device's rate and channel count: channel
+/// up/down-mix plus linear-interpolation resampling. Keeps the last frame
+/// across chunks so chunk boundaries don't click.
+pub struct Converter {
+    in_channels: usize,
+    out_channels: usize,
+    /// Input frames per output frame.
+    step: f64,
+    /// Read position relative to `prev` (0.0 = `prev`, 1.0 = first new frame).
+    pos: f64,
+    prev: Vec<f32>,
+    mixed: Vec<f32>,
+}
+
+impl Converter {
+    pub fn new(in_rate: u32, in_channels: usize, out_rate: u32, out_channels: usize) -> Self {
+        Self {
+            in_channels: in_channels.max(1),
+            out_channels: out_channels.max(1),
+            step: in_rate.max(1) as f64 / out_rate.max(1) as f64,
+            pos: 1.0,
+            prev: vec![0.0; out_channels.max(1)],
+            mixed: Vec::new(),
+        }
+    }
+
+    pub fn process(&mut self, input: &[f32], out: &mut VecDeque<f32>) {
+        let (ic, oc) = (self.in_channels, self.out_channels);
+        self.mixed.clear();
+        for frame in input.chunks_exact(ic) {
+            if ic == oc {
+                self.mixed.extend_from_slice(frame);
+            } else if oc == 1 {
+                self.mixed.push(frame.iter().sum::<f32>() / ic as f32);
+            } else if ic == 1 {
+                self.mixed.extend(std::iter::repeat_n(frame[0], oc));
+            } else {
+                self.mixed.extend((0..oc).map(|c| frame[c % ic]));
+            }
+        }
+        let frames = self.mixed.len() / oc;
+        if frames == 0 {
+            return;
+        }
+        if self.step == 1.0 {
+            out.extend(self.mixed.iter().copied());
+            return;
+        }
+
+        while self.pos < frames as f64 {
+            let i = self.pos.floor() as usize;
+            let t = (self.pos - i as f64) as f32;
+            let next = &self.mixed[i * oc..(i + 1) * oc];
+            for c in 0..oc {
+                let a = if i == 0 {
+                    self.prev[c]
+                } else {
+                    self.mixed[(i - 1) * oc + c]
+                };
+                out.push_back(a + (next[c] - a) * t);
+            }
+            self.pos += self.step;
+        }
+        self.pos -= frames as f64;
+        self.prev.copy_from_slice(&self.mixed[(frames - 1) * oc..]);
+    }
+}
+
+enum Command {
+    Load { path: PathBuf, generation: u64 },
+    Seek { pos: Duration, generation: u64 },
+    Stop,
+}
+
+/// Reports from the decode thread.
+#[derive(Debug)]
+pub enum EngineEvent {
+    Loaded { duration: Option<Duration> },
+    Failed(String),
+}
+
+/// Handle to the decode thread and the audio device it owns.
+pub struct Engine {
+    commands: Sender<Command>,
+    events: Receiver<EngineEvent>,
+    shared: Arc<Shared>,
+}
+
+impl Engine {
+    /// Open the default output device and start the decode thread.
+    pub fn spawn() -> anyhow::Result<Self> {
+        let (cmd_tx, cmd_rx) = mpsc::channel();
+        let (event_tx, event_rx) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        thread::Builder::new().name("dora-player".into()).spawn(move || {
+            // The output stream isn't `Send`, so it lives and dies here.
+            let (_output, shared) = match output::open() {
+                Ok(opened) => opened,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready_tx.send(Ok(Arc::clone(&shared)));
+            run(&shared, &cmd_rx, &event_tx);
+        })?;
+        let shared = ready_rx.recv().map_err(|_| anyhow::anyhow!("audio thread exited"))??;
+        Ok(Self {
+            commands: cmd_tx,
+            events: event_rx,
+            shared,
+        })
+    }
+
+    pub fn load(&self, path: PathBuf) {
+        let generation = self.shared.reset(Duration::ZERO, false);
+        self.shared.set_paused(false);
+        let _ = self.commands.send(Command::Load { path, generation });
+    }
+
+    pub fn seek(&self, pos: Duration) {
+        let generation = self.shared.reset(pos, false);
+        let _ = self.commands.send(Command::Seek { pos, generation });
+    }
+
+    pub fn stop(&self) {
+        self.shared.reset(Duration::ZERO, true);
+        let _ = self.commands.send(Command::Stop);
+    }
+
+    pub fn shared(&self) -> &Shared {
+        &self.shared
+    }
+
+    pub fn try_event(&self) -> Option<EngineEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+/// The file the decode thread is working on.
+struct Track {
+    decoder: Decoder,
+    converter: Converter,
+    generation: u64,
+    /// Decoded to the end. Kept open so a seek can rewind it.
+    at_end: bool,
+}
+
+/// Decode thread body. Returns when the [`Engine`] is dropped.
+fn run(shared: &Shared, commands: &Receiver<Command>, events: &Sender<EngineEvent>) {
+    let mut current: Option<Track> = None;
+    loop {
+        let command = if current.as_ref().is_some_and(|t| !t.at_end) {
+            match commands.try_recv() {
+                Ok(c) => Some(c),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        } else {
+            match commands.recv() {
+                Ok(c) => Some(c),
+                Err(_) => return,
+            }
+        };
+
+        match command {
+            Some(Command::Load { path, generation }) => {
+                current = None;
+                match Decoder::open(&path) {
+                    Ok(decoder) => {
+                        let _ = events.send(EngineEvent::Loaded {
+                            duration: decoder.duration,
+                        });
+                        let converter = Converter::new(
+                            decoder.sample_rate,
+                            decoder.channels,
+                            shared.sample_rate,
+                            shared.channels,
+                        );
+                        current = Some(Track {
+                            decoder,
+                            converter,
+                            generation,
+                            at_end: false,
+                        });
+                    }
+                    Err(e) => {
+                        log::warn!("player: cannot open {}: {}", path.display(), e);
+                        let _ = events.send(EngineEvent::Failed(format!("Can't play {} — {}", file_name(&path), e)));
+                        mark_done(shared, generation);
+                    }
+                }
+                continue;
+            }
+            Some(Command::Seek { pos, generation }) => {
+                match current.as_mut() {
+                    Some(track) => {
+                        if let Err(e) = track.decoder.seek(pos) {
+                            log::warn!("player: seek to {:?} failed: {}", pos, e);
+                        }
+                        track.converter = Converter::new(
+                            track.decoder.sample_rate,
+                            track.decoder.channels,
+                            shared.sample_rate,
+                            shared.channels,
+                        );
+                        track.generation = generation;
+                        track.at_end = false;
+                    }
+                    // Stopped or failed to open: nothing will fill the buffer.
+                    None => mark_done(shared, generation),
+                }
+                continue;
+            }
+            Some(Command::Stop) => {
+                current = None;
+                continue;
+            }
+            None => {}
+        }
+
+        let Some(track) = current.as_mut().filter(|t| !t.at_end) else {
+            continue;
+        };
+        if shared.buffered() >= BUFFER_AHEAD {
+            thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+        match track.decoder.next_chunk() {
+            Ok(Some(samples)) => {
+                let mut buffer = shared.buffer.lock().unwrap_or_else(|e| e.into_inner());
+                if shared.generation.load(Ordering::Relaxed) == track.generation {
+                    track.converter.process(samples, &mut buffer);
+                }
+            }
+            Ok(None) => {
+                mark_done(shared, track.generation);
+                track.at_end = true;
+            }
+            Err(e) => {
+                log::warn!("player: decode error: {}", e);
+                let _ = events.send(EngineEvent::Failed(format!("Playback stopped — {}", e)));
+                mark_done(shared, track.generation);
+                current = None;
+            }
+        }
+    }
+}
+
+fn mark_done(shared: &Shared, generation: u64) {
+    let _buffer = shared.buffer.lock();
+    if shared.generation.load(Ordering::Relaxed) == generation {
+        shared.decoding_done.store(true, Ordering::Relaxed);
+    }
+}
+
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_advances_position_and_pads_silence() {
+        let shared = Shared::new(1000, 2);
+        shared.reset(Duration::from_secs(10), false);
+        shared.buffer.lock().unwrap().extend([0.5f32; 600]);
+
+        let mut out = [1.0f32; 400];
+        shared.fill(&mut out);
+        assert!(out.iter().all(|s| *s == 0.5));
+        assert_eq!(shared.position(), Duration::from_millis(10_200));
+
+        shared.fill(&mut out);
+        assert!(out[..200].iter().all(|s| *s == 0.5));
+        assert!(out[200..].iter().all(|s| *s == 0.0));
+        assert_eq!(shared.position(), Duration::from_millis(10_300));
+        assert!(!shared.finished());
+
+        shared.decoding_done.store(true, Ordering::Relaxed);
+        assert!(shared.finished());
+    }
+
+    #[test]
+    fn paused_fill_plays_silence_and_keeps_position() {
+        let shared = Shared::new(1000, 1);
+        shared.reset(Duration::ZERO, false);
+        shared.buffer.lock().unwrap().extend([0.5f32; 100]);
+        shared.set_paused(true);
+
+        let mut out = [1.0f32; 50];
+        shared.fill(&mut out);
+        assert!(out.iter().all(|s| *s == 0.0));
+        assert_eq!(shared.position(), Duration::ZERO);
+        assert_eq!(shared.buffer.lock().unwrap().len(), 100);
+    }
+
+    #[test]
+    fn reset_invalidates_older_generation() {
+        let shared = Shared::new(1000, 1);
+        let first = shared.reset(Duration::ZERO, false);
+        let second = shared.reset(Duration::from_secs(5), false);
+        assert_ne!(first, second);
+        mark_done(&shared, first);
+        assert!(!shared.finished());
+        mark_done(&shared, second);
+        assert!(shared.finished());
+        assert_eq!(shared.position(), Duration::from_secs(5));
+    }
+
+    /// Drain `shared` like the output callback until the track finishes;
+    /// returns how many audible samples came out.
+    fn play_until_finished(shared: &Shared) -> usize {
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        let mut out = [0.0f32; 256];
+        let mut audible = 0;
+        while !shared.finished() {
+            assert!(std::time::Instant::now() < deadline, "playback never finished");
+            shared.fill(&mut out);
+            audible += out.iter().filter(|s| **s != 0.0).count();
+            thread::sleep(Duration::from_millis(1));
+        }
+        audible
+    }
+
+    #[test]
+    fn seek_after_end_of_stream_replays_and_finishes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.wav");
+        crate::player::decode::tests::write_test_wav(&path, 8000, 1, 1);
+
+        let shared = Arc::new(Shared::new(8000, 1));
+        let (cmd_tx, cmd_rx) = mpsc::channel();
+        let (event_tx, _event_rx) = mpsc::channel();
+        let thread_shared = Arc::clone(&shared);
+        let decode = thread::spawn(move || run(&thread_shared, &cmd_rx, &event_tx));
+
+        let generation = shared.reset(Duration::ZERO, false);
+        cmd_tx.send(Command::Load { path, generation }).unwrap();
+        assert!(play_until_finished(&shared) > 0);
+
+        // The decoder is at its end now; seeking back must decode again.
+        let generation = shared.reset(Duration::from_millis(500), false);
+        cmd_tx
+            .send(Command::Seek {
+                pos: Duration::from_millis(500),
+                generation,
+            })
+            .unwrap();
+        assert!(!shared.finished());
+        assert!(play_until_finished(&shared) > 0);
+
+        // With nothing loaded a seek has nothing to play, but still ends.
+        shared.reset(Duration::ZERO, true);
+        cmd_tx.send(Command::Stop).unwrap();
+        let generation = shared.reset(Duration::from_millis(50), false);
+        cmd_tx
+            .send(Command::Seek {
+                pos: Duration::from_millis(50),
+                generation,
+            })
+            .unwrap();
+        assert_eq!(play_until_finished(&shared), 0);
+
+        drop(cmd_tx);
+        decode.join().unwrap();
+    }
+
+    #[test]
+    fn converter_passes_through_matching_format() {
+        let mut conv = Converter::new(44_100, 2, 44_100, 2);
+        let mut out = VecDeque::new();
+        conv.process(&[0.1, 0.2, 0.3, 0.4], &mut out);
+        assert_eq!(out, [0.1, 0.2, 0.3, 0.4]);
+    }
+
+    #[test]
+    fn converter_remixes_channels() {
+        let mut out = VecDeque::new();
+        Converter::new(8000, 1, 8000, 2).process(&[0.5, -0.5], &mut out);
+        assert_eq!(out, [0.5, 0.5, -0.5, -0.5]);
+
+        out.clear();
+        Converter::new(8000, 2, 8000, 1).process(&[0.2, 0.4], &mut out);
+        assert!((out[0] - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn converter_resamples_across_chunks() {
+        // 2:1 downsampling keeps every other frame, regardless of chunking.
+        let mut conv = Converter::new(2000, 1, 1000, 1);
+        let mut out = VecDeque::new();
+        conv.process(&[1.0, 2.0, 3.0], &mut out);
+        conv.process(&[4.0, 5.0, 6.0, 7.0], &mut out);
+        assert_eq!(out, [1.0, 3.0, 5.0]);
+
+        // 1:2 upsampling interpolates between frames.
+        let mut conv = Converter::new(1000, 1, 2000, 1);
+        let mut out = VecDeque::new();
+        conv.process(&[0.0, 1.0], &mut out);
+        conv.process(&[2.0], &mut out);
+        assert_eq!(out, [0.0, 0.5, 1.0, 1.5]);
+    }
+}
@@ -0,0 +1,355 @@
+//! Embedded preview player: plays finished downloads inside the TUI.
+//!
+//! Decoding is pure Rust ([`decode`]), playback timing lives in
+//! [`engine::Shared`], and only [`output`] talks to the sound device — so
+//! everything but the device can be tested headless. The UI owns one
+//! [`Player`], calls the control methods from key handlers and
+//! [`Player::poll`] once per tick.
+
+pub mod decode;
+pub mod engine;
+mod output;
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use engine::{Engine, EngineEvent};
+
+/// Number of waveform peaks computed per track; the UI resamples to its width.
+const WAVEFORM_BUCKETS: usize = 240;
+/// How far the seek keys jump.
+pub const SEEK_STEP_SECS: i64 = 10;
+/// `prev` past this point restarts the current track instead.
+const RESTART_THRESHOLD: Duration = Duration::from_secs(3);
+
+/// One queued file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    pub path: PathBuf,
+    pub title: String,
+    pub artist: String,
+}
+
+impl Track {
+    /// "Artist — Title", or just the title.
+    pub fn display_name(&self) -> String {
+        if self.artist.is_empty() {
+            self.title.clone()
+        } else {
+            format!("{} — {}", self.artist, self.title)
+        }
+    }
+}
+
+/// What the UI should react to.
+#[derive(Debug)]
+pub enum PlayerEvent {
+    /// A track began playing (manually or by the queue advancing).
+    TrackStarted(Track),
+    Failed(String),
+}
+
+/// Play queue with a cursor. Kept separate from the engine so queue moves
+/// are testable.
+#[derive(Debug, Default)]
+struct Queue {
+    tracks: Vec<Track>,
+    index: usize,
+}
+
+impl Queue {
+    fn current(&self) -> Option<&Track> {
+        self.tracks.get(self.index)
+    }
+
+    /// Move to the next track; false at the end.
+    fn advance(&mut self) -> bool {
+        if self.index + 1 < self.tracks.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move to the previous track; false at the start.
+    fn back(&mut self) -> bool {
+        if self.index > 0 && !self.tracks.is_empty() {
+            self.index -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Player {
+    /// Started on first play, so dora never touches the sound device unless
+    /// asked to.
+    engine: Option<Engine>,
+    queue: Queue,
+    active: bool,
+    duration: Option<Duration>,
+    waveform: Vec<f32>,
+    waveform_rx: Option<Receiver<(PathBuf, Vec<f32>)>>,
+    events: VecDeque<PlayerEvent>,
+}
+
+impl Player {
+    /// Replace the queue with `tracks` and start playing `tracks[start]`.
+    pub fn play_queue(&mut self, tracks: Vec<Track>, start: usize) {
+        if tracks.is_empty() {
+            return;
+        }
+        if self.engine.is_none() {
+            match Engine::spawn() {
+                Ok(engine) => self.engine = Some(engine),
+                Err(e) => {
+                    log::warn!("player: no audio output: {}", e);
+                    self.events
+                        .push_back(PlayerEvent::Failed(format!("No audio output — {}", e)));
+                    return;
+                }
+            }
+        }
+        self.queue = Queue {
+            index: start.min(tracks.len() - 1),
+            tracks,
+        };
+        self.start_current();
+    }
+
+    fn start_current(&mut self) {
+        let (Some(engine), Some(track)) = (self.engine.as_ref(), self.queue.current().cloned()) else {
+            return;
+        };
+        engine.load(track.path.clone());
+        self.active = true;
+        self.duration = None;
+        self.waveform.clear();
+
+        let (tx, rx) = mpsc::channel();
+        let path = track.path.clone();
+        std::thread::spawn(move || match decode::waveform(&path, WAVEFORM_BUCKETS) {
+            Ok(peaks) => {
+                let _ = tx.send((path, peaks));
+            }
+            Err(e) => log::debug!("player: no waveform for {}: {}", path.display(), e),
+        });
+        self.waveform_rx = Some(rx);
+        self.events.push_back(PlayerEvent::TrackStarted(track));
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if let (true, Some(engine)) = (self.active, self.engine.as_ref()) {
+            let shared = engine.shared();
+            shared.set_paused(!shared.is_paused());
+        }
+    }
+
+    /// Jump `secs` forward (or back, when negative) within the track.
+    pub fn seek_by(&mut self, secs: i64) {
+        let pos = self.position();
+        let target = if secs < 0 {
+            pos.saturating_sub(Duration::from_secs(secs.unsigned_abs()))
+        } else {
+            pos + Duration::from_secs(secs as u64)
+        };
+        self.seek_to(target);
+    }
+
+    /// Jump to `fraction` (0.0–1.0) of the track, once its length is known.
+    pub fn seek_to_fraction(&mut self, fraction: f64) {
+        if let Some(d) = self.duration {
+            self.seek_to(d.mul_f64(fraction.clamp(0.0, 1.0)));
+        }
+    }
+
+    fn seek_to(&mut self, target: Duration) {
+        let (true, Some(engine)) = (self.active, self.engine.as_ref()) else {
+            return;
+        };
+        let target = match self.duration {
+            Some(d) => target.min(d.saturating_sub(Duration::from_millis(500))),
+            None => target,
+        };
+        engine.seek(target);
+    }
+
+    pub fn next(&mut self) {
+        if !self.active {
+            return;
+        }
+        if self.queue.advance() {
+            self.start_current();
+        } else {
+            self.stop();
+        }
+    }
+
+    /// Previous track, or back to the start if this one has been playing a
+    /// few seconds.
+    pub fn prev(&mut self) {
+        if !self.active {
+            return;
+        }
+        if self.position() > RESTART_THRESHOLD || !self.queue.back() {
+            self.seek_to(Duration::ZERO);
+        } else {
+            self.start_current();
+        }
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(engine) = self.engine.as_ref() {
+            engine.stop();
+        }
+        self.active = false;
+        self.queue = Queue::default();
+        self.duration = None;
+        self.waveform.clear();
+        self.waveform_rx = None;
+    }
+
+    /// Collect engine reports, advance the queue when a track ends and
+    /// return the next event for the UI. Call until it returns `None`.
+    pub fn poll(&mut self) -> Option<PlayerEvent> {
+        if let Some(engine) = self.engine.as_ref() {
+            while let Some(event) = engine.try_event() {
+                match event {
+                    EngineEvent::Loaded { duration } => self.duration = duration,
+                    EngineEvent::Failed(msg) => self.events.push_back(PlayerEvent::Failed(msg)),
+                }
+            }
+        }
+        if let Some(rx) = self.waveform_rx.as_ref()
+            && let Ok((path, peaks)) = rx.try_recv()
+        {
+            if self.queue.current().is_some_and(|t| t.path == path) {
+                self.waveform = peaks;
+            }
+            self.waveform_rx = None;
+        }
+        if self.active && self.engine.as_ref().is_some_and(|e| e.shared().finished()) {
+            self.next();
+        }
+        self.events.pop_front()
+    }
+
+    pub fn position(&self) -> Duration {
+        match (self.active, self.engine.as_ref()) {
+            (true, Some(engine)) => {
+                let pos = engine.shared().position();
+                self.duration.map_or(pos, |d| pos.min(d))
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// Played fraction of the current track (0.0 while the length is unknown).
+    pub fn progress(&self) -> f64 {
+        match self.duration {
+            Some(d) if !d.is_zero() => (self.position().as_secs_f64() / d.as_secs_f64()).min(1.0),
+            _ => 0.0,
+        }
+    }
+
+    pub fn current(&self) -> Option<&Track> {
+        if self.active { self.queue.current() } else { None }
+    }
+
+    /// 1-based position in the queue and its length.
+    pub fn queue_position(&self) -> (usize, usize) {
+        (self.queue.index + 1, self.queue.tracks.len())
+    }
+
+    /// Peaks of the current track; empty until computed.
+    pub fn waveform(&self) -> &[f32] {
+        &self.waveform
+    }
+
+    /// A track is loaded (playing or paused).
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.active && self.engine.as_ref().is_some_and(|e| e.shared().is_paused())
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.active && !self.is_paused()
+    }
+}
+
+/// `m:ss` for the now-playing bar.
+pub fn format_time(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(n: u32) -> Track {
+        Track {
+            path: PathBuf::from(format!("/tmp/{}.mp3", n)),
+            title: format!("Song {}", n),
+            artist: String::new(),
+        }
+    }
+
+    #[test]
+    fn queue_moves_within_bounds() {
+        let mut q = Queue {
+            tracks: vec![track(1), track(2), track(3)],
+            index: 1,
+        };
+        assert!(q.advance());
+        assert_eq!(q.current(), Some(&track(3)));
+        assert!(!q.advance());
+        assert_eq!(q.index, 2);
+        assert!(q.back());
+        assert!(q.back());
+        assert!(!q.back());
+        assert_eq!(q.current(), Some(&track(1)));
+
+        let mut empty = Queue::default();
+        assert!(!empty.advance());
+        assert!(!empty.back());
+        assert_eq!(empty.current(), None);
+    }
+
+    #[test]
+    fn idle_player_ignores_controls() {
+        let mut player = Player::default();
+        player.toggle_pause();
+        player.seek_by(10);
+        player.next();
+        player.prev();
+        assert!(!player.is_active());
+        assert!(!player.is_playing());
+        assert_eq!(player.position(), Duration::ZERO);
+        assert_eq!(player.current(), None);
+        assert!(player.poll().is_none());
+    }
+
+    #[test]
+    fn formats_times() {
+        assert_eq!(format_time(Duration::from_secs(5)), "0:05");
+        assert_eq!(format_time(Duration::from_secs(245)), "4:05");
+        assert_eq!(format_time(Duration::from_secs(3725)), "1:02:05");
+    }
+}
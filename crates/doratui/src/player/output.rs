@@ -0,0 +1,84 @@
+//! Sound device output (cpal), behind the `audio-output` feature.
+//!
+//! Without the feature [`open`] always fails, and the player reports that
+//! playback isn't available instead of playing.
+
+use std::sync::Arc;
+
+use super::engine::Shared;
+
+#[cfg(feature = "audio-output")]
+pub use imp::{Output, open};
+
+#[cfg(not(feature = "audio-output"))]
+pub struct Output;
+
+/// Open the default output device, feeding it from the returned [`Shared`].
+#[cfg(not(feature = "audio-output"))]
+pub fn open() -> anyhow::Result<(Output, Arc<Shared>)> {
+    anyhow::bail!("dora was built without audio output (rebuild with --features audio-output)")
+}
+
+#[cfg(feature = "audio-output")]
+mod imp {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use cpal::{SampleFormat, Stream, StreamError};
+
+    use super::{Arc, Shared};
+
+    /// Keeps the device stream running until dropped.
+    pub struct Output {
+        _stream: Stream,
+    }
+
+    /// Open the default output device, feeding it from the returned [`Shared`].
+    pub fn open() -> anyhow::Result<(Output, Arc<Shared>)> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| anyhow::anyhow!("no audio output device"))?;
+        let supported = device.default_output_config()?;
+        let config = supported.config();
+        let shared = Arc::new(Shared::new(config.sample_rate.0, config.channels as usize));
+        let on_error = |e: StreamError| log::warn!("player: output stream error: {}", e);
+
+        let source = Arc::clone(&shared);
+        let stream = match supported.sample_format() {
+            SampleFormat::F32 => {
+                device.build_output_stream(&config, move |data: &mut [f32], _| source.fill(data), on_error, None)?
+            }
+            SampleFormat::I16 => {
+                let mut scratch = Vec::new();
+                device.build_output_stream(
+                    &config,
+                    move |data: &mut [i16], _| {
+                        scratch.resize(data.len(), 0.0);
+                        source.fill(&mut scratch);
+                        for (out, s) in data.iter_mut().zip(&scratch) {
+                            *out = (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+                        }
+                    },
+                    on_error,
+                    None,
+                )?
+            }
+            SampleFormat::U16 => {
+                let mut scratch = Vec::new();
+                device.build_output_stream(
+                    &config,
+                    move |data: &mut [u16], _| {
+                        scratch.resize(data.len(), 0.0);
+                        source.fill(&mut scratch);
+                        for (out, s) in data.iter_mut().zip(&scratch) {
+                            *out = ((s.clamp(-1.0, 1.0) * 0.5 + 0.5) * u16::MAX as f32) as u16;
+                        }
+                    },
+                    on_error,
+                    None,
+                )?
+            }
+            other => anyhow::bail!("unsupported output sample format {:?}", other),
+        };
+        stream.play()?;
+        Ok((Output { _stream: stream }, shared))
+    }
+}
@@ -0,0 +1,208 @@
+//! Playlist and batch-URL import: the checklist shown before bulk enqueueing.
+//!
+//! A list comes from one of three places — a playlist / channel URL
+//! (expanded with doracore's `extract_playlist`), a multi-line paste, or a
+//! text file of URLs. All three end up as an [`ImportList`] the user ticks
+//! items in before they are queued.
+
+use std::path::Path;
+
+use anyhow::Context;
+use doracore::download::playlist::{extract_playlist, is_playlist_url};
+
+use crate::app::DownloadFormat;
+
+/// Rows shown per checklist page.
+pub const PAGE_SIZE: usize = 15;
+
+/// One candidate download in the checklist.
+#[derive(Debug, Clone)]
+pub struct ImportItem {
+    pub url: String,
+    /// Title from the playlist, or `None` for bare URLs.
+    pub title: Option<String>,
+    pub duration_secs: Option<u64>,
+    pub selected: bool,
+}
+
+/// A playlist or batch of URLs awaiting selection.
+#[derive(Debug, Clone)]
+pub struct ImportList {
+    /// Playlist title, or a description such as "3 pasted links".
+    pub title: String,
+    pub items: Vec<ImportItem>,
+    /// Entries in the source playlist; more than `items.len()` when doracore
+    /// capped the extraction.
+    pub total_count: usize,
+}
+
+/// Result type sent from the background import task.
+///
+/// Error is stringified at the boundary, as for `PreviewResult`.
+pub type ImportResult = Result<ImportList, String>;
+
+impl ImportList {
+    /// Build a checklist from plain URLs (paste or text file), all selected.
+    pub fn from_urls(title: String, urls: Vec<String>) -> Self {
+        let items: Vec<ImportItem> = urls
+            .into_iter()
+            .map(|url| ImportItem {
+                url,
+                title: None,
+                duration_secs: None,
+                selected: true,
+            })
+            .collect();
+        Self {
+            title,
+            total_count: items.len(),
+            items,
+        }
+    }
+
+    pub fn selected_count(&self) -> usize {
+        self.items.iter().filter(|i| i.selected).count()
+    }
+
+    /// Select everything, or nothing if everything is already selected.
+    pub fn toggle_all(&mut self) {
+        let select = self.selected_count() < self.items.len();
+        for item in &mut self.items {
+            item.selected = select;
+        }
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.items.len().div_ceil(PAGE_SIZE).max(1)
+    }
+
+    /// Estimated size of the selected items in MB, from their durations.
+    /// The second value counts selected items without a known duration.
+    pub fn estimated_mb(&self, format: DownloadFormat, audio_bitrate: &str, video_quality: &str) -> (f64, usize) {
+        let kbps = match format {
+            DownloadFormat::Mp3 => bitrate_kbps(audio_bitrate),
+            DownloadFormat::Mp4 => video_kbps(video_quality) + 128,
+        };
+        let mut unknown = 0;
+        let mut secs = 0u64;
+        for item in self.items.iter().filter(|i| i.selected) {
+            match item.duration_secs {
+                Some(d) => secs += d,
+                None => unknown += 1,
+            }
+        }
+        (secs as f64 * kbps as f64 / 8.0 / 1024.0, unknown)
+    }
+}
+
+/// True when `url` should open the checklist instead of the single-video preview.
+pub fn is_playlist(url: &str) -> bool {
+    url::Url::parse(url).is_ok_and(|u| is_playlist_url(&u))
+}
+
+/// Expand a playlist / channel URL into a checklist via yt-dlp `--flat-playlist`.
+pub async fn fetch_playlist(url: &str) -> anyhow::Result<ImportList> {
+    let parsed = url::Url::parse(url).context("Not a valid link")?;
+    let info = extract_playlist(&parsed).await?;
+    if info.entries.is_empty() {
+        anyhow::bail!("Playlist is empty or unavailable");
+    }
+    let items = info
+        .entries
+        .into_iter()
+        .map(|e| ImportItem {
+            url: e.url,
+            title: Some(e.title),
+            duration_secs: e.duration,
+            selected: true,
+        })
+        .collect();
+    Ok(ImportList {
+        title: info.title,
+        items,
+        total_count: info.entry_count,
+    })
+}
+
+/// Read a text file of URLs: one per line, blank lines and `#` comments skipped.
+pub fn read_url_file(path: &Path) -> anyhow::Result<Vec<String>> {
+    let content = fs_err::read_to_string(path)?;
+    let urls = parse_url_lines(&content);
+    if urls.is_empty() {
+        anyhow::bail!("No links found in {}", path.display());
+    }
+    Ok(urls)
+}
+
+/// Extract the `http(s)://` lines from pasted or file text, dropping duplicates.
+pub fn parse_url_lines(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('#') || !(line.starts_with("http://") || line.starts_with("https://")) {
+            continue;
+        }
+        if !urls.iter().any(|u| u == line) {
+            urls.push(line.to_string());
+        }
+    }
+    urls
+}
+
+fn bitrate_kbps(bitrate: &str) -> u64 {
+    bitrate.trim_end_matches('k').parse().unwrap_or(320)
+}
+
+/// Rough average bitrate of a YouTube-style H.264 stream per quality tier.
+fn video_kbps(quality: &str) -> u64 {
+    match quality {
+        "2160p" => 16_000,
+        "1440p" => 9_000,
+        "720p" => 2_500,
+        "480p" => 1_200,
+        "360p" => 700,
+        _ => 4_500, // 1080p / best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_url_lines_skips_comments_and_duplicates() {
+        let text = "# my list\nhttps://youtu.be/a\n\n  https://youtu.be/b  \nnot a link\nhttps://youtu.be/a\n";
+        assert_eq!(parse_url_lines(text), vec!["https://youtu.be/a", "https://youtu.be/b"]);
+    }
+
+    #[test]
+    fn toggle_all_selects_then_clears() {
+        let mut list = ImportList::from_urls("2 links".into(), vec!["https://a".into(), "https://b".into()]);
+        list.items[0].selected = false;
+        list.toggle_all();
+        assert_eq!(list.selected_count(), 2);
+        list.toggle_all();
+        assert_eq!(list.selected_count(), 0);
+    }
+
+    #[test]
+    fn estimate_uses_selected_durations() {
+        let mut list = ImportList::from_urls(
+            "3 links".into(),
+            vec!["https://a".into(), "https://b".into(), "https://c".into()],
+        );
+        list.items[0].duration_secs = Some(60);
+        list.items[1].duration_secs = Some(60);
+        list.items[1].selected = false;
+        let (mb, unknown) = list.estimated_mb(DownloadFormat::Mp3, "320k", "1080p");
+        // 60 s × 320 kbps = 2400 KB
+        assert!((mb - 2400.0 / 1024.0).abs() < 1e-9);
+        assert_eq!(unknown, 1);
+    }
+
+    #[test]
+    fn playlist_detection() {
+        assert!(is_playlist("https://www.youtube.com/playlist?list=PL123"));
+        assert!(!is_playlist("https://www.youtube.com/watch?v=abc"));
+        assert!(!is_playlist("not a url"));
+    }
+}
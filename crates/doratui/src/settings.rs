@@ -1,7 +1,10 @@
 //! Persistent settings for dora, stored in ~/.config/dora/settings.json.
 
 use std::path::PathBuf;
+use std::time::SystemTime;
 
+use chrono::{NaiveTime, TimeDelta};
+use doracore::download::filename_template::{DEFAULT_TEMPLATE, FilenameTemplate};
 use serde::{Deserialize, Serialize};
 
 use crate::theme::{CatppuccinFlavour, LogoScheme};
@@ -9,10 +12,12 @@ use crate::theme::{CatppuccinFlavour, LogoScheme};
 // ── Cycle-able option lists (shared with ui/settings.rs) ─────────────────────
 
 pub const AUDIO_BITRATES: &[&str] = &["320k", "256k", "192k", "128k"];
-pub const VIDEO_QUALITIES: &[&str] = &["1080p", "720p", "480p", "360p", "best"];
+pub const VIDEO_QUALITIES: &[&str] = &["2160p", "1440p", "1080p", "720p", "480p", "360p", "best"];
 pub const RATE_LIMITS: &[&str] = &["off", "2M", "5M", "10M"];
 pub const FORMATS: &[&str] = &["MP3", "MP4"];
 pub const THEME_FLAVOURS: &[&str] = &["Mocha", "Macchiato", "Frappe", "Latte"];
+pub const PARALLEL_LIMITS: &[&str] = &["1", "2", "3", "4", "6", "8"];
+pub const CHECK_INTERVALS: &[&str] = &["15", "30", "60", "180", "360", "720"];
 
 /// All user-configurable settings for the dora TUI.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,7 +31,8 @@ pub struct DoraSettings {
     pub audio_bitrate: String,
     /// Maximum video height for MP4 downloads.
     pub video_quality: String,
-    /// Download rate limit ("off" | "2M" | "5M" | "10M").
+    /// Global bandwidth cap ("off" | "2M" | "5M" | "10M"), shared evenly
+    /// between the parallel download slots.
     pub rate_limit: String,
     /// Path to a Netscape-format cookies file for yt-dlp.
     pub ytdlp_cookies: String,
@@ -57,6 +63,37 @@ pub struct DoraSettings {
     /// Genius API client access token.
     #[serde(default)]
     pub genius_token: String,
+
+    // ── Queue ────────────────────────────────────────────────────────────────
+    /// Queued downloads that may run at the same time.
+    #[serde(default = "default_max_parallel")]
+    pub max_parallel: usize,
+    /// Hours during which queued downloads may start ("HH:MM-HH:MM", may wrap
+    /// past midnight). Blank means any time.
+    #[serde(default)]
+    pub active_hours: String,
+
+    // ── Subscriptions ────────────────────────────────────────────────────────
+    /// Minutes between checks of each followed channel, playlist or feed.
+    #[serde(default = "default_subscription_check_mins")]
+    pub subscription_check_mins: u64,
+
+    // ── Library ──────────────────────────────────────────────────────────────
+    /// Output file name template; `/` makes folders under the output folder.
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+}
+
+fn default_max_parallel() -> usize {
+    3
+}
+
+fn default_subscription_check_mins() -> u64 {
+    60
+}
+
+fn default_filename_template() -> String {
+    DEFAULT_TEMPLATE.to_string()
 }
 
 impl Default for DoraSettings {
@@ -76,6 +113,10 @@ impl Default for DoraSettings {
             theme_flavour: CatppuccinFlavour::Mocha,
             logo_scheme: LogoScheme::default(),
             genius_token: String::new(),
+            max_parallel: default_max_parallel(),
+            active_hours: String::new(),
+            subscription_check_mins: default_subscription_check_mins(),
+            filename_template: default_filename_template(),
         }
     }
 }
@@ -91,6 +132,11 @@ impl DoraSettings {
         Self::default()
     }
 
+    /// Modification time of the settings file, for change polling.
+    pub fn file_mtime() -> Option<SystemTime> {
+        fs_err::metadata(config_path()).and_then(|m| m.modified()).ok()
+    }
+
     /// Save to `~/.config/dora/settings.json`.
     pub fn save(&self) -> anyhow::Result<()> {
         let path = config_path();
@@ -123,6 +169,23 @@ impl DoraSettings {
         }
     }
 
+    /// yt-dlp binary (None if blank — doracore then uses `YTDL_BIN`).
+    pub fn ytdlp_bin_opt(&self) -> Option<String> {
+        Some(self.ytdlp_bin.trim().to_string()).filter(|s| !s.is_empty())
+    }
+
+    /// Optional cookies file path for Instagram (None if blank).
+    pub fn instagram_cookies_opt(&self) -> Option<String> {
+        Some(self.instagram_cookies.trim())
+            .filter(|s| !s.is_empty())
+            .map(Self::expand_path)
+    }
+
+    /// Optional Instagram GraphQL doc_id (None if blank).
+    pub fn instagram_doc_id_opt(&self) -> Option<String> {
+        Some(self.instagram_doc_id.trim().to_string()).filter(|s| !s.is_empty())
+    }
+
     /// `--limit-rate` arg value, or None if rate_limit == "off".
     pub fn rate_limit_arg(&self) -> Option<&str> {
         if self.rate_limit == "off" {
@@ -131,9 +194,102 @@ impl DoraSettings {
             Some(&self.rate_limit)
         }
     }
+
+    /// Per-slot `--limit-rate` value: the global cap split evenly across
+    /// `max_parallel` slots, or None if rate_limit == "off".
+    pub fn slot_rate_limit(&self) -> Option<String> {
+        let cap = self.rate_limit_arg()?;
+        let slots = self.max_parallel.max(1) as u64;
+        let (num, unit) = cap.split_at(cap.len() - 1);
+        let kib = match (num.parse::<u64>(), unit) {
+            (Ok(n), "M") => n * 1024,
+            (Ok(n), "K") => n,
+            _ => return Some(cap.to_string()),
+        };
+        Some(format!("{}K", (kib / slots).max(1)))
+    }
+
+    /// Parsed `active_hours` as (start, end); None when blank or malformed.
+    pub fn active_window(&self) -> Option<(NaiveTime, NaiveTime)> {
+        parse_time_window(&self.active_hours)
+    }
+
+    /// Time between subscription checks (at least a minute).
+    pub fn subscription_interval(&self) -> TimeDelta {
+        TimeDelta::minutes(self.subscription_check_mins.max(1) as i64)
+    }
+
+    /// Parsed `filename_template`; the default when it does not parse.
+    pub fn filename_template(&self) -> FilenameTemplate {
+        FilenameTemplate::parse(&self.filename_template).unwrap_or_default()
+    }
+
+    /// Whether queued downloads may start at `now`.
+    pub fn in_active_window(&self, now: NaiveTime) -> bool {
+        match self.active_window() {
+            None => true,
+            Some((start, end)) if start <= end => start <= now && now < end,
+            // Window wraps past midnight, e.g. 23:00-07:00.
+            Some((start, end)) => now >= start || now < end,
+        }
+    }
 }
 
 fn config_path() -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
     PathBuf::from(home).join(".config").join("dora").join("settings.json")
 }
+
+/// Parse "HH:MM-HH:MM". Blank input is not a window (None).
+pub fn parse_time_window(text: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = text.trim().split_once('-')?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+    (start != end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn time_window_parsing() {
+        assert_eq!(parse_time_window("01:00-07:30"), Some((at(1, 0), at(7, 30))));
+        assert_eq!(parse_time_window(" 23:00 - 06:00 "), Some((at(23, 0), at(6, 0))));
+        assert_eq!(parse_time_window(""), None);
+        assert_eq!(parse_time_window("25:00-07:00"), None);
+        assert_eq!(parse_time_window("07:00-07:00"), None);
+    }
+
+    #[test]
+    fn active_window_wraps_midnight() {
+        let mut s = DoraSettings::default();
+        assert!(s.in_active_window(at(12, 0)));
+
+        s.active_hours = "23:00-07:00".into();
+        assert!(s.in_active_window(at(23, 30)));
+        assert!(s.in_active_window(at(3, 0)));
+        assert!(!s.in_active_window(at(7, 0)));
+        assert!(!s.in_active_window(at(12, 0)));
+
+        s.active_hours = "09:00-17:00".into();
+        assert!(s.in_active_window(at(9, 0)));
+        assert!(!s.in_active_window(at(17, 0)));
+    }
+
+    #[test]
+    fn bandwidth_cap_is_split_across_slots() {
+        let mut s = DoraSettings::default();
+        assert_eq!(s.slot_rate_limit(), None);
+
+        s.rate_limit = "5M".into();
+        s.max_parallel = 2;
+        assert_eq!(s.slot_rate_limit().as_deref(), Some("2560K"));
+        s.max_parallel = 1;
+        assert_eq!(s.slot_rate_limit().as_deref(), Some("5120K"));
+    }
+}
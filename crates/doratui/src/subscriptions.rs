@@ -0,0 +1,525 @@
+//! Followed YouTube channels, playlists and RSS / Atom feeds, stored in
+//! `~/.config/dora/subscriptions.json`.
+//!
+//! While the TUI or `dora daemon` runs, each subscription is checked every
+//! `subscription_check_mins`: channels and playlists are listed with
+//! `yt-dlp --flat-playlist`, feeds are fetched with conditional requests.
+//! Items not listed before are flagged new until the user queues or dismisses
+//! them; subscriptions with auto-download queue them straight away.
+//!
+//! The TUI and the daemon share the file, so every change goes through
+//! [`update`], which re-reads it first. Only one of them schedules checks: the
+//! daemon when it runs, otherwise the TUI.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local, TimeDelta};
+use doracore::download::feed::{self, FeedFetch, Validators};
+use doracore::download::playlist::extract_recent_entries;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use url::Url;
+
+use crate::app::DownloadFormat;
+
+/// Newest channel uploads listed per check.
+const CHANNEL_SCAN_LIMIT: usize = 15;
+/// Playlist additions can land anywhere, so more of the list is scanned.
+const PLAYLIST_SCAN_LIMIT: usize = 200;
+/// Feed items considered per check (feeds list newest first).
+const FEED_SCAN_LIMIT: usize = 30;
+/// Items kept per subscription for the tab.
+const MAX_ITEMS: usize = 50;
+/// Seen IDs kept per subscription.
+const SEEN_CAP: usize = 500;
+
+/// Last path segments that already name a channel tab.
+const CHANNEL_TABS: &[&str] = &["videos", "shorts", "streams", "podcasts", "releases"];
+
+/// How a subscription is listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SourceKind {
+    Channel,
+    Playlist,
+    Feed,
+}
+
+impl SourceKind {
+    /// YouTube channel pages and playlists are listed through yt-dlp; any
+    /// other http(s) link is treated as a feed. Single videos can't be followed.
+    pub fn detect(url: &Url) -> Option<Self> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+        let host = url.host_str()?.to_ascii_lowercase();
+        let host = host.strip_prefix("www.").or(host.strip_prefix("m.")).unwrap_or(&host);
+        if !matches!(host, "youtube.com" | "music.youtube.com" | "youtu.be") {
+            return Some(Self::Feed);
+        }
+        if url.query_pairs().any(|(k, _)| k == "list") {
+            return Some(Self::Playlist);
+        }
+        let path = url.path();
+        ["/@", "/channel/", "/c/", "/user/"]
+            .iter()
+            .any(|p| path.starts_with(p))
+            .then_some(Self::Channel)
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::Channel => "▶",
+            Self::Playlist => "☰",
+            Self::Feed => "📡",
+        }
+    }
+}
+
+/// One listed upload or episode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubItem {
+    /// Video ID or feed guid.
+    pub id: String,
+    pub title: String,
+    /// What gets downloaded: the video page, or a feed item's enclosure.
+    pub url: String,
+    #[serde(default)]
+    pub published: Option<String>,
+    /// Found by a check and not yet queued or dismissed.
+    #[serde(default)]
+    pub new: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub id: u64,
+    pub url: String,
+    pub kind: SourceKind,
+    /// Channel, playlist or feed title, filled in by the first check.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Format new items are downloaded in.
+    pub format: DownloadFormat,
+    /// Queue new items as soon as a check finds them.
+    #[serde(default)]
+    pub auto_download: bool,
+    /// Recent items, newest first.
+    #[serde(default)]
+    pub items: Vec<SubItem>,
+    #[serde(default)]
+    pub last_checked: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// IDs of everything listed so far, oldest first.
+    #[serde(default)]
+    seen: Vec<String>,
+    /// Set by the first successful check, which only records the backlog.
+    #[serde(default)]
+    synced: bool,
+    #[serde(default)]
+    validators: Validators,
+}
+
+impl Subscription {
+    /// Title once known, else the URL.
+    pub fn name(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.url)
+    }
+
+    pub fn new_count(&self) -> usize {
+        self.items.iter().filter(|i| i.new).count()
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>, interval: TimeDelta) -> bool {
+        self.last_checked.is_none_or(|t| now - t >= interval)
+    }
+
+    /// Clear the new flags, returning the items that had them.
+    pub fn take_new(&mut self) -> Vec<SubItem> {
+        self.items
+            .iter_mut()
+            .filter(|i| i.new)
+            .map(|i| {
+                i.new = false;
+                i.clone()
+            })
+            .collect()
+    }
+
+    /// URL handed to yt-dlp: channel pages are listed through their Videos tab.
+    fn listing_url(&self) -> String {
+        let url = self.url.trim_end_matches('/');
+        let last = url.rsplit('/').next().unwrap_or_default();
+        if self.kind == SourceKind::Channel && !CHANNEL_TABS.contains(&last) {
+            format!("{}/videos", url)
+        } else {
+            url.to_string()
+        }
+    }
+
+    /// Merge a successful check and return the items listed for the first
+    /// time. The first check only records what is already there, so following
+    /// a channel doesn't flood the queue with its back catalogue.
+    pub fn apply_check(&mut self, outcome: CheckOutcome, now: DateTime<Local>) -> Vec<SubItem> {
+        self.last_checked = Some(now);
+        self.last_error = None;
+        self.validators = outcome.validators;
+        if outcome.title.is_some() {
+            self.title = outcome.title;
+        }
+        let Some(listed) = outcome.items else {
+            return Vec::new(); // feed not modified
+        };
+
+        let mut known: HashSet<String> = self.seen.iter().cloned().collect();
+        let mut fresh = Vec::new();
+        for mut item in listed {
+            if known.insert(item.id.clone()) {
+                self.seen.push(item.id.clone());
+                item.new = self.synced;
+                fresh.push(item);
+            }
+        }
+        if self.seen.len() > SEEN_CAP {
+            self.seen.drain(..self.seen.len() - SEEN_CAP);
+        }
+        self.synced = true;
+
+        self.items.splice(0..0, fresh.iter().cloned());
+        self.items.truncate(MAX_ITEMS);
+        fresh.retain(|i| i.new);
+        fresh
+    }
+}
+
+// ── Persistence ───────────────────────────────────────────────────────────────
+
+fn store_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(home)
+        .join(".config")
+        .join("dora")
+        .join("subscriptions.json")
+}
+
+/// Load the subscriptions file; returns an empty list on error.
+pub fn load() -> Vec<Subscription> {
+    if let Ok(content) = fs_err::read_to_string(store_path())
+        && let Ok(subs) = serde_json::from_str::<Vec<Subscription>>(&content)
+    {
+        return subs;
+    }
+    Vec::new()
+}
+
+/// Overwrite the subscriptions file (best-effort; silently ignores errors).
+fn save(subs: &[Subscription]) {
+    let path = store_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs_err::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(subs) {
+        let _ = fs_err::write(path, json);
+    }
+}
+
+/// Re-read the file, apply `change`, write it back. Returns the new list and
+/// whatever `change` returned.
+pub fn update<R>(change: impl FnOnce(&mut Vec<Subscription>) -> R) -> (Vec<Subscription>, R) {
+    let mut subs = load();
+    let result = change(&mut subs);
+    save(&subs);
+    (subs, result)
+}
+
+/// Modification time of the subscriptions file, for change polling.
+pub fn file_mtime() -> Option<SystemTime> {
+    fs_err::metadata(store_path()).and_then(|m| m.modified()).ok()
+}
+
+/// Add a subscription for `input` (a URL or a bare YouTube `@handle`).
+pub fn follow(subs: &mut Vec<Subscription>, input: &str, format: DownloadFormat) -> Result<Subscription, String> {
+    let input = input.trim();
+    let input = match input.strip_prefix('@') {
+        Some(handle) => format!("https://www.youtube.com/@{}", handle),
+        None => input.to_string(),
+    };
+    let url = Url::parse(&input).map_err(|_| "Not a valid link".to_string())?;
+    let kind = SourceKind::detect(&url).ok_or_else(|| "Not a channel, playlist or feed link".to_string())?;
+    let url = url.to_string();
+    if subs.iter().any(|s| s.url == url) {
+        return Err("Already following this".to_string());
+    }
+    let sub = Subscription {
+        id: subs.iter().map(|s| s.id).max().unwrap_or(0) + 1,
+        url,
+        kind,
+        title: None,
+        format,
+        auto_download: false,
+        items: Vec::new(),
+        last_checked: None,
+        last_error: None,
+        seen: Vec::new(),
+        synced: false,
+        validators: Validators::default(),
+    };
+    subs.push(sub.clone());
+    Ok(sub)
+}
+
+// ── Checking ──────────────────────────────────────────────────────────────────
+
+/// What one check found.
+#[derive(Debug, Clone, Default)]
+pub struct CheckOutcome {
+    pub title: Option<String>,
+    /// Listed items, newest first; `None` when a feed was not modified.
+    pub items: Option<Vec<SubItem>>,
+    validators: Validators,
+}
+
+/// A finished check, sent back to the loop that started it.
+#[derive(Debug)]
+pub struct CheckReport {
+    pub id: u64,
+    pub result: Result<CheckOutcome, String>,
+}
+
+/// Result of recording a check, for toasts and auto-download.
+#[derive(Debug)]
+pub struct CheckSummary {
+    pub name: String,
+    /// Items seen for the first time.
+    pub fresh: usize,
+    /// Items to queue now (auto-download subscriptions only).
+    pub queue: Vec<SubItem>,
+    pub format: DownloadFormat,
+}
+
+async fn check(sub: Subscription) -> anyhow::Result<CheckOutcome> {
+    let listing = sub.listing_url();
+    if sub.kind == SourceKind::Feed {
+        return match feed::fetch_feed(&listing, &sub.validators).await? {
+            FeedFetch::NotModified => Ok(CheckOutcome {
+                validators: sub.validators,
+                ..Default::default()
+            }),
+            FeedFetch::Modified { body, validators } => {
+                let parsed = feed::parse_feed(&body)?;
+                let items = parsed
+                    .items
+                    .into_iter()
+                    .take(FEED_SCAN_LIMIT)
+                    .filter_map(|item| {
+                        let url = item.enclosure.map(|e| e.url).or(item.link)?;
+                        Some(SubItem {
+                            id: item.guid,
+                            title: item.title,
+                            url,
+                            published: item.published,
+                            new: false,
+                        })
+                    })
+                    .collect();
+                Ok(CheckOutcome {
+                    title: Some(parsed.title).filter(|t| !t.is_empty()),
+                    items: Some(items),
+                    validators,
+                })
+            }
+        };
+    }
+
+    let limit = match sub.kind {
+        SourceKind::Playlist => PLAYLIST_SCAN_LIMIT,
+        _ => CHANNEL_SCAN_LIMIT,
+    };
+    let info = extract_recent_entries(&Url::parse(&listing)?, limit).await?;
+    let mut items: Vec<SubItem> = info
+        .entries
+        .into_iter()
+        .map(|e| SubItem {
+            id: e.id.unwrap_or_else(|| e.url.clone()),
+            title: e.title,
+            url: e.url,
+            published: None,
+            new: false,
+        })
+        .collect();
+    let title = match sub.kind {
+        // The Videos tab is titled "<Channel> - Videos"; the uploader is cleaner.
+        SourceKind::Channel => info.uploader.or(Some(info.title)),
+        _ => {
+            items.reverse(); // playlists append at the end
+            Some(info.title)
+        }
+    };
+    Ok(CheckOutcome {
+        title,
+        items: Some(items),
+        validators: Validators::default(),
+    })
+}
+
+/// Write a finished check to the file. Returns the updated list and, unless
+/// the subscription was removed meanwhile or the check failed, a summary.
+pub fn record(report: CheckReport) -> (Vec<Subscription>, Option<CheckSummary>) {
+    let now = Local::now();
+    update(|subs| {
+        let sub = subs.iter_mut().find(|s| s.id == report.id)?;
+        match report.result {
+            Ok(outcome) => {
+                let fresh = sub.apply_check(outcome, now);
+                let queue = if sub.auto_download { sub.take_new() } else { Vec::new() };
+                Some(CheckSummary {
+                    name: sub.name().to_string(),
+                    fresh: fresh.len(),
+                    queue,
+                    format: sub.format,
+                })
+            }
+            Err(e) => {
+                log::warn!("subscription check failed for {}: {}", sub.url, e);
+                sub.last_checked = Some(now);
+                sub.last_error = Some(e);
+                None
+            }
+        }
+    })
+}
+
+/// Starts checks in the background and remembers which are still running.
+#[derive(Debug, Default)]
+pub struct Checker {
+    in_flight: HashSet<u64>,
+}
+
+impl Checker {
+    /// Check every subscription whose interval has elapsed.
+    pub fn start_due(&mut self, subs: &[Subscription], interval: TimeDelta, tx: &mpsc::Sender<CheckReport>) {
+        let now = Local::now();
+        for sub in subs.iter().filter(|s| s.is_due(now, interval)) {
+            self.start(sub, tx);
+        }
+    }
+
+    /// Check `sub` now unless a check is already running.
+    pub fn start(&mut self, sub: &Subscription, tx: &mpsc::Sender<CheckReport>) {
+        if !self.in_flight.insert(sub.id) {
+            return;
+        }
+        let (sub, tx) = (sub.clone(), tx.clone());
+        tokio::spawn(async move {
+            let id = sub.id;
+            let result = check(sub).await.map_err(|e| format!("{:#}", e));
+            let _ = tx.send(CheckReport { id, result }).await;
+        });
+    }
+
+    pub fn finish(&mut self, id: u64) {
+        self.in_flight.remove(&id);
+    }
+
+    pub fn is_checking(&self, id: u64) -> bool {
+        self.in_flight.contains(&id)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.in_flight.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str) -> SubItem {
+        SubItem {
+            id: id.into(),
+            title: format!("Video {}", id),
+            url: format!("https://youtu.be/{}", id),
+            published: None,
+            new: false,
+        }
+    }
+
+    fn listing(ids: &[&str]) -> CheckOutcome {
+        CheckOutcome {
+            title: Some("Channel".into()),
+            items: Some(ids.iter().map(|id| item(id)).collect()),
+            validators: Validators::default(),
+        }
+    }
+
+    fn subscription() -> Subscription {
+        let mut subs = Vec::new();
+        follow(&mut subs, "@somechannel", DownloadFormat::Mp3).unwrap()
+    }
+
+    #[test]
+    fn first_check_records_backlog_then_flags_new_items() {
+        let mut sub = subscription();
+        let now = Local::now();
+        assert!(sub.is_due(now, TimeDelta::minutes(60)));
+
+        assert!(sub.apply_check(listing(&["b", "a"]), now).is_empty());
+        assert_eq!(sub.name(), "Channel");
+        assert_eq!(sub.new_count(), 0);
+        assert!(!sub.is_due(now, TimeDelta::minutes(60)));
+
+        let fresh = sub.apply_check(listing(&["c", "b", "a"]), now);
+        assert_eq!(fresh.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(), ["c"]);
+        assert_eq!(
+            sub.items.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(),
+            ["c", "b", "a"]
+        );
+        assert_eq!(sub.new_count(), 1);
+
+        assert_eq!(sub.take_new().len(), 1);
+        assert_eq!(sub.new_count(), 0);
+        // Not modified: nothing changes.
+        assert!(
+            sub.apply_check(
+                CheckOutcome {
+                    items: None,
+                    ..Default::default()
+                },
+                now
+            )
+            .is_empty()
+        );
+        assert_eq!(sub.items.len(), 3);
+    }
+
+    #[test]
+    fn detect_source_kind() {
+        let kind = |s: &str| SourceKind::detect(&Url::parse(s).unwrap());
+        assert_eq!(kind("https://www.youtube.com/@veritasium"), Some(SourceKind::Channel));
+        assert_eq!(
+            kind("https://youtube.com/channel/UCHnyfMqiRRG1u-2MsSQLbXA/videos"),
+            Some(SourceKind::Channel)
+        );
+        assert_eq!(
+            kind("https://www.youtube.com/playlist?list=PL123"),
+            Some(SourceKind::Playlist)
+        );
+        assert_eq!(kind("https://www.youtube.com/watch?v=dQw4w9WgXcQ"), None);
+        assert_eq!(kind("https://feeds.example.com/podcast.xml"), Some(SourceKind::Feed));
+        assert_eq!(kind("ftp://example.com/feed"), None);
+    }
+
+    #[test]
+    fn follow_rejects_duplicates_and_lists_channel_videos_tab() {
+        let mut subs = Vec::new();
+        let sub = follow(&mut subs, "https://www.youtube.com/@veritasium/", DownloadFormat::Mp4).unwrap();
+        assert_eq!(sub.listing_url(), "https://www.youtube.com/@veritasium/videos");
+        assert!(follow(&mut subs, "https://www.youtube.com/@veritasium/", DownloadFormat::Mp4).is_err());
+        assert!(follow(&mut subs, "not a url", DownloadFormat::Mp4).is_err());
+        let second = follow(&mut subs, "https://example.com/feed.xml", DownloadFormat::Mp3).unwrap();
+        assert_eq!(second.id, sub.id + 1);
+        assert_eq!(second.listing_url(), "https://example.com/feed.xml");
+    }
+}
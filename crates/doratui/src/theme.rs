@@ -3,6 +3,11 @@
 //! The active theme is stored on `App::theme` and initialised from
 //! `settings.theme_flavour`. Use `app.theme.*` in all renderer code.
 //! The legacy constants remain for any code that hasn't been migrated yet.
+//!
+//! `~/.config/dora/theme.toml` can override individual colours on top of the
+//! active flavour; see [`PaletteOverrides`].
+
+use std::path::PathBuf;
 
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
@@ -145,6 +150,123 @@ pub fn palette(flavour: CatppuccinFlavour) -> ThemeColors {
     }
 }
 
+// ── User palette file ─────────────────────────────────────────────────────────
+
+/// Colour overrides from `~/.config/dora/theme.toml`, layered over the active
+/// flavour. `[colors]` applies to every flavour, a flavour table only while
+/// that flavour is active:
+///
+/// ```toml
+/// [colors]
+/// peach = "#ff9e64"
+///
+/// [latte]
+/// base = "#fdf6e3"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PaletteOverrides {
+    /// `(flavour, colour name, value)`; `None` = every flavour.
+    entries: Vec<(Option<CatppuccinFlavour>, String, Color)>,
+}
+
+impl PaletteOverrides {
+    pub fn path() -> PathBuf {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+        PathBuf::from(home).join(".config").join("dora").join("theme.toml")
+    }
+
+    /// Read [`Self::path`]. A missing file means no overrides; problems are
+    /// returned as warnings and the offending entries skipped.
+    pub fn load() -> (Self, Vec<String>) {
+        match fs_err::read_to_string(Self::path()) {
+            Ok(text) => Self::from_toml(&text),
+            Err(_) => (Self::default(), Vec::new()),
+        }
+    }
+
+    pub fn from_toml(text: &str) -> (Self, Vec<String>) {
+        let mut overrides = Self::default();
+        let mut warnings = Vec::new();
+        let table = match text.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(e) => return (overrides, vec![format!("theme.toml: {}", e.message())]),
+        };
+
+        for (section, value) in &table {
+            let flavour = match section.as_str() {
+                "colors" => None,
+                "mocha" => Some(CatppuccinFlavour::Mocha),
+                "macchiato" => Some(CatppuccinFlavour::Macchiato),
+                "frappe" => Some(CatppuccinFlavour::Frappe),
+                "latte" => Some(CatppuccinFlavour::Latte),
+                _ => {
+                    warnings.push(format!("theme.toml: unknown section [{}]", section));
+                    continue;
+                }
+            };
+            let Some(colors) = value.as_table() else {
+                warnings.push(format!("theme.toml: [{}] must be a table", section));
+                continue;
+            };
+            for (name, value) in colors {
+                let mut probe = palette(CatppuccinFlavour::Mocha);
+                if color_slot(&mut probe, name).is_none() {
+                    warnings.push(format!("theme.toml: unknown colour '{}'", name));
+                    continue;
+                }
+                match value.as_str().and_then(parse_hex) {
+                    Some(color) => overrides.entries.push((flavour, name.clone(), color)),
+                    None => warnings.push(format!("theme.toml: {}.{} must be \"#rrggbb\"", section, name)),
+                }
+            }
+        }
+        (overrides, warnings)
+    }
+
+    /// `palette(flavour)` with the overrides applied; flavour tables win over `[colors]`.
+    pub fn palette(&self, flavour: CatppuccinFlavour) -> ThemeColors {
+        let mut colors = palette(flavour);
+        for scope in [None, Some(flavour)] {
+            for (_, name, color) in self.entries.iter().filter(|(f, ..)| *f == scope) {
+                if let Some(slot) = color_slot(&mut colors, name) {
+                    *slot = *color;
+                }
+            }
+        }
+        colors
+    }
+}
+
+fn color_slot<'a>(colors: &'a mut ThemeColors, name: &str) -> Option<&'a mut Color> {
+    Some(match name {
+        "base" => &mut colors.base,
+        "crust" => &mut colors.crust,
+        "surface0" => &mut colors.surface0,
+        "surface1" => &mut colors.surface1,
+        "text" => &mut colors.text,
+        "subtext" => &mut colors.subtext,
+        "lavender" => &mut colors.lavender,
+        "mauve" => &mut colors.mauve,
+        "green" => &mut colors.green,
+        "red" => &mut colors.red,
+        "yellow" => &mut colors.yellow,
+        "blue" => &mut colors.blue,
+        "peach" => &mut colors.peach,
+        "teal" => &mut colors.teal,
+        _ => return None,
+    })
+}
+
+/// `"#rrggbb"` → `Color::Rgb`.
+fn parse_hex(s: &str) -> Option<Color> {
+    let hex = s.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
 // ── Logo colour scheme ─────────────────────────────────────────────────────────
 
 /// Logo colour scheme, cycled on logo click.
@@ -195,3 +317,62 @@ pub const BLUE: Color = Color::Rgb(137, 180, 250);
 pub const PEACH: Color = Color::Rgb(250, 179, 135);
 pub const MAUVE: Color = Color::Rgb(203, 166, 247);
 pub const TEAL: Color = Color::Rgb(148, 226, 213);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flavour_table_overrides_common_colours() {
+        let (overrides, warnings) = PaletteOverrides::from_toml(
+            r##"
+            [colors]
+            peach = "#ff9e64"
+            base = "#000000"
+
+            [latte]
+            base = "#FDF6E3"
+            "##,
+        );
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        let mocha = overrides.palette(CatppuccinFlavour::Mocha);
+        assert_eq!(mocha.peach, Color::Rgb(0xff, 0x9e, 0x64));
+        assert_eq!(mocha.base, Color::Rgb(0, 0, 0));
+        assert_eq!(mocha.text, palette(CatppuccinFlavour::Mocha).text);
+
+        let latte = overrides.palette(CatppuccinFlavour::Latte);
+        assert_eq!(latte.base, Color::Rgb(0xfd, 0xf6, 0xe3));
+        assert_eq!(latte.peach, Color::Rgb(0xff, 0x9e, 0x64));
+    }
+
+    #[test]
+    fn bad_entries_are_skipped_with_warnings() {
+        let (overrides, warnings) = PaletteOverrides::from_toml(
+            r##"
+            [colors]
+            peach = "orange"
+            pink = "#ffc0cb"
+            red = "#ff0000"
+
+            [solarized]
+            base = "#002b36"
+            "##,
+        );
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        let mocha = overrides.palette(CatppuccinFlavour::Mocha);
+        assert_eq!(mocha.red, Color::Rgb(255, 0, 0));
+        assert_eq!(mocha.peach, palette(CatppuccinFlavour::Mocha).peach);
+
+        let (_, warnings) = PaletteOverrides::from_toml("[colors");
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn parse_hex_rejects_malformed() {
+        assert_eq!(parse_hex("#1e1e2e"), Some(Color::Rgb(30, 30, 46)));
+        assert_eq!(parse_hex("1e1e2e"), None);
+        assert_eq!(parse_hex("#1e1e2"), None);
+        assert_eq!(parse_hex("#gggggg"), None);
+    }
+}
@@ -6,6 +6,7 @@ use ratatui::style::{Modifier, Style};
 use ratatui::widgets::{Block, BorderType, Borders, Cell, Paragraph, Row, Table};
 
 use crate::app::{App, ClickTarget};
+use crate::history_db::HistoryFilters;
 
 /// Render the History panel (right column of the Downloads tab).
 pub fn render_history(f: &mut Frame, area: Rect, app: &mut App) {
@@ -15,7 +16,9 @@ pub fn render_history(f: &mut Frame, area: Rect, app: &mut App) {
 
     // ── Filter bar (shown when search is active or filter is non-empty) ───────
     let mut top_offset: u16 = 0;
-    let show_filter_bar = app.history_search_mode || !app.history_filter.is_empty();
+    let facets = facet_summary(&app.history_facets);
+    let filtering = !app.history_filter.is_empty() || !facets.is_empty();
+    let show_filter_bar = app.history_search_mode || filtering;
 
     // Build block title
     let sort_label = app.history_sort.label();
@@ -24,7 +27,9 @@ pub fn render_history(f: &mut Frame, area: Rect, app: &mut App) {
     } else {
         String::new()
     };
-    let block_title = if !app.history_filter.is_empty() {
+    let block_title = if app.history_stats_visible {
+        " History Stats ".to_string()
+    } else if filtering {
         format!(
             " Download History  {}/{} matches  {} {}",
             filtered_indices.len(),
@@ -65,13 +70,18 @@ pub fn render_history(f: &mut Frame, area: Rect, app: &mut App) {
     // ── Render filter input bar inside inner area ─────────────────────────────
     if show_filter_bar {
         let cursor = if app.blink_on { "│" } else { " " };
+        let facets = if facets.is_empty() {
+            String::new()
+        } else {
+            format!("  ⚑ {}", facets)
+        };
         let filter_text = if app.history_search_mode {
             format!(
-                " 🔍 Filter: {}{}  [Enter] Lock  [Esc] Clear",
-                app.history_filter, cursor
+                " 🔍 Filter: {}{}{}  [Enter] Lock  [Esc] Clear",
+                app.history_filter, cursor, facets
             )
         } else {
-            format!(" 🔍 Filter: {}  [Esc] Clear", app.history_filter)
+            format!(" 🔍 Filter: {}{}  [Esc] Clear", app.history_filter, facets)
         };
         let filter_style = Style::default().fg(app.theme.yellow).add_modifier(Modifier::BOLD);
         let filter_area = Rect::new(inner.x, inner.y, inner.width, 1);
@@ -93,6 +103,21 @@ pub fn render_history(f: &mut Frame, area: Rect, app: &mut App) {
         inner.height.saturating_sub(top_offset),
     );
 
+    if app.history_stats_visible {
+        super::stats::render_stats(f, table_area, app);
+        let hint = " [i] Back to list  [f/w/o/z] Filter  [/] Search  [Esc] Close ";
+        f.render_widget(
+            Paragraph::new(hint).style(Style::default().fg(app.theme.subtext)),
+            Rect::new(
+                area.x + 1,
+                area.y + area.height.saturating_sub(1),
+                area.width.saturating_sub(2),
+                1,
+            ),
+        );
+        return;
+    }
+
     if filtered_indices.is_empty() {
         f.render_widget(
             Paragraph::new("\n  No entries match the filter.").style(Style::default().fg(app.theme.subtext)),
@@ -109,7 +134,7 @@ pub fn render_history(f: &mut Frame, area: Rect, app: &mut App) {
     // Scale proportionally to terminal width; clamp to reasonable min/max.
     let w = table_area.width;
     let sel_w: u16 = 2; // [x]
-    let fmt_w: u16 = 5;
+    let fmt_w: u16 = 9; // "FLAC fx ✂"
     let size_w: u16 = 9;
     let date_w: u16 = 12;
     let fixed = sel_w + fmt_w + size_w + date_w + 4 * 2; // 4 × column_spacing=2
@@ -154,7 +179,7 @@ pub fn render_history(f: &mut Frame, area: Rect, app: &mut App) {
                         app.theme.text
                     })),
                     Cell::from(entry.artist.clone()).style(Style::default().fg(app.theme.subtext)),
-                    Cell::from(entry.format.label()).style(Style::default().fg(app.theme.peach)),
+                    Cell::from(entry.options.label(entry.format)).style(Style::default().fg(app.theme.peach)),
                     Cell::from(format!("{:.1} MB", entry.size_mb)).style(Style::default().fg(app.theme.blue)),
                     Cell::from(when).style(Style::default().fg(app.theme.subtext)),
                 ])
@@ -201,10 +226,10 @@ pub fn render_history(f: &mut Frame, area: Rect, app: &mut App) {
     let hint_y = area.y + area.height.saturating_sub(1);
 
     // Left hint
-    let left_hint = if app.history_search_mode || !app.history_filter.is_empty() {
-        " [↑↓] Navigate  [/] Search  [Esc] Clear filter "
+    let left_hint = if app.history_search_mode || filtering {
+        " [↑↓] Navigate  [/] Search  [f/w/o/z] Filter  [i] Stats  [Esc] Clear filter "
     } else {
-        " [↑↓] Navigate  [r/Enter] Reveal  [/] Search  [s] Sort "
+        " [↑↓] Navigate  [r/Enter] Reveal  [/] Search  [s] Sort  [f/w/o/z] Filter  [i] Stats "
     };
     f.render_widget(
         Paragraph::new(left_hint).style(Style::default().fg(app.theme.subtext)),
@@ -225,3 +250,21 @@ pub fn render_history(f: &mut Frame, area: Rect, app: &mut App) {
         );
     }
 }
+
+/// "MP3 · 7 days · youtube" for the active facets; empty when none are set.
+fn facet_summary(filters: &HistoryFilters) -> String {
+    let mut parts = Vec::new();
+    if let Some(format) = filters.format {
+        parts.push(format.label().to_string());
+    }
+    if filters.period != Default::default() {
+        parts.push(filters.period.label().to_string());
+    }
+    if let Some(platform) = &filters.platform {
+        parts.push(platform.clone());
+    }
+    if filters.size != Default::default() {
+        parts.push(filters.size.label().to_string());
+    }
+    parts.join(" · ")
+}
@@ -0,0 +1,243 @@
+//! Playlist / batch import popup: paginated checklist with size estimate.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap};
+
+use crate::app::{App, ClickTarget, ImportState};
+use crate::keymap::Action;
+use crate::playlist_import::{ImportList, PAGE_SIZE};
+use crate::video_info::fmt_duration;
+
+use super::truncate;
+
+const SPINNER: &[&str] = &[
+    "\u{28fe}", "\u{28fd}", "\u{28fb}", "\u{287f}", "\u{28bf}", "\u{289f}", "\u{28af}", "\u{28f7}",
+];
+
+// ── Public entry point ────────────────────────────────────────────────────────
+
+pub fn render_import_popup(f: &mut Frame, area: Rect, app: &mut App) {
+    if area.width < 50 || area.height < 12 {
+        return;
+    }
+
+    let popup_w = 84_u16.min(area.width.saturating_sub(2));
+    let popup_h = (PAGE_SIZE as u16 + 7).min(area.height.saturating_sub(2));
+    let popup_x = area.x + (area.width.saturating_sub(popup_w)) / 2;
+    let popup_y = area.y + (area.height.saturating_sub(popup_h)) / 2;
+    let popup_area = Rect::new(popup_x, popup_y, popup_w, popup_h);
+
+    // Background blocker: clicking outside closes the checklist.
+    app.click_map.push((area, ClickTarget::ImportClose));
+
+    let title = match &app.import_state {
+        ImportState::Ready(list) => format!(" 📋 {} ", truncate(&list.title, popup_w as usize - 8)),
+        _ => " 📋 Import ".to_string(),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(app.theme.lavender))
+        .style(Style::default().bg(app.theme.base));
+
+    let inner = block.inner(popup_area);
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+
+    match app.import_state.clone() {
+        ImportState::Hidden => {}
+        ImportState::Loading { url } => render_loading(f, inner, &url, app),
+        ImportState::Ready(list) => render_checklist(f, inner, &list, app),
+        ImportState::Failed(msg) => render_failed(f, inner, &msg, app),
+    }
+}
+
+// ── Loading / failed ──────────────────────────────────────────────────────────
+
+fn render_loading(f: &mut Frame, area: Rect, url: &str, app: &App) {
+    let spinner = SPINNER[app.spinner_frame as usize % SPINNER.len()];
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {} Reading playlist…", spinner),
+            Style::default().fg(app.theme.lavender).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("  {}", truncate(url, area.width as usize - 4)),
+            Style::default().fg(app.theme.subtext),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("  [Esc] Cancel", Style::default().fg(app.theme.subtext))),
+    ];
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+fn render_failed(f: &mut Frame, area: Rect, msg: &str, app: &App) {
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  ✗ Could not read playlist",
+            Style::default().fg(app.theme.red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("  {}", msg),
+            Style::default().fg(app.theme.subtext),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("  [Esc] Close", Style::default().fg(app.theme.subtext))),
+    ];
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), area);
+}
+
+// ── Checklist ─────────────────────────────────────────────────────────────────
+
+fn render_checklist(f: &mut Frame, area: Rect, list: &ImportList, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // summary
+            Constraint::Min(1),    // items
+            Constraint::Length(1), // hint
+        ])
+        .split(area);
+
+    render_summary(f, chunks[0], list, app);
+
+    // Items on the cursor's page
+    let cursor = app.import_cursor.min(list.items.len().saturating_sub(1));
+    let page = cursor / PAGE_SIZE;
+    let first = page * PAGE_SIZE;
+    let rows_area = chunks[1];
+    let num_w = list.items.len().to_string().len();
+    let mut lines = Vec::new();
+    for (idx, item) in list.items.iter().enumerate().skip(first).take(PAGE_SIZE) {
+        let row_y = rows_area.y + (idx - first) as u16;
+        if row_y >= rows_area.y + rows_area.height {
+            break;
+        }
+        app.click_map.push((
+            Rect::new(rows_area.x, row_y, rows_area.width, 1),
+            ClickTarget::ImportToggleItem(idx),
+        ));
+
+        let is_cur = idx == cursor;
+        let check = if item.selected { "[x]" } else { "[ ]" };
+        let duration = item.duration_secs.map(fmt_duration).unwrap_or_default();
+        let name = item.title.as_deref().unwrap_or(&item.url);
+        let prefix = format!(
+            "{} {} {:>w$}. ",
+            if is_cur { "▶" } else { " " },
+            check,
+            idx + 1,
+            w = num_w
+        );
+        let name_w = (rows_area.width as usize).saturating_sub(prefix.chars().count() + duration.len() + 2);
+        let name = format!("{:<w$}", truncate(name, name_w), w = name_w);
+
+        let style = match (is_cur, item.selected) {
+            (true, _) => Style::default().fg(app.theme.lavender).add_modifier(Modifier::BOLD),
+            (false, true) => Style::default().fg(app.theme.text),
+            (false, false) => Style::default().fg(app.theme.subtext),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(prefix, style),
+            Span::styled(name, style),
+            Span::styled(format!(" {}", duration), Style::default().fg(app.theme.subtext)),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), rows_area);
+
+    render_hint_bar(f, chunks[2], list, app);
+}
+
+fn render_summary(f: &mut Frame, area: Rect, list: &ImportList, app: &App) {
+    let theme = &app.theme;
+    let selected = list.selected_count();
+    let (mb, unknown) = list.estimated_mb(
+        app.import_format,
+        &app.settings.audio_bitrate,
+        &app.settings.video_quality,
+    );
+    let size = if unknown == selected {
+        "size unknown".to_string()
+    } else if unknown > 0 {
+        format!("≈ {} (+{} unknown)", fmt_mb(mb), unknown)
+    } else {
+        format!("≈ {}", fmt_mb(mb))
+    };
+    let page = app.import_cursor / PAGE_SIZE + 1;
+
+    let mut spans = vec![
+        Span::styled(
+            format!(" {}/{} selected", selected, list.items.len()),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("  ·  {}", size), Style::default().fg(theme.blue)),
+        Span::styled(
+            format!("  ·  {}", app.import_format.label()),
+            Style::default().fg(theme.peach).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("  ·  page {}/{}", page, list.page_count()),
+            Style::default().fg(theme.subtext),
+        ),
+    ];
+    if list.total_count > list.items.len() {
+        spans.push(Span::styled(
+            format!("  ·  first {} of {}", list.items.len(), list.total_count),
+            Style::default().fg(theme.yellow),
+        ));
+    }
+    f.render_widget(
+        Paragraph::new(Line::from(spans)),
+        Rect::new(area.x, area.y, area.width, 1),
+    );
+}
+
+fn render_hint_bar(f: &mut Frame, area: Rect, list: &ImportList, app: &mut App) {
+    let k = |s: &str| {
+        Span::styled(
+            s.to_string(),
+            Style::default().fg(app.theme.peach).add_modifier(Modifier::BOLD),
+        )
+    };
+    let d = |s: &str| Span::styled(s.to_string(), Style::default().fg(app.theme.subtext));
+    let sep = || Span::raw("  ");
+
+    let key = |action: Action| k(&format!("[{}]", app.keymap.key_hint(action)));
+    let mut spans = vec![
+        Span::raw(" "),
+        key(Action::ImportToggle),
+        d(" Tick"),
+        sep(),
+        key(Action::ImportToggleAll),
+        d(" All"),
+        sep(),
+    ];
+    if list.page_count() > 1 {
+        spans.extend([k("[←→]"), d(" Page"), sep()]);
+    }
+    spans.extend([key(Action::ImportFormat), d(" MP3/MP4"), sep()]);
+    let enqueue_x = area.x + spans.iter().map(|s| s.width()).sum::<usize>() as u16;
+    let enqueue = format!(" Queue {}", list.selected_count());
+    let enqueue_w = ("[Enter]".len() + enqueue.len()) as u16;
+    spans.extend([k("[Enter]"), d(&enqueue), sep(), k("[Esc]"), d(" Cancel")]);
+
+    app.click_map
+        .push((Rect::new(enqueue_x, area.y, enqueue_w, 1), ClickTarget::ImportEnqueue));
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+fn fmt_mb(mb: f64) -> String {
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{:.0} MB", mb)
+    }
+}
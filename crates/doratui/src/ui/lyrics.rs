@@ -6,8 +6,12 @@ use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Wrap};
 
+use doracore::lyrics::synced::line_at;
+
 use crate::app::{App, ClickTarget};
 
+use super::truncate;
+
 const SPINNER: &[&str] = &[
     "\u{28fe}", "\u{28fd}", "\u{28fb}", "\u{287f}", "\u{28bf}", "\u{289f}", "\u{28af}", "\u{28f7}",
 ];
@@ -147,7 +151,45 @@ fn render_lyrics_content(f: &mut Frame, area: Rect, app: &mut App) {
 
             f.render_widget(Paragraph::new(header_lines), chunks[0]);
 
-            // 2. Render Scrollable Lyrics
+            // 2. Synced lyrics follow the player while it plays the track
+            // they were fetched for.
+            let following = !result.synced.is_empty()
+                && result
+                    .for_track
+                    .as_ref()
+                    .is_some_and(|path| app.player.current().is_some_and(|t| &t.path == path));
+            if following {
+                let current = line_at(&result.synced, app.player.position());
+                let height = chunks[1].height as usize;
+                // Keep the current line about a third of the way down.
+                let first = current.unwrap_or(0).saturating_sub(height / 3);
+                let lyrics_lines: Vec<Line> = result
+                    .synced
+                    .iter()
+                    .enumerate()
+                    .skip(first)
+                    .take(height)
+                    .map(|(i, line)| {
+                        let text = if line.text.is_empty() {
+                            "♪"
+                        } else {
+                            line.text.as_str()
+                        };
+                        let (marker, style) = match current {
+                            Some(c) if i == c => {
+                                ("▸", Style::default().fg(app.theme.peach).add_modifier(Modifier::BOLD))
+                            }
+                            Some(c) if i < c => (" ", Style::default().fg(app.theme.subtext)),
+                            _ => (" ", Style::default().fg(app.theme.text)),
+                        };
+                        Line::from(Span::styled(format!(" {} {}", marker, text), style))
+                    })
+                    .collect();
+                f.render_widget(Paragraph::new(lyrics_lines), chunks[1]);
+                return;
+            }
+
+            // 3. Render Scrollable Lyrics
             let mut lyrics_lines = Vec::new();
             for line in result.lyrics.lines() {
                 lyrics_lines.push(Line::from(format!("  {}", line)));
@@ -339,10 +381,3 @@ fn render_artist_songs(f: &mut Frame, area: Rect, app: &mut App) {
         Rect::new(inner.x, inner.y + inner.height.saturating_sub(1), inner.width, 1),
     );
 }
-
-fn truncate(s: &str, max_chars: usize) -> String {
-    if s.chars().count() <= max_chars {
-        return s.to_string();
-    }
-    s.chars().take(max_chars.saturating_sub(1)).collect::<String>() + "…"
-}
@@ -6,15 +6,20 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Tabs};
 
-use crate::app::{App, ClickTarget, HistoryEntry, Particle, SlotState, ToastKind, YtdlpStartup};
+use crate::app::{App, ClickTarget, HistoryEntry, Particle, QueuedDownload, SlotState, ToastKind, YtdlpStartup};
+use crate::keymap::{Action, Keymap};
 use crate::theme::ThemeColors;
 
 mod history;
+mod import;
 mod logo;
 mod lyrics;
+mod now_playing;
 pub mod preview;
 mod queue;
 pub mod settings;
+mod stats;
+mod subscriptions;
 
 /// Render the entire TUI for the current frame.
 pub fn render(f: &mut Frame, app: &mut App) {
@@ -30,6 +35,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
             Constraint::Length(8), // logo
             Constraint::Length(3), // tab bar
             Constraint::Min(1),    // main content
+            Constraint::Length(if app.player.is_active() { now_playing::HEIGHT } else { 0 }),
             Constraint::Length(1), // status bar
         ])
         .split(size);
@@ -41,14 +47,18 @@ pub fn render(f: &mut Frame, app: &mut App) {
         crate::app::Tab::Downloads => render_downloads_combined(f, vertical[2], app),
         crate::app::Tab::Lyrics => lyrics::render_lyrics(f, vertical[2], app),
         crate::app::Tab::Settings => settings::render_settings(f, vertical[2], app),
+        crate::app::Tab::Subscriptions => subscriptions::render_subscriptions(f, vertical[2], app),
     }
 
-    render_status_bar(f, vertical[3], app);
+    if app.player.is_active() {
+        now_playing::render_now_playing(f, vertical[3], app);
+    }
+    render_status_bar(f, vertical[4], app);
 
     // Overlays rendered last so they appear on top of everything.
     let theme = app.theme;
     if app.help_visible {
-        render_help_overlay(f, size, &theme);
+        render_help_overlay(f, size, &theme, &app.keymap);
     }
     if app.show_cookies_input {
         render_cookies_popup(f, size, app);
@@ -59,11 +69,17 @@ pub fn render(f: &mut Frame, app: &mut App) {
     if app.preview_state.is_visible() {
         preview::render_preview_popup(f, size, app);
     }
+    if app.import_state.is_visible() {
+        import::render_import_popup(f, size, app);
+    }
     if let Some(idx) = app.history_popup
         && let Some(entry) = app.history.iter().rev().nth(idx).cloned()
     {
         render_history_popup(f, size, &entry, app);
     }
+    if let Some(saved) = app.resume_prompt.clone() {
+        render_resume_prompt(f, size, &saved, app);
+    }
 
     // yt-dlp startup popups render on top of everything (highest z-order).
     let blue = app.theme.blue;
@@ -113,8 +129,17 @@ fn render_tabs(f: &mut Frame, area: Rect, app: &mut App) {
         crate::app::Tab::Downloads,
         crate::app::Tab::Lyrics,
         crate::app::Tab::Settings,
+        crate::app::Tab::Subscriptions,
     ];
 
+    // Unseen subscription items show as a badge on their tab.
+    let new_items = app.subscription_new_count();
+    let badge = |t: &crate::app::Tab| match t {
+        crate::app::Tab::Subscriptions if new_items > 0 => format!(" ●{}", new_items),
+        _ => String::new(),
+    };
+    let label_width = |t: &crate::app::Tab| (t.label().chars().count() + badge(t).chars().count()) as u16;
+
     let titles: Vec<Line> = tabs_list
         .iter()
         .map(|t| {
@@ -123,7 +148,13 @@ fn render_tabs(f: &mut Frame, area: Rect, app: &mut App) {
             } else {
                 Style::default().fg(app.theme.subtext)
             };
-            Line::from(Span::styled(t.label(), style))
+            Line::from(vec![
+                Span::styled(t.label(), style),
+                Span::styled(
+                    badge(t),
+                    Style::default().fg(app.theme.peach).add_modifier(Modifier::BOLD),
+                ),
+            ])
         })
         .collect();
 
@@ -135,10 +166,10 @@ fn render_tabs(f: &mut Frame, area: Rect, app: &mut App) {
 
     // Compute approximate tab-bar content width so we can center it.
     let n = tabs_list.len();
-    let label_chars: usize = tabs_list.iter().map(|t| t.label().chars().count()).sum();
-    let divider_chars = 5 * (n.saturating_sub(1));
-    let padding_chars = 2 * n;
-    let content_w = (label_chars + divider_chars + padding_chars) as u16;
+    let label_chars: u16 = tabs_list.iter().map(label_width).sum();
+    let divider_chars = 5 * (n.saturating_sub(1)) as u16;
+    let padding_chars = 2 * n as u16;
+    let content_w = label_chars + divider_chars + padding_chars;
 
     // Centre the tabs widget inside the area
     let centered_area = if content_w < area.width {
@@ -159,7 +190,7 @@ fn render_tabs(f: &mut Frame, area: Rect, app: &mut App) {
     {
         let mut x = centered_area.x;
         for (i, tab) in tabs_list.iter().enumerate() {
-            let label_w = tab.label().chars().count() as u16 + 2;
+            let label_w = label_width(tab) + 2;
             let click_rect = Rect::new(x, centered_area.y, label_w, centered_area.height);
             app.click_map.push((click_rect, ClickTarget::SwitchTab(*tab)));
             x += label_w;
@@ -214,11 +245,7 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
     let now = chrono::Local::now().format("%H:%M").to_string();
 
-    let active = app
-        .slots
-        .iter()
-        .filter(|s| matches!(s.state, SlotState::Downloading { .. }))
-        .count();
+    let active = app.slots.iter().filter(|s| s.state.is_active()).count();
     let pending = app
         .slots
         .iter()
@@ -227,12 +254,15 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
 
     // Append active theme flavour to version indicator
     let flavour_label = app.settings.theme_flavour.label();
-    let right_str = match (active, pending) {
+    let mut right_str = match (active, pending) {
         (0, 0) => format!(" {}  v{} [{}] ", now, VERSION, flavour_label),
         (a, 0) => format!(" ↓ {} downloading  {}  v{} [{}] ", a, now, VERSION, flavour_label),
         (0, p) => format!(" ⏳ {} pending  {}  v{} [{}] ", p, now, VERSION, flavour_label),
         (a, p) => format!(" ↓ {}  ⏳ {}  {}  v{} [{}] ", a, p, now, VERSION, flavour_label),
     };
+    if app.daemon_attached {
+        right_str.insert_str(0, " ⇄ daemon ");
+    }
     let right_width = right_str.chars().count() as u16;
 
     let chunks = Layout::default()
@@ -241,7 +271,7 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
         .split(area);
 
     // Left side: Bottom Keybar (htop-style)
-    let k = |key: &'static str, label: &'static str| {
+    let k = |key: &str, label: &'static str| {
         vec![
             Span::styled(
                 format!(" {} ", key),
@@ -254,13 +284,38 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
         ]
     };
 
+    let keys = &app.keymap;
+    let tabs = [
+        Action::TabDownloads,
+        Action::TabLyrics,
+        Action::TabSettings,
+        Action::TabSubscriptions,
+    ]
+    .map(|a| keys.key_hint(a))
+    .join("/");
     let mut hints = Vec::new();
-    hints.extend(k("1-3", "Tabs"));
+    hints.extend(k(&tabs, "Tabs"));
     hints.extend(k("Enter", "Preview"));
-    hints.extend(k("r", "Reveal"));
-    hints.extend(k("d", "Delete"));
-    hints.extend(k("T", "Theme"));
-    hints.extend(k("?", "Help"));
+    if app.player.is_active() {
+        let pause = if app.player.is_paused() { "Play" } else { "Pause" };
+        hints.extend(k(&keys.key_hint(Action::PlayPause), pause));
+        hints.extend(k(
+            &format!(
+                "{}{}",
+                keys.key_hint(Action::SeekBack),
+                keys.key_hint(Action::SeekForward)
+            ),
+            "Seek",
+        ));
+        hints.extend(k(&keys.key_hint(Action::StopPlayback), "Stop"));
+    } else if app.active_tab == crate::app::Tab::Downloads && !app.history.is_empty() {
+        hints.extend(k(&keys.key_hint(Action::PlayEntry), "Play"));
+        hints.extend(k(&keys.key_hint(Action::HistoryStats), "Stats"));
+    }
+    hints.extend(k(&keys.key_hint(Action::Reveal), "Reveal"));
+    hints.extend(k(&keys.key_hint(Action::RemoveSlot), "Delete"));
+    hints.extend(k(&keys.key_hint(Action::CycleTheme), "Theme"));
+    hints.extend(k(&keys.key_hint(Action::Help), "Help"));
     hints.extend(k("^C", "Quit"));
 
     f.render_widget(Paragraph::new(Line::from(hints)), chunks[0]);
@@ -403,89 +458,226 @@ fn render_reveal_popup(f: &mut Frame, area: Rect, path: &str, theme: &ThemeColor
     f.render_widget(Paragraph::new(text), inner);
 }
 
-fn render_help_overlay(f: &mut Frame, area: Rect, theme: &ThemeColors) {
-    if area.width < 44 || area.height < 14 {
-        return;
-    }
-
-    let popup_w = 60_u16.min(area.width.saturating_sub(4));
-    let popup_h = 25_u16.min(area.height.saturating_sub(4));
+fn render_resume_prompt(f: &mut Frame, area: Rect, saved: &[QueuedDownload], app: &mut App) {
+    const MAX_LISTED: usize = 5;
+    let theme = app.theme;
+    let listed = saved.len().min(MAX_LISTED) as u16;
+    let more = u16::from(saved.len() > MAX_LISTED);
+    let popup_w = 64_u16.min(area.width.saturating_sub(4));
+    let popup_h = (listed + more + 6).min(area.height.saturating_sub(4));
     let popup_x = area.x + (area.width.saturating_sub(popup_w)) / 2;
     let popup_y = area.y + (area.height.saturating_sub(popup_h)) / 2;
     let popup_area = Rect::new(popup_x, popup_y, popup_w, popup_h);
 
     let block = Block::default()
-        .title(" ? Keyboard Shortcuts ")
+        .title(" ⏯  Unfinished downloads ")
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(theme.lavender))
         .style(Style::default().bg(theme.base));
+    let inner = block.inner(popup_area);
+
+    let partial = saved.iter().filter(|q| q.output_path.is_some()).count();
+    let noun = if saved.len() == 1 { "download" } else { "downloads" };
+    let mut text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  Resume {} pending {}?", saved.len(), noun),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        )),
+    ];
+    let name_w = (inner.width as usize).saturating_sub(10);
+    for q in saved.iter().take(MAX_LISTED) {
+        let name = q.title.as_deref().unwrap_or(&q.url);
+        let name: String = name.chars().take(name_w).collect();
+        text.push(Line::from(vec![
+            Span::styled(format!("   {} ", q.format.label()), Style::default().fg(theme.peach)),
+            Span::styled(name, Style::default().fg(theme.subtext)),
+        ]));
+    }
+    if more > 0 {
+        text.push(Line::from(Span::styled(
+            format!("   … and {} more", saved.len() - MAX_LISTED),
+            Style::default().fg(theme.subtext),
+        )));
+    }
+    if partial > 0 {
+        text.push(Line::from(Span::styled(
+            format!("  {} partly downloaded — will continue where they stopped", partial),
+            Style::default().fg(theme.blue),
+        )));
+    } else {
+        text.push(Line::from(""));
+    }
 
     let k = |s: &'static str| Span::styled(s, Style::default().fg(theme.peach).add_modifier(Modifier::BOLD));
     let d = |s: &'static str| Span::styled(s, Style::default().fg(theme.text));
+    let hint_y = inner.y + text.len() as u16;
+    text.push(Line::from(vec![k("  [Y]"), d(" Resume    "), k("[N]"), d(" Discard")]));
+
+    // Modal: nothing underneath stays clickable while the prompt is up.
+    app.click_map.clear();
+    if hint_y < inner.y + inner.height {
+        app.click_map
+            .push((Rect::new(inner.x + 2, hint_y, 10, 1), ClickTarget::ResumeQueue(true)));
+        app.click_map
+            .push((Rect::new(inner.x + 16, hint_y, 11, 1), ClickTarget::ResumeQueue(false)));
+    }
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+    f.render_widget(Paragraph::new(text), inner);
+}
+
+fn render_help_overlay(f: &mut Frame, area: Rect, theme: &ThemeColors, keymap: &Keymap) {
+    if area.width < 44 || area.height < 14 {
+        return;
+    }
+
+    let key_style = Style::default().fg(theme.peach).add_modifier(Modifier::BOLD);
+    let row = |key: String, desc: &'static str| {
+        Line::from(vec![
+            Span::styled(format!("  {:<16}", key), key_style),
+            Span::styled(desc, Style::default().fg(theme.text)),
+        ])
+    };
+    // Fixed navigation keys vs. rebindable actions (labels from keys.toml).
+    let fixed = |key: &str, desc: &'static str| row(key.to_string(), desc);
+    let bound = |action: Action| row(keymap.label(action), action.description());
     let h = |s: &'static str| {
-        Span::styled(
+        Line::from(Span::styled(
             s,
             Style::default()
                 .fg(theme.lavender)
                 .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
-        )
+        ))
     };
-    let dim = |s: &'static str| Span::styled(s, Style::default().fg(theme.subtext));
-
-    let text: Vec<Line> = vec![
+    let dim = |s: String| Line::from(Span::styled(s, Style::default().fg(theme.subtext)));
+
+    let mut text: Vec<Line> = vec![Line::from(""), h("  Global")];
+    text.extend(
+        [
+            Action::TabDownloads,
+            Action::TabLyrics,
+            Action::TabSettings,
+            Action::TabSubscriptions,
+            Action::CycleTheme,
+            Action::Help,
+        ]
+        .map(bound),
+    );
+    text.extend([
+        fixed("Esc", "Close popup  /  clear active input"),
+        fixed("Ctrl+C", "Quit"),
         Line::from(""),
-        Line::from(h("  Global")),
-        Line::from(vec![
-            k("  1/2/3           "),
-            d("Switch tabs (Downloads / Lyrics / Settings)"),
-        ]),
-        Line::from(vec![
-            k("  T               "),
-            d("Cycle Catppuccin theme (Mocha/Macchiato/Frappe/Latte)"),
-        ]),
-        Line::from(vec![k("  Space           "), d("Toggle selection in History")]),
-        Line::from(vec![k("  ?               "), d("Open this help overlay")]),
-        Line::from(vec![k("  Esc             "), d("Close popup  /  clear active input")]),
-        Line::from(vec![k("  Ctrl+C          "), d("Quit")]),
+        h("  Downloads Tab"),
+        fixed("Enter", "Open preview → then confirm download"),
+    ]);
+    text.extend(
+        [
+            Action::Cookies,
+            Action::Reveal,
+            Action::RemoveSlot,
+            Action::HistorySearch,
+            Action::HistorySort,
+            Action::HistorySelect,
+            Action::HistoryFormat,
+            Action::HistoryPeriod,
+            Action::HistoryPlatform,
+            Action::HistorySize,
+            Action::HistoryStats,
+        ]
+        .map(bound),
+    );
+    text.extend([
+        fixed("↑ / ↓", "Scroll history (when URL bar is empty)"),
         Line::from(""),
-        Line::from(h("  Downloads Tab")),
-        Line::from(vec![k("  Enter           "), d("Open preview → then confirm download")]),
-        Line::from(vec![
-            k("  c               "),
-            d("Set cookies file (authenticated sites)"),
-        ]),
-        Line::from(vec![k("  r               "), d("Reveal most recent finished file")]),
-        Line::from(vec![k("  d               "), d("Remove last finished / failed slot")]),
+        h("  History Details"),
+    ]);
+    text.extend(
+        [
+            Action::EntryReveal,
+            Action::EntryOpenUrl,
+            Action::EntryDelete,
+            Action::EntryPlay,
+        ]
+        .map(bound),
+    );
+    text.extend([Line::from(""), h("  Player")]);
+    text.extend(
+        [
+            Action::PlayEntry,
+            Action::PlayPause,
+            Action::SeekBack,
+            Action::SeekForward,
+            Action::PrevTrack,
+            Action::NextTrack,
+            Action::StopPlayback,
+        ]
+        .map(bound),
+    );
+    text.push(fixed("click", "Seek on the waveform / progress bar"));
+    text.extend([
         Line::from(""),
-        Line::from(h("  Preview Popup")),
-        Line::from(vec![k("  ← →            "), d("Select quality (MP4)")]),
-        Line::from(vec![k("  Tab             "), d("Toggle MP3 / MP4 in preview")]),
-        Line::from(vec![k("  Enter           "), d("Start download with selected options")]),
-        Line::from(vec![k("  Esc             "), d("Cancel preview, restore URL")]),
+        h("  Preview Popup"),
+        fixed("← →", "Select quality (MP4)"),
+    ]);
+    text.extend([Action::PreviewFormat, Action::PreviewOptions, Action::PreviewSubtitles].map(bound));
+    text.extend([
+        fixed("Enter", "Start download with selected options"),
+        fixed("Esc", "Cancel preview, restore URL"),
         Line::from(""),
-        Line::from(vec![
-            k("  ↑ / ↓           "),
-            d("Scroll history (when URL bar is empty)"),
-        ]),
-        Line::from(vec![
-            k("  r / Enter       "),
-            d("Reveal selected history file in Finder"),
-        ]),
+        h("  Playlist / Batch Import"),
+        fixed("Enter / paste", "Playlist URL, several links or a .txt file"),
+    ]);
+    text.extend([Action::ImportToggle, Action::ImportToggleAll, Action::ImportFormat].map(bound));
+    text.extend([
+        fixed("← →", "Change page"),
+        fixed("Enter", "Queue ticked items"),
         Line::from(""),
-        Line::from(h("  Lyrics Tab")),
-        Line::from(vec![k("  Enter           "), d("Search for lyrics")]),
-        Line::from(vec![k("  ↑ / ↓           "), d("Scroll lyrics")]),
+        h("  Lyrics Tab"),
+        fixed("Enter", "Search for lyrics"),
+        fixed("↑ / ↓", "Scroll lyrics"),
         Line::from(""),
-        Line::from(h("  Settings Tab")),
-        Line::from(vec![k("  ↑ / ↓           "), d("Navigate settings items")]),
-        Line::from(vec![k("  ← / →           "), d("Cycle option values")]),
-        Line::from(vec![k("  Enter           "), d("Edit text field")]),
-        Line::from(vec![k("  o               "), d("Browse for file path")]),
-        Line::from(vec![k("  s               "), d("Save settings to disk")]),
+        h("  Settings Tab"),
+        fixed("↑ / ↓", "Navigate settings items"),
+        fixed("← / →", "Cycle option values"),
+        fixed("Enter", "Edit text field"),
+        fixed("o", "Browse for file path"),
+        fixed("s", "Save settings to disk"),
         Line::from(""),
-        Line::from(dim("                    Press any key to close")),
-    ];
+        h("  Subscriptions Tab"),
+        fixed("Enter", "Follow typed link  /  queue new items"),
+        fixed("↑ / ↓", "Select subscription"),
+    ]);
+    text.extend(
+        [
+            Action::SubFormat,
+            Action::SubAutoDownload,
+            Action::SubCheckNow,
+            Action::SubMarkSeen,
+            Action::SubUnfollow,
+        ]
+        .map(bound),
+    );
+    text.extend([
+        Line::from(""),
+        dim(format!("  Rebind keys in {}", Keymap::path().display())),
+        dim("                    Press any key to close".to_string()),
+    ]);
+
+    let popup_w = 60_u16.min(area.width.saturating_sub(4));
+    let popup_h = (text.len() as u16 + 2).min(area.height.saturating_sub(4));
+    let popup_x = area.x + (area.width.saturating_sub(popup_w)) / 2;
+    let popup_y = area.y + (area.height.saturating_sub(popup_h)) / 2;
+    let popup_area = Rect::new(popup_x, popup_y, popup_w, popup_h);
+
+    let block = Block::default()
+        .title(format!(" {} Keyboard Shortcuts ", keymap.label(Action::Help)))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.lavender))
+        .style(Style::default().bg(theme.base));
 
     let help = Paragraph::new(text).block(block);
     f.render_widget(Clear, popup_area);
@@ -654,7 +846,7 @@ fn render_history_details(f: &mut Frame, area: Rect, entry: &HistoryEntry, app:
         Line::from(vec![
             Span::styled("Format  ", Style::default().fg(app.theme.subtext)),
             Span::styled(
-                entry.format.label(),
+                entry.options.label(entry.format),
                 Style::default().fg(fmt_color).add_modifier(Modifier::BOLD),
             ),
         ]),
@@ -687,6 +879,12 @@ fn render_history_details(f: &mut Frame, area: Rect, entry: &HistoryEntry, app:
     btn_spans.push(k("[d] "));
     btn_spans.push(d("Delete"));
     btn_spans.push(sep());
+    btn_spans.push(Span::styled(
+        format!("[{}] ", app.keymap.key_hint(Action::EntryPlay)),
+        Style::default().fg(app.theme.peach).add_modifier(Modifier::BOLD),
+    ));
+    btn_spans.push(d("Play"));
+    btn_spans.push(sep());
     btn_spans.push(k("[Esc] "));
     btn_spans.push(d("Close"));
 
@@ -792,6 +990,18 @@ fn render_particles(f: &mut Frame, particles: &[Particle], bounds: Rect) {
     }
 }
 
+// ── Text helpers ──────────────────────────────────────────────────────────────
+
+/// Truncate to at most `max` chars, ending in `…` when cut.
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let cut: String = s.chars().take(max.saturating_sub(1)).collect();
+        format!("{}…", cut)
+    }
+}
+
 // ── Color helpers ─────────────────────────────────────────────────────────────
 
 /// Linearly interpolate between two Color::Rgb values.
@@ -0,0 +1,107 @@
+//! Now-playing bar of the preview player: track line, waveform and
+//! progress bar, shown above the status bar while a track is loaded.
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+
+use crate::app::{App, ClickTarget};
+use crate::player::decode::downsample_peaks;
+use crate::player::format_time;
+
+use super::truncate;
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Rows the bar needs.
+pub const HEIGHT: u16 = 3;
+
+pub fn render_now_playing(f: &mut Frame, area: Rect, app: &mut App) {
+    if area.height < HEIGHT || area.width < 20 {
+        return;
+    }
+    let Some(track) = app.player.current().cloned() else {
+        return;
+    };
+    let theme = app.theme;
+    let row = |i: u16| Rect::new(area.x, area.y + i, area.width, 1);
+
+    // ── Track line ──────────────────────────────────────────────────────────
+    let paused = app.player.is_paused();
+    let icon = if paused { " ⏸ " } else { " ▶ " };
+    let time = format!(
+        " {} / {} ",
+        format_time(app.player.position()),
+        app.player.duration().map_or_else(|| "–:––".to_string(), format_time)
+    );
+    let (pos, len) = app.player.queue_position();
+    let queue = if len > 1 {
+        format!("  {}/{}", pos, len)
+    } else {
+        String::new()
+    };
+    let name_w = (area.width as usize).saturating_sub(3 + time.chars().count() + queue.chars().count() + 1);
+    let name = truncate(&track.display_name(), name_w);
+    let pad = name_w.saturating_sub(name.chars().count());
+    let line = Line::from(vec![
+        Span::styled(
+            icon,
+            Style::default()
+                .fg(theme.base)
+                .bg(if paused { theme.yellow } else { theme.green })
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" "),
+        Span::styled(name, Style::default().fg(theme.text).add_modifier(Modifier::BOLD)),
+        Span::styled(queue, Style::default().fg(theme.subtext)),
+        Span::raw(" ".repeat(pad)),
+        Span::styled(time, Style::default().fg(theme.lavender)),
+    ]);
+    f.render_widget(Paragraph::new(line), row(0));
+    app.click_map
+        .push((Rect::new(area.x, area.y, 3, 1), ClickTarget::PlayerToggle));
+
+    // ── Waveform + progress bar ─────────────────────────────────────────────
+    let width = area.width.saturating_sub(2) as usize;
+    let played = (app.player.progress() * width as f64).round() as usize;
+    let peaks = downsample_peaks(app.player.waveform(), width);
+    let played_style = Style::default().fg(theme.peach);
+    let rest_style = Style::default().fg(theme.surface1);
+
+    let wave: Vec<Span> = (0..width)
+        .map(|i| {
+            let c = match peaks.get(i) {
+                Some(p) => BARS[((p * (BARS.len() - 1) as f32).round() as usize).min(BARS.len() - 1)],
+                None => '·',
+            };
+            Span::styled(c.to_string(), if i < played { played_style } else { rest_style })
+        })
+        .collect();
+    let wave_line = std::iter::once(Span::raw(" ")).chain(wave).collect::<Vec<_>>();
+    f.render_widget(Paragraph::new(Line::from(wave_line)), row(1));
+
+    let bar = Line::from(vec![
+        Span::raw(" "),
+        Span::styled("━".repeat(played.min(width)), Style::default().fg(theme.lavender)),
+        Span::styled(
+            if played < width { "●" } else { "" },
+            Style::default().fg(theme.lavender).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            "─".repeat(width.saturating_sub(played + 1)),
+            Style::default().fg(theme.surface0),
+        ),
+    ]);
+    f.render_widget(Paragraph::new(bar), row(2));
+
+    // Each column of the waveform / bar seeks to its share of the track.
+    for i in 0..width {
+        let permille = ((i as f64 + 0.5) / width as f64 * 1000.0) as u16;
+        app.click_map.push((
+            Rect::new(area.x + 1 + i as u16, area.y + 1, 1, 2),
+            ClickTarget::PlayerSeek(permille),
+        ));
+    }
+}
@@ -7,6 +7,8 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap};
 
 use crate::app::{App, ClickTarget, DownloadFormat, PreviewState};
+use crate::download_options::{DownloadOptions, OptionRow};
+use crate::keymap::Action;
 use crate::theme::ThemeColors;
 use crate::video_info::{THUMB_H, ThumbnailArt, VideoInfo, fmt_count, fmt_duration, fmt_size};
 
@@ -328,11 +330,15 @@ fn render_quality_row(
     app: &App,
     click_map: &mut Vec<(Rect, ClickTarget)>,
 ) {
-    // When subtitle menu is open, render it instead of quality row
+    // When subtitle or options menu is open, render it instead of quality row
     if app.preview_subs_menu {
         render_subs_menu(f, area, info, app, click_map);
         return;
     }
+    if app.preview_options_menu {
+        render_options_menu(f, area, app);
+        return;
+    }
 
     let fmt = app.preview_format;
     let cursor = app.preview_quality_cursor;
@@ -350,11 +356,14 @@ fn render_quality_row(
     ));
 
     if fmt == DownloadFormat::Mp3 {
+        let mut spans = vec![
+            fmt_span,
+            Span::styled("  [Tab] switch to MP4 →", Style::default().fg(app.theme.subtext)),
+        ];
+        let x = area.x + spans.iter().map(|s| s.width()).sum::<usize>() as u16;
+        push_options_button(&mut spans, x, area.y, app, click_map);
         f.render_widget(
-            Paragraph::new(Line::from(vec![
-                fmt_span,
-                Span::styled("  [Tab] switch to MP4 →", Style::default().fg(app.theme.subtext)),
-            ])),
+            Paragraph::new(Line::from(spans)),
             Rect::new(area.x, area.y, area.width, 1),
         );
         return;
@@ -400,6 +409,8 @@ fn render_quality_row(
         click_map.push((Rect::new(x, area.y, srt_w, 1), ClickTarget::PreviewToggleSubsMenu));
         spans.push(Span::styled(srt_label.to_string(), srt_style));
     }
+    let x = area.x + spans.iter().map(|s| s.width()).sum::<usize>() as u16;
+    push_options_button(&mut spans, x, area.y, app, click_map);
 
     f.render_widget(
         Paragraph::new(Line::from(spans)),
@@ -543,6 +554,107 @@ fn render_subs_menu(f: &mut Frame, area: Rect, info: &VideoInfo, app: &App, clic
     }
 }
 
+/// `[Opts]` button — shows the chosen options' tag once anything is set.
+fn push_options_button<'a>(
+    spans: &mut Vec<Span<'a>>,
+    x: u16,
+    y: u16,
+    app: &App,
+    click_map: &mut Vec<(Rect, ClickTarget)>,
+) {
+    let (label, style) = if app.preview_options == DownloadOptions::default() {
+        (" [Opts] ".to_string(), Style::default().fg(app.theme.subtext))
+    } else {
+        (
+            format!(" [{}] ", app.preview_options.label(app.preview_format)),
+            Style::default().fg(app.theme.green).add_modifier(Modifier::BOLD),
+        )
+    };
+    let w = Span::raw(label.as_str()).width() as u16;
+    click_map.push((Rect::new(x, y, w, 1), ClickTarget::PreviewToggleOptionsMenu));
+    spans.push(Span::styled(label, style));
+}
+
+// ── Download options menu ─────────────────────────────────────────────────────
+
+fn render_options_menu(f: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let rows = OptionRow::for_format(app.preview_format);
+    let cursor = app.preview_options_cursor.min(rows.len() - 1);
+    let row = rows[cursor];
+
+    if app.preview_options_editing {
+        // Cut time input mode
+        let mut spans = vec![
+            Span::styled(format!(" {}: ", row.label()), Style::default().fg(theme.lavender)),
+            Span::styled(
+                &app.preview_options_edit_buf,
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            ),
+        ];
+        if app.blink_on {
+            spans.push(Span::styled("▌", Style::default().fg(theme.lavender)));
+        }
+        spans.push(Span::styled(
+            "  m:ss or h:mm:ss, empty to clear  [Enter] Confirm  [Esc] Cancel",
+            Style::default().fg(theme.subtext),
+        ));
+        f.render_widget(
+            Paragraph::new(Line::from(spans)),
+            Rect::new(area.x, area.y, area.width, 1),
+        );
+        return;
+    }
+
+    // Line 1: selected option + summary of everything chosen
+    let spans = vec![
+        Span::styled(
+            format!(" Options {}/{}: ", cursor + 1, rows.len()),
+            Style::default().fg(theme.lavender),
+        ),
+        Span::styled(format!("{} ", row.label()), Style::default().fg(theme.text)),
+        Span::styled(
+            if row.is_text() {
+                format!("[{}]", app.preview_options.value(row))
+            } else {
+                format!("◀ {} ▶", app.preview_options.value(row))
+            },
+            Style::default().fg(theme.lavender).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("   → {}", app.preview_options.label(app.preview_format)),
+            Style::default().fg(theme.green),
+        ),
+    ];
+    f.render_widget(
+        Paragraph::new(Line::from(spans)),
+        Rect::new(area.x, area.y, area.width, 1),
+    );
+
+    // Line 2: hint bar for options menu
+    if area.height > 1 {
+        let k = |s: &str| {
+            Span::styled(
+                s.to_string(),
+                Style::default().fg(theme.peach).add_modifier(Modifier::BOLD),
+            )
+        };
+        let d = |s: &str| Span::styled(s.to_string(), Style::default().fg(theme.subtext));
+        let sep = || Span::raw("  ");
+        let mut hints = vec![k(" [↑↓]"), d(" Option"), sep()];
+        if row.is_text() {
+            hints.extend([k("[Enter]"), d(" Edit time"), sep()]);
+        } else {
+            hints.extend([k("[←→]"), d(" Change"), sep()]);
+        }
+        hints.extend([k("[r]"), d(" Reset"), sep(), k("[Esc]"), d(" Back")]);
+        f.render_widget(
+            Paragraph::new(Line::from(hints)),
+            Rect::new(area.x, area.y + 1, area.width, 1),
+        );
+    }
+}
+
 /// Build the quality option labels from the VideoInfo.
 pub fn quality_list(info: &VideoInfo) -> Vec<String> {
     let mut list: Vec<String> = info.available_heights.iter().map(|h| format!("{}p", h)).collect();
@@ -553,8 +665,8 @@ pub fn quality_list(info: &VideoInfo) -> Vec<String> {
 // ── Hint bar ──────────────────────────────────────────────────────────────────
 
 fn render_hint_bar(f: &mut Frame, area: Rect, app: &App, click_map: &mut Vec<(Rect, ClickTarget)>) {
-    // When subtitle menu is open, its own hint bar is rendered by render_subs_menu
-    if app.preview_subs_menu {
+    // When a sub-menu is open, its own hint bar is rendered by the menu
+    if app.preview_subs_menu || app.preview_options_menu {
         return;
     }
 
@@ -572,10 +684,12 @@ fn render_hint_bar(f: &mut Frame, area: Rect, app: &App, click_map: &mut Vec<(Re
     if mp4_hints {
         spans.extend([k(" [←→]"), d(" Quality"), sep()]);
     }
-    spans.extend([k("[Tab]"), d(" Toggle MP3/MP4"), sep()]);
+    let key = |action: Action| k(&format!("[{}]", app.keymap.key_hint(action)));
+    spans.extend([key(Action::PreviewFormat), d(" Toggle MP3/MP4"), sep()]);
     if mp4_hints {
-        spans.extend([k("[S]"), d(" Subs"), sep()]);
+        spans.extend([key(Action::PreviewSubtitles), d(" Subs"), sep()]);
     }
+    spans.extend([key(Action::PreviewOptions), d(" Options"), sep()]);
     spans.extend([k("[Enter]"), d(" Download"), sep(), k("[Esc]"), d(" Cancel")]);
 
     click_map.push((area, ClickTarget::PreviewDownload));
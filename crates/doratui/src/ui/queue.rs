@@ -8,6 +8,8 @@ use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
 
 use crate::app::{App, SlotState};
 
+use super::truncate;
+
 const SPINNER: &[&str] = &[
     "\u{28fe}", "\u{28fd}", "\u{28fb}", "\u{287f}", "\u{28bf}", "\u{289f}", "\u{28af}", "\u{28f7}",
 ];
@@ -35,11 +37,7 @@ pub fn render_queue(f: &mut Frame, area: Rect, app: &mut App) {
 }
 
 fn render_downloads(f: &mut Frame, area: Rect, app: &mut App) {
-    let active = app
-        .slots
-        .iter()
-        .filter(|s| matches!(s.state, SlotState::Downloading { .. }))
-        .count();
+    let active = app.slots.iter().filter(|s| s.state.is_active()).count();
     let total = app.slots.len();
 
     let title = if total == 0 {
@@ -82,6 +80,14 @@ fn render_downloads(f: &mut Frame, area: Rect, app: &mut App) {
     // while also mutating slot_screen_rects).
     let theme = app.theme;
 
+    // Start of the active-hours window when queued downloads are held back.
+    let now = chrono::Local::now().time();
+    let waiting_until = app
+        .settings
+        .active_window()
+        .filter(|_| !app.settings.in_active_window(now))
+        .map(|(start, _)| start.format("%H:%M").to_string());
+
     // Clear slot rects; they'll be repopulated during this render.
     app.slot_screen_rects.clear();
 
@@ -102,17 +108,31 @@ fn render_downloads(f: &mut Frame, area: Rect, app: &mut App) {
 
         match &slot.state {
             SlotState::Pending => {
-                f.render_widget(
-                    Paragraph::new(format!("  ⏳ [{}]  {}", slot.format.label(), truncated))
-                        .style(Style::default().fg(accent)),
-                    slot_area,
-                );
+                let mut lines = vec![Line::from(format!(
+                    "  ⏳ [{}]  {}",
+                    slot.options.label(slot.format),
+                    truncated
+                ))];
+                if let Some(ref start) = waiting_until
+                    && !slot.task_spawned
+                {
+                    lines.push(Line::from(Span::styled(
+                        format!("     waiting for active hours — starts at {}", start),
+                        Style::default().fg(theme.subtext),
+                    )));
+                }
+                f.render_widget(Paragraph::new(lines).style(Style::default().fg(accent)), slot_area);
             }
 
             SlotState::Fetching => {
                 f.render_widget(
-                    Paragraph::new(format!("  {} [{}]  {}", spinner, slot.format.label(), truncated))
-                        .style(Style::default().fg(accent).add_modifier(Modifier::BOLD)),
+                    Paragraph::new(format!(
+                        "  {} [{}]  {}",
+                        spinner,
+                        slot.options.label(slot.format),
+                        truncated
+                    ))
+                    .style(Style::default().fg(accent).add_modifier(Modifier::BOLD)),
                     slot_area,
                 );
             }
@@ -127,7 +147,7 @@ fn render_downloads(f: &mut Frame, area: Rect, app: &mut App) {
                     let label = format!(
                         "  {} [{}]  {}  {:3}%  {}",
                         speed_emoji(*speed_mbs),
-                        slot.format.label(),
+                        slot.options.label(slot.format),
                         truncated,
                         percent,
                         format_speed(*speed_mbs),
@@ -161,7 +181,7 @@ fn render_downloads(f: &mut Frame, area: Rect, app: &mut App) {
                     Paragraph::new(format!(
                         "  {} [{}]  {}",
                         speed_emoji(*speed_mbs),
-                        slot.format.label(),
+                        slot.options.label(slot.format),
                         truncated,
                     ))
                     .style(Style::default().fg(accent).add_modifier(Modifier::BOLD)),
@@ -185,6 +205,34 @@ fn render_downloads(f: &mut Frame, area: Rect, app: &mut App) {
                 }
             }
 
+            SlotState::Merging { position_secs } => {
+                f.render_widget(
+                    Paragraph::new(format!(
+                        "  {} [{}]  {}  merging {}",
+                        spinner,
+                        slot.options.label(slot.format),
+                        truncated,
+                        format_position(*position_secs),
+                    ))
+                    .style(Style::default().fg(accent).add_modifier(Modifier::BOLD)),
+                    slot_area,
+                );
+            }
+
+            SlotState::Postprocessing { label } => {
+                f.render_widget(
+                    Paragraph::new(format!(
+                        "  {} [{}]  {}  {}…",
+                        spinner,
+                        slot.options.label(slot.format),
+                        truncated,
+                        label
+                    ))
+                    .style(Style::default().fg(accent).add_modifier(Modifier::BOLD)),
+                    slot_area,
+                );
+            }
+
             SlotState::Celebrating { path, started } => {
                 // 1-second colour sweep: LAVENDER → GREEN
                 let progress = started.elapsed().as_secs_f32().clamp(0.0, 1.0);
@@ -195,8 +243,12 @@ fn render_downloads(f: &mut Frame, area: Rect, app: &mut App) {
                 };
                 let path_display = truncate(path, slot_area.width.saturating_sub(18) as usize);
                 f.render_widget(
-                    Paragraph::new(format!("  ✨ [{}]  {}    saved!", slot.format.label(), path_display,))
-                        .style(Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                    Paragraph::new(format!(
+                        "  ✨ [{}]  {}    saved!",
+                        slot.options.label(slot.format),
+                        path_display,
+                    ))
+                    .style(Style::default().fg(color).add_modifier(Modifier::BOLD)),
                     slot_area,
                 );
             }
@@ -206,7 +258,7 @@ fn render_downloads(f: &mut Frame, area: Rect, app: &mut App) {
                 f.render_widget(
                     Paragraph::new(format!(
                         "  ✅ [{}]  {}    [r] Reveal",
-                        slot.format.label(),
+                        slot.options.label(slot.format),
                         path_display,
                     ))
                     .style(Style::default().fg(theme.green)),
@@ -219,7 +271,7 @@ fn render_downloads(f: &mut Frame, area: Rect, app: &mut App) {
                 f.render_widget(
                     Paragraph::new(format!(
                         "  ✖ [{}]  {}  —  {}",
-                        slot.format.label(),
+                        slot.options.label(slot.format),
                         truncated,
                         reason_short,
                     ))
@@ -292,11 +344,7 @@ fn render_waveform(
 /// Render the 1-line stats footer at the bottom of the downloads panel.
 fn render_stats_footer(f: &mut Frame, area: Rect, app: &App) {
     // Count active downloads and aggregate speed.
-    let active_count = app
-        .slots
-        .iter()
-        .filter(|s| matches!(s.state, SlotState::Downloading { .. }))
-        .count();
+    let active_count = app.slots.iter().filter(|s| s.state.is_active()).count();
 
     let total_speed: f64 = app
         .slots
@@ -407,6 +455,12 @@ fn format_speed(speed: f64) -> String {
     format!("{:.1} MB/s", speed)
 }
 
+/// ffmpeg merge position as `m:ss`.
+fn format_position(secs: f32) -> String {
+    let total = secs.max(0.0) as u64;
+    format!("{}:{:02}", total / 60, total % 60)
+}
+
 /// Pick an accent colour for a slot based on its source domain.
 ///
 /// This gives each source a distinctive ambient tint so glancing at the queue
@@ -471,17 +525,3 @@ fn lerp_color(from: Color, to: Color, t: f32) -> Color {
     let b = (fb as f32 * (1.0 - t) + tb as f32 * t) as u8;
     Color::Rgb(r, g, b)
 }
-
-/// Truncate to `max_chars` Unicode scalar values, appending `…` if truncated.
-fn truncate(s: &str, max_chars: usize) -> String {
-    if max_chars == 0 {
-        return String::new();
-    }
-    let mut chars = s.chars();
-    let out: String = chars.by_ref().take(max_chars).collect();
-    if chars.next().is_some() {
-        format!("{}…", out)
-    } else {
-        out
-    }
-}
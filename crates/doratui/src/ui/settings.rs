@@ -1,5 +1,6 @@
 //! Settings tab renderer for the dora TUI.
 
+use doracore::download::filename_template::{DEFAULT_TEMPLATE, FilenameTemplate};
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
@@ -7,8 +8,11 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
 
 use crate::app::{App, ClickTarget};
-use crate::settings::{AUDIO_BITRATES, FORMATS, RATE_LIMITS, THEME_FLAVOURS, VIDEO_QUALITIES};
-use crate::theme::{CatppuccinFlavour, ThemeColors, palette};
+use crate::settings::{
+    AUDIO_BITRATES, CHECK_INTERVALS, FORMATS, PARALLEL_LIMITS, RATE_LIMITS, THEME_FLAVOURS, VIDEO_QUALITIES,
+    parse_time_window,
+};
+use crate::theme::{CatppuccinFlavour, ThemeColors};
 
 // ── Settings item descriptors ─────────────────────────────────────────────────
 
@@ -28,7 +32,7 @@ pub struct SettingsItem {
     pub choices: &'static [&'static str],
 }
 
-/// All 12 editable items (no section headers — those are rendered separately).
+/// All editable items (no section headers — those are rendered separately).
 pub const ITEMS: &[SettingsItem] = &[
     // ── yt-dlp (indices 0-5) ──────────────────────────────────────────────
     SettingsItem {
@@ -52,7 +56,7 @@ pub const ITEMS: &[SettingsItem] = &[
         choices: VIDEO_QUALITIES,
     },
     SettingsItem {
-        label: "Rate limit",
+        label: "Bandwidth cap",
         kind: ItemKind::Cycle,
         choices: RATE_LIMITS,
     },
@@ -100,6 +104,29 @@ pub const ITEMS: &[SettingsItem] = &[
         kind: ItemKind::Text,
         choices: &[],
     },
+    // ── Queue (indices 13-14) ─────────────────────────────────────────────
+    SettingsItem {
+        label: "Parallel downloads",
+        kind: ItemKind::Cycle,
+        choices: PARALLEL_LIMITS,
+    },
+    SettingsItem {
+        label: "Active hours",
+        kind: ItemKind::Text,
+        choices: &[],
+    },
+    // ── Subscriptions (index 15) ──────────────────────────────────────────
+    SettingsItem {
+        label: "Check every (min)",
+        kind: ItemKind::Cycle,
+        choices: CHECK_INTERVALS,
+    },
+    // ── Library (index 16) ────────────────────────────────────────────────
+    SettingsItem {
+        label: "Filename template",
+        kind: ItemKind::Text,
+        choices: &[],
+    },
 ];
 
 // ── Section layout ────────────────────────────────────────────────────────────
@@ -111,6 +138,9 @@ const SECTIONS: &[(&str, usize, usize)] = &[
     ("Conversion", 8, 3),
     ("Appearance", 11, 1),
     ("Lyrics", 12, 1),
+    ("Queue", 13, 2),
+    ("Subscriptions", 15, 1),
+    ("Library", 16, 1),
 ];
 
 // ── Public renderer ───────────────────────────────────────────────────────────
@@ -182,7 +212,9 @@ fn render_items(f: &mut Frame, area: Rect, app: &mut App) {
             } else if item.kind == ItemKind::Cycle {
                 format!("← {} →", value_str)
             } else {
-                let display = if value_str.is_empty() {
+                let display = if value_str.is_empty() && idx == 14 {
+                    "(any time)".to_string()
+                } else if value_str.is_empty() {
                     "(none)".to_string()
                 } else {
                     value_str.clone()
@@ -327,6 +359,10 @@ pub fn get_value(app: &App, idx: usize) -> String {
         10 => s.default_mp4_quality.clone(),
         11 => s.theme_flavour.label().to_string(),
         12 => s.genius_token.clone(),
+        13 => s.max_parallel.to_string(),
+        14 => s.active_hours.clone(),
+        15 => s.subscription_check_mins.to_string(),
+        16 => s.filename_template.clone(),
         _ => String::new(),
     }
 }
@@ -349,9 +385,33 @@ pub fn set_value(app: &mut App, idx: usize, value: String) {
         11 => {
             let flavour = CatppuccinFlavour::from_label(&value);
             app.settings.theme_flavour = flavour;
-            app.theme = palette(flavour);
+            app.apply_theme();
         }
         12 => app.settings.genius_token = value,
+        13 => {
+            if let Ok(n) = value.parse::<usize>() {
+                app.settings.max_parallel = n.max(1);
+            }
+        }
+        // Blank clears the window; malformed input keeps the old value.
+        14 => {
+            if value.trim().is_empty() || parse_time_window(&value).is_some() {
+                app.settings.active_hours = value.trim().to_string();
+            }
+        }
+        15 => {
+            if let Ok(n) = value.parse::<u64>() {
+                app.settings.subscription_check_mins = n.max(1);
+            }
+        }
+        // Blank restores the default; a template that does not parse keeps the old one.
+        16 => {
+            if value.trim().is_empty() {
+                app.settings.filename_template = DEFAULT_TEMPLATE.to_string();
+            } else if FilenameTemplate::parse(&value).is_ok() {
+                app.settings.filename_template = value.trim().to_string();
+            }
+        }
         _ => {}
     }
 }
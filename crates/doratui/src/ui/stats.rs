@@ -0,0 +1,131 @@
+//! History Stats panel: totals, downloads per day and top artists for the
+//! entries the history panel currently lists (search text and facets apply).
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Sparkline};
+
+use crate::app::App;
+
+use super::truncate;
+
+/// Width of the longest bar in the top-artists list.
+const ARTIST_BAR_W: usize = 20;
+
+pub fn render_stats(f: &mut Frame, area: Rect, app: &mut App) {
+    if area.height < 4 || area.width < 20 {
+        return;
+    }
+    let stats = app.history_stats();
+    let theme = app.theme;
+    let label = Style::default().fg(theme.lavender).add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(theme.subtext);
+    let mut y = area.y;
+    let bottom = area.y + area.height;
+    let line = |f: &mut Frame, y: &mut u16, content: Line| {
+        if *y < bottom {
+            f.render_widget(Paragraph::new(content), Rect::new(area.x, *y, area.width, 1));
+            *y += 1;
+        }
+    };
+
+    // ── Totals ──────────────────────────────────────────────────────────────
+    let recent: u64 = stats.per_day.iter().map(|(_, n)| n).sum();
+    line(
+        f,
+        &mut y,
+        Line::from(vec![
+            Span::styled(format!(" {} ", stats.count), label),
+            Span::styled("downloads  ·  ", dim),
+            Span::styled(fmt_mb(stats.total_mb), Style::default().fg(theme.blue)),
+            Span::styled(format!("  ·  {} in the last {} days", recent, stats.per_day.len()), dim),
+        ]),
+    );
+    if stats.count == 0 {
+        line(f, &mut y, Line::from(""));
+        line(f, &mut y, Line::styled("  No entries match the filter.", dim));
+        return;
+    }
+    line(f, &mut y, Line::from(""));
+
+    // ── Downloads per day ───────────────────────────────────────────────────
+    let peak = stats.per_day.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    line(
+        f,
+        &mut y,
+        Line::from(vec![
+            Span::styled(" Downloads per day", label),
+            Span::styled(format!("  (peak {})", peak), dim),
+        ]),
+    );
+    let chart_w = (area.width.saturating_sub(2) as usize).min(stats.per_day.len()) as u16;
+    let chart_h = (bottom.saturating_sub(y) / 3).clamp(1, 6);
+    if y + chart_h + 1 <= bottom {
+        // Newest days win when the panel is narrower than the series.
+        let skip = stats.per_day.len() - chart_w as usize;
+        let data: Vec<u64> = stats.per_day.iter().skip(skip).map(|(_, n)| *n).collect();
+        f.render_widget(
+            Sparkline::default()
+                .data(&data)
+                .max(peak.max(1))
+                .style(Style::default().fg(theme.peach)),
+            Rect::new(area.x + 1, y, chart_w, chart_h),
+        );
+        y += chart_h;
+        if let (Some((first, _)), Some((last, _))) = (stats.per_day.get(skip), stats.per_day.last()) {
+            let first = first.format("%d/%m").to_string();
+            let last = last.format("%d/%m").to_string();
+            let gap = (chart_w as usize).saturating_sub(first.len() + last.len());
+            line(
+                f,
+                &mut y,
+                Line::styled(format!(" {}{}{}", first, " ".repeat(gap), last), dim),
+            );
+        }
+    }
+    line(f, &mut y, Line::from(""));
+
+    // ── Top artists ─────────────────────────────────────────────────────────
+    if !stats.top_artists.is_empty() {
+        line(f, &mut y, Line::styled(" Top artists", label));
+        let top = stats.top_artists.first().map_or(1, |(_, n)| *n).max(1);
+        let name_w = (area.width as usize).saturating_sub(ARTIST_BAR_W + 10).clamp(8, 28);
+        for (artist, n) in &stats.top_artists {
+            let bar = (*n as usize * ARTIST_BAR_W).div_ceil(top as usize);
+            line(
+                f,
+                &mut y,
+                Line::from(vec![
+                    Span::styled(
+                        format!("  {:<w$} ", truncate(artist, name_w), w = name_w),
+                        Style::default().fg(theme.text),
+                    ),
+                    Span::styled("█".repeat(bar), Style::default().fg(theme.mauve)),
+                    Span::styled(format!(" {}", n), dim),
+                ]),
+            );
+        }
+        line(f, &mut y, Line::from(""));
+    }
+
+    // ── Platforms ───────────────────────────────────────────────────────────
+    let mut spans = vec![Span::styled(" Platforms  ", label)];
+    for (i, (platform, n)) in stats.platforms.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled("  ·  ", dim));
+        }
+        spans.push(Span::styled(platform.clone(), Style::default().fg(theme.teal)));
+        spans.push(Span::styled(format!(" {}", n), dim));
+    }
+    line(f, &mut y, Line::from(spans));
+}
+
+fn fmt_mb(mb: f64) -> String {
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{:.0} MB", mb)
+    }
+}
@@ -0,0 +1,280 @@
+//! Subscriptions tab: follow bar, followed sources on the left, the selected
+//! one's recent items on the right.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Wrap};
+
+use crate::app::{App, ClickTarget};
+use crate::keymap::Action;
+use crate::subscriptions::Subscription;
+
+use super::truncate;
+
+const SPINNER: &[&str] = &[
+    "\u{28fe}", "\u{28fd}", "\u{28fb}", "\u{287f}", "\u{28bf}", "\u{289f}", "\u{28af}", "\u{28f7}",
+];
+
+// ── Public entry point ────────────────────────────────────────────────────────
+
+pub fn render_subscriptions(f: &mut Frame, area: Rect, app: &mut App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // follow bar
+            Constraint::Min(1),    // list + items
+            Constraint::Length(1), // hint bar
+        ])
+        .split(area);
+
+    render_follow_bar(f, rows[0], app);
+
+    // Narrow terminals only get the list.
+    if rows[1].width < 80 {
+        render_list(f, rows[1], app);
+    } else {
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Min(0)])
+            .split(rows[1]);
+        render_list(f, cols[0], app);
+        render_items(f, cols[1], app);
+    }
+
+    render_hint_bar(f, rows[2], app);
+}
+
+// ── Follow bar ────────────────────────────────────────────────────────────────
+
+fn render_follow_bar(f: &mut Frame, area: Rect, app: &App) {
+    let cursor = if app.blink_on { "│" } else { " " };
+    let prompt = format!(
+        " Follow ❯ {}{}  [Enter] Follow as {}",
+        app.sub_input,
+        cursor,
+        crate::default_format(&app.settings).label()
+    );
+
+    let bar = Paragraph::new(prompt)
+        .block(
+            Block::default()
+                .title(" Channel, playlist or podcast feed ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(app.theme.lavender)),
+        )
+        .style(Style::default().fg(app.theme.text));
+
+    f.render_widget(bar, area);
+}
+
+// ── Subscription list ─────────────────────────────────────────────────────────
+
+fn render_list(f: &mut Frame, area: Rect, app: &mut App) {
+    let block = Block::default()
+        .title(format!(" 📡 Following ({}) ", app.subscriptions.len()))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(app.theme.surface0))
+        .style(Style::default().bg(app.theme.base));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if app.subscriptions.is_empty() {
+        let lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "  Nothing followed yet.",
+                Style::default().fg(app.theme.text).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                "  Paste a YouTube channel (or @handle), a playlist or a podcast RSS link above.",
+                Style::default().fg(app.theme.subtext),
+            )),
+        ];
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+        return;
+    }
+
+    let height = inner.height as usize;
+    let first = app.subs_cursor.saturating_sub(height.saturating_sub(1));
+    let spinner = SPINNER[app.spinner_frame as usize % SPINNER.len()];
+    let mut lines = Vec::new();
+    for (idx, sub) in app.subscriptions.iter().enumerate().skip(first).take(height) {
+        let row_y = inner.y + (idx - first) as u16;
+        app.click_map.push((
+            Rect::new(inner.x, row_y, inner.width, 1),
+            ClickTarget::SubscriptionSelect(idx),
+        ));
+
+        let is_cur = idx == app.subs_cursor;
+        let status = if app.subs_checker.is_checking(sub.id) {
+            Span::styled(format!(" {}", spinner), Style::default().fg(app.theme.lavender))
+        } else if sub.last_error.is_some() {
+            Span::styled(" ✗", Style::default().fg(app.theme.red))
+        } else {
+            Span::raw("")
+        };
+        let new_count = sub.new_count();
+        let badge = if new_count > 0 {
+            format!(" ●{}", new_count)
+        } else {
+            String::new()
+        };
+        let tail = format!(
+            " {}{}",
+            sub.format.label(),
+            if sub.auto_download { " auto" } else { "" }
+        );
+        let prefix = format!("{} {} ", if is_cur { "▶" } else { " " }, sub.kind.icon());
+        let name_w = (inner.width as usize)
+            .saturating_sub(prefix.chars().count() + tail.len() + badge.chars().count() + status.width() + 1);
+        let name = format!("{:<w$}", truncate(sub.name(), name_w), w = name_w);
+
+        let style = if is_cur {
+            Style::default().fg(app.theme.lavender).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.theme.text)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(prefix, style),
+            Span::styled(name, style),
+            Span::styled(badge, Style::default().fg(app.theme.peach).add_modifier(Modifier::BOLD)),
+            Span::styled(tail, Style::default().fg(app.theme.subtext)),
+            status,
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+// ── Items of the selected subscription ────────────────────────────────────────
+
+fn render_items(f: &mut Frame, area: Rect, app: &App) {
+    let Some(sub) = app.selected_subscription() else {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(app.theme.surface0))
+            .style(Style::default().bg(app.theme.base));
+        f.render_widget(block, area);
+        return;
+    };
+
+    let block = Block::default()
+        .title(format!(
+            " {} ",
+            truncate(sub.name(), area.width.saturating_sub(4) as usize)
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(app.theme.surface0))
+        .style(Style::default().bg(app.theme.base));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let width = inner.width as usize;
+    let dim = Style::default().fg(app.theme.subtext);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!(" {}", truncate(&sub.url, width.saturating_sub(2))),
+            dim,
+        )),
+        Line::from(Span::styled(format!(" {}", summary(sub)), dim)),
+    ];
+    if let Some(ref error) = sub.last_error {
+        lines.push(Line::from(Span::styled(
+            format!(" ✗ {}", truncate(error, width.saturating_sub(4))),
+            Style::default().fg(app.theme.red),
+        )));
+    }
+    lines.push(Line::from(""));
+
+    if sub.items.is_empty() {
+        let msg = if sub.last_checked.is_none() {
+            " Waiting for the first check…"
+        } else {
+            " No items found."
+        };
+        lines.push(Line::from(Span::styled(msg, dim)));
+    }
+    for item in &sub.items {
+        let (marker, style) = if item.new {
+            ("● ", Style::default().fg(app.theme.peach).add_modifier(Modifier::BOLD))
+        } else {
+            ("  ", Style::default().fg(app.theme.text))
+        };
+        let date = item
+            .published
+            .as_deref()
+            .map(|p| format!("  {}", truncate(p, 16)))
+            .unwrap_or_default();
+        let title_w = width.saturating_sub(3 + date.chars().count());
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {}", marker), style),
+            Span::styled(truncate(&item.title, title_w), style),
+            Span::styled(date, dim),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// "3 new · MP3 · auto-download · checked 14:05"
+fn summary(sub: &Subscription) -> String {
+    let mut parts = Vec::new();
+    let new_count = sub.new_count();
+    if new_count > 0 {
+        parts.push(format!("{} new", new_count));
+    }
+    parts.push(sub.format.label().to_string());
+    if sub.auto_download {
+        parts.push("auto-download".to_string());
+    }
+    parts.push(match sub.last_checked {
+        Some(t) => format!("checked {}", t.format("%H:%M")),
+        None => "not checked yet".to_string(),
+    });
+    parts.join(" · ")
+}
+
+// ── Hint bar ──────────────────────────────────────────────────────────────────
+
+fn render_hint_bar(f: &mut Frame, area: Rect, app: &App) {
+    let k = |s: &str| {
+        Span::styled(
+            s.to_string(),
+            Style::default().fg(app.theme.peach).add_modifier(Modifier::BOLD),
+        )
+    };
+    let d = |s: &'static str| Span::styled(s, Style::default().fg(app.theme.subtext));
+    let sep = || Span::raw("  ");
+    let key = |action: Action| k(&format!("[{}]", app.keymap.key_hint(action)));
+
+    let hints = Line::from(vec![
+        Span::raw(" "),
+        k("[↑↓]"),
+        d(" Select"),
+        sep(),
+        k("[Enter]"),
+        d(" Queue new"),
+        sep(),
+        key(Action::SubFormat),
+        d(" MP3/MP4"),
+        sep(),
+        key(Action::SubAutoDownload),
+        d(" Auto"),
+        sep(),
+        key(Action::SubCheckNow),
+        d(" Check now"),
+        sep(),
+        key(Action::SubMarkSeen),
+        d(" Mark seen"),
+        sep(),
+        key(Action::SubUnfollow),
+        d(" Unfollow"),
+    ]);
+    f.render_widget(Paragraph::new(hints), area);
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
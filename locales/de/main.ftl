@@ -209,6 +209,10 @@ bot_commands =
     .export = Verlauf exportieren
     .backup = DB-Backup erstellen (nur Admins)
     .plan = Abo-Informationen
+    .credits = Credits und Star-Pakete
+    .redeem = Promo- oder Geschenkcode einlösen
+    .gift = Ein Abo verschenken
+    .invite = Freunde einladen und Credits erhalten
     .users = Alle Nutzer auflisten (nur Admin)
     .setplan = Nutzerplan ändern (nur Admin)
     .subscriptions = Meine Inhalts-Abonnements
@@ -286,6 +290,9 @@ vault-disabled = Tresor deaktiviert\.
 vault-enabled = Tresor aktiviert\.
 vault-cache-hit = Sofort aus dem Tresor\-Cache
 
+download =
+    .hosted_link = 📦 «{$title}» ist zu groß für Telegram ({$size} MB), hier ist stattdessen ein Download-Link. Er ist {$hours} Std. gültig:
+
 progress =
     .starting = Download wird gestartet...
     .downloading = Herunterladen
@@ -231,6 +231,10 @@ bot_commands =
     .export = Export history
     .backup = Create DB backup (admins only)
     .plan = Subscription info
+    .credits = Credits balance and Star packs
+    .redeem = Redeem a promo or gift code
+    .gift = Gift a subscription to a friend
+    .invite = Invite friends and earn credits
     .users = List all users (admin only)
     .setplan = Change user plan (admin only)
     .transactions = View Stars transactions (admin only)
@@ -274,6 +278,69 @@ admin =
     .charge_recurring = \\(recurring\\)
     .charge_expires = • Expires: {$date}\n
 
+credits =
+    .title = 💎 Credits
+    .balance = Balance: {$balance} credits
+    .prices = Free plan prices:\n• High-res video (above 1080p): {$highres} credits ({$highres_free} free per day)\n• Stories render: {$stories} credits ({$stories_free} free per day)\n• Album split: {$album} credits ({$album_free} free per day)\nPremium and VIP never spend credits.
+    .history_title = Recent activity:
+    .history_empty = No credit activity yet.
+    .kind_purchase = purchase
+    .kind_debit = spent
+    .kind_allowance = free
+    .kind_refund = refund
+    .kind_revoke = revoked
+    .kind_grant = gift
+    .action_highres = high-res
+    .action_stories = stories
+    .action_album_split = album split
+    .buy_button = ➕ {$credits} credits — {$stars} ⭐
+    .invoice_title = {$credits} credits
+    .invoice_description = Credits for high-res downloads, stories renders and album splits. Credits never expire.
+    .invoice_sent = Invoice for {$credits} credits ({$stars} ⭐) sent.
+    .pay_button = Pay {$stars} ⭐
+    .purchased = ✅ Added {$credits} credits. Balance: {$balance}.
+    .insufficient = 💎 This needs {$cost} credits, you have {$balance}. Top up with /credits or upgrade your plan.
+    .refunded = ↩️ {$credits} credits returned — the task didn't go through.
+    .revoked = ↩️ {$credits} credits were removed after a Stars refund.
+    .purchase_failed = ❌ Could not add credits. Contact support — your payment is recorded.
+
+promo =
+    .usage = Send /redeem CODE to apply a promo or gift code.
+    .invalid = ❌ That doesn't look like a valid code.
+    .not_found = ❌ Code not found or no longer active.
+    .expired = ⌛ This code has expired.
+    .exhausted = ❌ This code has reached its usage limit.
+    .already_redeemed = ℹ️ You have already used this code.
+    .already_paid = ℹ️ You already have a paid plan — plan codes can only be used on the free plan.
+    .failed = ❌ Could not apply the code. Please try again later.
+    .plan_activated = 🎁 {$plan} activated for {$days} days!
+    .discount_saved = 🎟 Discount saved: -{$percent}% on your next subscription payment. Open /plan to subscribe.
+    .credits_added = 💎 Added {$credits} credits. Balance: {$balance}.
+    .gift_menu = 🎁 Gift a subscription\n\nPick a plan — you'll get a link for {$days} days of it to send to a friend.
+    .gift_button = {$plan} — {$stars} ⭐
+    .gift_invoice_title = Gift: {$plan} for {$days} days
+    .gift_invoice_description = A one-time {$plan} gift for {$days} days. You'll receive a link to forward to the recipient.
+    .gift_invoice_sent = Invoice for a {$plan} gift ({$stars} ⭐) sent.
+    .gift_pay_button = Pay {$stars} ⭐
+    .gift_ready = 🎁 Your gift is ready: {$plan} for {$days} days.\n\nForward this link to the recipient:\n{$link}\n\nOr they can send /redeem {$code}. The code works once.
+    .gift_failed = ❌ Could not create the gift code. Contact support — your payment is recorded.
+    .invite = 🤝 Invite friends\n\nShare your link:\n{$link}\n\nWhen a friend joins through it and finishes their first download, you both get {$credits} credits.\n\nInvited: {$invited} · Rewarded: {$rewarded}
+    .referral_reward = 🤝 Referral bonus: +{$credits} credits!
+
+api_keys =
+    .title = 🔑 API keys ({$count}/{$max})
+    .empty = You have no API keys yet.
+    .item = #{$id} {$name} · {$prefix}… · created {$created} · last used {$last_used}
+    .never = never
+    .usage = /apikey new NAME — create a key\n/apikey revoke ID — revoke a key\n\nSend the key as "Authorization: Bearer KEY" to the REST API:
+    .created = 🔑 Key «{$name}» created. Copy it now — it won't be shown again. Your plan allows {$rpm} requests per minute.
+    .limit = ❌ You already have {$max} keys. Revoke one with /apikey revoke ID first.
+    .revoked = ✅ Key #{$id} revoked.
+    .not_found = ❌ No active key #{$id}.
+    .revoke_usage = Send /apikey revoke ID — the ID is shown in /apikey.
+    .disabled = The REST API is not available right now.
+    .failed = ❌ Something went wrong. Please try again later.
+
 subscription =
     .info_header = 💳 *Subscription Information*\n\n
     .current_plan = 📊 *Your current plan:* {$plan} {$icon}\n
@@ -392,6 +459,7 @@ preview =
 download =
     .progress_title = Downloading
     .video_corrupted = Video file is corrupted or missing required tracks
+    .hosted_link = 📦 «{$title}» is too large for Telegram ({$size} MB), so here is a download link instead. It works for {$hours} h:
 
 progress =
     .starting = Starting download...
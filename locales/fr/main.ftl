@@ -209,6 +209,10 @@ bot_commands =
     .export = Exporter l'historique
     .backup = Créer une sauvegarde de la base (admin uniquement)
     .plan = Infos sur l'abonnement
+    .credits = Crédits et packs d'étoiles
+    .redeem = Utiliser un code promo ou cadeau
+    .gift = Offrir un abonnement
+    .invite = Inviter des amis et gagner des crédits
     .users = Lister tous les utilisateurs (admin uniquement)
     .setplan = Changer le plan d'un utilisateur (admin uniquement)
     .subscriptions = Mes abonnements aux contenus
@@ -286,6 +290,9 @@ vault-disabled = Coffre désactivé\.
 vault-enabled = Coffre activé\.
 vault-cache-hit = Instantané depuis le cache du coffre
 
+download =
+    .hosted_link = 📦 «{$title}» est trop volumineux pour Telegram ({$size} Mo), voici un lien de téléchargement à la place. Il est valable {$hours} h :
+
 progress =
     .starting = Début du téléchargement...
     .downloading = Téléchargement
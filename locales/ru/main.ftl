@@ -231,6 +231,10 @@ bot_commands =
     .export = Экспорт истории
     .backup = Создать бэкап БД (только для администраторов)
     .plan = Информация о подписке и тарифах
+    .credits = Баланс кредитов и пакеты за звёзды
+    .redeem = Активировать промокод или подарок
+    .gift = Подарить подписку другу
+    .invite = Пригласить друзей и получить кредиты
     .users = Список всех пользователей (только для администратора)
     .setplan = Изменить план пользователя (только для администратора)
     .transactions = Посмотреть транзакции Stars (только для администратора)
@@ -274,6 +278,69 @@ admin =
     .charge_recurring = \\(рекуррентный\\)
     .charge_expires = • Истекает: {$date}\n
 
+credits =
+    .title = 💎 Кредиты
+    .balance = Баланс: {$balance} кредитов
+    .prices = Цены на бесплатном тарифе:\n• Видео в высоком качестве (выше 1080p): {$highres} кредита ({$highres_free} бесплатно в день)\n• Рендер для сторис: {$stories} кредита ({$stories_free} бесплатно в день)\n• Выбор из альбома: {$album} кредит ({$album_free} бесплатно в день)\nНа Premium и VIP кредиты не тратятся.
+    .history_title = Последние операции:
+    .history_empty = Операций с кредитами пока нет.
+    .kind_purchase = покупка
+    .kind_debit = списание
+    .kind_allowance = бесплатно
+    .kind_refund = возврат
+    .kind_revoke = отзыв
+    .kind_grant = подарок
+    .action_highres = высокое качество
+    .action_stories = сторис
+    .action_album_split = выбор из альбома
+    .buy_button = ➕ {$credits} кредитов — {$stars} ⭐
+    .invoice_title = {$credits} кредитов
+    .invoice_description = Кредиты на видео в высоком качестве, рендер сторис и выбор из альбомов. Кредиты не сгорают.
+    .invoice_sent = Счёт на {$credits} кредитов ({$stars} ⭐) отправлен.
+    .pay_button = Оплатить {$stars} ⭐
+    .purchased = ✅ Начислено {$credits} кредитов. Баланс: {$balance}.
+    .insufficient = 💎 Нужно {$cost} кредитов, у вас {$balance}. Пополните через /credits или смените тариф.
+    .refunded = ↩️ Возвращено {$credits} кредитов — задача не выполнена.
+    .revoked = ↩️ {$credits} кредитов списано после возврата Stars.
+    .purchase_failed = ❌ Не удалось начислить кредиты. Напишите в поддержку — платёж сохранён.
+
+promo =
+    .usage = Отправь /redeem КОД, чтобы активировать промокод или подарок.
+    .invalid = ❌ Это не похоже на корректный код.
+    .not_found = ❌ Код не найден или больше не действует.
+    .expired = ⌛ Срок действия кода истёк.
+    .exhausted = ❌ Лимит использований кода исчерпан.
+    .already_redeemed = ℹ️ Ты уже использовал этот код.
+    .already_paid = ℹ️ У тебя уже есть платный план — коды на план работают только на бесплатном.
+    .failed = ❌ Не удалось применить код. Попробуй позже.
+    .plan_activated = 🎁 {$plan} активирован на {$days} дн.!
+    .discount_saved = 🎟 Скидка сохранена: -{$percent}% на следующую оплату подписки. Открой /plan, чтобы оформить.
+    .credits_added = 💎 Начислено {$credits} кредитов. Баланс: {$balance}.
+    .gift_menu = 🎁 Подарить подписку\n\nВыбери план — ты получишь ссылку на {$days} дн., которую можно отправить другу.
+    .gift_button = {$plan} — {$stars} ⭐
+    .gift_invoice_title = Подарок: {$plan} на {$days} дн.
+    .gift_invoice_description = Разовый подарок {$plan} на {$days} дн. После оплаты ты получишь ссылку для получателя.
+    .gift_invoice_sent = Счёт за подарок {$plan} ({$stars} ⭐) отправлен.
+    .gift_pay_button = Оплатить {$stars} ⭐
+    .gift_ready = 🎁 Подарок готов: {$plan} на {$days} дн.\n\nПерешли эту ссылку получателю:\n{$link}\n\nИли пусть отправит /redeem {$code}. Код одноразовый.
+    .gift_failed = ❌ Не удалось создать подарочный код. Напиши в поддержку — платёж сохранён.
+    .invite = 🤝 Пригласи друзей\n\nТвоя ссылка:\n{$link}\n\nКогда друг придёт по ней и завершит первое скачивание, вы оба получите по {$credits} кредитов.\n\nПриглашено: {$invited} · Награждено: {$rewarded}
+    .referral_reward = 🤝 Бонус за приглашение: +{$credits} кредитов!
+
+api_keys =
+    .title = 🔑 API-ключи ({$count}/{$max})
+    .empty = У тебя пока нет API-ключей.
+    .item = #{$id} {$name} · {$prefix}… · создан {$created} · использован {$last_used}
+    .never = никогда
+    .usage = /apikey new ИМЯ — создать ключ\n/apikey revoke ID — отозвать ключ\n\nПередавай ключ в заголовке "Authorization: Bearer КЛЮЧ" при запросах к REST API:
+    .created = 🔑 Ключ «{$name}» создан. Скопируй его сейчас — больше он показан не будет. Твой тариф позволяет {$rpm} запросов в минуту.
+    .limit = ❌ У тебя уже {$max} ключей. Сначала отзови один: /apikey revoke ID.
+    .revoked = ✅ Ключ #{$id} отозван.
+    .not_found = ❌ Нет активного ключа #{$id}.
+    .revoke_usage = Отправь /apikey revoke ID — ID есть в списке /apikey.
+    .disabled = REST API сейчас недоступен.
+    .failed = ❌ Что-то пошло не так. Попробуй позже.
+
 subscription =
     .info_header = 💳 *Информация о подписке*\n\n
     .current_plan = 📊 *Твой текущий план:* {$plan} {$icon}\n
@@ -392,6 +459,7 @@ preview =
 download =
     .progress_title = Скачивание
     .video_corrupted = Видео файл повреждён или не содержит все необходимые дорожки
+    .hosted_link = 📦 «{$title}» слишком большой для Telegram ({$size} МБ), поэтому вот ссылка для скачивания. Она действует {$hours} ч:
 
 progress =
     .starting = Начинаю скачивание...
@@ -0,0 +1,35 @@
+-- Pay-per-use credits bought with Telegram Stars. `credit_balances` holds the
+-- spendable balance per user; `credit_ledger` is the append-only history every
+-- balance change goes through (purchase, debit, refund, revoke, grant).
+--
+-- Premium actions (high-res video beyond the free allowance, stories render,
+-- album split) write a `debit` — or a zero-delta `allowance` row while the
+-- daily free allowance lasts — in the same transaction as their task. A failed
+-- or cancelled task writes exactly one `refund` row keyed by the same task_id.
+CREATE TABLE IF NOT EXISTS credit_balances (
+    user_id    INTEGER PRIMARY KEY,
+    balance    INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS credit_ledger (
+    id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id            INTEGER NOT NULL,
+    delta              INTEGER NOT NULL,   -- signed credit change (0 for 'allowance')
+    balance_after      INTEGER NOT NULL,
+    kind               TEXT    NOT NULL,   -- 'purchase' | 'debit' | 'allowance' | 'refund' | 'revoke' | 'grant'
+    action             TEXT,               -- 'highres' | 'stories' | 'album_split' (debit/allowance/refund)
+    task_id            TEXT,               -- task_queue.id or stories job id the row belongs to
+    telegram_charge_id TEXT,               -- Stars charge for 'purchase' / 'revoke'
+    stars_amount       INTEGER,            -- Stars paid for 'purchase'
+    note               TEXT,
+    created_at         TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_credit_ledger_user ON credit_ledger(user_id, created_at DESC);
+-- One debit/allowance and at most one refund per task: makes refunds idempotent.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_ledger_task_kind
+    ON credit_ledger(task_id, kind) WHERE task_id IS NOT NULL;
+-- A Stars charge is credited (and revoked) at most once.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_ledger_charge_kind
+    ON credit_ledger(telegram_charge_id, kind) WHERE telegram_charge_id IS NOT NULL;
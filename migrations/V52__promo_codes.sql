@@ -0,0 +1,43 @@
+-- Promo codes, gift subscriptions and referrals.
+--
+-- `promo_codes` holds admin-issued codes (`source = 'admin'`) and the single-use
+-- codes minted when someone buys a subscription as a gift (`source = 'gift'`).
+-- A code grants one of: a plan for `days` ('plan'), a percent discount on the
+-- next subscription invoice ('discount'), or `credits` ('credits').
+CREATE TABLE IF NOT EXISTS promo_codes (
+    code       TEXT PRIMARY KEY,
+    kind       TEXT    NOT NULL,          -- 'plan' | 'discount' | 'credits'
+    plan       TEXT,                      -- 'premium' | 'vip' for 'plan'
+    days       INTEGER,                   -- plan duration for 'plan'
+    percent    INTEGER,                   -- 1..=99 for 'discount'
+    credits    INTEGER,                   -- amount for 'credits'
+    max_uses   INTEGER,                   -- NULL = unlimited
+    used_count INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP,                 -- NULL = never
+    source     TEXT    NOT NULL DEFAULT 'admin',
+    created_by INTEGER NOT NULL,          -- admin id, or the gift buyer
+    is_active  INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One redemption per (code, user). Discount redemptions stay pending until a
+-- paid invoice consumes them (`consumed_at`).
+CREATE TABLE IF NOT EXISTS promo_redemptions (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    code        TEXT    NOT NULL,
+    user_id     INTEGER NOT NULL,
+    consumed_at TIMESTAMP,
+    redeemed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(code, user_id)
+);
+CREATE INDEX IF NOT EXISTS idx_promo_redemptions_user ON promo_redemptions(user_id);
+
+-- Who invited whom via `/start ref_<id>`. Both sides are rewarded once, after
+-- the invitee's first successful download (`rewarded_at`).
+CREATE TABLE IF NOT EXISTS referrals (
+    invitee_id  INTEGER PRIMARY KEY,
+    referrer_id INTEGER NOT NULL,
+    created_at  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    rewarded_at TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_referrals_referrer ON referrals(referrer_id);
@@ -0,0 +1,16 @@
+-- Podcast / RSS episode metadata captured by the `rss` content watcher.
+--
+-- Keyed by the enclosure URL that the download buttons point at, so the
+-- download pipeline can restore the episode title, show name, cover and
+-- chapters when the user taps MP3 long after the notification was sent.
+CREATE TABLE IF NOT EXISTS feed_episodes (
+    enclosure_url TEXT PRIMARY KEY,
+    feed_url      TEXT NOT NULL,
+    title         TEXT NOT NULL,
+    podcast       TEXT NOT NULL,
+    cover_url     TEXT,
+    duration_secs INTEGER,
+    chapters_json TEXT,                   -- inline (Podlove) chapters, JSON
+    chapters_url  TEXT,                   -- Podcasting 2.0 chapters document
+    created_at    TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
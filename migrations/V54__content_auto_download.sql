@@ -0,0 +1,15 @@
+-- Auto-download mode and per-subscription filters for content subscriptions.
+--
+-- When `auto_download` is set, new items that pass `filters` are queued in
+-- `auto_format` / `auto_quality` (NULL = the user's default quality or
+-- bitrate) instead of only producing a notification. `filters` is JSON:
+-- {"include": [..], "exclude": [..], "min_duration_secs": n,
+--  "max_duration_secs": n, "auto_mask": n}. `auto_day` / `auto_count` track
+-- how many items were queued today (UTC) against `daily_cap`.
+ALTER TABLE content_subscriptions ADD COLUMN auto_download INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE content_subscriptions ADD COLUMN auto_format TEXT NOT NULL DEFAULT 'mp3';
+ALTER TABLE content_subscriptions ADD COLUMN auto_quality TEXT DEFAULT NULL;
+ALTER TABLE content_subscriptions ADD COLUMN filters TEXT DEFAULT NULL;
+ALTER TABLE content_subscriptions ADD COLUMN daily_cap INTEGER NOT NULL DEFAULT 5;
+ALTER TABLE content_subscriptions ADD COLUMN auto_day TEXT DEFAULT NULL;
+ALTER TABLE content_subscriptions ADD COLUMN auto_count INTEGER NOT NULL DEFAULT 0;
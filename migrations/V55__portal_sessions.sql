@@ -0,0 +1,20 @@
+-- User portal (/me) web sessions.
+--
+-- Same scheme as admin_sessions (V43): token_hash is sha256(raw_token), the
+-- raw token only ever lives in the browser cookie. `csrf_token` is a random
+-- per-session value the portal page echoes back in `x-csrf-token`, so CSRF
+-- checks work on every instance without a process-local secret.
+
+CREATE TABLE IF NOT EXISTS portal_sessions (
+    token_hash BLOB PRIMARY KEY,
+    user_id    INTEGER NOT NULL,
+    csrf_token TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TEXT NOT NULL,
+    last_seen  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_agent TEXT,
+    ip         TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_portal_sessions_user ON portal_sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_portal_sessions_expires ON portal_sessions(expires_at);
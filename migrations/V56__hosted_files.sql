@@ -0,0 +1,24 @@
+-- Files published through signed web links when they exceed Telegram's
+-- upload caps (see doracore::core::hosted_links).
+--
+-- `id` is the random path segment of `/d/{id}`; the URL also carries an
+-- HMAC signature over (id, expiry), so a leaked id alone is useless.
+-- `file_path` points into DOWNLOAD_FOLDER/hosted/, which the periodic
+-- downloads cleanup never walks into — rows and files go away together once
+-- `expires_at` passes.
+
+CREATE TABLE IF NOT EXISTS hosted_files (
+    id               TEXT PRIMARY KEY,
+    user_id          INTEGER NOT NULL,
+    file_path        TEXT NOT NULL,
+    file_name        TEXT NOT NULL,
+    content_type     TEXT NOT NULL,
+    file_size        INTEGER NOT NULL,
+    download_count   INTEGER NOT NULL DEFAULT 0,
+    created_at       TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at       TEXT NOT NULL,
+    last_download_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_hosted_files_user ON hosted_files(user_id);
+CREATE INDEX IF NOT EXISTS idx_hosted_files_expires ON hosted_files(expires_at);
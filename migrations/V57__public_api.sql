@@ -0,0 +1,54 @@
+-- Public REST API (/api/v1) for programmatic downloads.
+--
+-- api_keys: per-user keys managed with /apikey in the bot. Like the session
+-- tables, only sha256(raw_key) is stored; `key_prefix` is the first few
+-- characters so users can tell their keys apart in listings.
+--
+-- api_jobs: task_queue rows submitted through the API. The worker publishes
+-- the result as a hosted link (V56) instead of uploading it to Telegram and
+-- records it here together with the link expiry, or the failure reason.
+-- Status while the job runs comes from the task_queue row.
+--
+-- api_requests: one row per authenticated API call, for analytics.
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id      INTEGER NOT NULL,
+    name         TEXT NOT NULL,
+    key_hash     BLOB NOT NULL UNIQUE,
+    key_prefix   TEXT NOT NULL,
+    created_at   TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TEXT,
+    revoked_at   TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_user ON api_keys(user_id);
+
+CREATE TABLE IF NOT EXISTS api_jobs (
+    task_id           TEXT PRIMARY KEY,
+    key_id            INTEGER NOT NULL,
+    user_id           INTEGER NOT NULL,
+    url               TEXT NOT NULL,
+    format            TEXT NOT NULL,
+    hosted_file_id    TEXT,
+    result_expires_at INTEGER,
+    error             TEXT,
+    created_at        TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at       TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_jobs_user ON api_jobs(user_id, created_at);
+
+CREATE TABLE IF NOT EXISTS api_requests (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    key_id      INTEGER NOT NULL,
+    user_id     INTEGER NOT NULL,
+    method      TEXT NOT NULL,
+    endpoint    TEXT NOT NULL,
+    status      INTEGER NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_requests_created ON api_requests(created_at);
+CREATE INDEX IF NOT EXISTS idx_api_requests_key ON api_requests(key_id, created_at);
@@ -0,0 +1,13 @@
+-- Admin dashboard roles (RBAC).
+--
+-- Users in ADMIN_IDS / ADMIN_USER_ID are always owners and never appear
+-- here. This table grants the other roles — viewer, support, operator,
+-- owner — to additional staff; the permission matrix itself lives in code
+-- (core/admin_roles.rs). Denied actions are written to admin_audit_log.
+
+CREATE TABLE IF NOT EXISTS admin_roles (
+    user_id    INTEGER PRIMARY KEY,
+    role       TEXT NOT NULL,
+    granted_by INTEGER NOT NULL,
+    granted_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,18 @@
+-- Admin web auth state that has to be shared between instances.
+--
+-- - admin_sessions.csrf_token: random per-session value the dashboard echoes
+--   back in `x-csrf-token`, replacing the CSRF token derived from a
+--   process-local secret. Sessions that predate this column get one here, so
+--   nobody is logged out by the upgrade.
+-- - web_rate_limits: fixed-window login attempt counters keyed by bucket
+--   (e.g. `login:<ip>`). `window_start` is unix seconds of the first hit in
+--   the current window.
+
+ALTER TABLE admin_sessions ADD COLUMN csrf_token TEXT;
+UPDATE admin_sessions SET csrf_token = lower(hex(randomblob(32))) WHERE csrf_token IS NULL;
+
+CREATE TABLE IF NOT EXISTS web_rate_limits (
+    bucket       TEXT PRIMARY KEY,
+    hits         INTEGER NOT NULL,
+    window_start INTEGER NOT NULL
+);
@@ -1,4 +1,13 @@
-pub(super) const POSTGRES_BOOTSTRAP_SQL: &str = r#"
+-- Postgres baseline: the shared-storage schema as of SQLite V52.
+--
+-- Postgres migrations are tracked in `pg_schema_history` and numbered in
+-- step with the SQLite `V*` files: a change introduced by SQLite `V53__x.sql`
+-- ships here as `PG53__x.sql` (a comment-only file when it is SQLite-only).
+-- The `PG` prefix keeps refinery from picking these files up.
+--
+-- Every statement is idempotent so deployments bootstrapped before
+-- versioning adopt this baseline without changes.
+
 CREATE TABLE IF NOT EXISTS users (
     telegram_id BIGINT PRIMARY KEY,
     username TEXT,
@@ -673,4 +682,65 @@ CREATE TABLE IF NOT EXISTS lyrics_overrides (
     created_at   TEXT NOT NULL,
     updated_at   TEXT NOT NULL
 );
-"#;
+
+-- V51: credits ledger — Star-pack balances and the append-only ledger of
+-- purchases, debits, refunds and revokes.
+CREATE TABLE IF NOT EXISTS credit_balances (
+    user_id    BIGINT PRIMARY KEY,
+    balance    BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS credit_ledger (
+    id                 BIGSERIAL PRIMARY KEY,
+    user_id            BIGINT NOT NULL,
+    delta              BIGINT NOT NULL,
+    balance_after      BIGINT NOT NULL,
+    kind               TEXT   NOT NULL,
+    action             TEXT,
+    task_id            TEXT,
+    telegram_charge_id TEXT,
+    stars_amount       BIGINT,
+    note               TEXT,
+    created_at         TIMESTAMPTZ DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_credit_ledger_user ON credit_ledger(user_id, created_at DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_ledger_task_kind
+    ON credit_ledger(task_id, kind) WHERE task_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_ledger_charge_kind
+    ON credit_ledger(telegram_charge_id, kind) WHERE telegram_charge_id IS NOT NULL;
+
+-- V52: promo codes, gift subscriptions and referrals.
+CREATE TABLE IF NOT EXISTS promo_codes (
+    code       TEXT PRIMARY KEY,
+    kind       TEXT    NOT NULL,
+    plan       TEXT,
+    days       INTEGER,
+    percent    INTEGER,
+    credits    BIGINT,
+    max_uses   INTEGER,
+    used_count INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ,
+    source     TEXT    NOT NULL DEFAULT 'admin',
+    created_by BIGINT  NOT NULL,
+    is_active  INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS promo_redemptions (
+    id          BIGSERIAL PRIMARY KEY,
+    code        TEXT   NOT NULL,
+    user_id     BIGINT NOT NULL,
+    consumed_at TIMESTAMPTZ,
+    redeemed_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(code, user_id)
+);
+CREATE INDEX IF NOT EXISTS idx_promo_redemptions_user ON promo_redemptions(user_id);
+
+CREATE TABLE IF NOT EXISTS referrals (
+    invitee_id  BIGINT PRIMARY KEY,
+    referrer_id BIGINT NOT NULL,
+    created_at  TIMESTAMPTZ DEFAULT NOW(),
+    rewarded_at TIMESTAMPTZ
+);
+CREATE INDEX IF NOT EXISTS idx_referrals_referrer ON referrals(referrer_id);
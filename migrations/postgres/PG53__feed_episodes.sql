@@ -0,0 +1,13 @@
+-- V53: podcast / RSS episode metadata captured by the `rss` content watcher,
+-- keyed by enclosure URL.
+CREATE TABLE IF NOT EXISTS feed_episodes (
+    enclosure_url TEXT PRIMARY KEY,
+    feed_url      TEXT NOT NULL,
+    title         TEXT NOT NULL,
+    podcast       TEXT NOT NULL,
+    cover_url     TEXT,
+    duration_secs BIGINT,
+    chapters_json TEXT,
+    chapters_url  TEXT,
+    created_at    TIMESTAMPTZ DEFAULT NOW()
+);
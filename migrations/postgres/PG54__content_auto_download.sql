@@ -0,0 +1,8 @@
+-- V54: auto-download mode and per-subscription filters (see V54 SQLite file).
+ALTER TABLE content_subscriptions ADD COLUMN IF NOT EXISTS auto_download INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE content_subscriptions ADD COLUMN IF NOT EXISTS auto_format TEXT NOT NULL DEFAULT 'mp3';
+ALTER TABLE content_subscriptions ADD COLUMN IF NOT EXISTS auto_quality TEXT;
+ALTER TABLE content_subscriptions ADD COLUMN IF NOT EXISTS filters TEXT;
+ALTER TABLE content_subscriptions ADD COLUMN IF NOT EXISTS daily_cap INTEGER NOT NULL DEFAULT 5;
+ALTER TABLE content_subscriptions ADD COLUMN IF NOT EXISTS auto_day TEXT;
+ALTER TABLE content_subscriptions ADD COLUMN IF NOT EXISTS auto_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,14 @@
+-- V55: user portal (/me) web sessions (see V55 SQLite file).
+CREATE TABLE IF NOT EXISTS portal_sessions (
+    token_hash BYTEA PRIMARY KEY,
+    user_id    BIGINT NOT NULL,
+    csrf_token TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    last_seen  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    user_agent TEXT,
+    ip         TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_portal_sessions_user ON portal_sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_portal_sessions_expires ON portal_sessions(expires_at);
@@ -0,0 +1,16 @@
+-- V56: files published through signed web links (see V56 SQLite file).
+CREATE TABLE IF NOT EXISTS hosted_files (
+    id               TEXT PRIMARY KEY,
+    user_id          BIGINT NOT NULL,
+    file_path        TEXT NOT NULL,
+    file_name        TEXT NOT NULL,
+    content_type     TEXT NOT NULL,
+    file_size        BIGINT NOT NULL,
+    download_count   BIGINT NOT NULL DEFAULT 0,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at       TIMESTAMPTZ NOT NULL,
+    last_download_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_hosted_files_user ON hosted_files(user_id);
+CREATE INDEX IF NOT EXISTS idx_hosted_files_expires ON hosted_files(expires_at);
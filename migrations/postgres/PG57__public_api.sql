@@ -0,0 +1,42 @@
+-- V57: public REST API keys, jobs and request log (see V57 SQLite file).
+CREATE TABLE IF NOT EXISTS api_keys (
+    id           BIGSERIAL PRIMARY KEY,
+    user_id      BIGINT NOT NULL,
+    name         TEXT NOT NULL,
+    key_hash     BYTEA NOT NULL UNIQUE,
+    key_prefix   TEXT NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at   TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_user ON api_keys(user_id);
+
+CREATE TABLE IF NOT EXISTS api_jobs (
+    task_id           TEXT PRIMARY KEY,
+    key_id            BIGINT NOT NULL,
+    user_id           BIGINT NOT NULL,
+    url               TEXT NOT NULL,
+    format            TEXT NOT NULL,
+    hosted_file_id    TEXT,
+    result_expires_at BIGINT,
+    error             TEXT,
+    created_at        TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at       TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_jobs_user ON api_jobs(user_id, created_at);
+
+CREATE TABLE IF NOT EXISTS api_requests (
+    id          BIGSERIAL PRIMARY KEY,
+    key_id      BIGINT NOT NULL,
+    user_id     BIGINT NOT NULL,
+    method      TEXT NOT NULL,
+    endpoint    TEXT NOT NULL,
+    status      INTEGER NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_requests_created ON api_requests(created_at);
+CREATE INDEX IF NOT EXISTS idx_api_requests_key ON api_requests(key_id, created_at);
@@ -0,0 +1,10 @@
+-- V58: admin dashboard roles (see V58 SQLite file). Kept in Postgres so a
+-- grant or revoke on one instance applies to all of them; rows still in an
+-- instance's SQLite file are moved over once on startup
+-- (SharedStorage::import_sqlite_admin_roles).
+CREATE TABLE IF NOT EXISTS admin_roles (
+    user_id    BIGINT PRIMARY KEY,
+    role       TEXT NOT NULL,
+    granted_by BIGINT NOT NULL,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,23 @@
+-- V59: admin web sessions and login rate limits (see V59 SQLite file).
+-- Admin sessions used to exist only in each instance's SQLite file; rows
+-- still there are moved over once on startup
+-- (SharedStorage::import_sqlite_admin_sessions).
+CREATE TABLE IF NOT EXISTS admin_sessions (
+    token_hash BYTEA PRIMARY KEY,
+    admin_id   BIGINT NOT NULL,
+    csrf_token TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    last_seen  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    user_agent TEXT,
+    ip         TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_sessions_admin ON admin_sessions(admin_id);
+CREATE INDEX IF NOT EXISTS idx_admin_sessions_expires ON admin_sessions(expires_at);
+
+CREATE TABLE IF NOT EXISTS web_rate_limits (
+    bucket       TEXT PRIMARY KEY,
+    hits         INTEGER NOT NULL,
+    window_start BIGINT NOT NULL
+);